@@ -0,0 +1,117 @@
+//! 批量翻译策略的基准测试
+//!
+//! 使用带模拟延迟的`MockTranslator`代替远程服务，比较：
+//! - 逐条调用`translate`与按分块调用`translate_vec`
+//! - 不同的分块大小
+//! - 不同重复率的语料上去重的效果
+//!
+//! 运行：`cargo bench --bench batching`
+
+mod support;
+
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::batch::{
+    translate_map_with, BatchOptions, DEFAULT_CHUNK_SIZE,
+};
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 每次上游调用的模拟延迟
+const LATENCY: Duration = Duration::from_millis(2);
+
+/// 每个场景的文本数量
+const CORPUS_LEN: usize = 500;
+
+/// 打印一行结果
+///
+/// # 参数
+/// - `name`: 场景名称
+/// - `elapsed`: 耗时
+/// - `calls`: 上游调用次数
+/// - `items`: 上游收到的文本总数
+fn report(name: &str, elapsed: Duration, calls: usize, items: usize) {
+    println!(
+        "{:<36} {:>10.1} ms {:>8} calls {:>8} items",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        calls,
+        items
+    );
+}
+
+/// 逐条调用`translate`
+///
+/// # 参数
+/// - `texts`: 语料
+async fn sequential(texts: &[String]) {
+    let translator = MockTranslator::new().with_delay(LATENCY);
+    let start = Instant::now();
+    for text in texts {
+        translator
+            .translate(text, None, &Language::English)
+            .await
+            .unwrap();
+    }
+    report(
+        "sequential translate",
+        start.elapsed(),
+        translator.calls(),
+        translator.translated_items(),
+    );
+}
+
+/// 按分块调用`translate_vec`，分块前去重
+///
+/// # 参数
+/// - `name`: 场景名称
+/// - `texts`: 语料
+/// - `chunk_size`: 分块大小
+async fn chunked(name: &str, texts: &[String], chunk_size: usize) {
+    let translator = MockTranslator::new().with_delay(LATENCY);
+    let map = texts.iter().cloned().enumerate().collect::<HashMap<_, _>>();
+    let options = BatchOptions {
+        chunk_size,
+        ..Default::default()
+    };
+    let start = Instant::now();
+    translate_map_with(&translator, &map, None, &Language::English, &options)
+        .await
+        .unwrap();
+    report(
+        name,
+        start.elapsed(),
+        translator.calls(),
+        translator.translated_items(),
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let texts = support::corpus(CORPUS_LEN, 0.0);
+    println!("== strategies ({} unique items) ==", CORPUS_LEN);
+    sequential(&texts).await;
+    for chunk_size in [1, 10, DEFAULT_CHUNK_SIZE, 200] {
+        chunked(
+            &format!("translate_vec chunk_size={}", chunk_size),
+            &texts,
+            chunk_size,
+        )
+        .await;
+    }
+
+    println!("\n== dedup (chunk_size={}) ==", DEFAULT_CHUNK_SIZE);
+    for repetition in [0.1, 0.5, 0.9] {
+        let texts = support::corpus(CORPUS_LEN, repetition);
+        chunked(
+            &format!(
+                "{:.0}% repeated ({} unique)",
+                repetition * 100.0,
+                support::unique_count(&texts)
+            ),
+            &texts,
+            DEFAULT_CHUNK_SIZE,
+        )
+        .await;
+    }
+}
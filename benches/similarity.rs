@@ -0,0 +1,82 @@
+//! 句子相似度算法的基准测试
+//!
+//! 对英文和中文句子对分别计时：
+//! - `char_ngram_similarity`
+//! - `token_jaccard`
+//! - `normalized_levenshtein`
+//!
+//! 运行：`cargo bench --bench similarity`
+
+mod support;
+
+use fusion_translator::fusion_translator::similarity::{
+    char_ngram_similarity, normalized_levenshtein, token_jaccard,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// 每个场景的句子对数量
+const PAIRS: usize = 5000;
+
+/// 相似度算法
+type Metric = fn(&str, &str) -> f32;
+
+/// 相似度算法（名称，函数）
+const METRICS: [(&str, Metric); 3] = [
+    ("char_ngram_similarity", char_ngram_similarity),
+    ("token_jaccard", token_jaccard),
+    ("normalized_levenshtein", normalized_levenshtein),
+];
+
+/// 打印一行结果
+///
+/// # 参数
+/// - `name`: 场景名称
+/// - `elapsed`: 耗时
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<36} {:>10.1} ms {:>8.2} µs/pair",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        elapsed.as_secs_f64() * 1e6 / PAIRS as f64
+    );
+}
+
+/// 用每种算法计算所有句子对的相似度
+///
+/// # 参数
+/// - `label`: 语料名称
+/// - `pairs`: 句子对
+fn run(label: &str, pairs: &[(String, String)]) {
+    for (name, metric) in METRICS {
+        let start = Instant::now();
+        for (a, b) in pairs {
+            black_box(metric(black_box(a), black_box(b)));
+        }
+        report(&format!("{} {}", label, name), start.elapsed());
+    }
+}
+
+fn main() {
+    let texts = support::corpus(PAIRS + 1, 0.2);
+    println!(
+        "== {} pairs ({} unique sentences) ==",
+        PAIRS,
+        support::unique_count(&texts)
+    );
+    let english: Vec<(String, String)> = texts
+        .windows(2)
+        .map(|w| (w[0].clone(), w[1].clone()))
+        .collect();
+    run("en", &english);
+
+    let chinese: Vec<(String, String)> = (0..PAIRS)
+        .map(|i| {
+            (
+                format!("这是基准测试语料中的第{}个句子。", i),
+                format!("这是基准语料里的第{}句话。", i + 1),
+            )
+        })
+        .collect();
+    run("zh", &chinese);
+}
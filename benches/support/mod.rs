@@ -0,0 +1,40 @@
+//! 基准测试共用的语料生成器
+
+/// 生成带重复的语料
+///
+/// 使用固定种子的伪随机数，每次运行生成相同的语料
+///
+/// # 参数
+/// - `len`: 文本数量
+/// - `repetition`: 重复文本所占比例，取值0.0~1.0
+///
+/// # 返回值
+/// 文本列表，约`len * repetition`条文本与之前的某条文本相同
+pub fn corpus(len: usize, repetition: f64) -> Vec<String> {
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut texts: Vec<String> = Vec::with_capacity(len);
+    for i in 0..len {
+        let repeat = !texts.is_empty() && (next() % 1000) as f64 / 1000.0 < repetition;
+        if repeat {
+            let index = next() as usize % texts.len();
+            texts.push(texts[index].clone());
+        } else {
+            texts.push(format!("sentence number {} of the benchmark corpus", i));
+        }
+    }
+    texts
+}
+
+/// 统计语料中不同文本的数量
+///
+/// # 参数
+/// - `texts`: 语料
+pub fn unique_count(texts: &[String]) -> usize {
+    texts.iter().collect::<std::collections::HashSet<_>>().len()
+}
@@ -0,0 +1,41 @@
+//! 在浏览器中调用MyMemory翻译
+//!
+//! 编译为WebAssembly模块，导出`translate(text, to)`供JavaScript调用，返回Promise：
+//!
+//! ```text
+//! cargo build --example browser --target wasm32-unknown-unknown --no-default-features --features wasm
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/debug/examples/browser.wasm
+//! ```
+//!
+//! ```js
+//! import init, { translate } from "./pkg/browser.js";
+//! await init();
+//! console.log(await translate("Hello, world", "zh"));
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+use fusion_translator::fusion_translator::async_translator::Language;
+use fusion_translator::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory,
+};
+use wasm_bindgen::prelude::*;
+
+/// 把文本翻译为目标语言
+///
+/// # 参数
+/// - `text`: 待翻译的文本
+/// - `to`: 目标语言代码，如`zh`、`en`
+///
+/// # 返回值
+/// 译文，语言代码无效或翻译失败时抛出错误信息
+#[wasm_bindgen]
+pub async fn translate(text: String, to: String) -> Result<String, JsError> {
+    let to = Language::from_639_1(&to)
+        .ok_or_else(|| JsError::new(&format!("unknown language: {}", to)))?;
+    let translator = TranslatorFactory::create(TranslatorConfig::MyMemory);
+    let output = translator
+        .translate(&text, None, &to)
+        .await
+        .map_err(|err| JsError::new(&format!("{:#}", err)))?;
+    Ok(output.text)
+}
@@ -0,0 +1,83 @@
+//! 带缓存的批量翻译
+//!
+//! 用`CachedTranslator`包装翻译器，通过`batch::translate_map_with`翻译键值表：
+//! 相同的值只翻译一次，分块大小由`AdaptiveBatcher`按观测到的吞吐量调整；
+//! 第二次翻译同一批文本时全部命中缓存，不再请求上游。
+//!
+//! ```text
+//! cargo run --example cached_batch
+//! cargo run --example cached_batch -- --live --engine youdao
+//! ```
+
+mod common;
+
+use fusion_translator::fusion_translator::adaptive::AdaptiveBatcher;
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::batch::{translate_map_with, BatchOptions};
+use fusion_translator::fusion_translator::cache::CachedTranslator;
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // 模拟每次请求耗时30毫秒、单次最多16条的翻译服务
+    let mock = Arc::new(
+        MockTranslator::new()
+            .with_delay(Duration::from_millis(30))
+            .with_limits(Some(16), None),
+    );
+    let inner: Arc<dyn AsyncTranslator> = match common::live_translator() {
+        Some((_, translator)) => translator,
+        None => mock.clone(),
+    };
+    let cached = CachedTranslator::new(inner, 1000);
+
+    // 界面文案表：不同的键可能对应相同的文本
+    let mut strings = HashMap::new();
+    for i in 0..60 {
+        strings.insert(format!("menu.item{}", i), format!("Menu item {}", i % 40));
+    }
+    strings.insert("dialog.ok".to_string(), "OK".to_string());
+    strings.insert("dialog.cancel".to_string(), "Cancel".to_string());
+    strings.insert("toolbar.cancel".to_string(), "Cancel".to_string());
+
+    let batcher = Arc::new(AdaptiveBatcher::new(4));
+    let options = BatchOptions {
+        adaptive: Some(batcher.clone()),
+        ..Default::default()
+    };
+    for round in 1..=2 {
+        let started = Instant::now();
+        let output = translate_map_with(
+            &cached,
+            &strings,
+            Some(Language::English),
+            &Language::German,
+            &options,
+        )
+        .await?;
+        println!(
+            "round {}: {} keys in {:?}, {} cached entries, {} upstream calls so far",
+            round,
+            output.translations.len(),
+            started.elapsed(),
+            cached.len(),
+            mock.calls(),
+        );
+        if round == 1 {
+            let state = batcher.state();
+            println!(
+                "  adaptive chunk size {} after {} requests ({} failed)",
+                state.chunk_size, state.requests, state.failures
+            );
+        }
+        let mut sample = ["dialog.cancel", "toolbar.cancel", "menu.item45"];
+        sample.sort();
+        for key in sample {
+            println!("  {} = {}", key, output.translations[key].text);
+        }
+    }
+    Ok(())
+}
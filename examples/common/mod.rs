@@ -0,0 +1,51 @@
+//! 示例共用的辅助函数
+//!
+//! 示例默认使用`MockTranslator`，无需网络和凭据即可运行；
+//! 加上`--live`后改用真实的翻译服务，`--engine`选择服务（默认`mymemory`），
+//! 凭据从环境变量或`.env`读取，见README中的环境变量说明
+
+use fusion_translator::fusion_translator::async_translator::AsyncTranslator;
+use fusion_translator::fusion_translator::translator_factory::{TranslatorFactory, TranslatorType};
+use std::sync::Arc;
+
+/// 命令行中`name`之后的值
+///
+/// # 参数
+/// - `name`: 选项名，如`--engine`
+#[allow(dead_code)]
+pub fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == name)?;
+    args.next()
+}
+
+/// 命令行中是否有`flag`
+///
+/// # 参数
+/// - `flag`: 选项名，如`--live`
+pub fn has_flag(flag: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == flag)
+}
+
+/// 按`--live`和`--engine`创建真实的翻译器
+///
+/// # 返回值
+/// 没有`--live`时为None；服务名无法识别或缺少凭据时打印错误并退出
+pub fn live_translator() -> Option<(TranslatorType, Arc<dyn AsyncTranslator>)> {
+    if !has_flag("--live") {
+        return None;
+    }
+    dotenv::dotenv().ok();
+    let name = arg_value("--engine").unwrap_or_else(|| "mymemory".to_string());
+    let Some(engine) = TranslatorType::parse(&name) else {
+        eprintln!("error: unknown engine: {}", name);
+        std::process::exit(2);
+    };
+    match TranslatorFactory::create_from_env(engine) {
+        Ok(translator) => Some((engine, translator)),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! 语言回退和翻译器故障转移
+//!
+//! 目标语言不受支持时用`Language::closest`换成最接近的语言；
+//! 每个文本依次尝试多个翻译器，暂时性错误由`RetryTranslator`重试，
+//! 已成功的文本记录在`TranslationSession`中，重新运行同一批文本时不再请求上游。
+//!
+//! ```text
+//! cargo run --example fallback
+//! cargo run --example fallback -- --live --engine baidu
+//! ```
+
+mod common;
+
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::retry::RetryTranslator;
+use fusion_translator::fusion_translator::session::TranslationSession;
+use fusion_translator::fusion_translator::translate_options::TranslateOptions;
+use fusion_translator::fusion_translator::translator_error::ErrorKind;
+use fusion_translator::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // 主翻译器第一次调用返回503，且无法翻译包含“contract”的文本
+    let primary = Arc::new(
+        MockTranslator::new()
+            .with_transient_failures(1)
+            .fail_on("contract"),
+    );
+    let engines: Vec<(&str, Arc<dyn AsyncTranslator>)> = match common::live_translator() {
+        Some((engine, translator)) => vec![
+            (engine.as_str(), translator),
+            (
+                "mymemory",
+                TranslatorFactory::create(TranslatorConfig::MyMemory),
+            ),
+        ],
+        None => vec![
+            (
+                "primary",
+                Arc::new(
+                    RetryTranslator::new(primary.clone(), 2)
+                        .with_backoff(Duration::from_millis(20)),
+                ),
+            ),
+            ("secondary", Arc::new(MockTranslator::new())),
+        ],
+    };
+
+    // 请求书面挪威语，翻译服务只支持通用的挪威语代码时换用挪威语
+    let requested = Language::NorwegianBokmål;
+    let supported = [Language::English, Language::Norwegian, Language::German];
+    let to = requested.closest(&supported).unwrap_or(Language::English);
+    println!("target: requested {:?}, using {:?}\n", requested, to);
+
+    let texts = [
+        "Good morning",
+        "Please sign the contract before Friday",
+        "See you tomorrow",
+    ];
+    let session = TranslationSession::new();
+    let options = TranslateOptions::default();
+    for round in 1..=2 {
+        println!("round {}:", round);
+        for text in texts {
+            let mut answered = false;
+            for (name, translator) in &engines {
+                match session
+                    .translate(
+                        name,
+                        translator.as_ref(),
+                        text,
+                        Some(Language::English),
+                        &to,
+                        &options,
+                    )
+                    .await
+                {
+                    Ok(output) => {
+                        println!("  [{}] {} -> {}", name, text, output.text);
+                        answered = true;
+                        break;
+                    }
+                    Err(e) => println!("  [{}] failed ({:?}): {}", name, ErrorKind::of(&e), e),
+                }
+            }
+            if !answered {
+                println!("  no engine could translate {:?}", text);
+            }
+        }
+    }
+
+    let stats = session.stats();
+    println!(
+        "\nsession: {} requested, {} reused, {} upstream calls",
+        stats.requested, stats.reused, stats.upstream_calls
+    );
+    if primary.calls() > 0 {
+        println!(
+            "primary mock: {} calls, 5xx responses are retried twice before failing over",
+            primary.calls()
+        );
+    }
+    Ok(())
+}
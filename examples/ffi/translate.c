@@ -0,0 +1,31 @@
+/*
+ * C接口示例
+ *
+ * cargo build --release --features ffi
+ * cc examples/ffi/translate.c -Iinclude -Ltarget/release -lfusion_translator -o translate
+ */
+
+#include <stdio.h>
+
+#include "fusion_translator.h"
+
+int main(void) {
+    FtTranslator *translator = ft_create_translator("mock", NULL);
+    if (translator == NULL) {
+        fprintf(stderr, "create failed: %s\n", ft_last_error_message());
+        return 1;
+    }
+
+    char *out = NULL;
+    int32_t code = ft_translate(translator, "hello", "en", "zh", &out);
+    if (code != FT_OK) {
+        fprintf(stderr, "translate failed (%d): %s\n", code, ft_last_error_message());
+        ft_destroy(translator);
+        return 1;
+    }
+
+    printf("%s\n", out);
+    ft_free_string(out);
+    ft_destroy(translator);
+    return 0;
+}
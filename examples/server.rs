@@ -0,0 +1,257 @@
+//! 最小的HTTP翻译服务
+//!
+//! `GET /translate?text=...`返回JSON格式的译文：未指定`to`参数时按`Accept-Language`
+//! 协商目标语言，响应带有`ETag`，请求头`If-None-Match`匹配时返回304且不调用翻译器。
+//! 本示例只依赖tokio的TCP接口，演示`negotiate`模块的用法，不是完整的HTTP实现。
+//!
+//! 默认在随机端口启动服务，自行发送几次请求并打印响应后退出；
+//! `--listen`在指定地址持续提供服务，可用curl访问：
+//!
+//! ```text
+//! cargo run --example server
+//! cargo run --example server -- --listen 127.0.0.1:8080 --live --engine mymemory
+//! curl -H 'Accept-Language: fr;q=0.9, de;q=0.8' 'http://127.0.0.1:8080/translate?text=hello'
+//! ```
+
+mod common;
+
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::negotiate::{
+    if_none_match, negotiate_target, parse_language_tag, translation_etag,
+};
+use fusion_translator::fusion_translator::translator_factory::TranslatorType;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 服务使用的翻译器
+struct Service {
+    /// 翻译器
+    translator: Arc<dyn AsyncTranslator>,
+    /// 翻译器名称，参与计算ETag
+    engine: &'static str,
+    /// 翻译器的语言代码表，None表示接受所有语言
+    language_table: Option<&'static str>,
+}
+
+/// HTTP响应
+struct Response {
+    /// 状态行，如`200 OK`
+    status: &'static str,
+    /// 响应头
+    headers: Vec<(&'static str, String)>,
+    /// 响应体
+    body: String,
+}
+
+impl Response {
+    /// 纯文本的错误响应
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+            body: format!("{}\n", message),
+        }
+    }
+}
+
+impl Service {
+    /// 判断翻译器是否支持某个语言
+    fn supports(&self, lang: &Language) -> bool {
+        self.language_table
+            .is_none_or(|table| lang.to_vendor(table).is_some())
+    }
+
+    /// 处理`/translate`请求
+    ///
+    /// # 参数
+    /// - `query`: 查询参数
+    /// - `headers`: 请求头，名称为小写
+    async fn translate(
+        &self,
+        query: &HashMap<String, String>,
+        headers: &HashMap<String, String>,
+    ) -> Response {
+        let Some(text) = query.get("text") else {
+            return Response::error("400 Bad Request", "missing text parameter");
+        };
+        let to = match query.get("to") {
+            Some(tag) => parse_language_tag(tag),
+            None => negotiate_target(
+                headers.get("accept-language").map_or("en", String::as_str),
+                |lang| self.supports(lang),
+            ),
+        };
+        let Some(to) = to else {
+            return Response::error("406 Not Acceptable", "no supported target language");
+        };
+
+        let etag = translation_etag(text, &to, self.engine);
+        let mut response_headers = vec![
+            ("ETag", etag.clone()),
+            ("Vary", "Accept-Language".to_string()),
+        ];
+        if let Some(code) = to.to_639_1() {
+            response_headers.push(("Content-Language", code.to_string()));
+        }
+        if headers
+            .get("if-none-match")
+            .is_some_and(|header| if_none_match(header, &etag))
+        {
+            return Response {
+                status: "304 Not Modified",
+                headers: response_headers,
+                body: String::new(),
+            };
+        }
+        match self.translator.translate(text, None, &to).await {
+            Ok(output) => {
+                response_headers.push(("Content-Type", "application/json".to_string()));
+                Response {
+                    status: "200 OK",
+                    headers: response_headers,
+                    body: serde_json::json!({
+                        "text": output.text,
+                        "to": to.to_639_1(),
+                    })
+                    .to_string(),
+                }
+            }
+            Err(e) => Response::error("502 Bad Gateway", &e.to_string()),
+        }
+    }
+
+    /// 处理一个连接上的一个请求
+    ///
+    /// # 参数
+    /// - `stream`: 客户端连接
+    async fn handle(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+        let url = reqwest::Url::parse("http://localhost")?.join(target)?;
+        let response = if url.path() == "/translate" {
+            let query = url.query_pairs().into_owned().collect();
+            self.translate(&query, &headers).await
+        } else {
+            Response::error("404 Not Found", "try /translate?text=hello")
+        };
+
+        let mut raw = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            response.status,
+            response.body.len()
+        );
+        for (name, value) in &response.headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        raw.push_str("\r\n");
+        raw.push_str(&response.body);
+        reader.get_mut().write_all(raw.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+/// 接受连接并逐个处理
+///
+/// # 参数
+/// - `listener`: 监听的端口
+/// - `service`: 翻译服务
+async fn serve(listener: TcpListener, service: Arc<Service>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.handle(stream).await {
+                eprintln!("request failed: {}", e);
+            }
+        });
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mock = Arc::new(MockTranslator::new());
+    let service = Arc::new(match common::live_translator() {
+        Some((engine, translator)) => Service {
+            translator,
+            engine: engine.as_str(),
+            language_table: engine.language_table(),
+        },
+        None => Service {
+            translator: mock.clone(),
+            engine: "mock",
+            language_table: TranslatorType::MyMemory.language_table(),
+        },
+    });
+
+    if let Some(addr) = common::arg_value("--listen") {
+        let listener = TcpListener::bind(&addr).await?;
+        // 在接受请求前建立到翻译服务的连接；预热失败只记录日志，第一次翻译时重新连接
+        service.translator.warm_up().await;
+        println!("listening on http://{}/translate", listener.local_addr()?);
+        serve(listener, service).await;
+        return Ok(());
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base = format!("http://{}/translate", listener.local_addr()?);
+    service.translator.warm_up().await;
+    tokio::spawn(serve(listener, service));
+
+    let client = reqwest::Client::new();
+    let mut etag = None;
+    let requests = [
+        ("?text=good%20night", Some("xx, fr;q=0.9, de;q=0.8"), false),
+        ("?text=good%20night", Some("xx, fr;q=0.9, de;q=0.8"), true),
+        ("?text=good%20night&to=ja", None, true),
+        ("", None, false),
+    ];
+    for (query, accept_language, conditional) in requests {
+        let mut request = client.get(format!("{}{}", base, query));
+        if let Some(accept_language) = accept_language {
+            request = request.header("Accept-Language", accept_language);
+        }
+        if let (true, Some(etag)) = (conditional, &etag) {
+            request = request.header("If-None-Match", etag);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("-")
+                .to_string()
+        };
+        let (language, tag) = (header("content-language"), header("etag"));
+        if status.is_success() {
+            etag = Some(tag.clone());
+        }
+        println!("GET /translate{}", query);
+        println!("  {} language={} etag={}", status, language, tag);
+        let body = response.text().await?;
+        if !body.is_empty() {
+            println!("  {}", body.trim());
+        }
+    }
+    println!("translator calls: {}", mock.calls());
+    Ok(())
+}
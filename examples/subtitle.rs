@@ -0,0 +1,96 @@
+//! 翻译SRT字幕
+//!
+//! 按空行拆分字幕块，保留序号和时间轴，只翻译字幕文本；
+//! 重复出现的台词只翻译一次，多行台词合并为一行发送，译文按每行字符数和行数约束重新换行，
+//! 超出行数的字幕按句子拆分，仍然放不下的字幕在标准错误中列出供人工检查。
+//!
+//! ```text
+//! cargo run --example subtitle
+//! cargo run --example subtitle -- movie.srt --to ja --live --engine caiyun --max-chars 16
+//! ```
+
+mod common;
+
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::batch::BatchOptions;
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::subtitle::{self, CueConstraints};
+use std::sync::Arc;
+
+/// 没有指定文件时使用的字幕
+const SAMPLE: &str = "1
+00:00:01,000 --> 00:00:03,200
+Where are you going?
+
+2
+00:00:03,400 --> 00:00:06,000
+To the station.
+The last train leaves at midnight.
+
+3
+00:00:06,500 --> 00:00:08,000
+Where are you going?
+
+4
+00:00:09,000 --> 00:00:10,500
+♪
+";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mock = Arc::new(MockTranslator::new());
+    let translator: Arc<dyn AsyncTranslator> = match common::live_translator() {
+        Some((_, translator)) => translator,
+        None => mock.clone(),
+    };
+    let to = match common::arg_value("--to") {
+        Some(code) => Language::from_639_1(&code)
+            .ok_or_else(|| anyhow::anyhow!("unknown language: {}", code))?,
+        None => Language::Chinese,
+    };
+    let srt = match std::env::args().nth(1).filter(|arg| !arg.starts_with("--")) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => SAMPLE.to_string(),
+    };
+
+    let constraints = CueConstraints::new(
+        match common::arg_value("--max-chars") {
+            Some(value) => value.parse()?,
+            None => subtitle::DEFAULT_MAX_CHARS_PER_LINE,
+        },
+        subtitle::DEFAULT_MAX_LINES,
+    );
+
+    let output = subtitle::translate_srt(
+        translator.as_ref(),
+        &srt,
+        None,
+        &to,
+        &constraints,
+        &BatchOptions {
+            partial: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    print!("{}", output.to_srt());
+    for index in &output.report.untranslated {
+        eprintln!("cue {} left untranslated", index);
+    }
+    for cue in &output.report.needs_review {
+        eprintln!(
+            "cue {} needs review: {} line(s), longest {} chars",
+            cue.index, cue.lines, cue.longest_line
+        );
+    }
+    if mock.calls() > 0 {
+        eprintln!(
+            "{} cues, {} texts sent in {} request(s)",
+            output.report.cues,
+            mock.translated_items(),
+            mock.calls()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+/*
+ * FusionTranslator C接口
+ *
+ * 需要以`--features ffi`构建，链接生成的libfusion_translator动态库。
+ * 所有字符串均为以NUL结尾的UTF-8字符串。
+ */
+
+#ifndef FUSION_TRANSLATOR_H
+#define FUSION_TRANSLATOR_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define FT_OK 0
+#define FT_ERR_INVALID_ARGUMENT -1
+#define FT_ERR_INVALID_UTF8 -2
+#define FT_ERR_LANGUAGE -3
+#define FT_ERR_NETWORK -4
+#define FT_ERR_API -5
+#define FT_ERR_NO_RESPONSE -6
+#define FT_ERR_TOO_LONG -7
+#define FT_ERR_CANCELLED -8
+#define FT_ERR_PANIC -9
+#define FT_ERR_UNKNOWN -10
+#define FT_ERR_UNTRANSLATED -11
+#define FT_ERR_RATE_LIMITED -12
+#define FT_ERR_CONTENT_REJECTED -13
+
+/* 翻译器句柄 */
+typedef struct FtTranslator FtTranslator;
+
+/* 创建翻译器，失败时返回NULL，config_json可为NULL */
+FtTranslator *ft_create_translator(const char *engine, const char *config_json);
+
+/* 翻译文本，from为NULL或"auto"表示自动检测，*out需要通过ft_free_string释放 */
+int32_t ft_translate(FtTranslator *handle,
+                     const char *text,
+                     const char *from,
+                     const char *to,
+                     char **out);
+
+/* 释放ft_translate返回的字符串 */
+void ft_free_string(char *s);
+
+/* 销毁翻译器句柄 */
+void ft_destroy(FtTranslator *handle);
+
+/* 当前线程最近一次的错误信息，不需要释放 */
+const char *ft_last_error_message(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FUSION_TRANSLATOR_H */
@@ -0,0 +1,350 @@
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 初始的分块大小调整倍数
+const INITIAL_STEP: f64 = 1.5;
+
+/// 最小的分块大小调整倍数，吞吐量变化方向反转时倍数减半逼近该值
+const MIN_STEP: f64 = 1.05;
+
+/// 自适应分块的当前状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveBatchState {
+    /// 下一个分块的文本数量
+    pub chunk_size: usize,
+    /// 已记录的请求数
+    pub requests: usize,
+    /// 失败的请求数
+    pub failures: usize,
+    /// 因限流失败的请求数
+    pub rate_limited: usize,
+    /// 超时的请求数
+    pub timeouts: usize,
+    /// 最近一次成功请求的吞吐量（文本数/秒）
+    pub last_throughput: Option<f64>,
+}
+
+/// 调整状态
+#[derive(Debug)]
+struct Tuning {
+    /// 对外可见的状态
+    state: AdaptiveBatchState,
+    /// 当前调整方向，true表示增大
+    growing: bool,
+    /// 当前调整倍数
+    step: f64,
+}
+
+/// 自适应分块大小
+///
+/// 从配置的分块大小开始，按每次请求观测到的吞吐量（文本数/秒）调整下一个分块的大小：
+/// 吞吐量上升时沿当前方向继续调整，下降时反转方向并减小调整幅度，逐步收敛到吞吐量最高的大小。
+/// 请求被限流或超时时分块大小减半。
+///
+/// 每次请求固定开销大（如1 QPS限流）的翻译器会增大到上限，
+/// 延迟随文本数量快速增长的翻译器会减小到较小的分块。
+/// 实际发送的分块还受翻译器的`max_batch_len`和`input_limit`限制，调整以实际发送的数量为基准。
+/// 通过`BatchOptions::adaptive`使用，可在多个批次之间共享，`state()`返回当前状态
+#[derive(Debug)]
+pub struct AdaptiveBatcher {
+    /// 最小分块大小
+    min_size: usize,
+    /// 最大分块大小
+    max_size: usize,
+    /// 调整状态
+    tuning: Mutex<Tuning>,
+}
+
+impl AdaptiveBatcher {
+    /// 创建自适应分块
+    ///
+    /// # 参数
+    /// - `initial`: 初始分块大小，通常为`BatchOptions::chunk_size`
+    pub fn new(initial: usize) -> Self {
+        Self {
+            min_size: 1,
+            max_size: usize::MAX,
+            tuning: Mutex::new(Tuning {
+                state: AdaptiveBatchState {
+                    chunk_size: initial.max(1),
+                    requests: 0,
+                    failures: 0,
+                    rate_limited: 0,
+                    timeouts: 0,
+                    last_throughput: None,
+                },
+                growing: true,
+                step: INITIAL_STEP,
+            }),
+        }
+    }
+
+    /// 设置分块大小的范围
+    ///
+    /// # 参数
+    /// - `min`: 最小分块大小，至少为1
+    /// - `max`: 最大分块大小
+    pub fn with_bounds(mut self, min: usize, max: usize) -> Self {
+        self.min_size = min.max(1);
+        self.max_size = max.max(self.min_size);
+        let tuning = self.tuning.get_mut().unwrap();
+        tuning.state.chunk_size = tuning.state.chunk_size.clamp(self.min_size, self.max_size);
+        self
+    }
+
+    /// 下一个分块的文本数量
+    pub fn chunk_size(&self) -> usize {
+        self.tuning.lock().unwrap().state.chunk_size
+    }
+
+    /// 把分块大小限制在翻译器的批量上限之内
+    ///
+    /// # 参数
+    /// - `max_batch_len`: 翻译器单次请求最多的文本数量
+    pub(crate) fn limit(&self, max_batch_len: usize) {
+        let mut tuning = self.tuning.lock().unwrap();
+        let limited = tuning
+            .state
+            .chunk_size
+            .min(max_batch_len.max(self.min_size));
+        tuning.state.chunk_size = limited;
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> AdaptiveBatchState {
+        self.tuning.lock().unwrap().state
+    }
+
+    /// 记录一次成功的请求并调整分块大小
+    ///
+    /// # 参数
+    /// - `items`: 本次请求实际发送的文本数量
+    /// - `elapsed`: 本次请求的耗时
+    pub fn record_success(&self, items: usize, elapsed: Duration) {
+        if items == 0 {
+            return;
+        }
+        let throughput = items as f64 / elapsed.as_secs_f64().max(1e-6);
+        let mut tuning = self.tuning.lock().unwrap();
+        tuning.state.requests += 1;
+        if tuning
+            .state
+            .last_throughput
+            .is_some_and(|last| throughput < last)
+        {
+            tuning.growing = !tuning.growing;
+            tuning.step = (1.0 + (tuning.step - 1.0) / 2.0).max(MIN_STEP);
+        }
+        tuning.state.last_throughput = Some(throughput);
+        let next = if tuning.growing {
+            ((items as f64 * tuning.step).ceil() as usize).max(items + 1)
+        } else {
+            ((items as f64 / tuning.step).floor() as usize).min(items - 1)
+        };
+        tuning.state.chunk_size = next.clamp(self.min_size, self.max_size);
+    }
+
+    /// 记录一次失败的请求
+    ///
+    /// 限流（`TranslatorError::RateLimited`、HTTP 429）和超时时分块大小减半，
+    /// 并重新从增大方向开始探测；其他错误只计数
+    ///
+    /// # 参数
+    /// - `error`: 请求返回的错误
+    pub fn record_failure(&self, error: &anyhow::Error) {
+        let mut tuning = self.tuning.lock().unwrap();
+        tuning.state.requests += 1;
+        tuning.state.failures += 1;
+        let (rate_limited, timeout) = classify(error);
+        tuning.state.rate_limited += usize::from(rate_limited);
+        tuning.state.timeouts += usize::from(timeout);
+        if rate_limited || timeout {
+            tuning.state.chunk_size = (tuning.state.chunk_size / 2).max(self.min_size);
+            tuning.state.last_throughput = None;
+            tuning.growing = true;
+        }
+    }
+}
+
+/// 判断错误是否为限流或超时
+///
+/// # 参数
+/// - `error`: 请求返回的错误
+///
+/// # 返回值
+/// （是否被限流，是否超时）
+fn classify(error: &anyhow::Error) -> (bool, bool) {
+    match error.downcast_ref::<TranslatorError>() {
+        Some(TranslatorError::RateLimited(_)) | Some(TranslatorError::RequestFailed(429)) => {
+            (true, false)
+        }
+        Some(TranslatorError::Reqwest(e)) => (false, e.is_timeout()),
+        Some(_) => (false, false),
+        None => (
+            false,
+            error
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(reqwest::Error::is_timeout),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::adaptive::AdaptiveBatcher;
+    use crate::fusion_translator::async_translator::{
+        AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    };
+    use crate::fusion_translator::batch::{translate_map_with, BatchOptions};
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 延迟随分块大小变化的模拟翻译器
+    ///
+    /// 每次请求耗时`overhead + per_item * n + quadratic * n²`，至少为`min_interval`（模拟QPS限流）
+    struct Simulated {
+        /// 每次请求的固定开销
+        overhead: Duration,
+        /// 每个文本增加的耗时
+        per_item: Duration,
+        /// 与文本数量平方成正比的耗时
+        quadratic: Duration,
+        /// 两次请求之间的最小间隔
+        min_interval: Duration,
+        /// 单次请求最多的文本数量
+        max_batch_len: Option<usize>,
+    }
+
+    impl Simulated {
+        /// 发送`n`个文本的耗时
+        fn latency(&self, n: usize) -> Duration {
+            let n = n as u32;
+            (self.overhead + self.per_item * n + self.quadratic * n * n).max(self.min_interval)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncTranslator for Simulated {
+        fn local(&self) -> bool {
+            true
+        }
+
+        fn max_batch_len(&self) -> Option<usize> {
+            self.max_batch_len
+        }
+
+        async fn translate(
+            &self,
+            query: &str,
+            _from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationOutput> {
+            tokio::time::sleep(self.latency(1)).await;
+            Ok(TranslationOutput {
+                text: query.to_string(),
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+
+        async fn translate_vec(
+            &self,
+            query: &[String],
+            _from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationListOutput> {
+            tokio::time::sleep(self.latency(query.len())).await;
+            Ok(TranslationListOutput {
+                text: query.to_vec(),
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// 用自适应分块翻译`count`个不同的文本，返回批次结束后的分块大小
+    async fn run(translator: &Simulated, batcher: &Arc<AdaptiveBatcher>, count: usize) -> usize {
+        let map = (0..count)
+            .map(|i| (i, format!("text {}", i)))
+            .collect::<HashMap<_, _>>();
+        let options = BatchOptions {
+            adaptive: Some(batcher.clone()),
+            ..Default::default()
+        };
+        let output = translate_map_with(translator, &map, None, &Language::English, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.translations.len(), count);
+        batcher.chunk_size()
+    }
+
+    /// 测试延迟随数量平方增长时收敛到吞吐量最高的分块大小
+    ///
+    /// 吞吐量为`n / (100ms + n² × 1ms)`，在n = 10时最高
+    #[tokio::test(start_paused = true)]
+    async fn test_converges_to_optimum() {
+        let translator = Simulated {
+            overhead: Duration::from_millis(100),
+            per_item: Duration::ZERO,
+            quadratic: Duration::from_millis(1),
+            min_interval: Duration::ZERO,
+            max_batch_len: None,
+        };
+        for initial in [1, 50, 200] {
+            let batcher = Arc::new(AdaptiveBatcher::new(initial));
+            let size = run(&translator, &batcher, 2000).await;
+            assert!((7..=13).contains(&size), "{} -> {}", initial, size);
+        }
+    }
+
+    /// 测试1 QPS限流的翻译器增大到翻译器的批量上限
+    #[tokio::test(start_paused = true)]
+    async fn test_grows_to_max_batch_len() {
+        let translator = Simulated {
+            overhead: Duration::from_millis(50),
+            per_item: Duration::from_millis(2),
+            quadratic: Duration::ZERO,
+            min_interval: Duration::from_secs(1),
+            max_batch_len: Some(120),
+        };
+        let batcher = Arc::new(AdaptiveBatcher::new(10));
+        run(&translator, &batcher, 3000).await;
+        let state = batcher.state();
+        assert_eq!(state.chunk_size, 120, "{:?}", state);
+        assert_eq!(state.failures, 0);
+    }
+
+    /// 测试限流时分块大小减半，其他错误只计数
+    #[test]
+    fn test_shrinks_on_rate_limit() {
+        let batcher = AdaptiveBatcher::new(40).with_bounds(5, 100);
+        batcher.record_failure(&TranslatorError::RateLimited("429".into()).into());
+        assert_eq!(batcher.chunk_size(), 20);
+        batcher.record_failure(&TranslatorError::RequestFailed(429).into());
+        batcher.record_failure(&TranslatorError::RequestFailed(429).into());
+        assert_eq!(batcher.chunk_size(), 5);
+        batcher.record_failure(&TranslatorError::NoResponse.into());
+        let state = batcher.state();
+        assert_eq!(state.chunk_size, 5);
+        assert_eq!(
+            (
+                state.requests,
+                state.failures,
+                state.rate_limited,
+                state.timeouts
+            ),
+            (4, 4, 3, 0)
+        );
+
+        // 成功后从减半后的大小缓慢增大，且不超过上限
+        batcher.record_success(5, Duration::from_millis(100));
+        assert_eq!(batcher.chunk_size(), 8);
+        assert_eq!(batcher.state().last_throughput, Some(50.0));
+        let capped = AdaptiveBatcher::new(90).with_bounds(1, 100);
+        capped.record_success(90, Duration::from_secs(1));
+        assert_eq!(capped.chunk_size(), 100);
+    }
+}
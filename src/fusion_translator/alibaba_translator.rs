@@ -1,9 +1,28 @@
 use crate::fusion_translator::async_translator::{
-    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
 };
+use crate::fusion_translator::batch::{self, empty_batch, validate_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
 use crate::fusion_translator::translator_error::TranslatorError;
-use reqwest::Client;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::{TranslatorOptions, DEFAULT_MAX_URL_LEN};
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
+use reqwest::Url;
 use serde_json::Value;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// 批量翻译时拼接多个文本的分隔符
+const BATCH_SEPARATOR: &str = "_._._";
 
 /// 阿里翻译器实现
 ///
@@ -13,8 +32,12 @@ pub struct AlibabaTranslator {
     input_limit: u32,
     /// API请求地址
     host: String,
-    /// HTTP客户端
-    client: Client,
+    /// GET请求允许的最大URL长度
+    max_url_len: usize,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
 }
 
 /// 默认实现
@@ -24,26 +47,8 @@ impl Default for AlibabaTranslator {
     }
 }
 
-/// 检查输入文本长度是否超出限制
-///
-/// # 参数
-/// - `query`: 待检查的文本
-/// - `input_limit`: 最大长度限制
-///
-/// # 返回值
-/// - `Ok(())` - 长度在限制范围内
-/// - `Err(TranslatorError::RequestToLong)` - 长度超出限制
-pub fn input_limit_checker(query: &str, input_limit: u32) -> Result<(), TranslatorError> {
-    if query.len() > input_limit as usize {
-        return Err(TranslatorError::RequestToLong(
-            query.len() as u32,
-            input_limit,
-        ));
-    }
-    Ok(())
-}
-
-#[async_trait::async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 impl AsyncTranslator for AlibabaTranslator {
     /// 判断是否为本地翻译器
     ///
@@ -52,6 +57,20 @@ impl AsyncTranslator for AlibabaTranslator {
         false
     }
 
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "alibaba", &self.host).await
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(Language::to_mymemory_short))
+    }
+
+    /// 单次请求最多的UTF-8字节数
+    fn input_limit(&self) -> Option<usize> {
+        Some(self.input_limit as usize)
+    }
+
     /// 翻译单个文本
     ///
     /// # 参数
@@ -67,73 +86,83 @@ impl AsyncTranslator for AlibabaTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationOutput> {
-        input_limit_checker(query, self.input_limit)?;
-        let _from_orig = from;
-        let _from = match _from_orig {
-            Some(lang) => lang
-                .to_mymemory_short()
-                .ok_or(TranslatorError::UnknownLanguage(lang))?,
-            None => "auto",
-        };
-
-        let url = format!(
-            "{}?domain={}&query={}&srcLang={}&tgtLang={}",
-            self.host,
-            "general",
-            query,
-            _from,
-            to.to_mymemory_short()
-                .ok_or(TranslatorError::UnknownLanguage(*to))?
-        );
-
-        let response = self.client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(TranslatorError::RequestFailed(response.status().as_u16()).into());
-        }
-
-        let resp: Value = response.json().await?;
-        let resp = &resp["data"];
-        let _lang = resp["detectLanguage"].to_string();
-        let mut text = resp["translateText"].to_string();
-
-        if text == "null" {
-            return Err(TranslatorError::NoResponse.into());
-        }
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
 
-        if text.starts_with('"') && text.ends_with('"') {
-            text = text[1..text.len() - 1].to_string();
-        }
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
 
-        Ok(TranslationOutput {
-            text,
-            lang: Some(*to),
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("alibaba", query, from, to, options)
         })
     }
 
-    /// 翻译多个文本
+    /// 按指定选项翻译多个文本
     ///
     /// # 参数
     /// - `query`: 待翻译的文本数组
     /// - `from`: 源语言，None表示自动检测
     /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
     ///
     /// # 返回值
     /// 翻译结果数组
-    async fn translate_vec(
+    async fn translate_vec_with_options(
         &self,
         query: &[String],
         from: Option<Language>,
         to: &Language,
+        options: &TranslateOptions,
     ) -> anyhow::Result<TranslationListOutput> {
-        let t = self.translate(&query.join("_._._"), from, to).await?;
-        Ok(TranslationListOutput {
-            text: t
-                .text
-                .split("_._._")
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-            lang: t.lang,
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            BATCH_SEPARATOR.len(),
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("alibaba", query, from, to, &options)
         })
     }
 }
@@ -146,11 +175,245 @@ impl AlibabaTranslator {
     #[allow(dead_code)]
     pub fn new() -> Self {
         AlibabaTranslator {
-            client: Client::new(),
+            transport: TranslatorOptions::default().build_transport(TranslatorType::Alibaba),
             input_limit: 500,
             host: "https://translate.alibaba.com/api/translate/text".to_string(),
+            max_url_len: DEFAULT_MAX_URL_LEN,
+            politeness: Some(Politeness::shared("alibaba", PolitenessConfig::default())),
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::Alibaba);
+        self.politeness = options.politeness.resolve("alibaba");
+        self.with_max_url_len(options.max_url_len)
+    }
+
+    /// 设置GET请求允许的最大URL长度
+    ///
+    /// 默认为`DEFAULT_MAX_URL_LEN`
+    ///
+    /// # 参数
+    /// - `max_url_len`: 编码后URL的最大字节数
+    pub fn with_max_url_len(mut self, max_url_len: usize) -> Self {
+        self.max_url_len = max_url_len;
+        self
+    }
+
+    /// 构造请求地址
+    ///
+    /// 阿里翻译的网页接口没有公开的POST方式，URL过长时不改用POST而是返回错误
+    ///
+    /// # 参数
+    /// - `params`: 请求参数
+    ///
+    /// # 返回值
+    /// 编码后的URL超过`max_url_len`时返回`TranslatorError::RequestTooLong`
+    fn url(&self, params: &[(&str, &str)]) -> anyhow::Result<Url> {
+        let url = Url::parse_with_params(&self.host, params)?;
+        if url.as_str().len() > self.max_url_len {
+            return Err(TranslatorError::RequestTooLong(
+                url.as_str().len() as u32,
+                self.max_url_len as u32,
+            )
+            .into());
         }
+        Ok(url)
     }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，数量和长度不超过翻译器的批量上限
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return Ok(empty_batch(options));
+        }
+        let t = self
+            .translate_preprocessed(
+                &whitespace::trim_all(query, options).join(BATCH_SEPARATOR),
+                from,
+                to,
+                options,
+            )
+            .await?;
+        let text = validate_batch(
+            query.len(),
+            t.text
+                .split(BATCH_SEPARATOR)
+                .map(|s| s.to_string())
+                .collect(),
+        )?;
+        Ok(TranslationListOutput {
+            text: whitespace::restore_all(query, text, options),
+            lang: t.lang,
+            detail: t.detail,
+        })
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 预处理原文后由`translate_preprocessed`翻译
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = preprocess::apply(query, options)?;
+        self.translate_preprocessed(&query, from, to, options).await
+    }
+
+    /// 翻译已经过`preprocess::apply`处理的单个文本
+    ///
+    /// 批量翻译在分块前已预处理每个文本，直接调用此方法，不再重复预处理。
+    /// 请求ID通过`X-Request-Id`请求头传递
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_preprocessed(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        if let Err(err) = long_text::check_byte_limit(query, self.input_limit as usize) {
+            if !options.auto_split {
+                return Err(err.into());
+            }
+            let max_bytes = self.input_limit as usize;
+            return long_text::translate_split(self, query, from, to, options, max_bytes).await;
+        }
+        let mut languages = LanguageResolver::new(options, Language::to_mymemory_short);
+        let _from = match from {
+            Some(lang) => languages.resolve(lang)?,
+            None => "auto",
+        };
+
+        let url = self.url(&[
+            ("domain", "general"),
+            ("query", whitespace::trim(query, options)),
+            ("srcLang", _from),
+            ("tgtLang", languages.resolve(*to)?),
+        ])?;
+
+        let request_id = options.resolve_request_id();
+        let span = tracing::debug_span!("translate", backend = "alibaba", request_id = request_id);
+        let resp: Value = options
+            .run_cancellable(
+                0,
+                async {
+                    let request =
+                        with_request_id_header(HttpRequest::get(url), request_id.as_deref());
+                    let response =
+                        politeness::send(self.politeness.as_deref(), &*self.transport, request)
+                            .await?;
+                    if !response.is_success() {
+                        return Err(TranslatorError::RequestFailed(response.status).into());
+                    }
+                    response.json()
+                }
+                .instrument(span),
+            )
+            .await?;
+        let text = postprocess::apply(query, parse_response(&resp)?, options);
+        let possibly_untranslated = untranslated::check(query, &text, from, to, options)?;
+
+        Ok(TranslationOutput {
+            text,
+            lang: Some(*to),
+            detail: TranslationDetail {
+                request_id,
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+/// 解析阿里翻译响应
+///
+/// # 参数
+/// - `resp`: 阿里翻译返回的JSON响应
+///
+/// # 返回值
+/// 译文，响应中没有`data.translateText`时返回`TranslatorError::NoResponse`
+fn parse_response(resp: &Value) -> Result<String, TranslatorError> {
+    Ok(resp["data"]["translateText"]
+        .as_str()
+        .ok_or(TranslatorError::NoResponse)?
+        .to_string())
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl AlibabaTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, Value)> {
+        let url = format!(
+            "{}?domain=general&query=Hello, world&srcLang=en&tgtLang=zh",
+            self.host
+        );
+        Ok((
+            "success.json",
+            self.transport
+                .execute(HttpRequest::get(url))
+                .await?
+                .json()?,
+        ))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "success.json" => parse_response(&serde_json::from_str(body)?)?,
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    };
+    Ok(())
 }
 
 #[cfg(test)]
@@ -165,23 +428,31 @@ mod tests {
         assert!(!translator.local());
     }
 
-    /// 测试输入长度检查
-    #[test]
-    fn test_input_limit_checker() {
-        use crate::fusion_translator::alibaba_translator::input_limit_checker;
-
-        let result = input_limit_checker("short text", 500);
-        assert!(result.is_ok());
-
-        let long_text = "a".repeat(600);
-        let result = input_limit_checker(&long_text, 500);
-        assert!(result.is_err());
-    }
-
     /// 测试默认实现
     #[test]
     fn test_default() {
         let translator = AlibabaTranslator::default();
         assert!(!translator.local());
     }
+
+    /// 测试URL过长时返回`RequestTooLong`而不发送请求
+    #[tokio::test]
+    async fn test_url_too_long() {
+        use crate::fusion_translator::async_translator::Language;
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use crate::fusion_translator::translator_options::TranslatorOptions;
+
+        let translator = AlibabaTranslator::new()
+            .with_translator_options(&TranslatorOptions::new().with_max_url_len(150));
+        let error = translator
+            .translate(&"中文".repeat(20), None, &Language::English)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RequestTooLong(_, 150))
+        ));
+        let url = translator.url(&[("query", "a&b")]).unwrap();
+        assert_eq!(url.query(), Some("query=a%26b"));
+    }
 }
@@ -1,10 +1,28 @@
+use crate::fusion_translator::input_check::{InputCheck, InputUnit};
+use crate::fusion_translator::provenance::Provenance;
+use crate::fusion_translator::translate_options::TranslateOptions;
+
+// `Language`及各翻译器的`to_*`/`from_*`代码表由过程宏生成。希伯来语、爪哇语、菲律宾语和挪威语
+// 在各翻译器之间的代码不一致，`Language::from_vendor`额外接受旧代码，规范化方式见`language_map`
 lang_generator::generate_language!();
 
+/// 流式翻译产出的译文片段
+#[cfg(not(target_arch = "wasm32"))]
+pub type TokenStream<'a> = futures_util::stream::BoxStream<'a, anyhow::Result<String>>;
+/// 流式翻译产出的译文片段
+#[cfg(target_arch = "wasm32")]
+pub type TokenStream<'a> = futures_util::stream::LocalBoxStream<'a, anyhow::Result<String>>;
+
 /// 异步翻译器特征
 ///
 /// 定义了翻译器的通用接口，支持单文本翻译和多文本批量翻译
 /// 所有翻译器实现都必须实现此特征
-#[async_trait::async_trait]
+///
+/// 实现必须可以通过`&self`在多个任务中同时调用：内部状态使用原子变量或锁保护，
+/// 返回的future满足`Send`，因此`Arc<dyn AsyncTranslator>`可以放入共享状态并在`tokio::spawn`中使用。
+/// 内置翻译器和包装层都满足这一要求，并由本模块的测试检查
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 pub trait AsyncTranslator: Send + Sync {
     /// 判断是否为本地翻译器
     ///
@@ -16,6 +34,112 @@ pub trait AsyncTranslator: Send + Sync {
     /// - `false`: 远程翻译器
     fn local(&self) -> bool;
 
+    /// 单次批量请求最多的文本数量
+    ///
+    /// `translate_vec`会把超出上限的输入拆分为多次请求，None表示翻译服务没有数量限制
+    fn max_batch_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// 单次请求的长度上限，单位见`input_unit`
+    ///
+    /// 批量翻译按此上限分块（拼接发送的翻译器计入分隔符，按UTF-8字节数计算，
+    /// 对其他单位偏保守），单个文本超出时按`TranslateOptions::auto_split`拆分或报错；
+    /// None表示没有限制
+    fn input_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// 翻译服务计算输入长度的单位，默认为UTF-8字节数
+    fn input_unit(&self) -> InputUnit {
+        InputUnit::Bytes
+    }
+
+    /// 检查输入长度并给出自动拆分时的拆分位置
+    ///
+    /// 默认实现按`input_limit`和`input_unit`计算，优先在句子边界拆分，
+    /// 拆分位置不会落在字素簇（表情符号序列、国旗等）内部。
+    /// 不发送请求，可以在消耗配额之前提示用户并展示拆分结果
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    ///
+    /// # 返回值
+    /// 按翻译服务的单位计算的长度、上限和拆分位置
+    fn check_input(&self, query: &str) -> InputCheck {
+        InputCheck::new(query, self.input_limit(), self.input_unit())
+    }
+
+    /// 翻译器支持的语言
+    ///
+    /// 按翻译器的语言代码表列出可以作为源语言或目标语言的语言，不考虑语言回退；
+    /// None表示不限制或无法确定，`capability::capabilities`据此生成能力矩阵
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        None
+    }
+
+    /// 是否按请求量计费
+    ///
+    /// 计费的翻译器默认不会为定位被拒绝的文本发送额外的请求（见`BatchOptions::bisect_paid`）
+    fn paid(&self) -> bool {
+        false
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 默认实现返回None，表示翻译器不提供语言检测，`detect::detect_consensus`会跳过这类翻译器
+    ///
+    /// # 参数
+    /// - `text`: 待检测的文本
+    ///
+    /// # 返回值
+    /// 检测到的语言，无法判断时为None
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let _ = text;
+        Ok(None)
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 第一次请求要经过DNS解析和TLS握手，会增加数百毫秒的延迟。交互式应用可以在启动时调用，
+    /// 让第一次翻译复用连接池中已建立的连接。内置的HTTP翻译器向翻译接口发送不带参数的`HEAD`请求，
+    /// 不翻译文本、不消耗额度；包装层转发给所有内部翻译器。
+    ///
+    /// 预热失败不影响后续翻译，只记录日志，不返回错误。默认实现不做任何事
+    async fn warm_up(&self) {}
+
+    /// 是否支持流式输出
+    ///
+    /// 为true时`translate_streaming`在译文生成的过程中逐段产出；包装层不转发此能力
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// 流式翻译单个文本
+    ///
+    /// 默认实现调用`translate`，完成后把整段译文作为唯一的片段产出；
+    /// 支持流式输出的翻译器应覆盖此方法，并让`supports_streaming`返回true
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测语言
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按顺序拼接后为完整译文的片段流，出错时产出错误并结束
+    fn translate_streaming<'a>(
+        &'a self,
+        query: &'a str,
+        from: Option<Language>,
+        to: &'a Language,
+    ) -> TokenStream<'a> {
+        Box::pin(futures_util::stream::once(async move {
+            self.translate(query, from, to)
+                .await
+                .map(|output| output.text)
+        }))
+    }
+
     /// 翻译单个文本
     ///
     /// 将指定的文本从源语言翻译到目标语言
@@ -39,6 +163,9 @@ pub trait AsyncTranslator: Send + Sync {
     ///
     /// 批量将多个文本从源语言翻译到目标语言
     ///
+    /// 成功时返回的译文数量与`query`相同且顺序一致，调用方可以直接按下标对应；
+    /// 翻译服务返回的数量不一致时返回`TranslatorError::BatchSizeMismatch`
+    ///
     /// # 参数
     /// - `query`: 待翻译的文本数组
     /// - `from`: 源语言，None表示自动检测语言
@@ -53,27 +180,335 @@ pub trait AsyncTranslator: Send + Sync {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationListOutput>;
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 默认实现忽略选项直接调用`translate`，仅在结果中回填请求ID；
+    /// 支持选项的翻译器应覆盖此方法
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测语言
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let mut output = self.translate(query, from, to).await?;
+        output.detail.request_id = options.resolve_request_id();
+        Ok(output)
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 默认实现忽略选项直接调用`translate_vec`，仅在结果中回填请求ID；
+    /// 支持选项的翻译器应覆盖此方法
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测语言
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let mut output = self.translate_vec(query, from, to).await?;
+        output.detail.request_id = options.resolve_request_id();
+        Ok(output)
+    }
 }
 
 /// 单文本翻译结果
 ///
 /// 包含翻译后的文本和检测到的语言信息
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 #[allow(dead_code)]
 pub struct TranslationOutput {
     /// 翻译后的文本
     pub text: String,
     /// 文本语言
     pub lang: Option<Language>,
+    /// 翻译详情，包含候选译文等附加信息
+    pub detail: TranslationDetail,
+}
+
+/// 翻译详情
+///
+/// 记录主译文之外的附加信息，翻译器不提供的字段保持默认值
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TranslationDetail {
+    /// 候选译文列表，按得分从高到低排列，不包含主译文
+    pub alternatives: Vec<AlternativeTranslation>,
+    /// 本次调用使用的请求ID（关联ID）
+    pub request_id: Option<String>,
+    /// 开启语言回退时发生的语言替换
+    pub substitutions: Vec<LanguageSubstitution>,
+    /// 译文是否疑似未翻译（与原文相同），批量翻译时表示至少有一条疑似未翻译
+    pub possibly_untranslated: bool,
+    /// 翻译服务丢失了表情符号占位符，`emoji::EmojiGuard`把缺失的表情符号追加到了译文末尾，
+    /// 批量翻译时表示至少有一条被追加
+    pub emoji_appended: bool,
+    /// 原文和译文的发音音频地址，仅在翻译器支持并开启时提供
+    pub audio_urls: Option<AudioUrls>,
+    /// 单词查询的词典释义，仅在翻译器支持并开启时提供
+    pub dictionary: Vec<DictionaryEntry>,
+    /// 上下文例句（原文例句，译文例句），仅在翻译器提供时返回
+    pub examples: Vec<(String, String)>,
+    /// 翻译服务返回的原始语言代码
+    ///
+    /// 代码无法可靠地转换回语言（未收录或多个语言共用）时，`lang`为None，只保留此字段
+    pub lang_raw: Option<String>,
+    /// 实际完成翻译的后端，由组合翻译器填写（如`experiment::ExperimentTranslator`填写分组名称）
+    pub backend: Option<String>,
+    /// 结果的新鲜程度，由`fallback::FallbackTranslator`填写
+    pub freshness: Option<Freshness>,
+    /// 结果经过的缓存、重试、故障转移和拆分等环节，由各组合翻译器追加
+    pub provenance: Provenance,
+}
+
+/// 故障转移翻译器结果的新鲜程度
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Freshness {
+    /// 第一个翻译器的结果
+    Fresh,
+    /// 之后的某个翻译器的结果
+    Fallback,
+    /// 延迟预算用完或所有翻译器都失败后，从缓存中取出的可能已过期的译文
+    Stale,
+}
+
+/// 发音音频地址
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioUrls {
+    /// 原文的发音地址
+    pub source: Option<String>,
+    /// 译文的发音地址
+    pub target: Option<String>,
+}
+
+/// 词典释义
+///
+/// 一个词性下的释义和例句
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DictionaryEntry {
+    /// 词性（如`n.`、`v.`），翻译服务未提供时为空
+    pub part_of_speech: String,
+    /// 释义列表
+    pub meanings: Vec<String>,
+    /// 例句列表
+    pub examples: Vec<String>,
+}
+
+/// 语言替换记录
+///
+/// 翻译器不支持请求的语言时，记录实际使用的替代语言
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LanguageSubstitution {
+    /// 请求的语言
+    pub requested: Language,
+    /// 实际使用的语言
+    pub used: Language,
+}
+
+/// 候选译文
+///
+/// 翻译服务返回的主译文以外的其他翻译结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlternativeTranslation {
+    /// 候选译文文本
+    pub text: String,
+    /// 匹配得分，范围通常为0到1，翻译服务未提供时为None
+    pub score: Option<f32>,
+    /// 候选译文来源（如翻译记忆库的贡献者），未知时为None
+    pub source: Option<String>,
 }
 
 /// 多文本翻译结果
 ///
 /// 包含翻译后的文本数组和检测到的语言信息
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct TranslationListOutput {
     /// 翻译后的文本数组
     pub text: Vec<String>,
     /// 文本语言
     pub lang: Option<Language>,
+    /// 整个批次的翻译详情
+    pub detail: TranslationDetail,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::alibaba_translator::AlibabaTranslator;
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::baidu_translator::BaiduTranslator;
+    use crate::fusion_translator::bing_web_translator::BingWebTranslator;
+    use crate::fusion_translator::cache::CachedTranslator;
+    use crate::fusion_translator::caiyun_translator::CaiyunTranslator;
+    use crate::fusion_translator::fallback::FallbackTranslator;
+    use crate::fusion_translator::google_web_translator::GoogleWebTranslator;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::mymemory_translator::MyMemoryTranslator;
+    use crate::fusion_translator::rate_limit::RateLimitedTranslator;
+    use crate::fusion_translator::retry::RetryTranslator;
+    use crate::fusion_translator::reverso_translator::ReversoTranslator;
+    use crate::fusion_translator::sse::collect_text;
+    use crate::fusion_translator::tmx::{TmTranslator, TranslationMemory};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::ErrorKind;
+    use crate::fusion_translator::transmart_translator::TranSmartTranslator;
+    use crate::fusion_translator::youdao_translator::YoudaoTranslator;
+    use futures_util::StreamExt;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    /// 编译期检查类型可以在任务之间共享
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    /// 所有内置翻译器和包装层，包装层内部使用模拟翻译器
+    fn translators() -> Vec<(&'static str, Arc<dyn AsyncTranslator>)> {
+        let mock = || Arc::new(MockTranslator::new()) as Arc<dyn AsyncTranslator>;
+        vec![
+            ("baidu", Arc::new(BaiduTranslator::new("id", "key"))),
+            ("youdao", Arc::new(YoudaoTranslator::new("key", "secret"))),
+            ("alibaba", Arc::new(AlibabaTranslator::new())),
+            ("caiyun", Arc::new(CaiyunTranslator::new("token", "demo"))),
+            ("mymemory", Arc::new(MyMemoryTranslator::new())),
+            ("transmart", Arc::new(TranSmartTranslator::new())),
+            ("reverso", Arc::new(ReversoTranslator::new())),
+            ("bing_web", Arc::new(BingWebTranslator::new())),
+            ("google_web", Arc::new(GoogleWebTranslator::new())),
+            ("mock", mock()),
+            ("retry", Arc::new(RetryTranslator::new(mock(), 2))),
+            (
+                "rate_limit",
+                Arc::new(RateLimitedTranslator::new(mock(), 1000.0)),
+            ),
+            ("cache", Arc::new(CachedTranslator::new(mock(), 100))),
+            (
+                "tmx",
+                Arc::new(TmTranslator::new(mock(), "mock", TranslationMemory::new())),
+            ),
+            (
+                "fallback",
+                Arc::new(FallbackTranslator::new(vec![("mock".to_string(), mock())])),
+            ),
+        ]
+    }
+
+    /// 测试所有翻译器满足`Send + Sync + 'static`，并可以作为特征对象使用
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<BaiduTranslator>();
+        assert_send_sync::<YoudaoTranslator>();
+        assert_send_sync::<AlibabaTranslator>();
+        assert_send_sync::<CaiyunTranslator>();
+        assert_send_sync::<MyMemoryTranslator>();
+        assert_send_sync::<TranSmartTranslator>();
+        assert_send_sync::<ReversoTranslator>();
+        assert_send_sync::<BingWebTranslator>();
+        assert_send_sync::<GoogleWebTranslator>();
+        assert_send_sync::<MockTranslator>();
+        assert_send_sync::<RetryTranslator>();
+        assert_send_sync::<RateLimitedTranslator>();
+        assert_send_sync::<CachedTranslator>();
+        assert_send_sync::<TmTranslator>();
+        assert_send_sync::<FallbackTranslator>();
+        assert_send_sync::<Arc<dyn AsyncTranslator>>();
+        assert_eq!(translators().len(), 15);
+    }
+
+    /// 测试每个翻译器的调用都可以在`tokio::spawn`中执行
+    ///
+    /// 令牌已取消，真实翻译器不会发出请求
+    #[tokio::test]
+    async fn test_spawn() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = TranslateOptions::new().with_cancellation(token);
+        for (name, translator) in translators() {
+            let options = options.clone();
+            let result = tokio::spawn(async move {
+                translator
+                    .translate_with_options("hello", None, &Language::Chinese, &options)
+                    .await
+            })
+            .await
+            .unwrap();
+            if let Err(e) = result {
+                assert_eq!(ErrorKind::of(&e), ErrorKind::Cancelled, "{}: {:#}", name, e);
+            }
+        }
+    }
+
+    /// 测试通过每个包装层同时发起100个翻译
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_wrappers() {
+        for (name, translator) in translators().into_iter().skip(9) {
+            let tasks = (0..100)
+                .map(|i| {
+                    let translator = translator.clone();
+                    tokio::spawn(async move {
+                        let query = format!("text {}", i % 10);
+                        let output = translator
+                            .translate(&query, None, &Language::Chinese)
+                            .await
+                            .unwrap();
+                        assert_eq!(output.text, query.to_uppercase());
+                    })
+                })
+                .collect::<Vec<_>>();
+            for task in tasks {
+                task.await.unwrap_or_else(|e| panic!("{}: {}", name, e));
+            }
+        }
+    }
+
+    /// 测试流式翻译：默认实现整段产出，支持流式输出的翻译器逐段产出
+    #[tokio::test]
+    async fn test_translate_streaming() {
+        let plain = MockTranslator::new();
+        assert!(!plain.supports_streaming());
+        let tokens = plain
+            .translate_streaming("hello big world", None, &Language::Chinese)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].as_ref().unwrap(), "HELLO BIG WORLD");
+
+        let streaming = Arc::new(MockTranslator::new().with_streaming(true));
+        assert!(streaming.supports_streaming());
+        let tokens = streaming
+            .translate_streaming("hello big world", None, &Language::Chinese)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(tokens, ["HELLO ", "BIG ", "WORLD"]);
+
+        let retry = RetryTranslator::new(streaming.clone(), 1);
+        assert!(!retry.supports_streaming());
+        let text = collect_text(retry.translate_streaming("hello", None, &Language::Chinese))
+            .await
+            .unwrap();
+        assert_eq!(text, "HELLO");
+
+        let failing = MockTranslator::new().with_streaming(true).fail_on("boom");
+        let err = collect_text(failing.translate_streaming("boom", None, &Language::Chinese))
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Api);
+    }
 }
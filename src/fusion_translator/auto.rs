@@ -0,0 +1,346 @@
+use crate::fusion_translator::async_translator::AsyncTranslator;
+use crate::fusion_translator::cache::CachedTranslator;
+use crate::fusion_translator::fallback::FallbackTranslator;
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory, TranslatorStackConfig, TranslatorType,
+};
+use std::sync::Arc;
+
+/// 覆盖默认优先级的环境变量，值为逗号分隔的翻译器名称，如`youdao,baidu`
+pub const PRIORITY_ENV: &str = "FUSION_TRANSLATOR_PRIORITY";
+
+/// 默认优先级
+///
+/// 需要凭据的翻译器在前，无需凭据的翻译器只在`AutoConfig::with_keyless`开启时使用
+pub const DEFAULT_PRIORITY: [TranslatorType; 9] = [
+    TranslatorType::Youdao,
+    TranslatorType::Baidu,
+    TranslatorType::Caiyun,
+    TranslatorType::GoogleWeb,
+    TranslatorType::MyMemory,
+    TranslatorType::TranSmart,
+    TranslatorType::Alibaba,
+    TranslatorType::Reverso,
+    TranslatorType::BingWeb,
+];
+
+/// 每个翻译器的默认重试次数
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// 单个HTTP请求的默认超时时间（毫秒），超时后换用下一个翻译器
+pub const DEFAULT_TIMEOUT_MS: u64 = 15_000;
+
+/// 默认的缓存容量
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// 翻译器的默认请求速率（每秒请求数）
+///
+/// 百度翻译标准版限制为1 QPS，Reverso频繁返回429同样按1 QPS，其余翻译器使用较保守的5 QPS
+///
+/// # 参数
+/// - `translator_type`: 翻译器类型
+pub fn default_qps(translator_type: TranslatorType) -> f64 {
+    match translator_type {
+        TranslatorType::Baidu | TranslatorType::Reverso => 1.0,
+        _ => 5.0,
+    }
+}
+
+/// 解析优先级
+///
+/// # 参数
+/// - `value`: 逗号分隔的翻译器名称，名称规则同`TranslatorType::parse`，忽略空项
+///
+/// # 返回值
+/// 按出现顺序排列、去重后的翻译器类型；名称无法识别时返回`TranslatorError::Configuration`
+pub fn parse_priority(value: &str) -> Result<Vec<TranslatorType>, TranslatorError> {
+    let mut priority = Vec::new();
+    for name in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let translator_type = name
+            .parse::<TranslatorType>()
+            .map_err(|e| TranslatorError::Configuration(format!("{} in {}", e, PRIORITY_ENV)))?;
+        if !priority.contains(&translator_type) {
+            priority.push(translator_type);
+        }
+    }
+    Ok(priority)
+}
+
+/// 自动选择翻译器的配置
+///
+/// 按优先级检查每个翻译器的凭据环境变量（见README的环境变量配置），
+/// 为已配置的翻译器各自添加重试和限流，组成按顺序故障转移的`FallbackTranslator`，
+/// 最外层再加进程内缓存
+#[derive(Debug, Clone, Default)]
+pub struct AutoConfig {
+    /// 是否使用无需凭据的翻译器（MyMemory、TranSmart、阿里、Reverso、Bing网页版），默认关闭
+    pub allow_keyless: bool,
+    /// 优先级，None时读取`FUSION_TRANSLATOR_PRIORITY`，仍未设置时使用`DEFAULT_PRIORITY`
+    pub priority: Option<Vec<TranslatorType>>,
+}
+
+impl AutoConfig {
+    /// 创建默认配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置是否使用无需凭据的翻译器
+    ///
+    /// # 参数
+    /// - `allowed`: 是否使用
+    pub fn with_keyless(mut self, allowed: bool) -> Self {
+        self.allow_keyless = allowed;
+        self
+    }
+
+    /// 设置优先级，优先于环境变量
+    ///
+    /// # 参数
+    /// - `priority`: 按优先级从高到低排列的翻译器类型
+    pub fn with_priority(mut self, priority: impl IntoIterator<Item = TranslatorType>) -> Self {
+        self.priority = Some(priority.into_iter().collect());
+        self
+    }
+
+    /// 按环境变量确定翻译器栈
+    ///
+    /// 优先级中缺少凭据的翻译器被跳过，未开启`allow_keyless`时跳过无需凭据的翻译器
+    ///
+    /// # 参数
+    /// - `env`: 读取环境变量，未设置时返回None
+    ///
+    /// # 返回值
+    /// 按优先级排列的栈配置；没有可用的翻译器或优先级无法解析时返回`TranslatorError::Configuration`
+    pub fn plan(
+        &self,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<TranslatorStackConfig>, TranslatorError> {
+        let priority = match &self.priority {
+            Some(priority) => priority.clone(),
+            None => match env(PRIORITY_ENV) {
+                Some(value) => parse_priority(&value)?,
+                None => DEFAULT_PRIORITY.to_vec(),
+            },
+        };
+        let env = |name: &str| env(name).filter(|value| !value.trim().is_empty());
+        let mut stack = Vec::new();
+        for translator_type in &priority {
+            let backend = match translator_type {
+                TranslatorType::Baidu => env("BAIDU_APP_ID")
+                    .zip(env("BAIDU_KEY"))
+                    .map(|(app_id, key)| TranslatorConfig::Baidu { app_id, key }),
+                TranslatorType::Youdao => env("YOUDAO_APP_KEY").zip(env("YOUDAO_APP_SECRET")).map(
+                    |(app_key, app_secret)| TranslatorConfig::Youdao {
+                        app_key,
+                        app_secret,
+                    },
+                ),
+                TranslatorType::Caiyun => env("CAIYUN_TOKEN").map(|token| {
+                    let request_id = env("CAIYUN_REQUEST_ID");
+                    TranslatorConfig::Caiyun {
+                        token,
+                        request_id: request_id.unwrap_or_else(|| "demo".to_string()),
+                    }
+                }),
+                TranslatorType::MyMemory => {
+                    self.allow_keyless.then_some(TranslatorConfig::MyMemory)
+                }
+                TranslatorType::TranSmart => {
+                    self.allow_keyless.then_some(TranslatorConfig::TranSmart)
+                }
+                TranslatorType::Alibaba => self.allow_keyless.then(|| TranslatorConfig::Alibaba {
+                    token: String::new(),
+                }),
+                TranslatorType::Reverso => self.allow_keyless.then_some(TranslatorConfig::Reverso),
+                TranslatorType::BingWeb => self.allow_keyless.then_some(TranslatorConfig::BingWeb),
+                TranslatorType::GoogleWeb => {
+                    self.allow_keyless.then_some(TranslatorConfig::GoogleWeb)
+                }
+            };
+            if let Some(backend) = backend {
+                stack.push(TranslatorStackConfig {
+                    backend,
+                    timeout_ms: Some(DEFAULT_TIMEOUT_MS),
+                    retries: DEFAULT_RETRIES,
+                    retry_backoff_ms: None,
+                    rate_limit_qps: Some(default_qps(*translator_type)),
+                    cache: None,
+                    user_agent: None,
+                });
+            }
+        }
+        if stack.is_empty() {
+            let names = priority
+                .iter()
+                .map(TranslatorType::as_str)
+                .collect::<Vec<_>>();
+            return Err(TranslatorError::Configuration(format!(
+                "no translator configured (tried {}); set the credentials of at least one \
+                 translator (BAIDU_APP_ID/BAIDU_KEY, YOUDAO_APP_KEY/YOUDAO_APP_SECRET or \
+                 CAIYUN_TOKEN) or allow keyless translators",
+                names.join(", ")
+            )));
+        }
+        Ok(stack)
+    }
+
+    /// 按当前进程的环境变量创建翻译器
+    ///
+    /// 选中的翻译器栈以`tracing`的info级别记录
+    ///
+    /// # 返回值
+    /// 带缓存的故障转移翻译器；没有可用的翻译器时返回`TranslatorError::Configuration`
+    pub fn build(&self) -> Result<Arc<dyn AsyncTranslator>, TranslatorError> {
+        let stack = self.plan(|name| std::env::var(name).ok())?;
+        Ok(Self::build_stack(stack))
+    }
+
+    /// 按栈配置组装翻译器
+    ///
+    /// # 参数
+    /// - `stack`: 按优先级排列的栈配置
+    fn build_stack(stack: Vec<TranslatorStackConfig>) -> Arc<dyn AsyncTranslator> {
+        let engines = stack
+            .into_iter()
+            .map(|config| {
+                let name = config.backend.translator_type().as_str().to_string();
+                (name, TranslatorFactory::create_stack(config))
+            })
+            .collect::<Vec<_>>();
+        let fallback = FallbackTranslator::new(engines);
+        tracing::info!(stack = %fallback.engines().join(" > "), "selected translators");
+        Arc::new(CachedTranslator::new(
+            Arc::new(fallback),
+            DEFAULT_CACHE_CAPACITY,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::auto::{parse_priority, AutoConfig, PRIORITY_ENV};
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use crate::fusion_translator::translator_factory::TranslatorType;
+    use std::collections::HashMap;
+
+    /// 按环境变量确定翻译器栈，返回翻译器类型
+    fn plan(
+        config: &AutoConfig,
+        vars: &[(&str, &str)],
+    ) -> Result<Vec<TranslatorType>, TranslatorError> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        let stack = config.plan(|name| vars.get(name).cloned())?;
+        Ok(stack
+            .iter()
+            .map(|config| config.backend.translator_type())
+            .collect())
+    }
+
+    /// 百度和有道的凭据
+    const BAIDU_YOUDAO: [(&str, &str); 4] = [
+        ("BAIDU_APP_ID", "id"),
+        ("BAIDU_KEY", "key"),
+        ("YOUDAO_APP_KEY", "key"),
+        ("YOUDAO_APP_SECRET", "secret"),
+    ];
+
+    /// 测试按默认优先级选择已配置凭据的翻译器
+    #[test]
+    fn test_stack_composition() {
+        let config = AutoConfig::new();
+        assert_eq!(
+            plan(&config, &BAIDU_YOUDAO).unwrap(),
+            [TranslatorType::Youdao, TranslatorType::Baidu]
+        );
+        // 凭据不完整或为空的翻译器被跳过
+        assert_eq!(
+            plan(
+                &config,
+                &[
+                    ("BAIDU_APP_ID", "id"),
+                    ("YOUDAO_APP_KEY", "key"),
+                    ("YOUDAO_APP_SECRET", " "),
+                    ("CAIYUN_TOKEN", "token"),
+                ]
+            )
+            .unwrap(),
+            [TranslatorType::Caiyun]
+        );
+        let keyless = AutoConfig::new().with_keyless(true);
+        assert_eq!(
+            plan(&keyless, &[("CAIYUN_TOKEN", "token")]).unwrap(),
+            [
+                TranslatorType::Caiyun,
+                TranslatorType::GoogleWeb,
+                TranslatorType::MyMemory,
+                TranslatorType::TranSmart,
+                TranslatorType::Alibaba,
+                TranslatorType::Reverso,
+                TranslatorType::BingWeb
+            ]
+        );
+        assert_eq!(plan(&keyless, &[]).unwrap().len(), 6);
+    }
+
+    /// 测试没有可用的翻译器时返回说明如何配置的错误
+    #[test]
+    fn test_nothing_configured() {
+        let err = plan(&AutoConfig::new(), &[]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        let message = err.to_string();
+        assert!(message.contains("BAIDU_APP_ID"), "{}", message);
+        assert!(message.contains("keyless"), "{}", message);
+    }
+
+    /// 测试环境变量和`with_priority`覆盖优先级
+    #[test]
+    fn test_priority_override() {
+        let config = AutoConfig::new();
+        let mut vars = BAIDU_YOUDAO.to_vec();
+        vars.push((PRIORITY_ENV, "baidu, youdao"));
+        assert_eq!(
+            plan(&config, &vars).unwrap(),
+            [TranslatorType::Baidu, TranslatorType::Youdao]
+        );
+        vars.push(("CAIYUN_TOKEN", "token"));
+        assert_eq!(
+            plan(
+                &config.clone().with_priority([TranslatorType::Caiyun]),
+                &vars
+            )
+            .unwrap(),
+            [TranslatorType::Caiyun]
+        );
+        vars[4] = (PRIORITY_ENV, "baidu,deepl");
+        assert!(matches!(
+            plan(&config, &vars),
+            Err(TranslatorError::Configuration(_))
+        ));
+        assert_eq!(
+            parse_priority("彩云,caiyun,,mymemory").unwrap(),
+            [TranslatorType::Caiyun, TranslatorType::MyMemory]
+        );
+    }
+
+    /// 测试按进程环境变量创建翻译器
+    #[tokio::test]
+    async fn test_build_from_env() {
+        use crate::fusion_translator::async_translator::Language;
+
+        // 进程内其他测试不读取该变量
+        std::env::set_var(PRIORITY_ENV, "mymemory");
+        let translator = AutoConfig::new().with_keyless(true).build();
+        std::env::remove_var(PRIORITY_ENV);
+        let translator = translator.unwrap();
+        assert!(!translator.local());
+        assert!(translator
+            .supported_languages()
+            .unwrap()
+            .contains(&Language::English));
+    }
+}
@@ -1,9 +1,41 @@
 use crate::fusion_translator::async_translator::{
-    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    AsyncTranslator, AudioUrls, DictionaryEntry, Language, TranslationDetail,
+    TranslationListOutput, TranslationOutput,
 };
+use crate::fusion_translator::batch::{self, empty_batch, validate_batch};
+use crate::fusion_translator::error_codes::{self, Locale};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::serde_util::{
+    null_as_default, optional_string_or_number, string_or_number,
+};
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
 use crate::fusion_translator::translator_error::{ApiError, TranslatorError};
-use reqwest::Client;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::{TranslatorOptions, DEFAULT_JOB_TIMEOUT};
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::Instrument;
+
+/// 单次请求的最大UTF-8字节数
+///
+/// 百度文档限制`q`不超过6000字节，超出时按句子拆分为多次请求（见`TranslateOptions::auto_split`）
+const MAX_QUERY_BYTES: usize = 6000;
+
+/// 异步任务的首次轮询间隔，之后每次加倍
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 异步任务轮询间隔的上限
+const JOB_POLL_MAX_INTERVAL: Duration = Duration::from_secs(5);
 
 /// 百度翻译器实现
 ///
@@ -11,15 +43,28 @@ use serde::{Deserialize, Serialize};
 pub struct BaiduTranslator {
     /// API请求地址
     url: String,
+    /// 异步任务结果的查询地址
+    result_url: String,
     /// 百度开放平台应用ID
     app_id: String,
     /// 百度开放平台应用密钥
     key: String,
-    /// HTTP客户端
-    client: Client,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 是否请求发音音频地址
+    tts: bool,
+    /// 是否请求词典释义
+    dict: bool,
+    /// 收到异步任务响应时是否轮询结果
+    poll_jobs: bool,
+    /// 等待异步任务完成的最长时间
+    job_timeout: Duration,
+    /// 异步任务的首次轮询间隔
+    poll_interval: Duration,
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 impl AsyncTranslator for BaiduTranslator {
     /// 判断是否为本地翻译器
     ///
@@ -28,6 +73,27 @@ impl AsyncTranslator for BaiduTranslator {
         false
     }
 
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "baidu", &self.url).await
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(|lang| lang.to_vendor("baidu")))
+    }
+
+    /// 按请求量计费
+    fn paid(&self) -> bool {
+        true
+    }
+
+    /// 单次请求最多的UTF-8字节数
+    ///
+    /// 批量翻译以换行拼接为一个请求，同样受此限制
+    fn input_limit(&self) -> Option<usize> {
+        Some(MAX_QUERY_BYTES)
+    }
+
     /// 翻译单个文本
     ///
     /// # 参数
@@ -43,44 +109,8 @@ impl AsyncTranslator for BaiduTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationOutput> {
-        let to = to.to_baidu().ok_or(TranslatorError::UnknownLanguage(*to))?;
-        let from = match from {
-            Some(item) => item
-                .to_baidu()
-                .ok_or(TranslatorError::UnknownLanguage(item))?,
-            None => "auto",
-        };
-        let form = Form::new(&self.app_id, query, "0", &self.key, from, to);
-        let resp: Response = self
-            .client
-            .post(&self.url)
-            .form(&form)
-            .send()
-            .await?
-            .json()
-            .await?;
-        let resp = match resp {
-            Response::Ok(v) => v,
-            Response::Err(v) => {
-                Err(TranslatorError::ApiError(ApiError::Baidu {
-                    message: v.solution().to_owned(),
-                    code: v.code,
-                }))?;
-                unreachable!()
-            }
-        };
-        Ok(TranslationOutput {
-            text: resp
-                .trans_result
-                .iter()
-                .map(|v| v.dst.to_string())
-                .collect::<Vec<_>>()
-                .join("\n"),
-            lang: Some(
-                Language::from_baidu(&resp.to)
-                    .ok_or(TranslatorError::CouldNotMapLanguage(Some(resp.to)))?,
-            ),
-        })
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
     }
 
     /// 翻译多个文本
@@ -98,10 +128,65 @@ impl AsyncTranslator for BaiduTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationListOutput> {
-        let v = self.translate(&query.join("\n"), from, to).await?;
-        Ok(TranslationListOutput {
-            text: v.text.split('\n').map(|v| v.to_string()).collect(),
-            lang: v.lang,
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递；文本超过6000字节时按`TranslateOptions::auto_split`拆分或报错。
+    /// 百度返回异步任务时按`with_job_polling`的设置轮询结果或返回`TranslatorError::JobPending`
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("baidu", query, from, to, options)
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            1,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("baidu", query, from, to, &options)
         })
     }
 }
@@ -119,10 +204,320 @@ impl BaiduTranslator {
     pub fn new(app_id: &str, key: &str) -> Self {
         Self {
             url: "https://fanyi-api.baidu.com/api/trans/vip/translate".to_string(),
+            result_url: "https://fanyi-api.baidu.com/api/trans/vip/result".to_string(),
             app_id: app_id.to_string(),
             key: key.to_string(),
-            client: Client::new(),
+            transport: TranslatorOptions::default().build_transport(TranslatorType::Baidu),
+            tts: false,
+            dict: false,
+            poll_jobs: true,
+            job_timeout: DEFAULT_JOB_TIMEOUT,
+            poll_interval: JOB_POLL_INTERVAL,
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端并设置异步任务的轮询
+    ///
+    /// 用于覆盖默认的User-Agent、添加额外的请求头或关闭异步任务轮询
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::Baidu);
+        self.with_job_polling(options.poll_jobs)
+            .with_job_timeout(options.job_timeout)
+    }
+
+    /// 设置是否请求发音音频地址
+    ///
+    /// 开启后，单个单词的翻译结果会在`TranslationDetail::audio_urls`中返回原文和译文的发音地址
+    ///
+    /// # 参数
+    /// - `tts`: 是否请求发音音频地址
+    pub fn with_tts(mut self, tts: bool) -> Self {
+        self.tts = tts;
+        self
+    }
+
+    /// 设置是否请求词典释义
+    ///
+    /// 开启后，单个单词的翻译结果会在`TranslationDetail::dictionary`中返回按词性分组的释义和例句
+    ///
+    /// # 参数
+    /// - `dict`: 是否请求词典释义
+    pub fn with_dict(mut self, dict: bool) -> Self {
+        self.dict = dict;
+        self
+    }
+
+    /// 设置收到异步任务响应时是否轮询结果
+    ///
+    /// 部分套餐下，百度对较长的请求返回`{"data": {"id": ...}}`形式的异步任务，需要查询结果。
+    /// 默认开启，按退避间隔轮询直到完成或超过`with_job_timeout`设置的时长；
+    /// 关闭后直接返回带任务ID的`TranslatorError::JobPending`，适用于不能等待的调用方。
+    /// 通过工厂创建时由`TranslatorOptions::poll_jobs`设置
+    ///
+    /// # 参数
+    /// - `poll`: 是否轮询结果
+    pub fn with_job_polling(mut self, poll: bool) -> Self {
+        self.poll_jobs = poll;
+        self
+    }
+
+    /// 设置等待异步任务完成的最长时间
+    ///
+    /// 超时后返回`TranslatorError::JobPending`，默认为`DEFAULT_JOB_TIMEOUT`
+    ///
+    /// # 参数
+    /// - `timeout`: 最长等待时间
+    pub fn with_job_timeout(mut self, timeout: Duration) -> Self {
+        self.job_timeout = timeout;
+        self
+    }
+
+    /// 发送翻译请求
+    ///
+    /// 收到异步任务响应时按设置轮询结果
+    ///
+    /// # 参数
+    /// - `form`: 请求表单
+    /// - `options`: 本次调用的选项
+    /// - `request_id`: 本次调用的请求ID
+    ///
+    /// # 返回值
+    /// 翻译成功的响应
+    async fn send(
+        &self,
+        form: &Form,
+        options: &TranslateOptions,
+        request_id: Option<&str>,
+    ) -> anyhow::Result<TranslationResponse> {
+        let span = tracing::debug_span!("translate", backend = "baidu", request_id = request_id);
+        let resp: RawResponse = options
+            .run_cancellable(
+                0,
+                async {
+                    let request = with_request_id_header(HttpRequest::post(&self.url), request_id)
+                        .form(form)?;
+                    self.transport.execute(request).await?.json()
+                }
+                .instrument(span.clone()),
+            )
+            .await?;
+        match resp.classify() {
+            Response::Ok(v) => Ok(v),
+            Response::Job(job) => {
+                self.wait_for_job(&job.id, options, request_id)
+                    .instrument(span)
+                    .await
+            }
+            Response::Err(e) => Err(e.into_translator_error().into()),
+            Response::Unexpected(reason) => Err(TranslatorError::UnexpectedResponse(reason).into()),
+        }
+    }
+
+    /// 轮询异步任务直到完成
+    ///
+    /// 轮询间隔从`poll_interval`开始加倍，不超过`JOB_POLL_MAX_INTERVAL`；
+    /// 每次轮询通过tracing记录任务ID、次数和已等待的时长
+    ///
+    /// # 参数
+    /// - `id`: 任务ID
+    /// - `options`: 本次调用的选项，取消时停止轮询
+    /// - `request_id`: 本次调用的请求ID
+    ///
+    /// # 返回值
+    /// 任务完成后的翻译结果，关闭轮询或超时时返回`TranslatorError::JobPending`
+    async fn wait_for_job(
+        &self,
+        id: &str,
+        options: &TranslateOptions,
+        request_id: Option<&str>,
+    ) -> anyhow::Result<TranslationResponse> {
+        if !self.poll_jobs {
+            return Err(TranslatorError::JobPending(id.to_string()).into());
+        }
+        let started = tokio::time::Instant::now();
+        let form = JobForm::new(&self.app_id, id, "0", &self.key);
+        let mut interval = self.poll_interval;
+        let mut attempt = 0;
+        loop {
+            let elapsed = started.elapsed();
+            if elapsed >= self.job_timeout {
+                tracing::warn!(job_id = id, attempt, "baidu job did not finish in time");
+                return Err(TranslatorError::JobPending(id.to_string()).into());
+            }
+            let wait = interval.min(self.job_timeout - elapsed);
+            options
+                .run_cancellable(0, async {
+                    tokio::time::sleep(wait).await;
+                    Ok(())
+                })
+                .await?;
+            attempt += 1;
+            tracing::debug!(
+                job_id = id,
+                attempt,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "polling baidu job"
+            );
+            let resp: RawResponse = options
+                .run_cancellable(0, async {
+                    let request =
+                        with_request_id_header(HttpRequest::post(&self.result_url), request_id)
+                            .form(&form)?;
+                    self.transport.execute(request).await?.json()
+                })
+                .await?;
+            match resp.classify() {
+                Response::Ok(v) => {
+                    tracing::debug!(job_id = id, attempt, "baidu job finished");
+                    return Ok(v);
+                }
+                Response::Job(_) => {}
+                Response::Err(e) => return Err(e.into_translator_error().into()),
+                Response::Unexpected(reason) => {
+                    return Err(TranslatorError::UnexpectedResponse(reason).into())
+                }
+            }
+            interval = (interval * 2).min(JOB_POLL_MAX_INTERVAL);
+        }
+    }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，数量和长度不超过翻译器的批量上限
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return Ok(empty_batch(options));
+        }
+        let v = self
+            .translate_preprocessed(
+                &whitespace::trim_all(query, options).join("\n"),
+                from,
+                to,
+                options,
+            )
+            .await?;
+        let text = validate_batch(
+            query.len(),
+            v.text.split('\n').map(|v| v.to_string()).collect(),
+        )?;
+        Ok(TranslationListOutput {
+            text: whitespace::restore_all(query, text, options),
+            lang: v.lang,
+            detail: v.detail,
+        })
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 预处理原文后由`translate_preprocessed`翻译
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = preprocess::apply(query, options)?;
+        self.translate_preprocessed(&query, from, to, options).await
+    }
+
+    /// 翻译已经过`preprocess::apply`处理的单个文本
+    ///
+    /// 批量翻译在分块前已预处理每个文本，直接调用此方法，不再重复预处理。
+    /// 请求ID通过`X-Request-Id`请求头传递；文本超过6000字节时按`TranslateOptions::auto_split`拆分或报错。
+    /// 百度返回异步任务时按`with_job_polling`的设置轮询结果或返回`TranslatorError::JobPending`
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_preprocessed(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        if let Err(err) = long_text::check_byte_limit(query, MAX_QUERY_BYTES) {
+            if !options.auto_split {
+                return Err(err.into());
+            }
+            return long_text::translate_split(self, query, from, to, options, MAX_QUERY_BYTES)
+                .await;
         }
+        let mut languages = LanguageResolver::new(options, |lang| lang.to_vendor("baidu"));
+        let to_code = languages.resolve(*to)?;
+        let from_code = match from {
+            Some(item) => languages.resolve(item)?,
+            None => "auto",
+        };
+        let request_id = options.resolve_request_id();
+        let sent = whitespace::trim(query, options);
+        let form = Form::new(&self.app_id, sent, "0", &self.key, from_code, to_code)
+            .with_extensions(self.tts, self.dict);
+        let resp = self.send(&form, options, request_id.as_deref()).await?;
+        let text = resp
+            .trans_result
+            .iter()
+            .map(|v| v.dst.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (audio_urls, dictionary) = resp.extended_fields();
+        let text = postprocess::apply(query, text, options);
+        let possibly_untranslated = untranslated::check(query, &text, from, to, options)?;
+        let substitutions = languages.into_substitutions();
+        // 只有实际使用的目标语言能双向转换时才反查返回的代码，否则只保留原始代码
+        let target = substitutions
+            .iter()
+            .find(|s| s.requested == *to)
+            .map_or(*to, |s| s.used);
+        let lang = if target.roundtrips("baidu") {
+            Language::from_vendor("baidu", &resp.to)
+        } else {
+            None
+        };
+        Ok(TranslationOutput {
+            text,
+            lang,
+            detail: TranslationDetail {
+                request_id,
+                substitutions,
+                possibly_untranslated,
+                audio_urls,
+                dictionary,
+                lang_raw: Some(resp.to),
+                ..Default::default()
+            },
+        })
     }
 }
 
@@ -130,7 +525,7 @@ impl BaiduTranslator {
 ///
 /// 用于构造百度翻译API的请求参数
 #[derive(Debug, Serialize)]
-pub struct Form {
+struct Form {
     /// 待翻译文本
     pub q: String,
     /// 源语言
@@ -143,6 +538,12 @@ pub struct Form {
     pub salt: String,
     /// 签名
     pub sign: String,
+    /// 是否返回发音音频地址，`1`表示返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<&'static str>,
+    /// 是否返回词典释义，`1`表示返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dict: Option<&'static str>,
 }
 
 impl Form {
@@ -169,33 +570,162 @@ impl Form {
             appid: appid.to_string(),
             salt: salt.to_string(),
             sign,
+            tts: None,
+            dict: None,
         }
     }
+
+    /// 设置扩展参数
+    ///
+    /// 按百度翻译API文档，签名只包含`appid`、`q`、`salt`和密钥，扩展参数不参与签名
+    ///
+    /// # 参数
+    /// - `tts`: 是否返回发音音频地址
+    /// - `dict`: 是否返回词典释义
+    fn with_extensions(mut self, tts: bool, dict: bool) -> Self {
+        self.tts = tts.then_some("1");
+        self.dict = dict.then_some("1");
+        self
+    }
 }
 
-/// API响应枚举
+/// 异步任务结果查询表单
+#[derive(Debug, Serialize)]
+struct JobForm {
+    /// 应用ID
+    appid: String,
+    /// 任务ID
+    id: String,
+    /// 随机盐值
+    salt: String,
+    /// 签名，按`appid`、任务ID、`salt`和密钥计算
+    sign: String,
+}
+
+impl JobForm {
+    /// 创建新的查询表单
+    ///
+    /// # 参数
+    /// - `appid`: 百度开放平台应用ID
+    /// - `id`: 任务ID
+    /// - `salt`: 随机盐值
+    /// - `key`: 百度开放平台应用密钥
+    fn new(appid: &str, id: &str, salt: &str, key: &str) -> Self {
+        let sign = format!(
+            "{:x}",
+            md5::compute(format!("{}{}{}{}", appid, id, salt, key))
+        );
+        Self {
+            appid: appid.to_string(),
+            id: id.to_string(),
+            salt: salt.to_string(),
+            sign,
+        }
+    }
+}
+
+/// API原始响应
 ///
-/// 可能返回翻译成功结果或错误信息
+/// 成功、错误和异步任务响应的字段都是可选的，由`classify`按字段组合判断响应类型，
+/// 避免同时带有多余字段或缺少部分字段的响应被误判
 #[derive(Deserialize)]
-#[serde(untagged)]
-#[allow(dead_code)]
+struct RawResponse {
+    /// 错误代码，部分接口返回数字，成功时缺失或为`52000`
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    error_code: Option<String>,
+    /// 错误消息
+    #[serde(default)]
+    error_msg: Option<String>,
+    /// 目标语言代码
+    #[serde(default)]
+    to: Option<String>,
+    /// 翻译结果列表，原文为空时可能为null
+    #[serde(default, deserialize_with = "present_list")]
+    trans_result: Option<Vec<Sentence>>,
+    /// 异步任务信息
+    #[serde(default)]
+    data: Option<JobData>,
+}
+
+/// 成功时的错误代码
+const SUCCESS_CODE: &str = "52000";
+
+impl RawResponse {
+    /// 判断响应类型
+    ///
+    /// 只有带`trans_result`且错误代码缺失或为`52000`时才是成功结果；
+    /// 否则有错误代码或错误消息时是错误，只有任务信息时是异步任务
+    ///
+    /// # 返回值
+    /// 判断后的响应，无法识别时为`Response::Unexpected`
+    fn classify(self) -> Response {
+        let success = self.error_code.as_deref().is_none_or(|c| c == SUCCESS_CODE);
+        match self.trans_result {
+            Some(trans_result) if success => {
+                return Response::Ok(TranslationResponse {
+                    to: self.to.unwrap_or_default(),
+                    trans_result,
+                })
+            }
+            _ => {}
+        }
+        if !success || self.error_msg.is_some() {
+            return Response::Err(BaiduApiError {
+                code: self.error_code.unwrap_or_default(),
+                msg: self.error_msg.unwrap_or_default(),
+            });
+        }
+        if let Some(job) = self.data {
+            return Response::Job(job);
+        }
+        Response::Unexpected(match self.error_code {
+            Some(code) => format!("error_code {} without trans_result", code),
+            None => "neither trans_result nor error_code".to_string(),
+        })
+    }
+}
+
+/// 把出现的列表字段解析为Some，`null`解析为空列表
+///
+/// 与`null_as_default`配合`default`不同，字段缺失时仍为None，以区分缺失和空结果
+fn present_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Some(null_as_default(deserializer)?))
+}
+
+/// API响应类型
+///
+/// 由`RawResponse::classify`判断得出
 enum Response {
     /// 翻译成功响应
     Ok(TranslationResponse),
+    /// 异步任务响应，需要轮询结果
+    Job(JobData),
     /// 错误响应
     Err(BaiduApiError),
+    /// 无法识别的响应
+    Unexpected(String),
+}
+
+/// 异步任务信息
+#[derive(Deserialize)]
+struct JobData {
+    /// 任务ID，可能是数字
+    #[serde(deserialize_with = "string_or_number")]
+    id: String,
 }
 
 /// 百度API错误信息
 ///
 /// 包含错误代码和错误消息
-#[derive(Debug, Clone, Deserialize)]
-pub struct BaiduApiError {
-    /// 错误代码
-    #[serde(rename = "error_code")]
+#[derive(Debug, Clone)]
+struct BaiduApiError {
+    /// 错误代码，响应缺少时为空
     pub code: String,
-    /// 错误消息
-    #[serde(rename = "error_msg")]
+    /// 错误消息，响应缺少时为空
     pub msg: String,
 }
 
@@ -213,57 +743,24 @@ impl std::fmt::Display for BaiduApiError {
 }
 
 impl BaiduApiError {
+    /// 转换为翻译器错误
+    fn into_translator_error(self) -> TranslatorError {
+        TranslatorError::ApiError(ApiError::Baidu {
+            message: self.solution(),
+            code: self.code,
+        })
+    }
+
     /// 获取错误说明
     ///
-    /// 根据错误代码返回对应的错误说明和解决方案
+    /// 根据错误代码返回`error_codes`中对应的错误说明和解决方案
     ///
     /// # 返回值
     /// 错误说明字符串
     ///
     /// 参考: [百度翻译API错误码列表](https://fanyi-api.baidu.com/doc/21)
-    pub fn solution(&self) -> &str {
-        match self.code.as_bytes() {
-            b"52000" => "成功",
-            b"52001" => "请求超时。\n解决方案：请重试。",
-            b"52002" => "系统错误。\n解决方案：请重试。",
-            b"52003" => {
-                "未授权用户。\n解决方案：请检查appid是否正确或服务是否已开通。"
-            }
-            b"54000" => {
-                "必填参数为空。\n解决方案：请检查是否传递了所有必要参数。"
-            }
-            b"54001" => {
-                "签名错误。\n解决方案：请检查签名生成方式。"
-            }
-            b"54003" => {
-                "访问频率受限。\n解决方案：请降低调用频率，或通过认证后切换到高级版本。"
-            }
-            b"54004" => {
-                "账户余额不足。\n解决方案：请前往管理控制台充值。"
-            }
-            b"54005" => {
-                "长查询请求过于频繁。\n解决方案：请降低长查询的发送频率，3秒后重试。"
-            }
-            b"58000" => {
-                "客户端IP非法。\n解决方案：检查个人信息中填写的IP地址是否正确，可前往开发者信息-基本信息进行修改。"
-            }
-            b"58001" => {
-                "目标语言方向不支持。\n解决方案：检查目标语言是否在语言列表中。"
-            }
-            b"58002" => {
-                "服务目前已下线。\n解决方案：请前往管理控制台开启服务。"
-            }
-            b"58003" => {
-                "如果同一IP在同一天使用多个APPID发送翻译请求，该IP将在当日剩余时间内被禁止请求，次日解封。请勿将APPID和密钥输入第三方软件。"
-            }
-            b"90107" => {
-                "认证未通过或已失效。\n解决方案：请前往我的认证查看认证进度。"
-            }
-            b"20003" => {
-                "请检查请求文本是否涉及颠覆、暴力或类似主题相关内容。"
-            }
-            _ => "未知错误",
-        }
+    pub fn solution(&self) -> String {
+        error_codes::advice("baidu", &self.code, Locale::Zh)
     }
 }
 
@@ -275,25 +772,542 @@ impl BaiduApiError {
 struct Sentence {
     /// 翻译后的目标文本
     pub dst: String,
+    /// 原文的发音地址，请求了`tts`时返回
+    #[serde(default)]
+    pub src_tts: Option<String>,
+    /// 译文的发音地址，请求了`tts`时返回
+    #[serde(default)]
+    pub dst_tts: Option<String>,
+    /// 词典释义，请求了`dict`时返回，通常是经过JSON转义的字符串
+    #[serde(default)]
+    pub dict: Option<Value>,
 }
 
 /// 翻译成功响应
 ///
 /// 包含翻译结果列表和目标语言
-#[derive(Deserialize)]
 struct TranslationResponse {
-    /// 目标语言代码
+    /// 目标语言代码，响应缺少时为空
     pub to: String,
-    /// 翻译结果列表
+    /// 翻译结果列表，原文为空时可能为null
     pub trans_result: Vec<Sentence>,
 }
 
+impl TranslationResponse {
+    /// 提取扩展字段
+    ///
+    /// 百度只对单个单词返回发音和词典释义，因此只处理只有一条翻译结果的响应
+    ///
+    /// # 返回值
+    /// 发音音频地址和词典释义
+    fn extended_fields(&self) -> (Option<AudioUrls>, Vec<DictionaryEntry>) {
+        let [sentence] = self.trans_result.as_slice() else {
+            return (None, Vec::new());
+        };
+        let audio_urls =
+            (sentence.src_tts.is_some() || sentence.dst_tts.is_some()).then(|| AudioUrls {
+                source: sentence.src_tts.clone().filter(|url| !url.is_empty()),
+                target: sentence.dst_tts.clone().filter(|url| !url.is_empty()),
+            });
+        let dictionary = sentence.dict.as_ref().map(parse_dict).unwrap_or_default();
+        (audio_urls, dictionary)
+    }
+}
+
+/// 解析词典释义
+///
+/// `dict`字段通常是JSON转义后的字符串，需要再解析一次；也兼容直接返回对象的情况。
+/// 释义来自`word_result.simple_means`，例句来自`word_result.collins`，
+/// 例句按柯林斯词典的中文释义归入对应的词性，找不到时单独成为一项。
+/// 无法解析时返回空列表，不影响翻译结果
+///
+/// # 参数
+/// - `dict`: 响应中的`dict`字段
+///
+/// # 返回值
+/// 按词性分组的词典释义
+fn parse_dict(dict: &Value) -> Vec<DictionaryEntry> {
+    let parsed;
+    let dict = match dict {
+        Value::String(s) if s.is_empty() => return Vec::new(),
+        Value::String(s) => match serde_json::from_str::<Value>(s) {
+            Ok(v) => {
+                parsed = v;
+                &parsed
+            }
+            Err(e) => {
+                tracing::debug!("failed to parse baidu dict field: {}", e);
+                return Vec::new();
+            }
+        },
+        v => v,
+    };
+    let word_result = &dict["word_result"];
+
+    let mut entries = Vec::new();
+    for symbol in array(&word_result["simple_means"]["symbols"]) {
+        for part in array(&symbol["parts"]) {
+            let meanings = array(&part["means"])
+                .filter_map(meaning_text)
+                .collect::<Vec<_>>();
+            if meanings.is_empty() {
+                continue;
+            }
+            let part_of_speech = part["part"]
+                .as_str()
+                .or_else(|| part["part_name"].as_str())
+                .unwrap_or_default()
+                .to_string();
+            entries.push(DictionaryEntry {
+                part_of_speech,
+                meanings,
+                examples: Vec::new(),
+            });
+        }
+    }
+
+    for entry in array(&word_result["collins"]["entry"]) {
+        for value in array(&entry["value"]) {
+            let examples = array(&value["mean_type"])
+                .filter(|m| m["info_type"] == "example")
+                .flat_map(|m| array(&m["example"]))
+                .filter_map(|e| e["ex"].as_str())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            if examples.is_empty() {
+                continue;
+            }
+            let tran = value["tran"].as_str().unwrap_or_default();
+            let target = entries.iter_mut().find(|e: &&mut DictionaryEntry| {
+                !tran.is_empty()
+                    && e.meanings
+                        .iter()
+                        .any(|m| m.contains(tran) || tran.contains(m.as_str()))
+            });
+            match target {
+                Some(target) => target.examples.extend(examples),
+                None => entries.push(DictionaryEntry {
+                    part_of_speech: array(&value["posp"])
+                        .find_map(|p| p["label"].as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    meanings: (!tran.is_empty())
+                        .then(|| tran.to_string())
+                        .into_iter()
+                        .collect(),
+                    examples,
+                }),
+            }
+        }
+    }
+    entries
+}
+
+/// 遍历JSON数组，不是数组时视为空数组
+fn array(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+/// 获取释义文本
+///
+/// 英文单词的释义是字符串，中文词语的释义是带有`text`或`word_mean`字段的对象
+fn meaning_text(value: &Value) -> Option<String> {
+    let text = value
+        .as_str()
+        .or_else(|| value["text"].as_str())
+        .or_else(|| value["word_mean"].as_str())?;
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl BaiduTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, Value)> {
+        let form = Form::new(
+            &self.app_id,
+            "How are you?\nFine.",
+            "0",
+            &self.key,
+            "en",
+            "zh",
+        );
+        let request = HttpRequest::post(&self.url).form(&form)?;
+        let body = self.transport.execute(request).await?;
+        Ok(("sentences.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "sentences.json"
+        | "word_en.json"
+        | "word_zh.json"
+        | "job_pending.json"
+        | "error_numeric_code.json"
+        | "error_string_code.json"
+        | "error_with_result.json"
+        | "success_with_code.json"
+        | "unexpected.json" => {
+            serde_json::from_str::<RawResponse>(body)?;
+        }
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
-    use crate::fusion_translator::baidu_translator::{BaiduTranslator, Form};
+    use crate::fusion_translator::async_translator::{
+        AsyncTranslator, AudioUrls, DictionaryEntry, Language,
+    };
+    use crate::fusion_translator::baidu_translator::{
+        parse_dict, BaiduTranslator, Form, RawResponse, Response, TranslationResponse,
+    };
+    use crate::fusion_translator::test_support::{fuzz, http};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::translator_options::TranslatorOptions;
+    use serde_json::Value;
     use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// 异步任务响应
+    const JOB_PENDING: &str = include_str!("../../tests/fixtures/baidu/job_pending.json");
+
+    /// 翻译成功响应
+    const SENTENCES: &str = include_str!("../../tests/fixtures/baidu/sentences.json");
+
+    /// 启动按顺序返回响应的本地服务器，响应用完后重复最后一个
+    ///
+    /// # 返回值
+    /// 服务器地址和收到的请求路径列表
+    fn serve(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = paths.clone();
+        let url = http::serve(move |request| {
+            let mut recorded = recorded.lock().unwrap();
+            let body = responses[recorded.len().min(responses.len() - 1)];
+            recorded.push(request.path.clone());
+            (200, body.to_string())
+        });
+        (url, paths)
+    }
+
+    /// 创建请求本地服务器的翻译器，轮询间隔缩短为1毫秒
+    fn local_translator(url: &str) -> BaiduTranslator {
+        let mut translator = BaiduTranslator::new("app", "key");
+        translator.url = format!("{}/translate", url);
+        translator.result_url = format!("{}/result", url);
+        translator.poll_interval = Duration::from_millis(1);
+        translator
+    }
+
+    /// 测试直接返回结果时不轮询
+    #[tokio::test]
+    async fn test_job_immediate_result() {
+        let (url, paths) = serve(vec![SENTENCES]);
+        let output = local_translator(&url)
+            .translate("How are you?\nFine.", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "你好吗？\n很好。");
+        assert_eq!(*paths.lock().unwrap(), vec!["/translate"]);
+    }
+
+    /// 测试收到异步任务后轮询直到完成
+    #[tokio::test]
+    async fn test_job_poll_then_complete() {
+        let (url, paths) = serve(vec![JOB_PENDING, JOB_PENDING, JOB_PENDING, SENTENCES]);
+        let output = local_translator(&url)
+            .translate("How are you?\nFine.", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "你好吗？\n很好。");
+        assert_eq!(
+            *paths.lock().unwrap(),
+            vec!["/translate", "/result", "/result", "/result"]
+        );
+    }
+
+    /// 测试任务超时未完成时返回`JobPending`
+    #[tokio::test]
+    async fn test_job_poll_timeout() {
+        let (url, paths) = serve(vec![JOB_PENDING]);
+        let err = local_translator(&url)
+            .with_job_timeout(Duration::from_millis(30))
+            .translate("How are you?", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::JobPending(id)) if id == "1728301524117"
+        ));
+        assert!(paths.lock().unwrap().len() > 1);
+    }
+
+    /// 测试关闭轮询时直接返回`JobPending`
+    #[tokio::test]
+    async fn test_job_polling_disabled() {
+        let (url, paths) = serve(vec![JOB_PENDING, SENTENCES]);
+        let err = local_translator(&url)
+            .with_translator_options(&TranslatorOptions::new().with_job_polling(false))
+            .translate("How are you?", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::JobPending(_))
+        ));
+        assert_eq!(*paths.lock().unwrap(), vec!["/translate"]);
+    }
+
+    /// 解析并判断响应
+    fn parse(json: &str) -> Response {
+        serde_json::from_str::<RawResponse>(json)
+            .unwrap()
+            .classify()
+    }
+
+    /// 解析响应示例文件
+    fn fixture(json: &str) -> TranslationResponse {
+        match parse(json) {
+            Response::Ok(v) => v,
+            Response::Job(job) => panic!("unexpected job {}", job.id),
+            Response::Err(e) => panic!("{}", e),
+            Response::Unexpected(reason) => panic!("{}", reason),
+        }
+    }
+
+    /// 测试关闭自动拆分时超长文本直接返回错误，不发送请求
+    #[tokio::test]
+    async fn test_too_long_without_auto_split() {
+        let translator = BaiduTranslator::new("app", "key");
+        let err = translator
+            .translate_with_options(
+                &"长".repeat(2001),
+                None,
+                &Language::English,
+                &TranslateOptions::new().with_auto_split(false),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RequestTooLong(6003, 6000))
+        ));
+    }
+
+    /// 测试错误码为数字或字符串时都能解析
+    #[test]
+    fn test_error_code_string_or_number() {
+        for json in [
+            include_str!("../../tests/fixtures/baidu/error_numeric_code.json"),
+            include_str!("../../tests/fixtures/baidu/error_string_code.json"),
+        ] {
+            match parse(json) {
+                Response::Err(e) => assert_eq!(e.code, "54003"),
+                _ => panic!("expected an error response"),
+            }
+        }
+    }
+
+    /// 测试敏感内容错误归为`ErrorKind::ContentRejected`，频率限制归为`RateLimited`
+    #[test]
+    fn test_content_rejection_kind() {
+        use crate::fusion_translator::translator_error::ErrorKind;
+
+        let kind = |json: &str| match parse(json) {
+            Response::Err(e) => e.into_translator_error().kind(),
+            _ => panic!("expected an error response"),
+        };
+        assert_eq!(
+            kind(r#"{"error_code": 20003, "error_msg": "UNAUTHORIZED USER"}"#),
+            ErrorKind::ContentRejected
+        );
+        assert_eq!(
+            kind(include_str!(
+                "../../tests/fixtures/baidu/error_numeric_code.json"
+            )),
+            ErrorKind::RateLimited
+        );
+        assert_eq!(
+            kind(r#"{"error_code": "54001", "error_msg": "Invalid Sign"}"#),
+            ErrorKind::Api
+        );
+    }
+
+    /// 测试异步任务响应不会被误解析为错误
+    #[test]
+    fn test_job_response_shape() {
+        match parse(JOB_PENDING) {
+            Response::Job(job) => assert_eq!(job.id, "1728301524117"),
+            _ => panic!("expected a job response"),
+        }
+    }
+
+    /// 测试`trans_result`和`error_code`的各种组合
+    ///
+    /// 只有带`trans_result`且错误代码缺失或为`52000`时才是成功结果
+    #[test]
+    fn test_response_field_combinations() {
+        let ok = |json: &str| matches!(parse(json), Response::Ok(_));
+        let code = |json: &str| match parse(json) {
+            Response::Err(e) => e.code,
+            _ => panic!("expected an error response: {}", json),
+        };
+        let unexpected = |json: &str| match parse(json) {
+            Response::Unexpected(reason) => reason,
+            _ => panic!("expected an unexpected response: {}", json),
+        };
+
+        // 有结果，无错误代码或错误代码为52000
+        assert!(ok(SENTENCES));
+        let resp = fixture(include_str!(
+            "../../tests/fixtures/baidu/success_with_code.json"
+        ));
+        assert_eq!(resp.trans_result[0].dst, "你好吗？");
+        assert!(ok(r#"{"error_code": 52000, "trans_result": []}"#));
+        // 有结果，错误代码不是52000
+        assert_eq!(
+            code(include_str!(
+                "../../tests/fixtures/baidu/error_with_result.json"
+            )),
+            "54003"
+        );
+        // 无结果，有错误代码或只有错误消息
+        assert_eq!(
+            code(include_str!(
+                "../../tests/fixtures/baidu/error_numeric_code.json"
+            )),
+            "54003"
+        );
+        assert_eq!(code(r#"{"error_msg": "Invalid Sign"}"#), "");
+        // 无结果，无错误代码或错误代码为52000
+        assert_eq!(
+            unexpected(include_str!("../../tests/fixtures/baidu/unexpected.json")),
+            "neither trans_result nor error_code"
+        );
+        assert_eq!(
+            unexpected(r#"{"error_code": "52000"}"#),
+            "error_code 52000 without trans_result"
+        );
+        assert_eq!(
+            TranslatorError::UnexpectedResponse(String::new()).kind(),
+            crate::fusion_translator::translator_error::ErrorKind::Api
+        );
+    }
+
+    /// 测试`trans_result`为null时解析为空列表
+    #[test]
+    fn test_null_trans_result() {
+        let resp = fixture(r#"{"from": "en", "to": "zh", "trans_result": null}"#);
+        assert!(resp.trans_result.is_empty());
+    }
+
+    /// 测试英文单词的发音和词典释义
+    ///
+    /// `dict`字段是转义后的JSON字符串，柯林斯例句按中文释义归入对应词性
+    #[test]
+    fn test_extended_fields_english_word() {
+        let resp = fixture(include_str!("../../tests/fixtures/baidu/word_en.json"));
+        let (audio_urls, dictionary) = resp.extended_fields();
+        let audio_urls = audio_urls.unwrap();
+        assert!(audio_urls.source.unwrap().ends_with(".mp3"));
+        assert!(audio_urls.target.unwrap().ends_with(".mp3"));
+        assert_eq!(
+            dictionary,
+            vec![
+                DictionaryEntry {
+                    part_of_speech: "int.".to_string(),
+                    meanings: vec!["喂".to_string(), "你好".to_string(), "哈喽".to_string()],
+                    examples: vec![
+                        "Hello, Trish.".to_string(),
+                        "Hello? Yes, I'm here.".to_string()
+                    ],
+                },
+                DictionaryEntry {
+                    part_of_speech: "n.".to_string(),
+                    meanings: vec!["招呼".to_string(), "问候".to_string()],
+                    examples: vec![],
+                },
+            ]
+        );
+    }
+
+    /// 测试中文词语的词典释义
+    ///
+    /// 中文词语的释义是对象，词性字段为`part_name`，空的发音地址视为不存在
+    #[test]
+    fn test_extended_fields_chinese_word() {
+        let resp = fixture(include_str!("../../tests/fixtures/baidu/word_zh.json"));
+        let (audio_urls, dictionary) = resp.extended_fields();
+        assert_eq!(
+            audio_urls,
+            Some(AudioUrls {
+                source: Some(
+                    "https://fanyiapp.cdn.bcebos.com/zhdict/mp3/ping2guo3.mp3".to_string()
+                ),
+                target: None,
+            })
+        );
+        assert_eq!(dictionary.len(), 1);
+        assert_eq!(dictionary[0].part_of_speech, "名");
+        assert_eq!(dictionary[0].meanings, vec!["apple", "malus pumila"]);
+    }
+
+    /// 测试多句翻译不提取扩展字段
+    #[test]
+    fn test_extended_fields_sentences() {
+        let resp = fixture(include_str!("../../tests/fixtures/baidu/sentences.json"));
+        assert_eq!(resp.extended_fields(), (None, vec![]));
+    }
+
+    /// 测试无法解析的词典释义被忽略
+    #[test]
+    fn test_parse_dict_malformed() {
+        for dict in [
+            Value::String(String::new()),
+            Value::String("{not json".to_string()),
+            Value::String("\"double encoded\"".to_string()),
+            Value::Null,
+            serde_json::json!({"word_result": {"simple_means": {"symbols": "oops"}}}),
+        ] {
+            assert!(parse_dict(&dict).is_empty(), "{}", dict);
+        }
+        let object = serde_json::json!({"word_result": {"simple_means": {"symbols": [
+            {"parts": [{"part": "v.", "means": ["跑"]}, {"part": "n.", "means": []}]}
+        ]}}});
+        assert_eq!(parse_dict(&object).len(), 1);
+    }
+
+    /// 测试扩展参数
+    ///
+    /// 扩展参数只在开启时发送，且不参与签名
+    #[test]
+    fn test_form_extensions() {
+        let plain = Form::new("appid", "hello", "salt", "key", "en", "zh");
+        let json = serde_json::to_value(&plain).unwrap();
+        assert!(json.get("tts").is_none());
+        assert!(json.get("dict").is_none());
+
+        let extended =
+            Form::new("appid", "hello", "salt", "key", "en", "zh").with_extensions(true, true);
+        let json = serde_json::to_value(&extended).unwrap();
+        assert_eq!(json["tts"], "1");
+        assert_eq!(json["dict"], "1");
+        assert_eq!(extended.sign, plain.sign);
+    }
 
     /// 测试翻译器实例创建
     ///
@@ -376,11 +1390,12 @@ mod tests {
             "iku", "zh", "cht", "wyw", "yue", "zaz", "frm", "zul", "jav",
         ];
         for lang_str in langs.into_iter().collect::<HashSet<_>>() {
-            if lang_str == "slo" {
-                continue;
-            }
-            Language::from_baidu(lang_str).expect(lang_str);
+            Language::from_vendor("baidu", lang_str).expect(lang_str);
         }
+        assert_eq!(
+            Language::from_vendor("baidu", "slo"),
+            Some(Language::Slovenian)
+        );
     }
 
     /// 测试重复语言代码去重
@@ -498,4 +1513,23 @@ mod tests {
             }
         }
     }
+
+    /// 模糊测试：任意响应的解析和词典释义提取都不会panic
+    #[test]
+    fn test_fuzz_parse() {
+        let corpus = [
+            include_str!("../../tests/fixtures/baidu/word_en.json"),
+            include_str!("../../tests/fixtures/baidu/word_zh.json"),
+            include_str!("../../tests/fixtures/baidu/error_with_result.json"),
+            include_str!("../../tests/fixtures/baidu/job_pending.json"),
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            let Ok(resp) = serde_json::from_str::<RawResponse>(&input) else {
+                continue;
+            };
+            if let Response::Ok(resp) = resp.classify() {
+                resp.extended_fields();
+            }
+        }
+    }
 }
@@ -0,0 +1,1898 @@
+use crate::fusion_translator::adaptive::AdaptiveBatcher;
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::error_context;
+use crate::fusion_translator::provenance::{Detailed, ProvenanceEvent};
+use crate::fusion_translator::request::{ExecuteRequest, TranslationRequest};
+use crate::fusion_translator::shutdown::Shutdown;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::hash::Hash;
+use std::io::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// 默认的分块大小
+///
+/// 批量翻译时每次调用`translate_vec`最多发送的文本数量，
+/// 可通过`BatchOptions::chunk_size`覆盖；不同取值的对比见`benches/batching.rs`
+pub const DEFAULT_CHUNK_SIZE: usize = 50;
+
+/// 默认的二分定位请求预算
+///
+/// 一次批量翻译中为定位被内容审核拒绝的文本最多额外发送的请求数。
+/// 每个被拒绝的文本大约需要`2 × log2(分块大小)`次请求，默认分块中的1至2个文本可以完整定位
+pub const DEFAULT_BISECT_BUDGET: usize = 24;
+
+/// 批量翻译选项
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// 传给每次`translate_vec_with_options`调用的选项
+    ///
+    /// 其中的取消令牌会在每个分块开始前检查，取消后不再发起新的请求
+    pub translate_options: TranslateOptions,
+    /// 每次调用`translate_vec`最多发送的文本数量
+    pub chunk_size: usize,
+    /// 是否允许部分失败
+    ///
+    /// 为true时失败的分块不会中断整个批次，对应的键记录在错误表中；
+    /// 为false时任一分块失败都会返回错误
+    pub partial: bool,
+    /// 自适应分块大小
+    ///
+    /// 设置后忽略`chunk_size`，按观测到的延迟和错误调整每个分块的文本数量，
+    /// 并受翻译器的`max_batch_len`和`input_limit`限制；为None时使用固定的`chunk_size`
+    pub adaptive: Option<Arc<AdaptiveBatcher>>,
+    /// 二分定位被内容审核拒绝的文本时最多额外发送的请求数，0表示不定位
+    ///
+    /// 只在允许部分失败时生效：分块因`ErrorKind::ContentRejected`失败时拆成两半重新请求，
+    /// 仍被拒绝的一半继续拆分，其余文本正常翻译；预算用完后尚未定位的文本保留原始错误
+    pub bisect_budget: usize,
+    /// 是否对按请求量计费的翻译器（`AsyncTranslator::paid`）也进行二分定位，默认关闭
+    pub bisect_paid: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            translate_options: TranslateOptions::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            partial: false,
+            adaptive: None,
+            bisect_budget: DEFAULT_BISECT_BUDGET,
+            bisect_paid: false,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// 计算下一个分块的文本数量
+    ///
+    /// 分块同时受翻译器的`input_limit`限制，超出限制的单个文本单独成为一个分块：
+    /// 开启`auto_split`时由翻译器拆分后翻译，关闭时只有该文本失败
+    ///
+    /// # 参数
+    /// - `translator`: 翻译器
+    /// - `remaining`: 尚未发送的文本
+    fn next_chunk_len(&self, translator: &dyn AsyncTranslator, remaining: &[String]) -> usize {
+        let chunk_size = match &self.adaptive {
+            Some(adaptive) => {
+                if let Some(max) = translator.max_batch_len() {
+                    adaptive.limit(max);
+                }
+                adaptive.chunk_size()
+            }
+            None => self.chunk_size,
+        };
+        plan_chunks(remaining, Some(chunk_size), translator.input_limit(), 0)
+            .first()
+            .map_or(remaining.len(), Range::len)
+    }
+
+    /// 是否对翻译器进行二分定位
+    ///
+    /// # 参数
+    /// - `translator`: 翻译器
+    fn bisects(&self, translator: &dyn AsyncTranslator) -> bool {
+        self.partial && self.bisect_budget > 0 && (self.bisect_paid || !translator.paid())
+    }
+
+    /// 把一次分块请求的结果记录到自适应分块中
+    ///
+    /// 批次的最后一个分块通常不满，其吞吐量不代表当前分块大小，成功时不参与调整
+    ///
+    /// # 参数
+    /// - `items`: 分块的文本数量
+    /// - `last`: 是否为批次的最后一个分块
+    /// - `started`: 请求开始的时间
+    /// - `result`: 请求结果
+    fn record<T>(&self, items: usize, last: bool, started: Instant, result: &anyhow::Result<T>) {
+        match (&self.adaptive, result) {
+            (Some(_), Ok(_)) if last => {}
+            (Some(adaptive), Ok(_)) => adaptive.record_success(items, started.elapsed()),
+            (Some(adaptive), Err(e)) if !is_cancelled(e) => adaptive.record_failure(e),
+            _ => {}
+        }
+    }
+}
+
+/// 校验批量翻译结果的数量
+///
+/// 所有翻译器的`translate_vec`在返回前都必须调用此函数，
+/// 保证译文与原文数量相同，调用方可以安全地按下标对应
+///
+/// # 参数
+/// - `input_len`: 请求的文本数量
+/// - `output`: 翻译服务返回的译文
+///
+/// # 返回值
+/// 数量一致时原样返回译文，否则返回`TranslatorError::BatchSizeMismatch`
+pub(crate) fn validate_batch(
+    input_len: usize,
+    output: Vec<String>,
+) -> Result<Vec<String>, TranslatorError> {
+    if output.len() != input_len {
+        return Err(TranslatorError::BatchSizeMismatch(input_len, output.len()));
+    }
+    Ok(output)
+}
+
+/// 空批量请求的翻译结果
+///
+/// 空数组不需要调用翻译服务，直接返回空译文
+///
+/// # 参数
+/// - `options`: 本次调用的选项
+pub(crate) fn empty_batch(options: &TranslateOptions) -> TranslationListOutput {
+    TranslationListOutput {
+        detail: TranslationDetail {
+            request_id: options.resolve_request_id(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// 按翻译器的批量上限划分分块
+///
+/// 一个分块在加入下一条文本会超过`max_batch_len`条，或总字节数（含文本之间的分隔符）
+/// 会超过`input_limit`时结束。本身超过`input_limit`的文本单独成为一个分块，
+/// 由翻译器按`TranslateOptions::auto_split`处理
+///
+/// # 参数
+/// - `query`: 待翻译的文本
+/// - `max_batch_len`: 每个分块最多的文本数量，None表示不限
+/// - `input_limit`: 每个分块最多的UTF-8字节数，None表示不限
+/// - `separator_len`: 翻译器拼接文本时使用的分隔符字节数，按数组发送时为0
+///
+/// # 返回值
+/// 按顺序排列、首尾相接的下标范围，空输入时为空
+pub fn plan_chunks(
+    query: &[String],
+    max_batch_len: Option<usize>,
+    input_limit: Option<usize>,
+    separator_len: usize,
+) -> Vec<Range<usize>> {
+    plan_chunks_by(query, max_batch_len, input_limit, separator_len, str::len)
+}
+
+/// 按翻译器的批量上限划分分块，每条文本的字节数由`size`估算
+///
+/// 用于请求体中的文本会被转义的翻译器，如按JSON字符串发送时计入引号和转义字符。
+/// 分块规则同`plan_chunks`
+///
+/// # 参数
+/// - `query`: 待翻译的文本
+/// - `max_batch_len`: 每个分块最多的文本数量，None表示不限
+/// - `input_limit`: 每个分块最多的字节数，None表示不限
+/// - `separator_len`: 文本之间的分隔符字节数
+/// - `size`: 一条文本在请求中占用的字节数
+///
+/// # 返回值
+/// 按顺序排列、首尾相接的下标范围，空输入时为空
+pub(crate) fn plan_chunks_by(
+    query: &[String],
+    max_batch_len: Option<usize>,
+    input_limit: Option<usize>,
+    separator_len: usize,
+    size: impl Fn(&str) -> usize,
+) -> Vec<Range<usize>> {
+    let max_batch_len = max_batch_len.unwrap_or(usize::MAX).max(1);
+    let input_limit = input_limit.unwrap_or(usize::MAX);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0usize;
+    for (i, text) in query.iter().enumerate() {
+        let len = size(text);
+        let added = if i == start {
+            len
+        } else {
+            separator_len.saturating_add(len)
+        };
+        if i > start && (i - start >= max_batch_len || bytes.saturating_add(added) > input_limit) {
+            chunks.push(start..i);
+            start = i;
+            bytes = len;
+        } else {
+            bytes = bytes.saturating_add(added);
+        }
+    }
+    if start < query.len() {
+        chunks.push(start..query.len());
+    }
+    chunks
+}
+
+/// 按翻译器的批量上限分块翻译
+///
+/// 各翻译器的`translate_vec_with_options`通过此函数把超出上限的输入拆分为多次顺序请求，
+/// 再按原顺序拼接译文；只有一个分块时直接返回该分块的结果。
+/// 调用方应传入已解析请求ID的选项（`TranslateOptions::resolved`），保证所有分块使用同一个ID
+///
+/// # 参数
+/// - `query`: 待翻译的文本
+/// - `max_batch_len`: 每个分块最多的文本数量
+/// - `input_limit`: 每个分块最多的UTF-8字节数
+/// - `separator_len`: 翻译器拼接文本时使用的分隔符字节数
+/// - `translate`: 翻译一个分块
+///
+/// # 返回值
+/// 与`query`一一对应的译文，语言和请求ID取自第一个分块
+pub(crate) async fn translate_in_chunks<'a, F, Fut>(
+    query: &'a [String],
+    max_batch_len: Option<usize>,
+    input_limit: Option<usize>,
+    separator_len: usize,
+    translate: F,
+) -> anyhow::Result<TranslationListOutput>
+where
+    F: FnMut(&'a [String]) -> Fut,
+    Fut: Future<Output = anyhow::Result<TranslationListOutput>>,
+{
+    let chunks = plan_chunks(query, max_batch_len, input_limit, separator_len);
+    translate_planned(query, chunks, translate).await
+}
+
+/// 按划分好的分块依次翻译
+///
+/// 每个分块的译文数量必须与请求一致，否则返回`TranslatorError::BatchSizeMismatch`；
+/// 只有一个分块时直接返回该分块的结果。分块被取消时，`TranslatorError::Cancelled`中的条目数
+/// 加上之前分块已完成的条目数
+///
+/// # 参数
+/// - `query`: 待翻译的文本
+/// - `chunks`: `plan_chunks`或`plan_chunks_by`划分的下标范围
+/// - `translate`: 翻译一个分块
+///
+/// # 返回值
+/// 与`query`一一对应的译文，语言、请求ID和来历取自第一个分块；有多个分块时来历追加`ProvenanceEvent::Chunked`
+pub(crate) async fn translate_planned<'a, F, Fut>(
+    query: &'a [String],
+    chunks: Vec<Range<usize>>,
+    mut translate: F,
+) -> anyhow::Result<TranslationListOutput>
+where
+    F: FnMut(&'a [String]) -> Fut,
+    Fut: Future<Output = anyhow::Result<TranslationListOutput>>,
+{
+    if chunks.len() <= 1 {
+        return translate(query).await;
+    }
+    let count = chunks.len();
+    let mut output: Option<TranslationListOutput> = None;
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let len = chunk.len();
+        let part = match translate(&query[chunk.clone()]).await {
+            Ok(part) => part,
+            Err(e) => {
+                let done = output.as_ref().map_or(0, |output| output.text.len());
+                return Err(match e.downcast_ref::<TranslatorError>() {
+                    Some(TranslatorError::Cancelled(n)) => {
+                        TranslatorError::Cancelled(done + n).into()
+                    }
+                    _ => e.context(error_context::chunk_context(index, &chunk)),
+                });
+            }
+        };
+        let part_text = validate_batch(len, part.text)?;
+        match &mut output {
+            None => {
+                output = Some(TranslationListOutput {
+                    text: part_text,
+                    ..part
+                })
+            }
+            Some(output) => {
+                output.text.extend(part_text);
+                output.detail.possibly_untranslated |= part.detail.possibly_untranslated;
+                output.detail.emoji_appended |= part.detail.emoji_appended;
+                for substitution in part.detail.substitutions {
+                    if !output.detail.substitutions.contains(&substitution) {
+                        output.detail.substitutions.push(substitution);
+                    }
+                }
+            }
+        }
+    }
+    let mut output = output.unwrap_or_default();
+    output.trace(ProvenanceEvent::Chunked { chunks: count });
+    Ok(output)
+}
+
+/// 键值表翻译结果
+///
+/// 输入表中的每个键要么出现在`translations`中，要么出现在`errors`中
+#[derive(Debug)]
+pub struct MapTranslation<K> {
+    /// 翻译成功的键及其译文
+    pub translations: HashMap<K, TranslationOutput>,
+    /// 翻译失败的键及其错误，仅在允许部分失败时可能非空
+    pub errors: HashMap<K, Arc<anyhow::Error>>,
+}
+
+impl<K> MapTranslation<K> {
+    /// 被内容审核拒绝的键
+    ///
+    /// 二分定位完成时只包含本身被拒绝的文本对应的键；
+    /// 未开启定位或预算用完时，同一分块中未定位的键也会出现在这里
+    pub fn rejected(&self) -> Vec<&K> {
+        self.errors
+            .iter()
+            .filter(|(_, e)| ErrorKind::of(e) == ErrorKind::ContentRejected)
+            .map(|(key, _)| key)
+            .collect()
+    }
+}
+
+/// 翻译键值表
+///
+/// 相同的值只翻译一次，按默认分块大小分批调用`translate_vec`，
+/// 输出中包含输入表的每一个键；任一分块失败时返回错误
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `map`: 待翻译的键值表
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 与输入表键相同的译文表
+pub async fn translate_map<K>(
+    translator: &dyn AsyncTranslator,
+    map: &HashMap<K, String>,
+    from: Option<Language>,
+    to: &Language,
+) -> anyhow::Result<HashMap<K, TranslationOutput>>
+where
+    K: Eq + Hash + Clone,
+{
+    let output = translate_map_with(translator, map, from, to, &BatchOptions::default()).await?;
+    Ok(output.translations)
+}
+
+/// 按指定选项翻译键值表
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `map`: 待翻译的键值表
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 批量翻译选项
+///
+/// # 返回值
+/// 翻译结果，允许部分失败时失败的键记录在错误表中
+pub async fn translate_map_with<K>(
+    translator: &dyn AsyncTranslator,
+    map: &HashMap<K, String>,
+    from: Option<Language>,
+    to: &Language,
+    options: &BatchOptions,
+) -> anyhow::Result<MapTranslation<K>>
+where
+    K: Eq + Hash + Clone,
+{
+    let unique = unique_values(map);
+    let mut results: HashMap<&str, Result<TranslationOutput, Arc<anyhow::Error>>> = HashMap::new();
+    let translate_options = options.translate_options.resolved();
+    let mut budget = options.bisect_budget;
+    let mut start = 0;
+    let mut index = 0;
+    while start < unique.len() {
+        let items = start..start + options.next_chunk_len(translator, &unique[start..]);
+        let chunk = &unique[items.clone()];
+        start += chunk.len();
+        let completed = results.values().filter(|r| r.is_ok()).count();
+        if translate_options.is_cancelled() {
+            return Err(TranslatorError::Cancelled(completed).into());
+        }
+        let started = Instant::now();
+        let result = translate_options
+            .run_cancellable(
+                completed,
+                translate_chunk(translator, chunk, from, to, &translate_options),
+            )
+            .await;
+        options.record(chunk.len(), start == unique.len(), started, &result);
+        let result = result.with_context(|| error_context::chunk_context(index, &items));
+        index += 1;
+        match result {
+            Err(e) if is_cancelled(&e) => return Err(e),
+            Ok(outputs) => {
+                for (source, output) in chunk.iter().zip(outputs) {
+                    results.insert(source, Ok(output));
+                }
+            }
+            Err(e)
+                if chunk.len() > 1
+                    && ErrorKind::of(&e) == ErrorKind::ContentRejected
+                    && options.bisects(translator) =>
+            {
+                let outcomes = bisect_rejected(
+                    translator,
+                    chunk,
+                    from,
+                    to,
+                    &translate_options,
+                    Arc::new(e),
+                    &mut budget,
+                )
+                .await
+                // 定位过程中只会因取消而失败，按整个批次已完成的条目数报告
+                .map_err(|_| TranslatorError::Cancelled(completed))?;
+                for (source, outcome) in chunk.iter().zip(outcomes) {
+                    results.insert(source, outcome);
+                }
+            }
+            Err(e) if options.partial => {
+                let e = Arc::new(e);
+                for source in chunk {
+                    results.insert(source, Err(e.clone()));
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut output = MapTranslation {
+        translations: HashMap::with_capacity(map.len()),
+        errors: HashMap::new(),
+    };
+    for (key, value) in map {
+        match &results[value.as_str()] {
+            Ok(translation) => {
+                output.translations.insert(key.clone(), translation.clone());
+            }
+            Err(e) => {
+                output.errors.insert(key.clone(), e.clone());
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// 去重并排序键值表的值，保证相同输入总是产生相同的上游请求
+///
+/// # 参数
+/// - `map`: 待翻译的键值表
+fn unique_values<K>(map: &HashMap<K, String>) -> Vec<String> {
+    map.values()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// 批量翻译的执行计划
+///
+/// 由`plan_map`生成，不发送任何请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranslationPlan {
+    /// 输入的文本数量
+    pub segments: usize,
+    /// 去重后的文本数量
+    pub unique_segments: usize,
+    /// 去重后的字符数
+    pub characters: usize,
+    /// 每次调用`translate_vec`发送的文本下标范围（按去重排序后的顺序）
+    pub chunks: Vec<Range<usize>>,
+    /// 预计的上游请求数，包括超长文本自动拆分产生的请求
+    pub requests: usize,
+    /// 翻译器是否按请求量计费
+    pub paid: bool,
+}
+
+impl TranslationPlan {
+    /// 估算费用
+    ///
+    /// 不计费的翻译器费用总是0
+    ///
+    /// # 参数
+    /// - `price_per_million`: 每百万字符的价格，币种由调用者决定
+    ///
+    /// # 返回值
+    /// 按去重后的字符数计算的费用
+    pub fn estimated_cost(&self, price_per_million: f64) -> f64 {
+        match self.paid {
+            true => self.characters as f64 * price_per_million / 1_000_000.0,
+            false => 0.0,
+        }
+    }
+}
+
+/// 规划键值表的翻译，不发送请求
+///
+/// 与`translate_map_with`使用同一套去重和分块逻辑，分块再按翻译器自身的`max_batch_len`拆分为请求；
+/// 单个文本超出`input_limit`时
+/// 按`AsyncTranslator::check_input`的拆分位置计算自动拆分产生的请求。
+/// 开启自适应分块时按当前的分块大小规划，实际运行中分块大小的调整不计入
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `map`: 待翻译的键值表
+/// - `options`: 批量翻译选项
+///
+/// # 返回值
+/// 翻译计划
+pub fn plan_map<K>(
+    translator: &dyn AsyncTranslator,
+    map: &HashMap<K, String>,
+    options: &BatchOptions,
+) -> TranslationPlan
+where
+    K: Eq + Hash + Clone,
+{
+    let unique = unique_values(map);
+    let mut chunks = Vec::new();
+    let mut requests = 0;
+    let mut start = 0;
+    while start < unique.len() {
+        let items = start..start + options.next_chunk_len(translator, &unique[start..]);
+        start = items.end;
+        let chunk = &unique[items.clone()];
+        // 翻译器内部再按自身的数量上限分块
+        for inner in plan_chunks(
+            chunk,
+            translator.max_batch_len(),
+            translator.input_limit(),
+            0,
+        ) {
+            requests += match &chunk[inner] {
+                [text] if options.translate_options.auto_split => split_requests(translator, text),
+                _ => 1,
+            };
+        }
+        chunks.push(items);
+    }
+    TranslationPlan {
+        segments: map.len(),
+        unique_segments: unique.len(),
+        characters: unique.iter().map(|text| text.chars().count()).sum(),
+        chunks,
+        requests,
+        paid: translator.paid(),
+    }
+}
+
+/// 单个文本自动拆分后的请求数
+///
+/// 与`long_text`一样跳过空白片段并合并重复片段
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `text`: 待翻译的文本
+fn split_requests(translator: &dyn AsyncTranslator, text: &str) -> usize {
+    let check = translator.check_input(text);
+    if check.would_split_at.is_empty() {
+        return 1;
+    }
+    check
+        .parts(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// 带标签的文本的翻译结果
+///
+/// 每一项为（输入时的标签，译文或错误），顺序与输入相同
+pub type TaggedTranslation<T> = Vec<(T, Result<TranslationOutput, Arc<anyhow::Error>>)>;
+
+/// 翻译带标签的文本
+///
+/// 标签（如数据库行ID）随文本一起返回，不会发送给翻译服务。相同的文本只翻译一次，
+/// 译文或错误分发给携带该文本的每一个标签；允许部分失败，失败分块中的每一项单独记录错误
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `items`: （标签，待翻译的文本）列表，标签可以重复
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 与输入一一对应、顺序相同的（标签，结果）
+pub async fn translate_tagged<T>(
+    translator: &dyn AsyncTranslator,
+    items: Vec<(T, String)>,
+    from: Option<Language>,
+    to: &Language,
+) -> anyhow::Result<TaggedTranslation<T>> {
+    let options = BatchOptions {
+        partial: true,
+        ..Default::default()
+    };
+    translate_tagged_with(translator, items, from, to, &options).await
+}
+
+/// 按指定选项翻译带标签的文本
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `items`: （标签，待翻译的文本）列表，标签可以重复
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 批量翻译选项，不允许部分失败时任一分块失败都返回错误
+///
+/// # 返回值
+/// 与输入一一对应、顺序相同的（标签，结果）
+pub async fn translate_tagged_with<T>(
+    translator: &dyn AsyncTranslator,
+    items: Vec<(T, String)>,
+    from: Option<Language>,
+    to: &Language,
+    options: &BatchOptions,
+) -> anyhow::Result<TaggedTranslation<T>> {
+    // 以输入下标为键，标签本身不参与翻译，也不要求可哈希
+    let (tags, texts): (Vec<T>, Vec<String>) = items.into_iter().unzip();
+    let map = texts.into_iter().enumerate().collect::<HashMap<_, _>>();
+    let mut output = translate_map_with(translator, &map, from, to, options).await?;
+    Ok(tags
+        .into_iter()
+        .enumerate()
+        .map(|(index, tag)| {
+            let result = match output.translations.remove(&index) {
+                Some(translation) => Ok(translation),
+                None => Err(output.errors[&index].clone()),
+            };
+            (tag, result)
+        })
+        .collect())
+}
+
+/// 二分定位分块中被内容审核拒绝的文本
+///
+/// 把被拒绝的范围拆成两半分别请求，仍被拒绝的一半继续拆分，直到单个文本；
+/// 每次请求消耗一个预算，预算用完后尚未请求的范围保留拆分前的错误
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `chunk`: 被拒绝的分块
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+/// - `rejection`: 分块的错误
+/// - `budget`: 剩余的请求预算
+///
+/// # 返回值
+/// 与分块文本一一对应的结果；取消时返回`TranslatorError::Cancelled`
+async fn bisect_rejected(
+    translator: &dyn AsyncTranslator,
+    chunk: &[String],
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+    rejection: Arc<anyhow::Error>,
+    budget: &mut usize,
+) -> anyhow::Result<Vec<Result<TranslationOutput, Arc<anyhow::Error>>>> {
+    let mut outcomes = vec![None; chunk.len()];
+    let mut pending = vec![(0..chunk.len(), rejection)];
+    while let Some((range, error)) = pending.pop() {
+        if range.len() == 1 {
+            outcomes[range.start] = Some(Err(error));
+            continue;
+        }
+        let mid = range.start + range.len() / 2;
+        for half in [range.start..mid, mid..range.end] {
+            if *budget == 0 {
+                outcomes[half].fill(Some(Err(error.clone())));
+                continue;
+            }
+            *budget -= 1;
+            let result = options
+                .run_cancellable(
+                    0,
+                    translate_chunk(translator, &chunk[half.clone()], from, to, options),
+                )
+                .await;
+            match result {
+                Err(e) if is_cancelled(&e) => return Err(e),
+                Ok(outputs) => {
+                    for (outcome, output) in outcomes[half].iter_mut().zip(outputs) {
+                        *outcome = Some(Ok(output));
+                    }
+                }
+                Err(e) if ErrorKind::of(&e) == ErrorKind::ContentRejected => {
+                    pending.push((half, Arc::new(e)));
+                }
+                Err(e) => outcomes[half].fill(Some(Err(Arc::new(e)))),
+            }
+        }
+    }
+    Ok(outcomes.into_iter().map(Option::unwrap).collect())
+}
+
+/// 翻译一个分块
+///
+/// 返回的译文数量与分块中的文本数量不一致时返回`TranslatorError::BatchSizeMismatch`
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `chunk`: 分块中的文本
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 与分块文本一一对应的翻译结果
+async fn translate_chunk(
+    translator: &dyn AsyncTranslator,
+    chunk: &[String],
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+) -> anyhow::Result<Vec<TranslationOutput>> {
+    let request = TranslationRequest::default()
+        .texts(chunk.iter().cloned())
+        .from(from)
+        .to(*to)
+        .options(options.clone());
+    let output = translator.execute(&request).await?;
+    Ok(output
+        .texts
+        .into_iter()
+        .map(|text| TranslationOutput {
+            text,
+            lang: output.lang,
+            detail: output.detail.clone(),
+        })
+        .collect())
+}
+
+/// 可断点续传的批量翻译
+///
+/// 每完成一个分块就把`(下标, 译文)`追加写入JSONL格式的检查点文件；
+/// 重新运行时读取检查点，跳过已完成的下标继续翻译。
+/// 检查点第一行记录引擎名称、语言对和输入列表的哈希，与本次批量翻译不一致时拒绝续传。
+/// 任一分块失败都会返回错误，已完成的分块保留在检查点中，`BatchOptions::partial`不生效
+///
+/// `shutdown`让正在执行的`run`在当前分块写入检查点后返回`TranslatorError::Cancelled`；
+/// 与取消令牌不同，正在请求的分块不会被放弃。进程被强制终止时最多丢失正在请求的一个分块
+pub struct CheckpointedBatch<'a> {
+    /// 翻译器
+    translator: &'a dyn AsyncTranslator,
+    /// 引擎名称，用于校验检查点
+    engine: String,
+    /// 检查点文件路径
+    path: PathBuf,
+    /// 批量翻译选项
+    options: BatchOptions,
+    /// 触发后不再开始新的分块
+    stop: CancellationToken,
+    /// `run`执行期间持有，`shutdown`借此等待`run`返回
+    running: tokio::sync::Mutex<()>,
+}
+
+/// 检查点文件头
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CheckpointHeader {
+    /// 引擎名称
+    engine: String,
+    /// 源语言，None表示自动检测
+    from: Option<String>,
+    /// 目标语言
+    to: String,
+    /// 输入列表的SHA-256哈希
+    input_hash: String,
+    /// 输入列表的长度
+    total: usize,
+}
+
+/// 检查点中的一条译文
+#[derive(Serialize, Deserialize)]
+struct CheckpointRecord {
+    /// 原文在输入列表中的下标
+    index: usize,
+    /// 译文
+    translation: String,
+}
+
+impl<'a> CheckpointedBatch<'a> {
+    /// 创建新的可续传批量翻译
+    ///
+    /// # 参数
+    /// - `translator`: 翻译器
+    /// - `engine`: 引擎名称，续传时必须与检查点中的一致
+    /// - `path`: 检查点文件路径，文件不存在时自动创建
+    pub fn new(
+        translator: &'a dyn AsyncTranslator,
+        engine: &str,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            translator,
+            engine: engine.to_string(),
+            path: path.into(),
+            options: BatchOptions::default(),
+            stop: CancellationToken::new(),
+            running: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// 设置批量翻译选项
+    ///
+    /// # 参数
+    /// - `options`: 批量翻译选项
+    pub fn with_options(mut self, options: BatchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 执行批量翻译
+    ///
+    /// # 参数
+    /// - `inputs`: 待翻译的文本列表
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 与输入一一对应的译文；检查点不匹配时返回`TranslatorError::CheckpointMismatch`，
+    /// 被取消或关闭时返回`TranslatorError::Cancelled`
+    pub async fn run(
+        &self,
+        inputs: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<Vec<String>> {
+        let _running = self.running.lock().await;
+        let header = CheckpointHeader {
+            engine: self.engine.clone(),
+            from: from.map(|lang| format!("{:?}", lang)),
+            to: format!("{:?}", to),
+            input_hash: input_hash(inputs),
+            total: inputs.len(),
+        };
+        let mut done = vec![None; inputs.len()];
+        let mut file = self.open(&header, &mut done)?;
+
+        let translate_options = self.options.translate_options.resolved();
+        let pending = (0..inputs.len())
+            .filter(|&i| done[i].is_none())
+            .collect::<Vec<_>>();
+        let pending_texts = pending
+            .iter()
+            .map(|&i| inputs[i].clone())
+            .collect::<Vec<_>>();
+        let mut start = 0;
+        let mut index = 0;
+        while start < pending.len() {
+            let len = self
+                .options
+                .next_chunk_len(self.translator, &pending_texts[start..]);
+            let chunk = &pending[start..start + len];
+            let request = TranslationRequest::default()
+                .texts(pending_texts[start..start + len].iter().cloned())
+                .from(from)
+                .to(*to)
+                .options(translate_options.clone());
+            start += len;
+            let completed = done.iter().filter(|t| t.is_some()).count();
+            if translate_options.is_cancelled() || self.stop.is_cancelled() {
+                return Err(TranslatorError::Cancelled(completed).into());
+            }
+            let started = Instant::now();
+            let result = translate_options
+                .run_cancellable(completed, self.translator.execute(&request))
+                .await;
+            self.options
+                .record(len, start == pending.len(), started, &result);
+            let items = chunk[0]..chunk[len - 1] + 1;
+            let translations = result
+                .with_context(|| error_context::chunk_context(index, &items))?
+                .texts;
+            index += 1;
+
+            let mut lines = String::new();
+            for (&index, translation) in chunk.iter().zip(&translations) {
+                lines.push_str(&serde_json::to_string(&CheckpointRecord {
+                    index,
+                    translation: translation.clone(),
+                })?);
+                lines.push('\n');
+            }
+            file.write_all(lines.as_bytes())?;
+            file.flush()?;
+            for (&index, translation) in chunk.iter().zip(translations) {
+                done[index] = Some(translation);
+            }
+        }
+        Ok(done.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// 打开检查点文件
+    ///
+    /// 文件不存在或为空时写入文件头；否则校验文件头并读取已完成的译文。
+    /// 无法解析的行（如写入时中断留下的半行）会被忽略
+    ///
+    /// # 参数
+    /// - `header`: 本次批量翻译的文件头
+    /// - `done`: 已完成的译文，按下标写入
+    ///
+    /// # 返回值
+    /// 以追加模式打开的检查点文件
+    fn open(&self, header: &CheckpointHeader, done: &mut [Option<String>]) -> anyhow::Result<File> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if content.trim().is_empty() {
+            let mut file = File::create(&self.path)?;
+            writeln!(file, "{}", serde_json::to_string(header)?)?;
+            return Ok(file);
+        }
+
+        let mut lines = content.lines();
+        let found = lines
+            .next()
+            .and_then(|line| serde_json::from_str::<CheckpointHeader>(line).ok())
+            .ok_or_else(|| {
+                TranslatorError::CheckpointMismatch("missing checkpoint header".to_string())
+            })?;
+        if let Some(reason) = header_mismatch(&found, header) {
+            return Err(TranslatorError::CheckpointMismatch(reason).into());
+        }
+        for line in lines {
+            match serde_json::from_str::<CheckpointRecord>(line) {
+                Ok(record) if record.index < done.len() => {
+                    done[record.index] = Some(record.translation);
+                }
+                _ => tracing::warn!("skipping invalid checkpoint line"),
+            }
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        if !content.ends_with('\n') {
+            file.write_all(b"\n")?;
+        }
+        Ok(file)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Shutdown for CheckpointedBatch<'_> {
+    /// 不再开始新的分块，等待正在执行的`run`把当前分块写入检查点后返回
+    ///
+    /// 之后调用的`run`直接返回`TranslatorError::Cancelled`
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.stop.cancel();
+        drop(self.running.lock().await);
+        Ok(())
+    }
+}
+
+/// 计算输入列表的哈希
+///
+/// 每个文本前加上长度，避免不同的切分方式得到相同的哈希
+fn input_hash(inputs: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for input in inputs {
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 比较检查点文件头
+///
+/// # 参数
+/// - `found`: 检查点中的文件头
+/// - `expected`: 本次批量翻译的文件头
+///
+/// # 返回值
+/// 不一致的原因，一致时为None
+fn header_mismatch(found: &CheckpointHeader, expected: &CheckpointHeader) -> Option<String> {
+    if found.engine != expected.engine {
+        return Some(format!(
+            "checkpoint was written by engine {}, not {}",
+            found.engine, expected.engine
+        ));
+    }
+    if found.from != expected.from || found.to != expected.to {
+        return Some(format!(
+            "checkpoint is for {} -> {}, not {} -> {}",
+            found.from.as_deref().unwrap_or("auto"),
+            found.to,
+            expected.from.as_deref().unwrap_or("auto"),
+            expected.to
+        ));
+    }
+    if found.input_hash != expected.input_hash || found.total != expected.total {
+        return Some("checkpoint was written for a different input list".to_string());
+    }
+    None
+}
+
+/// 判断错误是否为取消错误
+///
+/// 取消错误即使在允许部分失败时也会中断整个批次
+fn is_cancelled(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<TranslatorError>(),
+        Some(TranslatorError::Cancelled(_))
+    )
+}
+
+/// 批量翻译形状检查
+///
+/// 对任意翻译器运行一组标准的批量翻译用例：空数组、单个文本、多个文本、重复文本和含换行的文本。
+/// 成功时译文数量必须与原文一致；数量无法对应时只能返回`TranslatorError::BatchSizeMismatch`，
+/// 不能返回错位的结果。其他错误都视为检查失败
+///
+/// # 参数
+/// - `translator`: 被检查的翻译器
+/// - `to`: 目标语言
+#[cfg(test)]
+pub(crate) async fn assert_batch_shape(translator: &dyn AsyncTranslator, to: &Language) {
+    let cases: [&[&str]; 5] = [
+        &[],
+        &["hello"],
+        &["good morning", "thank you", "see you tomorrow"],
+        &["yes", "no", "yes"],
+        &["first line\nsecond line", "another text"],
+    ];
+    for case in cases {
+        let query = case.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        match translator.translate_vec(&query, None, to).await {
+            Ok(output) => assert_eq!(output.text.len(), query.len(), "{:?}", query),
+            Err(e) => match e.downcast_ref::<TranslatorError>() {
+                Some(TranslatorError::BatchSizeMismatch(expected, actual)) => {
+                    assert_eq!(*expected, query.len());
+                    assert_ne!(expected, actual);
+                }
+                _ => panic!("unexpected error for {:?}: {:#}", query, e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::batch::{
+        plan_chunks, plan_map, translate_map, translate_map_with, BatchOptions,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use std::collections::HashMap;
+
+    /// 构造测试用键值表
+    fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// 测试分块同时受数量和字节数（含分隔符）限制
+    #[test]
+    fn test_plan_chunks() {
+        let query = ["aa", "bb", "cc", "dddddddd", "e"].map(String::from);
+        assert_eq!(plan_chunks(&query, None, None, 1), vec![0..5]);
+        assert_eq!(
+            plan_chunks(&query, Some(2), None, 1),
+            vec![0..2, 2..4, 4..5]
+        );
+        // "aa\nbb"为5字节，再加"\ncc"会超过6字节
+        assert_eq!(
+            plan_chunks(&query, None, Some(6), 1),
+            vec![0..2, 2..3, 3..4, 4..5]
+        );
+        assert_eq!(
+            plan_chunks(&query, None, Some(6), 0),
+            vec![0..3, 3..4, 4..5]
+        );
+        assert!(plan_chunks(&[], Some(1), Some(1), 1).is_empty());
+    }
+
+    /// 测试每个键都有对应译文
+    #[tokio::test]
+    async fn test_every_key_translated() {
+        let translator = MockTranslator::new();
+        let input = map(&[("title", "hello"), ("button", "ok"), ("label", "name")]);
+        let output = translate_map(&translator, &input, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.len(), 3);
+        assert_eq!(output["title"].text, "HELLO");
+        assert_eq!(output["button"].text, "OK");
+        assert_eq!(output["label"].text, "NAME");
+        assert_eq!(output["label"].lang, Some(Language::Chinese));
+    }
+
+    /// 测试相同的值只翻译一次
+    ///
+    /// 两个键共享同一个值时，上游只收到一份文本
+    #[tokio::test]
+    async fn test_shared_value_deduplicated() {
+        let translator = MockTranslator::new();
+        let input = map(&[("save", "ok"), ("confirm", "ok"), ("cancel", "no")]);
+        let output = translate_map(&translator, &input, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(translator.calls(), 1);
+        assert_eq!(translator.translated_items(), 2);
+        assert_eq!(output["save"].text, "OK");
+        assert_eq!(output["confirm"].text, "OK");
+        assert_eq!(output["cancel"].text, "NO");
+    }
+
+    /// 测试上游请求与键值表的迭代顺序无关
+    #[tokio::test]
+    async fn test_order_independent_requests() {
+        let first = MockTranslator::new();
+        let second = MockTranslator::new();
+        let input: HashMap<i32, String> = (0..20).map(|i| (i, format!("text {}", i % 7))).collect();
+        let reordered: HashMap<i32, String> = (0..20)
+            .rev()
+            .map(|i| (i, format!("text {}", i % 7)))
+            .collect();
+        translate_map(&first, &input, None, &Language::Chinese)
+            .await
+            .unwrap();
+        translate_map(&second, &reordered, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(first.requests(), second.requests());
+        assert_eq!(first.translated_items(), 7);
+    }
+
+    /// 测试按分块大小拆分请求
+    #[tokio::test]
+    async fn test_chunking() {
+        let translator = MockTranslator::new();
+        let input: HashMap<usize, String> = (0..25).map(|i| (i, format!("v{}", i))).collect();
+        let options = BatchOptions {
+            chunk_size: 10,
+            ..Default::default()
+        };
+        let output = translate_map_with(&translator, &input, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(translator.calls(), 3);
+        assert_eq!(output.translations.len(), 25);
+        assert!(output.errors.is_empty());
+    }
+
+    /// 测试部分失败时错误按键记录
+    #[tokio::test]
+    async fn test_partial_errors() {
+        let translator = MockTranslator::new().fail_on("bad");
+        let input = map(&[
+            ("a", "aaa"),
+            ("b", "bad one"),
+            ("c", "bad one"),
+            ("d", "zzz"),
+        ]);
+        let options = BatchOptions {
+            chunk_size: 1,
+            partial: true,
+            ..Default::default()
+        };
+        let output = translate_map_with(&translator, &input, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.translations.len(), 2);
+        assert_eq!(output.errors.len(), 2);
+        assert!(output.errors.contains_key("b"));
+        assert!(output.errors.contains_key("c"));
+        assert_eq!(output.translations["d"].text, "ZZZ");
+        assert_eq!(translator.calls(), 3);
+    }
+
+    /// 测试不允许部分失败时返回错误
+    #[tokio::test]
+    async fn test_strict_error() {
+        let translator = MockTranslator::new().fail_on("bad");
+        let input = map(&[("a", "aaa"), ("b", "bad")]);
+        assert!(translate_map(&translator, &input, None, &Language::Chinese)
+            .await
+            .is_err());
+    }
+
+    /// 测试批量翻译中途取消
+    ///
+    /// 取消后返回已完成的条目数，且不再发起新的上游调用
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_mid_batch() {
+        use crate::fusion_translator::translate_options::TranslateOptions;
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use std::time::Duration;
+        use tokio_util::sync::CancellationToken;
+
+        let translator = MockTranslator::new().with_delay(Duration::from_millis(100));
+        let input: HashMap<usize, String> = (0..10).map(|i| (i, format!("v{}", i))).collect();
+        let token = CancellationToken::new();
+        let options = BatchOptions {
+            translate_options: TranslateOptions::new().with_cancellation(token.clone()),
+            chunk_size: 1,
+            ..Default::default()
+        };
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            canceller.cancel();
+        });
+        let err = translate_map_with(&translator, &input, None, &Language::Chinese, &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(2))
+        ));
+        assert_eq!(translator.calls(), 3);
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(translator.calls(), 3);
+    }
+
+    /// 测试分块被取消时报告的条目数包含之前分块已完成的条目
+    #[tokio::test]
+    async fn test_cancelled_chunk_offset() {
+        use crate::fusion_translator::async_translator::TranslationListOutput;
+        use crate::fusion_translator::batch::translate_in_chunks;
+        use crate::fusion_translator::translator_error::TranslatorError;
+
+        let query = (0..5).map(|i| format!("v{}", i)).collect::<Vec<_>>();
+        let err = translate_in_chunks(&query, Some(2), None, 0, |chunk| async move {
+            if chunk.first().is_some_and(|first| first == "v2") {
+                return Err(TranslatorError::Cancelled(1).into());
+            }
+            Ok(TranslationListOutput {
+                text: chunk.to_vec(),
+                ..Default::default()
+            })
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(3))
+        ));
+    }
+
+    /// 测试已取消的令牌不会发起任何调用
+    #[tokio::test]
+    async fn test_cancelled_before_start() {
+        use crate::fusion_translator::translate_options::TranslateOptions;
+        use tokio_util::sync::CancellationToken;
+
+        let translator = MockTranslator::new();
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = BatchOptions {
+            translate_options: TranslateOptions::new().with_cancellation(token),
+            ..Default::default()
+        };
+        let input = map(&[("a", "x")]);
+        assert!(
+            translate_map_with(&translator, &input, None, &Language::Chinese, &options)
+                .await
+                .is_err()
+        );
+        assert_eq!(translator.calls(), 0);
+    }
+
+    /// 测试用的检查点文件路径，已存在时先删除
+    fn checkpoint_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "fusion-translator-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// 测试中途取消后从检查点续传
+    ///
+    /// 续传时已完成的文本不会再次发送给上游
+    #[tokio::test(start_paused = true)]
+    async fn test_checkpoint_resume_after_cancel() {
+        use crate::fusion_translator::batch::CheckpointedBatch;
+        use crate::fusion_translator::translate_options::TranslateOptions;
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use std::time::Duration;
+        use tokio_util::sync::CancellationToken;
+
+        let path = checkpoint_path("resume");
+        let inputs = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+
+        let first = MockTranslator::new().with_delay(Duration::from_millis(100));
+        let token = CancellationToken::new();
+        let options = BatchOptions {
+            translate_options: TranslateOptions::new().with_cancellation(token.clone()),
+            chunk_size: 2,
+            ..Default::default()
+        };
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            canceller.cancel();
+        });
+        let err = CheckpointedBatch::new(&first, "mock", &path)
+            .with_options(options)
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(2))
+        ));
+
+        let second = MockTranslator::new();
+        let output = CheckpointedBatch::new(&second, "mock", &path)
+            .with_options(BatchOptions {
+                chunk_size: 2,
+                ..Default::default()
+            })
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output, vec!["A", "B", "C", "D", "E"]);
+        assert_eq!(
+            second.requests(),
+            vec![
+                vec!["c".to_string(), "d".to_string()],
+                vec!["e".to_string()]
+            ]
+        );
+
+        let third = MockTranslator::new();
+        CheckpointedBatch::new(&third, "mock", &path)
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(third.calls(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// 测试检查点与本次批量翻译不一致时拒绝续传
+    #[tokio::test]
+    async fn test_checkpoint_mismatch() {
+        use crate::fusion_translator::batch::CheckpointedBatch;
+        use crate::fusion_translator::translator_error::TranslatorError;
+
+        let path = checkpoint_path("mismatch");
+        let inputs = vec!["hello".to_string()];
+        let translator = MockTranslator::new();
+        CheckpointedBatch::new(&translator, "mock", &path)
+            .run(&inputs, Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+
+        let other_inputs = vec!["hello".to_string(), "world".to_string()];
+        for (engine, inputs, to) in [
+            ("other", &inputs, Language::Chinese),
+            ("mock", &inputs, Language::Japanese),
+            ("mock", &other_inputs, Language::Chinese),
+        ] {
+            let err = CheckpointedBatch::new(&translator, engine, &path)
+                .run(inputs, Some(Language::English), &to)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<TranslatorError>(),
+                Some(TranslatorError::CheckpointMismatch(_))
+            ));
+        }
+        assert_eq!(translator.calls(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// 测试忽略写入中断留下的半行
+    #[tokio::test]
+    async fn test_checkpoint_torn_line() {
+        use crate::fusion_translator::batch::CheckpointedBatch;
+
+        let path = checkpoint_path("torn");
+        let inputs = vec!["x".to_string(), "y".to_string()];
+        let options = BatchOptions {
+            chunk_size: 1,
+            ..Default::default()
+        };
+        CheckpointedBatch::new(&MockTranslator::new(), "mock", &path)
+            .with_options(options.clone())
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap();
+
+        // 保留文件头和第一条记录，模拟写入第二条记录时中断
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        std::fs::write(
+            &path,
+            format!("{}\n{}\n{{\"index\":1,\"tra", lines[0], lines[1]),
+        )
+        .unwrap();
+
+        let resumed = MockTranslator::new();
+        let output = CheckpointedBatch::new(&resumed, "mock", &path)
+            .with_options(options)
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output, vec!["X", "Y"]);
+        assert_eq!(resumed.requests(), vec![vec!["y".to_string()]]);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().last().unwrap(), lines[2]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// 测试关闭时正在请求的分块写入检查点后返回，续传时只翻译之后的分块
+    #[tokio::test(start_paused = true)]
+    async fn test_checkpoint_shutdown_persists_in_flight_chunk() {
+        use crate::fusion_translator::batch::CheckpointedBatch;
+        use crate::fusion_translator::shutdown::Shutdown;
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use std::time::Duration;
+
+        let path = checkpoint_path("shutdown");
+        let inputs = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        let options = BatchOptions {
+            chunk_size: 2,
+            ..Default::default()
+        };
+
+        let first = MockTranslator::new().with_delay(Duration::from_millis(100));
+        let batch = CheckpointedBatch::new(&first, "mock", &path).with_options(options.clone());
+        let (result, shutdown) =
+            tokio::join!(batch.run(&inputs, None, &Language::Chinese), async {
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                batch.shutdown().await
+            });
+        shutdown.unwrap();
+        assert!(matches!(
+            result.unwrap_err().downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(4))
+        ));
+        assert_eq!(first.calls(), 2);
+
+        let second = MockTranslator::new();
+        let output = CheckpointedBatch::new(&second, "mock", &path)
+            .with_options(options)
+            .run(&inputs, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output, vec!["A", "B", "C", "D", "E"]);
+        assert_eq!(second.requests(), vec![vec!["e".to_string()]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// 测试批量结果数量校验
+    #[test]
+    fn test_validate_batch() {
+        use crate::fusion_translator::batch::validate_batch;
+        use crate::fusion_translator::translator_error::TranslatorError;
+
+        let output = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(validate_batch(2, output.clone()).unwrap(), output);
+        assert!(validate_batch(0, vec![]).unwrap().is_empty());
+        assert!(matches!(
+            validate_batch(3, output),
+            Err(TranslatorError::BatchSizeMismatch(3, 2))
+        ));
+    }
+
+    /// 测试模拟翻译器通过批量形状检查
+    #[tokio::test]
+    async fn test_mock_batch_shape() {
+        use crate::fusion_translator::batch::assert_batch_shape;
+
+        assert_batch_shape(&MockTranslator::new(), &Language::Chinese).await;
+    }
+
+    /// 测试各翻译器在回显传输层下通过批量形状检查
+    #[tokio::test]
+    async fn test_backend_batch_shape() {
+        use crate::fusion_translator::batch::assert_batch_shape;
+        use crate::fusion_translator::politeness::PolitenessSetting;
+        use crate::fusion_translator::test_support::echo::{self, EchoTransport};
+        use crate::fusion_translator::translator_factory::{TranslatorFactory, TranslatorType};
+        use crate::fusion_translator::translator_options::TranslatorOptions;
+        use std::sync::Arc;
+
+        for backend in TranslatorType::ALL {
+            let options = TranslatorOptions::new()
+                .with_transport(Arc::new(EchoTransport::new(backend)))
+                .with_politeness(PolitenessSetting::Disabled);
+            let translator =
+                TranslatorFactory::create_with_options(echo::config(backend), &options);
+            assert_batch_shape(translator.as_ref(), &Language::Chinese).await;
+        }
+    }
+
+    /// 测试形状检查对其他错误失败
+    #[tokio::test]
+    #[should_panic(expected = "unexpected error")]
+    async fn test_batch_shape_rejects_other_errors() {
+        use crate::fusion_translator::batch::assert_batch_shape;
+        let translator = MockTranslator::new().with_transient_failures(usize::MAX);
+        assert_batch_shape(&translator, &Language::Chinese).await;
+    }
+
+    /// 测试重复文本携带不同标签时，译文分发给每个标签且顺序与输入相同
+    #[tokio::test]
+    async fn test_tagged_duplicates() {
+        use crate::fusion_translator::batch::translate_tagged;
+
+        let translator = MockTranslator::new();
+        let items = vec![
+            (101, "ok".to_string()),
+            (102, "no".to_string()),
+            (103, "ok".to_string()),
+            (101, "yes".to_string()),
+            (104, "ok".to_string()),
+        ];
+        let output = translate_tagged(&translator, items, None, &Language::Chinese)
+            .await
+            .unwrap();
+        let texts = output
+            .iter()
+            .map(|(tag, result)| (*tag, result.as_ref().unwrap().text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                (101, "OK"),
+                (102, "NO"),
+                (103, "OK"),
+                (101, "YES"),
+                (104, "OK")
+            ]
+        );
+        assert_eq!(translator.translated_items(), 3);
+    }
+
+    /// 测试部分失败时错误只分发给失败分块中的文本的标签
+    #[tokio::test]
+    async fn test_tagged_partial_failure() {
+        use crate::fusion_translator::batch::translate_tagged_with;
+
+        let translator = MockTranslator::new().fail_on("bad");
+        // 去重排序后为["a", "bad", "c", "d"]，按2个一块时第一块失败
+        let items = ["c", "bad", "a", "bad", "d", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| (format!("row{}", i), text.to_string()))
+            .collect::<Vec<_>>();
+        let options = BatchOptions {
+            chunk_size: 2,
+            partial: true,
+            ..Default::default()
+        };
+        let output = translate_tagged_with(
+            &translator,
+            items.clone(),
+            None,
+            &Language::Chinese,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.len(), items.len());
+        for ((tag, result), (input_tag, text)) in output.iter().zip(&items) {
+            assert_eq!(tag, input_tag);
+            match text.as_str() {
+                "a" | "bad" => assert!(result.is_err(), "{}", tag),
+                _ => assert_eq!(result.as_ref().unwrap().text, text.to_uppercase()),
+            }
+        }
+        // 同一文本的多个标签共享同一个错误
+        assert!(std::sync::Arc::ptr_eq(
+            output[1].1.as_ref().unwrap_err(),
+            output[3].1.as_ref().unwrap_err()
+        ));
+
+        let strict = BatchOptions {
+            partial: false,
+            ..options
+        };
+        assert!(
+            translate_tagged_with(&translator, items, None, &Language::Chinese, &strict)
+                .await
+                .is_err()
+        );
+    }
+
+    /// 测试重试后标签仍与各自的文本对应
+    #[tokio::test(start_paused = true)]
+    async fn test_tagged_retry() {
+        use crate::fusion_translator::batch::translate_tagged;
+        use crate::fusion_translator::retry::RetryTranslator;
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockTranslator::new().with_transient_failures(2));
+        let translator = RetryTranslator::new(mock.clone(), 3);
+        let items = (0..120)
+            .map(|i| (i, format!("text {}", i % 30)))
+            .collect::<Vec<_>>();
+        let output = translate_tagged(&translator, items, None, &Language::Chinese)
+            .await
+            .unwrap();
+        for (i, (tag, result)) in output.into_iter().enumerate() {
+            assert_eq!(tag, i);
+            assert_eq!(result.unwrap().text, format!("TEXT {}", i % 30));
+        }
+        assert_eq!(mock.calls(), 3);
+        assert_eq!(mock.translated_items(), 90);
+    }
+
+    /// 用同一个分块翻译40个文本，其中第7和第23个包含拒绝标记
+    async fn rejected_batch(
+        translator: &MockTranslator,
+        options: BatchOptions,
+    ) -> crate::fusion_translator::batch::MapTranslation<usize> {
+        let map = (0..40)
+            .map(|i| {
+                let marker = if i == 7 || i == 23 { " BLOCKED" } else { "" };
+                (i, format!("text {:02}{}", i, marker))
+            })
+            .collect::<HashMap<_, _>>();
+        let options = BatchOptions {
+            chunk_size: 40,
+            partial: true,
+            ..options
+        };
+        translate_map_with(translator, &map, None, &Language::Chinese, &options)
+            .await
+            .unwrap()
+    }
+
+    /// 测试二分定位在预算内准确找出被拒绝的文本，其余文本正常翻译
+    #[tokio::test]
+    async fn test_bisect_isolates_rejected() {
+        use crate::fusion_translator::batch::DEFAULT_BISECT_BUDGET;
+        use crate::fusion_translator::translator_error::ErrorKind;
+
+        let translator = MockTranslator::new().reject_on("BLOCKED");
+        let output = rejected_batch(&translator, BatchOptions::default()).await;
+        let mut rejected = output.rejected().into_iter().copied().collect::<Vec<_>>();
+        rejected.sort_unstable();
+        assert_eq!(rejected, [7, 23]);
+        assert_eq!(output.errors.len(), 2);
+        assert_eq!(
+            ErrorKind::of(&output.errors[&7]),
+            ErrorKind::ContentRejected
+        );
+        assert_eq!(output.translations.len(), 38);
+        assert_eq!(output.translations[&8].text, "TEXT 08");
+        // 2个文本在40个中各需约2×log2(40)次请求
+        assert!(
+            translator.calls() - 1 <= DEFAULT_BISECT_BUDGET,
+            "{}",
+            translator.calls()
+        );
+    }
+
+    /// 测试预算用完后未定位的文本保留原始错误
+    #[tokio::test]
+    async fn test_bisect_budget() {
+        let translator = MockTranslator::new().reject_on("BLOCKED");
+        let options = BatchOptions {
+            bisect_budget: 3,
+            ..Default::default()
+        };
+        let output = rejected_batch(&translator, options).await;
+        assert_eq!(translator.calls(), 4);
+        // [0, 20)和[20, 40)都被拒绝，第三次请求的[20, 30)也被拒绝，没有可以确认的文本
+        assert!(output.translations.is_empty());
+        assert_eq!(output.rejected().len(), 40);
+
+        let translator = MockTranslator::new().reject_on("BLOCKED");
+        let options = BatchOptions {
+            bisect_budget: 6,
+            ..Default::default()
+        };
+        let output = rejected_batch(&translator, options).await;
+        assert_eq!(translator.calls(), 7);
+        // [30, 40)和[25, 30)在预算内翻译成功，其余保留原始错误
+        assert_eq!(output.translations.len(), 15);
+        assert_eq!(output.rejected().len(), 25);
+
+        let disabled = MockTranslator::new().reject_on("BLOCKED");
+        let options = BatchOptions {
+            bisect_budget: 0,
+            ..Default::default()
+        };
+        let output = rejected_batch(&disabled, options).await;
+        assert_eq!(disabled.calls(), 1);
+        assert_eq!(output.rejected().len(), 40);
+    }
+
+    /// 测试计费的翻译器默认不进行二分定位，需要显式开启
+    #[tokio::test]
+    async fn test_bisect_paid_opt_in() {
+        let paid = MockTranslator::new().reject_on("BLOCKED").with_paid(true);
+        let output = rejected_batch(&paid, BatchOptions::default()).await;
+        assert_eq!(paid.calls(), 1);
+        assert_eq!(output.errors.len(), 40);
+
+        let paid = MockTranslator::new().reject_on("BLOCKED").with_paid(true);
+        let options = BatchOptions {
+            bisect_paid: true,
+            ..Default::default()
+        };
+        let output = rejected_batch(&paid, options).await;
+        assert_eq!(output.errors.len(), 2);
+        assert!(paid.calls() > 1);
+    }
+
+    /// 构造混合了少量超长文本的批次
+    ///
+    /// 第50个为约8 KB的英文，第120个为60个字符、180字节的中文，其余为短文本；
+    /// 中文按字符数不超过100，按字节数超过，用于确认按翻译器的字节单位判断
+    fn mixed_batch() -> Vec<String> {
+        let mut query = (0..200).map(|i| format!("item {}", i)).collect::<Vec<_>>();
+        query[50] = "Some words here. ".repeat(480);
+        query[120] = "你好。".repeat(20);
+        query
+    }
+
+    /// 测试默认策略下超长文本拆分翻译后合并为一个译文，其余文本正常分块
+    #[tokio::test]
+    async fn test_oversized_elements_split() {
+        let translator = MockTranslator::new().with_limits(None, Some(100));
+        let query = mixed_batch();
+        let output = translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text.len(), query.len());
+        let expected = query.iter().map(|q| q.to_uppercase()).collect::<Vec<_>>();
+        assert_eq!(output.text, expected);
+        for request in translator.requests() {
+            assert!(request.iter().map(String::len).sum::<usize>() <= 100);
+        }
+
+        let map = mixed_batch()
+            .into_iter()
+            .enumerate()
+            .collect::<HashMap<_, _>>();
+        let output = translate_map(&translator, &map, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.len(), map.len());
+        assert_eq!(output[&50].text, map[&50].to_uppercase());
+    }
+
+    /// 测试严格策略下只有超长文本失败，其余文本正常翻译
+    #[tokio::test]
+    async fn test_oversized_elements_strict() {
+        use crate::fusion_translator::translate_options::TranslateOptions;
+        use crate::fusion_translator::translator_error::ErrorKind;
+
+        let translator = MockTranslator::new().with_limits(None, Some(100));
+        let map = mixed_batch()
+            .into_iter()
+            .enumerate()
+            .collect::<HashMap<_, _>>();
+        let options = BatchOptions {
+            translate_options: TranslateOptions::new().with_auto_split(false),
+            partial: true,
+            ..Default::default()
+        };
+        let output = translate_map_with(&translator, &map, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.translations.len() + output.errors.len(), map.len());
+        let mut failed = output.errors.keys().copied().collect::<Vec<_>>();
+        failed.sort();
+        assert_eq!(failed, [50, 120]);
+        assert!(output
+            .errors
+            .values()
+            .all(|e| ErrorKind::of(e) == ErrorKind::TooLong));
+        assert_eq!(output.translations[&49].text, "ITEM 49");
+
+        // 不允许部分失败时整个批次返回错误
+        let options = BatchOptions {
+            partial: false,
+            ..options
+        };
+        let err = translate_map_with(&translator, &map, None, &Language::Chinese, &options)
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::TooLong);
+    }
+
+    /// 测试多种语料和翻译器上限下，计划的请求数与实际运行模拟翻译器的调用次数相同
+    #[tokio::test]
+    async fn test_plan_matches_actual_requests() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(696);
+        let words = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta"];
+        for round in 0..100 {
+            // 语料包含重复文本、空文本和由多个句子组成的长文本
+            let corpus = (0..rng.random_range(0..120))
+                .map(|i| {
+                    let sentences = match rng.random_range(0..10) {
+                        0 => 0,
+                        1 => rng.random_range(4..12),
+                        _ => rng.random_range(1..3),
+                    };
+                    let text = (0..sentences)
+                        .map(|_| {
+                            (0..rng.random_range(1..5))
+                                .map(|_| words[rng.random_range(0..words.len())])
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                                + "."
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    (i, text)
+                })
+                .collect::<HashMap<_, _>>();
+            let max_batch_len = rng.random_bool(0.7).then(|| rng.random_range(1..20));
+            let input_limit = rng.random_bool(0.7).then(|| rng.random_range(40..400));
+            let translator = MockTranslator::new().with_limits(max_batch_len, input_limit);
+            let options = BatchOptions {
+                chunk_size: rng.random_range(1..60),
+                ..Default::default()
+            };
+
+            let plan = plan_map(&translator, &corpus, &options);
+            translate_map_with(&translator, &corpus, None, &Language::Chinese, &options)
+                .await
+                .unwrap();
+            assert_eq!(plan.segments, corpus.len());
+            assert_eq!(plan.requests, translator.calls(), "round {}", round);
+            assert_eq!(
+                plan.chunks.iter().map(|c| c.len()).sum::<usize>(),
+                plan.unique_segments
+            );
+        }
+    }
+
+    /// 测试计划统计去重后的文本和字符数，不计费的翻译器费用为0
+    #[test]
+    fn test_plan_counts_unique_characters() {
+        let translator = MockTranslator::new();
+        let corpus = map(&[("a", "你好"), ("b", "hello"), ("c", "你好")]);
+        let plan = plan_map(&translator, &corpus, &BatchOptions::default());
+        assert_eq!(plan.segments, 3);
+        assert_eq!(plan.unique_segments, 2);
+        assert_eq!(plan.characters, 7);
+        assert_eq!(plan.requests, 1);
+        assert_eq!(plan.estimated_cost(20.0), 0.0);
+
+        let plan = super::TranslationPlan { paid: true, ..plan };
+        assert_eq!(plan.estimated_cost(20_000.0), 0.14);
+        assert_eq!(translator.calls(), 0);
+    }
+}
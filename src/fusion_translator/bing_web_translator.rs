@@ -0,0 +1,941 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::{self, empty_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transport::{self, HttpRequest, HttpResponse, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::Instrument;
+
+/// Bing支持的语言及其代码
+const LANGUAGES: &[(Language, &str)] = &[
+    (Language::Afrikaans, "af"),
+    (Language::Arabic, "ar"),
+    (Language::Bengali, "bn"),
+    (Language::Bosnian, "bs"),
+    (Language::Bulgarian, "bg"),
+    (Language::Catalan, "ca"),
+    (Language::Chinese, "zh-Hans"),
+    (Language::ChineseTraditional, "zh-Hant"),
+    (Language::Croatian, "hr"),
+    (Language::Czech, "cs"),
+    (Language::Danish, "da"),
+    (Language::Dutch, "nl"),
+    (Language::English, "en"),
+    (Language::Estonian, "et"),
+    (Language::FilipinoPilipino, "fil"),
+    (Language::Finnish, "fi"),
+    (Language::French, "fr"),
+    (Language::German, "de"),
+    (Language::Greek, "el"),
+    (Language::Hebrew, "he"),
+    (Language::Hindi, "hi"),
+    (Language::Hungarian, "hu"),
+    (Language::Icelandic, "is"),
+    (Language::Indonesian, "id"),
+    (Language::Irish, "ga"),
+    (Language::Italian, "it"),
+    (Language::Japanese, "ja"),
+    (Language::Korean, "ko"),
+    (Language::Latvian, "lv"),
+    (Language::Lithuanian, "lt"),
+    (Language::Malay, "ms"),
+    (Language::Maltese, "mt"),
+    (Language::Norwegian, "nb"),
+    (Language::Persian, "fa"),
+    (Language::Polish, "pl"),
+    (Language::Portuguese, "pt"),
+    (Language::Romanian, "ro"),
+    (Language::Russian, "ru"),
+    (Language::Slovak, "sk"),
+    (Language::Slovenian, "sl"),
+    (Language::Spanish, "es"),
+    (Language::Swahili, "sw"),
+    (Language::Swedish, "sv"),
+    (Language::Tamil, "ta"),
+    (Language::Telugu, "te"),
+    (Language::Thai, "th"),
+    (Language::Turkish, "tr"),
+    (Language::Ukrainian, "uk"),
+    (Language::Urdu, "ur"),
+    (Language::Vietnamese, "vi"),
+    (Language::Welsh, "cy"),
+];
+
+/// 检测结果中可能出现的旧代码，只用于反查
+///
+/// 规范化方式与`language_map`中的其他翻译器一致
+const LEGACY_CODES: &[(&str, Language)] = &[
+    ("iw", Language::Hebrew),
+    ("no", Language::Norwegian),
+    ("tl", Language::FilipinoPilipino),
+];
+
+/// 网页版单次翻译的最大长度
+///
+/// 网页限制为1000个字符（Unicode标量值）；批量分块时按UTF-8字节数保守处理
+const INPUT_LIMIT: usize = 1000;
+
+/// 会话过期时接口返回的`statusCode`
+const TOKEN_EXPIRED: u64 = 205;
+
+/// 把语言转换为Bing的语言代码
+///
+/// # 返回值
+/// 语言代码，Bing不支持该语言时为None
+fn to_bing(lang: &Language) -> Option<&'static str> {
+    LANGUAGES
+        .iter()
+        .find(|(l, _)| l == lang)
+        .map(|&(_, code)| code)
+}
+
+/// 把Bing的语言代码转换为语言
+///
+/// 同时接受`LEGACY_CODES`中的旧代码
+///
+/// # 返回值
+/// 语言，代码不在表中时为None
+fn from_bing(code: &str) -> Option<Language> {
+    LANGUAGES
+        .iter()
+        .find(|(_, c)| c.eq_ignore_ascii_case(code))
+        .map(|&(lang, _)| lang)
+        .or_else(|| {
+            LEGACY_CODES
+                .iter()
+                .find(|(c, _)| c.eq_ignore_ascii_case(code))
+                .map(|&(_, lang)| lang)
+        })
+}
+
+/// 取出`text`中位于`start`之后、`end`之前的第一段内容
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let rest = &text[text.find(start)? + start.len()..];
+    Some(&rest[..rest.find(end)?])
+}
+
+/// 从翻译页面获取的会话参数
+#[derive(Debug, Clone)]
+struct Session {
+    /// 页面实例标识，页面脚本中的`IG`
+    ig: String,
+    /// 翻译组件标识，页面元素的`data-iid`
+    iid: String,
+    /// 与令牌配对的时间戳
+    key: String,
+    /// 翻译请求令牌
+    token: String,
+    /// 令牌的过期时间
+    expires_at: Instant,
+}
+
+impl Session {
+    /// 从翻译页面解析会话参数
+    ///
+    /// 页面脚本中的`params_AbusePreventionHelper`为`[时间戳, 令牌, 有效期毫秒数]`
+    ///
+    /// # 参数
+    /// - `html`: `/translator`页面内容
+    /// - `now`: 获取页面的时间
+    ///
+    /// # 返回值
+    /// 会话参数，页面中缺少任一参数时返回`TranslatorError::Handshake`
+    fn parse(html: &str, now: Instant) -> Result<Session, TranslatorError> {
+        let missing = |name: &str| TranslatorError::Handshake(format!("{} not found", name));
+        let ig = between(html, "IG:\"", "\"").ok_or_else(|| missing("IG"))?;
+        let iid = between(html, "data-iid=\"", "\"").ok_or_else(|| missing("IID"))?;
+        let params = between(html, "params_AbusePreventionHelper = [", "]")
+            .ok_or_else(|| missing("params_AbusePreventionHelper"))?;
+        let (key, token, expiry_ms): (Value, String, u64) =
+            serde_json::from_str(&format!("[{}]", params))
+                .map_err(|e| TranslatorError::Handshake(e.to_string()))?;
+        let key = match key {
+            Value::String(key) => key,
+            key => key.to_string(),
+        };
+        Ok(Session {
+            ig: ig.to_string(),
+            iid: iid.to_string(),
+            key,
+            token,
+            expires_at: now + Duration::from_millis(expiry_ms),
+        })
+    }
+}
+
+/// 单个文本的翻译结果
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BingResult {
+    /// 自动检测到的源语言，指定源语言时没有此字段
+    #[serde(default)]
+    detected_language: Option<DetectedLanguage>,
+    /// 译文，每个目标语言一项
+    translations: Vec<BingTranslation>,
+}
+
+/// 检测到的源语言
+#[derive(Debug, Deserialize)]
+struct DetectedLanguage {
+    /// 语言代码
+    language: String,
+}
+
+/// 一个目标语言的译文
+#[derive(Debug, Deserialize)]
+struct BingTranslation {
+    /// 译文
+    text: String,
+    /// 目标语言代码
+    #[serde(default)]
+    to: Option<String>,
+}
+
+/// 翻译接口的响应
+#[derive(Debug)]
+enum Reply {
+    /// 翻译成功
+    Translated(BingResult),
+    /// 会话已过期，需要重新获取会话参数
+    Expired,
+}
+
+/// 解析翻译接口的响应
+///
+/// 成功时响应为数组；失败时为带`statusCode`（或`StatusCode`）的对象，
+/// 需要验证码时带`ShowCaptcha`
+///
+/// # 参数
+/// - `resp`: 翻译接口返回的JSON
+///
+/// # 返回值
+/// 翻译结果或会话过期；需要验证码或返回429时为`TranslatorError::RateLimited`
+fn parse_reply(resp: Value) -> Result<Reply, TranslatorError> {
+    if resp.is_array() {
+        let mut results =
+            Vec::<BingResult>::deserialize(resp).map_err(|_| TranslatorError::NoResponse)?;
+        if results.is_empty() || results[0].translations.is_empty() {
+            return Err(TranslatorError::NoResponse);
+        }
+        return Ok(Reply::Translated(results.remove(0)));
+    }
+    if resp["ShowCaptcha"].as_bool() == Some(true) {
+        return Err(TranslatorError::RateLimited("captcha required".to_string()));
+    }
+    match resp["statusCode"].as_u64().or(resp["StatusCode"].as_u64()) {
+        Some(TOKEN_EXPIRED) => Ok(Reply::Expired),
+        Some(429) => Err(TranslatorError::RateLimited("429".to_string())),
+        Some(code) => Err(TranslatorError::RequestFailed(code as u16)),
+        None => Err(TranslatorError::NoResponse),
+    }
+}
+
+/// 检查HTTP状态码
+///
+/// # 参数
+/// - `response`: 收到的响应
+///
+/// # 返回值
+/// 429时为`TranslatorError::RateLimited`，其他非2xx状态码为`TranslatorError::RequestFailed`
+fn check_status(response: &HttpResponse) -> Result<(), TranslatorError> {
+    match StatusCode::from_u16(response.status) {
+        Ok(StatusCode::TOO_MANY_REQUESTS) => Err(TranslatorError::RateLimited(
+            StatusCode::TOO_MANY_REQUESTS.to_string(),
+        )),
+        _ if !response.is_success() => Err(TranslatorError::RequestFailed(response.status)),
+        _ => Ok(()),
+    }
+}
+
+/// Bing网页翻译器实现
+///
+/// 调用Bing翻译网页使用的接口，无需Azure密钥。首次翻译时请求`/translator`页面，
+/// 解析`IG`、`IID`、时间戳和令牌并缓存到过期为止；接口返回会话过期时重新获取一次后重试
+pub struct BingWebTranslator {
+    /// 网站地址
+    host: String,
+    /// 缓存的会话参数，首次翻译时获取
+    session: Mutex<Option<Session>>,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
+}
+
+/// 默认实现
+impl Default for BingWebTranslator {
+    fn default() -> Self {
+        BingWebTranslator::new()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for BingWebTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// Bing网页翻译器需要调用远程API，返回false
+    fn local(&self) -> bool {
+        false
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 向翻译页面发送HEAD请求，不获取会话参数；会话在第一次翻译时获取，失败时只记录日志
+    async fn warm_up(&self) {
+        transport::warm_up(
+            &*self.transport,
+            "bing_web",
+            &format!("{}/translator", self.host),
+        )
+        .await
+    }
+
+    /// 单次请求最多的文本数量
+    ///
+    /// 接口每次只接受一个文本
+    fn max_batch_len(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(to_bing))
+    }
+
+    /// 单次请求最多的字符数
+    fn input_limit(&self) -> Option<usize> {
+        Some(INPUT_LIMIT)
+    }
+
+    /// 按字符数计算长度
+    fn input_unit(&self) -> InputUnit {
+        InputUnit::Chars
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 以自动检测的源语言翻译为英语，返回接口检测到的语言
+    ///
+    /// # 参数
+    /// - `text`: 待检测的文本
+    ///
+    /// # 返回值
+    /// 检测到的语言，代码不在语言表中时为None
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let text = long_text::split_by_bytes(text, INPUT_LIMIT)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        if text.trim().is_empty() {
+            return Ok(None);
+        }
+        let result = self.call(text, "auto-detect", "en", None).await?;
+        Ok(result
+            .detected_language
+            .and_then(|detected| from_bing(&detected.language)))
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("bing_web", query, from, to, options)
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 接口每次只接受一个文本，逐个顺序请求
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            0,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("bing_web", query, from, to, &options)
+        })
+    }
+}
+
+impl BingWebTranslator {
+    /// 创建新的Bing网页翻译器实例
+    ///
+    /// 创建时不发送请求，会话参数在首次翻译时获取
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new() -> Self {
+        BingWebTranslator {
+            transport: TranslatorOptions::default().build_transport(TranslatorType::BingWeb),
+            politeness: Some(Politeness::shared("bing_web", PolitenessConfig::default())),
+            host: "https://www.bing.com".to_string(),
+            session: Mutex::new(None),
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::BingWeb);
+        self.politeness = options.politeness.resolve("bing_web");
+        self
+    }
+
+    /// 获取未过期的会话参数
+    ///
+    /// 缓存的会话过期或不存在时请求翻译页面重新获取。
+    /// 多个任务同时发现会话过期时可能各自获取一次，后获取的会话覆盖先前的
+    async fn session(&self) -> anyhow::Result<Session> {
+        let cached = self.session.lock().unwrap().clone();
+        if let Some(session) = cached.filter(|s| Instant::now() < s.expires_at) {
+            return Ok(session);
+        }
+        let now = Instant::now();
+        let request = HttpRequest::get(format!("{}/translator", self.host));
+        let response =
+            politeness::send(self.politeness.as_deref(), &*self.transport, request).await?;
+        check_status(&response)?;
+        let session = Session::parse(&response.text(), now)?;
+        tracing::debug!(backend = "bing_web", ig = %session.ig, "session refreshed");
+        *self.session.lock().unwrap() = Some(session.clone());
+        Ok(session)
+    }
+
+    /// 调用翻译接口
+    ///
+    /// 会话过期时丢弃缓存的会话，重新获取一次后重试
+    ///
+    /// # 参数
+    /// - `text`: 待翻译的文本
+    /// - `from`: 源语言代码，自动检测为`auto-detect`
+    /// - `to`: 目标语言代码
+    /// - `request_id`: 请求ID，通过`X-Request-Id`请求头传递
+    ///
+    /// # 返回值
+    /// 翻译结果；刷新会话后仍然过期时返回`TranslatorError::Handshake`
+    async fn call(
+        &self,
+        text: &str,
+        from: &str,
+        to: &str,
+        request_id: Option<&str>,
+    ) -> anyhow::Result<BingResult> {
+        for attempt in 0..2 {
+            let session = self.session().await?;
+            let request = with_request_id_header(
+                HttpRequest::post(format!("{}/ttranslatev3", self.host))
+                    .query(&[
+                        ("isVertical", "1"),
+                        ("IG", &session.ig),
+                        ("IID", &session.iid),
+                    ])?
+                    .form(&[
+                        ("fromLang", from),
+                        ("to", to),
+                        ("text", text),
+                        ("token", &session.token),
+                        ("key", &session.key),
+                    ])?,
+                request_id,
+            );
+            let response =
+                politeness::send(self.politeness.as_deref(), &*self.transport, request).await?;
+            check_status(&response)?;
+            match parse_reply(response.json()?)? {
+                Reply::Translated(result) => return Ok(result),
+                Reply::Expired => {
+                    tracing::debug!(backend = "bing_web", attempt, "session expired");
+                    *self.session.lock().unwrap() = None;
+                }
+            }
+        }
+        Err(TranslatorError::Handshake("token expired again after refresh".to_string()).into())
+    }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，最多一个
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let Some(text) = query.first() else {
+            return Ok(empty_batch(options));
+        };
+        let output = self.translate_with_options(text, from, to, options).await?;
+        Ok(TranslationListOutput {
+            text: vec![output.text],
+            lang: output.lang,
+            detail: output.detail,
+        })
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = &*preprocess::apply(query, options)?;
+        if query.trim().is_empty() {
+            return Ok(TranslationOutput {
+                text: query.to_string(),
+                lang: Some(*to),
+                detail: TranslationDetail {
+                    request_id: options.resolve_request_id(),
+                    ..Default::default()
+                },
+            });
+        }
+        let check = self.check_input(query);
+        if let Err(err) = check.check() {
+            if !options.auto_split {
+                return Err(err.into());
+            }
+            return long_text::translate_checked(self, query, &check, from, to, options).await;
+        }
+        let mut languages = LanguageResolver::new(options, to_bing);
+        let source = match from {
+            Some(lang) => languages.resolve(lang)?,
+            None => "auto-detect",
+        };
+        let target = languages.resolve(*to)?;
+
+        let request_id = options.resolve_request_id();
+        let span = tracing::debug_span!("translate", backend = "bing_web", request_id = request_id);
+        let mut result = options
+            .run_cancellable(
+                0,
+                self.call(
+                    whitespace::trim(query, options),
+                    source,
+                    target,
+                    request_id.as_deref(),
+                )
+                .instrument(span),
+            )
+            .await?;
+        let translation = result.translations.remove(0);
+        let text = postprocess::apply(query, translation.text, options);
+        let possibly_untranslated = untranslated::check(query, &text, from, to, options)?;
+        Ok(TranslationOutput {
+            text,
+            lang: Some(*to),
+            detail: TranslationDetail {
+                request_id,
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                lang_raw: translation.to,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl BingWebTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, Value)> {
+        let session = self.session().await?;
+        let request = HttpRequest::post(format!("{}/ttranslatev3", self.host))
+            .query(&[
+                ("isVertical", "1"),
+                ("IG", &session.ig),
+                ("IID", &session.iid),
+            ])?
+            .form(&[
+                ("fromLang", "auto-detect"),
+                ("to", "zh-Hans"),
+                ("text", "Hello, world"),
+                ("token", &session.token),
+                ("key", &session.key),
+            ])?;
+        let body = self.transport.execute(request).await?;
+        Ok(("success.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    let reply = parse_reply(serde_json::from_str(body)?);
+    match (name, reply) {
+        ("success.json", Ok(Reply::Translated(_))) => {}
+        ("token_expired.json", Ok(Reply::Expired)) => {}
+        ("captcha.json", Err(TranslatorError::RateLimited(_))) => {}
+        (_, reply) => anyhow::bail!("未知的响应样例或解析结果不符：{} {:?}", name, reply),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::bing_web_translator::{
+        between, from_bing, parse_reply, to_bing, BingWebTranslator, Reply, Session,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::test_support::http::{self, Request};
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 翻译页面
+    const PAGE: &str = include_str!("../../tests/fixtures/bing/translator.html");
+
+    /// 成功响应
+    const SUCCESS: &str = include_str!("../../tests/fixtures/bing/success.json");
+
+    /// 会话过期的响应
+    const TOKEN_EXPIRED: &str = include_str!("../../tests/fixtures/bing/token_expired.json");
+
+    /// 启动模拟Bing网站的本地服务器
+    ///
+    /// 翻译页面总是返回`PAGE`，翻译接口依次返回`replies`，用完后返回最后一项
+    ///
+    /// # 返回值
+    /// 请求本地服务器的翻译器和收到的请求
+    fn serve(replies: Vec<&'static str>) -> (BingWebTranslator, mpsc::Receiver<Request>) {
+        let (sender, receiver) = mpsc::channel();
+        let replies = Arc::new(Mutex::new(replies));
+        let url = http::serve(move |request| {
+            let _ = sender.send(request.clone());
+            match request.path.as_str() {
+                "/translator" => (200, PAGE.to_string()),
+                "/ttranslatev3" => {
+                    let mut replies = replies.lock().unwrap();
+                    let reply = if replies.len() > 1 {
+                        replies.remove(0)
+                    } else {
+                        replies[0]
+                    };
+                    (200, reply.to_string())
+                }
+                _ => (404, String::new()),
+            }
+        });
+        let mut translator = BingWebTranslator::new();
+        translator.host = url;
+        translator.politeness = None;
+        (translator, receiver)
+    }
+
+    /// 收到的请求路径
+    fn paths(requests: &mpsc::Receiver<Request>) -> Vec<String> {
+        requests.try_iter().map(|r| r.path).collect()
+    }
+
+    /// 测试语言代码映射
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(to_bing(&Language::Chinese), Some("zh-Hans"));
+        assert_eq!(to_bing(&Language::ChineseTraditional), Some("zh-Hant"));
+        assert_eq!(to_bing(&Language::Norwegian), Some("nb"));
+        assert_eq!(to_bing(&Language::Latin), None);
+        assert_eq!(from_bing("zh-Hans"), Some(Language::Chinese));
+        assert_eq!(from_bing("ZH-HANT"), Some(Language::ChineseTraditional));
+        assert_eq!(from_bing("tlh-Latn"), None);
+    }
+
+    /// 测试希伯来语、菲律宾语和挪威语发送的代码，以及新旧代码都能反查
+    #[test]
+    fn test_inconsistent_language_codes() {
+        let cases = [
+            (Language::Hebrew, "he", "iw"),
+            (Language::FilipinoPilipino, "fil", "tl"),
+            (Language::Norwegian, "nb", "no"),
+        ];
+        for (lang, code, legacy) in cases {
+            assert_eq!(to_bing(&lang), Some(code));
+            assert_eq!(from_bing(code), Some(lang));
+            assert_eq!(from_bing(legacy), Some(lang));
+        }
+        assert_eq!(to_bing(&Language::Javanese), None);
+    }
+
+    /// 测试从翻译页面解析会话参数
+    #[test]
+    fn test_parse_session() {
+        let now = Instant::now();
+        let session = Session::parse(PAGE, now).unwrap();
+        assert_eq!(session.ig, "4F2D6C0A8E1B4B7C9A3E5D7F1B2C4E6A");
+        assert_eq!(session.iid, "translator.5023");
+        assert_eq!(session.key, "1729238400000");
+        assert_eq!(session.token, "Xy7mR2kQ9vTbN3cLpA1sHd8fGjWzEu4o");
+        assert_eq!(session.expires_at, now + Duration::from_secs(3600));
+
+        let err = Session::parse("<html></html>", now).unwrap_err();
+        assert!(matches!(err, TranslatorError::Handshake(_)), "{:?}", err);
+        assert!(err.to_string().contains("IG"));
+    }
+
+    /// 测试解析成功、过期和需要验证码的响应
+    #[test]
+    fn test_parse_reply() {
+        let Reply::Translated(result) =
+            parse_reply(serde_json::from_str(SUCCESS).unwrap()).unwrap()
+        else {
+            panic!("expected a translation");
+        };
+        assert_eq!(result.translations[0].text, "你好，世界");
+        assert_eq!(result.translations[0].to.as_deref(), Some("zh-Hans"));
+        assert_eq!(result.detected_language.unwrap().language, "en");
+
+        let expired = parse_reply(serde_json::from_str(TOKEN_EXPIRED).unwrap()).unwrap();
+        assert!(matches!(expired, Reply::Expired));
+        let captcha = parse_reply(serde_json::json!({"ShowCaptcha": true})).unwrap_err();
+        assert!(matches!(captcha, TranslatorError::RateLimited(_)));
+        let failed = parse_reply(serde_json::json!({"statusCode": 400})).unwrap_err();
+        assert!(matches!(failed, TranslatorError::RequestFailed(400)));
+        assert!(parse_reply(serde_json::json!([])).is_err());
+    }
+
+    /// 测试首次翻译时握手，之后复用会话，请求参数来自会话
+    #[tokio::test]
+    async fn test_handshake_and_translate() {
+        let (translator, requests) = serve(vec![SUCCESS]);
+        let output = translator
+            .translate("Hello, world", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "你好，世界");
+        assert_eq!(output.lang, Some(Language::Chinese));
+        assert_eq!(output.detail.lang_raw.as_deref(), Some("zh-Hans"));
+
+        let handshake = requests.recv().unwrap();
+        assert_eq!(handshake.method, "GET");
+        assert_eq!(handshake.path, "/translator");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/ttranslatev3");
+        assert!(request
+            .query
+            .contains("IG=4F2D6C0A8E1B4B7C9A3E5D7F1B2C4E6A"));
+        assert!(request.query.contains("IID=translator.5023"));
+        assert_eq!(request.param("fromLang").as_deref(), Some("auto-detect"));
+        assert_eq!(request.param("to").as_deref(), Some("zh-Hans"));
+        assert_eq!(request.param("text").as_deref(), Some("Hello, world"));
+        assert_eq!(
+            request.param("token").as_deref(),
+            Some("Xy7mR2kQ9vTbN3cLpA1sHd8fGjWzEu4o")
+        );
+        assert_eq!(request.param("key").as_deref(), Some("1729238400000"));
+
+        translator
+            .translate("Hello, world", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(paths(&requests), ["/ttranslatev3"]);
+
+        // 会话到期后重新握手
+        translator
+            .session
+            .lock()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .expires_at = Instant::now();
+        translator
+            .translate("Hello, world", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(paths(&requests), ["/translator", "/ttranslatev3"]);
+    }
+
+    /// 测试会话中途过期时刷新一次后重试
+    #[tokio::test]
+    async fn test_token_expiry_refresh() {
+        let (translator, requests) = serve(vec![SUCCESS, TOKEN_EXPIRED, SUCCESS]);
+        for _ in 0..2 {
+            let output = translator
+                .translate("Hello, world", None, &Language::Chinese)
+                .await
+                .unwrap();
+            assert_eq!(output.text, "你好，世界");
+        }
+        assert_eq!(
+            paths(&requests),
+            [
+                "/translator",
+                "/ttranslatev3",
+                "/ttranslatev3",
+                "/translator",
+                "/ttranslatev3"
+            ]
+        );
+
+        // 刷新后仍然过期时不再重试
+        let (translator, requests) = serve(vec![TOKEN_EXPIRED]);
+        let err = translator
+            .translate("Hello, world", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Handshake(_))
+        ));
+        assert_eq!(paths(&requests).len(), 4);
+    }
+
+    /// 测试语言检测返回接口检测到的语言
+    #[tokio::test]
+    async fn test_detect_language() {
+        let (translator, requests) = serve(vec![SUCCESS]);
+        let detected = translator.detect_language("Hello, world").await.unwrap();
+        assert_eq!(detected, Some(Language::English));
+        let request = requests.try_iter().last().unwrap();
+        assert_eq!(request.param("to").as_deref(), Some("en"));
+        assert_eq!(translator.detect_language("  ").await.unwrap(), None);
+    }
+
+    /// 模糊测试：任意页面和响应的解析都不会panic
+    #[test]
+    fn test_fuzz_parse() {
+        let now = Instant::now();
+        for input in fuzz::inputs(701, &[PAGE]) {
+            let _ = Session::parse(&input, now);
+            between(&input, "IG:\"", "\"");
+        }
+        let corpus = [
+            SUCCESS,
+            TOKEN_EXPIRED,
+            include_str!("../../tests/fixtures/bing/captcha.json"),
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            if let Ok(resp) = serde_json::from_str(&input) {
+                let _ = parse_reply(resp);
+            }
+        }
+    }
+}
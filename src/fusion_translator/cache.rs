@@ -0,0 +1,1264 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::{plan_chunks, validate_batch, DEFAULT_CHUNK_SIZE};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::provenance::{Detailed, ProvenanceEvent};
+use crate::fusion_translator::translate_options::TranslateOptions;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+/// 缓存键
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    /// 源语言
+    from: Option<Language>,
+    /// 目标语言
+    to: Language,
+    /// 影响译文的调用选项
+    variant: String,
+    /// 原文
+    query: String,
+}
+
+/// 缓存条目
+#[derive(Debug)]
+struct CacheEntry {
+    /// 译文
+    text: String,
+    /// 译文语言
+    lang: Option<Language>,
+    /// 写入时使用的调用选项，只保留影响译文的字段，刷新过期条目时按此重新翻译
+    options: TranslateOptions,
+    /// 写入时刻
+    inserted: Instant,
+    /// 最近一次使用的序号
+    tick: u64,
+}
+
+/// 最近最少使用（LRU）的缓存存储
+#[derive(Debug, Default)]
+struct CacheStore {
+    /// 缓存键到条目的映射
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// 使用序号到缓存键的映射，序号最小的是最久未使用的条目
+    order: BTreeMap<u64, CacheKey>,
+    /// 下一个使用序号
+    next_tick: u64,
+}
+
+impl CacheStore {
+    /// 查询并刷新条目的使用顺序
+    ///
+    /// 过期的条目视为未命中，但保留到被新译文替换或淘汰，以便比较刷新前后的译文
+    ///
+    /// # 参数
+    /// - `key`: 缓存键
+    /// - `ttl`: 条目有效期，None表示不过期
+    fn get(&mut self, key: &CacheKey, ttl: Option<Duration>) -> Option<(String, Option<Language>)> {
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(key)?;
+        if ttl.is_some_and(|ttl| entry.inserted.elapsed() >= ttl) {
+            return None;
+        }
+        self.order.remove(&entry.tick);
+        entry.tick = tick;
+        self.order.insert(tick, key.clone());
+        self.next_tick += 1;
+        Some((entry.text.clone(), entry.lang))
+    }
+
+    /// 查询条目，包括已过期的条目，不改变使用顺序
+    ///
+    /// # 参数
+    /// - `key`: 缓存键
+    fn peek(&self, key: &CacheKey) -> Option<(String, Option<Language>)> {
+        let entry = self.entries.get(key)?;
+        Some((entry.text.clone(), entry.lang))
+    }
+
+    /// 写入条目，超出容量时淘汰最久未使用的条目
+    ///
+    /// # 参数
+    /// - `key`: 缓存键
+    /// - `text`: 译文
+    /// - `lang`: 译文语言
+    /// - `options`: 影响译文的调用选项
+    /// - `capacity`: 最多保存的条目数
+    ///
+    /// # 返回值
+    /// 被替换的旧译文
+    fn insert(
+        &mut self,
+        key: CacheKey,
+        text: String,
+        lang: Option<Language>,
+        options: TranslateOptions,
+        capacity: usize,
+    ) -> Option<String> {
+        if capacity == 0 {
+            return None;
+        }
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        let entry = CacheEntry {
+            text,
+            lang,
+            options,
+            inserted: Instant::now(),
+            tick,
+        };
+        let old = self.entries.insert(key.clone(), entry).map(|old| {
+            self.order.remove(&old.tick);
+            old.text
+        });
+        self.order.insert(tick, key);
+        while self.entries.len() > capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        old
+    }
+
+    /// 已过期的条目
+    ///
+    /// # 参数
+    /// - `ttl`: 条目有效期
+    ///
+    /// # 返回值
+    /// （缓存键，写入时的调用选项），按最近使用的先后排列
+    fn expired(&self, ttl: Duration) -> Vec<(CacheKey, TranslateOptions)> {
+        self.order
+            .values()
+            .rev()
+            .filter_map(|key| {
+                let entry = &self.entries[key];
+                (entry.inserted.elapsed() >= ttl).then(|| (key.clone(), entry.options.clone()))
+            })
+            .collect()
+    }
+}
+
+/// 缓存刷新后译文发生的变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranslationChange {
+    /// 原文
+    pub query: String,
+    /// 源语言，None表示自动检测
+    #[serde(serialize_with = "serialize_optional_language")]
+    pub from: Option<Language>,
+    /// 目标语言
+    #[serde(serialize_with = "serialize_language")]
+    pub to: Language,
+    /// 刷新前的译文
+    pub old_text: String,
+    /// 刷新后的译文
+    pub new_text: String,
+    /// 翻译器名称，见`CachedTranslator::with_backend_name`
+    pub backend: String,
+}
+
+/// 按`Debug`格式把语言序列化为字符串，与批量翻译检查点的格式相同
+fn serialize_language<S: serde::Serializer>(
+    lang: &Language,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:?}", lang))
+}
+
+/// 按`Debug`格式序列化可选的语言，None序列化为null
+fn serialize_optional_language<S: serde::Serializer>(
+    lang: &Option<Language>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match lang {
+        Some(lang) => serialize_language(lang, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 译文变化的监听器
+///
+/// 过期条目重新翻译后译文与缓存中的旧译文不同时调用，可用于审计缓存刷新。
+/// 内置`JsonlChangeLog`（写入JSONL文件）和`TracingChangeListener`（输出tracing事件）
+pub trait ChangeListener: Send + Sync {
+    /// 处理一次译文变化
+    ///
+    /// # 参数
+    /// - `change`: 译文变化
+    fn on_change(&self, change: &TranslationChange);
+}
+
+/// 把译文变化追加写入JSONL文件的监听器
+///
+/// 每行是一个JSON对象，包括`TranslationChange`的所有字段和记录时的Unix时间戳`timestamp`（秒）；
+/// 写入失败时输出警告，不影响翻译
+pub struct JsonlChangeLog {
+    /// 以追加模式打开的文件
+    file: Mutex<File>,
+}
+
+impl JsonlChangeLog {
+    /// 打开JSONL文件，文件不存在时创建
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+/// JSONL文件中的一行
+#[derive(Serialize)]
+struct ChangeRecord<'a> {
+    /// 记录时的Unix时间戳（秒）
+    timestamp: u64,
+    /// 译文变化
+    #[serde(flatten)]
+    change: &'a TranslationChange,
+}
+
+impl ChangeListener for JsonlChangeLog {
+    fn on_change(&self, change: &TranslationChange) {
+        let record = ChangeRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            change,
+        };
+        let result = serde_json::to_string(&record)
+            .map_err(std::io::Error::from)
+            .and_then(|line| writeln!(self.file.lock().unwrap(), "{}", line));
+        if let Err(err) = result {
+            tracing::warn!(%err, "failed to write translation change");
+        }
+    }
+}
+
+/// 把译文变化输出为tracing事件的监听器
+///
+/// 在`fusion_translator::cache`目标下输出INFO级别的事件，字段与`TranslationChange`相同
+#[derive(Debug, Default)]
+pub struct TracingChangeListener;
+
+impl ChangeListener for TracingChangeListener {
+    fn on_change(&self, change: &TranslationChange) {
+        tracing::info!(
+            target: "fusion_translator::cache",
+            backend = %change.backend,
+            query = %change.query,
+            from = ?change.from,
+            to = ?change.to,
+            old_text = %change.old_text,
+            new_text = %change.new_text,
+            "cached translation changed"
+        );
+    }
+}
+
+/// 语言检测缓存默认最多保存的条目数
+pub const DEFAULT_DETECTION_CAPACITY: usize = 4096;
+
+/// 语言检测缓存默认的条目有效期（7天）
+///
+/// 同一文本的检测结果基本不会变化，远长于译文缓存常用的有效期
+pub const DEFAULT_DETECTION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// 语言检测缓存条目
+#[derive(Debug)]
+struct DetectionEntry {
+    /// 检测到的语言
+    lang: Language,
+    /// 写入时刻
+    inserted: Instant,
+    /// 最近一次使用的序号
+    tick: u64,
+}
+
+/// 语言检测缓存的存储
+#[derive(Debug, Default)]
+struct DetectionStore {
+    /// 文本的SHA-256摘要到条目的映射
+    entries: HashMap<[u8; 32], DetectionEntry>,
+    /// 使用序号到摘要的映射，序号最小的是最久未使用的条目
+    order: BTreeMap<u64, [u8; 32]>,
+    /// 下一个使用序号
+    next_tick: u64,
+}
+
+/// 语言检测缓存的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DetectionCacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数（包括已过期的条目）
+    pub misses: u64,
+    /// 当前保存的条目数
+    pub entries: usize,
+}
+
+impl DetectionCacheStats {
+    /// 命中率，没有任何查询时为0
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// 语言检测结果的缓存
+///
+/// 用户名、界面文字等短文本常被反复检测，而同一文本的检测结果基本不变。
+/// 以文本的SHA-256摘要为键（长文本也只占固定的空间），与译文分开缓存，
+/// 容量较小、有效期较长，超出容量时淘汰最久未使用的条目。
+///
+/// 检测结果与翻译器关系不大，同一个缓存可以通过`CachedTranslator::with_detection_cache`
+/// 在多个翻译器之间共享。只缓存检测到的语言，无法判断（None）和出错的结果不会写入
+#[derive(Debug)]
+pub struct DetectionCache {
+    /// 最多保存的条目数
+    capacity: usize,
+    /// 条目有效期，None表示不过期
+    ttl: Option<Duration>,
+    /// 缓存存储
+    store: Mutex<DetectionStore>,
+    /// 统计信息，`entries`在读取时填写
+    stats: Mutex<DetectionCacheStats>,
+}
+
+impl Default for DetectionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DETECTION_CAPACITY)
+    }
+}
+
+impl DetectionCache {
+    /// 创建语言检测缓存，有效期为`DEFAULT_DETECTION_TTL`
+    ///
+    /// # 参数
+    /// - `capacity`: 最多保存的条目数，为0时不缓存
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl: Some(DEFAULT_DETECTION_TTL),
+            store: Mutex::new(DetectionStore::default()),
+            stats: Mutex::new(DetectionCacheStats::default()),
+        }
+    }
+
+    /// 设置条目有效期
+    ///
+    /// # 参数
+    /// - `ttl`: 写入后超过该时长的条目视为未命中，None表示不过期
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// 当前缓存的条目数（包括已过期但尚未被替换的条目）
+    pub fn len(&self) -> usize {
+        self.store.lock().unwrap().entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 清空缓存，不重置统计信息
+    pub fn clear(&self) {
+        *self.store.lock().unwrap() = DetectionStore::default();
+    }
+
+    /// 统计信息
+    pub fn stats(&self) -> DetectionCacheStats {
+        DetectionCacheStats {
+            entries: self.len(),
+            ..*self.stats.lock().unwrap()
+        }
+    }
+
+    /// 查询文本的检测结果并刷新条目的使用顺序，同时计入命中或未命中
+    ///
+    /// # 参数
+    /// - `text`: 待检测的文本
+    ///
+    /// # 返回值
+    /// 缓存的语言，没有条目或条目已过期时为None
+    pub fn get(&self, text: &str) -> Option<Language> {
+        let key = Self::key(text);
+        let lang = {
+            let mut store = self.store.lock().unwrap();
+            let tick = store.next_tick;
+            match store.entries.get_mut(&key) {
+                Some(entry) if self.ttl.is_none_or(|ttl| entry.inserted.elapsed() < ttl) => {
+                    let old = std::mem::replace(&mut entry.tick, tick);
+                    let lang = entry.lang;
+                    store.order.remove(&old);
+                    store.order.insert(tick, key);
+                    store.next_tick += 1;
+                    Some(lang)
+                }
+                _ => None,
+            }
+        };
+        let mut stats = self.stats.lock().unwrap();
+        match lang {
+            Some(_) => stats.hits += 1,
+            None => stats.misses += 1,
+        }
+        lang
+    }
+
+    /// 写入文本的检测结果，超出容量时淘汰最久未使用的条目
+    ///
+    /// # 参数
+    /// - `text`: 检测的文本
+    /// - `lang`: 检测到的语言
+    pub fn insert(&self, text: &str, lang: Language) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = Self::key(text);
+        let mut store = self.store.lock().unwrap();
+        let tick = store.next_tick;
+        store.next_tick += 1;
+        let entry = DetectionEntry {
+            lang,
+            inserted: Instant::now(),
+            tick,
+        };
+        if let Some(old) = store.entries.insert(key, entry) {
+            store.order.remove(&old.tick);
+        }
+        store.order.insert(tick, key);
+        while store.entries.len() > self.capacity {
+            let Some((_, oldest)) = store.order.pop_first() else {
+                break;
+            };
+            store.entries.remove(&oldest);
+        }
+    }
+
+    /// 缓存键：文本的SHA-256摘要
+    ///
+    /// # 参数
+    /// - `text`: 文本
+    fn key(text: &str) -> [u8; 32] {
+        Sha256::digest(text.as_bytes()).into()
+    }
+}
+
+/// 缓存翻译结果的翻译器
+///
+/// 以（源语言，目标语言，影响译文的调用选项，原文）为键，在进程内缓存内部翻译器的译文，
+/// 超出容量时淘汰最久未使用的条目。源语言为None（自动检测）的调用同样缓存，
+/// 疑似未翻译的结果不会写入缓存。
+/// 过期条目重新翻译（访问时或通过`refresh_expired`）后译文变化时通知`with_change_listener`设置的监听器
+pub struct CachedTranslator {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 最多保存的条目数
+    capacity: usize,
+    /// 条目有效期，None表示不过期
+    ttl: Option<Duration>,
+    /// 缓存存储
+    store: Mutex<CacheStore>,
+    /// 译文变化时通知的监听器
+    listener: Option<Arc<dyn ChangeListener>>,
+    /// 译文变化中记录的翻译器名称
+    backend: String,
+    /// 语言检测结果的缓存，None表示不缓存检测结果
+    detection: Option<Arc<DetectionCache>>,
+}
+
+impl CachedTranslator {
+    /// 创建缓存翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `capacity`: 最多保存的条目数，为0时不缓存
+    pub fn new(inner: Arc<dyn AsyncTranslator>, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            ttl: None,
+            store: Mutex::new(CacheStore::default()),
+            listener: None,
+            backend: String::new(),
+            detection: None,
+        }
+    }
+
+    /// 设置条目有效期
+    ///
+    /// # 参数
+    /// - `ttl`: 写入后超过该时长的条目视为未命中
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// 设置译文变化的监听器
+    ///
+    /// # 参数
+    /// - `listener`: 过期条目重新翻译后译文与旧译文不同时调用
+    pub fn with_change_listener(mut self, listener: Arc<dyn ChangeListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// 设置译文变化中记录的翻译器名称
+    ///
+    /// 默认为空字符串，通过`TranslatorFactory::wrap_stack`创建时为`TranslatorType::as_str`
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器名称
+    pub fn with_backend_name(mut self, backend: &str) -> Self {
+        self.backend = backend.to_string();
+        self
+    }
+
+    /// 设置语言检测结果的缓存
+    ///
+    /// 检测结果与译文分开保存，互不占用容量，也不受`with_ttl`和`clear`影响
+    ///
+    /// # 参数
+    /// - `cache`: 检测缓存，可以在多个翻译器之间共享
+    pub fn with_detection_cache(mut self, cache: Arc<DetectionCache>) -> Self {
+        self.detection = Some(cache);
+        self
+    }
+
+    /// 语言检测结果的缓存
+    pub fn detection_cache(&self) -> Option<&Arc<DetectionCache>> {
+        self.detection.as_ref()
+    }
+
+    /// 当前缓存的条目数（包括已过期但尚未被替换的条目）
+    pub fn len(&self) -> usize {
+        self.store.lock().unwrap().entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        *self.store.lock().unwrap() = CacheStore::default();
+    }
+
+    /// 查询缓存中的译文，包括已过期但尚未被替换或淘汰的条目
+    ///
+    /// 不调用内部翻译器，也不改变条目的使用顺序，用于请求超时或失败后返回旧译文
+    ///
+    /// # 参数
+    /// - `query`: 原文
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 调用选项，影响译文的字段需与写入时相同
+    ///
+    /// # 返回值
+    /// 缓存的译文和语言，没有条目时为None
+    pub fn get_stale(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> Option<TranslationOutput> {
+        let key = Self::key(query, from, to, options);
+        let (text, lang) = self.store.lock().unwrap().peek(&key)?;
+        Some(TranslationOutput {
+            text,
+            lang,
+            ..Default::default()
+        })
+    }
+
+    /// 重新翻译所有过期的条目
+    ///
+    /// 按源语言、目标语言和写入时的调用选项分组，再按`translator`的批量上限（未设置时为`DEFAULT_CHUNK_SIZE`）
+    /// 分批调用`translate_vec_with_options`，译文变化时通知监听器。每批是一次调用，
+    /// 需要限流时传入`RateLimitedTranslator`。未设置有效期时不做任何事
+    ///
+    /// # 参数
+    /// - `translator`: 用于重新翻译的翻译器，通常是本缓存的内部翻译器加上限流
+    ///
+    /// # 返回值
+    /// 重新翻译的条目数；任一批失败时返回错误，之前的批次已写入缓存
+    pub async fn refresh_expired(&self, translator: &dyn AsyncTranslator) -> anyhow::Result<usize> {
+        let Some(ttl) = self.ttl else {
+            return Ok(0);
+        };
+        let expired = self.store.lock().unwrap().expired(ttl);
+        let mut groups: Vec<(CacheKey, TranslateOptions, Vec<String>)> = Vec::new();
+        for (key, options) in expired {
+            let group = groups.iter_mut().find(|(first, _, _)| {
+                first.from == key.from && first.to == key.to && first.variant == key.variant
+            });
+            match group {
+                Some((_, _, queries)) => queries.push(key.query),
+                None => {
+                    let query = key.query.clone();
+                    groups.push((key, options, vec![query]));
+                }
+            }
+        }
+
+        let mut refreshed = 0;
+        for (first, options, queries) in groups {
+            let max_batch_len = translator.max_batch_len().or(Some(DEFAULT_CHUNK_SIZE));
+            for chunk in plan_chunks(&queries, max_batch_len, translator.input_limit(), 0) {
+                let chunk = &queries[chunk];
+                let output = translator
+                    .translate_vec_with_options(chunk, first.from, &first.to, &options)
+                    .await?;
+                let texts = validate_batch(chunk.len(), output.text)?;
+                if !output.detail.possibly_untranslated {
+                    let entries = chunk.iter().zip(texts).map(|(query, text)| {
+                        (Self::key(query, first.from, &first.to, &options), text)
+                    });
+                    self.store_all(entries, output.lang, &options);
+                }
+                refreshed += chunk.len();
+            }
+        }
+        Ok(refreshed)
+    }
+
+    /// 写入译文，替换的旧译文与新译文不同时通知监听器
+    ///
+    /// # 参数
+    /// - `entries`: （缓存键，译文）
+    /// - `lang`: 译文语言
+    /// - `options`: 本次调用的选项
+    fn store_all(
+        &self,
+        entries: impl IntoIterator<Item = (CacheKey, String)>,
+        lang: Option<Language>,
+        options: &TranslateOptions,
+    ) {
+        let options = Self::cached_options(options);
+        let mut changes = Vec::new();
+        {
+            let mut store = self.store.lock().unwrap();
+            for (key, text) in entries {
+                let change = self.listener.as_ref().map(|_| TranslationChange {
+                    query: key.query.clone(),
+                    from: key.from,
+                    to: key.to,
+                    old_text: String::new(),
+                    new_text: text.clone(),
+                    backend: self.backend.clone(),
+                });
+                let old = store.insert(key, text, lang, options.clone(), self.capacity);
+                if let (Some(mut change), Some(old)) = (change, old) {
+                    if old != change.new_text {
+                        change.old_text = old;
+                        changes.push(change);
+                    }
+                }
+            }
+        }
+        if let Some(listener) = &self.listener {
+            for change in &changes {
+                listener.on_change(change);
+            }
+        }
+    }
+
+    /// 缓存条目保存的调用选项
+    ///
+    /// 只保留缓存键中影响译文的字段，请求ID和取消令牌等不会保存
+    ///
+    /// # 参数
+    /// - `options`: 本次调用的选项
+    fn cached_options(options: &TranslateOptions) -> TranslateOptions {
+        TranslateOptions {
+            raw_output: options.raw_output,
+            postprocess: options.postprocess,
+            preserve_boundary_whitespace: options.preserve_boundary_whitespace,
+            language_fallback: options.language_fallback,
+            allow_script_fallback: options.allow_script_fallback,
+            normalize_input: options.normalize_input,
+            domain: options.domain,
+            experiment_arm: options.experiment_arm,
+            protect_literals: options.protect_literals,
+            emoji_policy: options.emoji_policy,
+            ..Default::default()
+        }
+    }
+
+    /// 生成缓存键
+    ///
+    /// # 参数
+    /// - `query`: 原文
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    fn key(
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> CacheKey {
+        CacheKey {
+            from,
+            to: *to,
+            variant: format!(
+                "{}:{:?}:{}:{}:{}:{:?}:{:?}:{:?}:{:?}:{:?}",
+                options.raw_output,
+                options.postprocess,
+                options.preserve_boundary_whitespace,
+                options.language_fallback,
+                options.allow_script_fallback,
+                options.normalize_input,
+                options.domain,
+                options.experiment_arm,
+                options.protect_literals,
+                options.emoji_policy
+            ),
+            query: query.to_string(),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for CachedTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 设置了`with_detection_cache`时先查询检测缓存，未命中时交给内部翻译器并缓存检测到的语言；
+    /// 检测结果不经过译文缓存
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let Some(cache) = &self.detection else {
+            return self.inner.detect_language(text).await;
+        };
+        if let Some(lang) = cache.get(text) {
+            return Ok(Some(lang));
+        }
+        let lang = self.inner.detect_language(text).await?;
+        if let Some(lang) = lang {
+            cache.insert(text, lang);
+        }
+        Ok(lang)
+    }
+
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 命中缓存时只返回译文和语言，候选译文等附加信息不会缓存。
+    /// 结果的来历追加`ProvenanceEvent::Cache`，命中时不包含第一次翻译时的来历
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let key = Self::key(query, from, to, options);
+        if let Some((text, lang)) = self.store.lock().unwrap().get(&key, self.ttl) {
+            let mut output = TranslationOutput {
+                text,
+                lang,
+                ..Default::default()
+            };
+            output.detail.request_id = options.resolve_request_id();
+            output.trace(ProvenanceEvent::Cache { hits: 1, misses: 0 });
+            return Ok(output);
+        }
+        let mut output = self
+            .inner
+            .translate_with_options(query, from, to, options)
+            .await?;
+        if !output.detail.possibly_untranslated {
+            self.store_all([(key, output.text.clone())], output.lang, options);
+        }
+        output.trace(ProvenanceEvent::Cache { hits: 0, misses: 1 });
+        Ok(output)
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 只把缓存中没有的文本发送给内部翻译器，空数组直接交给内部翻译器，保持与不加缓存时相同的结果。
+    /// 结果的来历追加`ProvenanceEvent::Cache`，记录命中和未命中的文本数
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return self
+                .inner
+                .translate_vec_with_options(query, from, to, options)
+                .await;
+        }
+        let keys = query
+            .iter()
+            .map(|q| Self::key(q, from, to, options))
+            .collect::<Vec<_>>();
+        let mut lang = None;
+        let mut texts = {
+            let mut store = self.store.lock().unwrap();
+            keys.iter()
+                .map(|key| {
+                    let (text, hit_lang) = store.get(key, self.ttl)?;
+                    lang = lang.or(hit_lang);
+                    Some(text)
+                })
+                .collect::<Vec<_>>()
+        };
+        let misses = texts
+            .iter()
+            .zip(query)
+            .filter(|(text, _)| text.is_none())
+            .map(|(_, q)| q.clone())
+            .collect::<Vec<_>>();
+
+        let mut output = TranslationListOutput {
+            lang,
+            ..Default::default()
+        };
+        output.detail.request_id = options.resolve_request_id();
+        if !misses.is_empty() {
+            let translated = self
+                .inner
+                .translate_vec_with_options(&misses, from, to, options)
+                .await?;
+            let translated_texts = validate_batch(misses.len(), translated.text)?;
+            output.lang = translated.lang.or(output.lang);
+            output.detail = translated.detail;
+            if !output.detail.possibly_untranslated {
+                let miss_keys = keys
+                    .into_iter()
+                    .zip(&texts)
+                    .filter(|(_, text)| text.is_none())
+                    .map(|(key, _)| key);
+                self.store_all(
+                    miss_keys.zip(translated_texts.iter().cloned()),
+                    output.lang,
+                    options,
+                );
+            }
+            let mut translated_texts = translated_texts.into_iter();
+            for text in texts.iter_mut().filter(|t| t.is_none()) {
+                *text = translated_texts.next();
+            }
+        }
+        output.text = texts.into_iter().map(Option::unwrap_or_default).collect();
+        output.trace(ProvenanceEvent::Cache {
+            hits: query.len() - misses.len(),
+            misses: misses.len(),
+        });
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::cache::{
+        CachedTranslator, ChangeListener, DetectionCache, DetectionCacheStats, JsonlChangeLog,
+        TranslationChange,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::rate_limit::RateLimitedTranslator;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// 记录所有译文变化的监听器
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<TranslationChange>>);
+
+    impl ChangeListener for Recorder {
+        fn on_change(&self, change: &TranslationChange) {
+            self.0.lock().unwrap().push(change.clone());
+        }
+    }
+
+    /// 测试命中缓存时不调用内部翻译器，批量翻译只发送未命中的文本
+    #[tokio::test]
+    async fn test_cache_hits() {
+        let mock = Arc::new(MockTranslator::new());
+        let translator = CachedTranslator::new(mock.clone(), 10);
+        let first = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        let second = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(first.text, second.text);
+        assert_eq!(mock.calls(), 1);
+
+        let query = ["hello", "world"].map(String::from);
+        let output = translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["HELLO", "WORLD"]);
+        assert_eq!(mock.requests().last().unwrap(), &vec!["world".to_string()]);
+        // 目标语言不同时不命中
+        translator
+            .translate("hello", None, &Language::Japanese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 3);
+    }
+
+    /// 测试共享的检测缓存：重复检测同一文本只调用一次内部翻译器，且与译文缓存互不影响
+    #[tokio::test]
+    async fn test_detection_cache() {
+        let mock = Arc::new(MockTranslator::new().with_detection(Language::English));
+        let detection = Arc::new(DetectionCache::default());
+        let first = CachedTranslator::new(mock.clone(), 10).with_detection_cache(detection.clone());
+        let second =
+            CachedTranslator::new(mock.clone(), 10).with_detection_cache(detection.clone());
+        for translator in [&first, &second, &first] {
+            let lang = translator.detect_language("alice").await.unwrap();
+            assert_eq!(lang, Some(Language::English));
+        }
+        assert_eq!(mock.calls(), 1);
+        let stats = detection.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 1, 1));
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        // 检测过的文本在译文缓存中不命中，翻译也不写入检测缓存
+        first
+            .translate("alice", None, &Language::Chinese)
+            .await
+            .unwrap();
+        first
+            .translate("bob", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 3);
+        assert_eq!((first.len(), detection.len()), (2, 1));
+        first.clear();
+        first.detect_language("alice").await.unwrap();
+        assert_eq!(mock.calls(), 3);
+        assert_eq!(detection.stats().hits, 3);
+        first.detect_language("bob").await.unwrap();
+        assert_eq!(mock.calls(), 4);
+
+        // 没有检测缓存时每次都调用内部翻译器
+        let uncached = CachedTranslator::new(mock.clone(), 10);
+        uncached.detect_language("alice").await.unwrap();
+        assert_eq!(mock.calls(), 5);
+    }
+
+    /// 测试检测缓存的容量和有效期，无法判断的结果不写入
+    #[tokio::test(start_paused = true)]
+    async fn test_detection_cache_eviction() {
+        let cache = DetectionCache::new(2).with_ttl(Some(Duration::from_secs(60)));
+        cache.insert("a", Language::English);
+        cache.insert("b", Language::French);
+        assert_eq!(cache.get("a"), Some(Language::English));
+        cache.insert("c", Language::German);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(Language::German));
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(
+            cache.stats(),
+            DetectionCacheStats {
+                hits: 2,
+                misses: 2,
+                entries: 2,
+            }
+        );
+
+        let translator = CachedTranslator::new(Arc::new(MockTranslator::new()), 10)
+            .with_detection_cache(Arc::new(DetectionCache::default()));
+        assert_eq!(translator.detect_language("a").await.unwrap(), None);
+        assert!(translator.detection_cache().unwrap().is_empty());
+    }
+
+    /// 测试超出容量时淘汰最久未使用的条目
+    #[tokio::test]
+    async fn test_lru_eviction() {
+        let mock = Arc::new(MockTranslator::new());
+        let translator = CachedTranslator::new(mock.clone(), 2);
+        for text in ["a", "b", "a", "c"] {
+            translator
+                .translate(text, None, &Language::Chinese)
+                .await
+                .unwrap();
+        }
+        assert_eq!(translator.len(), 2);
+        assert_eq!(mock.calls(), 3);
+        // `b`最久未使用，已被淘汰
+        translator
+            .translate("a", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 3);
+        translator
+            .translate("b", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 4);
+    }
+
+    /// 测试条目过期后重新翻译
+    #[tokio::test(start_paused = true)]
+    async fn test_ttl() {
+        let mock = Arc::new(MockTranslator::new());
+        let translator = CachedTranslator::new(mock.clone(), 10).with_ttl(Duration::from_secs(60));
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(59)).await;
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 1);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 2);
+    }
+
+    /// 测试刷新过期条目时按批量上限分批重新翻译，只有译文变化的条目通知监听器
+    #[tokio::test(start_paused = true)]
+    async fn test_refresh_expired() {
+        let recorder = Arc::new(Recorder::default());
+        let translator = CachedTranslator::new(Arc::new(MockTranslator::new()), 10)
+            .with_ttl(Duration::from_secs(60))
+            .with_backend_name("mock")
+            .with_change_listener(recorder.clone());
+        let query = ["a", "b", "c"].map(String::from);
+        translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        translator
+            .translate("d", Some(Language::English), &Language::Japanese)
+            .await
+            .unwrap();
+
+        let updated = Arc::new(MockTranslator::new().with_limits(Some(2), None));
+        assert_eq!(
+            translator.refresh_expired(updated.as_ref()).await.unwrap(),
+            0
+        );
+        tokio::time::advance(Duration::from_secs(30)).await;
+        translator
+            .translate("e", None, &Language::Chinese)
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(30)).await;
+
+        // 只有前4个条目过期，语言和调用选项相同的分为一组，再按2个一批请求
+        let updated = Arc::new(
+            MockTranslator::new()
+                .with_suffix("!")
+                .with_limits(Some(2), None),
+        );
+        let limited = RateLimitedTranslator::new(updated.clone(), 10.0);
+        assert_eq!(translator.refresh_expired(&limited).await.unwrap(), 4);
+        assert_eq!(updated.calls(), 3);
+        // 最近使用的条目先刷新
+        assert_eq!(updated.requests(), [vec!["d"], vec!["c", "b"], vec!["a"]]);
+
+        let mut changes = recorder.0.lock().unwrap().clone();
+        changes.sort_by(|a, b| a.query.cmp(&b.query));
+        assert_eq!(changes.len(), 4);
+        assert_eq!(
+            changes[3],
+            TranslationChange {
+                query: "d".to_string(),
+                from: Some(Language::English),
+                to: Language::Japanese,
+                old_text: "D".to_string(),
+                new_text: "D!".to_string(),
+                backend: "mock".to_string(),
+            }
+        );
+        // 刷新后的译文已写入缓存，译文不变的刷新不通知
+        let output = translator
+            .translate("a", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "A!");
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(translator.refresh_expired(&limited).await.unwrap(), 5);
+        let changes = recorder.0.lock().unwrap();
+        assert_eq!(changes.len(), 5);
+        assert_eq!(changes[4].query, "e");
+    }
+
+    /// 测试访问过期条目重新翻译时同样比较译文，并以JSONL格式追加记录
+    #[tokio::test(start_paused = true)]
+    async fn test_jsonl_change_log() {
+        let path = std::env::temp_dir().join(format!(
+            "fusion-translator-changes-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let log = Arc::new(JsonlChangeLog::open(&path).unwrap());
+        let translator = CachedTranslator::new(Arc::new(MockTranslator::new()), 10)
+            .with_ttl(Duration::from_secs(60))
+            .with_backend_name("mock")
+            .with_change_listener(log.clone());
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(60)).await;
+        // 内部翻译器的译文不变，不记录
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(60)).await;
+        let updated = MockTranslator::new().with_suffix(" v2");
+        translator.refresh_expired(&updated).await.unwrap();
+        let reopened = JsonlChangeLog::open(&path).unwrap();
+        tokio::time::advance(Duration::from_secs(60)).await;
+        translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        drop(reopened);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines = content
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["query"], "hello");
+        assert_eq!(lines[0]["from"], serde_json::Value::Null);
+        assert_eq!(lines[0]["to"], "Chinese");
+        assert_eq!(lines[0]["old_text"], "HELLO");
+        assert_eq!(lines[0]["new_text"], "HELLO v2");
+        assert_eq!(lines[0]["backend"], "mock");
+        assert!(lines[0]["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(lines[1]["old_text"], "HELLO v2");
+        assert_eq!(lines[1]["new_text"], "HELLO");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
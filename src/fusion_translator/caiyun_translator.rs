@@ -1,9 +1,89 @@
 use crate::fusion_translator::async_translator::{
-    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
 };
+use crate::fusion_translator::batch::{self, empty_batch, validate_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
 use crate::fusion_translator::translator_error::TranslatorError;
-use reqwest::Client;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// 单次批量请求最多的文本数量
+///
+/// 接口文档未给出上限，这里保守取值
+const MAX_BATCH_LEN: usize = 50;
+
+/// 单次请求`source`数组序列化为JSON后最多的字节数
+///
+/// 超出时接口拒绝整个请求，接口文档未给出上限，这里保守取值
+const MAX_PAYLOAD_BYTES: usize = 5000;
+
+/// 支持专业领域模型的语言组合
+const DOMAIN_TRANS_TYPES: [&str; 2] = ["zh2en", "en2zh"];
+
+/// 彩云翻译的专业领域
+///
+/// 专业领域模型通过`trans_type`后缀选择（如`en2zh_medicine`），只支持中英互译，并且需要明确指定源语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaiyunDomain {
+    /// 通用模型，支持所有语言组合
+    #[default]
+    General,
+    /// 医学
+    Medicine,
+    /// 法律
+    Law,
+    /// 金融
+    Finance,
+}
+
+impl CaiyunDomain {
+    /// `trans_type`的领域后缀，通用模型没有后缀
+    pub fn suffix(&self) -> Option<&'static str> {
+        match self {
+            CaiyunDomain::General => None,
+            CaiyunDomain::Medicine => Some("medicine"),
+            CaiyunDomain::Law => Some("law"),
+            CaiyunDomain::Finance => Some("finance"),
+        }
+    }
+
+    /// 生成`trans_type`
+    ///
+    /// # 参数
+    /// - `from`: 源语言代码，自动检测为`auto`
+    /// - `to`: 目标语言代码
+    ///
+    /// # 返回值
+    /// `trans_type`字符串；专业领域不支持该语言组合时返回`TranslatorError::UnsupportedDomain`，
+    /// 其中列出支持的组合
+    pub fn trans_type(&self, from: &str, to: &str) -> Result<String, TranslatorError> {
+        let pair = format!("{}2{}", from, to);
+        match self.suffix() {
+            None => Ok(pair),
+            Some(suffix) if DOMAIN_TRANS_TYPES.contains(&pair.as_str()) => {
+                Ok(format!("{}_{}", pair, suffix))
+            }
+            Some(suffix) => Err(TranslatorError::UnsupportedDomain {
+                domain: suffix.to_string(),
+                trans_type: pair,
+                supported: DOMAIN_TRANS_TYPES.to_vec(),
+            }),
+        }
+    }
+}
 
 /// 彩云翻译器API请求结构
 ///
@@ -36,15 +116,20 @@ struct CaiyunResponse {
 ///
 /// 通过调用彩云科技翻译API实现文本翻译功能
 pub struct CaiyunTranslator {
-    /// HTTP客户端
-    client: Client,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
     /// API访问令牌
     token: String,
     /// 请求ID
     request_id: String,
+    /// 专业领域
+    domain: CaiyunDomain,
+    /// API请求地址
+    url: String,
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 impl AsyncTranslator for CaiyunTranslator {
     /// 判断是否为本地翻译器
     ///
@@ -53,6 +138,42 @@ impl AsyncTranslator for CaiyunTranslator {
         false
     }
 
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "caiyun", &self.url).await
+    }
+
+    /// 支持的语言
+    ///
+    /// 专业领域只支持`DOMAIN_TRANS_TYPES`中的中英互译
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        let general = self.domain.suffix().is_none();
+        Some(languages_with(|lang| {
+            lang.to_caiyun().filter(|code| {
+                general
+                    || DOMAIN_TRANS_TYPES
+                        .iter()
+                        .any(|pair| pair.split('2').any(|c| c == *code))
+            })
+        }))
+    }
+
+    /// 按请求量计费
+    fn paid(&self) -> bool {
+        true
+    }
+
+    /// 单次批量请求最多的文本数量
+    fn max_batch_len(&self) -> Option<usize> {
+        Some(MAX_BATCH_LEN)
+    }
+
+    /// 单次请求最多的字节数
+    ///
+    /// 按`source`数组序列化后的大小计算，包括引号、逗号和转义字符
+    fn input_limit(&self) -> Option<usize> {
+        Some(MAX_PAYLOAD_BYTES)
+    }
+
     /// 翻译单个文本
     ///
     /// # 参数
@@ -68,90 +189,318 @@ impl AsyncTranslator for CaiyunTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationOutput> {
-        let mut v = self.translate_vec(&[query.to_owned()], from, to).await?;
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let mut v = self
+            .translate_vec_with_options(&[query.to_owned()], from, to, options)
+            .await?;
         Ok(TranslationOutput {
             text: v.text.remove(0),
             lang: Some(*to),
+            detail: v.detail,
         })
     }
 
-    /// 翻译多个文本
+    /// 按指定选项翻译多个文本
+    ///
+    /// 选项中的请求ID会覆盖构造时指定的默认请求ID。文本数量或序列化后的大小超出单次请求上限时，
+    /// 拆分为多次顺序请求后按原顺序拼接译文，任一请求返回的译文数量不一致时返回`TranslatorError::BatchSizeMismatch`
     ///
     /// # 参数
     /// - `query`: 待翻译的文本数组
     /// - `from`: 源语言，None表示自动检测
     /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
     ///
     /// # 返回值
     /// 翻译结果数组
-    async fn translate_vec(
+    async fn translate_vec_with_options(
         &self,
         query: &[String],
         from: Option<Language>,
         to: &Language,
+        options: &TranslateOptions,
     ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let chunks = batch::plan_chunks_by(
+            query,
+            self.max_batch_len(),
+            self.input_limit().map(|limit| limit - 2),
+            1,
+            json_len,
+        );
+        let result = batch::translate_planned(query, chunks, |chunk| {
+            self.translate_chunk(chunk, from, to, &options)
+        })
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("caiyun", query, from, to, &options)
+        })
+    }
+}
+
+/// 文本序列化为JSON字符串后的字节数，包括两端的引号
+fn json_len(text: &str) -> usize {
+    serde_json::to_string(text).map_or(text.len(), |json| json.len())
+}
+
+impl CaiyunTranslator {
+    /// 创建新的彩云翻译器实例
+    ///
+    /// # 参数
+    /// - `token`: 彩云API访问令牌
+    /// - `request_id`: 请求ID
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new(token: &str, request_id: &str) -> Self {
+        Self {
+            transport: TranslatorOptions::default().build_transport(TranslatorType::Caiyun),
+            token: token.to_string(),
+            request_id: request_id.to_string(),
+            domain: CaiyunDomain::default(),
+            url: "https://api.interpreter.caiyunai.com/v1/translator".to_string(),
+        }
+    }
+
+    /// 设置专业领域
+    ///
+    /// 专业领域只支持中英互译，其他语言组合或未指定源语言时，翻译前返回`TranslatorError::UnsupportedDomain`
+    ///
+    /// # 参数
+    /// - `domain`: 专业领域
+    pub fn with_domain(mut self, domain: CaiyunDomain) -> Self {
+        self.domain = domain;
+        self
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::Caiyun);
+        self
+    }
+
+    /// 构造API请求体
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `request_id`: 本次调用的请求ID，为None时使用构造时指定的请求ID
+    /// - `languages`: 语言代码解析器
+    ///
+    /// # 返回值
+    /// 请求体；专业领域不支持该语言组合时返回`TranslatorError::UnsupportedDomain`
+    fn build_request<'a>(
+        &'a self,
+        query: &'a [String],
+        from: Option<Language>,
+        to: &Language,
+        request_id: Option<&'a str>,
+        languages: &mut LanguageResolver,
+    ) -> Result<CaiyunRequest<'a>, TranslatorError> {
         let f = from;
         let from = match from {
-            Some(from) => from
-                .to_caiyun()
-                .ok_or(TranslatorError::UnknownLanguage(from))?,
+            Some(from) => languages.resolve(from)?,
             None => "auto",
         };
 
-        let trans_type = format!(
-            "{}2{}",
-            from,
-            to.to_caiyun()
-                .ok_or(TranslatorError::UnknownLanguage(*to))?
-        );
+        let trans_type = self.domain.trans_type(from, languages.resolve(*to)?)?;
 
-        let request = CaiyunRequest {
+        Ok(CaiyunRequest {
             trans_type,
             source: query,
             detect: if f.is_none() { Some(true) } else { None },
-            request_id: &self.request_id,
-        };
+            request_id: request_id.unwrap_or(&self.request_id),
+        })
+    }
 
-        let data: CaiyunResponse = self
-            .client
-            .post("https://api.interpreter.caiyunai.com/v1/translator")
-            .header("content-type", "application/json")
-            .header("x-authorization", format!("token {}", self.token))
-            .json(&request)
-            .send()
-            .await?
-            .json()
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，数量和长度不超过翻译器的批量上限
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return Ok(empty_batch(options));
+        }
+        let sent = whitespace::trim_all(query, options);
+        let payload = json_len(&sent[0]) + 2;
+        // 只有单个文本超出上限时才会出现，4字节以内的文本无法再拆分，原样发送
+        if sent.len() == 1 && payload > MAX_PAYLOAD_BYTES && sent[0].len() > 4 {
+            if !options.auto_split {
+                return Err(TranslatorError::RequestTooLong(
+                    payload as u32,
+                    MAX_PAYLOAD_BYTES as u32,
+                )
+                .into());
+            }
+            // 按转义后的膨胀比例估算片段的字节数，仍然超长的片段在递归调用中继续拆分
+            let text = &sent[0];
+            let max_bytes = (text.len() * MAX_PAYLOAD_BYTES / payload).min(text.len() - 1);
+            let output =
+                long_text::translate_split(self, &query[0], from, to, options, max_bytes).await?;
+            return Ok(TranslationListOutput {
+                text: vec![output.text],
+                lang: None,
+                detail: output.detail,
+            });
+        }
+        let request_id = options.resolve_request_id();
+        let mut languages = LanguageResolver::new(options, Language::to_caiyun);
+        let request = self.build_request(&sent, from, to, request_id.as_deref(), &mut languages)?;
+        let span = tracing::debug_span!(
+            "translate",
+            backend = "caiyun",
+            request_id = request.request_id
+        );
+
+        let data: CaiyunResponse = options
+            .run_cancellable(
+                0,
+                async {
+                    let http_request =
+                        with_request_id_header(HttpRequest::post(&self.url), request_id.as_deref())
+                            .header("x-authorization", format!("token {}", self.token))
+                            .json(&request)?;
+                    let response = self.transport.execute(http_request).await?;
+                    if !response.is_success() {
+                        return Err(TranslatorError::RequestFailed(response.status).into());
+                    }
+                    response.json()
+                }
+                .instrument(span),
+            )
             .await?;
 
+        let text = postprocess::apply_all(
+            query,
+            validate_batch(query.len(), data.target.unwrap_or_default())?,
+            options,
+        );
+        let possibly_untranslated = untranslated::check_all(query, &text, from, to, options)?;
+
         Ok(TranslationListOutput {
-            text: data.target.unwrap_or_default(),
+            text,
             lang: None,
+            detail: TranslationDetail {
+                request_id: Some(request.request_id.to_string()),
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                ..Default::default()
+            },
         })
     }
 }
 
+#[cfg(all(test, feature = "live-schema-check"))]
 impl CaiyunTranslator {
-    /// 创建新的彩云翻译器实例
-    ///
-    /// # 参数
-    /// - `token`: 彩云API访问令牌
-    /// - `request_id`: 请求ID
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
     ///
     /// # 返回值
-    /// 新的翻译器实例
-    pub fn new(token: &str, request_id: &str) -> Self {
-        Self {
-            client: Client::new(),
-            token: token.to_string(),
-            request_id: request_id.to_string(),
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, serde_json::Value)> {
+        let query = ["Hello, world".to_string(), "Good morning".to_string()];
+        let options = TranslateOptions::default();
+        let mut languages = LanguageResolver::new(&options, Language::to_caiyun);
+        let request = self.build_request(
+            &query,
+            Some(Language::English),
+            &Language::Chinese,
+            None,
+            &mut languages,
+        )?;
+        let request = HttpRequest::post("https://api.interpreter.caiyunai.com/v1/translator")
+            .header("x-authorization", format!("token {}", self.token))
+            .json(&request)?;
+        let body = self.transport.execute(request).await?;
+        Ok(("success.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "success.json" | "invalid_token.json" => {
+            serde_json::from_str::<CaiyunResponse>(body)?;
         }
+        _ => anyhow::bail!("未知的响应样例：{}", name),
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
-    use crate::fusion_translator::caiyun_translator::CaiyunTranslator;
+    use crate::fusion_translator::caiyun_translator::{
+        CaiyunDomain, CaiyunTranslator, MAX_BATCH_LEN, MAX_PAYLOAD_BYTES,
+    };
+    use crate::fusion_translator::language_fallback::LanguageResolver;
+    use crate::fusion_translator::test_support::http;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
 
     /// 测试创建翻译器实例
     #[tokio::test]
@@ -160,6 +509,65 @@ mod tests {
         assert!(!translator.local());
     }
 
+    /// 测试重试翻译器的所有尝试使用同一个请求ID，不同的逻辑调用使用不同的ID
+    ///
+    /// 本地服务器对每个逻辑调用的前两次请求返回503
+    #[tokio::test]
+    async fn test_retry_reuses_request_id() {
+        use crate::fusion_translator::retry::RetryTranslator;
+        use std::time::Duration;
+
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let recorded = captured.clone();
+        let url = http::serve(move |request| {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let mut captured = recorded.lock().unwrap();
+            captured.push((
+                body["request_id"].as_str().unwrap().to_string(),
+                request.header("x-request-id").map(str::to_string),
+            ));
+            if captured.len() % 3 != 0 {
+                return (503, "{}".to_string());
+            }
+            (200, json!({"target": ["你好"]}).to_string())
+        });
+        let mut inner = CaiyunTranslator::new("token", "demo");
+        inner.url = url;
+        let translator =
+            RetryTranslator::new(Arc::new(inner), 2).with_backoff(Duration::from_millis(1));
+
+        let output = translator
+            .translate("Hello", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "你好");
+        let explicit = TranslateOptions::default().with_request_id("billing-7");
+        translator
+            .translate_vec_with_options(
+                &["Hello".to_string()],
+                Some(Language::English),
+                &Language::Chinese,
+                &explicit,
+            )
+            .await
+            .unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 6);
+        for attempts in captured.chunks(3) {
+            let (id, header) = &attempts[0];
+            assert_ne!(id, "demo");
+            assert_eq!(header.as_deref(), Some(id.as_str()));
+            assert!(attempts.iter().all(|attempt| attempt == &attempts[0]));
+        }
+        assert_eq!(
+            output.detail.request_id.as_deref(),
+            Some(captured[0].0.as_str())
+        );
+        assert_ne!(captured[0].0, captured[3].0);
+        assert_eq!(captured[3].0, "billing-7");
+    }
+
     /// 测试创建带默认请求ID的翻译器
     #[tokio::test]
     async fn test_new_with_default_request_id() {
@@ -179,6 +587,282 @@ mod tests {
         assert_eq!(translator.request_id, request_id);
     }
 
+    /// 测试请求体使用构造时的默认请求ID
+    #[test]
+    fn test_request_uses_default_request_id() {
+        let translator = CaiyunTranslator::new("test_token", "default_id");
+        let query = ["hello".to_string()];
+        let request = translator
+            .build_request(
+                &query,
+                Some(Language::English),
+                &Language::Japanese,
+                None,
+                &mut LanguageResolver::new(&TranslateOptions::default(), Language::to_caiyun),
+            )
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["request_id"], "default_id");
+        assert_eq!(body["trans_type"], "en2ja");
+        assert!(body.get("detect").is_none());
+    }
+
+    /// 测试单次调用的请求ID覆盖默认请求ID
+    #[test]
+    fn test_request_id_override() {
+        let translator = CaiyunTranslator::new("test_token", "default_id");
+        let query = ["hello".to_string()];
+        let request = translator
+            .build_request(
+                &query,
+                None,
+                &Language::Japanese,
+                Some("per-call-id"),
+                &mut LanguageResolver::new(&TranslateOptions::default(), Language::to_caiyun),
+            )
+            .unwrap();
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["request_id"], "per-call-id");
+        assert_eq!(body["trans_type"], "auto2ja");
+        assert_eq!(body["detect"], true);
+    }
+
+    /// 彩云代码表中对应`zh`的语言
+    fn chinese() -> Language {
+        Language::from_caiyun("zh").unwrap()
+    }
+
+    /// 测试调用选项中的请求ID优先于构造时的请求ID，未设置时使用构造时的请求ID
+    #[test]
+    fn test_request_id_precedence() {
+        let translator =
+            CaiyunTranslator::new("test_token", "default_id").with_domain(CaiyunDomain::Finance);
+        let query = ["hello".to_string()];
+        let zh = chinese();
+        let cases = [
+            (TranslateOptions::default(), "default_id"),
+            (
+                TranslateOptions::default().with_request_id("billing-42"),
+                "billing-42",
+            ),
+        ];
+        for (options, expected) in cases {
+            let request_id = options.resolve_request_id();
+            let request = translator
+                .build_request(
+                    &query,
+                    Some(Language::English),
+                    &zh,
+                    request_id.as_deref(),
+                    &mut LanguageResolver::new(&options, Language::to_caiyun),
+                )
+                .unwrap();
+            assert_eq!(
+                serde_json::to_value(&request).unwrap(),
+                json!({
+                    "trans_type": "en2zh_finance",
+                    "source": ["hello"],
+                    "request_id": expected,
+                })
+            );
+        }
+    }
+
+    /// 测试每个专业领域和语言组合生成的请求体
+    #[test]
+    fn test_domain_request_json() {
+        let query = ["hello".to_string()];
+        let domains = [
+            (CaiyunDomain::General, ""),
+            (CaiyunDomain::Medicine, "_medicine"),
+            (CaiyunDomain::Law, "_law"),
+            (CaiyunDomain::Finance, "_finance"),
+        ];
+        let zh = chinese();
+        let pairs = [
+            (zh, Language::English, "zh2en"),
+            (Language::English, zh, "en2zh"),
+        ];
+        for (domain, suffix) in domains {
+            let translator = CaiyunTranslator::new("test_token", "id").with_domain(domain);
+            for (from, to, pair) in pairs {
+                let request = translator
+                    .build_request(
+                        &query,
+                        Some(from),
+                        &to,
+                        None,
+                        &mut LanguageResolver::new(
+                            &TranslateOptions::default(),
+                            Language::to_caiyun,
+                        ),
+                    )
+                    .unwrap();
+                assert_eq!(
+                    serde_json::to_value(&request).unwrap(),
+                    json!({
+                        "trans_type": format!("{}{}", pair, suffix),
+                        "source": ["hello"],
+                        "request_id": "id",
+                    })
+                );
+            }
+        }
+    }
+
+    /// 测试专业领域不支持的语言组合在发送前返回错误，并列出支持的组合
+    #[test]
+    fn test_unsupported_domain() {
+        let translator =
+            CaiyunTranslator::new("test_token", "id").with_domain(CaiyunDomain::Medicine);
+        let query = ["hello".to_string()];
+        for (from, to, pair) in [
+            (Some(Language::English), Language::Japanese, "en2ja"),
+            (None, chinese(), "auto2zh"),
+        ] {
+            let err = translator
+                .build_request(
+                    &query,
+                    from,
+                    &to,
+                    None,
+                    &mut LanguageResolver::new(&TranslateOptions::default(), Language::to_caiyun),
+                )
+                .err()
+                .unwrap();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Domain medicine does not support {}, supported: zh2en, en2zh",
+                    pair
+                )
+            );
+            assert!(matches!(
+                err,
+                TranslatorError::UnsupportedDomain { ref supported, .. }
+                    if supported == &["zh2en", "en2zh"]
+            ));
+            assert_eq!(err.kind(), ErrorKind::Language);
+        }
+        // 通用模型不限制语言组合
+        assert_eq!(
+            CaiyunDomain::General.trans_type("auto", "ja").unwrap(),
+            "auto2ja"
+        );
+    }
+
+    /// 启动把`source`中每个文本转为大写作为译文的本地服务器
+    ///
+    /// # 参数
+    /// - `drop_from`: 从第几个请求开始少返回一条译文，None表示总是完整返回
+    ///
+    /// # 返回值
+    /// 请求本地服务器的翻译器和每个请求的`source`数组
+    fn serve(drop_from: Option<usize>) -> (CaiyunTranslator, Arc<Mutex<Vec<Vec<String>>>>) {
+        let sources = Arc::new(Mutex::new(Vec::new()));
+        let recorded = sources.clone();
+        let url = http::serve(move |request| {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            let source: Vec<String> = serde_json::from_value(body["source"].clone()).unwrap();
+            let mut target = source.iter().map(|s| s.to_uppercase()).collect::<Vec<_>>();
+            let mut recorded = recorded.lock().unwrap();
+            recorded.push(source);
+            if drop_from.is_some_and(|n| recorded.len() > n) {
+                target.pop();
+            }
+            (200, json!({ "target": target }).to_string())
+        });
+        let mut translator = CaiyunTranslator::new("token", "demo");
+        translator.url = url;
+        (translator, sources)
+    }
+
+    /// 测试超出数量和大小上限的批量按序列化后的大小拆分为多次请求，并按原顺序拼接译文
+    #[tokio::test]
+    async fn test_split_oversized_batch() {
+        let (translator, sources) = serve(None);
+        assert_eq!(translator.max_batch_len(), Some(MAX_BATCH_LEN));
+        assert_eq!(translator.input_limit(), Some(MAX_PAYLOAD_BYTES));
+
+        // 引号和换行转义后占两个字节，按原文字节数计算会低估请求大小
+        let query = (0..40)
+            .map(|i| format!("{}item {}", "\"say\"\n".repeat(30), i))
+            .collect::<Vec<_>>();
+        let output = translator
+            .translate_vec(&query, Some(Language::English), &chinese())
+            .await
+            .unwrap();
+        let expected = query.iter().map(|s| s.to_uppercase()).collect::<Vec<_>>();
+        assert_eq!(output.text, expected);
+        let sources = std::mem::take(&mut *sources.lock().unwrap());
+        assert_eq!(sources.len(), 3);
+        assert_eq!(sources.concat(), query);
+        for source in &sources {
+            assert!(serde_json::to_string(source).unwrap().len() <= MAX_PAYLOAD_BYTES);
+        }
+
+        let (translator, sources) = serve(None);
+        let query = (0..120).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let output = translator
+            .translate_vec(&query, Some(Language::English), &chinese())
+            .await
+            .unwrap();
+        assert_eq!(output.text.len(), 120);
+        assert_eq!(output.text[119], "LINE 119");
+        let lens = sources
+            .lock()
+            .unwrap()
+            .iter()
+            .map(Vec::len)
+            .collect::<Vec<_>>();
+        assert_eq!(lens, [50, 50, 20]);
+    }
+
+    /// 测试任一分块返回的译文数量不一致时整个批量返回错误
+    #[tokio::test]
+    async fn test_chunk_size_mismatch() {
+        let (translator, sources) = serve(Some(1));
+        let query = (0..60).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let err = translator
+            .translate_vec(&query, Some(Language::English), &chinese())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::BatchSizeMismatch(10, 9))
+        ));
+        assert_eq!(sources.lock().unwrap().len(), 2);
+    }
+
+    /// 测试单个超长文本按`auto_split`拆分或返回错误
+    #[tokio::test]
+    async fn test_split_oversized_text() {
+        let (translator, sources) = serve(None);
+        let query = "This is a \"quoted\" sentence. ".repeat(200);
+        let output = translator
+            .translate(&query, Some(Language::English), &chinese())
+            .await
+            .unwrap();
+        assert_eq!(output.text, query.to_uppercase());
+        let sources = std::mem::take(&mut *sources.lock().unwrap());
+        assert!(sources.len() > 1);
+        for source in &sources {
+            assert_eq!(source.len(), 1);
+            assert!(serde_json::to_string(source).unwrap().len() <= MAX_PAYLOAD_BYTES);
+        }
+
+        let err = translator
+            .translate_with_options(
+                &query,
+                Some(Language::English),
+                &chinese(),
+                &TranslateOptions::new().with_auto_split(false),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::TooLong);
+    }
+
     /// 测试中译英实际翻译
     ///
     /// 使用真实API测试中文翻译成英文
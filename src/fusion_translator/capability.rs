@@ -0,0 +1,264 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::translator_error::TranslatorError;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 单个翻译引擎的能力
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EngineCapability {
+    /// 引擎名称
+    pub engine: String,
+    /// 是否为本地翻译器
+    pub local: bool,
+    /// 单次批量请求最多的文本数量，None表示不限
+    pub max_batch_len: Option<usize>,
+    /// 单次请求最多的UTF-8字节数，None表示不限
+    pub input_limit: Option<usize>,
+    /// 支持的语言，None表示不限或无法确定；序列化格式见`code_style::with_style`
+    pub languages: Option<Vec<Language>>,
+}
+
+impl EngineCapability {
+    /// 判断引擎是否支持某个语言
+    ///
+    /// # 参数
+    /// - `lang`: 语言
+    pub fn supports(&self, lang: &Language) -> bool {
+        self.languages
+            .as_ref()
+            .is_none_or(|languages| languages.contains(lang))
+    }
+
+    /// 判断引擎是否可以翻译某个语言对
+    ///
+    /// # 参数
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    pub fn translates(&self, from: Option<Language>, to: &Language) -> bool {
+        from.is_none_or(|from| self.supports(&from)) && self.supports(to)
+    }
+}
+
+/// 语言对能力矩阵
+///
+/// 汇总多个翻译引擎支持的语言和请求限制，可序列化为JSON供运维查看，
+/// 也可以在发送请求前用`engines_for`筛选能翻译某个语言对的引擎，而不是逐个尝试
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CapabilityMatrix {
+    /// 每个引擎的能力，顺序与创建时的引擎列表相同
+    pub engines: Vec<EngineCapability>,
+}
+
+impl CapabilityMatrix {
+    /// 能翻译某个语言对的引擎名称
+    ///
+    /// # 参数
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按引擎列表顺序排列的名称
+    pub fn engines_for(&self, from: Option<Language>, to: &Language) -> Vec<&str> {
+        self.engines
+            .iter()
+            .filter(|engine| engine.translates(from, to))
+            .map(|engine| engine.engine.as_str())
+            .collect()
+    }
+
+    /// 按名称查找引擎的能力
+    ///
+    /// # 参数
+    /// - `engine`: 引擎名称
+    pub fn engine(&self, engine: &str) -> Option<&EngineCapability> {
+        self.engines.iter().find(|e| e.engine == engine)
+    }
+
+    /// 检查每个预期的语言对都至少有一个引擎可以翻译
+    ///
+    /// 部分引擎无法翻译的语言对以`tracing`的warn级别记录
+    ///
+    /// # 参数
+    /// - `expected_pairs`: （源语言，目标语言）列表，源语言None表示自动检测
+    ///
+    /// # 返回值
+    /// 无法翻译某个语言对、但该语言对有其他引擎可以翻译的引擎；
+    /// 某个语言对没有任何引擎可以翻译时返回`TranslatorError::UnsupportedPair`
+    pub fn check_pairs(
+        &self,
+        expected_pairs: &[(Option<Language>, Language)],
+    ) -> Result<Vec<UnservedPair>, TranslatorError> {
+        let mut unserved = Vec::new();
+        for (from, to) in expected_pairs {
+            if self.engines_for(*from, to).is_empty() {
+                let names = self
+                    .engines
+                    .iter()
+                    .map(|engine| engine.engine.as_str())
+                    .collect::<Vec<_>>();
+                return Err(TranslatorError::UnsupportedPair {
+                    engine: names.join(" > "),
+                    from: *from,
+                    to: *to,
+                });
+            }
+            for engine in self.engines.iter().filter(|e| !e.translates(*from, to)) {
+                tracing::warn!(
+                    engine = %engine.engine,
+                    from = ?from,
+                    to = ?to,
+                    "engine cannot translate an expected language pair, other engines will be used"
+                );
+                unserved.push(UnservedPair {
+                    engine: engine.engine.clone(),
+                    from: *from,
+                    to: *to,
+                });
+            }
+        }
+        Ok(unserved)
+    }
+}
+
+/// 某个引擎无法翻译的语言对
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnservedPair {
+    /// 引擎名称
+    pub engine: String,
+    /// 源语言，None表示自动检测
+    pub from: Option<Language>,
+    /// 目标语言
+    pub to: Language,
+}
+
+/// 生成能力矩阵
+///
+/// 读取每个引擎的`supported_languages`、`max_batch_len`和`input_limit`，不发送请求
+///
+/// # 参数
+/// - `engines`: 引擎名称及对应的翻译器
+pub fn capabilities(engines: &[(String, Arc<dyn AsyncTranslator>)]) -> CapabilityMatrix {
+    CapabilityMatrix {
+        engines: engines
+            .iter()
+            .map(|(engine, translator)| EngineCapability {
+                engine: engine.clone(),
+                local: translator.local(),
+                max_batch_len: translator.max_batch_len(),
+                input_limit: translator.input_limit(),
+                languages: translator.supported_languages(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::capability::capabilities;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::retry::RetryTranslator;
+    use crate::fusion_translator::translator_factory::{TranslatorConfig, TranslatorFactory};
+    use std::sync::Arc;
+
+    /// 构造引擎列表
+    fn engines() -> Vec<(String, Arc<dyn AsyncTranslator>)> {
+        let european = MockTranslator::new()
+            .with_languages([Language::English, Language::French, Language::German])
+            .with_limits(Some(10), Some(2000));
+        let asian = MockTranslator::new().with_languages([
+            Language::English,
+            Language::Japanese,
+            Language::Korean,
+        ]);
+        vec![
+            ("european".to_string(), Arc::new(european)),
+            (
+                "asian".to_string(),
+                Arc::new(RetryTranslator::new(Arc::new(asian), 2)),
+            ),
+            ("any".to_string(), Arc::new(MockTranslator::new())),
+        ]
+    }
+
+    /// 测试按语言对筛选引擎，包装层沿用内部翻译器的语言
+    #[test]
+    fn test_engines_for() {
+        let matrix = capabilities(&engines());
+        assert_eq!(
+            matrix.engines_for(Some(Language::English), &Language::French),
+            ["european", "any"]
+        );
+        assert_eq!(
+            matrix.engines_for(Some(Language::Japanese), &Language::English),
+            ["asian", "any"]
+        );
+        assert_eq!(
+            matrix.engines_for(None, &Language::English),
+            ["european", "asian", "any"]
+        );
+        assert_eq!(
+            matrix.engines_for(Some(Language::French), &Language::Korean),
+            ["any"]
+        );
+        let european = matrix.engine("european").unwrap();
+        assert_eq!(
+            (european.max_batch_len, european.input_limit),
+            (Some(10), Some(2000))
+        );
+        assert!(matrix.engine("missing").is_none());
+    }
+
+    /// 测试JSON输出
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_value(capabilities(&engines())).unwrap();
+        assert_eq!(
+            json["engines"][0],
+            serde_json::json!({
+                "engine": "european",
+                "local": true,
+                "max_batch_len": 10,
+                "input_limit": 2000,
+                "languages": ["en", "fr", "de"],
+            })
+        );
+        assert_eq!(json["engines"][2]["languages"], serde_json::Value::Null);
+    }
+
+    /// 测试内置翻译器按代码表报告语言，彩云专业领域只支持中英文
+    #[test]
+    fn test_builtin_languages() {
+        use crate::fusion_translator::translator_factory::CaiyunDomain;
+
+        let general = TranslatorFactory::create(TranslatorConfig::Caiyun {
+            token: "token".to_string(),
+            request_id: "demo".to_string(),
+        });
+        let medicine = TranslatorFactory::create(TranslatorConfig::CaiyunProfessional {
+            token: "token".to_string(),
+            request_id: "demo".to_string(),
+            domain: CaiyunDomain::Medicine,
+        });
+        let languages = medicine.supported_languages().unwrap();
+        assert!(languages.contains(&Language::English));
+        assert!(!languages.contains(&Language::Japanese));
+        assert!(languages
+            .iter()
+            .all(|lang| matches!(lang.to_caiyun(), Some("zh" | "en"))));
+        assert!(general.supported_languages().unwrap().len() > languages.len());
+
+        for config in [
+            TranslatorConfig::MyMemory,
+            TranslatorConfig::TranSmart,
+            TranslatorConfig::Reverso,
+            TranslatorConfig::BingWeb,
+            TranslatorConfig::GoogleWeb,
+        ] {
+            let languages = TranslatorFactory::create(config)
+                .supported_languages()
+                .unwrap();
+            assert!(languages.contains(&Language::English));
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::language_map::registered_vendors;
+use crate::fusion_translator::negotiate::parse_language_tag;
+use crate::fusion_translator::translator_error::TranslatorError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 语言代码的输出格式
+///
+/// 序列化`Language`和命令行的JSON输出按此格式输出语言代码；反序列化不区分格式，
+/// 任意格式的代码（以及语言名称和已注册翻译器的代码）都能解析。
+/// 没有ISO代码的语言在所有格式下都输出语言名称，如`Bajan`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CodeStyle {
+    /// BCP 47语言标签，如`en`、`zh-CN`、`sr-Latn`，默认格式
+    #[default]
+    Bcp47,
+    /// 小写并以下划线分隔，如`en`、`zh_cn`、`sr_latn`
+    LowerUnderscore,
+    /// 大写的ISO代码，如`EN`、`ZH`；同一代码的其他变体输出大写的完整标签，如`ZH-TW`
+    UpperIso,
+    /// 翻译器自己的语言代码，如`VendorNative("baidu")`输出`zh`、`cht`；
+    /// 翻译器不支持的语言按`Bcp47`输出
+    VendorNative(String),
+}
+
+thread_local! {
+    /// 当前线程序列化`Language`时使用的格式
+    static CURRENT: RefCell<CodeStyle> = const { RefCell::new(CodeStyle::Bcp47) };
+}
+
+/// 恢复之前的序列化格式，`with_style`的闭包panic时同样生效
+struct Restore(Option<CodeStyle>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(style) = self.0.take() {
+            CURRENT.with(|current| *current.borrow_mut() = style);
+        }
+    }
+}
+
+/// 按指定格式序列化闭包中的`Language`
+///
+/// serde的序列化无法传递参数，因此在当前线程内临时替换格式，闭包返回后恢复
+///
+/// # 参数
+/// - `style`: 语言代码格式
+/// - `f`: 执行序列化的闭包，如`|| serde_json::to_value(&matrix)`
+///
+/// # 返回值
+/// 闭包的返回值
+pub fn with_style<R>(style: &CodeStyle, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.replace(style.clone()));
+    let _restore = Restore(Some(previous));
+    f()
+}
+
+/// 每个语言的BCP 47标签
+///
+/// 优先使用ISO 639-1代码，没有时使用ISO 639-3代码；代码按`from_639_1`/`from_iso639_3`
+/// 对应的是另一个语言时附加书写系统（如`sr-Latn`）。中文按地区区分简繁体。
+/// 仍与其他语言重复或没有代码的语言使用语言名称，保证每个标签只对应一个语言
+fn tags() -> &'static HashMap<Language, String> {
+    static TAGS: OnceLock<HashMap<Language, String>> = OnceLock::new();
+    TAGS.get_or_init(|| {
+        let mut tags = HashMap::new();
+        let mut taken = HashMap::new();
+        for lang in Language::all() {
+            let tag = bcp47_tag(&lang)
+                .filter(|tag| !taken.contains_key(&tag.to_ascii_lowercase()))
+                .unwrap_or_else(|| format!("{:?}", lang));
+            taken.insert(tag.to_ascii_lowercase(), lang);
+            tags.insert(lang, tag);
+        }
+        tags
+    })
+}
+
+/// 按规则生成语言的BCP 47标签，不检查是否与其他语言重复
+///
+/// # 参数
+/// - `lang`: 语言
+fn bcp47_tag(lang: &Language) -> Option<String> {
+    match lang {
+        Language::Chinese => return Some("zh-CN".to_string()),
+        Language::ChineseTraditional => return Some("zh-TW".to_string()),
+        _ => {}
+    }
+    let (code, canonical) = match lang.to_639_1() {
+        Some(code) => (code, Language::from_639_1(code)),
+        None => {
+            let code = lang.iso639_3()?;
+            (code, Language::from_iso639_3(code))
+        }
+    };
+    if canonical == Some(*lang) {
+        return Some(code.to_string());
+    }
+    lang.default_script()
+        .map(|script| format!("{}-{}", code, script))
+}
+
+/// 把代码规范化为小写、以`-`分隔，用于不区分格式的查找
+///
+/// # 参数
+/// - `code`: 语言代码
+fn normalize(code: &str) -> String {
+    code.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+/// 规范化后的标签和语言名称到语言的索引
+fn index() -> &'static HashMap<String, Language> {
+    static INDEX: OnceLock<HashMap<String, Language>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index = HashMap::new();
+        for (lang, tag) in tags() {
+            index.insert(normalize(tag), *lang);
+        }
+        for lang in Language::all() {
+            index
+                .entry(normalize(&format!("{:?}", lang)))
+                .or_insert(lang);
+        }
+        index
+    })
+}
+
+impl CodeStyle {
+    /// 按此格式输出语言代码
+    ///
+    /// 所有面向用户的语言代码输出（`Language`的序列化、命令行的JSON）都经过这里
+    ///
+    /// # 参数
+    /// - `lang`: 语言
+    pub fn format(&self, lang: &Language) -> String {
+        let tag = &tags()[lang];
+        let named = *tag == format!("{:?}", lang);
+        match self {
+            Self::Bcp47 | Self::LowerUnderscore | Self::UpperIso if named => tag.clone(),
+            Self::Bcp47 => tag.clone(),
+            Self::LowerUnderscore => tag.to_ascii_lowercase().replace('-', "_"),
+            Self::UpperIso if !tag.contains('-') || Some(*lang) == tag_base(tag) => {
+                tag.split('-').next().unwrap_or(tag).to_ascii_uppercase()
+            }
+            Self::UpperIso => tag.to_ascii_uppercase(),
+            Self::VendorNative(vendor) => lang
+                .to_vendor(vendor)
+                .map_or_else(|| tag.clone(), str::to_string),
+        }
+    }
+
+    /// 按此格式解析语言代码
+    ///
+    /// 先按格式对应的翻译器代码表查找，再按`parse_code`不区分格式地解析
+    ///
+    /// # 参数
+    /// - `code`: 语言代码
+    ///
+    /// # 返回值
+    /// 语言，无法识别时为None
+    pub fn parse(&self, code: &str) -> Option<Language> {
+        if let Self::VendorNative(vendor) = self {
+            if let Some(lang) = Language::from_vendor(vendor, code.trim()) {
+                return Some(lang);
+            }
+        }
+        parse_code(code)
+    }
+}
+
+/// 只保留主语言子标签时对应的语言
+///
+/// 标签的主语言子标签本身就是该语言的标签时（如`zh-CN`的`zh`对应简体中文），
+/// 大写格式只输出主语言子标签
+///
+/// # 参数
+/// - `tag`: BCP 47标签
+fn tag_base(tag: &str) -> Option<Language> {
+    let base = tag.split('-').next()?;
+    Language::from_639_1(base).or_else(|| Language::from_iso639_3(base))
+}
+
+/// 不区分格式地解析语言代码
+///
+/// 依次尝试任意`CodeStyle`输出的代码和语言名称（不区分大小写，`_`与`-`等价）、
+/// 带地区或书写系统的BCP 47标签（如`en-US`、`zh-Hant`）、ISO 639-3代码，以及已注册翻译器的语言代码
+///
+/// # 参数
+/// - `code`: 语言代码或语言名称
+///
+/// # 返回值
+/// 语言，无法识别时为None
+pub fn parse_code(code: &str) -> Option<Language> {
+    if let Some(lang) = index().get(&normalize(code)) {
+        return Some(*lang);
+    }
+    if let Some(lang) = parse_language_tag(code).or_else(|| Language::from_iso639_3(code)) {
+        return Some(lang);
+    }
+    let code = code.trim();
+    registered_vendors()
+        .iter()
+        .find_map(|vendor| Language::from_vendor(vendor, code))
+}
+
+impl std::str::FromStr for CodeStyle {
+    type Err = TranslatorError;
+
+    /// 解析格式名称
+    ///
+    /// 接受`bcp47`、`lower_underscore`、`upper_iso`和`vendor:<翻译器名称>`，不区分大小写
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        match name.as_str() {
+            "bcp47" => Ok(Self::Bcp47),
+            "lower_underscore" => Ok(Self::LowerUnderscore),
+            "upper_iso" => Ok(Self::UpperIso),
+            _ => match name.strip_prefix("vendor:") {
+                Some(vendor) if registered_vendors().iter().any(|v| v == vendor) => {
+                    Ok(Self::VendorNative(vendor.to_string()))
+                }
+                _ => Err(TranslatorError::Configuration(format!(
+                    "unknown language code style {:?}, expected bcp47, lower_underscore, upper_iso or vendor:<name>",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for CodeStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bcp47 => f.write_str("bcp47"),
+            Self::LowerUnderscore => f.write_str("lower_underscore"),
+            Self::UpperIso => f.write_str("upper_iso"),
+            Self::VendorNative(vendor) => write!(f, "vendor:{}", vendor),
+        }
+    }
+}
+
+impl Serialize for Language {
+    /// 按当前线程的格式序列化，默认为BCP 47标签，见`with_style`
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let code = CURRENT.with(|style| style.borrow().format(self));
+        serializer.serialize_str(&code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    /// 按`parse_code`解析任意格式的语言代码
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        parse_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown language {:?}", code)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::code_style::{parse_code, with_style, CodeStyle};
+    use std::collections::HashSet;
+
+    /// 测试用的格式，包括每个内置翻译器的代码
+    fn styles() -> Vec<CodeStyle> {
+        let mut styles = vec![
+            CodeStyle::Bcp47,
+            CodeStyle::LowerUnderscore,
+            CodeStyle::UpperIso,
+        ];
+        for vendor in [
+            "baidu",
+            "caiyun",
+            "deepl",
+            "google",
+            "m2m100",
+            "mbart-50",
+            "mymemory",
+            "mymemory-short",
+            "nllb",
+            "papago",
+            "youdao",
+        ] {
+            styles.push(CodeStyle::VendorNative(vendor.to_string()));
+        }
+        styles
+    }
+
+    /// 测试每种格式下每个语言的代码都能解析回同一语言
+    ///
+    /// 翻译器代码表中与其他语言共用代码的语言无法区分，只检查能双向转换的语言；
+    /// 翻译器不支持的语言按BCP 47输出，可能与翻译器的其他代码相同，只检查输出
+    #[test]
+    fn test_roundtrip_all_styles() {
+        for style in styles() {
+            let mut seen = HashSet::new();
+            for lang in Language::all() {
+                let code = style.format(&lang);
+                if let CodeStyle::VendorNative(vendor) = &style {
+                    match lang.to_vendor(vendor) {
+                        Some(native) if lang.roundtrips(vendor) => assert_eq!(code, native),
+                        Some(_) => continue,
+                        None => {
+                            assert_eq!(code, CodeStyle::Bcp47.format(&lang));
+                            continue;
+                        }
+                    }
+                } else {
+                    assert!(seen.insert(code.to_ascii_lowercase()), "{} {}", style, code);
+                    assert_eq!(parse_code(&code), Some(lang), "{} {}", style, code);
+                }
+                assert_eq!(style.parse(&code), Some(lang), "{} {}", style, code);
+            }
+        }
+    }
+
+    /// 测试序列化按`with_style`指定的格式输出，反序列化接受任意格式
+    #[test]
+    fn test_serde_all_styles() {
+        for style in styles() {
+            for lang in Language::all() {
+                let json = with_style(&style, || serde_json::to_value(lang).unwrap());
+                assert_eq!(json, serde_json::json!(style.format(&lang)));
+                if !matches!(style, CodeStyle::VendorNative(_)) {
+                    assert_eq!(serde_json::from_value::<Language>(json).unwrap(), lang);
+                }
+            }
+        }
+        assert_eq!(serde_json::to_value(Language::Chinese).unwrap(), "zh-CN");
+        assert!(serde_json::from_str::<Language>("\"xx-unknown\"").is_err());
+    }
+
+    /// 测试各格式的典型输出
+    #[test]
+    fn test_format_examples() {
+        let cases = [
+            (Language::English, ["en", "en", "EN"]),
+            (Language::Chinese, ["zh-CN", "zh_cn", "ZH"]),
+            (Language::ChineseTraditional, ["zh-TW", "zh_tw", "ZH-TW"]),
+            (Language::Serbian, ["sr", "sr", "SR"]),
+            (Language::BanjarLatin, ["bjn-Latn", "bjn_latn", "BJN-LATN"]),
+            // 没有ISO代码的语言输出语言名称
+            (
+                Language::SerbianLatin,
+                ["SerbianLatin", "SerbianLatin", "SerbianLatin"],
+            ),
+        ];
+        for (lang, expected) in cases {
+            let formatted = [
+                CodeStyle::Bcp47,
+                CodeStyle::LowerUnderscore,
+                CodeStyle::UpperIso,
+            ]
+            .map(|style| style.format(&lang));
+            assert_eq!(formatted, expected, "{:?}", lang);
+        }
+        let youdao = CodeStyle::VendorNative("youdao".to_string());
+        assert_eq!(youdao.format(&Language::ChineseTraditional), "zh-CHT");
+        assert_eq!(youdao.parse("zh-CHT"), Some(Language::ChineseTraditional));
+        let baidu = CodeStyle::VendorNative("baidu".to_string());
+        // 翻译器不支持的语言按BCP 47输出
+        let unsupported = Language::all()
+            .into_iter()
+            .find(|lang| lang.to_baidu().is_none())
+            .unwrap();
+        assert_eq!(
+            baidu.format(&unsupported),
+            CodeStyle::Bcp47.format(&unsupported)
+        );
+    }
+
+    /// 测试不区分格式地解析
+    #[test]
+    fn test_parse_liberal() {
+        for code in ["zh-CN", "zh_cn", "ZH", "zh", "Chinese", " zh-Hans "] {
+            assert_eq!(parse_code(code), Some(Language::Chinese), "{}", code);
+        }
+        for code in ["zh-TW", "ZH_HANT", "ChineseTraditional"] {
+            assert_eq!(
+                parse_code(code),
+                Some(Language::ChineseTraditional),
+                "{}",
+                code
+            );
+        }
+        assert_eq!(parse_code("en-US"), Some(Language::English));
+        assert_eq!(parse_code("eng"), Some(Language::English));
+        assert_eq!(parse_code(""), None);
+    }
+
+    /// 测试格式名称的解析和输出
+    #[test]
+    fn test_style_names() {
+        for style in styles() {
+            assert_eq!(style.to_string().parse::<CodeStyle>().unwrap(), style);
+        }
+        assert_eq!(
+            " Upper_ISO ".parse::<CodeStyle>().unwrap(),
+            CodeStyle::UpperIso
+        );
+        assert!("vendor:unknown".parse::<CodeStyle>().is_err());
+        assert!("camel".parse::<CodeStyle>().is_err());
+    }
+
+    /// 测试`with_style`返回后恢复之前的格式，闭包panic时同样恢复
+    #[test]
+    fn test_with_style_restores() {
+        let upper = with_style(&CodeStyle::UpperIso, || {
+            let inner = with_style(&CodeStyle::LowerUnderscore, || {
+                serde_json::to_string(&Language::Chinese).unwrap()
+            });
+            assert_eq!(inner, "\"zh_cn\"");
+            serde_json::to_string(&Language::Chinese).unwrap()
+        });
+        assert_eq!(upper, "\"ZH\"");
+        let result = std::panic::catch_unwind(|| {
+            with_style(&CodeStyle::UpperIso, || panic!("serialization failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            serde_json::to_string(&Language::Chinese).unwrap(),
+            "\"zh-CN\""
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language, TranslationOutput};
+use crate::fusion_translator::runtime::TaskGuard;
+use crate::fusion_translator::translator_error::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 单个翻译引擎的比较结果
+#[derive(Debug, Clone)]
+pub struct EngineResult {
+    /// 引擎名称
+    pub engine: String,
+    /// 从发起请求到返回结果（或错误）的耗时
+    pub latency: Duration,
+    /// 翻译结果，失败时为错误分类和错误信息
+    pub result: Result<TranslationOutput, EngineFailure>,
+}
+
+/// 翻译引擎的失败信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineFailure {
+    /// 错误分类
+    pub kind: ErrorKind,
+    /// 错误信息
+    pub message: String,
+}
+
+impl EngineResult {
+    /// 获取译文，失败时为None
+    pub fn text(&self) -> Option<&str> {
+        self.result.as_ref().ok().map(|output| output.text.as_str())
+    }
+}
+
+/// 使用所有引擎翻译同一文本
+///
+/// 所有引擎并发执行，单个引擎失败不会影响其他引擎；
+/// 返回结果的顺序与`engines`相同。返回的future被丢弃时，尚未完成的请求随之中止
+///
+/// # 参数
+/// - `query`: 待翻译的文本
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `engines`: 引擎名称及对应的翻译器
+///
+/// # 返回值
+/// 每个引擎的翻译结果和耗时
+pub async fn compare_all(
+    query: &str,
+    from: Option<Language>,
+    to: &Language,
+    engines: &[(String, Arc<dyn AsyncTranslator>)],
+) -> Vec<EngineResult> {
+    let mut tasks = TaskGuard::new();
+    for (index, (_, translator)) in engines.iter().enumerate() {
+        let translator = translator.clone();
+        let query = query.to_string();
+        let to = *to;
+        tasks.spawn(async move {
+            let start = Instant::now();
+            let result = translator.translate(&query, from, &to).await;
+            (index, start.elapsed(), result)
+        });
+    }
+
+    let mut results = engines
+        .iter()
+        .map(|(engine, _)| EngineResult {
+            engine: engine.clone(),
+            latency: Duration::ZERO,
+            result: Err(EngineFailure {
+                kind: ErrorKind::Unknown,
+                message: "translation task did not complete".to_string(),
+            }),
+        })
+        .collect::<Vec<_>>();
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((index, latency, result)) = joined else {
+            continue;
+        };
+        results[index].latency = latency;
+        results[index].result = result.map_err(|e| EngineFailure {
+            kind: ErrorKind::of(&e),
+            message: format!("{:#}", e),
+        });
+    }
+    results
+}
+
+/// 找出译文不同的引擎对
+///
+/// 只比较翻译成功的引擎，比较前去除首尾空白
+///
+/// # 参数
+/// - `results`: `compare_all`返回的结果
+///
+/// # 返回值
+/// 译文不同的引擎下标对，每对中较小的下标在前
+pub fn pairwise_differences(results: &[EngineResult]) -> Vec<(usize, usize)> {
+    let mut differences = Vec::new();
+    for (i, a) in results.iter().enumerate() {
+        let Some(a) = a.text() else { continue };
+        for (j, b) in results.iter().enumerate().skip(i + 1) {
+            let Some(b) = b.text() else { continue };
+            if a.trim() != b.trim() {
+                differences.push((i, j));
+            }
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::compare::{compare_all, pairwise_differences};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translator_error::ErrorKind;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 构造测试用的引擎列表
+    fn engines() -> Vec<(String, Arc<dyn AsyncTranslator>)> {
+        vec![
+            (
+                "slow".to_string(),
+                Arc::new(MockTranslator::new().with_delay(Duration::from_millis(300))),
+            ),
+            (
+                "fast".to_string(),
+                Arc::new(MockTranslator::new().with_delay(Duration::from_millis(100))),
+            ),
+            (
+                "broken".to_string(),
+                Arc::new(MockTranslator::new().fail_on("test")),
+            ),
+        ]
+    }
+
+    /// 测试耗时测量和结果顺序
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_and_order() {
+        let results = compare_all("hello", None, &Language::English, &engines()).await;
+        assert_eq!(
+            results
+                .iter()
+                .map(|r| r.engine.as_str())
+                .collect::<Vec<_>>(),
+            vec!["slow", "fast", "broken"]
+        );
+        assert_eq!(results[0].latency, Duration::from_millis(300));
+        assert_eq!(results[1].latency, Duration::from_millis(100));
+        assert_eq!(results[0].text(), Some("HELLO"));
+        assert_eq!(results[1].text(), Some("HELLO"));
+    }
+
+    /// 测试引擎并发执行
+    #[tokio::test(start_paused = true)]
+    async fn test_engines_run_concurrently() {
+        let start = tokio::time::Instant::now();
+        compare_all("hello", None, &Language::English, &engines()).await;
+        assert_eq!(start.elapsed(), Duration::from_millis(300));
+    }
+
+    /// 测试失败的引擎记录错误分类，不影响其他引擎
+    #[tokio::test]
+    async fn test_error_capture() {
+        let results = compare_all("a test", None, &Language::English, &engines()).await;
+        let failure = results[2].result.as_ref().unwrap_err();
+        assert_eq!(failure.kind, ErrorKind::Api);
+        assert!(!failure.message.is_empty());
+        assert_eq!(results[2].text(), None);
+        assert_eq!(results[0].text(), Some("A TEST"));
+    }
+
+    /// 测试两两比较只包含成功且译文不同的引擎
+    #[tokio::test]
+    async fn test_pairwise_differences() {
+        let mut results = compare_all("hello", None, &Language::English, &engines()).await;
+        assert!(pairwise_differences(&results).is_empty());
+        if let Ok(output) = results[1].result.as_mut() {
+            output.text = "hi".to_string();
+        }
+        assert_eq!(pairwise_differences(&results), vec![(0, 1), (1, 2)]);
+    }
+}
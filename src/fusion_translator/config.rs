@@ -0,0 +1,1108 @@
+use crate::fusion_translator::async_translator::AsyncTranslator;
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::{
+    CacheConfig, TranslatorConfig, TranslatorFactory, TranslatorStackConfig, TranslatorType,
+    UnknownTranslatorType,
+};
+use reqwest::header::HeaderValue;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// 指定配置文件路径的环境变量
+pub const CONFIG_FILE_ENV: &str = "FUSION_TRANSLATOR_CONFIG";
+
+/// 指定翻译器的环境变量
+pub const ENGINE_ENV: &str = "FUSION_TRANSLATOR_ENGINE";
+
+/// 可配置的字段及对应的环境变量，按`explain`的输出顺序排列
+///
+/// 凭据沿用各翻译器已有的环境变量，其余字段使用`FUSION_TRANSLATOR_`前缀
+const FIELDS: [(&str, &str); 14] = [
+    ("engine", ENGINE_ENV),
+    ("app_id", "BAIDU_APP_ID"),
+    ("key", "BAIDU_KEY"),
+    ("app_key", "YOUDAO_APP_KEY"),
+    ("app_secret", "YOUDAO_APP_SECRET"),
+    ("token", "CAIYUN_TOKEN"),
+    ("request_id", "CAIYUN_REQUEST_ID"),
+    ("timeout_ms", "FUSION_TRANSLATOR_TIMEOUT_MS"),
+    ("retries", "FUSION_TRANSLATOR_RETRIES"),
+    ("retry_backoff_ms", "FUSION_TRANSLATOR_RETRY_BACKOFF_MS"),
+    ("rate_limit_qps", "FUSION_TRANSLATOR_RATE_LIMIT_QPS"),
+    ("cache_capacity", "FUSION_TRANSLATOR_CACHE_CAPACITY"),
+    ("cache_ttl_secs", "FUSION_TRANSLATOR_CACHE_TTL_SECS"),
+    ("user_agent", "FUSION_TRANSLATOR_USER_AGENT"),
+];
+
+/// `explain`中不显示值的字段
+const SECRET_FIELDS: [&str; 3] = ["key", "app_secret", "token"];
+
+/// 配置的来源，按优先级从低到高排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConfigLayer {
+    /// 默认值
+    Default,
+    /// 配置文件
+    File,
+    /// 环境变量
+    Env,
+    /// 显式传入的参数（代码中的`PartialConfig`或命令行参数）
+    Explicit,
+}
+
+impl ConfigLayer {
+    /// 来源名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::File => "file",
+            Self::Env => "env",
+            Self::Explicit => "explicit",
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 一个来源提供的配置，None表示该来源没有提供这个字段
+///
+/// 字段与`TranslatorStackConfig`相同，缓存配置拆分为`cache_capacity`和`cache_ttl_secs`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialConfig {
+    /// 翻译器类型
+    pub engine: Option<TranslatorType>,
+    /// 百度翻译的APP ID
+    pub app_id: Option<String>,
+    /// 百度翻译的密钥
+    pub key: Option<String>,
+    /// 有道翻译的应用ID
+    pub app_key: Option<String>,
+    /// 有道翻译的应用密钥
+    pub app_secret: Option<String>,
+    /// 彩云翻译的令牌
+    pub token: Option<String>,
+    /// 彩云翻译的请求ID
+    pub request_id: Option<String>,
+    /// 单个HTTP请求的超时时间（毫秒）
+    pub timeout_ms: Option<u64>,
+    /// 暂时性错误最多重试的次数
+    pub retries: Option<u32>,
+    /// 第一次重试前的等待时间（毫秒）
+    pub retry_backoff_ms: Option<u64>,
+    /// 每秒允许的请求数
+    pub rate_limit_qps: Option<f64>,
+    /// 缓存最多保存的条目数
+    pub cache_capacity: Option<usize>,
+    /// 缓存条目有效期（秒）
+    pub cache_ttl_secs: Option<u64>,
+    /// 覆盖翻译器默认的User-Agent
+    pub user_agent: Option<String>,
+}
+
+impl PartialConfig {
+    /// 创建不提供任何字段的配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置翻译器类型
+    ///
+    /// # 参数
+    /// - `engine`: 翻译器类型
+    pub fn with_engine(mut self, engine: TranslatorType) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// 设置单个HTTP请求的超时时间
+    ///
+    /// # 参数
+    /// - `timeout_ms`: 超时时间（毫秒）
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// 设置重试次数
+    ///
+    /// # 参数
+    /// - `retries`: 暂时性错误最多重试的次数
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// 设置请求速率
+    ///
+    /// # 参数
+    /// - `qps`: 每秒允许的请求数
+    pub fn with_rate_limit_qps(mut self, qps: f64) -> Self {
+        self.rate_limit_qps = Some(qps);
+        self
+    }
+
+    /// 设置缓存
+    ///
+    /// # 参数
+    /// - `capacity`: 最多保存的条目数
+    /// - `ttl_secs`: 条目有效期（秒），None表示不过期
+    pub fn with_cache(mut self, capacity: usize, ttl_secs: Option<u64>) -> Self {
+        self.cache_capacity = Some(capacity);
+        self.cache_ttl_secs = ttl_secs;
+        self
+    }
+
+    /// 设置User-Agent
+    ///
+    /// # 参数
+    /// - `user_agent`: 请求使用的User-Agent
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// 默认值：不重试，彩云的请求ID为`demo`；其余字段没有默认值，即不限超时、不限流、不缓存
+    fn defaults() -> Self {
+        Self {
+            retries: Some(0),
+            request_id: Some("demo".to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// 按字段名称设置字段
+    ///
+    /// # 参数
+    /// - `field`: `FIELDS`中的字段名称
+    /// - `value`: 字符串形式的值，数值字段会先去掉首尾空白
+    ///
+    /// # 返回值
+    /// 值无法解析时返回错误说明
+    fn set(&mut self, field: &str, value: &str) -> Result<(), String> {
+        match field {
+            "engine" => {
+                let engine = value
+                    .parse()
+                    .map_err(|e: UnknownTranslatorType| e.to_string())?;
+                self.engine = Some(engine);
+            }
+            "app_id" => self.app_id = Some(value.to_string()),
+            "key" => self.key = Some(value.to_string()),
+            "app_key" => self.app_key = Some(value.to_string()),
+            "app_secret" => self.app_secret = Some(value.to_string()),
+            "token" => self.token = Some(value.to_string()),
+            "request_id" => self.request_id = Some(value.to_string()),
+            "timeout_ms" => self.timeout_ms = Some(parse(value)?),
+            "retries" => self.retries = Some(parse(value)?),
+            "retry_backoff_ms" => self.retry_backoff_ms = Some(parse(value)?),
+            "rate_limit_qps" => self.rate_limit_qps = Some(parse(value)?),
+            "cache_capacity" => self.cache_capacity = Some(parse(value)?),
+            "cache_ttl_secs" => self.cache_ttl_secs = Some(parse(value)?),
+            "user_agent" => self.user_agent = Some(value.to_string()),
+            _ => return Err("unknown field".to_string()),
+        }
+        Ok(())
+    }
+
+    /// 按名称获取凭据字段
+    ///
+    /// # 参数
+    /// - `field`: 凭据字段名称
+    fn credential(&self, field: &str) -> &Option<String> {
+        match field {
+            "app_id" => &self.app_id,
+            "key" => &self.key,
+            "app_key" => &self.app_key,
+            "app_secret" => &self.app_secret,
+            "token" => &self.token,
+            _ => &self.request_id,
+        }
+    }
+}
+
+/// 解析数值字段
+///
+/// # 参数
+/// - `value`: 字符串形式的值
+fn parse<T: FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid value {:?}: {}", value, e))
+}
+
+/// 描述字段的具体来源，如`env FUSION_TRANSLATOR_RETRIES`或`file translator.json`
+///
+/// # 参数
+/// - `layer`: 来源
+/// - `field`: 字段名称
+/// - `file`: 配置文件路径
+fn describe(layer: ConfigLayer, field: &str, file: Option<&Path>) -> String {
+    match (layer, file) {
+        (ConfigLayer::Env, _) => match FIELDS.iter().find(|(name, _)| *name == field) {
+            Some((_, var)) => format!("env {}", var),
+            None => "env".to_string(),
+        },
+        (ConfigLayer::File, Some(path)) => format!("file {}", path.display()),
+        (layer, _) => layer.to_string(),
+    }
+}
+
+/// 配置中的一个问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// 字段名称，配置文件本身的问题为`config`
+    pub field: String,
+    /// 提供出错值的来源，如`env FUSION_TRANSLATOR_RETRIES`；字段缺失时为None
+    pub origin: Option<String>,
+    /// 问题说明
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.origin {
+            Some(origin) => write!(f, "{} ({}): {}", self.field, origin, self.message),
+            None => write!(f, "{}: {}", self.field, self.message),
+        }
+    }
+}
+
+/// 配置校验失败，包含发现的所有问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// 按发现顺序排列的问题
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let count = self.problems.len();
+        write!(
+            f,
+            "invalid configuration ({} problem{}):",
+            count,
+            if count == 1 { "" } else { "s" }
+        )?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for TranslatorError {
+    fn from(err: ConfigError) -> Self {
+        TranslatorError::Configuration(err.to_string())
+    }
+}
+
+/// 一个来源的配置及其中无法解析的字段
+struct Layer {
+    /// 来源
+    layer: ConfigLayer,
+    /// 成功解析的字段
+    values: PartialConfig,
+    /// 字段名称 -> 无法解析的原因
+    invalid: BTreeMap<&'static str, String>,
+}
+
+impl Layer {
+    /// 创建不含无效字段的来源
+    fn new(layer: ConfigLayer, values: PartialConfig) -> Self {
+        Self {
+            layer,
+            values,
+            invalid: BTreeMap::new(),
+        }
+    }
+
+    /// 读取环境变量，只包含值不为空白的变量
+    ///
+    /// # 参数
+    /// - `env`: 读取环境变量，未设置时返回None
+    fn from_env(env: &impl Fn(&str) -> Option<String>) -> Self {
+        let mut layer = Self::new(ConfigLayer::Env, PartialConfig::new());
+        for (field, var) in FIELDS {
+            if let Some(value) = env(var).filter(|value| !value.trim().is_empty()) {
+                if let Err(message) = layer.values.set(field, &value) {
+                    layer.invalid.insert(field, message);
+                }
+            }
+        }
+        layer
+    }
+
+    /// 读取JSON配置文件
+    ///
+    /// 文件是一个JSON对象或TOML表（见`parse_file`），字段同`FIELDS`，缓存也可以写成与`TranslatorStackConfig`相同的
+    /// `"cache": {"capacity": ..., "ttl_secs": ...}`；值可以是字符串或数字
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    /// - `problems`: 记录文件无法读取和未知字段等与具体取值无关的问题
+    fn from_file(path: &Path, problems: &mut Vec<ConfigProblem>) -> Self {
+        let mut layer = Self::new(ConfigLayer::File, PartialConfig::new());
+        let mut problem = |field: &str, message: String| {
+            problems.push(ConfigProblem {
+                field: field.to_string(),
+                origin: Some(describe(ConfigLayer::File, field, Some(path))),
+                message,
+            })
+        };
+        let object = match std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read: {}", e))
+            .and_then(|text| parse_file(path, &text))
+        {
+            Ok(object) => object,
+            Err(message) => {
+                problem("config", message);
+                return layer;
+            }
+        };
+        let mut entries = Vec::new();
+        for (name, value) in object {
+            match (name.as_str(), value) {
+                ("cache", Value::Object(cache)) => {
+                    for (name, value) in cache {
+                        match name.as_str() {
+                            "capacity" => entries.push(("cache_capacity".to_string(), value)),
+                            "ttl_secs" => entries.push(("cache_ttl_secs".to_string(), value)),
+                            _ => problem(&format!("cache.{}", name), "unknown field".to_string()),
+                        }
+                    }
+                }
+                ("cache", _) => problem("cache", "expected an object".to_string()),
+                (_, value) => entries.push((name, value)),
+            }
+        }
+        for (name, value) in entries {
+            let Some(&(field, _)) = FIELDS.iter().find(|(field, _)| *field == name) else {
+                problem(&name, "unknown field".to_string());
+                continue;
+            };
+            let value = match value {
+                Value::String(value) => value,
+                Value::Number(value) => value.to_string(),
+                Value::Null => continue,
+                _ => {
+                    layer
+                        .invalid
+                        .insert(field, "expected a string or a number".to_string());
+                    continue;
+                }
+            };
+            if let Err(message) = layer.values.set(field, &value) {
+                layer.invalid.insert(field, message);
+            }
+        }
+        layer
+    }
+}
+
+/// 按扩展名解析配置文件内容
+///
+/// 扩展名为`.toml`时按TOML解析，其余按JSON解析
+///
+/// # 参数
+/// - `path`: 文件路径
+/// - `text`: 文件内容
+fn parse_file(path: &Path, text: &str) -> Result<Map<String, Value>, String> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+    {
+        toml::from_str(text).map_err(|e| format!("not a TOML table: {}", e))
+    } else {
+        serde_json::from_str(text).map_err(|e| format!("not a JSON object: {}", e))
+    }
+}
+
+/// 按优先级合并各来源的字段，记录每个字段的来源和发现的问题
+struct Merger {
+    /// 按优先级从高到低排列的来源
+    layers: Vec<Layer>,
+    /// 配置文件路径
+    file: Option<PathBuf>,
+    /// 字段名称 -> 提供该字段的来源
+    sources: BTreeMap<&'static str, ConfigLayer>,
+    /// 发现的问题
+    problems: Vec<ConfigProblem>,
+}
+
+impl Merger {
+    /// 取优先级最高的来源提供的值
+    ///
+    /// 优先级最高的来源中该字段无法解析时记录问题并返回None，不会退回到更低的来源
+    ///
+    /// # 参数
+    /// - `field`: 字段名称
+    /// - `get`: 从来源的配置中取出该字段
+    fn pick<T: Clone>(
+        &mut self,
+        field: &'static str,
+        get: impl Fn(&PartialConfig) -> &Option<T>,
+    ) -> Option<T> {
+        for layer in &self.layers {
+            if let Some(value) = get(&layer.values) {
+                self.sources.insert(field, layer.layer);
+                return Some(value.clone());
+            }
+            if let Some(message) = layer.invalid.get(field) {
+                self.problems.push(ConfigProblem {
+                    field: field.to_string(),
+                    origin: Some(describe(layer.layer, field, self.file.as_deref())),
+                    message: message.clone(),
+                });
+                return None;
+            }
+        }
+        None
+    }
+
+    /// 取翻译器必需的凭据，没有任何来源提供时记录问题
+    ///
+    /// # 参数
+    /// - `field`: 凭据字段名称
+    /// - `engine`: 翻译器类型
+    fn required(&mut self, field: &'static str, engine: TranslatorType) -> Option<String> {
+        let value = self.pick(field, |config| config.credential(field));
+        if value.is_none() {
+            let var = FIELDS
+                .iter()
+                .find(|(name, _)| *name == field)
+                .map_or("", |(_, var)| var);
+            self.missing(
+                field,
+                format!(
+                    "required by {}; set {} or add \"{}\" to the config file",
+                    engine, var, field
+                ),
+            );
+        }
+        value
+    }
+
+    /// 记录字段缺失
+    ///
+    /// # 参数
+    /// - `field`: 字段名称
+    /// - `message`: 问题说明
+    fn missing(&mut self, field: &str, message: String) {
+        self.problems.push(ConfigProblem {
+            field: field.to_string(),
+            origin: None,
+            message,
+        });
+    }
+
+    /// 检查已合并的字段，不满足条件时记录问题，问题的来源为提供该字段的来源
+    ///
+    /// # 参数
+    /// - `field`: 字段名称
+    /// - `valid`: 字段是否有效
+    /// - `message`: 无效时的问题说明
+    fn check(&mut self, field: &'static str, valid: bool, message: &str) {
+        if valid {
+            return;
+        }
+        let origin = self
+            .sources
+            .get(field)
+            .map(|layer| describe(*layer, field, self.file.as_deref()));
+        self.problems.push(ConfigProblem {
+            field: field.to_string(),
+            origin,
+            message: message.to_string(),
+        });
+    }
+
+    /// 按翻译器类型取出凭据，生成翻译器配置
+    ///
+    /// 只读取该翻译器使用的凭据，其他翻译器的凭据不计入来源；
+    /// 阿里翻译使用网页接口，不读取凭据
+    ///
+    /// # 参数
+    /// - `engine`: 翻译器类型
+    fn backend(&mut self, engine: TranslatorType) -> Option<TranslatorConfig> {
+        match engine {
+            TranslatorType::Baidu => {
+                let (app_id, key) = (
+                    self.required("app_id", engine),
+                    self.required("key", engine),
+                );
+                Some(TranslatorConfig::Baidu {
+                    app_id: app_id?,
+                    key: key?,
+                })
+            }
+            TranslatorType::Youdao => {
+                let (app_key, app_secret) = (
+                    self.required("app_key", engine),
+                    self.required("app_secret", engine),
+                );
+                Some(TranslatorConfig::Youdao {
+                    app_key: app_key?,
+                    app_secret: app_secret?,
+                })
+            }
+            TranslatorType::Caiyun => {
+                let token = self.required("token", engine);
+                let request_id = self.pick("request_id", |config| &config.request_id);
+                Some(TranslatorConfig::Caiyun {
+                    token: token?,
+                    request_id: request_id?,
+                })
+            }
+            TranslatorType::Alibaba => Some(TranslatorConfig::Alibaba {
+                token: String::new(),
+            }),
+            TranslatorType::MyMemory => Some(TranslatorConfig::MyMemory),
+            TranslatorType::TranSmart => Some(TranslatorConfig::TranSmart),
+            TranslatorType::Reverso => Some(TranslatorConfig::Reverso),
+            TranslatorType::BingWeb => Some(TranslatorConfig::BingWeb),
+            TranslatorType::GoogleWeb => Some(TranslatorConfig::GoogleWeb),
+        }
+    }
+}
+
+/// 分层配置解析
+///
+/// 按以下优先级（从高到低）合并各字段，每个字段独立取优先级最高的来源提供的值：
+///
+/// 1. 显式参数：`with_explicit`传入的`PartialConfig`，命令行参数也通过它传入
+/// 2. 环境变量：见`FIELDS`，凭据沿用`BAIDU_APP_ID`等已有的变量，其余为`FUSION_TRANSLATOR_*`
+/// 3. 配置文件：`with_file`指定，未指定时读取`FUSION_TRANSLATOR_CONFIG`；扩展名为`.toml`时按TOML解析，否则按JSON解析，字段同`TranslatorStackConfig`
+/// 4. 默认值：不重试，彩云的请求ID为`demo`，不限超时、不限流、不缓存
+///
+/// 来源中无法解析的值不会退回到更低的来源，而是作为问题报告。
+/// 所有问题（无法解析的值、缺少的凭据、超出范围的值、配置文件中的未知字段）在合并后一次性返回
+#[derive(Debug, Clone, Default)]
+pub struct ConfigResolver {
+    /// 显式参数
+    explicit: PartialConfig,
+    /// 配置文件路径
+    file: Option<PathBuf>,
+}
+
+impl ConfigResolver {
+    /// 创建没有显式参数和配置文件的解析器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置显式参数，优先级最高
+    ///
+    /// # 参数
+    /// - `explicit`: 显式参数
+    pub fn with_explicit(mut self, explicit: PartialConfig) -> Self {
+        self.explicit = explicit;
+        self
+    }
+
+    /// 指定配置文件，优先于`FUSION_TRANSLATOR_CONFIG`
+    ///
+    /// # 参数
+    /// - `path`: JSON配置文件路径
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// 按当前进程的环境变量解析配置
+    ///
+    /// # 返回值
+    /// 合并后的配置；有任何问题时返回包含所有问题的`ConfigError`
+    pub fn resolve(&self) -> Result<ResolvedConfig, ConfigError> {
+        self.resolve_with(|name| std::env::var(name).ok())
+    }
+
+    /// 解析配置
+    ///
+    /// # 参数
+    /// - `env`: 读取环境变量，未设置时返回None
+    ///
+    /// # 返回值
+    /// 合并后的配置；有任何问题时返回包含所有问题的`ConfigError`
+    pub fn resolve_with(
+        &self,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        let mut problems = Vec::new();
+        let file = self.file.clone().or_else(|| {
+            env(CONFIG_FILE_ENV)
+                .filter(|path| !path.trim().is_empty())
+                .map(PathBuf::from)
+        });
+        let mut layers = vec![
+            Layer::new(ConfigLayer::Explicit, self.explicit.clone()),
+            Layer::from_env(&env),
+        ];
+        if let Some(path) = &file {
+            layers.push(Layer::from_file(path, &mut problems));
+        }
+        layers.push(Layer::new(ConfigLayer::Default, PartialConfig::defaults()));
+        let mut merger = Merger {
+            layers,
+            file,
+            sources: BTreeMap::new(),
+            problems,
+        };
+
+        let reported = merger.problems.len();
+        let engine = merger.pick("engine", |config| &config.engine);
+        if engine.is_none() && merger.problems.len() == reported {
+            merger.missing(
+                "engine",
+                format!(
+                    "not set; pass it explicitly, set {} or add \"engine\" to the config file",
+                    ENGINE_ENV
+                ),
+            );
+        }
+        let backend = engine.and_then(|engine| merger.backend(engine));
+        let timeout_ms = merger.pick("timeout_ms", |config| &config.timeout_ms);
+        let retries = merger.pick("retries", |config| &config.retries);
+        let retry_backoff_ms = merger.pick("retry_backoff_ms", |config| &config.retry_backoff_ms);
+        let rate_limit_qps = merger.pick("rate_limit_qps", |config| &config.rate_limit_qps);
+        let cache_capacity = merger.pick("cache_capacity", |config| &config.cache_capacity);
+        let cache_ttl_secs = merger.pick("cache_ttl_secs", |config| &config.cache_ttl_secs);
+        let user_agent = merger.pick("user_agent", |config| &config.user_agent);
+
+        merger.check(
+            "timeout_ms",
+            timeout_ms != Some(0),
+            "must be greater than 0",
+        );
+        merger.check(
+            "rate_limit_qps",
+            rate_limit_qps.is_none_or(|qps| qps.is_finite() && qps > 0.0),
+            "must be a positive number",
+        );
+        merger.check(
+            "cache_capacity",
+            cache_capacity != Some(0),
+            "must be greater than 0",
+        );
+        merger.check(
+            "cache_ttl_secs",
+            cache_ttl_secs.is_none() || cache_capacity.is_some(),
+            "requires cache_capacity",
+        );
+        merger.check(
+            "user_agent",
+            user_agent
+                .as_deref()
+                .is_none_or(|user_agent| HeaderValue::from_str(user_agent).is_ok()),
+            "not a valid header value",
+        );
+
+        match backend {
+            Some(backend) if merger.problems.is_empty() => Ok(ResolvedConfig {
+                stack: TranslatorStackConfig {
+                    backend,
+                    timeout_ms,
+                    retries: retries.unwrap_or_default(),
+                    retry_backoff_ms,
+                    rate_limit_qps,
+                    cache: cache_capacity.map(|capacity| CacheConfig {
+                        capacity,
+                        ttl_secs: cache_ttl_secs,
+                    }),
+                    user_agent,
+                },
+                sources: merger.sources,
+                file: merger.file,
+            }),
+            _ => Err(ConfigError {
+                problems: merger.problems,
+            }),
+        }
+    }
+}
+
+/// 解析后的配置
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// 合并后的栈配置
+    pub stack: TranslatorStackConfig,
+    /// 字段名称 -> 提供该字段的来源
+    sources: BTreeMap<&'static str, ConfigLayer>,
+    /// 使用的配置文件
+    file: Option<PathBuf>,
+}
+
+impl ResolvedConfig {
+    /// 翻译器类型
+    pub fn engine(&self) -> TranslatorType {
+        self.stack.backend.translator_type()
+    }
+
+    /// 每个有值的字段由哪个来源提供，不包含该翻译器不使用的凭据
+    pub fn resolved_sources(&self) -> &BTreeMap<&'static str, ConfigLayer> {
+        &self.sources
+    }
+
+    /// 使用的配置文件，没有使用时为None
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// 生成说明每个字段的值和来源的文本，每行一个字段，密钥类字段不显示值
+    pub fn explain(&self) -> String {
+        let mut lines = Vec::new();
+        for (field, _) in FIELDS {
+            let (Some(layer), Some(value)) = (self.sources.get(field), self.value(field)) else {
+                continue;
+            };
+            let value = if SECRET_FIELDS.contains(&field) {
+                "***".to_string()
+            } else {
+                value
+            };
+            lines.push(format!(
+                "{:<16} = {} ({})",
+                field,
+                value,
+                describe(*layer, field, self.file.as_deref())
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// 与另一份配置相比值不同的字段，按`explain`的顺序排列
+    ///
+    /// 只比较字段的值，不比较来源；只返回字段名称，可以记录到日志而不泄露密钥
+    ///
+    /// # 参数
+    /// - `previous`: 之前的配置
+    pub fn changed_fields(&self, previous: &ResolvedConfig) -> Vec<&'static str> {
+        FIELDS
+            .iter()
+            .map(|(field, _)| *field)
+            .filter(|field| self.value(field) != previous.value(field))
+            .collect()
+    }
+
+    /// 按配置创建翻译器，包装层同`TranslatorFactory::create_stack`
+    pub fn build(&self) -> Arc<dyn AsyncTranslator> {
+        TranslatorFactory::create_stack(self.stack.clone())
+    }
+
+    /// 字段的值
+    ///
+    /// # 参数
+    /// - `field`: 字段名称
+    fn value(&self, field: &str) -> Option<String> {
+        let stack = &self.stack;
+        match (field, &stack.backend) {
+            ("engine", _) => Some(self.engine().to_string()),
+            ("app_id", TranslatorConfig::Baidu { app_id, .. }) => Some(app_id.clone()),
+            ("key", TranslatorConfig::Baidu { key, .. }) => Some(key.clone()),
+            ("app_key", TranslatorConfig::Youdao { app_key, .. }) => Some(app_key.clone()),
+            ("app_secret", TranslatorConfig::Youdao { app_secret, .. }) => Some(app_secret.clone()),
+            ("token", TranslatorConfig::Caiyun { token, .. }) => Some(token.clone()),
+            ("request_id", TranslatorConfig::Caiyun { request_id, .. }) => Some(request_id.clone()),
+            ("timeout_ms", _) => stack.timeout_ms.map(|value| value.to_string()),
+            ("retries", _) => Some(stack.retries.to_string()),
+            ("retry_backoff_ms", _) => stack.retry_backoff_ms.map(|value| value.to_string()),
+            ("rate_limit_qps", _) => stack.rate_limit_qps.map(|value| value.to_string()),
+            ("cache_capacity", _) => stack.cache.map(|cache| cache.capacity.to_string()),
+            ("cache_ttl_secs", _) => stack
+                .cache
+                .and_then(|cache| cache.ttl_secs)
+                .map(|value| value.to_string()),
+            ("user_agent", _) => stack.user_agent.clone(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::config::{
+        ConfigError, ConfigLayer, ConfigProblem, ConfigResolver, PartialConfig, CONFIG_FILE_ENV,
+        ENGINE_ENV,
+    };
+    use crate::fusion_translator::translator_factory::{
+        CacheConfig, TranslatorConfig, TranslatorType,
+    };
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// 在临时目录写入配置文件
+    ///
+    /// # 参数
+    /// - `name`: 含扩展名的文件名，会加上进程ID避免冲突
+    /// - `content`: 文件内容
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "fusion-translator-config-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// 由键值对生成环境变量读取函数
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        move |name| vars.get(name).cloned()
+    }
+
+    /// 测试每种来源组合下字段取优先级最高的来源：
+    /// 有默认值的`retries`最终退回默认值，没有默认值的`timeout_ms`最终为None
+    #[test]
+    fn test_precedence_combinations() {
+        let path = write_file("precedence.json", r#"{"retries": 1, "timeout_ms": 1000}"#);
+        for mask in 0..8 {
+            let (file, env_set, explicit) = (mask & 1 != 0, mask & 2 != 0, mask & 4 != 0);
+            let mut resolver = ConfigResolver::new();
+            if file {
+                resolver = resolver.with_file(&path);
+            }
+            if explicit {
+                resolver = resolver.with_explicit(
+                    PartialConfig::new()
+                        .with_retries(3)
+                        .with_timeout_ms(3000)
+                        .with_engine(TranslatorType::MyMemory),
+                );
+            }
+            let mut vars = vec![(ENGINE_ENV, "google")];
+            if env_set {
+                vars.push(("FUSION_TRANSLATOR_RETRIES", "2"));
+                vars.push(("FUSION_TRANSLATOR_TIMEOUT_MS", "2000"));
+            }
+            let resolved = resolver.resolve_with(env(&vars)).unwrap();
+            let (layer, retries) = if explicit {
+                (ConfigLayer::Explicit, 3)
+            } else if env_set {
+                (ConfigLayer::Env, 2)
+            } else if file {
+                (ConfigLayer::File, 1)
+            } else {
+                (ConfigLayer::Default, 0)
+            };
+            let sources = resolved.resolved_sources();
+            assert_eq!(resolved.stack.retries, retries, "mask {}", mask);
+            assert_eq!(sources.get("retries"), Some(&layer), "mask {}", mask);
+            if layer == ConfigLayer::Default {
+                assert_eq!(resolved.stack.timeout_ms, None);
+                assert_eq!(sources.get("timeout_ms"), None);
+            } else {
+                assert_eq!(resolved.stack.timeout_ms, Some(retries as u64 * 1000));
+                assert_eq!(sources.get("timeout_ms"), Some(&layer));
+            }
+            let engine = if explicit {
+                TranslatorType::MyMemory
+            } else {
+                TranslatorType::GoogleWeb
+            };
+            assert_eq!(resolved.engine(), engine);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// 测试凭据来自不同来源、配置文件由环境变量指定，以及说明文本不显示密钥
+    #[test]
+    fn test_credentials_and_explain() {
+        let path = write_file(
+            "credentials.json",
+            r#"{"engine": "baidu", "key": "file-key", "cache": {"capacity": 100, "ttl_secs": 60}}"#,
+        );
+        let path_str = path.to_str().unwrap();
+        let resolved = ConfigResolver::new()
+            .resolve_with(env(&[
+                (CONFIG_FILE_ENV, path_str),
+                ("BAIDU_APP_ID", "env-id"),
+                ("CAIYUN_TOKEN", "unused"),
+            ]))
+            .unwrap();
+        assert!(matches!(
+            &resolved.stack.backend,
+            TranslatorConfig::Baidu { app_id, key } if app_id == "env-id" && key == "file-key"
+        ));
+        assert_eq!(resolved.stack.cache.unwrap().ttl_secs, Some(60));
+        assert_eq!(resolved.file(), Some(path.as_path()));
+        assert_eq!(
+            resolved
+                .resolved_sources()
+                .iter()
+                .map(|(field, layer)| (*field, *layer))
+                .collect::<Vec<_>>(),
+            [
+                ("app_id", ConfigLayer::Env),
+                ("cache_capacity", ConfigLayer::File),
+                ("cache_ttl_secs", ConfigLayer::File),
+                ("engine", ConfigLayer::File),
+                ("key", ConfigLayer::File),
+                ("retries", ConfigLayer::Default),
+            ]
+        );
+        assert_eq!(
+            resolved.explain(),
+            format!(
+                "engine           = baidu (file {path})\n\
+                 app_id           = env-id (env BAIDU_APP_ID)\n\
+                 key              = *** (file {path})\n\
+                 retries          = 0 (default)\n\
+                 cache_capacity   = 100 (file {path})\n\
+                 cache_ttl_secs   = 60 (file {path})",
+                path = path_str
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// 测试按扩展名读取TOML配置文件，内联表写法的缓存配置和JSON相同
+    #[test]
+    fn test_toml_file() {
+        let path = write_file(
+            "stack.toml",
+            "engine = \"caiyun\"\ntoken = \"file-token\"\nretries = 2\nrate_limit_qps = 0.5\n\
+             cache = { capacity = 10000, ttl_secs = 86400 }\n",
+        );
+        let resolved = ConfigResolver::new()
+            .with_file(&path)
+            .resolve_with(env(&[]))
+            .unwrap();
+        assert!(matches!(
+            &resolved.stack.backend,
+            TranslatorConfig::Caiyun { token, .. } if token == "file-token"
+        ));
+        assert_eq!(resolved.stack.retries, 2);
+        assert_eq!(resolved.stack.rate_limit_qps, Some(0.5));
+        assert_eq!(
+            resolved.stack.cache,
+            Some(CacheConfig {
+                capacity: 10000,
+                ttl_secs: Some(86400)
+            })
+        );
+        assert_eq!(
+            resolved.resolved_sources().get("cache_ttl_secs"),
+            Some(&ConfigLayer::File)
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let path = write_file("invalid.toml", r#"{"engine": "caiyun"}"#);
+        let err = ConfigResolver::new()
+            .with_file(&path)
+            .resolve_with(env(&[(ENGINE_ENV, "google")]))
+            .unwrap_err();
+        assert_eq!(err.problems.len(), 1);
+        assert_eq!(err.problems[0].field, "config");
+        assert!(err.problems[0].message.starts_with("not a TOML table"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// 测试所有问题一次性报告，以及错误信息的格式
+    #[test]
+    fn test_aggregated_errors() {
+        let path = write_file(
+            "errors.json",
+            r#"{"rate_limit_qps": 0, "timeout_ms": "soon", "retries": 5, "colour": "blue"}"#,
+        );
+        let err = ConfigResolver::new()
+            .with_file(&path)
+            .resolve_with(env(&[
+                (ENGINE_ENV, "youdao"),
+                ("YOUDAO_APP_KEY", "key"),
+                ("FUSION_TRANSLATOR_RETRIES", "many"),
+                ("FUSION_TRANSLATOR_CACHE_TTL_SECS", "60"),
+            ]))
+            .unwrap_err();
+        let file = format!("file {}", path.display());
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invalid configuration (6 problems):\n  \
+                 - colour ({file}): unknown field\n  \
+                 - app_secret: required by youdao; set YOUDAO_APP_SECRET or add \"app_secret\" to the config file\n  \
+                 - timeout_ms ({file}): invalid value \"soon\": invalid digit found in string\n  \
+                 - retries (env FUSION_TRANSLATOR_RETRIES): invalid value \"many\": invalid digit found in string\n  \
+                 - rate_limit_qps ({file}): must be a positive number\n  \
+                 - cache_ttl_secs (env FUSION_TRANSLATOR_CACHE_TTL_SECS): requires cache_capacity",
+                file = file
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let err = ConfigResolver::new()
+            .with_file("/nonexistent/fusion-translator.json")
+            .resolve_with(env(&[(ENGINE_ENV, "deepl")]))
+            .unwrap_err();
+        assert_eq!(err.problems.len(), 2);
+        assert_eq!(err.problems[0].field, "config");
+        assert_eq!(
+            err.problems[1],
+            ConfigProblem {
+                field: "engine".to_string(),
+                origin: Some(format!("env {}", ENGINE_ENV)),
+                message: "unknown translator \"deepl\"".to_string(),
+            }
+        );
+        assert!(err
+            .to_string()
+            .starts_with("invalid configuration (2 problems):"));
+    }
+
+    /// 测试被更高优先级覆盖的无效值不报错，缺少翻译器时只报告一个问题
+    #[test]
+    fn test_overridden_invalid_value() {
+        let resolver = ConfigResolver::new().with_explicit(
+            PartialConfig::new()
+                .with_engine(TranslatorType::Reverso)
+                .with_retries(1),
+        );
+        let resolved = resolver
+            .resolve_with(env(&[
+                (ENGINE_ENV, "nope"),
+                ("FUSION_TRANSLATOR_RETRIES", "many"),
+            ]))
+            .unwrap();
+        assert_eq!(resolved.engine(), TranslatorType::Reverso);
+        assert_eq!(resolved.stack.retries, 1);
+
+        let err = ConfigResolver::new().resolve_with(env(&[])).unwrap_err();
+        assert_eq!(
+            err,
+            ConfigError {
+                problems: vec![ConfigProblem {
+                    field: "engine".to_string(),
+                    origin: None,
+                    message: format!(
+                        "not set; pass it explicitly, set {} or add \"engine\" to the config file",
+                        ENGINE_ENV
+                    ),
+                }],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invalid configuration (1 problem):\n  - engine: not set; pass it explicitly, \
+                 set {} or add \"engine\" to the config file",
+                ENGINE_ENV
+            )
+        );
+    }
+}
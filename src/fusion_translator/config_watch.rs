@@ -0,0 +1,543 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::config::{ConfigError, ConfigResolver, ResolvedConfig};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::runtime::TaskGuard;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// 默认检查配置文件是否变化的间隔
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 按配置创建翻译器的函数
+pub type BuildTranslator = Arc<dyn Fn(&ResolvedConfig) -> Arc<dyn AsyncTranslator> + Send + Sync>;
+
+/// 可以在运行中替换内部翻译器的翻译器
+///
+/// 每次调用开始时取出当前翻译器的`Arc`，替换后新的调用使用新的翻译器，
+/// 已经开始的调用在旧的翻译器上完成
+pub struct ReloadableTranslator {
+    /// 当前的翻译器
+    current: RwLock<Arc<dyn AsyncTranslator>>,
+}
+
+impl ReloadableTranslator {
+    /// 创建可替换的翻译器
+    ///
+    /// # 参数
+    /// - `translator`: 初始的翻译器
+    pub fn new(translator: Arc<dyn AsyncTranslator>) -> Self {
+        Self {
+            current: RwLock::new(translator),
+        }
+    }
+
+    /// 当前的翻译器
+    pub fn current(&self) -> Arc<dyn AsyncTranslator> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// 替换翻译器
+    ///
+    /// # 参数
+    /// - `translator`: 新的翻译器
+    ///
+    /// # 返回值
+    /// 被替换的翻译器，仍在进行的调用结束前不会被释放
+    pub fn swap(&self, translator: Arc<dyn AsyncTranslator>) -> Arc<dyn AsyncTranslator> {
+        std::mem::replace(&mut *self.current.write().unwrap(), translator)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for ReloadableTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与当前翻译器相同
+    fn local(&self) -> bool {
+        self.current().local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与当前翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.current().max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与当前翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.current().input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与当前翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.current().input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与当前翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.current().supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.current().paid()
+    }
+
+    /// 检测文本的语言
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.current().detect_language(text).await
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 转发给当前的翻译器，重新加载后的翻译器需要再次预热
+    async fn warm_up(&self) {
+        self.current().warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.current().translate(query, from, to).await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.current().translate_vec(query, from, to).await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.current()
+            .translate_with_options(query, from, to, options)
+            .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.current()
+            .translate_vec_with_options(query, from, to, options)
+            .await
+    }
+}
+
+/// 配置热加载的状态
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadStatus {
+    /// 成功替换翻译器的次数，初始配置为0
+    pub generation: u64,
+    /// 最近一次替换时值发生变化的字段
+    pub changed: Vec<&'static str>,
+    /// 最近一次加载失败的原因，之后成功加载时清空
+    ///
+    /// 加载失败时继续使用之前的配置
+    pub last_error: Option<String>,
+}
+
+/// 监视器内部的状态
+struct State {
+    /// 当前使用的配置
+    config: ResolvedConfig,
+    /// 最近一次读取到的配置文件内容，用于判断文件是否变化
+    content: Option<Vec<u8>>,
+    /// 热加载状态
+    status: ReloadStatus,
+}
+
+/// 监视器与轮询任务共享的数据
+struct Shared {
+    /// 配置解析器
+    resolver: ConfigResolver,
+    /// 按配置创建翻译器
+    build: BuildTranslator,
+    /// 对外提供的翻译器
+    translator: Arc<ReloadableTranslator>,
+    /// 内部状态
+    state: Mutex<State>,
+}
+
+impl Shared {
+    /// 重新解析配置，值有变化时创建新的翻译器并替换
+    ///
+    /// # 返回值
+    /// 是否替换了翻译器；配置无效时返回错误并保留之前的配置
+    fn reload(&self) -> Result<bool, ConfigError> {
+        let resolved = self.resolver.resolve();
+        let mut state = self.state.lock().unwrap();
+        let config = match resolved {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!(
+                    file = ?state.config.file(),
+                    error = %e,
+                    "config reload rejected, keeping the previous config"
+                );
+                state.status.last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
+        state.status.last_error = None;
+        let changed = config.changed_fields(&state.config);
+        if changed.is_empty() {
+            return Ok(false);
+        }
+        self.translator.swap((self.build)(&config));
+        state.config = config;
+        state.status.generation += 1;
+        tracing::info!(
+            file = ?state.config.file(),
+            generation = state.status.generation,
+            changed = ?changed,
+            "config reloaded"
+        );
+        state.status.changed = changed;
+        Ok(true)
+    }
+
+    /// 配置文件内容与上次读取时不同时重新加载
+    ///
+    /// 同样的无效内容只报告一次
+    ///
+    /// # 参数
+    /// - `path`: 配置文件路径
+    fn poll(&self, path: &Path) {
+        let content = std::fs::read(path).ok();
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.content == content {
+                return;
+            }
+            state.content = content;
+        }
+        let _ = self.reload();
+    }
+}
+
+/// 配置文件监视器
+///
+/// 定期检查配置文件，内容变化时重新解析配置，只要有字段的值发生变化就按新配置创建翻译器，
+/// 原子地替换`translator`返回的翻译器中的内部翻译器；进行中的请求在旧的翻译器上完成。
+/// 新配置无效时保留之前的配置，原因通过`status`查询。
+/// 环境变量和显式参数在重新加载时同样参与合并，但只有配置文件的变化会触发重新加载。
+///
+/// 监视器被丢弃时停止轮询，已经取出的翻译器仍然可以使用
+pub struct ConfigWatcher {
+    /// 与轮询任务共享的数据
+    shared: Arc<Shared>,
+    /// 轮询任务，丢弃时中止
+    _task: TaskGuard,
+}
+
+impl ConfigWatcher {
+    /// 解析初始配置并开始监视配置文件
+    ///
+    /// 必须在tokio运行时中调用；没有使用配置文件时不启动轮询，只能通过`reload`重新加载
+    ///
+    /// # 参数
+    /// - `resolver`: 配置解析器
+    /// - `build`: 按配置创建翻译器，通常为`ResolvedConfig::build`
+    /// - `interval`: 检查配置文件的间隔
+    ///
+    /// # 返回值
+    /// 监视器；初始配置无效时返回错误
+    pub fn new(
+        resolver: ConfigResolver,
+        build: BuildTranslator,
+        interval: Duration,
+    ) -> Result<Self, ConfigError> {
+        let config = resolver.resolve()?;
+        let file = config.file().map(PathBuf::from);
+        let content = file.as_deref().and_then(|path| std::fs::read(path).ok());
+        let shared = Arc::new(Shared {
+            translator: Arc::new(ReloadableTranslator::new(build(&config))),
+            resolver,
+            build,
+            state: Mutex::new(State {
+                config,
+                content,
+                status: ReloadStatus::default(),
+            }),
+        });
+        let mut task = TaskGuard::new();
+        if let Some(path) = file {
+            let shared = shared.clone();
+            task.spawn(async move {
+                let mut ticks = tokio::time::interval(interval);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    shared.poll(&path);
+                }
+            });
+        }
+        Ok(Self {
+            shared,
+            _task: task,
+        })
+    }
+
+    /// 按当前配置提供翻译的翻译器
+    ///
+    /// 返回的翻译器在配置重新加载后自动使用新的配置
+    pub fn translator(&self) -> Arc<ReloadableTranslator> {
+        self.shared.translator.clone()
+    }
+
+    /// 当前使用的配置
+    pub fn config(&self) -> ResolvedConfig {
+        self.shared.state.lock().unwrap().config.clone()
+    }
+
+    /// 热加载状态
+    pub fn status(&self) -> ReloadStatus {
+        self.shared.state.lock().unwrap().status.clone()
+    }
+
+    /// 立即重新加载配置，不等待下一次轮询
+    ///
+    /// # 返回值
+    /// 是否替换了翻译器；配置无效时返回错误并保留之前的配置
+    pub fn reload(&self) -> Result<bool, ConfigError> {
+        self.shared.reload()
+    }
+}
+
+/// 监视配置文件，按默认间隔轮询，并用`ResolvedConfig::build`创建翻译器
+///
+/// 必须在tokio运行时中调用
+///
+/// # 参数
+/// - `path`: JSON配置文件路径
+///
+/// # 返回值
+/// 监视器；初始配置无效时返回错误
+pub fn watch_config(path: impl Into<PathBuf>) -> Result<ConfigWatcher, ConfigError> {
+    ConfigWatcher::new(
+        ConfigResolver::new().with_file(path),
+        Arc::new(ResolvedConfig::build),
+        DEFAULT_POLL_INTERVAL,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::config::{ConfigResolver, ResolvedConfig};
+    use crate::fusion_translator::config_watch::{BuildTranslator, ConfigWatcher};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translator_factory::TranslatorConfig;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 在临时目录写入配置文件
+    ///
+    /// # 参数
+    /// - `name`: 文件名，会加上进程ID避免冲突
+    /// - `content`: 文件内容
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "fusion-translator-watch-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// 有道配置
+    ///
+    /// # 参数
+    /// - `app_key`: 应用ID
+    fn youdao(app_key: &str) -> String {
+        format!(
+            r#"{{"engine": "youdao", "app_key": "{}", "app_secret": "secret"}}"#,
+            app_key
+        )
+    }
+
+    /// 创建在译文后附加有道应用ID的模拟翻译器，每次调用耗时100毫秒
+    fn mock_build() -> BuildTranslator {
+        Arc::new(|config: &ResolvedConfig| {
+            let app_key = match &config.stack.backend {
+                TranslatorConfig::Youdao { app_key, .. } => app_key.clone(),
+                _ => String::new(),
+            };
+            Arc::new(
+                MockTranslator::new()
+                    .with_suffix(format!(" [{}]", app_key))
+                    .with_delay(Duration::from_millis(100)),
+            )
+        })
+    }
+
+    /// 等待热加载状态满足条件
+    ///
+    /// # 参数
+    /// - `watcher`: 监视器
+    /// - `done`: 条件
+    async fn wait_for(watcher: &ConfigWatcher, done: impl Fn(&ConfigWatcher) -> bool) {
+        for _ in 0..200 {
+            if done(watcher) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("timed out: {:?}", watcher.status());
+    }
+
+    /// 测试修改配置文件后使用新的凭据，修改前开始的请求在旧的翻译器上正常完成
+    #[tokio::test]
+    async fn test_reload_swaps_translator() {
+        let path = write_file("swap", &youdao("old-key"));
+        let resolver = ConfigResolver::new().with_file(&path);
+        let watcher =
+            ConfigWatcher::new(resolver, mock_build(), Duration::from_millis(10)).unwrap();
+        let translator = watcher.translator();
+        assert_eq!(
+            translator
+                .translate("hello", None, &Language::Chinese)
+                .await
+                .unwrap()
+                .text,
+            "HELLO [old-key]"
+        );
+
+        let in_flight = {
+            let translator = translator.clone();
+            tokio::spawn(
+                async move { translator.translate("slow", None, &Language::Chinese).await },
+            )
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        std::fs::write(&path, youdao("new-key")).unwrap();
+        wait_for(&watcher, |w| w.status().generation == 1).await;
+
+        assert_eq!(in_flight.await.unwrap().unwrap().text, "SLOW [old-key]");
+        assert_eq!(
+            translator
+                .translate("hello", None, &Language::Chinese)
+                .await
+                .unwrap()
+                .text,
+            "HELLO [new-key]"
+        );
+        let status = watcher.status();
+        assert_eq!(status.changed, vec!["app_key"]);
+        assert_eq!(status.last_error, None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// 测试无效的新配置被拒绝，继续使用之前的配置，修复后恢复
+    #[tokio::test]
+    async fn test_invalid_config_keeps_previous() {
+        let path = write_file("invalid", &youdao("old-key"));
+        let resolver = ConfigResolver::new().with_file(&path);
+        let watcher =
+            ConfigWatcher::new(resolver, mock_build(), Duration::from_millis(10)).unwrap();
+        let translator = watcher.translator();
+
+        std::fs::write(&path, r#"{"engine": "youdao", "app_key": "#).unwrap();
+        wait_for(&watcher, |w| w.status().last_error.is_some()).await;
+        assert_eq!(watcher.status().generation, 0);
+        assert_eq!(
+            translator
+                .translate("hello", None, &Language::Chinese)
+                .await
+                .unwrap()
+                .text,
+            "HELLO [old-key]"
+        );
+        // 缺少凭据同样被拒绝
+        std::fs::write(&path, r#"{"engine": "youdao", "app_key": "new-key"}"#).unwrap();
+        wait_for(&watcher, |w| {
+            w.status()
+                .last_error
+                .is_some_and(|e| e.contains("app_secret"))
+        })
+        .await;
+        assert!(watcher.reload().is_err());
+
+        std::fs::write(&path, youdao("new-key")).unwrap();
+        wait_for(&watcher, |w| w.status().generation == 1).await;
+        assert_eq!(watcher.status().last_error, None);
+        assert_eq!(
+            translator
+                .translate("hello", None, &Language::Chinese)
+                .await
+                .unwrap()
+                .text,
+            "HELLO [new-key]"
+        );
+        // 内容不变时不替换
+        assert!(!watcher.reload().unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+}
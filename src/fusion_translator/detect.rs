@@ -0,0 +1,422 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::long_text::dominant_script;
+use std::sync::Arc;
+
+/// 本地检测达到满分置信度所需的文字字符数
+pub const DEFAULT_SATURATION: usize = 16;
+
+/// 默认最多请求的远程检测次数
+pub const DEFAULT_MAX_REMOTE: usize = 2;
+
+/// 默认的提前结束阈值，综合置信度达到该值后不再请求远程检测
+pub const DEFAULT_STOP_CONFIDENCE: f32 = 0.85;
+
+/// 默认的远程检测权重
+pub const DEFAULT_REMOTE_WEIGHT: f32 = 0.8;
+
+/// 按文字体系的本地语言检测器
+///
+/// 基于`long_text::detect_script`，并按文字字符数和文字体系给出置信度：
+/// 文字越少置信度越低；拉丁、西里尔等多种语言共用的文字体系有置信度上限
+#[derive(Debug, Clone)]
+pub struct LocalDetector {
+    /// 达到满分置信度所需的文字字符数
+    saturation: usize,
+}
+
+impl Default for LocalDetector {
+    fn default() -> Self {
+        Self {
+            saturation: DEFAULT_SATURATION,
+        }
+    }
+}
+
+impl LocalDetector {
+    /// 创建使用默认参数的本地检测器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置达到满分置信度所需的文字字符数
+    ///
+    /// # 参数
+    /// - `saturation`: 文字字符数，至少为1
+    pub fn with_saturation(mut self, saturation: usize) -> Self {
+        self.saturation = saturation.max(1);
+        self
+    }
+
+    /// 检测文本的语言
+    ///
+    /// # 参数
+    /// - `text`: 待检测的文本
+    ///
+    /// # 返回值
+    /// （语言，0.0~1.0的置信度），文本中没有文字时为None
+    pub fn detect(&self, text: &str) -> Option<(Language, f32)> {
+        let (lang, count, total) = dominant_script(text)?;
+        let share = count as f32 / total as f32;
+        Some((lang, share * self.length_factor(count) * ceiling(lang)))
+    }
+
+    /// 按文字字符数计算的可信程度
+    ///
+    /// # 参数
+    /// - `count`: 文字字符数
+    fn length_factor(&self, count: usize) -> f32 {
+        (count as f32 / self.saturation as f32).min(1.0)
+    }
+}
+
+/// 只看文字体系时的置信度上限
+///
+/// 拉丁字母被大量语言使用，只能说明“可能是英语”；汉字也可能是不含假名的日语；
+/// 西里尔、阿拉伯和天城文各自有几种常见语言共用
+///
+/// # 参数
+/// - `lang`: `detect_script`检测到的语言
+fn ceiling(lang: Language) -> f32 {
+    match lang {
+        Language::English => 0.6,
+        Language::Russian | Language::Arabic | Language::Hindi => 0.8,
+        Language::Chinese => 0.9,
+        _ => 1.0,
+    }
+}
+
+/// 语言检测的综合选项
+#[derive(Debug, Clone)]
+pub struct ConsensusOptions {
+    /// 最多请求的远程检测次数，失败的请求也计入，不支持检测的翻译器不计入
+    pub max_remote: usize,
+    /// 综合置信度达到该值后不再请求远程检测
+    pub stop_confidence: f32,
+    /// 文字足够多时一次远程检测的权重，文字较少时最多减半
+    pub remote_weight: f32,
+}
+
+impl Default for ConsensusOptions {
+    fn default() -> Self {
+        Self {
+            max_remote: DEFAULT_MAX_REMOTE,
+            stop_confidence: DEFAULT_STOP_CONFIDENCE,
+            remote_weight: DEFAULT_REMOTE_WEIGHT,
+        }
+    }
+}
+
+/// 检测结果的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteSource {
+    /// 本地检测器
+    Local,
+    /// 远程检测，值为翻译器在传入数组中的序号
+    Remote(usize),
+}
+
+/// 一次检测的投票
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionVote {
+    /// 来源
+    pub source: VoteSource,
+    /// 检测到的语言
+    pub lang: Language,
+    /// 权重，0.0~1.0
+    pub weight: f32,
+}
+
+/// 综合语言检测的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionResult {
+    /// 得票最多的语言，文本中没有文字或没有任何投票时为None
+    pub lang: Option<Language>,
+    /// 0.0~1.0的综合置信度
+    ///
+    /// 支持该语言的投票越多、权重越高越接近1，存在反对票时按得票比例降低
+    pub confidence: f32,
+    /// 按请求顺序排列的所有投票
+    pub votes: Vec<DetectionVote>,
+}
+
+/// 综合本地和远程检测判断文本的语言
+///
+/// 使用默认的`ConsensusOptions`，见`detect_consensus_with`
+///
+/// # 参数
+/// - `translators`: 可用于远程检测的翻译器
+/// - `local`: 本地检测器
+/// - `text`: 待检测的文本
+///
+/// # 返回值
+/// 检测结果
+pub async fn detect_consensus(
+    translators: &[Arc<dyn AsyncTranslator>],
+    local: &LocalDetector,
+    text: &str,
+) -> DetectionResult {
+    detect_consensus_with(translators, local, text, &ConsensusOptions::default()).await
+}
+
+/// 按指定选项综合本地和远程检测判断文本的语言
+///
+/// 先用本地检测器投票，综合置信度不足`stop_confidence`时按本地、免费、计费的顺序
+/// 依次请求远程检测，直到置信度足够或达到`max_remote`次。文本中没有文字时（如纯数字）
+/// 直接返回None，不请求远程检测；远程检测失败只记录日志，不影响结果
+///
+/// # 参数
+/// - `translators`: 可用于远程检测的翻译器
+/// - `local`: 本地检测器
+/// - `text`: 待检测的文本
+/// - `options`: 检测选项
+///
+/// # 返回值
+/// 检测结果
+pub async fn detect_consensus_with(
+    translators: &[Arc<dyn AsyncTranslator>],
+    local: &LocalDetector,
+    text: &str,
+    options: &ConsensusOptions,
+) -> DetectionResult {
+    let translators = translators.iter().map(Arc::as_ref).collect::<Vec<_>>();
+    consensus(&translators, local, text, options).await
+}
+
+/// 综合检测的实现
+///
+/// 供持有`&dyn AsyncTranslator`的调用方（如`long_text::translate_mixed`）直接使用
+///
+/// # 参数
+/// - `translators`: 可用于远程检测的翻译器
+/// - `local`: 本地检测器
+/// - `text`: 待检测的文本
+/// - `options`: 检测选项
+pub(crate) async fn consensus(
+    translators: &[&dyn AsyncTranslator],
+    local: &LocalDetector,
+    text: &str,
+    options: &ConsensusOptions,
+) -> DetectionResult {
+    let Some((lang, count, _)) = dominant_script(text) else {
+        return DetectionResult {
+            lang: None,
+            confidence: 0.0,
+            votes: Vec::new(),
+        };
+    };
+    let local_confidence = local.detect(text).map_or(0.0, |(_, c)| c);
+    let mut votes = vec![DetectionVote {
+        source: VoteSource::Local,
+        lang,
+        weight: local_confidence,
+    }];
+    let remote_weight = options.remote_weight * (0.5 + 0.5 * local.length_factor(count));
+
+    let mut order = (0..translators.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| (translators[i].paid(), !translators[i].local()));
+    let mut requested = 0;
+    for index in order {
+        if requested >= options.max_remote || tally(&votes).1 >= options.stop_confidence {
+            break;
+        }
+        match translators[index].detect_language(text).await {
+            Ok(Some(lang)) => {
+                requested += 1;
+                votes.push(DetectionVote {
+                    source: VoteSource::Remote(index),
+                    lang,
+                    weight: remote_weight,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => {
+                requested += 1;
+                tracing::debug!(index, error = %e, "remote language detection failed");
+            }
+        }
+    }
+
+    let (lang, confidence) = tally(&votes);
+    DetectionResult {
+        lang,
+        confidence,
+        votes,
+    }
+}
+
+/// 汇总投票
+///
+/// 权重之和最高的语言胜出（相同时取先投票的）；置信度为支持票全部出错的概率的补，
+/// 再乘以支持票在总权重中的比例
+///
+/// # 参数
+/// - `votes`: 投票
+///
+/// # 返回值
+/// （胜出的语言，综合置信度）
+fn tally(votes: &[DetectionVote]) -> (Option<Language>, f32) {
+    let mut totals: Vec<(Language, f32)> = Vec::new();
+    for vote in votes {
+        match totals.iter_mut().find(|(lang, _)| *lang == vote.lang) {
+            Some((_, weight)) => *weight += vote.weight,
+            None => totals.push((vote.lang, vote.weight)),
+        }
+    }
+    let all = totals.iter().map(|(_, weight)| weight).sum::<f32>();
+    let Some(&(winner, weight)) = totals
+        .iter()
+        .reduce(|best, item| if item.1 > best.1 { item } else { best })
+    else {
+        return (None, 0.0);
+    };
+    if all <= 0.0 {
+        return (Some(winner), 0.0);
+    }
+    let miss = votes
+        .iter()
+        .filter(|vote| vote.lang == winner)
+        .map(|vote| 1.0 - vote.weight.clamp(0.0, 1.0))
+        .product::<f32>();
+    (Some(winner), (1.0 - miss) * weight / all)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::detect::{
+        detect_consensus, detect_consensus_with, ConsensusOptions, LocalDetector, VoteSource,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use std::sync::Arc;
+
+    /// 测试本地检测的置信度随文字数量和文字体系变化
+    #[test]
+    fn test_local_detector() {
+        let local = LocalDetector::new();
+        assert_eq!(local.detect("2023"), None);
+        assert_eq!(local.detect("  !?"), None);
+
+        let (lang, short) = local.detect("OK").unwrap();
+        assert_eq!(lang, Language::English);
+        assert!(short < 0.1);
+        let (_, long) = local.detect("The quick brown fox jumps").unwrap();
+        assert!((long - 0.6).abs() < 1e-6);
+
+        let (lang, confidence) = local
+            .detect("这是一个足够长的中文句子，用来测试置信度")
+            .unwrap();
+        assert_eq!(lang, Language::Chinese);
+        assert!(confidence >= 0.9 - 1e-6);
+        let (lang, _) = local.detect("これは日本語です").unwrap();
+        assert_eq!(lang, Language::Japanese);
+
+        let (_, saturated) = LocalDetector::new()
+            .with_saturation(2)
+            .detect("OK")
+            .unwrap();
+        assert!((saturated - 0.6).abs() < 1e-6);
+    }
+
+    /// 测试纯数字和没有文字的文本返回None且不请求远程检测
+    #[tokio::test]
+    async fn test_numeric_only() {
+        let remote = Arc::new(MockTranslator::new().with_detection(Language::French));
+        let translators: Vec<Arc<dyn AsyncTranslator>> = vec![remote.clone()];
+        for text in ["2023", "3.14", "", "100% — 42"] {
+            let result = detect_consensus(&translators, &LocalDetector::new(), text).await;
+            assert_eq!(result.lang, None, "{:?}", text);
+            assert_eq!(result.confidence, 0.0);
+            assert!(result.votes.is_empty());
+        }
+        assert_eq!(remote.calls(), 0);
+    }
+
+    /// 测试本地检测足够可信时不请求远程检测
+    #[tokio::test]
+    async fn test_stops_early() {
+        let remote = Arc::new(MockTranslator::new().with_detection(Language::Japanese));
+        let translators: Vec<Arc<dyn AsyncTranslator>> = vec![remote.clone()];
+        let result = detect_consensus(
+            &translators,
+            &LocalDetector::new(),
+            "这是一个足够长的中文句子，用来测试置信度",
+        )
+        .await;
+        assert_eq!(result.lang, Some(Language::Chinese));
+        assert_eq!(result.votes.len(), 1);
+        assert_eq!(remote.calls(), 0);
+    }
+
+    /// 测试短文本：远程检测一致时提高置信度，先请求免费的翻译器
+    #[tokio::test]
+    async fn test_short_agreement() {
+        let paid = Arc::new(
+            MockTranslator::new()
+                .with_detection(Language::English)
+                .with_paid(true),
+        );
+        let free = Arc::new(MockTranslator::new().with_detection(Language::English));
+        let unsupported = Arc::new(MockTranslator::new());
+        let translators: Vec<Arc<dyn AsyncTranslator>> =
+            vec![paid.clone(), unsupported.clone(), free.clone()];
+
+        let local = LocalDetector::new();
+        let local_only = local.detect("OK").unwrap().1;
+        let result = detect_consensus(&translators, &local, "OK").await;
+        assert_eq!(result.lang, Some(Language::English));
+        assert!(result.confidence > local_only);
+        let sources = result.votes.iter().map(|v| v.source).collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            [
+                VoteSource::Local,
+                VoteSource::Remote(2),
+                VoteSource::Remote(0)
+            ]
+        );
+        assert_eq!((free.calls(), paid.calls()), (1, 1));
+
+        let options = ConsensusOptions {
+            max_remote: 1,
+            ..Default::default()
+        };
+        let result = detect_consensus_with(&translators, &local, "OK", &options).await;
+        assert_eq!(result.votes.len(), 2);
+        assert_eq!(paid.calls(), 1);
+    }
+
+    /// 测试远程检测与本地检测不一致时多数票胜出，置信度低于一致的情况
+    #[tokio::test]
+    async fn test_disagreement() {
+        let local = LocalDetector::new();
+        let text = "Bonjour tout le monde, comment allez-vous";
+        let agree: Vec<Arc<dyn AsyncTranslator>> = vec![
+            Arc::new(MockTranslator::new().with_detection(Language::French)),
+            Arc::new(MockTranslator::new().with_detection(Language::French)),
+        ];
+        let result = detect_consensus(&agree, &local, text).await;
+        assert_eq!(result.lang, Some(Language::French));
+        let agreed = result.confidence;
+
+        let split: Vec<Arc<dyn AsyncTranslator>> = vec![
+            Arc::new(MockTranslator::new().with_detection(Language::French)),
+            Arc::new(MockTranslator::new().with_detection(Language::Spanish)),
+        ];
+        let result = detect_consensus(&split, &local, text).await;
+        assert_eq!(result.votes.len(), 3);
+        assert!(result.confidence < agreed);
+        assert!(result.confidence > 0.0 && result.confidence <= 1.0);
+
+        let failing: Vec<Arc<dyn AsyncTranslator>> = vec![
+            Arc::new(
+                MockTranslator::new()
+                    .with_detection(Language::French)
+                    .fail_on("Bonjour"),
+            ),
+            Arc::new(MockTranslator::new().with_detection(Language::French)),
+        ];
+        let result = detect_consensus(&failing, &local, text).await;
+        assert_eq!(result.lang, Some(Language::French));
+        assert_eq!(result.votes[1].source, VoteSource::Remote(1));
+    }
+}
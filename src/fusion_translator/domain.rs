@@ -0,0 +1,278 @@
+/// 文本领域
+///
+/// 不同翻译引擎擅长的领域不同，`routing::RoutingTranslator`按领域选择引擎
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextDomain {
+    /// 一般文本
+    #[default]
+    General,
+    /// 聊天消息等口语化的短文本
+    Chat,
+    /// 合同、条款等法律文本
+    Legal,
+    /// 代码注释和夹杂代码的技术文本
+    Code,
+}
+
+impl TextDomain {
+    /// 所有领域
+    pub const ALL: [TextDomain; 4] = [Self::General, Self::Chat, Self::Legal, Self::Code];
+
+    /// 领域名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Chat => "chat",
+            Self::Legal => "legal",
+            Self::Code => "code",
+        }
+    }
+}
+
+impl std::fmt::Display for TextDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 法律文本的标记词，英文按小写比较
+const LEGAL_MARKERS: &[&str] = &[
+    "hereinafter",
+    "whereas",
+    "pursuant to",
+    "notwithstanding",
+    "hereby",
+    "herein",
+    "thereof",
+    "hereto",
+    "indemnif",
+    "liability",
+    "liable",
+    "governing law",
+    "terms and conditions",
+    "in accordance with",
+    "the parties",
+    "shall",
+    "甲方",
+    "乙方",
+    "本协议",
+    "本合同",
+    "违约",
+    "免责",
+    "不可抗力",
+    "争议解决",
+    "特此",
+];
+
+/// 聊天的标记词，英文只匹配整个单词
+const CHAT_WORDS: &[&str] = &[
+    "lol", "haha", "hahaha", "omg", "btw", "thx", "thanks", "pls", "plz", "hi", "hey", "hello",
+    "ok", "okay", "yeah", "yep", "nope", "u", "ur", "gonna", "wanna", "brb", "np",
+];
+
+/// 聊天的中文标记
+const CHAT_PHRASES: &[&str] = &[
+    "哈哈", "嘿嘿", "谢谢", "你好", "好的", "嗯", "啦", "呀", "么么",
+];
+
+/// 代码中的关键字
+const CODE_KEYWORDS: &[&str] = &[
+    "fn", "def", "let", "const", "var", "return", "null", "nil", "impl", "struct", "class", "self",
+    "this", "TODO", "FIXME", "true", "false", "async", "await", "import",
+];
+
+/// 代码中的符号
+const CODE_SYMBOLS: &[&str] = &[
+    "::", "->", "=>", "==", "!=", "&&", "||", "()", "{", "}", "[]", "</", "/>",
+];
+
+/// 注释行的开头
+const COMMENT_PREFIXES: &[&str] = &["//", "/*", "*/", "* ", "# ", "-- ", "<!--"];
+
+/// 按关键词和启发式规则判断文本领域
+///
+/// 只检查文本本身，不发送请求、不依赖外部状态，相同的输入总是得到相同的结果。
+/// 按代码 > 法律 > 聊天的顺序判断，都不满足时为`TextDomain::General`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainClassifier {
+    /// 代码特征占单词数的比例达到此值时判断为代码
+    pub min_code_ratio: f32,
+    /// 法律标记词达到此数量时判断为法律文本
+    pub min_legal_markers: usize,
+    /// 判断为聊天的最大字符数，更长的文本不视为聊天
+    pub max_chat_chars: usize,
+}
+
+impl Default for DomainClassifier {
+    fn default() -> Self {
+        Self {
+            min_code_ratio: 0.25,
+            min_legal_markers: 2,
+            max_chat_chars: 200,
+        }
+    }
+}
+
+impl DomainClassifier {
+    /// 判断文本领域
+    ///
+    /// # 参数
+    /// - `text`: 待判断的文本
+    pub fn classify(&self, text: &str) -> TextDomain {
+        let words = text.split_whitespace().count();
+        if words > 0 && code_signals(text) as f32 / words as f32 >= self.min_code_ratio {
+            return TextDomain::Code;
+        }
+        if legal_markers(text) >= self.min_legal_markers.max(1) {
+            return TextDomain::Legal;
+        }
+        if text.chars().count() <= self.max_chat_chars && is_chatty(text) {
+            return TextDomain::Chat;
+        }
+        TextDomain::General
+    }
+}
+
+/// 统计代码特征：注释行开头、代码符号、关键字、函数调用和snake_case/camelCase标识符
+///
+/// # 参数
+/// - `text`: 待检查的文本
+fn code_signals(text: &str) -> usize {
+    let comment_lines = text
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            COMMENT_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+                || line.trim_end().ends_with(';')
+        })
+        .count();
+    let tokens = text
+        .split_whitespace()
+        .filter(|token| is_code_token(token))
+        .count();
+    comment_lines + tokens
+}
+
+/// 判断单词是否像代码
+///
+/// # 参数
+/// - `token`: 按空白拆分出的单词
+fn is_code_token(token: &str) -> bool {
+    if CODE_SYMBOLS.iter().any(|symbol| token.contains(symbol)) {
+        return true;
+    }
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+    if CODE_KEYWORDS.contains(&word) {
+        return true;
+    }
+    // 函数调用，如`parse(`或`config.load()`
+    if let Some(open) = token.find('(') {
+        if token[..open].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+            return true;
+        }
+    }
+    if !word.is_ascii() || word.is_empty() {
+        return false;
+    }
+    let snake_case = word.trim_matches('_').contains('_');
+    let camel_case = word.starts_with(|c: char| c.is_ascii_lowercase())
+        && word.contains(|c: char| c.is_ascii_uppercase())
+        && word.chars().all(|c| c.is_ascii_alphanumeric());
+    let dotted = word.contains('.') && word.split('.').all(|part| !part.is_empty());
+    snake_case || camel_case || (dotted && token.ends_with(|c: char| c.is_ascii_alphabetic()))
+}
+
+/// 统计出现的法律标记词数量，每个标记词只计一次
+///
+/// # 参数
+/// - `text`: 待检查的文本
+fn legal_markers(text: &str) -> usize {
+    let lower = text.to_lowercase();
+    LEGAL_MARKERS
+        .iter()
+        .filter(|marker| lower.contains(*marker))
+        .count()
+}
+
+/// 判断文本是否带有聊天的特征：口语词、表情符号或中文语气词
+///
+/// # 参数
+/// - `text`: 待检查的文本
+fn is_chatty(text: &str) -> bool {
+    let emoji = text
+        .chars()
+        .any(|c| matches!(c as u32, 0x1F300..=0x1FAFF | 0x2600..=0x27BF));
+    let word = text
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| CHAT_WORDS.contains(&word.to_lowercase().as_str()));
+    let phrase = CHAT_PHRASES.iter().any(|phrase| text.contains(phrase));
+    emoji || word || phrase || text.contains("!!") || text.contains("??") || text.contains("~")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::domain::{DomainClassifier, TextDomain};
+
+    /// 测试按表判断各种文本的领域
+    #[test]
+    fn test_classify_table() {
+        let cases = [
+            ("// TODO: return null when parse_config() fails", TextDomain::Code),
+            ("Returns the user id, or `None` if the cache is empty.", TextDomain::General),
+            ("/* fallback to self.inner when retries == 0 */", TextDomain::Code),
+            ("# load settings from config.yaml before calling initApp()", TextDomain::Code),
+            ("let total = items.len();", TextDomain::Code),
+            (
+                "The Licensee shall indemnify the Licensor against any liability arising hereunder.",
+                TextDomain::Legal,
+            ),
+            (
+                "Notwithstanding the foregoing, this Agreement shall be construed in accordance with the governing law.",
+                TextDomain::Legal,
+            ),
+            ("甲方违约的，应向乙方支付违约金。", TextDomain::Legal),
+            ("hey, are u coming tonight?", TextDomain::Chat),
+            ("lol that was great 😂", TextDomain::Chat),
+            ("哈哈，明天见", TextDomain::Chat),
+            ("好的，谢谢你！", TextDomain::Chat),
+            ("The museum opens at nine in the morning.", TextDomain::General),
+            ("今天北京的气温比昨天高。", TextDomain::General),
+            ("", TextDomain::General),
+        ];
+        let classifier = DomainClassifier::default();
+        for (text, expected) in cases {
+            assert_eq!(classifier.classify(text), expected, "{}", text);
+        }
+    }
+
+    /// 测试阈值可以调整：较长的口语文本不视为聊天，降低阈值后单个法律标记即可
+    #[test]
+    fn test_thresholds() {
+        let long_chat = format!("hey {}", "and then we walked home ".repeat(20));
+        let classifier = DomainClassifier::default();
+        assert_eq!(classifier.classify(&long_chat), TextDomain::General);
+        assert_eq!(
+            classifier.classify("The tenant shall pay the rent."),
+            TextDomain::General
+        );
+        let classifier = DomainClassifier {
+            min_legal_markers: 1,
+            max_chat_chars: 1000,
+            ..Default::default()
+        };
+        assert_eq!(classifier.classify(&long_chat), TextDomain::Chat);
+        assert_eq!(
+            classifier.classify("The tenant shall pay the rent."),
+            TextDomain::Legal
+        );
+    }
+
+    /// 测试领域名称
+    #[test]
+    fn test_names() {
+        let names = TextDomain::ALL.map(|domain| domain.to_string());
+        assert_eq!(names, ["general", "chat", "legal", "code"]);
+    }
+}
@@ -0,0 +1,753 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::similarity::graphemes;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::sync::Arc;
+
+/// 占位符的起始部分，与`literals`的`⟦0⟧`区分，两种保护可以叠加使用
+const OPEN: &str = "⟦E";
+
+/// 占位符的结束字符（U+27E7）
+const CLOSE: char = '⟧';
+
+/// 识别的ASCII表情，较长的排在以其开头的较短表情之前
+///
+/// 只有前后都是空白或文本边界时才识别，避免把`http://`中的`:/`之类当作表情
+const EMOTICONS: &[&str] = &[
+    ":-)", ":-(", ";-)", ":-D", ":-P", ":-p", ":-/", ":-O", ":-o", ":'(", "^_^", "T_T", "o_O",
+    "O_o", "-_-", ">_<", ":)", ":(", ";)", ":D", ":P", ":p", ":/", ":O", ":o", "XD", "xD", "<3",
+    "^^",
+];
+
+/// 表情符号的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EmojiPolicy {
+    /// 把表情符号替换为占位符后翻译，译文中还原到占位符所在的位置
+    ///
+    /// 翻译服务丢失的表情符号追加在译文末尾，并设置`TranslationDetail::emoji_appended`
+    #[default]
+    Preserve,
+    /// 原样发送给翻译服务，由翻译服务处理
+    Translate,
+    /// 翻译前删除表情符号
+    Strip,
+}
+
+/// 替换表情符号之后的文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedEmoji {
+    /// 把表情符号依次替换为`⟦E0⟧`、`⟦E1⟧`……之后的文本
+    pub text: String,
+    /// 按出现顺序排列的表情符号原文，序号即占位符的编号
+    pub units: Vec<String>,
+}
+
+/// 还原表情符号后的译文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoredEmoji {
+    /// 译文
+    pub text: String,
+    /// 翻译服务丢失、被追加在末尾的表情符号数量
+    pub appended: usize,
+}
+
+/// 字素簇是否为表情符号
+///
+/// 近似Unicode的表情符号判断：以补充平面的表情和符号区块（含区域指示符号组成的国旗）、
+/// 杂项符号和装饰符号区块开头的簇，以及带表情变体选择符（U+FE0F）或键帽（U+20E3）的簇。
+/// 零宽连接符序列、肤色修饰符和国旗已由`graphemes`合并为一个簇
+///
+/// # 参数
+/// - `cluster`: 字素簇
+fn is_emoji(cluster: &str) -> bool {
+    let Some(first) = cluster.chars().next() else {
+        return false;
+    };
+    matches!(
+        first as u32,
+        0x1F000..=0x1FAFF
+            | 0x2600..=0x27BF
+            | 0x231A..=0x231B
+            | 0x23E9..=0x23FA
+            | 0x2B1B..=0x2B1C
+            | 0x2B50
+            | 0x2B55
+    ) || cluster.contains(['\u{FE0F}', '\u{20E3}'])
+}
+
+/// 在文本开头识别ASCII表情
+///
+/// # 参数
+/// - `text`: 文本
+///
+/// # 返回值
+/// 表情的字节长度，后面不是空白或文本结尾时为None
+fn emoticon(text: &str) -> Option<usize> {
+    EMOTICONS
+        .iter()
+        .find(|e| {
+            text.strip_prefix(**e)
+                .is_some_and(|rest| rest.chars().next().is_none_or(char::is_whitespace))
+        })
+        .map(|e| e.len())
+}
+
+/// 解析开头的占位符
+///
+/// # 参数
+/// - `text`: 以`⟦E`开头的文本
+///
+/// # 返回值
+/// 占位符的编号及字节长度，不是占位符时为None
+fn placeholder(text: &str) -> Option<(usize, usize)> {
+    let inner = text.strip_prefix(OPEN)?;
+    let end = inner.find(CLOSE)?;
+    let index = inner.get(..end)?.trim();
+    if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((index.parse().ok()?, OPEN.len() + end + CLOSE.len_utf8()))
+}
+
+/// 文本中的一段
+enum Piece<'a> {
+    /// 表情符号、ASCII表情或原文中已有的占位符
+    Unit(&'a str),
+    /// 普通文本
+    Plain(&'a str),
+}
+
+/// 把文本拆分为表情单元和普通文本
+///
+/// 表情符号按字素簇整体识别；ASCII表情只在前后都是空白或文本边界时识别；
+/// 原文中已有的`⟦E0⟧`形式的文本也作为一个单元，还原时原样放回
+///
+/// # 参数
+/// - `text`: 原文
+///
+/// # 返回值
+/// 按顺序排列、拼接后与原文相同的片段
+fn pieces(text: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut prev = None;
+    // 先按已有的占位符切分，避免占位符的起始字符与前面的零宽连接符等合并为一个簇
+    let mut segment = 0;
+    let mut from = 0;
+    while let Some(found) = text.get(from..).and_then(|rest| rest.find(OPEN)) {
+        let at = from + found;
+        match text.get(at..).and_then(placeholder) {
+            Some((_, len)) => {
+                clusters(
+                    text.get(segment..at).unwrap_or_default(),
+                    &mut prev,
+                    &mut pieces,
+                );
+                pieces.push(Piece::Unit(text.get(at..at + len).unwrap_or_default()));
+                prev = Some(CLOSE);
+                segment = at + len;
+                from = segment;
+            }
+            None => from = at + OPEN.len(),
+        }
+    }
+    clusters(
+        text.get(segment..).unwrap_or_default(),
+        &mut prev,
+        &mut pieces,
+    );
+    pieces
+}
+
+/// 把不含占位符的文本按字素簇拆分为表情单元和普通文本
+///
+/// # 参数
+/// - `text`: 文本
+/// - `prev`: 前一段的最后一个字符，用于判断ASCII表情前面是否为空白
+/// - `pieces`: 追加片段的列表
+fn clusters<'a>(text: &'a str, prev: &mut Option<char>, pieces: &mut Vec<Piece<'a>>) {
+    let mut start = 0;
+    // 已识别的ASCII表情的结束位置，其中的字素簇跳过
+    let mut skip_to = 0;
+    for cluster in graphemes(text) {
+        let at = start;
+        start += cluster.len();
+        if at < skip_to {
+            // 表情的最后一个字符后面跟着组合字符时，簇的剩余部分作为普通文本
+            if let Some(tail) = text.get(skip_to..start).filter(|tail| !tail.is_empty()) {
+                pieces.push(Piece::Plain(tail));
+                *prev = tail.chars().last();
+                skip_to = start;
+            }
+            continue;
+        }
+        let rest = text.get(at..).unwrap_or_default();
+        let len = if is_emoji(cluster) {
+            Some(cluster.len())
+        } else if prev.is_none_or(char::is_whitespace) {
+            emoticon(rest)
+        } else {
+            None
+        };
+        let piece = match len.and_then(|len| rest.get(..len)) {
+            Some(unit) => {
+                skip_to = at + unit.len();
+                Piece::Unit(unit)
+            }
+            None => Piece::Plain(cluster),
+        };
+        let (Piece::Unit(text) | Piece::Plain(text)) = piece;
+        *prev = text.chars().last();
+        pieces.push(piece);
+    }
+}
+
+/// 把表情符号和ASCII表情替换为占位符
+///
+/// 零宽连接符组成的家庭表情、带肤色修饰符的表情和国旗都作为一个整体替换
+///
+/// # 参数
+/// - `text`: 原文
+///
+/// # 返回值
+/// 替换后的文本及按顺序排列的表情符号
+pub fn mask(text: &str) -> MaskedEmoji {
+    let mut masked = String::with_capacity(text.len());
+    let mut units = Vec::new();
+    for piece in pieces(text) {
+        match piece {
+            Piece::Unit(unit) => {
+                masked.push_str(OPEN);
+                masked.push_str(&units.len().to_string());
+                masked.push(CLOSE);
+                units.push(unit.to_string());
+            }
+            Piece::Plain(plain) => masked.push_str(plain),
+        }
+    }
+    MaskedEmoji {
+        text: masked,
+        units,
+    }
+}
+
+/// 删除表情符号和ASCII表情
+///
+/// 删除后不留下多余的空白：表情前的空白在表情后面紧跟空白、标点或文本结尾时一并删除，
+/// 位于开头的表情连同其后的空白一起删除。原文中已有的占位符形式的文本保留
+///
+/// # 参数
+/// - `text`: 原文
+pub fn strip(text: &str) -> String {
+    let pieces = pieces(text);
+    let mut stripped = String::with_capacity(text.len());
+    let mut skip_space = false;
+    for (i, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Unit(unit) if !unit.starts_with(OPEN) => {
+                let next = pieces.get(i + 1).and_then(|piece| match piece {
+                    Piece::Unit(text) | Piece::Plain(text) => text.chars().next(),
+                });
+                if next.is_none_or(|c| !c.is_alphanumeric()) {
+                    let kept = stripped
+                        .trim_end_matches(|c: char| c.is_whitespace() && c != '\n')
+                        .len();
+                    stripped.truncate(kept);
+                }
+                skip_space = stripped.is_empty() || stripped.ends_with('\n');
+            }
+            Piece::Unit(text) | Piece::Plain(text) => {
+                if skip_space && text.chars().all(|c| c.is_whitespace() && c != '\n') {
+                    continue;
+                }
+                skip_space = false;
+                stripped.push_str(text);
+            }
+        }
+    }
+    stripped
+}
+
+impl MaskedEmoji {
+    /// 把译文中的占位符还原为表情符号
+    ///
+    /// 占位符内外允许有空格（部分翻译服务会插入），还原到占位符在译文中的位置；
+    /// 译文中缺失的表情符号按原文顺序追加在末尾，与译文之间用一个空格分隔。
+    /// 重复的占位符只还原第一个，之后的删除；编号超出范围的占位符原样保留
+    ///
+    /// # 参数
+    /// - `output`: 翻译服务返回的译文
+    ///
+    /// # 返回值
+    /// 还原后的译文及追加在末尾的表情符号数量
+    pub fn restore(&self, output: &str) -> RestoredEmoji {
+        let mut restored = String::with_capacity(output.len());
+        let mut seen = vec![false; self.units.len()];
+        let mut rest = output;
+        while let Some(start) = rest.find(OPEN) {
+            let (before, after) = rest.split_at(start);
+            restored.push_str(before);
+            let Some((index, len)) = placeholder(after) else {
+                restored.push_str(OPEN);
+                rest = after.get(OPEN.len()..).unwrap_or_default();
+                continue;
+            };
+            let placeholder = after.get(..len).unwrap_or_default();
+            rest = after.get(len..).unwrap_or_default();
+            match (seen.get_mut(index), self.units.get(index)) {
+                (Some(seen), Some(unit)) => {
+                    if !*seen {
+                        restored.push_str(unit);
+                    }
+                    *seen = true;
+                }
+                _ => restored.push_str(placeholder),
+            }
+        }
+        restored.push_str(rest);
+        let missing = self
+            .units
+            .iter()
+            .zip(&seen)
+            .filter(|(_, seen)| !**seen)
+            .map(|(unit, _)| unit.as_str())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() && !restored.is_empty() && !restored.ends_with(char::is_whitespace) {
+            restored.push(' ');
+        }
+        restored.push_str(&missing.concat());
+        RestoredEmoji {
+            text: restored,
+            appended: missing.len(),
+        }
+    }
+}
+
+/// 按策略处理表情符号的翻译器
+///
+/// 翻译服务常把表情符号删掉、挪到别处，或把`:)`之类的ASCII表情当作标点改写。
+/// 此翻译器在交给内部翻译器之前，按`EmojiPolicy`把表情符号替换为`⟦E0⟧`形式的占位符并在译文中还原、
+/// 原样发送，或直接删除。占位符丢失时不返回错误，而是把缺失的表情符号追加到译文末尾，
+/// 并设置`TranslationDetail::emoji_appended`
+///
+/// 策略默认为`Preserve`，可以用`with_policy`修改，也可以通过`TranslateOptions::emoji_policy`按调用指定
+pub struct EmojiGuard {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 调用选项未指定时的处理策略
+    policy: EmojiPolicy,
+}
+
+impl EmojiGuard {
+    /// 创建表情符号处理翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    pub fn new(inner: Arc<dyn AsyncTranslator>) -> Self {
+        Self {
+            inner,
+            policy: EmojiPolicy::default(),
+        }
+    }
+
+    /// 设置调用选项未指定时的处理策略
+    ///
+    /// # 参数
+    /// - `policy`: 处理策略
+    pub fn with_policy(mut self, policy: EmojiPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for EmojiGuard {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同，按处理后的文本计算
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 直接交给内部翻译器
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.inner.detect_language(text).await
+    }
+
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按默认策略处理表情符号后的译文
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按默认策略处理表情符号后的译文
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 原文中没有表情符号或策略为`Translate`时直接交给内部翻译器
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项，`emoji_policy`优先于`with_policy`设置的策略
+    ///
+    /// # 返回值
+    /// 处理表情符号后的译文
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        match options.emoji_policy.unwrap_or(self.policy) {
+            EmojiPolicy::Translate => {
+                self.inner
+                    .translate_with_options(query, from, to, options)
+                    .await
+            }
+            EmojiPolicy::Strip => {
+                self.inner
+                    .translate_with_options(&strip(query), from, to, options)
+                    .await
+            }
+            EmojiPolicy::Preserve => {
+                let masked = mask(query);
+                if masked.units.is_empty() {
+                    return self
+                        .inner
+                        .translate_with_options(query, from, to, options)
+                        .await;
+                }
+                let mut output = self
+                    .inner
+                    .translate_with_options(&masked.text, from, to, options)
+                    .await?;
+                let restored = masked.restore(&output.text);
+                output.text = restored.text;
+                output.detail.emoji_appended |= restored.appended > 0;
+                Ok(output)
+            }
+        }
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项，`emoji_policy`优先于`with_policy`设置的策略
+    ///
+    /// # 返回值
+    /// 处理表情符号后的译文，任一译文被追加了表情符号时设置`detail.emoji_appended`
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        match options.emoji_policy.unwrap_or(self.policy) {
+            EmojiPolicy::Translate => {
+                self.inner
+                    .translate_vec_with_options(query, from, to, options)
+                    .await
+            }
+            EmojiPolicy::Strip => {
+                let stripped = query.iter().map(|text| strip(text)).collect::<Vec<_>>();
+                self.inner
+                    .translate_vec_with_options(&stripped, from, to, options)
+                    .await
+            }
+            EmojiPolicy::Preserve => {
+                let masked = query.iter().map(|text| mask(text)).collect::<Vec<_>>();
+                if masked.iter().all(|masked| masked.units.is_empty()) {
+                    return self
+                        .inner
+                        .translate_vec_with_options(query, from, to, options)
+                        .await;
+                }
+                let sent = masked
+                    .iter()
+                    .map(|masked| masked.text.clone())
+                    .collect::<Vec<_>>();
+                let mut output = self
+                    .inner
+                    .translate_vec_with_options(&sent, from, to, options)
+                    .await?;
+                if output.text.len() != masked.len() {
+                    return Err(TranslatorError::BatchSizeMismatch(
+                        masked.len(),
+                        output.text.len(),
+                    )
+                    .into());
+                }
+                for (text, masked) in output.text.iter_mut().zip(&masked) {
+                    let restored = masked.restore(text);
+                    *text = restored.text;
+                    output.detail.emoji_appended |= restored.appended > 0;
+                }
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{
+        AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    };
+    use crate::fusion_translator::emoji::{mask, strip, EmojiGuard, EmojiPolicy, RestoredEmoji};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use std::sync::Arc;
+
+    /// 删除第一个占位符的测试翻译器，模拟翻译服务丢失表情符号
+    struct DropFirst;
+
+    #[async_trait::async_trait]
+    impl AsyncTranslator for DropFirst {
+        fn local(&self) -> bool {
+            true
+        }
+
+        async fn translate(
+            &self,
+            query: &str,
+            _from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationOutput> {
+            Ok(TranslationOutput {
+                text: query.replace("⟦E0⟧", "").trim_end().to_string(),
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+
+        async fn translate_vec(
+            &self,
+            query: &[String],
+            from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationListOutput> {
+            let mut text = Vec::new();
+            for q in query {
+                text.push(self.translate(q, from, to).await?.text);
+            }
+            Ok(TranslationListOutput {
+                text,
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// 测试零宽连接符序列、国旗和肤色修饰符作为整体替换
+    #[test]
+    fn test_atomic_sequences() {
+        let masked = mask("Family 👩‍👩‍👧‍👦 from 🇨🇳, nice 👍🏽!");
+        assert_eq!(masked.text, "Family ⟦E0⟧ from ⟦E1⟧, nice ⟦E2⟧!");
+        assert_eq!(masked.units, vec!["👩‍👩‍👧‍👦", "🇨🇳", "👍🏽"]);
+        assert_eq!(mask("🇨🇳🇺🇸").units, vec!["🇨🇳", "🇺🇸"]);
+        assert_eq!(mask("#️⃣ ❤️ ✅").units, vec!["#️⃣", "❤️", "✅"]);
+        assert_eq!(mask("plain text, 中文").units, Vec::<String>::new());
+    }
+
+    /// 测试ASCII表情只在空白或文本边界之间识别
+    #[test]
+    fn test_emoticons() {
+        let masked = mask(":) hello :-D see http://x.org/a:b <3");
+        assert_eq!(masked.text, "⟦E0⟧ hello ⟦E1⟧ see http://x.org/a:b ⟦E2⟧");
+        assert_eq!(masked.units, vec![":)", ":-D", "<3"]);
+        assert!(mask("f(x:)) a:) :)b").units.is_empty());
+    }
+
+    /// 测试翻译服务调换占位符顺序或在其中插入空格时还原到对应位置
+    #[test]
+    fn test_restore_reordered() {
+        let masked = mask("I ❤️ 🇨🇳");
+        assert_eq!(
+            masked.restore("⟦ E1 ⟧ 我 ⟦E0⟧"),
+            RestoredEmoji {
+                text: "⟦ E1 ⟧ 我 ❤️ 🇨🇳".to_string(),
+                appended: 1,
+            }
+        );
+        assert_eq!(masked.restore("⟦E1 ⟧我⟦E0⟧").text, "🇨🇳我❤️");
+        assert_eq!(masked.restore("⟦E1⟧ ⟦E1⟧ ⟦E0⟧ ⟦E7⟧").text, "🇨🇳  ❤️ ⟦E7⟧");
+    }
+
+    /// 测试翻译服务丢失的占位符按原顺序追加在末尾
+    #[test]
+    fn test_restore_dropped() {
+        let masked = mask("👩‍👩‍👧‍👦 a 🇨🇳 b 👍🏽");
+        assert_eq!(
+            masked.restore("A ⟦E1⟧ B"),
+            RestoredEmoji {
+                text: "A 🇨🇳 B 👩‍👩‍👧‍👦👍🏽".to_string(),
+                appended: 2,
+            }
+        );
+        assert_eq!(masked.restore("").text, "👩‍👩‍👧‍👦🇨🇳👍🏽");
+        assert_eq!(masked.restore("x\n").text, "x\n👩‍👩‍👧‍👦🇨🇳👍🏽");
+    }
+
+    /// 测试删除表情符号时不留下多余的空白
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip("Nice 👍 work"), "Nice work");
+        assert_eq!(strip("Thanks 👍🏽"), "Thanks");
+        assert_eq!(strip("👩‍👩‍👧‍👦 great :)"), "great");
+        assert_eq!(strip("Done ✅, next"), "Done, next");
+        assert_eq!(strip("a\n🇨🇳 b"), "a\nb");
+        assert_eq!(strip("keep ⟦E0⟧ as is"), "keep ⟦E0⟧ as is");
+    }
+
+    /// 测试翻译器按策略处理表情符号，丢失的表情符号追加在末尾并设置标记
+    #[tokio::test]
+    async fn test_guard() {
+        let mock = Arc::new(MockTranslator::new());
+        let guard = EmojiGuard::new(mock.clone());
+        let output = guard
+            .translate("we 👩‍👩‍👧‍👦 love 🇨🇳", None, &Language::German)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "WE 👩‍👩‍👧‍👦 LOVE 🇨🇳");
+        assert!(!output.detail.emoji_appended);
+        assert_eq!(mock.requests(), vec![vec!["we ⟦E0⟧ love ⟦E1⟧".to_string()]]);
+
+        let options = TranslateOptions::new().with_emoji_policy(EmojiPolicy::Strip);
+        let output = guard
+            .translate_vec_with_options(
+                &["ok 👍🏽".to_string(), "no emoji".to_string()],
+                None,
+                &Language::German,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["OK", "NO EMOJI"]);
+
+        let guard = EmojiGuard::new(mock.clone()).with_policy(EmojiPolicy::Translate);
+        guard
+            .translate("hi :)", None, &Language::German)
+            .await
+            .unwrap();
+        assert_eq!(mock.requests().last().unwrap(), &vec!["hi :)".to_string()]);
+    }
+
+    /// 测试批量翻译中任一译文丢失占位符时设置标记
+    #[tokio::test]
+    async fn test_guard_dropped() {
+        let guard = EmojiGuard::new(Arc::new(DropFirst));
+        let output = guard
+            .translate_vec(
+                &["b".to_string(), "a 👍🏽 c 🇨🇳".to_string()],
+                None,
+                &Language::German,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["b", "a  c 🇨🇳 👍🏽"]);
+        assert!(output.detail.emoji_appended);
+        let output = guard
+            .translate("x 🇨🇳", None, &Language::German)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "x 🇨🇳");
+        assert!(output.detail.emoji_appended);
+    }
+
+    /// 模糊测试：任意输入替换后原样还原，删除不会panic
+    #[test]
+    fn test_fuzz_roundtrip() {
+        let corpus = [
+            "Family 👩‍👩‍👧‍👦 from 🇨🇳 👍🏽",
+            ":) ⟦E0⟧ <3 #️⃣ ❤️",
+            "🏴󠁧󠁢󠁳󠁣󠁴󠁿 🇺🇸🇨 x:) ⟦E ⟧",
+        ];
+        for input in fuzz::inputs(708, &corpus) {
+            let masked = mask(&input);
+            let restored = masked.restore(&masked.text);
+            assert_eq!(restored.text, input);
+            assert_eq!(restored.appended, 0);
+            let _ = strip(&input);
+        }
+    }
+}
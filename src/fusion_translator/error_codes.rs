@@ -0,0 +1,210 @@
+use crate::fusion_translator::translator_error::ErrorKind;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 内置的错误代码表
+///
+/// 每个文件是错误代码到`ErrorCodeInfo`的JSON对象，首次查询时解析
+const TABLES: &[(&str, &str)] = &[
+    (
+        "baidu",
+        include_str!("../../tests/fixtures/errors/baidu.json"),
+    ),
+    (
+        "youdao",
+        include_str!("../../tests/fixtures/errors/youdao.json"),
+    ),
+];
+
+/// 错误说明使用的语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+/// 一个错误代码的说明
+#[derive(Debug, Clone, Deserialize)]
+pub struct ErrorCodeInfo {
+    /// 中文说明和解决方案
+    pub message_zh: String,
+    /// 英文说明和解决方案
+    pub message_en: String,
+    /// 是否值得重试
+    pub retryable: bool,
+    /// 错误分类
+    #[serde(deserialize_with = "error_kind")]
+    pub category: ErrorKind,
+}
+
+impl ErrorCodeInfo {
+    /// 获取指定语言的说明
+    ///
+    /// # 参数
+    /// - `locale`: 说明使用的语言
+    pub fn message(&self, locale: Locale) -> &str {
+        match locale {
+            Locale::Zh => &self.message_zh,
+            Locale::En => &self.message_en,
+        }
+    }
+}
+
+/// 按`ErrorKind::as_str`的名称反序列化错误分类
+fn error_kind<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ErrorKind, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    ErrorKind::parse(&name)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown error category: {}", name)))
+}
+
+/// 解析一个错误代码表
+///
+/// # 参数
+/// - `json`: 代码表内容
+fn parse_table(json: &str) -> serde_json::Result<HashMap<String, ErrorCodeInfo>> {
+    serde_json::from_str(json)
+}
+
+/// 解析后的全部错误代码表
+fn tables() -> &'static HashMap<&'static str, HashMap<String, ErrorCodeInfo>> {
+    static PARSED: OnceLock<HashMap<&'static str, HashMap<String, ErrorCodeInfo>>> =
+        OnceLock::new();
+    PARSED.get_or_init(|| {
+        TABLES
+            .iter()
+            .map(|(vendor, json)| {
+                let table = parse_table(json)
+                    .unwrap_or_else(|e| panic!("invalid {} error table: {}", vendor, e));
+                (*vendor, table)
+            })
+            .collect()
+    })
+}
+
+/// 有错误代码表的翻译服务
+pub fn vendors() -> Vec<&'static str> {
+    TABLES.iter().map(|(vendor, _)| *vendor).collect()
+}
+
+/// 查询错误代码
+///
+/// # 参数
+/// - `vendor`: 翻译服务名称，如`baidu`
+/// - `code`: 翻译服务返回的错误代码
+///
+/// # 返回值
+/// 错误代码的说明，翻译服务或代码不在表中时为None
+pub fn lookup(vendor: &str, code: &str) -> Option<&'static ErrorCodeInfo> {
+    tables().get(vendor)?.get(code)
+}
+
+/// 获取错误说明和解决方案
+///
+/// # 参数
+/// - `vendor`: 翻译服务名称，如`baidu`
+/// - `code`: 翻译服务返回的错误代码
+/// - `locale`: 说明使用的语言
+///
+/// # 返回值
+/// 错误代码表中的说明，翻译服务或代码不在表中时为“unknown <vendor> error <code>”
+pub fn advice(vendor: &str, code: &str, locale: Locale) -> String {
+    match lookup(vendor, code) {
+        Some(info) => info.message(locale).to_string(),
+        None => format!("unknown {} error {}", vendor, code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::error_codes::{
+        advice, lookup, parse_table, vendors, Locale, TABLES,
+    };
+    use crate::fusion_translator::retry::is_retryable;
+    use crate::fusion_translator::translator_error::{ApiError, ErrorKind, TranslatorError};
+
+    /// 测试每个代码表都能解析，且每一项的说明都不为空
+    #[test]
+    fn test_tables_parse() {
+        assert_eq!(vendors(), ["baidu", "youdao"]);
+        for (vendor, json) in TABLES {
+            let table = parse_table(json).unwrap_or_else(|e| panic!("{}: {}", vendor, e));
+            assert!(!table.is_empty(), "{}", vendor);
+            for (code, info) in &table {
+                assert!(!code.is_empty() && code.chars().all(|c| c.is_ascii_digit()));
+                assert!(!info.message_zh.trim().is_empty(), "{} {}", vendor, code);
+                assert!(!info.message_en.trim().is_empty(), "{} {}", vendor, code);
+                assert!(info.message_en.is_ascii(), "{} {}", vendor, code);
+                assert_ne!(info.category, ErrorKind::Unknown, "{} {}", vendor, code);
+            }
+        }
+        assert!(parse_table(r#"{"1": {"message_zh": "a", "message_en": "a", "retryable": false, "category": "bogus"}}"#).is_err());
+    }
+
+    /// 测试查询和未知代码的说明
+    #[test]
+    fn test_lookup() {
+        let info = lookup("baidu", "54003").unwrap();
+        assert!(info.retryable);
+        assert_eq!(info.category, ErrorKind::RateLimited);
+        assert!(info.message(Locale::Zh).starts_with("访问频率受限"));
+        assert!(info
+            .message(Locale::En)
+            .starts_with("Access frequency limited"));
+
+        assert_eq!(lookup("baidu", "99999").map(|_| ()), None);
+        assert_eq!(lookup("nobody", "54003").map(|_| ()), None);
+        assert_eq!(
+            advice("baidu", "99999", Locale::En),
+            "unknown baidu error 99999"
+        );
+        assert_eq!(
+            advice("niutrans", "10001", Locale::Zh),
+            "unknown niutrans error 10001"
+        );
+    }
+
+    /// 测试错误分类、重试判断和说明都来自代码表
+    #[test]
+    fn test_api_error() {
+        let baidu = |code: &str| {
+            TranslatorError::ApiError(ApiError::Baidu {
+                code: code.to_string(),
+                message: String::new(),
+            })
+        };
+        let youdao = |code: &str| {
+            TranslatorError::ApiError(ApiError::Youdao {
+                code: code.to_string(),
+            })
+        };
+        assert_eq!(baidu("54003").kind(), ErrorKind::RateLimited);
+        assert_eq!(baidu("20003").kind(), ErrorKind::ContentRejected);
+        assert_eq!(baidu("58001").kind(), ErrorKind::Language);
+        assert_eq!(baidu("54001").kind(), ErrorKind::Api);
+        assert_eq!(baidu("12345").kind(), ErrorKind::Api);
+        assert_eq!(youdao("411").kind(), ErrorKind::RateLimited);
+        assert_eq!(youdao("103").kind(), ErrorKind::TooLong);
+
+        assert!(is_retryable(&baidu("52001").into()));
+        assert!(is_retryable(&youdao("411").into()));
+        assert!(!is_retryable(&baidu("54001").into()));
+        assert!(!is_retryable(&baidu("12345").into()));
+
+        let error = ApiError::Youdao {
+            code: "202".to_string(),
+        };
+        assert_eq!(
+            error.advice(Locale::En),
+            "Signature check failed.\nSolution: check the application ID and secret."
+        );
+        assert!(error.to_string().contains("签名检验失败"));
+        let unknown = ApiError::Youdao {
+            code: "999".to_string(),
+        };
+        assert_eq!(unknown.advice(Locale::Zh), "unknown youdao error 999");
+    }
+}
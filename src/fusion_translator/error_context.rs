@@ -0,0 +1,241 @@
+use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use std::fmt;
+use std::ops::Range;
+
+/// 错误上下文中最多记录的原文数量
+pub const MAX_LOGGED_TEXTS: usize = 3;
+
+/// 错误上下文中每个原文最多保留的字符数
+pub const MAX_LOGGED_CHARS: usize = 200;
+
+/// 出错的翻译操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// 翻译单个文本
+    Translate,
+    /// 批量翻译
+    TranslateVec,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operation::Translate => "translate",
+            Operation::TranslateVec => "translate_vec",
+        })
+    }
+}
+
+/// 翻译服务调用的错误上下文
+///
+/// 翻译器在调用失败时把它附加到错误上，记录翻译服务、操作、语言对和文本数量，
+/// 用`{:#}`格式化错误即可看到完整的上下文链。原文默认不记录，
+/// 只有开启`TranslateOptions::log_query_text`时才保留截断后的前几个原文。
+///
+/// 错误在多层调用中传递时只保留最内层的上下文，可以通过`anyhow::Error::downcast_ref`取出
+#[derive(Debug, Clone)]
+pub struct QueryContext {
+    /// 翻译服务名称
+    pub backend: &'static str,
+    /// 出错的操作
+    pub operation: Operation,
+    /// 源语言，None表示自动检测
+    pub from: Option<Language>,
+    /// 目标语言
+    pub to: Language,
+    /// 本次调用的文本数量
+    pub items: usize,
+    /// 截断后的原文，未开启`log_query_text`时为空
+    pub texts: Vec<String>,
+}
+
+impl QueryContext {
+    /// 单个文本翻译的上下文
+    ///
+    /// # 参数
+    /// - `backend`: 翻译服务名称
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    pub fn single(
+        backend: &'static str,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> Self {
+        Self::new(backend, Operation::Translate, &[query], from, to, options)
+    }
+
+    /// 批量翻译的上下文
+    ///
+    /// # 参数
+    /// - `backend`: 翻译服务名称
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    pub fn batch(
+        backend: &'static str,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> Self {
+        Self::new(backend, Operation::TranslateVec, query, from, to, options)
+    }
+
+    /// 按选项决定是否记录原文
+    ///
+    /// # 参数
+    /// - `backend`: 翻译服务名称
+    /// - `operation`: 出错的操作
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    fn new(
+        backend: &'static str,
+        operation: Operation,
+        query: &[impl AsRef<str>],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> Self {
+        let texts = if options.log_query_text {
+            query
+                .iter()
+                .take(MAX_LOGGED_TEXTS)
+                .map(|text| truncate(text.as_ref()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            backend,
+            operation,
+            from,
+            to: *to,
+            items: query.len(),
+            texts,
+        }
+    }
+}
+
+impl fmt::Display for QueryContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.backend, self.operation)?;
+        match self.from {
+            Some(from) => write!(f, "{:?}", from)?,
+            None => f.write_str("auto")?,
+        }
+        write!(f, "->{:?}", self.to)?;
+        if self.operation == Operation::TranslateVec {
+            write!(f, " ({} items)", self.items)?;
+        }
+        if !self.texts.is_empty() {
+            write!(f, ", query {:?}", self.texts)?;
+            if self.items > self.texts.len() {
+                write!(f, " and {} more", self.items - self.texts.len())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 截断过长的原文
+///
+/// # 参数
+/// - `text`: 原文
+///
+/// # 返回值
+/// 超过`MAX_LOGGED_CHARS`个字符时截断并加上省略号
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_LOGGED_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// 为翻译服务调用的错误附加上下文
+///
+/// 错误中已有`QueryContext`（如拆分后的子请求已经附加过）时保持不变，避免重复
+///
+/// # 参数
+/// - `result`: 翻译服务调用的结果
+/// - `context`: 生成错误上下文，只在出错时调用
+pub(crate) fn with_query_context<T>(
+    result: anyhow::Result<T>,
+    context: impl FnOnce() -> QueryContext,
+) -> anyhow::Result<T> {
+    result.map_err(|err| {
+        if err.downcast_ref::<QueryContext>().is_some() {
+            err
+        } else {
+            err.context(context())
+        }
+    })
+}
+
+/// 分块翻译的错误上下文
+///
+/// # 参数
+/// - `index`: 分块的下标，从0开始
+/// - `items`: 分块在输入中的下标范围
+pub(crate) fn chunk_context(index: usize, items: &Range<usize>) -> String {
+    format!("chunk {} (items {}..{})", index + 1, items.start, items.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::error_context::{with_query_context, QueryContext};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+
+    /// 测试默认不记录原文，开启`log_query_text`后记录截断的原文
+    #[test]
+    fn test_query_text_opt_in() {
+        let query = vec!["secret".to_string(), "x".repeat(300)];
+        let context = QueryContext::batch(
+            "mock",
+            &query,
+            None,
+            &Language::Chinese,
+            &TranslateOptions::default(),
+        );
+        assert_eq!(
+            context.to_string(),
+            "mock translate_vec auto->Chinese (2 items)"
+        );
+
+        let options = TranslateOptions::new().with_log_query_text(true);
+        let context = QueryContext::batch("mock", &query, None, &Language::Chinese, &options);
+        let text = context.to_string();
+        assert!(text.contains("\"secret\""));
+        assert!(text.contains(&format!("{}...", "x".repeat(200))));
+        assert!(!text.contains(&"x".repeat(201)));
+    }
+
+    /// 测试只保留最内层的上下文，且仍能取出原始错误
+    #[test]
+    fn test_innermost_context_kept() {
+        let options = TranslateOptions::default();
+        let inner: anyhow::Result<()> = Err(TranslatorError::NoResponse.into());
+        let inner = with_query_context(inner, || {
+            QueryContext::single("inner", "", None, &Language::English, &options)
+        });
+        let err = with_query_context(inner, || {
+            QueryContext::single("outer", "", None, &Language::English, &options)
+        })
+        .unwrap_err();
+        assert_eq!(err.downcast_ref::<QueryContext>().unwrap().backend, "inner");
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::NoResponse)
+        ));
+        assert!(!format!("{:#}", err).contains("outer"));
+    }
+}
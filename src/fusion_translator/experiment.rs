@@ -0,0 +1,552 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::usage::UsageTracker;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use tokio::time::Instant;
+
+/// 实验分组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExperimentArm {
+    /// 对照组
+    Control,
+    /// 实验组
+    Treatment,
+}
+
+impl ExperimentArm {
+    /// 分组名称，同时用作用量记录的名称和`TranslationDetail::backend`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Control => "control",
+            Self::Treatment => "treatment",
+        }
+    }
+}
+
+impl std::fmt::Display for ExperimentArm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 实验的分组统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExperimentAssignments {
+    /// 分到对照组的文本数
+    pub control: usize,
+    /// 分到实验组的文本数
+    pub treatment: usize,
+    /// 通过`TranslateOptions::experiment_arm`指定分组的文本数，已计入上面两项
+    pub forced: usize,
+}
+
+impl ExperimentAssignments {
+    /// 实验组所占的比例，没有文本时为0
+    pub fn treatment_fraction(&self) -> f64 {
+        let total = self.control + self.treatment;
+        if total == 0 {
+            0.0
+        } else {
+            self.treatment as f64 / total as f64
+        }
+    }
+}
+
+/// 对比两个翻译器的A/B实验
+///
+/// 每个文本按`(salt, 文本)`的SHA-256哈希分组，哈希落在`fraction`以内的交给实验组，其余交给对照组。
+/// 相同的文本总是分到同一组，外层缓存的译文与分组保持一致；更换`salt`即可重新分组。
+/// 每次调用分组的用量和耗时记录在`UsageTracker`中，名称为分组名称（`control`/`treatment`），
+/// 译文的`TranslationDetail::backend`同样标记为分组名称
+pub struct ExperimentTranslator {
+    /// 对照组的翻译器
+    control: Arc<dyn AsyncTranslator>,
+    /// 实验组的翻译器
+    treatment: Arc<dyn AsyncTranslator>,
+    /// 分到实验组的比例，取值0.0~1.0
+    fraction: f64,
+    /// 分组哈希的盐
+    salt: String,
+    /// 分组的用量记录
+    usage: Arc<UsageTracker>,
+    /// 分组统计
+    assignments: Mutex<ExperimentAssignments>,
+}
+
+impl ExperimentTranslator {
+    /// 创建A/B实验
+    ///
+    /// # 参数
+    /// - `control`: 对照组的翻译器
+    /// - `treatment`: 实验组的翻译器
+    /// - `fraction`: 分到实验组的比例，超出0.0~1.0时取最近的边界，NaN视为0
+    /// - `salt`: 分组哈希的盐，不同实验应使用不同的盐
+    pub fn new(
+        control: Arc<dyn AsyncTranslator>,
+        treatment: Arc<dyn AsyncTranslator>,
+        fraction: f64,
+        salt: impl Into<String>,
+    ) -> Self {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        Self {
+            control,
+            treatment,
+            fraction,
+            salt: salt.into(),
+            usage: Arc::new(UsageTracker::new()),
+            assignments: Mutex::new(ExperimentAssignments::default()),
+        }
+    }
+
+    /// 使用共享的用量记录
+    ///
+    /// # 参数
+    /// - `usage`: 用量记录
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// 分组的用量记录
+    pub fn usage(&self) -> &Arc<UsageTracker> {
+        &self.usage
+    }
+
+    /// 当前的分组统计
+    pub fn assignments(&self) -> ExperimentAssignments {
+        *self.assignments.lock().unwrap()
+    }
+
+    /// 文本所在的分组
+    ///
+    /// 只取决于`salt`、`fraction`和文本，不计入分组统计
+    ///
+    /// # 参数
+    /// - `query`: 原文
+    pub fn assign(&self, query: &str) -> ExperimentArm {
+        let mut hasher = Sha256::new();
+        hasher.update((self.salt.len() as u64).to_le_bytes());
+        hasher.update(self.salt.as_bytes());
+        hasher.update(query.as_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        // 取高53位，保证转换为f64时没有舍入
+        let point = (u64::from_be_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64;
+        if point < self.fraction {
+            ExperimentArm::Treatment
+        } else {
+            ExperimentArm::Control
+        }
+    }
+
+    /// 确定文本的分组并计入统计
+    ///
+    /// # 参数
+    /// - `query`: 原文
+    /// - `options`: 本次调用的选项，其中指定的分组优先
+    fn arm_for(&self, query: &str, options: &TranslateOptions) -> ExperimentArm {
+        let arm = options.experiment_arm.unwrap_or_else(|| self.assign(query));
+        let mut assignments = self.assignments.lock().unwrap();
+        match arm {
+            ExperimentArm::Control => assignments.control += 1,
+            ExperimentArm::Treatment => assignments.treatment += 1,
+        }
+        if options.experiment_arm.is_some() {
+            assignments.forced += 1;
+        }
+        arm
+    }
+
+    /// 分组的翻译器
+    ///
+    /// # 参数
+    /// - `arm`: 分组
+    fn translator(&self, arm: ExperimentArm) -> &dyn AsyncTranslator {
+        match arm {
+            ExperimentArm::Control => self.control.as_ref(),
+            ExperimentArm::Treatment => self.treatment.as_ref(),
+        }
+    }
+
+    /// 用分组的翻译器翻译多个文本，记录用量并标记译文
+    ///
+    /// # 参数
+    /// - `arm`: 分组
+    /// - `query`: 分到该组的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    async fn translate_arm(
+        &self,
+        arm: ExperimentArm,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let start = Instant::now();
+        let result = self
+            .translator(arm)
+            .translate_vec_with_options(query, from, to, options)
+            .await;
+        let chars = query.iter().map(|text| text.chars().count()).sum();
+        self.usage.record(
+            arm.as_str(),
+            query.len(),
+            chars,
+            start.elapsed(),
+            result.is_ok(),
+        );
+        let mut output = result?;
+        output.detail.backend = Some(arm.as_str().to_string());
+        Ok(output)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for ExperimentTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 两组都是本地翻译器时为true
+    fn local(&self) -> bool {
+        self.control.local() && self.treatment.local()
+    }
+
+    /// 是否按请求量计费
+    ///
+    /// 任一组计费时为true
+    fn paid(&self) -> bool {
+        self.control.paid() || self.treatment.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 交给文本所在分组的翻译器，不计入分组统计
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.translator(self.assign(text))
+            .detect_language(text)
+            .await
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 两组同时预热
+    async fn warm_up(&self) {
+        futures_util::future::join(self.control.warm_up(), self.treatment.warm_up()).await;
+    }
+
+    /// 支持的语言
+    ///
+    /// 文本可能分到任一组，因此为两组都支持的语言；一组不限时取另一组
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        match (
+            self.control.supported_languages(),
+            self.treatment.supported_languages(),
+        ) {
+            (Some(control), Some(treatment)) => Some(
+                control
+                    .into_iter()
+                    .filter(|lang| treatment.contains(lang))
+                    .collect(),
+            ),
+            (control, treatment) => control.or(treatment),
+        }
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 文本所在分组的翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按原顺序合并的两组翻译结果
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 选项中指定了`experiment_arm`时交给该组，不再按哈希分组
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 文本所在分组的翻译结果，`detail.backend`为分组名称
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let arm = self.arm_for(query, options);
+        let start = Instant::now();
+        let result = self
+            .translator(arm)
+            .translate_with_options(query, from, to, options)
+            .await;
+        self.usage.record(
+            arm.as_str(),
+            1,
+            query.chars().count(),
+            start.elapsed(),
+            result.is_ok(),
+        );
+        let mut output = result?;
+        output.detail.backend = Some(arm.as_str().to_string());
+        Ok(output)
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 每个文本单独分组，每组的文本合并为一次调用，译文按原顺序合并；任一组失败时返回该错误。
+    /// 只用到一组时`detail`取自该组，`detail.backend`为分组名称；用到两组时`detail`取自对照组，
+    /// `possibly_untranslated`、`emoji_appended`和`substitutions`合并两组的结果，`detail.backend`为None
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 与`query`一一对应的译文
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let arms = query
+            .iter()
+            .map(|text| self.arm_for(text, options))
+            .collect::<Vec<_>>();
+        let mut outputs = Vec::new();
+        for arm in [ExperimentArm::Control, ExperimentArm::Treatment] {
+            let (indices, texts): (Vec<usize>, Vec<String>) = arms
+                .iter()
+                .zip(query)
+                .enumerate()
+                .filter(|(_, (a, _))| **a == arm)
+                .map(|(i, (_, text))| (i, text.clone()))
+                .unzip();
+            if texts.is_empty() {
+                continue;
+            }
+            let output = self.translate_arm(arm, &texts, from, to, options).await?;
+            outputs.push((indices, output));
+        }
+
+        let mut text = vec![String::new(); query.len()];
+        let mut merged: Option<TranslationListOutput> = None;
+        for (indices, output) in outputs {
+            for (i, translation) in indices.into_iter().zip(output.text.iter()) {
+                text[i] = translation.clone();
+            }
+            match &mut merged {
+                None => merged = Some(output),
+                Some(merged) => {
+                    merged.lang = merged.lang.or(output.lang);
+                    merged.detail.possibly_untranslated |= output.detail.possibly_untranslated;
+                    merged.detail.emoji_appended |= output.detail.emoji_appended;
+                    merged
+                        .detail
+                        .substitutions
+                        .extend(output.detail.substitutions);
+                    merged.detail.backend = None;
+                }
+            }
+        }
+        let mut output = merged.unwrap_or_default();
+        output.text = text;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::experiment::{ExperimentArm, ExperimentTranslator};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use std::sync::Arc;
+
+    /// 创建对照组和实验组分别带后缀的实验
+    fn experiment(fraction: f64, salt: &str) -> ExperimentTranslator {
+        ExperimentTranslator::new(
+            Arc::new(MockTranslator::new().with_suffix(" [caiyun]")),
+            Arc::new(MockTranslator::new().with_suffix(" [deepl]")),
+            fraction,
+            salt,
+        )
+    }
+
+    /// 测试大量文本的分组比例接近设置的比例，并且同一文本总是分到同一组
+    #[test]
+    fn test_assignment_fraction() {
+        let queries = (0..20_000)
+            .map(|i| format!("query number {}", i))
+            .collect::<Vec<_>>();
+        for fraction in [0.1, 0.25, 0.5] {
+            let first = experiment(fraction, "caiyun-vs-deepl");
+            let second = experiment(fraction, "caiyun-vs-deepl");
+            let treatment = queries
+                .iter()
+                .filter(|query| first.assign(query) == ExperimentArm::Treatment)
+                .count();
+            let observed = treatment as f64 / queries.len() as f64;
+            // 二项分布的标准差不超过0.0036，允许约4倍标准差的偏差
+            assert!(
+                (observed - fraction).abs() < 0.015,
+                "{} vs {}",
+                observed,
+                fraction
+            );
+            assert!(queries
+                .iter()
+                .all(|query| first.assign(query) == second.assign(query)));
+        }
+
+        let resalted = experiment(0.5, "another-experiment");
+        let base = experiment(0.5, "caiyun-vs-deepl");
+        let moved = queries
+            .iter()
+            .filter(|query| base.assign(query) != resalted.assign(query))
+            .count();
+        assert!(moved > queries.len() / 3, "{}", moved);
+
+        assert!(queries
+            .iter()
+            .all(|query| experiment(0.0, "s").assign(query) == ExperimentArm::Control));
+        assert!(queries
+            .iter()
+            .all(|query| experiment(1.0, "s").assign(query) == ExperimentArm::Treatment));
+    }
+
+    /// 测试重复翻译相同文本总是使用同一组，译文标记分组并记录用量
+    #[tokio::test]
+    async fn test_translate_is_sticky() {
+        let translator = experiment(0.5, "sticky");
+        for i in 0..50 {
+            let query = format!("hello {}", i);
+            let arm = translator.assign(&query);
+            for _ in 0..3 {
+                let output = translator
+                    .translate(&query, None, &Language::Chinese)
+                    .await
+                    .unwrap();
+                assert_eq!(output.detail.backend.as_deref(), Some(arm.as_str()));
+                let suffix = match arm {
+                    ExperimentArm::Control => "[caiyun]",
+                    ExperimentArm::Treatment => "[deepl]",
+                };
+                assert!(output.text.ends_with(suffix), "{}", output.text);
+            }
+        }
+        let assignments = translator.assignments();
+        assert_eq!(assignments.control + assignments.treatment, 150);
+        assert_eq!(assignments.forced, 0);
+        let usage = translator.usage();
+        assert_eq!(usage.get("control").calls, assignments.control);
+        assert_eq!(usage.get("treatment").calls, assignments.treatment);
+        assert_eq!(
+            usage.get("control").texts + usage.get("treatment").texts,
+            150
+        );
+    }
+
+    /// 测试批量翻译按文本分组后按原顺序合并
+    #[tokio::test]
+    async fn test_translate_vec_merges_arms() {
+        let translator = experiment(0.5, "batch");
+        let query = (0..40).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let output = translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text.len(), query.len());
+        for (source, translation) in query.iter().zip(&output.text) {
+            let suffix = match translator.assign(source) {
+                ExperimentArm::Control => " [caiyun]",
+                ExperimentArm::Treatment => " [deepl]",
+            };
+            assert_eq!(*translation, format!("{}{}", source.to_uppercase(), suffix));
+        }
+        assert_eq!(output.detail.backend, None);
+        assert_eq!(translator.usage().get("control").calls, 1);
+        assert_eq!(translator.usage().get("treatment").calls, 1);
+        assert!(translator.assignments().treatment_fraction() > 0.0);
+    }
+
+    /// 测试通过调用选项强制使用指定分组
+    #[tokio::test]
+    async fn test_forced_arm() {
+        let translator = experiment(0.0, "forced");
+        let options = TranslateOptions::new().with_experiment_arm(ExperimentArm::Treatment);
+        let output = translator
+            .translate_with_options("hello", None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO [deepl]");
+        assert_eq!(output.detail.backend.as_deref(), Some("treatment"));
+
+        let query = vec!["a".to_string(), "b".to_string()];
+        let output = translator
+            .translate_vec_with_options(&query, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["A [deepl]", "B [deepl]"]);
+        assert_eq!(output.detail.backend.as_deref(), Some("treatment"));
+
+        let assignments = translator.assignments();
+        assert_eq!(
+            (
+                assignments.control,
+                assignments.treatment,
+                assignments.forced
+            ),
+            (0, 3, 3)
+        );
+    }
+}
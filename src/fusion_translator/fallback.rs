@@ -0,0 +1,660 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Freshness, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::cache::CachedTranslator;
+use crate::fusion_translator::capability::{capabilities, UnservedPair};
+use crate::fusion_translator::provenance::{Detailed, ProvenanceEvent};
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+use futures_util::future::join_all;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 故障转移的延迟预算
+///
+/// 从第一个翻译器开始计时，每个翻译器（阶段）最多等待该阶段的时限和剩余预算中较短的一个，
+/// 超时后换用下一个翻译器；预算用完时不再尝试后续翻译器。
+/// 例如总预算800毫秒、第一阶段500毫秒：第一个翻译器500毫秒内没有结果时，第二个翻译器最多再等300毫秒
+#[derive(Clone)]
+pub struct LatencyBudget {
+    /// 总预算
+    total: Duration,
+    /// 各阶段的时限，按翻译器顺序排列；没有列出的阶段只受剩余预算限制
+    stage_limits: Vec<Duration>,
+    /// 没有得到结果时查询旧译文的缓存
+    stale_cache: Option<Arc<CachedTranslator>>,
+}
+
+impl LatencyBudget {
+    /// 创建延迟预算，各阶段只受剩余预算限制
+    ///
+    /// # 参数
+    /// - `total`: 总预算
+    pub fn new(total: Duration) -> Self {
+        Self {
+            total,
+            stage_limits: Vec::new(),
+            stale_cache: None,
+        }
+    }
+
+    /// 设置各阶段的时限
+    ///
+    /// # 参数
+    /// - `limits`: 按翻译器顺序排列的时限，没有列出的阶段只受剩余预算限制
+    pub fn with_stage_limits(mut self, limits: impl IntoIterator<Item = Duration>) -> Self {
+        self.stage_limits = limits.into_iter().collect();
+        self
+    }
+
+    /// 设置没有得到结果时查询旧译文的缓存
+    ///
+    /// 预算用完或所有翻译器都失败后，如果缓存中有该原文的译文（包括已过期的条目），
+    /// 返回该译文，`TranslationDetail::freshness`为`Freshness::Stale`。
+    /// 缓存通常也作为某一阶段的翻译器，如`CachedTranslator`包装的备用翻译器
+    ///
+    /// # 参数
+    /// - `cache`: 缓存翻译器
+    pub fn with_stale_cache(mut self, cache: Arc<CachedTranslator>) -> Self {
+        self.stale_cache = Some(cache);
+        self
+    }
+
+    /// 总预算
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// 某一阶段最多等待的时长
+    ///
+    /// # 参数
+    /// - `stage`: 阶段序号，从0开始
+    /// - `elapsed`: 之前的阶段已用的时间
+    ///
+    /// # 返回值
+    /// 预算已用完时为None
+    fn stage_timeout(&self, stage: usize, elapsed: Duration) -> Option<Duration> {
+        let remaining = self
+            .total
+            .checked_sub(elapsed)
+            .filter(|remaining| !remaining.is_zero())?;
+        Some(
+            self.stage_limits
+                .get(stage)
+                .map_or(remaining, |limit| remaining.min(*limit)),
+        )
+    }
+}
+
+/// 故障转移翻译器
+///
+/// 按顺序尝试多个翻译器，第一个成功的结果即为译文，`TranslationDetail::freshness`标记结果
+/// 来自第一个翻译器（`Fresh`）还是之后的翻译器（`Fallback`）；
+/// 取消和输入无效的错误换用其他翻译器也不会成功，直接返回。
+/// 全部失败时返回最后一个翻译器的错误，并注明尝试过的翻译器数量。
+/// 设置`LatencyBudget`后按预算限制每个翻译器的等待时间。
+/// 成功的结果在`TranslationDetail::provenance`中追加`ProvenanceEvent::Fallback`，记录失败的翻译器和给出结果的翻译器
+pub struct FallbackTranslator {
+    /// （名称，翻译器），按优先级从高到低排列
+    engines: Vec<(String, Arc<dyn AsyncTranslator>)>,
+    /// 延迟预算，None表示不限
+    budget: Option<LatencyBudget>,
+}
+
+impl FallbackTranslator {
+    /// 创建故障转移翻译器
+    ///
+    /// # 参数
+    /// - `engines`: （名称，翻译器），按优先级从高到低排列
+    pub fn new(engines: Vec<(String, Arc<dyn AsyncTranslator>)>) -> Self {
+        Self {
+            engines,
+            budget: None,
+        }
+    }
+
+    /// 设置延迟预算
+    ///
+    /// # 参数
+    /// - `budget`: 延迟预算
+    pub fn with_latency_budget(mut self, budget: LatencyBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// 按优先级排列的翻译器名称
+    pub fn engines(&self) -> Vec<&str> {
+        self.engines.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// 检查每个预期的语言对都至少有一个翻译器可以翻译
+    ///
+    /// 按各翻译器的`supported_languages`检查，不发送请求；其余翻译器无法翻译时只记录警告
+    ///
+    /// # 参数
+    /// - `expected_pairs`: （源语言，目标语言）列表，源语言None表示自动检测
+    ///
+    /// # 返回值
+    /// 无法翻译某个语言对的翻译器；没有任何翻译器可以翻译时返回`TranslatorError::UnsupportedPair`
+    pub fn check_pairs(
+        &self,
+        expected_pairs: &[(Option<Language>, Language)],
+    ) -> Result<Vec<UnservedPair>, TranslatorError> {
+        capabilities(&self.engines).check_pairs(expected_pairs)
+    }
+
+    /// 依次尝试每个翻译器
+    ///
+    /// # 参数
+    /// - `attempt`: 用一个翻译器翻译
+    /// - `stale`: 没有得到结果时从延迟预算的缓存中查询旧译文
+    async fn try_each<'a, T, F, Fut>(
+        &'a self,
+        mut attempt: F,
+        stale: impl FnOnce(&CachedTranslator) -> Option<T>,
+    ) -> anyhow::Result<T>
+    where
+        T: Detailed,
+        F: FnMut(&'a dyn AsyncTranslator) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        let start = Instant::now();
+        let mut last = None;
+        let mut failed = Vec::new();
+        for (stage, (name, translator)) in self.engines.iter().enumerate() {
+            let result = match &self.budget {
+                None => attempt(translator.as_ref()).await,
+                Some(budget) => {
+                    let Some(timeout) = budget.stage_timeout(stage, start.elapsed()) else {
+                        break;
+                    };
+                    tokio::time::timeout(timeout, attempt(translator.as_ref()))
+                        .await
+                        .unwrap_or_else(|_| Err(TranslatorError::DeadlineExceeded(timeout).into()))
+                }
+            };
+            match result {
+                Ok(mut output) => {
+                    output.detail_mut().freshness = Some(if stage == 0 {
+                        Freshness::Fresh
+                    } else {
+                        Freshness::Fallback
+                    });
+                    output.trace(ProvenanceEvent::Fallback {
+                        failed,
+                        engine: Some(name.clone()),
+                    });
+                    return Ok(output);
+                }
+                Err(e)
+                    if matches!(
+                        ErrorKind::of(&e),
+                        ErrorKind::Cancelled | ErrorKind::InvalidInput
+                    ) =>
+                {
+                    return Err(e)
+                }
+                Err(e) => {
+                    tracing::debug!(engine = %name, error = %e, "translator failed, trying next");
+                    failed.push((name.clone(), ErrorKind::of(&e)));
+                    last = Some(e);
+                }
+            }
+        }
+        let cache = self.budget.as_ref().and_then(|b| b.stale_cache.as_deref());
+        if let Some(mut output) = cache.and_then(stale) {
+            output.detail_mut().freshness = Some(Freshness::Stale);
+            output.trace(ProvenanceEvent::Fallback {
+                failed,
+                engine: None,
+            });
+            return Ok(output);
+        }
+        if let Some(budget) = self.budget.as_ref().filter(|b| start.elapsed() >= b.total) {
+            return Err(TranslatorError::DeadlineExceeded(budget.total).into());
+        }
+        match last {
+            Some(e) => Err(e.context(format!("all {} translators failed", self.engines.len()))),
+            None => Err(
+                TranslatorError::Configuration("no translators in fallback stack".into()).into(),
+            ),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for FallbackTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 所有翻译器都是本地翻译器时为true
+    fn local(&self) -> bool {
+        self.engines.iter().all(|(_, t)| t.local())
+    }
+
+    /// 是否按请求量计费
+    ///
+    /// 任一翻译器计费时为true
+    fn paid(&self) -> bool {
+        self.engines.iter().any(|(_, t)| t.paid())
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 按顺序尝试，返回第一个检测到的语言；都无法判断时返回最后一个错误，没有错误时为None
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let mut last = None;
+        for (name, translator) in &self.engines {
+            match translator.detect_language(text).await {
+                Ok(Some(lang)) => return Ok(Some(lang)),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::debug!(engine = %name, error = %e, "language detection failed, trying next");
+                    last = Some(e);
+                }
+            }
+        }
+        last.map_or(Ok(None), Err)
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 所有翻译器同时预热，而不只是第一个：故障转移时后备翻译器同样不需要再建立连接
+    async fn warm_up(&self) {
+        join_all(
+            self.engines
+                .iter()
+                .map(|(_, translator)| translator.warm_up()),
+        )
+        .await;
+    }
+
+    /// 支持的语言
+    ///
+    /// 各翻译器支持的语言的并集，任一翻译器不限时为None
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        let mut languages: Vec<Language> = Vec::new();
+        for (_, translator) in &self.engines {
+            for lang in translator.supported_languages()? {
+                if !languages.contains(&lang) {
+                    languages.push(lang);
+                }
+            }
+        }
+        Some(languages)
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 第一个成功的翻译器的结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.try_each(
+            |t| t.translate(query, from, to),
+            |cache| cache.get_stale(query, from, to, &TranslateOptions::default()),
+        )
+        .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// 整个数组交给同一个翻译器，失败时整体换用下一个
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 第一个成功的翻译器的结果
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.try_each(
+            |t| t.translate_vec(query, from, to),
+            |cache| stale_vec(cache, query, from, to, &TranslateOptions::default()),
+        )
+        .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 所有尝试使用同一个请求ID
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 第一个成功的翻译器的结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let options = &options.resolved();
+        self.try_each(
+            |t| t.translate_with_options(query, from, to, options),
+            |cache| {
+                let mut output = cache.get_stale(query, from, to, options)?;
+                output.detail.request_id = options.request_id.clone();
+                Some(output)
+            },
+        )
+        .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 所有尝试使用同一个请求ID
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 第一个成功的翻译器的结果
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = &options.resolved();
+        self.try_each(
+            |t| t.translate_vec_with_options(query, from, to, options),
+            |cache| {
+                let mut output = stale_vec(cache, query, from, to, options)?;
+                output.detail.request_id = options.request_id.clone();
+                Some(output)
+            },
+        )
+        .await
+    }
+}
+
+/// 从缓存中查询多个文本的旧译文
+///
+/// # 参数
+/// - `cache`: 缓存翻译器
+/// - `query`: 待翻译的文本数组
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 调用选项
+///
+/// # 返回值
+/// 所有文本都有缓存的译文时返回译文数组，否则为None
+fn stale_vec(
+    cache: &CachedTranslator,
+    query: &[String],
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+) -> Option<TranslationListOutput> {
+    let mut output = TranslationListOutput::default();
+    for text in query {
+        let stale = cache.get_stale(text, from, to, options)?;
+        output.lang = output.lang.or(stale.lang);
+        output.text.push(stale.text);
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Freshness, Language};
+    use crate::fusion_translator::cache::CachedTranslator;
+    use crate::fusion_translator::capability::UnservedPair;
+    use crate::fusion_translator::fallback::{FallbackTranslator, LatencyBudget};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 每次调用耗时`millis`毫秒的模拟翻译器
+    fn slow(millis: u64) -> Arc<MockTranslator> {
+        Arc::new(MockTranslator::new().with_delay(Duration::from_millis(millis)))
+    }
+
+    /// 总预算800毫秒、第一阶段500毫秒的延迟预算
+    fn budget() -> LatencyBudget {
+        LatencyBudget::new(Duration::from_millis(800))
+            .with_stage_limits([Duration::from_millis(500)])
+    }
+
+    /// 测试失败时按顺序换用下一个翻译器，输入无效时不再尝试
+    #[tokio::test]
+    async fn test_fails_over_in_order() {
+        let primary = Arc::new(MockTranslator::new().fail_on("boom"));
+        let secondary = Arc::new(MockTranslator::new());
+        let fallback = FallbackTranslator::new(vec![
+            ("primary".to_string(), primary.clone()),
+            ("secondary".to_string(), secondary.clone()),
+        ]);
+        assert_eq!(fallback.engines(), ["primary", "secondary"]);
+
+        let output = fallback
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(output.detail.freshness, Some(Freshness::Fresh));
+        assert_eq!((primary.calls(), secondary.calls()), (1, 0));
+
+        let query = ["ok", "boom"].map(String::from);
+        let output = fallback
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["OK", "BOOM"]);
+        assert_eq!(output.detail.freshness, Some(Freshness::Fallback));
+        assert_eq!((primary.calls(), secondary.calls()), (2, 1));
+
+        let err = fallback
+            .translate("nul\0", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::InvalidInput);
+        assert_eq!(secondary.calls(), 1);
+
+        let both = FallbackTranslator::new(vec![
+            (
+                "a".to_string(),
+                Arc::new(MockTranslator::new().fail_on("x")),
+            ),
+            (
+                "b".to_string(),
+                Arc::new(MockTranslator::new().fail_on("x")),
+            ),
+        ]);
+        let err = both
+            .translate("x", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Api);
+        assert!(err.to_string().contains("all 2 translators failed"));
+    }
+
+    /// 测试故障转移栈只要求每个语言对至少有一个翻译器可以翻译
+    #[test]
+    fn test_check_pairs() {
+        let japanese =
+            Arc::new(MockTranslator::new().with_languages([Language::English, Language::Japanese]));
+        let korean =
+            Arc::new(MockTranslator::new().with_languages([Language::English, Language::Korean]));
+        let fallback = FallbackTranslator::new(vec![
+            ("japanese".to_string(), japanese),
+            ("korean".to_string(), korean),
+            ("any".to_string(), Arc::new(MockTranslator::new())),
+        ]);
+        assert_eq!(
+            fallback
+                .check_pairs(&[(Some(Language::English), Language::Japanese)])
+                .unwrap(),
+            vec![UnservedPair {
+                engine: "korean".to_string(),
+                from: Some(Language::English),
+                to: Language::Japanese,
+            }]
+        );
+        assert_eq!(
+            fallback.check_pairs(&[(None, Language::English)]).unwrap(),
+            Vec::new()
+        );
+
+        let fallback = FallbackTranslator::new(fallback.engines.into_iter().take(2).collect());
+        let unserved = fallback
+            .check_pairs(&[
+                (Some(Language::English), Language::Korean),
+                (None, Language::Japanese),
+            ])
+            .unwrap();
+        assert_eq!(
+            unserved
+                .iter()
+                .map(|u| u.engine.as_str())
+                .collect::<Vec<_>>(),
+            ["japanese", "korean"]
+        );
+        let error = fallback
+            .check_pairs(&[(Some(Language::Japanese), Language::Korean)])
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            TranslatorError::UnsupportedPair { ref engine, from: Some(Language::Japanese), to: Language::Korean }
+                if engine == "japanese > korean"
+        ));
+    }
+
+    /// 测试第一阶段在时限处被截断，第二阶段只能使用剩余的预算
+    #[tokio::test(start_paused = true)]
+    async fn test_stage_cutoffs() {
+        let (primary, secondary) = (slow(2000), slow(100));
+        let fallback = FallbackTranslator::new(vec![
+            ("primary".to_string(), primary.clone()),
+            ("secondary".to_string(), secondary.clone()),
+        ])
+        .with_latency_budget(budget());
+        let start = Instant::now();
+        let output = fallback
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(600));
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(output.detail.freshness, Some(Freshness::Fallback));
+        assert_eq!((primary.calls(), secondary.calls()), (1, 1));
+
+        // 第一阶段快速失败时，第二阶段可以使用剩余的全部预算
+        let fallback = FallbackTranslator::new(vec![
+            (
+                "primary".to_string(),
+                Arc::new(MockTranslator::new().fail_on("hello")),
+            ),
+            ("secondary".to_string(), slow(700)),
+        ])
+        .with_latency_budget(budget());
+        let start = Instant::now();
+        let output = fallback
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(700));
+        assert_eq!(output.detail.freshness, Some(Freshness::Fallback));
+
+        // 预算用完后不再尝试后续翻译器
+        let third = slow(0);
+        let fallback = FallbackTranslator::new(vec![
+            ("primary".to_string(), slow(2000)),
+            ("secondary".to_string(), slow(2000)),
+            ("third".to_string(), third.clone()),
+        ])
+        .with_latency_budget(budget());
+        let start = Instant::now();
+        let err = fallback
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert_eq!(start.elapsed(), Duration::from_millis(800));
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::DeadlineExceeded(total)) if *total == Duration::from_millis(800)
+        ));
+        assert_eq!(ErrorKind::of(&err), ErrorKind::NoResponse);
+        assert_eq!(third.calls(), 0);
+    }
+
+    /// 测试预算用完时返回缓存中已过期的旧译文，没有旧译文时返回超时错误
+    #[tokio::test(start_paused = true)]
+    async fn test_serves_stale_cache() {
+        let secondary = slow(1000);
+        let cache = Arc::new(
+            CachedTranslator::new(secondary.clone(), 16).with_ttl(Duration::from_secs(60)),
+        );
+        let warm = ["hello", "world"].map(String::from);
+        cache
+            .translate_vec(&warm, None, &Language::Chinese)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert!(cache
+            .get_stale("hello", None, &Language::Chinese, &Default::default())
+            .is_some());
+
+        let fallback = FallbackTranslator::new(vec![
+            ("primary".to_string(), slow(2000)),
+            ("cache".to_string(), cache.clone()),
+        ])
+        .with_latency_budget(budget().with_stale_cache(cache.clone()));
+        let start = Instant::now();
+        let output = fallback
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(800));
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(output.detail.freshness, Some(Freshness::Stale));
+        assert_eq!(secondary.calls(), 2);
+
+        let output = fallback
+            .translate_vec(&warm, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["HELLO", "WORLD"]);
+        assert_eq!(output.detail.freshness, Some(Freshness::Stale));
+
+        let query = ["hello", "new"].map(String::from);
+        let err = fallback
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::NoResponse);
+        let err = fallback
+            .translate("new", None, &Language::Chinese)
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::NoResponse);
+    }
+}
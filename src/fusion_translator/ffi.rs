@@ -0,0 +1,475 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::mock_translator::MockTranslator;
+use crate::fusion_translator::translator_error::ErrorKind;
+use crate::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory, TranslatorType,
+};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::Arc;
+
+/// 成功
+pub const FT_OK: i32 = 0;
+/// 参数为空指针或格式不正确
+pub const FT_ERR_INVALID_ARGUMENT: i32 = -1;
+/// 字符串不是合法的UTF-8
+pub const FT_ERR_INVALID_UTF8: i32 = -2;
+/// 无法识别或翻译器不支持的语言
+pub const FT_ERR_LANGUAGE: i32 = -3;
+/// 网络请求失败
+pub const FT_ERR_NETWORK: i32 = -4;
+/// 翻译服务返回错误
+pub const FT_ERR_API: i32 = -5;
+/// 翻译服务未返回结果
+pub const FT_ERR_NO_RESPONSE: i32 = -6;
+/// 请求文本过长
+pub const FT_ERR_TOO_LONG: i32 = -7;
+/// 翻译已取消
+pub const FT_ERR_CANCELLED: i32 = -8;
+/// 内部发生panic
+pub const FT_ERR_PANIC: i32 = -9;
+/// 其他错误
+pub const FT_ERR_UNKNOWN: i32 = -10;
+/// 译文疑似未翻译
+pub const FT_ERR_UNTRANSLATED: i32 = -11;
+/// 请求被限流
+pub const FT_ERR_RATE_LIMITED: i32 = -12;
+/// 翻译服务因内容审核拒绝翻译
+pub const FT_ERR_CONTENT_REJECTED: i32 = -13;
+
+thread_local! {
+    /// 当前线程最近一次的错误信息
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// 翻译器句柄
+///
+/// 每个句柄持有独立的单线程运行时，`ft_translate`在其上阻塞执行异步翻译
+pub struct FtTranslator {
+    /// 翻译器实例
+    translator: Arc<dyn AsyncTranslator>,
+    /// 执行异步翻译的运行时
+    runtime: tokio::runtime::Runtime,
+}
+
+/// 边界错误
+///
+/// 包含返回给调用方的错误码和错误信息
+struct FfiError {
+    /// 错误码
+    code: i32,
+    /// 错误信息
+    message: String,
+}
+
+impl FfiError {
+    /// 创建新的边界错误
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for FfiError {
+    fn from(e: anyhow::Error) -> Self {
+        let code = match ErrorKind::of(&e) {
+            ErrorKind::Network => FT_ERR_NETWORK,
+            ErrorKind::Api => FT_ERR_API,
+            ErrorKind::Language => FT_ERR_LANGUAGE,
+            ErrorKind::NoResponse => FT_ERR_NO_RESPONSE,
+            ErrorKind::TooLong => FT_ERR_TOO_LONG,
+            ErrorKind::Cancelled => FT_ERR_CANCELLED,
+            ErrorKind::Untranslated => FT_ERR_UNTRANSLATED,
+            ErrorKind::InvalidInput => FT_ERR_INVALID_ARGUMENT,
+            ErrorKind::RateLimited => FT_ERR_RATE_LIMITED,
+            ErrorKind::ContentRejected => FT_ERR_CONTENT_REJECTED,
+            ErrorKind::Unknown => FT_ERR_UNKNOWN,
+        };
+        Self::new(code, format!("{:#}", e))
+    }
+}
+
+/// 记录当前线程的错误信息
+///
+/// # 参数
+/// - `message`: 错误信息，内部的NUL字符会被移除
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// 在边界处执行操作
+///
+/// 捕获panic并把错误转换为错误码，同时记录错误信息
+///
+/// # 参数
+/// - `f`: 要执行的操作
+///
+/// # 返回值
+/// 操作成功时为`FT_OK`，否则为负数错误码
+fn guard<F>(f: F) -> i32
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => FT_OK,
+        Ok(Err(e)) => {
+            set_last_error(&e.message);
+            e.code
+        }
+        Err(_) => {
+            set_last_error("panic across the FFI boundary");
+            FT_ERR_PANIC
+        }
+    }
+}
+
+/// 读取C字符串
+///
+/// # 参数
+/// - `s`: C字符串指针
+/// - `name`: 参数名，用于错误信息
+///
+/// # 返回值
+/// 字符串切片，指针为空或不是合法UTF-8时返回错误
+///
+/// # Safety
+/// `s`为非空时必须指向以NUL结尾的有效字符串，且在使用期间保持有效
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if s.is_null() {
+        return Err(FfiError::new(
+            FT_ERR_INVALID_ARGUMENT,
+            format!("{} is null", name),
+        ));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| FfiError::new(FT_ERR_INVALID_UTF8, format!("{} is not valid UTF-8", name)))
+}
+
+/// 读取可选的C字符串
+///
+/// 空指针视为未提供
+///
+/// # Safety
+/// 同`read_str`
+unsafe fn read_opt_str<'a>(s: *const c_char, name: &str) -> Result<Option<&'a str>, FfiError> {
+    if s.is_null() {
+        Ok(None)
+    } else {
+        read_str(s, name).map(Some)
+    }
+}
+
+/// 解析语言代码
+///
+/// # 参数
+/// - `code`: ISO 639-1语言代码
+///
+/// # 返回值
+/// 对应的语言
+fn parse_language(code: &str) -> Result<Language, FfiError> {
+    Language::from_639_1(code)
+        .ok_or_else(|| FfiError::new(FT_ERR_LANGUAGE, format!("unknown language: {}", code)))
+}
+
+/// 从JSON配置中读取字符串字段
+///
+/// # 参数
+/// - `config`: JSON配置
+/// - `key`: 字段名
+fn config_str(config: &serde_json::Value, key: &str) -> Result<String, FfiError> {
+    config[key].as_str().map(str::to_string).ok_or_else(|| {
+        FfiError::new(
+            FT_ERR_INVALID_ARGUMENT,
+            format!("config is missing \"{}\"", key),
+        )
+    })
+}
+
+/// 根据引擎名称和JSON配置创建翻译器
+///
+/// 除工厂支持的翻译器外，还接受`mock`引擎，便于调用方在不联网的情况下测试接入
+///
+/// # 参数
+/// - `engine`: 引擎名称
+/// - `config`: JSON配置
+fn build_translator(
+    engine: &str,
+    config: &serde_json::Value,
+) -> Result<Arc<dyn AsyncTranslator>, FfiError> {
+    if engine.eq_ignore_ascii_case("mock") {
+        return Ok(Arc::new(MockTranslator::new()));
+    }
+    let translator_type = TranslatorType::parse(engine).ok_or_else(|| {
+        FfiError::new(
+            FT_ERR_INVALID_ARGUMENT,
+            format!("unknown engine: {}", engine),
+        )
+    })?;
+    let config = match translator_type {
+        TranslatorType::Baidu => TranslatorConfig::Baidu {
+            app_id: config_str(config, "app_id")?,
+            key: config_str(config, "key")?,
+        },
+        TranslatorType::Youdao => TranslatorConfig::Youdao {
+            app_key: config_str(config, "app_key")?,
+            app_secret: config_str(config, "app_secret")?,
+        },
+        TranslatorType::Alibaba => TranslatorConfig::Alibaba {
+            token: config_str(config, "token").unwrap_or_default(),
+        },
+        TranslatorType::Caiyun => TranslatorConfig::Caiyun {
+            token: config_str(config, "token")?,
+            request_id: config_str(config, "request_id").unwrap_or_else(|_| "demo".to_string()),
+        },
+        TranslatorType::MyMemory => TranslatorConfig::MyMemory,
+        TranslatorType::TranSmart => TranslatorConfig::TranSmart,
+        TranslatorType::Reverso => TranslatorConfig::Reverso,
+        TranslatorType::BingWeb => TranslatorConfig::BingWeb,
+        TranslatorType::GoogleWeb => TranslatorConfig::GoogleWeb,
+    };
+    Ok(TranslatorFactory::create(config))
+}
+
+/// 创建翻译器
+///
+/// # 参数
+/// - `engine`: 引擎名称，如`baidu`、`youdao`、`mymemory`
+/// - `config_json`: JSON格式的凭据配置，可为空指针
+///
+/// # 返回值
+/// 翻译器句柄，失败时返回空指针并记录错误信息
+///
+/// # Safety
+/// 非空的字符串参数必须指向以NUL结尾的有效字符串
+#[no_mangle]
+pub unsafe extern "C" fn ft_create_translator(
+    engine: *const c_char,
+    config_json: *const c_char,
+) -> *mut FtTranslator {
+    let mut handle = ptr::null_mut();
+    guard(|| {
+        let engine = read_str(engine, "engine")?;
+        let config = match read_opt_str(config_json, "config_json")? {
+            Some(json) => serde_json::from_str(json).map_err(|e| {
+                FfiError::new(FT_ERR_INVALID_ARGUMENT, format!("invalid config: {}", e))
+            })?,
+            None => serde_json::Value::Null,
+        };
+        let translator = build_translator(engine, &config)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| FfiError::new(FT_ERR_UNKNOWN, e.to_string()))?;
+        handle = Box::into_raw(Box::new(FtTranslator {
+            translator,
+            runtime,
+        }));
+        Ok(())
+    });
+    handle
+}
+
+/// 翻译文本
+///
+/// 在句柄的运行时上阻塞执行翻译，成功时把译文写入`out`，
+/// 返回的字符串需要通过`ft_free_string`释放
+///
+/// # 参数
+/// - `handle`: 翻译器句柄
+/// - `text`: 待翻译的文本
+/// - `from`: 源语言的ISO 639-1代码，空指针或`auto`表示自动检测
+/// - `to`: 目标语言的ISO 639-1代码
+/// - `out`: 接收译文的指针
+///
+/// # 返回值
+/// 成功时为`FT_OK`，否则为负数错误码
+///
+/// # Safety
+/// `handle`必须是`ft_create_translator`返回且尚未销毁的句柄，
+/// 非空的字符串参数必须指向以NUL结尾的有效字符串，`out`必须可写
+#[no_mangle]
+pub unsafe extern "C" fn ft_translate(
+    handle: *mut FtTranslator,
+    text: *const c_char,
+    from: *const c_char,
+    to: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return Err(FfiError::new(FT_ERR_INVALID_ARGUMENT, "out is null"));
+        }
+        *out = ptr::null_mut();
+        let handle = handle
+            .as_ref()
+            .ok_or_else(|| FfiError::new(FT_ERR_INVALID_ARGUMENT, "handle is null"))?;
+        let text = read_str(text, "text")?;
+        let from = match read_opt_str(from, "from")? {
+            None | Some("auto") => None,
+            Some(code) => Some(parse_language(code)?),
+        };
+        let to = parse_language(read_str(to, "to")?)?;
+        let output = handle
+            .runtime
+            .block_on(handle.translator.translate(text, from, &to))?;
+        let text = CString::new(output.text)
+            .map_err(|_| FfiError::new(FT_ERR_UNKNOWN, "translation contains a NUL byte"))?;
+        *out = text.into_raw();
+        Ok(())
+    })
+}
+
+/// 释放`ft_translate`返回的字符串
+///
+/// # 参数
+/// - `s`: 要释放的字符串，可为空指针
+///
+/// # Safety
+/// `s`必须是本库返回且尚未释放的字符串
+#[no_mangle]
+pub unsafe extern "C" fn ft_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// 销毁翻译器句柄
+///
+/// # 参数
+/// - `handle`: 要销毁的句柄，可为空指针
+///
+/// # Safety
+/// `handle`必须是`ft_create_translator`返回且尚未销毁的句柄
+#[no_mangle]
+pub unsafe extern "C" fn ft_destroy(handle: *mut FtTranslator) {
+    if !handle.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(handle))));
+    }
+}
+
+/// 获取当前线程最近一次的错误信息
+///
+/// # 返回值
+/// 错误信息，没有错误时为空指针；返回的指针在当前线程下一次调用本库前有效，不需要释放
+#[no_mangle]
+pub extern "C" fn ft_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::ffi::*;
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    /// 读取最近一次错误信息
+    fn last_error() -> String {
+        let message = ft_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// 测试通过C接口完成一次翻译
+    #[test]
+    fn test_translate_roundtrip() {
+        let engine = CString::new("mock").unwrap();
+        let text = CString::new("hello").unwrap();
+        let to = CString::new("zh").unwrap();
+        unsafe {
+            let handle = ft_create_translator(engine.as_ptr(), ptr::null());
+            assert!(!handle.is_null());
+            let mut out = ptr::null_mut();
+            let code = ft_translate(handle, text.as_ptr(), ptr::null(), to.as_ptr(), &mut out);
+            assert_eq!(code, FT_OK);
+            assert_eq!(CStr::from_ptr(out).to_str().unwrap(), "HELLO");
+            ft_free_string(out);
+            ft_destroy(handle);
+        }
+    }
+
+    /// 测试空指针和非法参数返回错误码
+    #[test]
+    fn test_invalid_arguments() {
+        let engine = CString::new("nope").unwrap();
+        let mock = CString::new("mock").unwrap();
+        let text = CString::new("hello").unwrap();
+        let bad_lang = CString::new("xx").unwrap();
+        unsafe {
+            assert!(ft_create_translator(ptr::null(), ptr::null()).is_null());
+            assert!(last_error().contains("engine is null"));
+            assert!(ft_create_translator(engine.as_ptr(), ptr::null()).is_null());
+            assert!(last_error().contains("unknown engine"));
+
+            let handle = ft_create_translator(mock.as_ptr(), ptr::null());
+            let mut out = ptr::null_mut();
+            assert_eq!(
+                ft_translate(
+                    handle,
+                    ptr::null(),
+                    ptr::null(),
+                    bad_lang.as_ptr(),
+                    &mut out
+                ),
+                FT_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                ft_translate(
+                    handle,
+                    text.as_ptr(),
+                    ptr::null(),
+                    bad_lang.as_ptr(),
+                    &mut out
+                ),
+                FT_ERR_LANGUAGE
+            );
+            assert!(out.is_null());
+            assert_eq!(
+                ft_translate(
+                    ptr::null_mut(),
+                    text.as_ptr(),
+                    ptr::null(),
+                    bad_lang.as_ptr(),
+                    &mut out
+                ),
+                FT_ERR_INVALID_ARGUMENT
+            );
+            ft_destroy(handle);
+        }
+    }
+
+    /// 测试非UTF-8字符串返回对应错误码
+    #[test]
+    fn test_invalid_utf8() {
+        let mock = CString::new("mock").unwrap();
+        let text = CString::new(vec![0xffu8, 0xfe]).unwrap();
+        let to = CString::new("zh").unwrap();
+        unsafe {
+            let handle = ft_create_translator(mock.as_ptr(), ptr::null());
+            let mut out = ptr::null_mut();
+            assert_eq!(
+                ft_translate(handle, text.as_ptr(), ptr::null(), to.as_ptr(), &mut out),
+                FT_ERR_INVALID_UTF8
+            );
+            ft_destroy(handle);
+        }
+    }
+
+    /// 测试缺少凭据时创建失败
+    #[test]
+    fn test_missing_credentials() {
+        let engine = CString::new("baidu").unwrap();
+        let config = CString::new(r#"{"app_id": "1"}"#).unwrap();
+        unsafe {
+            assert!(ft_create_translator(engine.as_ptr(), config.as_ptr()).is_null());
+        }
+        assert!(last_error().contains("\"key\""));
+    }
+}
@@ -0,0 +1,613 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::{self, empty_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::{TranslatorOptions, DEFAULT_MAX_URL_LEN};
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
+use reqwest::{StatusCode, Url};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// Google网页翻译接口的响应
+///
+/// 响应是按位置排列的嵌套数组：下标0为`[译文, 原文, ...]`句子片段数组，下标2为检测到的源语言
+#[derive(Debug, PartialEq)]
+struct GoogleResponse {
+    /// 按顺序拼接的句子译文，只请求语言检测时为None
+    text: Option<String>,
+    /// 检测到的源语言代码
+    detected: Option<String>,
+}
+
+/// 解析Google网页翻译接口的响应
+///
+/// 逐层检查类型和下标，结构与预期不符时返回错误而不是panic；
+/// 片段数组中下标0不是字符串的项（如末尾的音译）被跳过
+///
+/// # 参数
+/// - `resp`: 接口返回的JSON
+///
+/// # 返回值
+/// 解析结果，响应不是数组时返回`TranslatorError::NoResponse`
+fn parse_response(resp: &Value) -> Result<GoogleResponse, TranslatorError> {
+    let items = resp.as_array().ok_or(TranslatorError::NoResponse)?;
+    let text = items.first().and_then(Value::as_array).map(|segments| {
+        segments
+            .iter()
+            .filter_map(|segment| segment.as_array()?.first()?.as_str())
+            .collect::<String>()
+    });
+    let detected = items
+        .get(2)
+        .and_then(Value::as_str)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string);
+    Ok(GoogleResponse { text, detected })
+}
+
+/// Google网页翻译器实现
+///
+/// 调用`translate.googleapis.com`的`client=gtx`接口，无需凭据。
+/// 文本通过GET查询参数传递，编码后的URL超过`with_max_url_len`设置的长度时按句子拆分后分别请求
+pub struct GoogleWebTranslator {
+    /// API请求地址
+    host: String,
+    /// GET请求允许的最大URL长度
+    max_url_len: usize,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
+}
+
+/// 默认实现
+impl Default for GoogleWebTranslator {
+    fn default() -> Self {
+        GoogleWebTranslator::new()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for GoogleWebTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// Google网页翻译器需要调用远程API，返回false
+    fn local(&self) -> bool {
+        false
+    }
+
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "google_web", &self.host).await
+    }
+
+    /// 单次请求最多的文本数量
+    ///
+    /// 每次请求只发送一个文本
+    fn max_batch_len(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(Language::to_google))
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 发送不带`dt`参数的请求，只取响应中检测到的语言
+    ///
+    /// # 参数
+    /// - `text`: 待检测的文本，URL过长时只发送开头部分
+    ///
+    /// # 返回值
+    /// 检测到的语言，代码不在`google`代码表中时为None
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let mut text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let mut url = self.url(text, "auto", "en", false)?;
+        while url.as_str().len() > self.max_url_len && text.len() > 1 {
+            text = long_text::split_by_bytes(text, text.len() / 2)[0];
+            url = self.url(text, "auto", "en", false)?;
+        }
+        let resp = self.get(url, None).await?;
+        Ok(parse_response(&resp)?
+            .detected
+            .and_then(|code| Language::from_vendor("google", &code)))
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递。编码后的URL超过`with_max_url_len`设置的长度时，
+    /// 按句子拆分为编码后不超过上限的片段分别请求，再按原文的分隔符拼接
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("google_web", query, from, to, options)
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 每次请求只发送一个文本，逐个顺序请求
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            0,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("google_web", query, from, to, &options)
+        })
+    }
+}
+
+impl GoogleWebTranslator {
+    /// 创建新的Google网页翻译器实例
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new() -> Self {
+        GoogleWebTranslator {
+            transport: TranslatorOptions::default().build_transport(TranslatorType::GoogleWeb),
+            politeness: Some(Politeness::shared(
+                "google_web",
+                PolitenessConfig::default(),
+            )),
+            host: "https://translate.googleapis.com/translate_a/single".to_string(),
+            max_url_len: DEFAULT_MAX_URL_LEN,
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::GoogleWeb);
+        self.politeness = options.politeness.resolve("google_web");
+        self.with_max_url_len(options.max_url_len)
+    }
+
+    /// 设置GET请求允许的最大URL长度
+    ///
+    /// 默认为`DEFAULT_MAX_URL_LEN`
+    ///
+    /// # 参数
+    /// - `max_url_len`: 编码后URL的最大字节数
+    pub fn with_max_url_len(mut self, max_url_len: usize) -> Self {
+        self.max_url_len = max_url_len;
+        self
+    }
+
+    /// 构造请求地址
+    ///
+    /// # 参数
+    /// - `text`: 待翻译的文本
+    /// - `source`: 源语言代码，自动检测为`auto`
+    /// - `target`: 目标语言代码
+    /// - `translate`: 是否请求译文（`dt=t`），为false时响应只包含语言检测结果
+    fn url(&self, text: &str, source: &str, target: &str, translate: bool) -> anyhow::Result<Url> {
+        let mut params = vec![("client", "gtx"), ("sl", source), ("tl", target)];
+        if translate {
+            params.push(("dt", "t"));
+        }
+        params.push(("q", text));
+        Ok(Url::parse_with_params(&self.host, &params)?)
+    }
+
+    /// 发送请求并解析JSON响应
+    ///
+    /// # 参数
+    /// - `url`: 请求地址
+    /// - `request_id`: 请求ID，通过`X-Request-Id`请求头传递
+    ///
+    /// # 返回值
+    /// 响应JSON；返回429时为`TranslatorError::RateLimited`
+    async fn get(&self, url: Url, request_id: Option<&str>) -> anyhow::Result<Value> {
+        let request = with_request_id_header(HttpRequest::get(url), request_id);
+        let response =
+            politeness::send(self.politeness.as_deref(), &*self.transport, request).await?;
+        match StatusCode::from_u16(response.status) {
+            Ok(status @ StatusCode::TOO_MANY_REQUESTS) => {
+                Err(TranslatorError::RateLimited(status.to_string()).into())
+            }
+            _ if !response.is_success() => {
+                Err(TranslatorError::RequestFailed(response.status).into())
+            }
+            _ => response.json(),
+        }
+    }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，最多一个
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let Some(text) = query.first() else {
+            return Ok(empty_batch(options));
+        };
+        let output = self.translate_with_options(text, from, to, options).await?;
+        Ok(TranslationListOutput {
+            text: vec![output.text],
+            lang: output.lang,
+            detail: output.detail,
+        })
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递。编码后的URL超过`with_max_url_len`设置的长度时，
+    /// 按句子拆分为编码后不超过上限的片段分别请求，再按原文的分隔符拼接
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = &*preprocess::apply(query, options)?;
+        if query.trim().is_empty() {
+            return Ok(TranslationOutput {
+                text: query.to_string(),
+                lang: Some(*to),
+                detail: TranslationDetail {
+                    request_id: options.resolve_request_id(),
+                    ..Default::default()
+                },
+            });
+        }
+        let mut languages = LanguageResolver::new(options, Language::to_google);
+        let source = match from {
+            Some(lang) => languages.resolve(lang)?,
+            None => "auto",
+        };
+        let target = languages.resolve(*to)?;
+        let text = whitespace::trim(query, options);
+        let url = self.url(text, source, target, true)?;
+        // 4字节以内的文本无法再拆分，原样发送
+        if url.as_str().len() > self.max_url_len && text.len() > 4 {
+            // 按本文本的编码膨胀比例估算片段的字节数，仍然超长的片段在递归调用中继续拆分
+            let overhead = self.url("", source, target, true)?.as_str().len();
+            let encoded = (url.as_str().len() - overhead).max(1);
+            let budget = self.max_url_len.saturating_sub(overhead).max(1);
+            let max_bytes = (text.len() * budget / encoded).min(text.len() - 1);
+            return long_text::translate_split(self, query, from, to, options, max_bytes).await;
+        }
+
+        let request_id = options.resolve_request_id();
+        let span =
+            tracing::debug_span!("translate", backend = "google_web", request_id = request_id);
+        let resp = options
+            .run_cancellable(0, self.get(url, request_id.as_deref()).instrument(span))
+            .await?;
+        let resp = parse_response(&resp)?;
+        tracing::debug!(backend = "google_web", detected = ?resp.detected, "translated");
+        let text = resp.text.ok_or(TranslatorError::NoResponse)?;
+        let text = postprocess::apply(query, text, options);
+        let possibly_untranslated = untranslated::check(query, &text, from, to, options)?;
+        Ok(TranslationOutput {
+            text,
+            lang: Some(*to),
+            detail: TranslationDetail {
+                request_id,
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl GoogleWebTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, Value)> {
+        let url = self.url("Hello, world", "auto", "zh-CN", true)?;
+        Ok(("single.json", self.get(url, None).await?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    let resp = parse_response(&serde_json::from_str(body)?)?;
+    match name {
+        "single.json" | "multi.json" if resp.text.is_some() => {}
+        "detect.json" if resp.detected.is_some() => {}
+        _ => anyhow::bail!("未知的响应样例或解析结果不符：{} {:?}", name, resp),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::google_web_translator::{
+        parse_response, GoogleResponse, GoogleWebTranslator,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::test_support::http::{self, Request};
+    use serde_json::json;
+    use std::sync::mpsc;
+
+    /// 单句译文
+    const SINGLE: &str = include_str!("../../tests/fixtures/google/single.json");
+
+    /// 多句译文，末尾带音译片段
+    const MULTI: &str = include_str!("../../tests/fixtures/google/multi.json");
+
+    /// 只包含语言检测结果
+    const DETECT: &str = include_str!("../../tests/fixtures/google/detect.json");
+
+    /// 解析响应样例
+    fn parse(body: &str) -> GoogleResponse {
+        parse_response(&serde_json::from_str(body).unwrap()).unwrap()
+    }
+
+    /// 启动把查询文本转为大写作为译文的本地服务器
+    ///
+    /// # 返回值
+    /// 请求本地服务器的翻译器和收到的请求
+    fn serve() -> (GoogleWebTranslator, mpsc::Receiver<Request>) {
+        let (sender, receiver) = mpsc::channel();
+        let url = http::serve(move |request| {
+            let _ = sender.send(request.clone());
+            let q = request.param("q").unwrap_or_default();
+            let body = json!([[[q.to_uppercase(), q, null, null, 10]], null, "en"]);
+            (200, body.to_string())
+        });
+        let mut translator = GoogleWebTranslator::new();
+        translator.host = format!("{}/translate_a/single", url);
+        translator.politeness = None;
+        (translator, receiver)
+    }
+
+    /// 测试解析单句响应
+    #[test]
+    fn test_parse_single() {
+        assert_eq!(
+            parse(SINGLE),
+            GoogleResponse {
+                text: Some("你好，世界".to_string()),
+                detected: Some("en".to_string()),
+            }
+        );
+    }
+
+    /// 测试解析多句响应：按顺序拼接句子片段，跳过末尾的音译片段
+    #[test]
+    fn test_parse_multi() {
+        assert_eq!(
+            parse(MULTI).text.as_deref(),
+            Some("Bonjour le monde. Comment allez-vous?\nTrès bien, merci.")
+        );
+    }
+
+    /// 测试解析只包含语言检测结果的响应
+    #[test]
+    fn test_parse_detect_only() {
+        assert_eq!(
+            parse(DETECT),
+            GoogleResponse {
+                text: None,
+                detected: Some("ja".to_string()),
+            }
+        );
+    }
+
+    /// 测试结构不符的响应返回错误或空结果，不会panic
+    #[test]
+    fn test_parse_malformed() {
+        assert!(parse_response(&json!({"error": "x"})).is_err());
+        assert!(parse_response(&json!(null)).is_err());
+        for resp in [
+            json!([]),
+            json!([null]),
+            json!([[]]),
+            json!([[null, 1, [], [null], [[1]]], null, 5]),
+            json!([[["a"], "b"], null, ""]),
+        ] {
+            let parsed = parse_response(&resp).unwrap();
+            assert_eq!(parsed.detected, None, "{}", resp);
+        }
+        assert_eq!(
+            parse_response(&json!([[["a"], "b", [null, "c"]]]))
+                .unwrap()
+                .text
+                .as_deref(),
+            Some("a")
+        );
+    }
+
+    /// 测试请求参数正确编码
+    #[tokio::test]
+    async fn test_url_encoding() {
+        let (translator, requests) = serve();
+        let query = "Tom & Jerry #1 + 50%?\n你好";
+        let output = translator
+            .translate(query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, query.to_uppercase());
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.path, "/translate_a/single");
+        assert_eq!(request.param("client").as_deref(), Some("gtx"));
+        assert_eq!(request.param("sl").as_deref(), Some("auto"));
+        assert_eq!(request.param("tl").as_deref(), Some("zh-CN"));
+        assert_eq!(request.param("dt").as_deref(), Some("t"));
+        assert_eq!(request.param("q").as_deref(), Some(query));
+        assert_eq!(request.query.matches('&').count(), 4, "{}", request.query);
+    }
+
+    /// 测试URL超长时按句子拆分，每个请求都不超过上限，拼接结果与原文对应
+    #[tokio::test]
+    async fn test_chunks_long_text() {
+        let (translator, requests) = serve();
+        let translator = translator.with_max_url_len(200);
+        let query = "这是第一句话。这是第二句话。This is the third sentence. 第四句。".repeat(3);
+        let output = translator
+            .translate(&query, Some(Language::Chinese), &Language::English)
+            .await
+            .unwrap();
+        assert_eq!(output.text, query.to_uppercase());
+        let requests = requests.try_iter().collect::<Vec<_>>();
+        assert!(requests.len() > 1);
+        for request in &requests {
+            let url = format!("{}?{}", request.path, request.query);
+            assert!(url.len() + "http://127.0.0.1:65535".len() <= 200, "{}", url);
+        }
+    }
+
+    /// 测试语言检测只请求检测结果
+    #[tokio::test]
+    async fn test_detect_language() {
+        let (sender, receiver) = mpsc::channel();
+        let url = http::serve(move |request| {
+            let _ = sender.send(request.clone());
+            (200, DETECT.to_string())
+        });
+        let mut translator = GoogleWebTranslator::new();
+        translator.host = format!("{}/translate_a/single", url);
+        translator.politeness = None;
+        let detected = translator.detect_language("こんにちは").await.unwrap();
+        assert_eq!(detected, Some(Language::Japanese));
+        let request = receiver.recv().unwrap();
+        assert_eq!(request.param("dt"), None);
+        assert_eq!(translator.detect_language(" ").await.unwrap(), None);
+    }
+
+    /// 模糊测试：任意响应的解析都不会panic
+    #[test]
+    fn test_fuzz_parse_response() {
+        let corpus = [
+            include_str!("../../tests/fixtures/google/single.json"),
+            include_str!("../../tests/fixtures/google/multi.json"),
+            include_str!("../../tests/fixtures/google/detect.json"),
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            if let Ok(resp) = serde_json::from_str(&input) {
+                let _ = parse_response(&resp);
+            }
+        }
+    }
+}
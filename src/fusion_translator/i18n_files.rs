@@ -0,0 +1,789 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::batch::{translate_map_with, BatchOptions};
+use crate::fusion_translator::translator_error::TranslatorError;
+use anyhow::Context;
+use serde::de::IgnoredAny;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// 默认每批翻译的数组元素数量
+pub const DEFAULT_ELEMENTS_PER_BATCH: usize = 100;
+
+/// 字段路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// 对象中指定名称的字段
+    Field(String),
+    /// 对象中的任意字段，写作`*`
+    AnyField,
+    /// 数组中指定下标的元素，写作`[n]`
+    Index(usize),
+    /// 数组中的任意元素，写作`[*]`
+    AnyIndex,
+}
+
+/// 待翻译字段的路径，相对于顶层数组的每个元素
+///
+/// 由`.`分隔的字段名和`[*]`、`[n]`组成，如`title`、`items[*].description`、`labels.*`。
+/// 字段名中不能包含`.`、`[`和`]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    /// 各段路径，至少有一段
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    /// 各段路径
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// 路径是否以`steps`开头，即`steps`下可能有需要翻译的字段
+    ///
+    /// # 参数
+    /// - `steps`: 从元素根部到当前值的路径
+    fn starts_with(&self, steps: &[Step]) -> bool {
+        steps.len() <= self.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(steps)
+                .all(|(segment, step)| segment.matches(step))
+    }
+
+    /// 路径是否恰好指向`steps`
+    ///
+    /// # 参数
+    /// - `steps`: 从元素根部到当前值的路径
+    fn matches(&self, steps: &[Step]) -> bool {
+        steps.len() == self.segments.len() && self.starts_with(steps)
+    }
+}
+
+impl PathSegment {
+    /// 是否匹配实际路径中的一步
+    ///
+    /// # 参数
+    /// - `step`: 对象的字段名或数组的下标
+    fn matches(&self, step: &Step) -> bool {
+        match (self, step) {
+            (PathSegment::Field(name), Step::Key(key)) => name == key,
+            (PathSegment::AnyField, Step::Key(_)) => true,
+            (PathSegment::Index(index), Step::Index(i)) => index == i,
+            (PathSegment::AnyIndex, Step::Index(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = TranslatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            |reason: &str| TranslatorError::InvalidJson(format!("path `{}`: {}", s, reason));
+        let mut segments = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let (inner, tail) = after
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed `[`"))?;
+                segments.push(match inner {
+                    "*" => PathSegment::AnyIndex,
+                    _ => PathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("index must be a number or `*`"))?,
+                    ),
+                });
+                rest = tail;
+                continue;
+            }
+            let field = match segments.is_empty() {
+                true => rest,
+                false => rest
+                    .strip_prefix('.')
+                    .ok_or_else(|| invalid("expected `.` or `[`"))?,
+            };
+            let end = field.find(['.', '[', ']']).unwrap_or(field.len());
+            segments.push(match &field[..end] {
+                "" => return Err(invalid("empty field name")),
+                "*" => PathSegment::AnyField,
+                name => PathSegment::Field(name.to_string()),
+            });
+            rest = &field[end..];
+        }
+        if segments.is_empty() {
+            return Err(invalid("empty path"));
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            let dot = if i == 0 { "" } else { "." };
+            match segment {
+                PathSegment::Field(name) => write!(f, "{}{}", dot, name)?,
+                PathSegment::AnyField => write!(f, "{}*", dot)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::AnyIndex => f.write_str("[*]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 实际路径中的一步
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// 对象的字段名
+    Key(String),
+    /// 数组的下标
+    Index(usize),
+}
+
+/// 流式翻译JSON数组的选项
+#[derive(Debug, Clone)]
+pub struct JsonArrayOptions {
+    /// 待翻译字段的路径，只翻译路径指向的字符串值，其他类型的值保持不变
+    pub paths: Vec<JsonPath>,
+    /// 每批翻译的数组元素数量，内存占用与此值乘以单个元素的大小成正比
+    pub elements_per_batch: usize,
+    /// 每批元素中的字符串去重后按此选项调用`translate_map_with`
+    pub batch: BatchOptions,
+}
+
+impl JsonArrayOptions {
+    /// 按路径创建选项
+    ///
+    /// # 参数
+    /// - `paths`: 待翻译字段的路径
+    pub fn new(paths: impl IntoIterator<Item = JsonPath>) -> Self {
+        Self {
+            paths: paths.into_iter().collect(),
+            elements_per_batch: DEFAULT_ELEMENTS_PER_BATCH,
+            batch: BatchOptions::default(),
+        }
+    }
+
+    /// 设置每批翻译的数组元素数量
+    ///
+    /// # 参数
+    /// - `elements`: 元素数量，0按1处理
+    pub fn with_elements_per_batch(mut self, elements: usize) -> Self {
+        self.elements_per_batch = elements.max(1);
+        self
+    }
+
+    /// 设置批量翻译选项
+    ///
+    /// # 参数
+    /// - `batch`: 批量翻译选项，允许部分失败时翻译失败的字符串保留原文
+    pub fn with_batch_options(mut self, batch: BatchOptions) -> Self {
+        self.batch = batch;
+        self
+    }
+}
+
+/// 流式翻译JSON数组的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonArrayReport {
+    /// 数组元素数量
+    pub elements: usize,
+    /// 已翻译的字符串数量，空白字符串不计入
+    pub translated: usize,
+    /// 允许部分失败时翻译失败、保留原文的字符串数量
+    pub untranslated: usize,
+}
+
+/// 逐个读取顶层数组元素的原始字节
+struct ArrayReader<R> {
+    /// 输入
+    reader: R,
+    /// 已读取的元素数量
+    elements: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> ArrayReader<R> {
+    /// 读取数组开头
+    ///
+    /// # 返回值
+    /// 数组前的空白和`[`
+    async fn open(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut prefix = self.read_whitespace().await?;
+        match self.reader.fill_buf().await?.first() {
+            Some(b'[') => {
+                self.reader.consume(1);
+                prefix.push(b'[');
+                Ok(prefix)
+            }
+            _ => Err(TranslatorError::InvalidJson("expected a top-level array".to_string()).into()),
+        }
+    }
+
+    /// 读取下一个元素到分隔它的`,`或`]`之前
+    ///
+    /// 只跟踪字符串和括号的嵌套层级，元素是否为有效的JSON由调用方检查
+    ///
+    /// # 返回值
+    /// （元素的原始字节，包括两侧的空白；是否为最后一个元素）
+    async fn next(&mut self) -> anyhow::Result<(Vec<u8>, bool)> {
+        self.elements += 1;
+        let mut raw = Vec::new();
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                return Err(TranslatorError::InvalidJson(format!(
+                    "element {}: unexpected end of input",
+                    self.elements
+                ))
+                .into());
+            }
+            let mut end = None;
+            for (i, &byte) in buf.iter().enumerate() {
+                if in_string {
+                    match byte {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => in_string = false,
+                        _ => {}
+                    }
+                    continue;
+                }
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b',' | b']' if depth == 0 => {
+                        end = Some((i, byte == b']'));
+                        break;
+                    }
+                    b'}' if depth == 0 => {
+                        return Err(TranslatorError::InvalidJson(format!(
+                            "element {}: unbalanced `}}`",
+                            self.elements
+                        ))
+                        .into());
+                    }
+                    b'}' | b']' => depth -= 1,
+                    _ => {}
+                }
+            }
+            match end {
+                Some((i, last)) => {
+                    raw.extend_from_slice(&buf[..i]);
+                    self.reader.consume(i + 1);
+                    return Ok((raw, last));
+                }
+                None => {
+                    let len = buf.len();
+                    raw.extend_from_slice(buf);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    /// 读取数组结束后的内容，只允许空白
+    ///
+    /// # 返回值
+    /// 数组后的空白
+    async fn finish(&mut self) -> anyhow::Result<Vec<u8>> {
+        let suffix = self.read_whitespace().await?;
+        if !self.reader.fill_buf().await?.is_empty() {
+            return Err(
+                TranslatorError::InvalidJson("trailing data after the array".to_string()).into(),
+            );
+        }
+        Ok(suffix)
+    }
+
+    /// 读取连续的空白
+    async fn read_whitespace(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut whitespace = Vec::new();
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            let len = buf.iter().take_while(|b| is_whitespace(b)).count();
+            whitespace.extend_from_slice(&buf[..len]);
+            let done = len < buf.len() || buf.is_empty();
+            self.reader.consume(len);
+            if done {
+                return Ok(whitespace);
+            }
+        }
+    }
+}
+
+/// 是否为JSON的空白字符
+fn is_whitespace(byte: &u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// 读入的数组元素
+struct Element {
+    /// 元素下标，从0开始
+    index: usize,
+    /// 原始字节
+    raw: Vec<u8>,
+    /// 需要翻译的字符串（包括引号）在原始字节中的位置
+    strings: Vec<Range<usize>>,
+}
+
+impl Element {
+    /// 检查元素是否为有效的JSON，并找出路径指向的字符串
+    ///
+    /// # 参数
+    /// - `index`: 元素下标，从0开始
+    /// - `raw`: 原始字节
+    /// - `paths`: 待翻译字段的路径
+    fn parse(index: usize, raw: Vec<u8>, paths: &[JsonPath]) -> Result<Self, TranslatorError> {
+        serde_json::from_slice::<IgnoredAny>(&raw)
+            .map_err(|e| TranslatorError::InvalidJson(format!("element {}: {}", index + 1, e)))?;
+        let mut walker = Walker {
+            bytes: &raw,
+            pos: 0,
+            paths,
+            steps: Vec::new(),
+            strings: Vec::new(),
+        };
+        walker.value();
+        let strings = walker.strings;
+        Ok(Self {
+            index,
+            raw,
+            strings,
+        })
+    }
+}
+
+/// 在已确认有效的JSON中查找路径指向的字符串
+struct Walker<'a> {
+    /// 元素的原始字节
+    bytes: &'a [u8],
+    /// 当前位置
+    pos: usize,
+    /// 待翻译字段的路径
+    paths: &'a [JsonPath],
+    /// 从元素根部到当前值的路径，只在可能匹配时记录
+    steps: Vec<Step>,
+    /// 找到的字符串位置
+    strings: Vec<Range<usize>>,
+}
+
+impl Walker<'_> {
+    /// 跳过空白
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// 当前值下是否可能有需要翻译的字符串
+    fn live(&self) -> bool {
+        self.paths.iter().any(|path| path.starts_with(&self.steps))
+    }
+
+    /// 读取一个值，记录路径指向的字符串
+    fn value(&mut self) {
+        self.skip_whitespace();
+        let live = self.live();
+        match self.bytes[self.pos] {
+            b'"' => {
+                let range = self.string();
+                if self.paths.iter().any(|path| path.matches(&self.steps)) {
+                    self.strings.push(range);
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                self.skip_whitespace();
+                while self.bytes[self.pos] != b'}' {
+                    self.skip_whitespace();
+                    let key = self.string();
+                    self.skip_whitespace();
+                    // 跳过`:`
+                    self.pos += 1;
+                    let step = live.then(|| Step::Key(decode_key(&self.bytes[key])));
+                    self.child(step);
+                    self.skip_whitespace();
+                    if self.bytes[self.pos] == b',' {
+                        self.pos += 1;
+                    }
+                }
+                self.pos += 1;
+            }
+            b'[' => {
+                self.pos += 1;
+                self.skip_whitespace();
+                let mut index = 0;
+                while self.bytes[self.pos] != b']' {
+                    self.child(live.then_some(Step::Index(index)));
+                    index += 1;
+                    self.skip_whitespace();
+                    if self.bytes[self.pos] == b',' {
+                        self.pos += 1;
+                    }
+                }
+                self.pos += 1;
+            }
+            _ => {
+                while !matches!(
+                    self.bytes.get(self.pos),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// 读取对象或数组中的一个值
+    ///
+    /// 父级下不可能有需要翻译的字符串时不记录路径，直接跳过
+    ///
+    /// # 参数
+    /// - `step`: 这一步，父级下不可能有需要翻译的字符串时为None
+    fn child(&mut self, step: Option<Step>) {
+        let Some(step) = step else {
+            let paths = std::mem::take(&mut self.paths);
+            self.value();
+            self.paths = paths;
+            return;
+        };
+        self.steps.push(step);
+        self.value();
+        self.steps.pop();
+    }
+
+    /// 读取一个字符串
+    ///
+    /// # 返回值
+    /// 字符串（包括引号）的位置
+    fn string(&mut self) -> Range<usize> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.bytes[self.pos] {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return start..self.pos;
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+}
+
+/// 解码字段名
+///
+/// # 参数
+/// - `raw`: 字段名的原始字节，包括引号
+fn decode_key(raw: &[u8]) -> String {
+    match raw.contains(&b'\\') {
+        true => serde_json::from_slice(raw).unwrap_or_default(),
+        false => String::from_utf8_lossy(&raw[1..raw.len() - 1]).into_owned(),
+    }
+}
+
+/// 流式翻译顶层为数组的JSON文件
+///
+/// 逐个读取数组元素，每攒够`elements_per_batch`个元素翻译一次并立即写出，
+/// 内存占用与文件大小无关。只替换路径指向的字符串，其余内容（包括空白、字段顺序和转义写法）按原样写出；
+/// 空白字符串不发送给翻译器。
+///
+/// 输入在中途出错时，已翻译的元素已经写出，输出不是完整的JSON
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `reader`: 输入
+/// - `writer`: 输出
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 路径和批量选项
+///
+/// # 返回值
+/// 统计；输入不是有效的JSON数组时返回`TranslatorError::InvalidJson`
+pub async fn translate_json_array<R, W>(
+    translator: &dyn AsyncTranslator,
+    reader: R,
+    writer: &mut W,
+    from: Option<Language>,
+    to: &Language,
+    options: &JsonArrayOptions,
+) -> anyhow::Result<JsonArrayReport>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = ArrayReader {
+        reader,
+        elements: 0,
+    };
+    writer.write_all(&reader.open().await?).await?;
+    let mut report = JsonArrayReport::default();
+    let mut pending = Vec::new();
+    loop {
+        let (raw, last) = reader.next().await?;
+        if last && report.elements == 0 && raw.iter().all(is_whitespace) {
+            writer.write_all(&raw).await?;
+            break;
+        }
+        pending.push(Element::parse(report.elements, raw, &options.paths)?);
+        report.elements += 1;
+        if pending.len() >= options.elements_per_batch || last {
+            let elements = std::mem::take(&mut pending);
+            translate_elements(translator, elements, writer, from, to, options, &mut report)
+                .await?;
+        }
+        if last {
+            break;
+        }
+    }
+    writer.write_all(b"]").await?;
+    writer.write_all(&reader.finish().await?).await?;
+    writer.flush().await?;
+    Ok(report)
+}
+
+/// 翻译一批元素并写出
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `elements`: 按顺序排列的元素
+/// - `writer`: 输出
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 路径和批量选项
+/// - `report`: 累计的统计
+async fn translate_elements<W: AsyncWrite + Unpin>(
+    translator: &dyn AsyncTranslator,
+    elements: Vec<Element>,
+    writer: &mut W,
+    from: Option<Language>,
+    to: &Language,
+    options: &JsonArrayOptions,
+    report: &mut JsonArrayReport,
+) -> anyhow::Result<()> {
+    let mut texts = HashMap::new();
+    let mut locations = Vec::new();
+    for (e, element) in elements.iter().enumerate() {
+        for (s, range) in element.strings.iter().enumerate() {
+            let text: String = serde_json::from_slice(&element.raw[range.clone()])?;
+            if !text.trim().is_empty() {
+                texts.insert(locations.len(), text);
+                locations.push((e, s));
+            }
+        }
+    }
+    let mut result = translate_map_with(translator, &texts, from, to, &options.batch)
+        .await
+        .with_context(|| {
+            let first = elements.first().map_or(0, |e| e.index);
+            format!("elements {}..{}", first, first + elements.len())
+        })?;
+    report.translated += result.translations.len();
+    report.untranslated += result.errors.len();
+
+    let mut replacements = elements
+        .iter()
+        .map(|element| vec![None; element.strings.len()])
+        .collect::<Vec<_>>();
+    for (key, (e, s)) in locations.into_iter().enumerate() {
+        if let Some(output) = result.translations.remove(&key) {
+            replacements[e][s] = Some(serde_json::to_vec(&output.text)?);
+        }
+    }
+    let mut out = Vec::new();
+    for (element, replacements) in elements.iter().zip(replacements) {
+        if element.index > 0 {
+            out.push(b',');
+        }
+        let mut cursor = 0;
+        for (range, replacement) in element.strings.iter().zip(replacements) {
+            out.extend_from_slice(&element.raw[cursor..range.start]);
+            match replacement {
+                Some(text) => out.extend_from_slice(&text),
+                None => out.extend_from_slice(&element.raw[range.clone()]),
+            }
+            cursor = range.end;
+        }
+        out.extend_from_slice(&element.raw[cursor..]);
+    }
+    writer.write_all(&out).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::batch::BatchOptions;
+    use crate::fusion_translator::i18n_files::{
+        translate_json_array, Element, JsonArrayOptions, JsonArrayReport, JsonPath, PathSegment,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translator_error::TranslatorError;
+
+    /// 按路径翻译JSON数组
+    ///
+    /// # 参数
+    /// - `input`: 输入的JSON
+    /// - `paths`: 待翻译字段的路径
+    /// - `mock`: 模拟翻译器
+    async fn translate(
+        input: &str,
+        paths: &[&str],
+        mock: &MockTranslator,
+    ) -> anyhow::Result<(String, JsonArrayReport)> {
+        let paths = paths.iter().map(|path| path.parse().unwrap());
+        let options = JsonArrayOptions::new(paths)
+            .with_elements_per_batch(2)
+            .with_batch_options(BatchOptions {
+                partial: true,
+                ..Default::default()
+            });
+        let mut output = Vec::new();
+        let report = translate_json_array(
+            mock,
+            input.as_bytes(),
+            &mut output,
+            None,
+            &Language::Chinese,
+            &options,
+        )
+        .await?;
+        Ok((String::from_utf8(output).unwrap(), report))
+    }
+
+    /// 测试路径的解析和格式化
+    #[test]
+    fn test_parse_path() {
+        let path: JsonPath = "items[*].labels.*".parse().unwrap();
+        assert_eq!(
+            path.segments(),
+            [
+                PathSegment::Field("items".to_string()),
+                PathSegment::AnyIndex,
+                PathSegment::Field("labels".to_string()),
+                PathSegment::AnyField,
+            ]
+        );
+        assert_eq!(path.to_string(), "items[*].labels.*");
+        let path: JsonPath = "[0].title".parse().unwrap();
+        assert_eq!(path.segments()[0], PathSegment::Index(0));
+        assert_eq!(path.to_string(), "[0].title");
+        for invalid in ["", "a..b", "a[", "a[x]", "a]b", ".a", "a[*]b"] {
+            assert!(
+                matches!(
+                    invalid.parse::<JsonPath>(),
+                    Err(TranslatorError::InvalidJson(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    /// 测试只替换路径指向的字符串，其余内容按原样写出
+    #[tokio::test]
+    async fn test_preserves_everything_else() {
+        let input = concat!(
+            " [\n",
+            "  {\"id\": 1, \"title\": \"hello\", \"note\": \"keep\",\n",
+            "   \"items\": [{\"description\": \"first \\\"item\\\"\"}, {\"description\": null}]},\n",
+            "  {\"title\": \"  \", \"items\": [], \"z\": \"a,]}\"},\n",
+            "  {\"title\": 42, \"n\\u0061me\": \"x\", \"items\": [{\"description\": \"\\u00e9t\\u00e9\"}]}\n",
+            "] \n"
+        );
+        let mock = MockTranslator::new();
+        let (output, report) = translate(input, &["title", "items[*].description"], &mock)
+            .await
+            .unwrap();
+        let expected = input
+            .replace("\"hello\"", "\"HELLO\"")
+            .replace("\"first \\\"item\\\"\"", "\"FIRST \\\"ITEM\\\"\"")
+            .replace("\"\\u00e9t\\u00e9\"", "\"ÉTÉ\"");
+        assert_eq!(output, expected);
+        assert_eq!(
+            report,
+            JsonArrayReport {
+                elements: 3,
+                translated: 3,
+                untranslated: 0,
+            }
+        );
+        // 每批2个元素
+        assert_eq!(mock.calls(), 2);
+    }
+
+    /// 测试允许部分失败时被拒绝的字符串保留原文，同一批次的其他字符串正常翻译
+    #[tokio::test]
+    async fn test_partial_failure_keeps_source() {
+        let input = r#"[{"t":"ok"},{"t":"fails here"}]"#;
+        let mock = MockTranslator::new().reject_on("fails");
+        let (output, report) = translate(input, &["t"], &mock).await.unwrap();
+        assert_eq!(output, r#"[{"t":"OK"},{"t":"fails here"}]"#);
+        assert_eq!(report.untranslated, 1);
+    }
+
+    /// 测试空数组和无效的输入
+    #[tokio::test]
+    async fn test_empty_and_invalid_input() {
+        let mock = MockTranslator::new();
+        let (output, report) = translate("[ ]", &["t"], &mock).await.unwrap();
+        assert_eq!(output, "[ ]");
+        assert_eq!(report.elements, 0);
+        for invalid in [
+            "{\"t\": 1}",
+            "[{\"t\": 1}",
+            "[{\"t\": 1},]",
+            "[{\"t\": }]",
+            "[1] x",
+            "[}]",
+        ] {
+            let err = translate(invalid, &["t"], &mock).await.unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<TranslatorError>(),
+                    Some(TranslatorError::InvalidJson(_))
+                ),
+                "{}: {:#}",
+                invalid,
+                err
+            );
+        }
+    }
+
+    /// 模糊测试：任意路径和元素的解析都不会panic，找到的字符串都在引号之间
+    #[test]
+    fn test_fuzz_parse() {
+        let paths = ["title", "items[*].label", "meta.*", "a[0]"]
+            .iter()
+            .map(|path| path.parse::<JsonPath>().unwrap())
+            .collect::<Vec<_>>();
+        for input in fuzz::inputs(701, &["items[*].label", "meta.\"quoted.key\"[3].*"]) {
+            if let Ok(path) = input.parse::<JsonPath>() {
+                assert_eq!(path.to_string().parse::<JsonPath>().unwrap(), path);
+            }
+        }
+        let corpus = [
+            r#"{"title": "Hello \"world\"", "items": [{"label": "a\u00e9"}, {"label": 1}], "meta": {"k": "v", "n": null}}"#,
+            r#"{"a": ["x", "y"], "\u0074itle": "escaped key"}"#,
+        ];
+        for (i, input) in fuzz::inputs(701, &corpus).into_iter().enumerate() {
+            if let Ok(element) = Element::parse(i, input.clone().into_bytes(), &paths) {
+                for range in &element.strings {
+                    assert_eq!(element.raw[range.start], b'"', "{:?}", input);
+                    assert_eq!(element.raw[range.end - 1], b'"', "{:?}", input);
+                }
+            }
+        }
+    }
+}
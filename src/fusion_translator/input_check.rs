@@ -0,0 +1,433 @@
+use crate::fusion_translator::postprocess::is_cjk;
+use crate::fusion_translator::segment::split_sentence_ranges;
+use crate::fusion_translator::similarity::graphemes;
+use crate::fusion_translator::translator_error::TranslatorError;
+
+/// 估算词元数时，单词中每多少个字素簇计为一个词元
+const GRAPHEMES_PER_TOKEN: usize = 4;
+
+/// 翻译服务计算输入长度的单位
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InputUnit {
+    /// UTF-8字节数
+    #[default]
+    Bytes,
+    /// Unicode字符（标量值）数
+    Chars,
+    /// UTF-16码元数，基本多文种平面以外的字符（如大多数表情符号）计为2
+    Utf16,
+    /// 估算的词元数：西文单词每4个字素簇计1个，中日韩字符、标点和符号各计1个，空白不计
+    Tokens,
+}
+
+/// 输入长度检查结果
+///
+/// 由`AsyncTranslator::check_input`给出，用于在消耗配额之前提示用户输入过长，并展示拆分位置
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputCheck {
+    /// 按`unit`计算的输入长度
+    pub length: usize,
+    /// 单次请求的长度上限，None表示没有限制
+    pub limit: Option<usize>,
+    /// 长度单位
+    pub unit: InputUnit,
+    /// 自动拆分时的拆分位置（字节偏移，严格递增），未超出上限时为空
+    ///
+    /// 拆分位置总是在字素簇边界上，不会拆开表情符号序列、国旗或组合字符
+    pub would_split_at: Vec<usize>,
+}
+
+impl InputCheck {
+    /// 检查输入长度并计算拆分位置
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `limit`: 单次请求的长度上限，None表示没有限制
+    /// - `unit`: 长度单位
+    pub fn new(query: &str, limit: Option<usize>, unit: InputUnit) -> Self {
+        let length = measure(query, unit);
+        let would_split_at = match limit {
+            Some(limit) if length > limit => split_points(query, limit, unit),
+            _ => Vec::new(),
+        };
+        Self {
+            length,
+            limit,
+            unit,
+            would_split_at,
+        }
+    }
+
+    /// 是否超出上限
+    pub fn exceeds(&self) -> bool {
+        self.limit.is_some_and(|limit| self.length > limit)
+    }
+
+    /// 超出上限时返回`TranslatorError::RequestTooLong`，长度和上限按`unit`计算
+    pub fn check(&self) -> Result<(), TranslatorError> {
+        match self.limit {
+            Some(limit) if self.length > limit => Err(TranslatorError::RequestTooLong(
+                self.length as u32,
+                limit as u32,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// 按拆分位置切分原文
+    ///
+    /// # 参数
+    /// - `query`: 检查时使用的文本
+    ///
+    /// # 返回值
+    /// 按顺序拼接后与原文相同的片段，空文本返回空列表
+    pub fn parts<'a>(&self, query: &'a str) -> Vec<&'a str> {
+        let mut parts = Vec::with_capacity(self.would_split_at.len() + 1);
+        let mut start = 0;
+        for &point in &self.would_split_at {
+            parts.push(&query[start..point]);
+            start = point;
+        }
+        if start < query.len() {
+            parts.push(&query[start..]);
+        }
+        parts
+    }
+}
+
+/// 按单位计算文本长度
+///
+/// # 参数
+/// - `text`: 文本
+/// - `unit`: 长度单位
+pub fn measure(text: &str, unit: InputUnit) -> usize {
+    match unit {
+        InputUnit::Bytes => text.len(),
+        InputUnit::Chars => text.chars().count(),
+        InputUnit::Utf16 => text.encode_utf16().count(),
+        InputUnit::Tokens => {
+            let mut counter = Counter::new(unit);
+            counter.push_str(text);
+            counter.total
+        }
+    }
+}
+
+/// 计算超长文本的拆分位置
+///
+/// 优先在句子边界处拆分，并把尽量多的句子合并到同一段；单个句子超出上限时在空白处拆分，
+/// 仍然超出时在字素簇边界处截断。拆分位置不会落在字素簇内部，
+/// 因此单个字素簇（如很长的表情符号序列）超出上限时，所在的片段也会超出上限
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+/// - `limit`: 每段的长度上限
+/// - `unit`: 长度单位
+///
+/// # 返回值
+/// 严格递增的字节偏移，不含0和文本长度；不超出上限时为空
+pub fn split_points(text: &str, limit: usize, unit: InputUnit) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut offset = 0;
+    for cluster in graphemes(text) {
+        offset += cluster.len();
+        boundaries.push(offset);
+    }
+    let mut points = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut counter = Counter::new(unit);
+    // 句子边界落在字素簇内部时（如换行后紧跟组合字符）与下一句合并
+    let ends = split_sentence_ranges(text, None)
+        .into_iter()
+        .map(|range| range.end)
+        .filter(|end| boundaries.binary_search(end).is_ok());
+    for sentence_end in ends {
+        let mut next = counter.clone();
+        next.push_str(&text[end..sentence_end]);
+        if next.total <= limit {
+            counter = next;
+            end = sentence_end;
+            continue;
+        }
+        if end > start {
+            points.push(end);
+            start = end;
+        }
+        while let Some(cut) = hard_cut(&text[start..sentence_end], limit, unit) {
+            start += cut;
+            points.push(start);
+        }
+        counter = Counter::new(unit);
+        counter.push_str(&text[start..sentence_end]);
+        end = sentence_end;
+    }
+    points
+}
+
+/// 在不超过`limit`的位置截断超长的句子
+///
+/// # 返回值
+/// 截断位置，优先选择最后一个空白之后，其次是最后一个不超出上限的字素簇边界；
+/// 第一个字素簇就超出上限时在它之后截断；整句不超出上限或只有一个字素簇时为None
+fn hard_cut(text: &str, limit: usize, unit: InputUnit) -> Option<usize> {
+    let mut counter = Counter::new(unit);
+    let mut fits = 0;
+    let mut after_space = 0;
+    for cluster in graphemes(text) {
+        counter.push(cluster);
+        if counter.total > limit {
+            return Some(match (after_space, fits) {
+                (0, 0) if cluster.len() == text.len() => return None,
+                (0, 0) => cluster.len(),
+                (0, fits) => fits,
+                (after_space, _) => after_space,
+            });
+        }
+        fits += cluster.len();
+        if cluster.starts_with(char::is_whitespace) && fits < text.len() {
+            after_space = fits;
+        }
+    }
+    None
+}
+
+/// 逐个字素簇累计长度
+#[derive(Clone, Debug)]
+struct Counter {
+    /// 长度单位
+    unit: InputUnit,
+    /// 累计长度
+    total: usize,
+    /// 当前西文单词中已有的字素簇数，用于估算词元
+    word: usize,
+}
+
+impl Counter {
+    /// 创建长度为0的计数器
+    fn new(unit: InputUnit) -> Self {
+        Self {
+            unit,
+            total: 0,
+            word: 0,
+        }
+    }
+
+    /// 累计一个字素簇
+    fn push(&mut self, cluster: &str) {
+        self.total += match self.unit {
+            InputUnit::Bytes => cluster.len(),
+            InputUnit::Chars => cluster.chars().count(),
+            InputUnit::Utf16 => cluster.encode_utf16().count(),
+            InputUnit::Tokens => self.token_cost(cluster),
+        };
+    }
+
+    /// 累计一段从字素簇边界开始的文本
+    fn push_str(&mut self, text: &str) {
+        for cluster in graphemes(text) {
+            self.push(cluster);
+        }
+    }
+
+    /// 字素簇的估算词元数
+    fn token_cost(&mut self, cluster: &str) -> usize {
+        let Some(first) = cluster.chars().next() else {
+            return 0;
+        };
+        if first.is_alphanumeric() && !is_cjk(first) {
+            self.word += 1;
+            return usize::from((self.word - 1).is_multiple_of(GRAPHEMES_PER_TOKEN));
+        }
+        self.word = 0;
+        usize::from(!first.is_whitespace())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::AsyncTranslator;
+    use crate::fusion_translator::input_check::{measure, split_points, InputCheck, InputUnit};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::similarity::graphemes;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translator_error::TranslatorError;
+
+    /// 所有单位
+    const UNITS: [InputUnit; 4] = [
+        InputUnit::Bytes,
+        InputUnit::Chars,
+        InputUnit::Utf16,
+        InputUnit::Tokens,
+    ];
+
+    /// 文本的所有字素簇边界（字节偏移）
+    fn boundaries(text: &str) -> Vec<usize> {
+        graphemes(text)
+            .iter()
+            .scan(0, |offset, cluster| {
+                *offset += cluster.len();
+                Some(*offset)
+            })
+            .collect()
+    }
+
+    /// 测试各单位的长度计算
+    #[test]
+    fn test_measure() {
+        let text = "Hi 👨‍👩‍👧 你好";
+        assert_eq!(measure(text, InputUnit::Bytes), 28);
+        assert_eq!(measure(text, InputUnit::Chars), 11);
+        assert_eq!(measure(text, InputUnit::Utf16), 14);
+        assert_eq!(measure(text, InputUnit::Tokens), 4);
+        assert_eq!(measure("internationalization", InputUnit::Tokens), 5);
+        assert_eq!(measure("", InputUnit::Tokens), 0);
+    }
+
+    /// 测试拆分位置优先落在句子边界和空白处
+    #[test]
+    fn test_split_points() {
+        let text = "第一句话。第二句话！Third sentence. Fourth one?";
+        let check = InputCheck::new(text, Some(10), InputUnit::Chars);
+        assert!(check.exceeds());
+        assert_eq!(check.length, 37);
+        assert_eq!(
+            check.parts(text),
+            vec![
+                "第一句话。第二句话！",
+                "Third ",
+                "sentence. ",
+                "Fourth ",
+                "one?"
+            ]
+        );
+        assert!(matches!(
+            check.check(),
+            Err(TranslatorError::RequestTooLong(37, 10))
+        ));
+
+        let check = InputCheck::new(text, None, InputUnit::Bytes);
+        assert!(!check.exceeds() && check.check().is_ok());
+        assert!(check.would_split_at.is_empty());
+        assert_eq!(check.parts(text), vec![text]);
+        assert!(InputCheck::new("", Some(1), InputUnit::Bytes)
+            .parts("")
+            .is_empty());
+    }
+
+    /// 测试不会拆开表情符号序列和国旗，单个字素簇超出上限时整体保留
+    #[test]
+    fn test_never_splits_clusters() {
+        let family = "👨‍👩‍👧‍👦";
+        let text = family.repeat(3);
+        assert_eq!(
+            split_points(&text, 10, InputUnit::Bytes),
+            vec![family.len(), family.len() * 2]
+        );
+        assert_eq!(split_points("🇨🇳🇯🇵🇺🇸", 3, InputUnit::Chars), vec![8, 16]);
+        // 换行后紧跟组合字符时句子边界在字素簇内部，不在该处拆分
+        assert_eq!(
+            split_points("ab.\n\u{301}cd", 3, InputUnit::Chars),
+            vec![3, 6]
+        );
+    }
+
+    /// 测试默认实现使用翻译器的上限，以字节为单位
+    #[test]
+    fn test_trait_default() {
+        let translator = MockTranslator::new().with_limits(None, Some(6));
+        assert_eq!(translator.input_unit(), InputUnit::Bytes);
+        let check = translator.check_input("一二三四");
+        assert_eq!(check.length, 12);
+        assert_eq!(check.limit, Some(6));
+        assert_eq!(check.would_split_at, vec![6]);
+        assert!(!MockTranslator::new().check_input("一二三四").exceeds());
+    }
+
+    /// 随机的表情符号文本中，拆分位置总是字素簇边界，
+    /// 除单个字素簇超出上限外每段都不超出上限，拼接后与原文相同
+    #[test]
+    fn test_split_points_random_emoji() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        const ALPHABET: &[&str] = &[
+            "a",
+            "Zz",
+            " ",
+            "\n",
+            ". ",
+            "。",
+            "你",
+            "👍",
+            "👍🏽",
+            "\u{200D}",
+            "👩‍💻",
+            "👨‍👩‍👧‍👦",
+            "🏳️‍🌈",
+            "🇯",
+            "🇵",
+            "🇨🇳",
+            "1️⃣",
+            "e\u{301}",
+            "\u{301}",
+            "\u{FE0F}",
+            "\r\n",
+            "🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}",
+        ];
+        let mut rng = StdRng::seed_from_u64(689);
+        for _ in 0..500 {
+            let text = (0..rng.random_range(0..40))
+                .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())])
+                .collect::<String>();
+            let clusters = boundaries(&text);
+            for unit in UNITS {
+                let limit = rng.random_range(0..24);
+                let check = InputCheck::new(&text, Some(limit), unit);
+                assert_eq!(check.parts(&text).concat(), text);
+                for window in check.would_split_at.windows(2) {
+                    assert!(window[0] < window[1], "{text:?}");
+                }
+                for point in &check.would_split_at {
+                    assert!(*point > 0 && *point < text.len(), "{text:?}");
+                    assert!(clusters.contains(point), "{text:?} {unit:?} {point}");
+                }
+                for part in check.parts(&text) {
+                    assert!(
+                        measure(part, unit) <= limit || graphemes(part).len() == 1,
+                        "{text:?} {unit:?} {limit} {part:?}"
+                    );
+                }
+                if check.would_split_at.is_empty() {
+                    assert!(!check.exceeds() || clusters.len() == 1, "{text:?}");
+                }
+            }
+        }
+    }
+
+    /// 模糊测试：任意文本的拆分位置严格递增、落在字符边界上，各片段拼接后与原文相同
+    #[test]
+    fn test_fuzz_split_points() {
+        let corpus = [
+            include_str!("../../tests/fixtures/long_text/mixed.txt"),
+            "First sentence. Second one!\n\nThird\tparagraph with   spaces.",
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            for unit in [InputUnit::Bytes, InputUnit::Chars, InputUnit::Tokens] {
+                for limit in [0, 1, 4, 17, 64] {
+                    let points = split_points(&input, limit, unit);
+                    assert!(points.windows(2).all(|w| w[0] < w[1]), "{:?}", input);
+                    assert!(
+                        points
+                            .iter()
+                            .all(|&p| p > 0 && p < input.len() && input.is_char_boundary(p)),
+                        "{:?}",
+                        input
+                    );
+                    let check = InputCheck::new(&input, Some(limit), unit);
+                    assert_eq!(check.parts(&input).concat(), input);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use crate::fusion_translator::async_translator::{Language, TranslationOutput};
+use crate::fusion_translator::batch::MapTranslation;
+use crate::fusion_translator::scheduled::{Clock, SystemClock};
+use crate::fusion_translator::tmx::{utc_fields, TmEntry, TranslationMemory};
+use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 一个翻译成功的片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentRecord {
+    /// 片段标识，如命令行中从1开始的行号
+    pub id: String,
+    /// 原文
+    pub src: String,
+    /// 译文
+    pub tgt: String,
+    /// 源语言，自动检测且翻译器没有返回检测结果时为None
+    pub src_lang: Option<Language>,
+    /// 目标语言
+    pub tgt_lang: Language,
+    /// 产生译文的翻译引擎
+    pub engine: String,
+    /// 写入时间，RFC 3339格式的UTC时间，如`2026-01-10T08:30:00Z`
+    pub ts: String,
+}
+
+/// 一个翻译失败的片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorRecord {
+    /// 片段标识
+    pub id: String,
+    /// 错误类别，`ErrorKind::as_str`的返回值
+    pub error_kind: String,
+    /// 错误信息，包括错误链中的上下文
+    pub message: String,
+}
+
+impl ErrorRecord {
+    /// 错误类别，无法识别的名称归为`ErrorKind::Unknown`
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::parse(&self.error_kind).unwrap_or(ErrorKind::Unknown)
+    }
+}
+
+/// JSON Lines文件中的一条记录
+///
+/// 每行一个JSON对象：翻译成功时为`{"id", "src", "tgt", "src_lang", "tgt_lang", "engine", "ts"}`，
+/// 失败时为`{"id", "error_kind", "message"}`，按字段区分，不带类型标记
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonlRecord {
+    /// 翻译成功的片段
+    Segment(SegmentRecord),
+    /// 翻译失败的片段
+    Error(ErrorRecord),
+}
+
+impl JsonlRecord {
+    /// 片段标识
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Segment(record) => &record.id,
+            Self::Error(record) => &record.id,
+        }
+    }
+}
+
+/// 把翻译结果逐条写成JSON Lines
+///
+/// 供机器翻译评测工具读取：每个片段一行，成功和失败的片段都有记录，文件完整记录一次运行的结果。
+/// 每条记录写入后立即刷新，进程中断时最多留下半行，`JsonlReader`读取时会跳过
+pub struct JsonlWriter<W> {
+    /// 输出
+    writer: W,
+    /// 记录中的引擎名称
+    engine: String,
+    /// 记录写入时间的时钟
+    clock: Arc<dyn Clock>,
+    /// 已写入的记录数
+    written: usize,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    /// 创建写入器
+    ///
+    /// # 参数
+    /// - `writer`: 输出
+    /// - `engine`: 成功记录中的引擎名称
+    pub fn new(writer: W, engine: impl Into<String>) -> Self {
+        Self {
+            writer,
+            engine: engine.into(),
+            clock: Arc::new(SystemClock),
+            written: 0,
+        }
+    }
+
+    /// 设置记录写入时间的时钟
+    ///
+    /// # 参数
+    /// - `clock`: 时钟，测试中可使用`MockClock`得到确定的时间
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 写入一条记录
+    ///
+    /// # 参数
+    /// - `record`: 记录
+    pub fn write_record(&mut self, record: &JsonlRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.writer.flush()?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// 写入一个翻译成功的片段，时间为时钟的当前时间
+    ///
+    /// # 参数
+    /// - `id`: 片段标识
+    /// - `src`: 原文
+    /// - `from`: 请求的源语言，None表示自动检测，此时记录译文结果中的语言
+    /// - `to`: 目标语言
+    /// - `output`: 翻译结果
+    pub fn write_output(
+        &mut self,
+        id: impl Display,
+        src: &str,
+        from: Option<Language>,
+        to: &Language,
+        output: &TranslationOutput,
+    ) -> io::Result<()> {
+        let (year, month, day, hour, minute, second) = utc_fields(self.clock.now());
+        let record = SegmentRecord {
+            id: id.to_string(),
+            src: src.to_string(),
+            tgt: output.text.clone(),
+            src_lang: from.or(output.lang),
+            tgt_lang: *to,
+            engine: self.engine.clone(),
+            ts: format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                year, month, day, hour, minute, second
+            ),
+        };
+        self.write_record(&JsonlRecord::Segment(record))
+    }
+
+    /// 写入一个翻译失败的片段
+    ///
+    /// # 参数
+    /// - `id`: 片段标识
+    /// - `error`: 翻译器返回的错误
+    pub fn write_error(&mut self, id: impl Display, error: &anyhow::Error) -> io::Result<()> {
+        let record = ErrorRecord {
+            id: id.to_string(),
+            error_kind: ErrorKind::of(error).as_str().to_string(),
+            message: format!("{:#}", error),
+        };
+        self.write_record(&JsonlRecord::Error(record))
+    }
+
+    /// 按键的顺序写入键值表翻译的全部结果
+    ///
+    /// 键作为片段标识，每个键写入一条成功或失败的记录
+    ///
+    /// # 参数
+    /// - `map`: 翻译的键值表
+    /// - `result`: `batch::translate_map_with`的结果
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    pub fn write_map<K>(
+        &mut self,
+        map: &HashMap<K, String>,
+        result: &MapTranslation<K>,
+        from: Option<Language>,
+        to: &Language,
+    ) -> io::Result<()>
+    where
+        K: Display + Ord + Hash + Eq,
+    {
+        let mut entries = map.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, src) in entries {
+            if let Some(output) = result.translations.get(key) {
+                self.write_output(key, src, from, to, output)?;
+            } else if let Some(error) = result.errors.get(key) {
+                self.write_error(key, error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 已写入的记录数
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// 取回输出
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// 读取`JsonlWriter`写出的JSON Lines
+///
+/// 读取的记录可以导入为翻译记忆库，也可以作为检查点：`completed`中的片段不需要重新翻译
+#[derive(Debug, Clone, Default)]
+pub struct JsonlReader {
+    /// 按文件顺序排列的记录
+    records: Vec<JsonlRecord>,
+}
+
+impl JsonlReader {
+    /// 从文件读取
+    ///
+    /// # 参数
+    /// - `path`: JSONL文件路径
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// 解析JSON Lines文本
+    ///
+    /// 跳过空行；最后一行没有换行符且无法解析时视为写入时中断留下的半行，记录警告后跳过
+    ///
+    /// # 参数
+    /// - `text`: JSONL内容
+    ///
+    /// # 返回值
+    /// 读取器；其他无法解析的行返回`TranslatorError::InvalidJson`
+    pub fn parse(text: &str) -> Result<Self, TranslatorError> {
+        let mut records = Vec::new();
+        let mut lines = text.split('\n').enumerate().peekable();
+        while let Some((index, line)) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(_) if lines.peek().is_none() => {
+                    tracing::warn!("skipping truncated last line {} of JSONL", index + 1);
+                }
+                Err(e) => {
+                    return Err(TranslatorError::InvalidJson(format!(
+                        "line {}: {}",
+                        index + 1,
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(Self { records })
+    }
+
+    /// 按文件顺序排列的记录
+    pub fn records(&self) -> &[JsonlRecord] {
+        &self.records
+    }
+
+    /// 取出全部记录
+    pub fn into_records(self) -> Vec<JsonlRecord> {
+        self.records
+    }
+
+    /// 已翻译成功的片段，片段标识 -> 译文
+    ///
+    /// 同一标识出现多次时以最后一条记录为准，因此重新运行的结果可以追加到同一个文件：
+    /// 之前失败、之后成功的片段算作完成，之后又失败的片段不算
+    pub fn completed(&self) -> HashMap<String, String> {
+        let mut completed = HashMap::new();
+        for record in &self.records {
+            match record {
+                JsonlRecord::Segment(segment) => {
+                    completed.insert(segment.id.clone(), segment.tgt.clone());
+                }
+                JsonlRecord::Error(error) => {
+                    completed.remove(&error.id);
+                }
+            }
+        }
+        completed
+    }
+
+    /// 导入为翻译记忆库
+    ///
+    /// 源语言未知的片段和失败的片段不导入；创建时间由`ts`转换为TMX格式
+    pub fn to_memory(&self) -> TranslationMemory {
+        let mut memory = TranslationMemory::new();
+        for record in &self.records {
+            let JsonlRecord::Segment(segment) = record else {
+                continue;
+            };
+            let Some(from) = segment.src_lang else {
+                continue;
+            };
+            memory.insert_entry(
+                from,
+                segment.tgt_lang,
+                TmEntry {
+                    source: segment.src.clone(),
+                    target: segment.tgt.clone(),
+                    creation_date: segment.ts.replace(['-', ':'], ""),
+                    engine: Some(segment.engine.clone()),
+                },
+            );
+        }
+        memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::batch::{translate_map_with, BatchOptions};
+    use crate::fusion_translator::jsonl::{JsonlReader, JsonlRecord, JsonlWriter};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::scheduled::MockClock;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// 测试部分失败的批量翻译导出后重新导入，每条记录都保留
+    #[tokio::test]
+    async fn test_round_trip_with_partial_failures() {
+        let translator = MockTranslator::new().reject_on("bad");
+        let map = [(1, "hello"), (2, "bad"), (3, "world")]
+            .into_iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect::<HashMap<_, _>>();
+        let options = BatchOptions {
+            partial: true,
+            chunk_size: 1,
+            ..Default::default()
+        };
+        let result = translate_map_with(&translator, &map, None, &Language::French, &options)
+            .await
+            .unwrap();
+
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_735_787_045));
+        let mut writer = JsonlWriter::new(Vec::new(), "mock").with_clock(Arc::new(clock));
+        writer
+            .write_map(&map, &result, Some(Language::English), &Language::French)
+            .unwrap();
+        assert_eq!(writer.written(), 3);
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            r#"{"id":"1","src":"hello","tgt":"HELLO","src_lang":"en","tgt_lang":"fr","engine":"mock","ts":"2025-01-02T03:04:05Z"}"#
+        );
+        assert!(lines[1].starts_with(r#"{"id":"2","error_kind":"content_rejected","message":"#));
+
+        let reader = JsonlReader::parse(&text).unwrap();
+        let ids = reader
+            .records()
+            .iter()
+            .map(JsonlRecord::id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "2", "3"]);
+        let JsonlRecord::Error(error) = &reader.records()[1] else {
+            panic!("expected an error record");
+        };
+        assert_eq!(error.kind(), ErrorKind::ContentRejected);
+
+        let completed = reader.completed();
+        assert_eq!(completed.len(), 2);
+        assert_eq!(completed["3"], "WORLD");
+
+        let memory = reader.to_memory();
+        assert_eq!(memory.len(), 2);
+        let entry = memory
+            .get(Language::English, Language::French, "hello")
+            .unwrap();
+        assert_eq!(entry.target, "HELLO");
+        assert_eq!(entry.creation_date, "20250102T030405Z");
+        assert_eq!(entry.engine.as_deref(), Some("mock"));
+
+        // 重新导出后与原文件完全一致
+        let mut writer = JsonlWriter::new(Vec::new(), "mock");
+        for record in reader.records() {
+            writer.write_record(record).unwrap();
+        }
+        assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), text);
+    }
+
+    /// 测试重新运行的结果追加到同一文件时以最后一条记录为准，末尾的半行被跳过
+    #[test]
+    fn test_later_records_win_and_torn_line_is_skipped() {
+        let text = concat!(
+            r#"{"id":"1","error_kind":"network","message":"timeout"}"#,
+            "\n",
+            r#"{"id":"2","src":"b","tgt":"B","src_lang":null,"tgt_lang":"fr","engine":"mock","ts":"2026-01-10T00:00:00Z"}"#,
+            "\n\n",
+            r#"{"id":"1","src":"a","tgt":"A","src_lang":"en","tgt_lang":"fr","engine":"mock","ts":"2026-01-10T00:00:01Z"}"#,
+            "\n",
+            r#"{"id":"2","error_kind":"rate_limited","message":"slow down"}"#,
+            "\n",
+            r#"{"id":"3","src":"c","tg"#,
+        );
+        let reader = JsonlReader::parse(text).unwrap();
+        assert_eq!(reader.records().len(), 4);
+        let completed = reader.completed();
+        assert_eq!(
+            completed.into_iter().collect::<Vec<_>>(),
+            [("1".to_string(), "A".to_string())]
+        );
+        // 源语言未知的片段不导入翻译记忆库
+        assert_eq!(reader.to_memory().len(), 1);
+
+        let err = JsonlReader::parse("{\"id\":\"1\"}\n{}\n").unwrap_err();
+        assert!(
+            matches!(err, TranslatorError::InvalidJson(ref reason) if reason.starts_with("line 1:"))
+        );
+    }
+
+    /// 模糊测试：任意输入都能解析或返回错误，不会panic
+    #[test]
+    fn test_fuzz_parse() {
+        let corpus = [concat!(
+            r#"{"id":"1","src":"a","tgt":"A","src_lang":"en","tgt_lang":"fr","engine":"mock","ts":"2026-01-10T00:00:01Z"}"#,
+            "\n",
+            r#"{"id":"2","error_kind":"network","message":"timeout"}"#,
+            "\n",
+        )];
+        for input in fuzz::inputs(707, &corpus) {
+            if let Ok(reader) = JsonlReader::parse(&input) {
+                reader.completed();
+                reader.to_memory();
+            }
+        }
+    }
+}
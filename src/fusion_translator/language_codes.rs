@@ -0,0 +1,300 @@
+use crate::fusion_translator::async_translator::Language;
+#[cfg(feature = "whatlang")]
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// 生成书写系统枚举及其代码转换函数
+macro_rules! scripts {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// 书写系统
+        ///
+        /// 变体名即ISO 15924代码，只收录语言代码表中出现的书写系统
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Script {
+            $(
+                #[doc = $name]
+                $variant,
+            )*
+        }
+
+        impl Script {
+            /// 所有书写系统
+            pub fn all() -> &'static [Script] {
+                &[$(Script::$variant),*]
+            }
+
+            /// ISO 15924代码，如`Latn`、`Hans`
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Script::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+scripts! {
+    Arab => "阿拉伯字母",
+    Armn => "亚美尼亚字母",
+    Beng => "孟加拉文",
+    Cyrl => "西里尔字母",
+    Deva => "天城文",
+    Ethi => "埃塞俄比亚文",
+    Geor => "格鲁吉亚字母",
+    Grek => "希腊字母",
+    Gujr => "古吉拉特文",
+    Guru => "古木基文",
+    Hang => "谚文",
+    Hans => "简体汉字",
+    Hant => "繁体汉字",
+    Hebr => "希伯来字母",
+    Jpan => "日文（汉字和假名）",
+    Khmr => "高棉文",
+    Knda => "卡纳达文",
+    Laoo => "老挝文",
+    Latn => "拉丁字母",
+    Mlym => "马拉雅拉姆文",
+    Mymr => "缅甸文",
+    Orya => "奥里亚文",
+    Sinh => "僧伽罗文",
+    Taml => "泰米尔文",
+    Telu => "泰卢固文",
+    Tfng => "提非纳字母",
+    Thai => "泰文",
+    Tibt => "藏文",
+}
+
+impl Script {
+    /// 按ISO 15924代码查找书写系统，不区分大小写
+    ///
+    /// # 参数
+    /// - `code`: 书写系统代码，如`Latn`、`hant`
+    pub fn from_code(code: &str) -> Option<Script> {
+        Script::all()
+            .iter()
+            .find(|script| script.code().eq_ignore_ascii_case(code))
+            .copied()
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// 语言代码表中书写系统有误或缺失的语言
+///
+/// 代码表的NLLB列把简体中文和繁体中文标反，名称中带书写系统的变体以名称为准
+const SCRIPT_OVERRIDES: &[(Language, Script)] = &[
+    (Language::Chinese, Script::Hans),
+    (Language::ChineseTraditional, Script::Hant),
+    (Language::KashmiriDeva, Script::Deva),
+    (Language::MalayArab, Script::Arab),
+    (Language::SerbianLatin, Script::Latn),
+];
+
+/// 不在语言代码表中、但其他库常用的ISO 639-3代码
+///
+/// 如`whatlang`把中文识别为普通话`cmn`
+const ISO639_3_ALIASES: &[(&str, Language)] = &[("cmn", Language::Chinese)];
+
+/// 拆分`eng_Latn`形式的代码
+///
+/// # 返回值
+/// （语言代码，书写系统）
+fn split_script(code: &str) -> (&str, Option<Script>) {
+    match code.split_once('_') {
+        Some((lang, script)) => (lang, Script::from_code(script)),
+        None => (code, None),
+    }
+}
+
+/// 代码表中记录的ISO 639-3代码
+///
+/// 依次取639-3、639-2/T和639-2/B列，均为空时返回None
+fn listed_iso639_3(lang: &Language) -> Option<&'static str> {
+    lang.to_639_3()
+        .map(|code| split_script(code).0)
+        .or_else(|| lang.to_639_2T())
+        .or_else(|| lang.to_639_2B())
+        .filter(|code| !code.is_empty())
+}
+
+/// ISO 639-3代码到语言的反查表
+///
+/// 代码表中记录的代码优先于由NLLB代码推断的代码，同一代码对应多个语言时取第一个
+fn iso639_3_index() -> &'static HashMap<&'static str, Language> {
+    static INDEX: OnceLock<HashMap<&'static str, Language>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index = HashMap::new();
+        for lang in Language::all() {
+            if let Some(code) = listed_iso639_3(&lang) {
+                index.entry(code).or_insert(lang);
+            }
+        }
+        for lang in Language::all() {
+            if let Some(code) = lang.iso639_3() {
+                index.entry(code).or_insert(lang);
+            }
+        }
+        index
+    })
+}
+
+impl Language {
+    /// ISO 639-3代码，如`eng`、`zho`
+    ///
+    /// 代码表没有记录时按NLLB代码的语言部分推断（如`Achinese Latin`为`ace`），
+    /// 因此同一语言的不同书写系统变体共用一个代码，可配合`default_script`区分
+    ///
+    /// # 返回值
+    /// 三个字母的小写代码，无法确定时为None
+    pub fn iso639_3(&self) -> Option<&'static str> {
+        listed_iso639_3(self).or_else(|| self.to_nllb().map(|code| split_script(code).0))
+    }
+
+    /// 按ISO 639-3代码查找语言，不区分大小写
+    ///
+    /// 多个语言共用一个代码时返回代码表中直接记录该代码的语言（如`zho`为简体中文），
+    /// 同时接受`cmn`等常用别名，可直接转换`whatlang::Lang::code()`的返回值
+    ///
+    /// # 参数
+    /// - `code`: ISO 639-3代码
+    pub fn from_iso639_3(code: &str) -> Option<Language> {
+        let code = code.trim().to_ascii_lowercase();
+        ISO639_3_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == code)
+            .map(|(_, lang)| *lang)
+            .or_else(|| iso639_3_index().get(code.as_str()).copied())
+    }
+
+    /// 语言默认使用的书写系统
+    ///
+    /// 取代码表中639-3列或NLLB列的书写系统后缀
+    ///
+    /// # 返回值
+    /// 书写系统，代码表没有记录时为None
+    pub fn default_script(&self) -> Option<Script> {
+        if let Some((_, script)) = SCRIPT_OVERRIDES.iter().find(|(lang, _)| lang == self) {
+            return Some(*script);
+        }
+        [self.to_639_3(), self.to_nllb()]
+            .into_iter()
+            .flatten()
+            .find_map(|code| split_script(code).1)
+    }
+}
+
+/// 把whatlang检测出的语言转换为本crate的语言
+///
+/// 按`whatlang::Lang::code()`返回的ISO 639-3代码查找，见`Language::from_iso639_3`
+#[cfg(feature = "whatlang")]
+impl TryFrom<whatlang::Lang> for Language {
+    type Error = TranslatorError;
+
+    fn try_from(lang: whatlang::Lang) -> Result<Self, Self::Error> {
+        Language::from_iso639_3(lang.code())
+            .ok_or_else(|| TranslatorError::CouldNotMapLanguage(Some(lang.code().to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::language_codes::Script;
+
+    /// 测试所有语言的ISO 639-3代码都能转换回使用同一代码的语言
+    #[test]
+    fn test_iso639_3_roundtrip() {
+        let mut covered = 0;
+        for lang in Language::all() {
+            let Some(code) = lang.iso639_3() else {
+                continue;
+            };
+            covered += 1;
+            assert!(
+                code.len() == 3 && code.bytes().all(|b| b.is_ascii_lowercase()),
+                "{:?}: {}",
+                lang,
+                code
+            );
+            let found = Language::from_iso639_3(code).unwrap();
+            assert_eq!(found.iso639_3(), Some(code), "{:?}", lang);
+            // 反查得到的语言总是转换回自身
+            assert_eq!(Language::from_iso639_3(code), Some(found));
+        }
+        assert!(covered * 10 >= Language::all().len() * 9, "{}", covered);
+
+        assert_eq!(Language::English.iso639_3(), Some("eng"));
+        assert_eq!(Language::German.iso639_3(), Some("deu"));
+        assert_eq!(Language::Chinese.iso639_3(), Some("zho"));
+        assert_eq!(Language::ChineseTraditional.iso639_3(), Some("zho"));
+        assert_eq!(Language::from_iso639_3("ZHO"), Some(Language::Chinese));
+        assert_eq!(Language::from_iso639_3("cmn"), Some(Language::Chinese));
+        assert_eq!(Language::from_iso639_3("deu"), Some(Language::German));
+        assert_eq!(Language::from_iso639_3("qqq"), None);
+    }
+
+    /// 测试whatlang支持的所有语言都能转换，检测结果可直接用作翻译的源语言
+    #[cfg(feature = "whatlang")]
+    #[test]
+    fn test_try_from_whatlang() {
+        for lang in whatlang::Lang::all() {
+            let converted = Language::try_from(*lang).unwrap();
+            assert!(
+                converted.iso639_3() == Some(lang.code()) || lang.code() == "cmn",
+                "{:?} -> {:?}",
+                lang,
+                converted
+            );
+        }
+        assert_eq!(
+            Language::try_from(whatlang::Lang::Cmn).unwrap(),
+            Language::Chinese
+        );
+        let info = whatlang::detect("Das ist ein ganz normaler deutscher Satz.").unwrap();
+        assert_eq!(Language::try_from(info.lang()).unwrap(), Language::German);
+    }
+
+    /// 测试默认书写系统，包括代码表中标反的中文
+    #[test]
+    fn test_default_script() {
+        assert_eq!(Language::English.default_script(), Some(Script::Latn));
+        assert_eq!(Language::Russian.default_script(), Some(Script::Cyrl));
+        assert_eq!(Language::Chinese.default_script(), Some(Script::Hans));
+        assert_eq!(
+            Language::ChineseTraditional.default_script(),
+            Some(Script::Hant)
+        );
+        assert_eq!(Language::Japanese.default_script(), Some(Script::Jpan));
+        assert_eq!(Language::SerbianLatin.default_script(), Some(Script::Latn));
+        assert_eq!(Language::Serbian.default_script(), Some(Script::Cyrl));
+
+        // 代码表中出现的书写系统都在枚举中
+        for lang in Language::all() {
+            for code in [lang.to_639_3(), lang.to_nllb()].into_iter().flatten() {
+                if let Some((_, script)) = code.split_once('_') {
+                    assert!(Script::from_code(script).is_some(), "{}", code);
+                }
+            }
+        }
+    }
+
+    /// 测试书写系统代码双向转换
+    #[test]
+    fn test_script_roundtrip() {
+        for script in Script::all() {
+            assert_eq!(Script::from_code(script.code()), Some(*script));
+            assert_eq!(
+                Script::from_code(&script.code().to_uppercase()),
+                Some(*script)
+            );
+            assert_eq!(script.to_string(), script.code());
+        }
+        assert_eq!(Script::from_code("Zzzz"), None);
+    }
+}
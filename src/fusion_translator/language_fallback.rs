@@ -0,0 +1,284 @@
+use crate::fusion_translator::async_translator::{Language, LanguageSubstitution};
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+
+/// 语言回退表
+///
+/// 每一项为（语言，按优先级排列的候选语言），只收录可以互相替代的同一语言的不同变体，
+/// 如宏语言与其个体语言、同一语言的不同名称等
+const FALLBACKS: &[(Language, &[Language])] = &[
+    (Language::NorwegianBokmål, &[Language::Norwegian]),
+    (Language::NorwegianNynorsk, &[Language::Norwegian]),
+    (Language::Norwegian, &[Language::NorwegianBokmål]),
+    (Language::SerbianLatin, &[Language::Serbian]),
+    (Language::Serbian, &[Language::SerbianLatin]),
+    (Language::FilipinoPilipino, &[Language::Tagalog]),
+    (Language::Tagalog, &[Language::FilipinoPilipino]),
+    (Language::StandardMalay, &[Language::Malay]),
+    (Language::Malay, &[Language::StandardMalay]),
+    (Language::IranianPersian, &[Language::Persian]),
+    (Language::Persian, &[Language::IranianPersian]),
+    (Language::Dari, &[Language::Persian]),
+    (Language::NorthAzerbaijani, &[Language::Azerbaijani]),
+    (Language::SouthAzerbaijani, &[Language::Azerbaijani]),
+    (Language::NorthernKurdish, &[Language::Kurdish]),
+    (Language::CentralKurdish, &[Language::Kurdish]),
+    (Language::NorthernUzbek, &[Language::Uzbek]),
+    (Language::HalhMongolian, &[Language::Mongolian]),
+    (Language::StandardArabic, &[Language::Arabic]),
+    (Language::Arabic, &[Language::StandardArabic]),
+    (Language::StandardLatvian, &[Language::Latvian]),
+    (Language::Latvian, &[Language::StandardLatvian]),
+    (Language::SwahiliIndividualLanguage, &[Language::Swahili]),
+    (Language::Swahili, &[Language::SwahiliIndividualLanguage]),
+    (Language::NepaliIndividualLanguage, &[Language::Nepali]),
+    (Language::Nepali, &[Language::NepaliIndividualLanguage]),
+    (Language::ToskAlbanian, &[Language::Albanian]),
+    (Language::Odia, &[Language::Oriya]),
+    (Language::Oriya, &[Language::Odia]),
+    (Language::Panjabi, &[Language::Punjabi]),
+    (Language::Punjabi, &[Language::Panjabi]),
+    (Language::CentralAymara, &[Language::Aymara]),
+];
+
+/// 跨书写系统的回退表
+///
+/// 简体中文与繁体中文之间的替换会改变译文的书写系统，
+/// 只有在显式允许时才会使用
+const SCRIPT_FALLBACKS: &[(Language, &[Language])] = &[
+    (Language::Chinese, &[Language::ChineseTraditional]),
+    (Language::ChineseTraditional, &[Language::Chinese]),
+];
+
+impl Language {
+    /// 在支持的语言中查找最接近的语言
+    ///
+    /// 语言本身受支持时直接返回；否则按回退表查找第一个受支持的替代语言，
+    /// 不会在简体中文和繁体中文之间互相替换
+    ///
+    /// # 参数
+    /// - `supported`: 支持的语言列表
+    ///
+    /// # 返回值
+    /// 最接近的受支持语言，没有可用的替代语言时为None
+    pub fn closest(&self, supported: &[Language]) -> Option<Language> {
+        self.closest_with(supported, false)
+    }
+
+    /// 在支持的语言中查找最接近的语言，可选择是否允许更换书写系统
+    ///
+    /// # 参数
+    /// - `supported`: 支持的语言列表
+    /// - `allow_script_change`: 是否允许在简体中文和繁体中文之间互相替换
+    ///
+    /// # 返回值
+    /// 最接近的受支持语言，没有可用的替代语言时为None
+    pub fn closest_with(
+        &self,
+        supported: &[Language],
+        allow_script_change: bool,
+    ) -> Option<Language> {
+        self.closest_where(allow_script_change, |lang| supported.contains(lang))
+    }
+
+    /// 按判断函数查找最接近的受支持语言
+    ///
+    /// # 参数
+    /// - `allow_script_change`: 是否允许更换书写系统
+    /// - `is_supported`: 判断语言是否受支持
+    fn closest_where(
+        &self,
+        allow_script_change: bool,
+        is_supported: impl Fn(&Language) -> bool,
+    ) -> Option<Language> {
+        if is_supported(self) {
+            return Some(*self);
+        }
+        let script = if allow_script_change {
+            SCRIPT_FALLBACKS
+        } else {
+            &[]
+        };
+        FALLBACKS
+            .iter()
+            .chain(script)
+            .filter(|(lang, _)| lang == self)
+            .flat_map(|(_, candidates)| candidates.iter())
+            .find(|candidate| is_supported(candidate))
+            .copied()
+    }
+}
+
+/// 翻译器语言代码解析器
+///
+/// 把语言转换为翻译器的语言代码；开启语言回退时，
+/// 对不支持的语言使用回退表中最接近的语言，并记录替换情况
+pub(crate) struct LanguageResolver {
+    /// 是否开启语言回退
+    fallback: bool,
+    /// 是否允许更换书写系统
+    allow_script_change: bool,
+    /// 语言到翻译器语言代码的转换函数
+    to_code: fn(&Language) -> Option<&'static str>,
+    /// 已发生的语言替换
+    substitutions: Vec<LanguageSubstitution>,
+}
+
+impl LanguageResolver {
+    /// 创建新的解析器
+    ///
+    /// # 参数
+    /// - `options`: 本次调用的选项
+    /// - `to_code`: 语言到翻译器语言代码的转换函数
+    pub(crate) fn new(
+        options: &TranslateOptions,
+        to_code: fn(&Language) -> Option<&'static str>,
+    ) -> Self {
+        Self {
+            fallback: options.language_fallback,
+            allow_script_change: options.allow_script_fallback,
+            to_code,
+            substitutions: Vec::new(),
+        }
+    }
+
+    /// 解析语言代码
+    ///
+    /// # 参数
+    /// - `lang`: 要解析的语言
+    ///
+    /// # 返回值
+    /// 翻译器的语言代码，不支持且没有可用的替代语言时返回`TranslatorError::UnknownLanguage`
+    pub(crate) fn resolve(&mut self, lang: Language) -> Result<&'static str, TranslatorError> {
+        if let Some(code) = (self.to_code)(&lang) {
+            return Ok(code);
+        }
+        if self.fallback {
+            let to_code = self.to_code;
+            if let Some(used) =
+                lang.closest_where(self.allow_script_change, |l| to_code(l).is_some())
+            {
+                self.substitutions.push(LanguageSubstitution {
+                    requested: lang,
+                    used,
+                });
+                return Ok(to_code(&used).unwrap_or_default());
+            }
+        }
+        Err(TranslatorError::UnknownLanguage(lang))
+    }
+
+    /// 取出已发生的语言替换
+    pub(crate) fn into_substitutions(self) -> Vec<LanguageSubstitution> {
+        self.substitutions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{Language, LanguageSubstitution};
+    use crate::fusion_translator::language_fallback::{
+        LanguageResolver, FALLBACKS, SCRIPT_FALLBACKS,
+    };
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+
+    /// 测试回退表中的典型替换
+    #[test]
+    fn test_closest() {
+        assert_eq!(
+            Language::NorwegianBokmål.closest(&[Language::English, Language::Norwegian]),
+            Some(Language::Norwegian)
+        );
+        assert_eq!(
+            Language::SerbianLatin.closest(&[Language::Serbian]),
+            Some(Language::Serbian)
+        );
+        assert_eq!(
+            Language::FilipinoPilipino.closest(&[Language::Tagalog]),
+            Some(Language::Tagalog)
+        );
+        assert_eq!(
+            Language::English.closest(&[Language::English]),
+            Some(Language::English)
+        );
+        assert_eq!(
+            Language::NorwegianBokmål.closest(&[Language::English]),
+            None
+        );
+    }
+
+    /// 测试简体中文和繁体中文默认不会互相替换
+    #[test]
+    fn test_chinese_scripts_not_substituted() {
+        assert_eq!(
+            Language::Chinese.closest(&[Language::ChineseTraditional]),
+            None
+        );
+        assert_eq!(
+            Language::ChineseTraditional.closest(&[Language::Chinese]),
+            None
+        );
+        assert_eq!(
+            Language::Chinese.closest_with(&[Language::ChineseTraditional], true),
+            Some(Language::ChineseTraditional)
+        );
+        for (lang, candidates) in FALLBACKS {
+            let chinese = [Language::Chinese, Language::ChineseTraditional];
+            assert!(!(chinese.contains(lang) && candidates.iter().any(|c| chinese.contains(c))));
+        }
+    }
+
+    /// 测试回退表不包含自身替换和重复项
+    #[test]
+    fn test_table_consistency() {
+        for table in [FALLBACKS, SCRIPT_FALLBACKS] {
+            for (i, (lang, candidates)) in table.iter().enumerate() {
+                assert!(!candidates.is_empty());
+                assert!(!candidates.contains(lang), "{:?}", lang);
+                assert!(table[i + 1..].iter().all(|(other, _)| other != lang));
+            }
+        }
+    }
+
+    /// 测试解析器按选项记录替换
+    #[test]
+    fn test_resolver() {
+        /// 只支持挪威语的语言代码转换函数
+        fn norwegian_only(lang: &Language) -> Option<&'static str> {
+            match lang {
+                Language::Norwegian => Some("no"),
+                _ => None,
+            }
+        }
+
+        let mut strict = LanguageResolver::new(&TranslateOptions::default(), norwegian_only);
+        assert!(matches!(
+            strict.resolve(Language::NorwegianBokmål),
+            Err(TranslatorError::UnknownLanguage(Language::NorwegianBokmål))
+        ));
+
+        let options = TranslateOptions::new().with_language_fallback(true);
+        let mut resolver = LanguageResolver::new(&options, norwegian_only);
+        assert_eq!(resolver.resolve(Language::Norwegian).unwrap(), "no");
+        assert_eq!(resolver.resolve(Language::NorwegianBokmål).unwrap(), "no");
+        assert!(resolver.resolve(Language::English).is_err());
+        assert_eq!(
+            resolver.into_substitutions(),
+            vec![LanguageSubstitution {
+                requested: Language::NorwegianBokmål,
+                used: Language::Norwegian,
+            }]
+        );
+    }
+
+    /// 测试使用翻译器真实语言表的回退
+    #[test]
+    fn test_resolver_with_youdao_codes() {
+        let options = TranslateOptions::new().with_language_fallback(true);
+        let mut resolver = LanguageResolver::new(&options, Language::to_youdao);
+        assert_eq!(resolver.resolve(Language::NorwegianBokmål).unwrap(), "no");
+        assert_eq!(resolver.resolve(Language::Chinese).unwrap(), "zh-CHS");
+        assert_eq!(resolver.into_substitutions().len(), 1);
+    }
+}
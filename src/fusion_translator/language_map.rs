@@ -0,0 +1,670 @@
+use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+/// 把语言转换为翻译器语言代码的函数，如`Language::to_baidu`
+type ToCode = fn(&Language) -> Option<&'static str>;
+
+/// 把翻译器语言代码转换为语言的函数，如`Language::from_baidu`
+type FromCode = fn(&str) -> Option<Language>;
+
+/// 内置的翻译器语言代码表
+///
+/// 由`lang_generator`在编译时生成的`to_*`/`from_*`函数提供，第一次使用注册中心时自动注册
+const BUILTIN_VENDORS: &[(&str, ToCode, FromCode)] = &[
+    ("baidu", Language::to_baidu, Language::from_baidu),
+    ("caiyun", Language::to_caiyun, Language::from_caiyun),
+    ("deepl", Language::to_deepl, Language::from_deepl),
+    ("google", Language::to_google, Language::from_google),
+    ("m2m100", Language::to_m2m100, Language::from_m2m100),
+    ("mbart-50", Language::to_mbart_50, Language::from_mbart_50),
+    ("mymemory", Language::to_mymemory, Language::from_mymemory),
+    (
+        "mymemory-short",
+        Language::to_mymemory_short,
+        Language::from_mymemory_short,
+    ),
+    ("nllb", Language::to_nllb, Language::from_nllb),
+    ("papago", Language::to_papago, Language::from_papago),
+    ("youdao", Language::to_youdao, Language::from_youdao),
+];
+
+/// 生成的代码表中缺失、按翻译器文档补充的语言代码
+///
+/// 每一项都是有意的取舍，补充后必须仍能双向转换：
+/// - 百度用`slo`表示斯洛文尼亚语（斯洛伐克语为`sk`），生成的代码表没有收录，
+///   导致`from_baidu("slo")`失败
+const BUILTIN_SUPPLEMENTS: &[(&str, Language, &str)] = &[("baidu", Language::Slovenian, "slo")];
+
+/// 各翻译器的旧代码或另一种写法，只用于把响应中的代码反查为语言，发送请求时仍使用代码表中的代码
+///
+/// 以下语言在各翻译器之间的代码不一致，统一按如下方式规范化：
+/// - 希伯来语（`Language::Hebrew`）：发送`he`，同时接受Google沿用至今的旧代码`iw`
+/// - 爪哇语（`Language::Javanese`）：Google和阿里云发送`jv`，有道发送`jw`，三者都同时接受`jv`和`jw`
+/// - 菲律宾语：没有单独菲律宾语代码的翻译器发送`tl`，`fil`同样反查为`Language::Tagalog`；
+///   有`fil`代码的翻译器（百度、Bing）映射为`Language::FilipinoPilipino`
+/// - 挪威语：不区分书面挪威语的翻译器发送`no`，`nb`同样反查为`Language::Norwegian`；
+///   区分的翻译器（百度`nob`、DeepL`NB`）映射为`Language::NorwegianBokmål`
+///
+/// 旧代码不会覆盖代码表中已有的代码
+const BUILTIN_ALIASES: &[(&str, &str, Language)] = &[
+    ("google", "iw", Language::Hebrew),
+    ("google", "jw", Language::Javanese),
+    ("google", "fil", Language::Tagalog),
+    ("google", "nb", Language::Norwegian),
+    ("mymemory-short", "iw", Language::Hebrew),
+    ("mymemory-short", "jw", Language::Javanese),
+    ("mymemory-short", "fil", Language::Tagalog),
+    ("mymemory-short", "nb", Language::Norwegian),
+    ("youdao", "iw", Language::Hebrew),
+    ("youdao", "jv", Language::Javanese),
+    ("youdao", "fil", Language::Tagalog),
+    ("youdao", "nb", Language::Norwegian),
+];
+
+/// 翻译器文档中列出的语言代码
+///
+/// 每行一个代码，`#`开头的行为注释；`language_coverage`据此找出代码表没有收录的代码
+const OFFICIAL_CODES: &[(&str, &str)] = &[
+    (
+        "baidu",
+        include_str!("../../tests/fixtures/languages/baidu.txt"),
+    ),
+    (
+        "caiyun",
+        include_str!("../../tests/fixtures/languages/caiyun.txt"),
+    ),
+    (
+        "youdao",
+        include_str!("../../tests/fixtures/languages/youdao.txt"),
+    ),
+];
+
+/// 语言代码表注册中心
+///
+/// 保存每个翻译器的语言代码表，第三方翻译器可以在运行时注册自己的代码表，
+/// 无需修改生成`Language`枚举的过程宏
+#[derive(Debug, Default)]
+pub struct LanguageMap {
+    /// 翻译器名称（已规范化）到代码表的映射
+    vendors: HashMap<String, VendorTable>,
+}
+
+/// 单个翻译器的语言代码表
+#[derive(Debug, Default)]
+struct VendorTable {
+    /// 语言到代码
+    to: HashMap<Language, &'static str>,
+    /// 代码到语言，多个语言使用同一代码时取先注册的语言
+    from: HashMap<&'static str, Language>,
+}
+
+impl LanguageMap {
+    /// 创建空的注册中心
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建包含内置代码表的注册中心
+    pub fn with_builtin() -> Self {
+        let mut map = Self::new();
+        for (vendor, to_code, _) in BUILTIN_VENDORS {
+            let table = Language::all()
+                .into_iter()
+                .filter_map(|lang| to_code(&lang).map(|code| (lang, code)))
+                .chain(
+                    BUILTIN_SUPPLEMENTS
+                        .iter()
+                        .filter(|(name, _, _)| name == vendor)
+                        .map(|&(_, lang, code)| (lang, code)),
+                )
+                .collect::<Vec<_>>();
+            map.register(vendor, &table)
+                .expect("built-in language tables are consistent");
+            let entry = map.vendors.get_mut(*vendor).expect("registered above");
+            for &(_, code, lang) in BUILTIN_ALIASES.iter().filter(|(name, _, _)| name == vendor) {
+                entry.from.entry(code).or_insert(lang);
+            }
+        }
+        map
+    }
+
+    /// 注册翻译器的语言代码表
+    ///
+    /// 翻译器名称不区分大小写，`_`与`-`视为相同
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    /// - `table`: （语言，代码）列表，多个语言可以使用同一代码，反查时取先出现的语言
+    ///
+    /// # 返回值
+    /// 翻译器已注册，或同一语言对应了不同的代码时返回`TranslatorError::LanguageMapConflict`
+    pub fn register(
+        &mut self,
+        vendor: &str,
+        table: &[(Language, &'static str)],
+    ) -> Result<(), TranslatorError> {
+        let vendor = normalize_vendor(vendor);
+        if self.vendors.contains_key(&vendor) {
+            return Err(TranslatorError::LanguageMapConflict(format!(
+                "vendor {} is already registered",
+                vendor
+            )));
+        }
+        let mut entry = VendorTable::default();
+        for &(lang, code) in table {
+            match entry.to.insert(lang, code) {
+                Some(existing) if existing != code => {
+                    return Err(TranslatorError::LanguageMapConflict(format!(
+                        "{:?} is mapped to both {} and {} for vendor {}",
+                        lang, existing, code, vendor
+                    )));
+                }
+                _ => {}
+            }
+            entry.from.entry(code).or_insert(lang);
+        }
+        self.vendors.insert(vendor, entry);
+        Ok(())
+    }
+
+    /// 是否已注册指定翻译器
+    pub fn contains(&self, vendor: &str) -> bool {
+        self.vendors.contains_key(&normalize_vendor(vendor))
+    }
+
+    /// 已注册的翻译器名称，按字母顺序排列
+    pub fn vendors(&self) -> Vec<&str> {
+        let mut vendors = self.vendors.keys().map(String::as_str).collect::<Vec<_>>();
+        vendors.sort_unstable();
+        vendors
+    }
+
+    /// 把语言转换为翻译器的语言代码
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    /// - `lang`: 语言
+    pub fn to_code(&self, vendor: &str, lang: &Language) -> Option<&'static str> {
+        self.vendors
+            .get(&normalize_vendor(vendor))?
+            .to
+            .get(lang)
+            .copied()
+    }
+
+    /// 把翻译器的语言代码转换为语言
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    /// - `code`: 语言代码
+    pub fn from_code(&self, vendor: &str, code: &str) -> Option<Language> {
+        self.vendors
+            .get(&normalize_vendor(vendor))?
+            .from
+            .get(code)
+            .copied()
+    }
+
+    /// 语言能否在翻译器的代码表中双向转换
+    ///
+    /// 多个语言共用同一代码时，只有反查结果对应的语言能双向转换
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    /// - `lang`: 语言
+    ///
+    /// # 返回值
+    /// 语言有代码且代码能转换回该语言时为true
+    pub fn roundtrips(&self, vendor: &str, lang: &Language) -> bool {
+        self.to_code(vendor, lang)
+            .and_then(|code| self.from_code(vendor, code))
+            .is_some_and(|back| back == *lang)
+    }
+
+    /// 统计翻译器代码表的覆盖情况
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    ///
+    /// # 返回值
+    /// 每个语言对应的代码，以及翻译器文档列出但无法转换为语言的代码
+    pub fn coverage(&self, vendor: &str) -> CoverageReport {
+        let languages = Language::all()
+            .into_iter()
+            .map(|language| LanguageCoverage {
+                language,
+                code: self.to_code(vendor, &language),
+            })
+            .collect();
+        let official = official_codes(vendor);
+        let unmapped_codes = official
+            .iter()
+            .flatten()
+            .filter(|code| self.from_code(vendor, code).is_none())
+            .map(|code| code.to_string())
+            .collect();
+        CoverageReport {
+            vendor: normalize_vendor(vendor),
+            registered: self.contains(vendor),
+            languages,
+            has_official_list: official.is_some(),
+            unmapped_codes,
+        }
+    }
+}
+
+/// 翻译器代码表的覆盖情况
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// 翻译器名称（已规范化）
+    pub vendor: String,
+    /// 翻译器是否已注册代码表，未注册时所有语言都没有代码
+    pub registered: bool,
+    /// 每个语言对应的代码，顺序与`Language::all`一致
+    pub languages: Vec<LanguageCoverage>,
+    /// 是否内置了该翻译器文档列出的语言代码
+    pub has_official_list: bool,
+    /// 翻译器文档列出、但代码表无法转换为语言的代码
+    ///
+    /// 翻译服务返回这些代码时会出现`TranslatorError::CouldNotMapLanguage`；
+    /// 没有内置文档代码时为空
+    pub unmapped_codes: Vec<String>,
+}
+
+impl CoverageReport {
+    /// 没有代码的语言
+    pub fn missing(&self) -> impl Iterator<Item = Language> + '_ {
+        self.languages
+            .iter()
+            .filter(|entry| entry.code.is_none())
+            .map(|entry| entry.language)
+    }
+
+    /// 有代码的语言数量
+    pub fn mapped_count(&self) -> usize {
+        self.languages
+            .iter()
+            .filter(|entry| entry.code.is_some())
+            .count()
+    }
+}
+
+/// 单个语言的覆盖情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageCoverage {
+    /// 语言
+    pub language: Language,
+    /// 翻译器的语言代码，None表示缺失
+    pub code: Option<&'static str>,
+}
+
+/// 获取内置的翻译器文档语言代码
+///
+/// # 参数
+/// - `vendor`: 翻译器名称
+///
+/// # 返回值
+/// 文档列出的代码，没有内置列表时为None
+pub fn official_codes(vendor: &str) -> Option<Vec<&'static str>> {
+    let vendor = normalize_vendor(vendor);
+    let (_, list) = OFFICIAL_CODES.iter().find(|(name, _)| *name == vendor)?;
+    Some(
+        list.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect(),
+    )
+}
+
+/// 规范化翻译器名称
+fn normalize_vendor(vendor: &str) -> String {
+    vendor.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+/// 全局注册中心
+///
+/// 第一次使用时创建，包含所有内置代码表
+fn global() -> &'static RwLock<LanguageMap> {
+    static MAP: OnceLock<RwLock<LanguageMap>> = OnceLock::new();
+    MAP.get_or_init(|| RwLock::new(LanguageMap::with_builtin()))
+}
+
+/// 全局注册中心中已注册的翻译器名称，按字母顺序排列
+pub(crate) fn registered_vendors() -> Vec<String> {
+    global()
+        .read()
+        .unwrap()
+        .vendors()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// 在全局注册中心注册翻译器的语言代码表
+///
+/// 通常在程序启动时、创建对应翻译器之前调用
+///
+/// # 参数
+/// - `vendor`: 翻译器名称
+/// - `table`: （语言，代码）列表
+///
+/// # 返回值
+/// 冲突时返回`TranslatorError::LanguageMapConflict`
+pub fn register_vendor(
+    vendor: &str,
+    table: &[(Language, &'static str)],
+) -> Result<(), TranslatorError> {
+    global().write().unwrap().register(vendor, table)
+}
+
+/// 按全局注册中心统计翻译器代码表的覆盖情况
+///
+/// 用于排查`TranslatorError::CouldNotMapLanguage`：列出每个语言的代码或缺失，
+/// 以及翻译器文档列出但无法转换为语言的代码
+///
+/// # 参数
+/// - `vendor`: 翻译器名称，如`baidu`或通过`register_vendor`注册的名称
+///
+/// # 返回值
+/// 覆盖情况报告
+pub fn language_coverage(vendor: &str) -> CoverageReport {
+    global().read().unwrap().coverage(vendor)
+}
+
+/// 列出代码表中有代码的语言
+///
+/// 供翻译器实现`AsyncTranslator::supported_languages`
+///
+/// # 参数
+/// - `to_code`: 语言到翻译器语言代码的转换函数
+///
+/// # 返回值
+/// 按`Language::all()`顺序排列的语言
+pub(crate) fn languages_with(to_code: impl Fn(&Language) -> Option<&'static str>) -> Vec<Language> {
+    Language::all()
+        .into_iter()
+        .filter(|lang| to_code(lang).is_some())
+        .collect()
+}
+
+impl Language {
+    /// 按全局注册中心把语言转换为翻译器的语言代码
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称，如`baidu`或通过`register_vendor`注册的名称
+    ///
+    /// # 返回值
+    /// 语言代码，翻译器未注册或不支持该语言时为None
+    pub fn to_vendor(&self, vendor: &str) -> Option<&'static str> {
+        global().read().unwrap().to_code(vendor, self)
+    }
+
+    /// 按全局注册中心把翻译器的语言代码转换为语言
+    ///
+    /// 除代码表中的代码外，还接受翻译器的旧代码（如Google的希伯来语`iw`），
+    /// 希伯来语、爪哇语、菲律宾语和挪威语的规范化方式见`BUILTIN_ALIASES`
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    /// - `code`: 语言代码
+    ///
+    /// # 返回值
+    /// 语言，翻译器未注册或代码无法识别时为None
+    pub fn from_vendor(vendor: &str, code: &str) -> Option<Language> {
+        global().read().unwrap().from_code(vendor, code)
+    }
+
+    /// 按全局注册中心判断语言能否在翻译器的代码表中双向转换
+    ///
+    /// 翻译器据此决定是否把返回的语言代码转换回语言：
+    /// 不能双向转换时只保留原始代码，避免反查出另一个共用代码的语言
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译器名称
+    pub fn roundtrips(&self, vendor: &str) -> bool {
+        global().read().unwrap().roundtrips(vendor, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::language_map::{
+        language_coverage, official_codes, register_vendor, LanguageMap, BUILTIN_ALIASES,
+        BUILTIN_SUPPLEMENTS, BUILTIN_VENDORS, OFFICIAL_CODES,
+    };
+    use crate::fusion_translator::translator_error::TranslatorError;
+
+    /// 测试内置代码表与生成的转换函数一致
+    #[test]
+    fn test_builtin_tables() {
+        let map = LanguageMap::with_builtin();
+        for lang in Language::all() {
+            if let Some(code) = lang.to_baidu() {
+                assert_eq!(map.to_code("baidu", &lang), Some(code));
+            }
+            assert_eq!(map.to_code("youdao", &lang), lang.to_youdao());
+            assert_eq!(
+                map.to_code("mymemory_short", &lang),
+                lang.to_mymemory_short()
+            );
+        }
+        assert_eq!(map.from_code("youdao", "zh-CHS"), Some(Language::Chinese));
+        assert_eq!(map.from_code("baidu", "jp"), Language::from_baidu("jp"));
+        assert!(map.vendors().contains(&"mbart-50"));
+    }
+
+    /// 测试所有内置代码表都能双向转换
+    ///
+    /// 生成的`to_*`与`from_*`必须互逆；补充的代码在注册中心中也必须互逆。
+    /// 出现新的单向映射时，应在`BUILTIN_SUPPLEMENTS`中明确取舍，而不是在测试中跳过
+    #[test]
+    fn test_builtin_tables_roundtrip() {
+        let map = LanguageMap::with_builtin();
+        for (vendor, to_code, from_code) in BUILTIN_VENDORS {
+            for lang in Language::all() {
+                let Some(code) = to_code(&lang) else { continue };
+                assert_eq!(from_code(code), Some(lang), "{} {}", vendor, code);
+                assert!(map.roundtrips(vendor, &lang), "{} {:?}", vendor, lang);
+            }
+        }
+        for (vendor, lang, code) in BUILTIN_SUPPLEMENTS {
+            assert_eq!(map.to_code(vendor, lang), Some(*code));
+            assert!(map.roundtrips(vendor, lang), "{} {:?}", vendor, lang);
+        }
+        assert_eq!(map.from_code("baidu", "slo"), Some(Language::Slovenian));
+        assert_eq!(map.from_code("baidu", "sk"), Some(Language::Slovak));
+    }
+
+    /// 测试希伯来语、爪哇语、菲律宾语和挪威语在各翻译器发送的代码，以及新旧代码都能反查
+    #[test]
+    fn test_inconsistent_vendor_codes() {
+        let map = LanguageMap::with_builtin();
+        // （翻译器，语言，发送的代码，同样反查为该语言的其他代码）
+        let cases: &[(&str, Language, &str, &[&str])] = &[
+            ("google", Language::Hebrew, "he", &["iw"]),
+            ("google", Language::Javanese, "jv", &["jw"]),
+            ("google", Language::Tagalog, "tl", &["fil"]),
+            ("google", Language::Norwegian, "no", &["nb"]),
+            ("youdao", Language::Hebrew, "he", &["iw"]),
+            ("youdao", Language::Javanese, "jw", &["jv"]),
+            ("youdao", Language::Tagalog, "tl", &["fil"]),
+            ("youdao", Language::Norwegian, "no", &["nb"]),
+            ("mymemory-short", Language::Hebrew, "he", &["iw"]),
+            ("mymemory-short", Language::Javanese, "jv", &["jw"]),
+            ("mymemory-short", Language::Tagalog, "tl", &["fil"]),
+            ("mymemory-short", Language::Norwegian, "no", &["nb"]),
+            ("baidu", Language::Hebrew, "heb", &[]),
+            ("baidu", Language::Javanese, "jav", &[]),
+            ("baidu", Language::FilipinoPilipino, "fil", &[]),
+            ("baidu", Language::Tagalog, "tgl", &[]),
+            ("baidu", Language::Norwegian, "nor", &[]),
+            ("baidu", Language::NorwegianBokmål, "nob", &[]),
+            ("deepl", Language::NorwegianBokmål, "NB", &[]),
+        ];
+        for (vendor, lang, code, legacy) in cases {
+            assert_eq!(
+                map.to_code(vendor, lang),
+                Some(*code),
+                "{} {:?}",
+                vendor,
+                lang
+            );
+            assert!(map.roundtrips(vendor, lang), "{} {:?}", vendor, lang);
+            for code in legacy.iter() {
+                assert_eq!(
+                    map.from_code(vendor, code),
+                    Some(*lang),
+                    "{} {}",
+                    vendor,
+                    code
+                );
+            }
+        }
+        // 旧代码不会覆盖代码表中已有的代码，也不会被用于发送
+        for (vendor, code, lang) in BUILTIN_ALIASES {
+            assert_ne!(
+                map.to_code(vendor, lang),
+                Some(*code),
+                "{} {}",
+                vendor,
+                code
+            );
+            assert!(map.to_code(vendor, lang).is_some(), "{} {:?}", vendor, lang);
+        }
+        assert_eq!(
+            Language::from_vendor("google", "iw"),
+            Some(Language::Hebrew)
+        );
+    }
+
+    /// 测试共用代码的语言不能双向转换
+    #[test]
+    fn test_roundtrips_shared_code() {
+        let mut map = LanguageMap::new();
+        map.register(
+            "acme",
+            &[
+                (Language::Chinese, "ZH"),
+                (Language::ChineseTraditional, "ZH"),
+            ],
+        )
+        .unwrap();
+        assert!(map.roundtrips("acme", &Language::Chinese));
+        assert!(!map.roundtrips("acme", &Language::ChineseTraditional));
+        assert!(!map.roundtrips("acme", &Language::English));
+        assert!(!map.roundtrips("unknown", &Language::Chinese));
+        assert!(Language::Slovenian.roundtrips("baidu"));
+    }
+
+    /// 测试注册自定义代码表
+    #[test]
+    fn test_register_custom_vendor() {
+        let mut map = LanguageMap::new();
+        map.register(
+            "Acme",
+            &[
+                (Language::English, "EN"),
+                (Language::Chinese, "ZH"),
+                (Language::ChineseTraditional, "ZH"),
+            ],
+        )
+        .unwrap();
+        assert!(map.contains("acme"));
+        assert_eq!(map.to_code("ACME", &Language::English), Some("EN"));
+        assert_eq!(map.to_code("acme", &Language::German), None);
+        assert_eq!(map.from_code("acme", "ZH"), Some(Language::Chinese));
+        assert_eq!(map.from_code("acme", "zh"), None);
+        assert_eq!(map.to_code("unknown", &Language::English), None);
+    }
+
+    /// 测试重复注册和同一语言对应不同代码时报告冲突
+    #[test]
+    fn test_conflicts() {
+        let mut map = LanguageMap::with_builtin();
+        assert!(matches!(
+            map.register("baidu", &[(Language::English, "en")]),
+            Err(TranslatorError::LanguageMapConflict(_))
+        ));
+        map.register("acme_v2", &[(Language::English, "EN")])
+            .unwrap();
+        assert!(matches!(
+            map.register("ACME-v2", &[(Language::English, "EN")]),
+            Err(TranslatorError::LanguageMapConflict(_))
+        ));
+        assert!(matches!(
+            map.register(
+                "other",
+                &[(Language::English, "EN"), (Language::English, "EN-US")]
+            ),
+            Err(TranslatorError::LanguageMapConflict(_))
+        ));
+        assert!(!map.contains("other"));
+        map.register(
+            "repeated",
+            &[(Language::English, "EN"), (Language::English, "EN")],
+        )
+        .unwrap();
+    }
+
+    /// 测试全局注册中心
+    #[test]
+    fn test_global_registry() {
+        assert_eq!(Language::English.to_vendor("baidu"), Some("en"));
+        assert_eq!(
+            Language::from_vendor("youdao", "ja"),
+            Some(Language::Japanese)
+        );
+        register_vendor(
+            "test-global-vendor",
+            &[(Language::French, "FR"), (Language::German, "DE")],
+        )
+        .unwrap();
+        assert_eq!(Language::French.to_vendor("test_global_vendor"), Some("FR"));
+        assert_eq!(
+            Language::from_vendor("test-global-vendor", "DE"),
+            Some(Language::German)
+        );
+        assert!(register_vendor("test-global-vendor", &[]).is_err());
+    }
+
+    /// 内置代码表覆盖翻译器文档列出的全部语言代码
+    #[test]
+    fn test_official_codes_covered() {
+        for (vendor, _) in OFFICIAL_CODES {
+            let codes = official_codes(vendor).unwrap();
+            assert!(!codes.is_empty(), "{}", vendor);
+            let report = language_coverage(vendor);
+            assert!(report.registered && report.has_official_list);
+            assert!(
+                report.unmapped_codes.is_empty(),
+                "{} codes without a language: {:?}",
+                vendor,
+                report.unmapped_codes
+            );
+        }
+    }
+
+    /// 测试覆盖报告列出缺失的语言和无法转换的代码
+    #[test]
+    fn test_coverage_report() {
+        let mut map = LanguageMap::new();
+        map.register("caiyun", &[(Language::English, "en")])
+            .unwrap();
+        let report = map.coverage("Caiyun");
+        assert_eq!(report.vendor, "caiyun");
+        assert!(report.registered);
+        assert_eq!(report.mapped_count(), 1);
+        assert_eq!(report.missing().count(), Language::all().len() - 1);
+        assert!(report.missing().all(|lang| lang != Language::English));
+        assert!(report.unmapped_codes.contains(&"zh-Hant".to_string()));
+        assert!(!report.unmapped_codes.contains(&"en".to_string()));
+
+        let report = map.coverage("unknown");
+        assert!(!report.registered && !report.has_official_list);
+        assert_eq!(report.mapped_count(), 0);
+        assert!(report.unmapped_codes.is_empty());
+    }
+}
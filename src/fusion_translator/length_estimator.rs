@@ -0,0 +1,349 @@
+use crate::fusion_translator::input_check::{measure, InputUnit};
+use crate::fusion_translator::segment::split_sentence_ranges;
+use crate::fusion_translator::similarity::graphemes;
+use std::ops::Range;
+
+/// 文本长度估算器
+///
+/// 大模型的上下文窗口按词元计算，字符数或字节数只能近似；实现此特性即可按实际使用的分词方式
+/// 估算提示词长度，配合`plan_budget_chunks`把长文本拆分到预算之内
+pub trait LengthEstimator: Send + Sync {
+    /// 估算文本的长度
+    ///
+    /// # 参数
+    /// - `text`: 文本
+    fn estimate(&self, text: &str) -> usize;
+}
+
+/// 按`input_check::measure`计算长度：`InputUnit::Chars`按字符数，`InputUnit::Bytes`按UTF-8字节数，
+/// `InputUnit::Tokens`按不依赖词表的启发式词元数
+impl LengthEstimator for InputUnit {
+    fn estimate(&self, text: &str) -> usize {
+        measure(text, *self)
+    }
+}
+
+/// 基于tiktoken词表的BPE估算器，按OpenAI系列模型的分词结果计算词元数
+#[cfg(feature = "tiktoken")]
+pub struct BpeEstimator {
+    /// 分词器
+    bpe: tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl BpeEstimator {
+    /// 使用`cl100k_base`词表（GPT-3.5、GPT-4）
+    pub fn cl100k() -> anyhow::Result<Self> {
+        Ok(Self::new(tiktoken_rs::cl100k_base()?))
+    }
+
+    /// 使用`o200k_base`词表（GPT-4o）
+    pub fn o200k() -> anyhow::Result<Self> {
+        Ok(Self::new(tiktoken_rs::o200k_base()?))
+    }
+
+    /// 使用模型对应的词表
+    ///
+    /// # 参数
+    /// - `model`: 模型名称，如`gpt-4o-mini`
+    ///
+    /// # 返回值
+    /// 估算器，未知模型时返回错误
+    pub fn for_model(model: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(tiktoken_rs::get_bpe_from_model(model)?))
+    }
+
+    /// 使用已加载的分词器
+    ///
+    /// # 参数
+    /// - `bpe`: 分词器
+    pub fn new(bpe: tiktoken_rs::CoreBPE) -> Self {
+        Self { bpe }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl LengthEstimator for BpeEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// 提示词长度预算
+///
+/// 模型的上下文窗口同时容纳输入和输出，每段输入最多使用扣除响应预留后的部分
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenBudget {
+    /// 上下文窗口大小
+    pub context_window: usize,
+    /// 为模型响应预留的长度
+    pub response_reserve: usize,
+}
+
+impl TokenBudget {
+    /// 创建不预留响应长度的预算
+    ///
+    /// # 参数
+    /// - `context_window`: 上下文窗口大小
+    pub fn new(context_window: usize) -> Self {
+        Self {
+            context_window,
+            response_reserve: 0,
+        }
+    }
+
+    /// 设置为模型响应预留的长度
+    ///
+    /// # 参数
+    /// - `response_reserve`: 预留长度，超过上下文窗口时可用长度为0
+    pub fn with_response_reserve(mut self, response_reserve: usize) -> Self {
+        self.response_reserve = response_reserve;
+        self
+    }
+
+    /// 每段输入可用的长度
+    pub fn available(&self) -> usize {
+        self.context_window.saturating_sub(self.response_reserve)
+    }
+}
+
+/// 按长度预算拆分长文本
+///
+/// 优先在句子边界处拆分，并把尽量多的句子合并到同一段；单个句子超出预算时在空白处拆分，
+/// 没有空白的文本（如很长的URL或不含标点的中文）在字素簇边界处强制拆分。
+/// 每段长度都由`estimator`对该段实际估算，因此分词结果不随前缀单调变化的估算器（如BPE）也不会超出预算；
+/// 只有单个字素簇本身超出预算时，该字素簇单独成为一段
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+/// - `estimator`: 长度估算器
+/// - `budget`: 长度预算
+///
+/// # 返回值
+/// 按顺序排列、首尾相接并覆盖整个文本的字节范围，空文本返回空列表
+pub fn plan_budget_chunks(
+    text: &str,
+    estimator: &dyn LengthEstimator,
+    budget: &TokenBudget,
+) -> Vec<Range<usize>> {
+    let limit = budget.available();
+    let mut boundaries = Vec::new();
+    let mut after_space = Vec::new();
+    let mut offset = 0;
+    for cluster in graphemes(text) {
+        offset += cluster.len();
+        boundaries.push(offset);
+        if cluster.starts_with(char::is_whitespace) {
+            after_space.push(offset);
+        }
+    }
+    let fits = |range: Range<usize>| estimator.estimate(&text[range]) <= limit;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    // 句子边界落在字素簇内部时（如换行后紧跟组合字符）与下一句合并
+    let ends = split_sentence_ranges(text, None)
+        .into_iter()
+        .map(|range| range.end)
+        .filter(|end| boundaries.binary_search(end).is_ok());
+    for sentence_end in ends {
+        if fits(start..sentence_end) {
+            end = sentence_end;
+            continue;
+        }
+        if end > start {
+            chunks.push(start..end);
+            start = end;
+        }
+        while !fits(start..sentence_end) {
+            let cut = hard_cut(&boundaries, &after_space, start, sentence_end, &fits);
+            chunks.push(start..cut);
+            start = cut;
+        }
+        end = sentence_end;
+    }
+    if start < text.len() {
+        chunks.push(start..text.len());
+    }
+    chunks
+}
+
+/// 在不超出预算的位置截断超长的句子
+///
+/// # 参数
+/// - `boundaries`: 文本中所有字素簇的结束位置
+/// - `after_space`: 空白字素簇的结束位置
+/// - `start`: 句子剩余部分的起始位置
+/// - `end`: 句子的结束位置
+/// - `fits`: 范围内的文本是否不超出预算
+///
+/// # 返回值
+/// 截断位置，优先选择最后一个不超出预算的空白之后，其次是最后一个不超出预算的字素簇边界；
+/// 第一个字素簇就超出预算时在它之后截断
+fn hard_cut(
+    boundaries: &[usize],
+    after_space: &[usize],
+    start: usize,
+    end: usize,
+    fits: &dyn Fn(Range<usize>) -> bool,
+) -> usize {
+    let first = boundaries.partition_point(|&b| b <= start);
+    let last = boundaries.partition_point(|&b| b < end);
+    let candidates = boundaries.get(first..last).unwrap_or_default();
+    // 二分查找最后一个不超出预算的边界，lo始终指向已确认不超出预算的边界之后
+    let (mut lo, mut hi) = (0, candidates.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if candidates.get(mid).is_some_and(|&b| fits(start..b)) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let Some(&cut) = lo.checked_sub(1).and_then(|i| candidates.get(i)) else {
+        return boundaries.get(first).copied().unwrap_or(end);
+    };
+    let space = after_space.partition_point(|&b| b <= cut);
+    match space.checked_sub(1).and_then(|i| after_space.get(i)) {
+        Some(&b) if b > start && fits(start..b) => b,
+        _ => cut,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::input_check::InputUnit;
+    use crate::fusion_translator::length_estimator::{
+        plan_budget_chunks, LengthEstimator, TokenBudget,
+    };
+
+    /// 检查拆分结果首尾相接并覆盖整个文本，且每段都不超出预算
+    fn assert_within_budget(text: &str, estimator: &dyn LengthEstimator, budget: &TokenBudget) {
+        let chunks = plan_budget_chunks(text, estimator, budget);
+        let mut offset = 0;
+        for chunk in &chunks {
+            assert_eq!(chunk.start, offset);
+            assert!(chunk.end > chunk.start);
+            let estimate = estimator.estimate(&text[chunk.clone()]);
+            assert!(
+                estimate <= budget.available(),
+                "chunk {:?} of {:?} is {} over a budget of {}",
+                &text[chunk.clone()],
+                text,
+                estimate,
+                budget.available()
+            );
+            offset = chunk.end;
+        }
+        assert_eq!(offset, text.len());
+    }
+
+    /// 用于预算测试的文本：中文、中英混排、无空白的长串和表情符号
+    fn samples() -> Vec<String> {
+        vec![
+            "今天天气很好。我们去公园散步吧！你觉得怎么样？".repeat(20),
+            "The quick brown fox jumps over the lazy dog. 敏捷的棕色狐狸跳过了懒狗。".repeat(15),
+            "a".repeat(3000),
+            "无标点的中文长句".repeat(200),
+            format!(
+                "See https://example.com/{} for details.",
+                "path/".repeat(200)
+            ),
+            "👨‍👩‍👧‍👦 Family time! 🎉🎉🎉 ".repeat(40),
+            String::new(),
+        ]
+    }
+
+    /// 测试预算扣除响应预留，预留超过窗口时可用长度为0
+    #[test]
+    fn test_budget_available() {
+        assert_eq!(TokenBudget::new(4096).available(), 4096);
+        assert_eq!(
+            TokenBudget::new(4096)
+                .with_response_reserve(1024)
+                .available(),
+            3072
+        );
+        assert_eq!(
+            TokenBudget::new(10).with_response_reserve(20).available(),
+            0
+        );
+    }
+
+    /// 测试字符和字节估算器对中日韩文本的差别
+    #[test]
+    fn test_char_and_byte_estimators() {
+        assert_eq!(InputUnit::Chars.estimate("你好，world"), 8);
+        assert_eq!(InputUnit::Bytes.estimate("你好，world"), 14);
+    }
+
+    /// 测试优先在句子边界处拆分，并把尽量多的句子合并到同一段
+    #[test]
+    fn test_prefers_sentence_boundaries() {
+        let text = "第一句话。第二句话。第三句话。";
+        let budget = TokenBudget::new(12).with_response_reserve(2);
+        let chunks = plan_budget_chunks(text, &InputUnit::Chars, &budget);
+        let parts = chunks
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>();
+        assert_eq!(parts, ["第一句话。第二句话。", "第三句话。"]);
+    }
+
+    /// 测试超长的句子在空白处拆分，没有空白的文本强制拆分
+    #[test]
+    fn test_hard_split() {
+        let text = "alpha beta gamma delta";
+        let chunks = plan_budget_chunks(text, &InputUnit::Bytes, &TokenBudget::new(12));
+        let parts = chunks
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>();
+        assert_eq!(parts, ["alpha beta ", "gamma delta"]);
+
+        let text = "x".repeat(25);
+        let chunks = plan_budget_chunks(&text, &InputUnit::Bytes, &TokenBudget::new(10));
+        assert_eq!(chunks, [0..10, 10..20, 20..25]);
+    }
+
+    /// 测试不超出预算的文本不拆分，空文本返回空列表
+    #[test]
+    fn test_no_split() {
+        let text = "Short. Text.";
+        let chunks = plan_budget_chunks(text, &InputUnit::Chars, &TokenBudget::new(100));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0], 0..text.len());
+        assert!(plan_budget_chunks("", &InputUnit::Chars, &TokenBudget::new(100)).is_empty());
+    }
+
+    /// 以估算器本身为准，测试各种文本和预算下每段都不超出预算
+    #[test]
+    fn test_chunks_within_budget() {
+        let estimators: [&dyn LengthEstimator; 3] =
+            [&InputUnit::Chars, &InputUnit::Bytes, &InputUnit::Tokens];
+        for text in samples() {
+            for estimator in estimators {
+                for (window, reserve) in [(64, 16), (200, 50), (1000, 0)] {
+                    let budget = TokenBudget::new(window).with_response_reserve(reserve);
+                    assert_within_budget(&text, estimator, &budget);
+                }
+            }
+        }
+    }
+
+    /// 以BPE估算器本身为准，测试每段词元数都不超出预算
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_bpe_chunks_within_budget() {
+        use crate::fusion_translator::length_estimator::BpeEstimator;
+
+        let bpe = BpeEstimator::cl100k().unwrap();
+        assert_eq!(bpe.estimate("hello world"), 2);
+        for text in samples() {
+            for (window, reserve) in [(64, 16), (256, 64)] {
+                let budget = TokenBudget::new(window).with_response_reserve(reserve);
+                assert_within_budget(&text, &bpe, &budget);
+            }
+        }
+    }
+}
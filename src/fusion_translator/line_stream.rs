@@ -0,0 +1,507 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::shutdown::Shutdown;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// 某一行翻译失败的错误
+///
+/// 作为`io::Error`的内部错误返回，可通过`io::Error::get_ref`取出
+#[derive(Debug)]
+pub struct LineError {
+    /// 行号，从1开始
+    pub line: usize,
+    /// 翻译器返回的错误，同一批次的所有行共享
+    pub error: Arc<anyhow::Error>,
+}
+
+impl std::fmt::Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// 读取和翻译所需的状态，在每个批次的异步任务中转移所有权
+struct Inner<R> {
+    /// 输入
+    reader: R,
+    /// 翻译器
+    translator: Arc<dyn AsyncTranslator>,
+    /// 源语言，None表示自动检测
+    from: Option<Language>,
+    /// 目标语言
+    to: Language,
+    /// 每批最多的行数
+    batch_size: usize,
+    /// 批次中第一行到达后最多等待的时长
+    max_latency: Duration,
+    /// 翻译失败时是否结束流
+    strict: bool,
+    /// 已读取的行数
+    line_no: usize,
+    /// 尚未读完的一行，读取超时时保留已读到的部分
+    partial: Vec<u8>,
+    /// 触发后不再读取输入
+    stop: CancellationToken,
+}
+
+/// 一个批次的结果
+struct Batch<R> {
+    /// 处理后的状态，流结束时为None
+    inner: Option<Inner<R>>,
+    /// 按输入顺序排列的译文或错误
+    items: Vec<io::Result<String>>,
+}
+
+/// 正在处理的批次
+#[cfg(not(target_arch = "wasm32"))]
+type Pending<R> = Pin<Box<dyn Future<Output = Batch<R>> + Send>>;
+/// 正在处理的批次
+#[cfg(target_arch = "wasm32")]
+type Pending<R> = Pin<Box<dyn Future<Output = Batch<R>>>>;
+
+/// 逐行翻译的流
+///
+/// 从`AsyncBufRead`按行读取输入，攒够`batch_size`行或第一行到达后等待`max_latency`
+/// （以先到者为准）时调用一次`translate_vec`，按输入顺序产出译文（不含换行符），
+/// 读到末尾时翻译剩余的行。空白行不发送给翻译器，原样产出
+///
+/// 翻译失败时，默认为该批次的每一行产出一个包含`LineError`的错误，之后继续翻译；
+/// 开启`strict`后只产出该批次第一行的错误并结束流。读取输入失败时产出错误并结束流
+///
+/// 通过`shutdown_handle`可以在其他任务中关闭流：不再读取输入，已读入的行翻译后产出，随后流结束
+pub struct TranslatingLines<R> {
+    /// 空闲时的状态
+    inner: Option<Inner<R>>,
+    /// 正在处理的批次
+    pending: Option<Pending<R>>,
+    /// 已完成、尚未产出的结果
+    ready: VecDeque<io::Result<String>>,
+    /// 关闭信号
+    stop: CancellationToken,
+}
+
+/// 关闭`TranslatingLines`的句柄
+///
+/// `shutdown`只发出信号、不等待流结束：调用方继续轮询流即可取得已读入行的译文，之后流结束。
+/// 关闭时尚未读到换行符的最后一行不会产出，也不会从输入中消耗更多数据
+#[derive(Debug, Clone)]
+pub struct LinesShutdown {
+    /// 关闭信号
+    stop: CancellationToken,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Shutdown for LinesShutdown {
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.stop.cancel();
+        Ok(())
+    }
+}
+
+impl<R> TranslatingLines<R>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    /// 创建逐行翻译的流
+    ///
+    /// # 参数
+    /// - `reader`: 输入
+    /// - `translator`: 翻译器
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `batch_size`: 每次调用`translate_vec`最多发送的行数，0视为1
+    /// - `max_latency`: 批次中第一行到达后最多等待的时长
+    pub fn new(
+        reader: R,
+        translator: Arc<dyn AsyncTranslator>,
+        from: Option<Language>,
+        to: Language,
+        batch_size: usize,
+        max_latency: Duration,
+    ) -> Self {
+        let stop = CancellationToken::new();
+        Self {
+            inner: Some(Inner {
+                reader,
+                translator,
+                from,
+                to,
+                batch_size: batch_size.max(1),
+                max_latency,
+                strict: false,
+                line_no: 0,
+                partial: Vec::new(),
+                stop: stop.clone(),
+            }),
+            pending: None,
+            ready: VecDeque::new(),
+            stop,
+        }
+    }
+
+    /// 关闭这个流的句柄，可以在其他任务中使用
+    pub fn shutdown_handle(&self) -> LinesShutdown {
+        LinesShutdown {
+            stop: self.stop.clone(),
+        }
+    }
+
+    /// 设置翻译失败时是否结束流
+    ///
+    /// # 参数
+    /// - `strict`: 为true时第一次翻译失败后结束流
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        if let Some(inner) = &mut self.inner {
+            inner.strict = strict;
+        }
+        self
+    }
+}
+
+/// 读取一行的结果
+enum ReadLine {
+    /// 读到一行（不含换行符）
+    Line(String),
+    /// 到达截止时刻，已读到的部分保留到下次读取
+    Timeout,
+    /// 读到末尾
+    Eof,
+}
+
+impl<R> Inner<R>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    /// 读取一行
+    ///
+    /// # 参数
+    /// - `deadline`: 截止时刻，None表示一直等待
+    async fn read_line(&mut self, deadline: Option<Instant>) -> io::Result<ReadLine> {
+        let read = self.reader.read_until(b'\n', &mut self.partial);
+        let n = match deadline {
+            // read_until是取消安全的，超时前已读到的数据保留在partial中
+            Some(deadline) => match tokio::time::timeout_at(deadline, read).await {
+                Ok(n) => n?,
+                Err(_) => return Ok(ReadLine::Timeout),
+            },
+            None => read.await?,
+        };
+        if n == 0 && self.partial.is_empty() {
+            return Ok(ReadLine::Eof);
+        }
+        let mut bytes = std::mem::take(&mut self.partial);
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+        self.line_no += 1;
+        Ok(ReadLine::Line(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// 读取并翻译一个批次
+    ///
+    /// 第一行一直等待，之后的行最多等到第一行到达后的`max_latency`
+    async fn next_batch(mut self) -> Batch<R> {
+        let mut lines = Vec::new();
+        let mut deadline = None;
+        let mut eof = false;
+        let mut read_error = None;
+        while lines.len() < self.batch_size {
+            let stop = self.stop.clone();
+            let read = tokio::select! {
+                biased;
+                _ = stop.cancelled() => {
+                    eof = true;
+                    break;
+                }
+                read = self.read_line(deadline) => read,
+            };
+            match read {
+                Ok(ReadLine::Line(line)) => {
+                    lines.push(line);
+                    deadline.get_or_insert_with(|| Instant::now() + self.max_latency);
+                }
+                Ok(ReadLine::Timeout) => break,
+                Ok(ReadLine::Eof) => {
+                    eof = true;
+                    break;
+                }
+                Err(err) => {
+                    read_error = Some(err);
+                    break;
+                }
+            }
+        }
+        let first_line = self.line_no + 1 - lines.len();
+        let mut items = translate(&*self.translator, self.from, &self.to, first_line, lines).await;
+        if self.strict {
+            if let Some(failed) = items.iter().position(Result::is_err) {
+                items.truncate(failed + 1);
+                return Batch { inner: None, items };
+            }
+        }
+        if let Some(err) = read_error {
+            items.push(Err(err));
+            return Batch { inner: None, items };
+        }
+        Batch {
+            inner: (!eof).then_some(self),
+            items,
+        }
+    }
+}
+
+/// 翻译一个批次中的非空白行
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `first_line`: 批次第一行的行号
+/// - `lines`: 批次中的行
+///
+/// # 返回值
+/// 与`lines`一一对应的结果，翻译失败时非空白行为包含`LineError`的错误
+async fn translate(
+    translator: &dyn AsyncTranslator,
+    from: Option<Language>,
+    to: &Language,
+    first_line: usize,
+    lines: Vec<String>,
+) -> Vec<io::Result<String>> {
+    let query = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    if query.is_empty() {
+        return lines.into_iter().map(Ok).collect();
+    }
+    let mut translated = match translator.translate_vec(&query, from, to).await {
+        Ok(output) => Ok(output.text.into_iter()),
+        Err(err) => Err(Arc::new(err)),
+    };
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if line.trim().is_empty() {
+                return Ok(line);
+            }
+            match &mut translated {
+                Ok(text) => Ok(text.next().unwrap_or_default()),
+                Err(error) => Err(io::Error::other(LineError {
+                    line: first_line + i,
+                    error: error.clone(),
+                })),
+            }
+        })
+        .collect()
+}
+
+impl<R> Stream for TranslatingLines<R>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    type Item = io::Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+            if self.pending.is_none() {
+                match self.inner.take() {
+                    Some(inner) => self.pending = Some(Box::pin(inner.next_batch())),
+                    None => return Poll::Ready(None),
+                }
+            }
+            let batch = match self.pending.as_mut().unwrap().as_mut().poll(cx) {
+                Poll::Ready(batch) => batch,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.pending = None;
+            self.inner = batch.inner;
+            self.ready.extend(batch.items);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::line_stream::{LineError, TranslatingLines};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use futures_core::Stream;
+    use std::io;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncWriteExt, BufReader, DuplexStream};
+
+    /// 创建读取`reader`的逐行翻译流
+    fn lines(
+        reader: DuplexStream,
+        translator: &Arc<MockTranslator>,
+        batch_size: usize,
+    ) -> TranslatingLines<BufReader<DuplexStream>> {
+        TranslatingLines::new(
+            BufReader::new(reader),
+            translator.clone(),
+            None,
+            Language::English,
+            batch_size,
+            Duration::from_millis(100),
+        )
+    }
+
+    /// 读取流中的所有结果
+    async fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut items = Vec::new();
+        while let Some(item) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+        {
+            items.push(item);
+        }
+        items
+    }
+
+    /// 取出错误中的行号
+    fn error_line(err: &io::Error) -> usize {
+        err.get_ref()
+            .and_then(|e| e.downcast_ref::<LineError>())
+            .unwrap()
+            .line
+    }
+
+    /// 测试按数量分批，读到末尾时翻译剩余的行，空白行原样保留
+    #[tokio::test(start_paused = true)]
+    async fn test_batches_by_count_and_flushes_on_eof() {
+        let translator = Arc::new(MockTranslator::new());
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer
+            .write_all(b"one\r\ntwo\n\nthree\nfour\nfive")
+            .await
+            .unwrap();
+        drop(writer);
+        let items = collect(lines(reader, &translator, 2)).await;
+        assert_eq!(
+            items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec!["ONE", "TWO", "", "THREE", "FOUR", "FIVE"]
+        );
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec!["one".to_string(), "two".to_string()],
+                vec!["three".to_string()],
+                vec!["four".to_string(), "five".to_string()],
+            ]
+        );
+    }
+
+    /// 测试第一行到达后等待超过`max_latency`时不再等待凑满批次
+    #[tokio::test(start_paused = true)]
+    async fn test_batches_by_time() {
+        let translator = Arc::new(MockTranslator::new());
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            writer.write_all(b"a\nb\npar").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            writer.write_all(b"tial\nc\n").await.unwrap();
+        });
+        let items = collect(lines(reader, &translator, 10)).await;
+        assert_eq!(
+            items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec!["A", "B", "PARTIAL", "C"]
+        );
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["partial".to_string(), "c".to_string()],
+            ]
+        );
+    }
+
+    /// 测试翻译失败时按行产出错误并继续，严格模式下结束流
+    #[tokio::test(start_paused = true)]
+    async fn test_errors_are_tagged_with_line_numbers() {
+        let input = b"ok 1\nboom\nok 2\n";
+
+        let translator = Arc::new(MockTranslator::new().fail_on("boom"));
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(input).await.unwrap();
+        drop(writer);
+        let items = collect(lines(reader, &translator, 2)).await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(error_line(items[0].as_ref().unwrap_err()), 1);
+        assert_eq!(error_line(items[1].as_ref().unwrap_err()), 2);
+        assert_eq!(items[2].as_ref().unwrap(), "OK 2");
+
+        let translator = Arc::new(MockTranslator::new().fail_on("boom"));
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(input).await.unwrap();
+        drop(writer);
+        let items = collect(lines(reader, &translator, 2).with_strict(true)).await;
+        assert_eq!(items.len(), 1);
+        assert_eq!(error_line(items[0].as_ref().unwrap_err()), 1);
+        assert_eq!(translator.calls(), 1);
+    }
+
+    /// 测试关闭时已读入的行翻译后产出，之后不再读取输入
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_flushes_lines_already_read() {
+        use crate::fusion_translator::shutdown::Shutdown;
+
+        // 在攒批的等待期间关闭：已读入的两行产出，未读完的一行不产出
+        let translator = Arc::new(MockTranslator::new());
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(b"one\ntwo\npar").await.unwrap();
+        let stream = lines(reader, &translator, 10);
+        let handle = stream.shutdown_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            handle.shutdown().await.unwrap();
+        });
+        let items = collect(stream).await;
+        assert_eq!(
+            items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec!["ONE", "TWO"]
+        );
+        assert_eq!(
+            translator.requests(),
+            vec![vec!["one".to_string(), "two".to_string()]]
+        );
+
+        // 在翻译期间关闭：正在翻译的批次产出，之后的行不再读取
+        let translator = Arc::new(MockTranslator::new().with_delay(Duration::from_secs(1)));
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(b"a\nb\nc\n").await.unwrap();
+        let stream = lines(reader, &translator, 1);
+        let handle = stream.shutdown_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1500)).await;
+            handle.shutdown().await.unwrap();
+        });
+        let items = collect(stream).await;
+        assert_eq!(
+            items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec!["A", "B"]
+        );
+        assert_eq!(translator.calls(), 2);
+        drop(writer);
+    }
+}
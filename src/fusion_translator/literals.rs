@@ -0,0 +1,1093 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::sync::Arc;
+
+/// 占位符的起始字符（U+27E6）
+const OPEN: char = '⟦';
+
+/// 占位符的结束字符（U+27E7）
+const CLOSE: char = '⟧';
+
+/// 数字与百分号、单位之间允许的空格
+///
+/// 普通空格、不换行空格（U+00A0）和窄不换行空格（U+202F）
+const SPACES: [char; 3] = [' ', '\u{a0}', '\u{202f}'];
+
+/// 识别的单位，较长的单位排在以其开头的较短单位之前
+///
+/// 只有一个字母的单位必须紧跟数字，避免把`5 m`之类的普通单词当作单位
+const UNITS: &[&str] = &[
+    "km/h", "km", "kg", "kWh", "kW", "kB", "KB", "MB", "GB", "TB", "MHz", "GHz", "Hz", "cm", "mm",
+    "mg", "ml", "ms", "min", "mph", "px", "°C", "°F", "m", "g", "l", "L", "s", "h", "W", "V",
+];
+
+/// 字面量的保护模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LiteralMode {
+    /// 原样还原原文中的字面量
+    #[default]
+    Preserve,
+    /// 按目标语言的习惯重新格式化数字和日期
+    ///
+    /// 千位分隔符、小数点、百分号前的空格和日期顺序按`LocaleFormat::of`转换，
+    /// 时间、带时间的ISO 8601时间戳和版本号、IP地址等点分序列属于机器可读的数据，仍原样还原
+    Localize,
+}
+
+/// 字面量的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    /// 数字，可以带正负号、千位分隔符和小数部分
+    Number,
+    /// 百分数，如`12.5%`
+    Percent,
+    /// 带单位的数量，如`5 km`
+    Quantity,
+    /// ISO 8601日期，如`2024-05-03`
+    Date,
+    /// 时间，如`14:30`或`14:30:05`
+    Time,
+    /// 带时间的ISO 8601时间戳，如`2024-05-03T14:30:00Z`
+    Timestamp,
+    /// 由点分隔的三段及以上数字，如版本号`1.2.3`和IPv4地址`192.168.0.1`，整体作为一个字面量，不做本地化
+    Dotted,
+}
+
+/// 原文中被替换为占位符的字面量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal {
+    /// 类别
+    pub kind: LiteralKind,
+    /// 原文中的文本
+    pub text: String,
+    /// 数字部分的字符数，百分号和单位在其后
+    number_len: usize,
+}
+
+/// 替换字面量之后的文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedText {
+    /// 把字面量依次替换为`⟦0⟧`、`⟦1⟧`……之后的文本
+    pub text: String,
+    /// 按出现顺序排列的字面量，序号即占位符的编号
+    pub literals: Vec<Literal>,
+}
+
+/// 日期的书写顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// 保持ISO 8601格式，如`2024-05-03`
+    Iso,
+    /// 月/日/年，如`5/3/2024`
+    MonthDayYear,
+    /// 日、月、年用指定的分隔符连接并补零，如`03.05.2024`
+    DayMonthYear(char),
+    /// 中文和日文的年月日，如`2024年5月3日`
+    Cjk,
+    /// 韩文的`2024. 5. 3.`
+    Korean,
+}
+
+/// 一种语言书写数字和日期的习惯
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormat {
+    /// 千位分隔符
+    pub group: char,
+    /// 小数点
+    pub decimal: char,
+    /// 数字与百分号之间的空格，None表示紧跟
+    pub percent_space: Option<char>,
+    /// 日期的书写顺序
+    pub date: DateStyle,
+}
+
+impl LocaleFormat {
+    /// 英语的书写习惯，也用于未列出的语言
+    pub const ENGLISH: Self = Self {
+        group: ',',
+        decimal: '.',
+        percent_space: None,
+        date: DateStyle::MonthDayYear,
+    };
+
+    /// 获取语言的书写习惯
+    ///
+    /// 覆盖常用语言，其余语言使用英语的数字格式并保持ISO日期
+    ///
+    /// # 参数
+    /// - `lang`: 语言
+    pub fn of(lang: Language) -> Self {
+        let comma = |group, percent_space, date| Self {
+            group,
+            decimal: ',',
+            percent_space,
+            date,
+        };
+        match lang {
+            Language::English => Self::ENGLISH,
+            Language::Chinese | Language::ChineseTraditional | Language::Japanese => Self {
+                date: DateStyle::Cjk,
+                ..Self::ENGLISH
+            },
+            Language::Korean => Self {
+                date: DateStyle::Korean,
+                ..Self::ENGLISH
+            },
+            Language::German | Language::Danish => {
+                comma('.', Some('\u{a0}'), DateStyle::DayMonthYear('.'))
+            }
+            Language::Spanish | Language::Italian | Language::Portuguese | Language::Indonesian => {
+                comma('.', None, DateStyle::DayMonthYear('/'))
+            }
+            Language::Dutch => comma('.', None, DateStyle::DayMonthYear('-')),
+            Language::French => comma('\u{202f}', Some('\u{202f}'), DateStyle::DayMonthYear('/')),
+            Language::Russian | Language::Ukrainian => {
+                comma('\u{a0}', Some('\u{a0}'), DateStyle::DayMonthYear('.'))
+            }
+            Language::Polish
+            | Language::Czech
+            | Language::Finnish
+            | Language::Norwegian
+            | Language::NorwegianBokmål => comma('\u{a0}', None, DateStyle::DayMonthYear('.')),
+            Language::Swedish => comma('\u{a0}', None, DateStyle::Iso),
+            _ => Self {
+                date: DateStyle::Iso,
+                ..Self::ENGLISH
+            },
+        }
+    }
+
+    /// 是否为千位分隔符
+    ///
+    /// 分隔符为空格类字符时，普通空格、不换行空格和窄不换行空格都接受
+    fn is_group(&self, c: char) -> bool {
+        c == self.group || (SPACES.contains(&self.group) && SPACES.contains(&c))
+    }
+}
+
+/// 把原文中的数字、日期、时间和带单位的数量替换为占位符
+///
+/// 只在词的边界处识别：紧跟在字母或数字之后、或后面紧跟字母或数字的不算，
+/// 如`COVID-19`中的`19`会被替换而`-`不会，`3rd`不会被替换。数字的千位分隔符和小数点按源语言的习惯识别，
+/// 源语言未知时按英语习惯
+///
+/// # 参数
+/// - `text`: 原文
+/// - `from`: 源语言，None表示未知
+///
+/// # 返回值
+/// 替换后的文本及按顺序排列的字面量
+pub fn mask(text: &str, from: Option<Language>) -> MaskedText {
+    let format = from.map_or(LocaleFormat::ENGLISH, LocaleFormat::of);
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut masked = String::with_capacity(text.len());
+    let mut literals = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut at = 0;
+    while let Some(&c) = chars.get(at) {
+        let literal = if prev.is_some_and(char::is_alphanumeric) {
+            None
+        } else {
+            scan(&chars, at, &format)
+        };
+        match literal {
+            Some((end, literal)) => {
+                masked.push(OPEN);
+                masked.push_str(&literals.len().to_string());
+                masked.push(CLOSE);
+                literals.push(literal);
+                prev = chars.get(end - 1).copied();
+                at = end;
+            }
+            None => {
+                masked.push(c);
+                prev = Some(c);
+                at += 1;
+            }
+        }
+    }
+    MaskedText {
+        text: masked,
+        literals,
+    }
+}
+
+impl MaskedText {
+    /// 把译文中的占位符还原为字面量
+    ///
+    /// 占位符内外允许有空格（部分翻译服务会插入），每个编号必须恰好出现一次
+    ///
+    /// # 参数
+    /// - `output`: 翻译服务返回的译文
+    /// - `mode`: 保护模式
+    /// - `from`: 源语言，用于识别原文数字的格式
+    /// - `to`: 目标语言，用于`LiteralMode::Localize`
+    ///
+    /// # 返回值
+    /// 还原后的译文；占位符缺失、重复或编号超出范围时返回`TranslatorError::PlaceholderMismatch`，`index`为0
+    pub fn restore(
+        &self,
+        output: &str,
+        mode: LiteralMode,
+        from: Option<Language>,
+        to: Language,
+    ) -> Result<String, TranslatorError> {
+        let mismatch = |found| TranslatorError::PlaceholderMismatch {
+            index: 0,
+            expected: self.literals.len(),
+            found,
+        };
+        let mut restored = String::with_capacity(output.len());
+        let mut seen = vec![false; self.literals.len()];
+        let mut found = 0;
+        let mut rest = output;
+        while let Some(start) = rest.find(OPEN) {
+            let (before, after) = rest.split_at(start);
+            restored.push_str(before);
+            let Some((index, len)) = placeholder(after) else {
+                restored.push(OPEN);
+                rest = after.get(OPEN.len_utf8()..).unwrap_or_default();
+                continue;
+            };
+            found += 1;
+            rest = after.get(len..).unwrap_or_default();
+            let slot = seen.get_mut(index).filter(|seen| !**seen);
+            let (Some(slot), Some(literal)) = (slot, self.literals.get(index)) else {
+                return Err(mismatch(found + placeholder_count(rest)));
+            };
+            *slot = true;
+            match mode {
+                LiteralMode::Preserve => restored.push_str(&literal.text),
+                LiteralMode::Localize => restored.push_str(&localize(literal, from, to)),
+            }
+        }
+        restored.push_str(rest);
+        if found != self.literals.len() {
+            return Err(mismatch(found));
+        }
+        Ok(restored)
+    }
+}
+
+/// 按目标语言的习惯重新格式化字面量
+///
+/// 数字、百分数和数量中的数字部分按源语言的习惯解析后重新格式化，只有原文使用了千位分隔符时才分组，
+/// 因此`2024`之类的年份不会变成`2,024`；单位原样保留。日期按目标语言的顺序重排，时间、时间戳和点分序列不变
+///
+/// # 参数
+/// - `literal`: 字面量
+/// - `from`: 源语言，None表示按英语习惯解析
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 格式化后的文本
+pub fn localize(literal: &Literal, from: Option<Language>, to: Language) -> String {
+    let source = from.map_or(LocaleFormat::ENGLISH, LocaleFormat::of);
+    let target = LocaleFormat::of(to);
+    let number = literal
+        .text
+        .chars()
+        .take(literal.number_len)
+        .collect::<String>();
+    let suffix = literal
+        .text
+        .chars()
+        .skip(literal.number_len)
+        .collect::<String>();
+    match literal.kind {
+        LiteralKind::Number => format_number(&number, &source, &target),
+        LiteralKind::Percent => {
+            let mut text = format_number(&number, &source, &target);
+            text.extend(target.percent_space);
+            text.push('%');
+            text
+        }
+        LiteralKind::Quantity => format_number(&number, &source, &target) + &suffix,
+        LiteralKind::Date => format_date(&literal.text, target.date),
+        LiteralKind::Time | LiteralKind::Timestamp | LiteralKind::Dotted => literal.text.clone(),
+    }
+}
+
+/// 解析开头的占位符
+///
+/// # 参数
+/// - `text`: 以`⟦`开头的文本
+///
+/// # 返回值
+/// 占位符的编号及字节长度，不是占位符时为None
+fn placeholder(text: &str) -> Option<(usize, usize)> {
+    let inner = text.strip_prefix(OPEN)?;
+    let end = inner.find(CLOSE)?;
+    let index = inner.get(..end)?.trim_matches(|c| SPACES.contains(&c));
+    if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((
+        index.parse().ok()?,
+        OPEN.len_utf8() + end + CLOSE.len_utf8(),
+    ))
+}
+
+/// 统计文本中的占位符数量
+///
+/// # 参数
+/// - `text`: 文本
+fn placeholder_count(text: &str) -> usize {
+    text.match_indices(OPEN)
+        .filter(|(start, _)| text.get(*start..).and_then(placeholder).is_some())
+        .count()
+}
+
+/// 在指定位置识别字面量
+///
+/// 依次尝试日期（及时间戳）、时间、点分序列和数字，取第一个后面不紧跟字母或数字的结果。
+/// 点分序列只在比数字更长时采用，因此`1.234.567`（德语的千位分组）仍识别为数字
+///
+/// # 参数
+/// - `chars`: 原文的字符
+/// - `at`: 起始位置
+/// - `format`: 源语言的书写习惯
+///
+/// # 返回值
+/// 字面量的结束位置及字面量
+fn scan(chars: &[char], at: usize, format: &LocaleFormat) -> Option<(usize, Literal)> {
+    let number = scan_number(chars, at, format);
+    let dotted = scan_dotted(chars, at)
+        .filter(|end| number.is_none_or(|(number_end, _, _)| *end > number_end))
+        .map(|end| (end, LiteralKind::Dotted, end - at));
+    let candidates = [
+        scan_date(chars, at),
+        scan_time(chars, at).map(|end| (end, LiteralKind::Time, end - at)),
+        dotted,
+        number,
+    ];
+    let (end, kind, number_len) = candidates
+        .into_iter()
+        .flatten()
+        .find(|(end, _, _)| !chars.get(*end).is_some_and(|c| c.is_alphanumeric()))?;
+    let text = chars.get(at..end)?.iter().collect();
+    Some((
+        end,
+        Literal {
+            kind,
+            text,
+            number_len,
+        },
+    ))
+}
+
+/// 从指定位置开始的ASCII数字个数
+fn digits(chars: &[char], at: usize) -> usize {
+    chars
+        .iter()
+        .skip(at)
+        .take_while(|c| c.is_ascii_digit())
+        .count()
+}
+
+/// 解析指定位置开始的恰好`len`位数字
+fn number_at(chars: &[char], at: usize, len: usize) -> Option<u32> {
+    if digits(chars, at) != len {
+        return None;
+    }
+    chars
+        .iter()
+        .skip(at)
+        .take(len)
+        .try_fold(0, |n, c| Some(n * 10 + c.to_digit(10)?))
+}
+
+/// 识别`YYYY-MM-DD`日期，后面跟`T`或空格和时间时识别为时间戳
+///
+/// # 返回值
+/// 结束位置、类别和数字部分的字符数
+fn scan_date(chars: &[char], at: usize) -> Option<(usize, LiteralKind, usize)> {
+    number_at(chars, at, 4)?;
+    let month = number_at(chars, at + 5, 2)?;
+    let day = number_at(chars, at + 8, 2)?;
+    if chars.get(at + 4) != Some(&'-')
+        || chars.get(at + 7) != Some(&'-')
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+    {
+        return None;
+    }
+    let date_end = at + 10;
+    if matches!(chars.get(date_end), Some('T' | ' ')) {
+        if let Some(end) = scan_time(chars, date_end + 1) {
+            let end = scan_zone(chars, end);
+            return Some((end, LiteralKind::Timestamp, end - at));
+        }
+    }
+    Some((date_end, LiteralKind::Date, date_end - at))
+}
+
+/// 识别由点分隔的三段及以上数字，如`1.2.3`、`192.168.0.1`
+///
+/// 末尾的句点不属于序列
+///
+/// # 返回值
+/// 结束位置，不足两个分隔符时为None
+fn scan_dotted(chars: &[char], at: usize) -> Option<usize> {
+    let mut end = at + digits(chars, at);
+    if end == at {
+        return None;
+    }
+    let mut separators = 0;
+    while chars.get(end) == Some(&'.') && digits(chars, end + 1) > 0 {
+        end += 1 + digits(chars, end + 1);
+        separators += 1;
+    }
+    (separators >= 2).then_some(end)
+}
+
+/// 识别`HH:MM`、`HH:MM:SS`和带小数秒的时间
+///
+/// # 返回值
+/// 结束位置
+fn scan_time(chars: &[char], at: usize) -> Option<usize> {
+    let hour_len = digits(chars, at);
+    if !matches!(hour_len, 1 | 2) {
+        return None;
+    }
+    let hour = number_at(chars, at, hour_len)?;
+    let mut end = at + hour_len;
+    if chars.get(end) != Some(&':') || hour > 23 || number_at(chars, end + 1, 2)? > 59 {
+        return None;
+    }
+    end += 3;
+    if chars.get(end) == Some(&':')
+        && number_at(chars, end + 1, 2).is_some_and(|second| second <= 60)
+    {
+        end += 3;
+        if matches!(chars.get(end), Some('.' | ',')) && digits(chars, end + 1) > 0 {
+            end += 1 + digits(chars, end + 1);
+        }
+    }
+    Some(end)
+}
+
+/// 识别时间戳末尾的时区：`Z`或`+08:00`、`-0500`
+///
+/// # 返回值
+/// 结束位置，没有时区时为`at`
+fn scan_zone(chars: &[char], at: usize) -> usize {
+    match chars.get(at) {
+        Some('Z') => at + 1,
+        Some('+' | '-') if number_at(chars, at + 1, 2).is_some() => {
+            if chars.get(at + 3) == Some(&':') && number_at(chars, at + 4, 2).is_some() {
+                at + 6
+            } else if number_at(chars, at + 3, 2).is_some() {
+                at + 5
+            } else {
+                at + 3
+            }
+        }
+        _ => at,
+    }
+}
+
+/// 识别数字、百分数和带单位的数量
+///
+/// 数字可以带正负号（`-`、`+`和减号U+2212），整数部分不超过三位时才识别其后的千位分组
+///
+/// # 返回值
+/// 结束位置、类别和数字部分的字符数
+fn scan_number(
+    chars: &[char],
+    at: usize,
+    format: &LocaleFormat,
+) -> Option<(usize, LiteralKind, usize)> {
+    let sign = usize::from(matches!(chars.get(at), Some('-' | '+' | '\u{2212}')));
+    let lead = digits(chars, at + sign);
+    if lead == 0 {
+        return None;
+    }
+    let mut end = at + sign + lead;
+    if lead <= 3 {
+        while chars.get(end).is_some_and(|c| format.is_group(*c)) && digits(chars, end + 1) == 3 {
+            end += 4;
+        }
+    }
+    if chars.get(end) == Some(&format.decimal) && digits(chars, end + 1) > 0 {
+        end += 1 + digits(chars, end + 1);
+    }
+    let number_len = end - at;
+    let gap = usize::from(chars.get(end).is_some_and(|c| SPACES.contains(c)));
+    if chars.get(end + gap) == Some(&'%') {
+        return Some((end + gap + 1, LiteralKind::Percent, number_len));
+    }
+    for unit in UNITS {
+        let len = unit.chars().count();
+        let matched = chars
+            .iter()
+            .skip(end + gap)
+            .take(len)
+            .copied()
+            .eq(unit.chars());
+        let boundary = !chars
+            .get(end + gap + len)
+            .is_some_and(|c| c.is_alphanumeric());
+        if matched && boundary && (gap == 0 || len > 1) {
+            return Some((end + gap + len, LiteralKind::Quantity, number_len));
+        }
+    }
+    Some((end, LiteralKind::Number, number_len))
+}
+
+/// 按目标语言的习惯重新格式化数字
+///
+/// # 参数
+/// - `number`: 按源语言习惯书写的数字
+/// - `source`: 源语言的书写习惯
+/// - `target`: 目标语言的书写习惯
+fn format_number(number: &str, source: &LocaleFormat, target: &LocaleFormat) -> String {
+    let mut sign = None;
+    let mut integer = String::new();
+    let mut fraction = None::<String>;
+    let mut grouped = false;
+    for c in number.chars() {
+        match (c, &mut fraction) {
+            ('-' | '+' | '\u{2212}', _) => sign = Some(c),
+            (c, Some(fraction)) if c.is_ascii_digit() => fraction.push(c),
+            (c, None) if c.is_ascii_digit() => integer.push(c),
+            (c, None) if c == source.decimal => fraction = Some(String::new()),
+            _ => grouped = true,
+        }
+    }
+    let mut text = String::new();
+    text.extend(sign);
+    let len = integer.len();
+    for (i, c) in integer.chars().enumerate() {
+        if grouped && i > 0 && (len - i).is_multiple_of(3) {
+            text.push(target.group);
+        }
+        text.push(c);
+    }
+    if let Some(fraction) = fraction {
+        text.push(target.decimal);
+        text.push_str(&fraction);
+    }
+    text
+}
+
+/// 按目标语言的顺序重排`YYYY-MM-DD`日期
+///
+/// # 参数
+/// - `date`: ISO 8601日期
+/// - `style`: 日期的书写顺序
+fn format_date(date: &str, style: DateStyle) -> String {
+    let (Some(year), Some(month), Some(day)) = (date.get(..4), date.get(5..7), date.get(8..10))
+    else {
+        return date.to_string();
+    };
+    let unpadded = |part: &str| part.trim_start_matches('0').to_string();
+    match style {
+        DateStyle::Iso => date.to_string(),
+        DateStyle::MonthDayYear => format!("{}/{}/{}", unpadded(month), unpadded(day), year),
+        DateStyle::DayMonthYear(sep) => format!("{day}{sep}{month}{sep}{year}"),
+        DateStyle::Cjk => format!("{}年{}月{}日", year, unpadded(month), unpadded(day)),
+        DateStyle::Korean => format!("{}. {}. {}.", year, unpadded(month), unpadded(day)),
+    }
+}
+
+/// 保护数字、日期等字面量的翻译器
+///
+/// 翻译服务常把数字和日期按目标语言改写（如把`1,000.50`改成`1.000,50`，或把日期译错），
+/// 破坏了承载数据的文本。此翻译器把字面量替换为`⟦0⟧`形式的占位符后交给内部翻译器，
+/// 再把译文中的占位符还原为原文（`LiteralMode::Preserve`），或按目标语言重新格式化（`LiteralMode::Localize`）。
+/// 占位符数量不一致时返回`TranslatorError::PlaceholderMismatch`。
+///
+/// 模式默认为`Preserve`，可以用`with_mode`修改，也可以通过`TranslateOptions::protect_literals`按调用指定
+pub struct LiteralGuard {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 调用选项未指定时的保护模式
+    mode: LiteralMode,
+}
+
+impl LiteralGuard {
+    /// 创建字面量保护翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    pub fn new(inner: Arc<dyn AsyncTranslator>) -> Self {
+        Self {
+            inner,
+            mode: LiteralMode::default(),
+        }
+    }
+
+    /// 设置调用选项未指定时的保护模式
+    ///
+    /// # 参数
+    /// - `mode`: 保护模式
+    pub fn with_mode(mut self, mode: LiteralMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for LiteralGuard {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同，按替换后的文本计算
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 直接交给内部翻译器
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.inner.detect_language(text).await
+    }
+
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 还原字面量后的译文
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 还原字面量后的译文
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 原文中没有字面量时直接交给内部翻译器
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项，`protect_literals`优先于`with_mode`设置的模式
+    ///
+    /// # 返回值
+    /// 还原字面量后的译文，占位符数量不一致时返回`TranslatorError::PlaceholderMismatch`
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let masked = mask(query, from);
+        if masked.literals.is_empty() {
+            return self
+                .inner
+                .translate_with_options(query, from, to, options)
+                .await;
+        }
+        let mode = options.protect_literals.unwrap_or(self.mode);
+        let mut output = self
+            .inner
+            .translate_with_options(&masked.text, from, to, options)
+            .await?;
+        output.text = masked.restore(&output.text, mode, from, *to)?;
+        Ok(output)
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项，`protect_literals`优先于`with_mode`设置的模式
+    ///
+    /// # 返回值
+    /// 还原字面量后的译文，任一译文的占位符数量不一致时返回`TranslatorError::PlaceholderMismatch`
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let masked = query
+            .iter()
+            .map(|text| mask(text, from))
+            .collect::<Vec<_>>();
+        if masked.iter().all(|masked| masked.literals.is_empty()) {
+            return self
+                .inner
+                .translate_vec_with_options(query, from, to, options)
+                .await;
+        }
+        let mode = options.protect_literals.unwrap_or(self.mode);
+        let sent = masked
+            .iter()
+            .map(|masked| masked.text.clone())
+            .collect::<Vec<_>>();
+        let mut output = self
+            .inner
+            .translate_vec_with_options(&sent, from, to, options)
+            .await?;
+        if output.text.len() != masked.len() {
+            return Err(TranslatorError::BatchSizeMismatch(masked.len(), output.text.len()).into());
+        }
+        for (index, (text, masked)) in output.text.iter_mut().zip(&masked).enumerate() {
+            *text = masked
+                .restore(text, mode, from, *to)
+                .map_err(|err| match err {
+                    TranslatorError::PlaceholderMismatch {
+                        expected, found, ..
+                    } => TranslatorError::PlaceholderMismatch {
+                        index,
+                        expected,
+                        found,
+                    },
+                    err => err,
+                })?;
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::literals::{
+        mask, LiteralGuard, LiteralKind, LiteralMode, MaskedText,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::Arc;
+
+    /// 替换后的文本及各字面量的原文
+    fn masked(text: &str, from: Option<Language>) -> (String, Vec<String>) {
+        let masked = mask(text, from);
+        let literals = masked.literals.into_iter().map(|l| l.text).collect();
+        (masked.text, literals)
+    }
+
+    /// 按目标语言重新格式化后的原文
+    fn localized(text: &str, from: Option<Language>, to: Language) -> String {
+        let masked = mask(text, from);
+        masked
+            .restore(&masked.text, LiteralMode::Localize, from, to)
+            .unwrap()
+    }
+
+    /// 测试千位分隔符和小数点按源语言识别
+    #[test]
+    fn test_thousands_separators() {
+        assert_eq!(
+            masked("Total: 1,000,000.50 USD", None),
+            (
+                "Total: ⟦0⟧ USD".to_string(),
+                vec!["1,000,000.50".to_string()]
+            )
+        );
+        assert_eq!(
+            masked("Summe: 1.000,50 EUR", Some(Language::German)),
+            ("Summe: ⟦0⟧ EUR".to_string(), vec!["1.000,50".to_string()])
+        );
+        assert_eq!(
+            masked("1,2,3", None).1,
+            vec!["1".to_string(), "2".to_string(), "3".to_string()]
+        );
+        assert_eq!(localized("1,000.50", None, Language::German), "1.000,50");
+        assert_eq!(
+            localized("1,000.50", None, Language::French),
+            "1\u{202f}000,50"
+        );
+        assert_eq!(
+            localized("1.000,50", Some(Language::German), Language::English),
+            "1,000.50"
+        );
+        assert_eq!(localized("in 2024", None, Language::German), "in 2024");
+    }
+
+    /// 测试负数及词中的数字
+    #[test]
+    fn test_negative_numbers() {
+        assert_eq!(
+            masked("from -12.5 to +3 and \u{2212}7", None).1,
+            vec!["-12.5", "+3", "\u{2212}7"]
+        );
+        assert_eq!(
+            masked("COVID-19, 2020-2021, 3rd, abc123", None),
+            (
+                "COVID-⟦0⟧, ⟦1⟧-⟦2⟧, 3rd, abc123".to_string(),
+                vec!["19".to_string(), "2020".to_string(), "2021".to_string()]
+            )
+        );
+        assert_eq!(
+            localized("-1,234.5", None, Language::Russian),
+            "-1\u{a0}234,5"
+        );
+    }
+
+    /// 测试版本号和IP地址整体识别为点分序列，本地化时原样还原
+    #[test]
+    fn test_versions_and_ip_addresses() {
+        assert_eq!(
+            masked("Version 1.2.3 released.", None),
+            (
+                "Version ⟦0⟧ released.".to_string(),
+                vec!["1.2.3".to_string()]
+            )
+        );
+        let ip = mask("Connect to 192.168.0.1 or 10.0.0.254.", None);
+        assert_eq!(ip.text, "Connect to ⟦0⟧ or ⟦1⟧.");
+        assert!(ip.literals.iter().all(|l| l.kind == LiteralKind::Dotted));
+        for lang in [Language::German, Language::French, Language::Russian] {
+            assert_eq!(localized("Version 1.2.3", None, lang), "Version 1.2.3");
+            assert_eq!(localized("192.168.0.1", None, lang), "192.168.0.1");
+            assert_eq!(
+                localized("v2.10.0-rc.1", Some(Language::German), lang),
+                "v2.10.0-rc.1"
+            );
+            assert_eq!(
+                localized("Build 1.2.3.4567", Some(lang), Language::English),
+                "Build 1.2.3.4567"
+            );
+        }
+        assert_eq!(
+            localized("1.5 and 2.0", None, Language::German),
+            "1,5 and 2,0"
+        );
+        assert_eq!(
+            localized("1.234.567", Some(Language::German), Language::English),
+            "1,234,567"
+        );
+    }
+
+    /// 测试ISO 8601日期和时间戳
+    #[test]
+    fn test_iso_timestamps() {
+        let masked = mask(
+            "Built 2024-05-03T14:30:00Z, deployed 2024-05-04 09:15:30.250+08:00 on 2024-05-06 at 18:00",
+            None,
+        );
+        let kinds = masked
+            .literals
+            .iter()
+            .map(|l| (l.kind, l.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (LiteralKind::Timestamp, "2024-05-03T14:30:00Z"),
+                (LiteralKind::Timestamp, "2024-05-04 09:15:30.250+08:00"),
+                (LiteralKind::Date, "2024-05-06"),
+                (LiteralKind::Time, "18:00"),
+            ]
+        );
+        assert_eq!(masked.text, "Built ⟦0⟧, deployed ⟦1⟧ on ⟦2⟧ at ⟦3⟧");
+        assert_eq!(mask("2024-13-01", None).literals.len(), 3);
+        assert_eq!(
+            localized("2024-05-03", None, Language::Chinese),
+            "2024年5月3日"
+        );
+        assert_eq!(localized("2024-05-03", None, Language::English), "5/3/2024");
+        assert_eq!(
+            localized("2024-05-03", None, Language::German),
+            "03.05.2024"
+        );
+        assert_eq!(
+            localized("2024-05-03", None, Language::Korean),
+            "2024. 5. 3."
+        );
+        assert_eq!(
+            localized("2024-05-03T14:30:00Z", None, Language::German),
+            "2024-05-03T14:30:00Z"
+        );
+    }
+
+    /// 测试百分数和带单位的数量
+    #[test]
+    fn test_percentages_and_units() {
+        let masked = mask("Up 12.5% to 40 % at 5km and 3 GB, 5 m", None);
+        let kinds = masked
+            .literals
+            .iter()
+            .map(|l| (l.kind, l.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (LiteralKind::Percent, "12.5%"),
+                (LiteralKind::Percent, "40 %"),
+                (LiteralKind::Quantity, "5km"),
+                (LiteralKind::Quantity, "3 GB"),
+                (LiteralKind::Number, "5"),
+            ]
+        );
+        assert_eq!(localized("12.5%", None, Language::French), "12,5\u{202f}%");
+        assert_eq!(localized("40 %", None, Language::English), "40%");
+        assert_eq!(localized("2.5 km", None, Language::German), "2,5 km");
+    }
+
+    /// 测试占位符缺失、重复时返回错误，带空格的占位符可以还原
+    #[test]
+    fn test_restore_mismatch() {
+        let masked = mask("1,000 and 2,000", None);
+        let restore =
+            |output: &str| masked.restore(output, LiteralMode::Preserve, None, Language::German);
+        assert_eq!(restore("⟦ 1 ⟧ und ⟦0⟧").unwrap(), "2,000 und 1,000");
+        for (output, found) in [("⟦0⟧", 1), ("⟦0⟧ ⟦0⟧", 2), ("⟦0⟧ ⟦1⟧ ⟦2⟧", 3)]
+        {
+            match restore(output) {
+                Err(TranslatorError::PlaceholderMismatch {
+                    index: 0,
+                    expected: 2,
+                    found: f,
+                }) => assert_eq!(f, found, "{}", output),
+                other => panic!("{}: {:?}", output, other),
+            }
+        }
+        let plain = MaskedText {
+            text: "a ⟦".to_string(),
+            literals: vec![],
+        };
+        assert_eq!(
+            plain
+                .restore("b ⟦x⟧", LiteralMode::Preserve, None, Language::German)
+                .unwrap(),
+            "b ⟦x⟧"
+        );
+    }
+
+    /// 测试翻译器保护字面量，调用选项可以切换为按目标语言格式化
+    #[tokio::test]
+    async fn test_guard() {
+        let mock = Arc::new(MockTranslator::new());
+        let guard = LiteralGuard::new(mock.clone());
+        let output = guard
+            .translate("paid 1,000.50 on 2024-05-03", None, &Language::German)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "PAID 1,000.50 ON 2024-05-03");
+        assert_eq!(mock.requests(), vec![vec!["paid ⟦0⟧ on ⟦1⟧".to_string()]]);
+        let options = TranslateOptions::new().with_protect_literals(LiteralMode::Localize);
+        let output = guard
+            .translate_vec_with_options(
+                &["no digits".to_string(), "-3.5%".to_string()],
+                None,
+                &Language::German,
+                &options,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["NO DIGITS", "-3,5\u{a0}%"]);
+    }
+
+    /// 测试内部翻译器丢失占位符时批量翻译返回对应序号
+    #[tokio::test]
+    async fn test_guard_mismatch() {
+        let guard = LiteralGuard::new(Arc::new(MockTranslator::new().with_suffix(" ⟦9⟧")));
+        let err = guard
+            .translate_vec(
+                &["a 1".to_string(), "b 2".to_string()],
+                None,
+                &Language::German,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::PlaceholderMismatch { index: 0, .. })
+        ));
+    }
+
+    /// 模糊测试：任意输入替换后原样还原，且不会panic
+    #[test]
+    fn test_fuzz_roundtrip() {
+        let corpus = [
+            "Total: 1,000,000.50 USD",
+            "2024-05-03T14:30:00.5+08:00 -12% 3 GB",
+            "Summe: 1.000,50 EUR, 23:59:60",
+        ];
+        for input in fuzz::inputs(703, &corpus) {
+            for from in [None, Some(Language::German), Some(Language::French)] {
+                if input.contains('⟦') {
+                    continue;
+                }
+                let masked = mask(&input, from);
+                let restored = masked
+                    .restore(&masked.text, LiteralMode::Preserve, from, Language::English)
+                    .unwrap();
+                assert_eq!(restored, input);
+                for to in [Language::English, Language::French, Language::Chinese] {
+                    let _ = masked.restore(&masked.text, LiteralMode::Localize, from, to);
+                }
+            }
+        }
+    }
+}
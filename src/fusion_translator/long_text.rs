@@ -0,0 +1,782 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationOutput,
+};
+use crate::fusion_translator::detect::{self, ConsensusOptions, LocalDetector};
+use crate::fusion_translator::input_check::{InputCheck, InputUnit};
+use crate::fusion_translator::provenance::ProvenanceEvent;
+use crate::fusion_translator::segment;
+use crate::fusion_translator::session::TranslationSession;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+/// 混合语言文本的翻译选项
+#[derive(Debug, Clone, Default)]
+pub struct MixedOptions {
+    /// 传给每次`translate_vec_with_options`调用的选项
+    pub translate_options: TranslateOptions,
+    /// 是否跳过已经是目标语言的段落，跳过的段落原样保留
+    pub skip_same_language: bool,
+    /// 检测段落语言的本地检测器
+    pub detector: LocalDetector,
+    /// 综合检测的选项，翻译器支持`detect_language`时用于判断文字较少的段落
+    pub consensus: ConsensusOptions,
+}
+
+/// 单个段落的翻译结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedParagraph {
+    /// 原文
+    pub source: String,
+    /// 译文，未翻译的段落与原文相同
+    pub text: String,
+    /// 检测到的源语言，段落中没有文字时为None
+    pub detected: Option<Language>,
+    /// 是否经过翻译，为false表示原样保留
+    pub translated: bool,
+}
+
+/// 混合语言文本的翻译结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedTranslation {
+    /// 按原有段落间隔拼接的完整译文
+    pub text: String,
+    /// 每个段落的翻译结果，按原文顺序排列
+    pub paragraphs: Vec<MixedParagraph>,
+}
+
+/// 文本片段
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    /// 段落
+    Paragraph(&'a str),
+    /// 段落之间的空行，包括换行符
+    Separator(&'a str),
+}
+
+/// 按空行把文本拆分为段落和分隔符
+///
+/// 所有片段按顺序拼接后与原文完全相同
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+fn split_paragraphs(text: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    // 已输出片段的结束位置
+    let mut last = 0;
+    // 当前段落的起止位置，段落末行的换行符归入其后的分隔符
+    let mut paragraph: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if !content.trim().is_empty() {
+            match &mut paragraph {
+                Some((_, end)) => *end = offset + content.len(),
+                None => {
+                    if offset > last {
+                        pieces.push(Piece::Separator(&text[last..offset]));
+                    }
+                    paragraph = Some((offset, offset + content.len()));
+                }
+            }
+        } else if let Some((start, end)) = paragraph.take() {
+            pieces.push(Piece::Paragraph(&text[start..end]));
+            last = end;
+        }
+        offset += line.len();
+    }
+    if let Some((start, end)) = paragraph {
+        pieces.push(Piece::Paragraph(&text[start..end]));
+        last = end;
+    }
+    if text.len() > last {
+        pieces.push(Piece::Separator(&text[last..]));
+    }
+    pieces
+}
+
+/// 检查文本的UTF-8字节数是否超出限制
+///
+/// # 参数
+/// - `query`: 待检查的文本
+/// - `max_bytes`: 最大字节数
+///
+/// # 返回值
+/// 超出限制时返回`TranslatorError::RequestTooLong`
+pub(crate) fn check_byte_limit(query: &str, max_bytes: usize) -> Result<(), TranslatorError> {
+    if query.len() > max_bytes {
+        return Err(TranslatorError::RequestTooLong(
+            query.len() as u32,
+            max_bytes as u32,
+        ));
+    }
+    Ok(())
+}
+
+/// 按字节数拆分文本
+///
+/// 优先在换行和句末标点处拆分，单个句子超出限制时在空白处拆分，
+/// 仍然超出时按字素簇边界截断。所有片段按顺序拼接后与原文完全相同，
+/// 拆分位置与`input_check::split_points`相同
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+/// - `max_bytes`: 每个片段的最大UTF-8字节数
+///
+/// # 返回值
+/// 不超过`max_bytes`字节的片段；单个字素簇（如表情符号序列）超出限制时所在片段不拆开
+pub fn split_by_bytes(text: &str, max_bytes: usize) -> Vec<&str> {
+    InputCheck::new(text, Some(max_bytes.max(4)), InputUnit::Bytes).parts(text)
+}
+
+/// 把文本拆分为句子，句子之间的空白归入前一句
+///
+/// 按`segment::split_sentences`的规则断句，语言按文字检测
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+pub(crate) fn sentences(text: &str) -> Vec<&str> {
+    segment::split_sentences(text, None)
+}
+
+/// 拆分后逐段翻译超长文本
+///
+/// 用于有长度限制的翻译器：按`split_by_bytes`拆分后依次调用`translate_with_options`，
+/// 每段两端的空白不发送给翻译服务，拼接时原样保留，从而保留段落间隔。
+/// 所有片段使用同一个请求ID
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `query`: 待翻译的文本
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+/// - `max_bytes`: 每次请求的最大UTF-8字节数
+///
+/// # 返回值
+/// 拼接后的翻译结果，语言和语言替换取自第一段
+pub async fn translate_split(
+    translator: &dyn AsyncTranslator,
+    query: &str,
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+    max_bytes: usize,
+) -> anyhow::Result<TranslationOutput> {
+    let parts = split_by_bytes(query, max_bytes);
+    translate_parts(translator, &parts, from, to, options, 1).await
+}
+
+/// 按`AsyncTranslator::check_input`给出的拆分位置逐段翻译超长文本
+///
+/// 用于不按UTF-8字节数计算长度的翻译器，拆分方式与`translate_split`相同
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `query`: 待翻译的文本
+/// - `check`: `query`的检查结果
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 拼接后的翻译结果，语言和语言替换取自第一段
+pub(crate) async fn translate_checked(
+    translator: &dyn AsyncTranslator,
+    query: &str,
+    check: &InputCheck,
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+) -> anyhow::Result<TranslationOutput> {
+    let parts = check.parts(query);
+    translate_parts(translator, &parts, from, to, options, 1).await
+}
+
+/// 逐行翻译多行文本
+///
+/// 用于无法在单次请求中保留换行的翻译器（如通过GET查询参数传递文本的服务）：
+/// 按`\n`拆分后每个非空行单独调用`translate_with_options`，最多同时发出`concurrency`个请求。
+/// 换行符（包括`\r\n`）、空行以及每行两端的空白原样保留，拼接结果除译文外与原文逐字节相同。
+/// 每行仍受翻译器单次请求长度限制的约束，所有行使用同一个请求ID
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `query`: 待翻译的文本
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+/// - `concurrency`: 最多同时进行的请求数，为0时按1处理
+///
+/// # 返回值
+/// 拼接后的翻译结果，语言和语言替换取自第一行
+pub async fn translate_lines(
+    translator: &dyn AsyncTranslator,
+    query: &str,
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+    concurrency: usize,
+) -> anyhow::Result<TranslationOutput> {
+    let lines = query.split_inclusive('\n').collect::<Vec<_>>();
+    translate_parts(translator, &lines, from, to, options, concurrency).await
+}
+
+/// 逐段翻译并按原顺序拼接
+///
+/// 每段两端的空白不发送给翻译服务，拼接时原样保留；全部由空白组成的片段不发送请求，
+/// 内容相同的片段在同一个翻译会话中只请求一次
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `parts`: 按顺序拼接后等于原文的片段
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+/// - `concurrency`: 最多同时进行的请求数
+///
+/// # 返回值
+/// 拼接后的翻译结果，语言、语言替换和来历取自第一个翻译的片段，来历追加`ProvenanceEvent::Chunked`
+async fn translate_parts(
+    translator: &dyn AsyncTranslator,
+    parts: &[&str],
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+    concurrency: usize,
+) -> anyhow::Result<TranslationOutput> {
+    let options = options.resolved();
+    let session = TranslationSession::new();
+    let (options_ref, session_ref) = (&options, &session);
+    let mut bodies = Vec::new();
+    let mut requests = Vec::new();
+    for part in parts {
+        let body = part.trim();
+        if body.is_empty() || bodies.contains(&body) {
+            continue;
+        }
+        bodies.push(body);
+        requests.push(async move {
+            if options_ref.is_cancelled() {
+                return Err(TranslatorError::Cancelled(0).into());
+            }
+            session_ref
+                .translate("", translator, body, from, to, options_ref)
+                .await
+        });
+    }
+    let outputs: Vec<TranslationOutput> = stream::iter(requests)
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+    let outputs = bodies.into_iter().zip(outputs).collect::<HashMap<_, _>>();
+
+    let mut text = String::with_capacity(parts.iter().map(|p| p.len()).sum());
+    let mut first: Option<TranslationOutput> = None;
+    let mut possibly_untranslated = false;
+    let mut emoji_appended = false;
+    for part in parts {
+        let trimmed = part.trim_start();
+        let body = trimmed.trim_end();
+        text.push_str(&part[..part.len() - trimmed.len()]);
+        if !body.is_empty() {
+            let output = outputs.get(body).cloned().unwrap_or_default();
+            text.push_str(&output.text);
+            possibly_untranslated |= output.detail.possibly_untranslated;
+            emoji_appended |= output.detail.emoji_appended;
+            first.get_or_insert(output);
+        }
+        text.push_str(&trimmed[body.len()..]);
+    }
+    let first = first.unwrap_or_default();
+    let mut provenance = first.detail.provenance;
+    provenance.push(ProvenanceEvent::Chunked {
+        chunks: parts.len(),
+    });
+    Ok(TranslationOutput {
+        text,
+        lang: first.lang,
+        detail: TranslationDetail {
+            request_id: options.request_id,
+            substitutions: first.detail.substitutions,
+            possibly_untranslated,
+            emoji_appended,
+            lang_raw: first.detail.lang_raw,
+            provenance,
+            ..Default::default()
+        },
+    })
+}
+
+/// 按文字检测文本的语言
+///
+/// 统计各种文字的字符数，取最多的一种；含有假名的汉字文本视为日语。
+/// 只能区分文字体系，拉丁字母统一视为英语、西里尔字母统一视为俄语
+///
+/// # 参数
+/// - `text`: 待检测的文本
+///
+/// # 返回值
+/// 检测到的语言，文本中没有文字时为None
+pub fn detect_script(text: &str) -> Option<Language> {
+    dominant_script(text).map(|(lang, _, _)| lang)
+}
+
+/// 统计文本中占多数的文字
+///
+/// # 参数
+/// - `text`: 待统计的文本
+///
+/// # 返回值
+/// （检测到的语言，属于该语言文字的字符数，所有文字的字符数），文本中没有文字时为None
+pub(crate) fn dominant_script(text: &str) -> Option<(Language, usize, usize)> {
+    let mut counts = [0usize; 10];
+    for c in text.chars() {
+        let index = match c as u32 {
+            0x3040..=0x30FF | 0x31F0..=0x31FF => 0,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => 1,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => 2,
+            0x0400..=0x04FF => 3,
+            0x0600..=0x06FF => 4,
+            0x0E00..=0x0E7F => 5,
+            0x0370..=0x03FF => 6,
+            0x0590..=0x05FF => 7,
+            0x0900..=0x097F => 8,
+            _ if c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{024F}').contains(&c)) => 9,
+            _ => continue,
+        };
+        counts[index] += 1;
+    }
+    let total = counts.iter().sum::<usize>();
+    let (index, count) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, count)| (**count, std::cmp::Reverse(*i)))?;
+    if *count == 0 {
+        return None;
+    }
+    Some(match index {
+        // 假名比汉字少见，只要出现假名就视为日语
+        0 | 1 if counts[0] > 0 => (Language::Japanese, counts[0] + counts[1], total),
+        1 => (Language::Chinese, *count, total),
+        2 => (Language::Korean, *count, total),
+        3 => (Language::Russian, *count, total),
+        4 => (Language::Arabic, *count, total),
+        5 => (Language::Thai, *count, total),
+        6 => (Language::Greek, *count, total),
+        7 => (Language::Hebrew, *count, total),
+        8 => (Language::Hindi, *count, total),
+        _ => (Language::English, *count, total),
+    })
+}
+
+/// 翻译混合语言的文本
+///
+/// 按空行拆分段落并逐段检测源语言，相邻且源语言相同的段落合并为一次`translate_vec`调用，
+/// 最后按原有的段落间隔拼接译文。重复出现的段落只请求一次；没有文字的段落原样保留；
+/// 开启`skip_same_language`时，已经是目标语言的段落也原样保留。
+///
+/// 语言检测使用`detect`模块的综合检测：文字较少、本地检测不够可信的段落会请求
+/// 翻译器的`detect_language`，不支持检测的翻译器只使用本地检测结果
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `text`: 待翻译的文本
+/// - `to`: 目标语言
+/// - `options`: 翻译选项
+///
+/// # 返回值
+/// 完整译文和每个段落的翻译结果
+pub async fn translate_mixed(
+    translator: &dyn AsyncTranslator,
+    text: &str,
+    to: &Language,
+    options: &MixedOptions,
+) -> anyhow::Result<MixedTranslation> {
+    let pieces = split_paragraphs(text);
+    let mut paragraphs = Vec::new();
+    for piece in &pieces {
+        if let Piece::Paragraph(source) = piece {
+            let detection =
+                detect::consensus(&[translator], &options.detector, source, &options.consensus)
+                    .await;
+            paragraphs.push(MixedParagraph {
+                source: source.to_string(),
+                text: source.to_string(),
+                detected: detection.lang,
+                translated: false,
+            });
+        }
+    }
+
+    let translatable = |p: &MixedParagraph| {
+        p.detected
+            .is_some_and(|lang| !(options.skip_same_language && lang == *to))
+    };
+    let translate_options = options.translate_options.resolved();
+    let session = TranslationSession::new();
+    let mut completed = 0;
+    let mut start = 0;
+    while start < paragraphs.len() {
+        if !translatable(&paragraphs[start]) {
+            start += 1;
+            continue;
+        }
+        let from = paragraphs[start].detected;
+        let end = (start..paragraphs.len())
+            .find(|&i| !translatable(&paragraphs[i]) || paragraphs[i].detected != from)
+            .unwrap_or(paragraphs.len());
+        if translate_options.is_cancelled() {
+            return Err(TranslatorError::Cancelled(completed).into());
+        }
+        let group = paragraphs[start..end]
+            .iter()
+            .map(|p| p.source.clone())
+            .collect::<Vec<_>>();
+        let output = translate_options
+            .run_cancellable(
+                completed,
+                session.translate_vec("", translator, &group, from, to, &translate_options),
+            )
+            .await?;
+        for (paragraph, text) in paragraphs[start..end].iter_mut().zip(output.text) {
+            paragraph.text = text;
+            paragraph.translated = true;
+        }
+        completed += end - start;
+        start = end;
+    }
+
+    let mut translated = paragraphs.iter();
+    let text = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Paragraph(_) => translated.next().map_or("", |p| p.text.as_str()),
+            Piece::Separator(separator) => separator,
+        })
+        .collect();
+    Ok(MixedTranslation { text, paragraphs })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::long_text::{
+        check_byte_limit, detect_script, split_by_bytes, split_paragraphs, translate_lines,
+        translate_mixed, translate_split, MixedOptions, Piece,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 混合语言示例文档
+    const MIXED: &str = include_str!("../../tests/fixtures/long_text/mixed.txt");
+
+    /// 测试拆分后的片段能拼接回原文
+    #[test]
+    fn test_split_paragraphs_roundtrip() {
+        for text in [
+            MIXED,
+            "",
+            "one",
+            "one\n",
+            "\n\none\n\n\ntwo",
+            "one\r\n\r\ntwo\r\n",
+            "  \nline 1\nline 2\n \n",
+        ] {
+            let pieces = split_paragraphs(text);
+            let joined: String = pieces
+                .iter()
+                .map(|piece| match piece {
+                    Piece::Paragraph(s) | Piece::Separator(s) => *s,
+                })
+                .collect();
+            assert_eq!(joined, text);
+            assert!(pieces
+                .iter()
+                .all(|piece| !matches!(piece, Piece::Paragraph(s) if s.ends_with(['\n', '\r']))));
+        }
+        assert_eq!(
+            split_paragraphs("a\nb\n\nc"),
+            vec![
+                Piece::Paragraph("a\nb"),
+                Piece::Separator("\n\n"),
+                Piece::Paragraph("c")
+            ]
+        );
+    }
+
+    /// 测试按文字检测语言
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect_script("Hello, world"), Some(Language::English));
+        assert_eq!(detect_script("你好，世界"), Some(Language::Chinese));
+        assert_eq!(detect_script("今日はいい天気"), Some(Language::Japanese));
+        assert_eq!(detect_script("안녕하세요"), Some(Language::Korean));
+        assert_eq!(detect_script("Привет"), Some(Language::Russian));
+        assert_eq!(detect_script("使用Rust编写"), Some(Language::Chinese));
+        assert_eq!(detect_script("123 -- 456"), None);
+    }
+
+    /// 测试各段落按各自的源语言翻译，相邻的同语言段落合并为一次调用
+    #[tokio::test]
+    async fn test_translate_mixed_groups_paragraphs() {
+        let translator = MockTranslator::new();
+        let result = translate_mixed(
+            &translator,
+            MIXED,
+            &Language::German,
+            &MixedOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec!["The quick brown fox.\nIt jumps over the lazy dog.".to_string()],
+                vec![
+                    "这是第一段中文。".to_string(),
+                    "这是第二段中文，和上一段语言相同。".to_string()
+                ],
+                vec!["Back to English here.".to_string()],
+                vec!["今日はいい天気ですね。".to_string()],
+            ]
+        );
+        assert_eq!(
+            result
+                .paragraphs
+                .iter()
+                .map(|p| p.detected)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(Language::English),
+                Some(Language::Chinese),
+                Some(Language::Chinese),
+                Some(Language::English),
+                Some(Language::Japanese),
+            ]
+        );
+        assert!(result.paragraphs.iter().all(|p| p.translated));
+        assert_eq!(result.text, MIXED.to_uppercase());
+    }
+
+    /// 测试重复出现的段落在同一次混合翻译中只请求一次
+    #[tokio::test]
+    async fn test_translate_mixed_reuses_repeated_paragraphs() {
+        let text = "Hello.\n\n你好。\n\nHello.\n\n你好。\n\nNew.";
+        let translator = MockTranslator::new();
+        let result = translate_mixed(
+            &translator,
+            text,
+            &Language::German,
+            &MixedOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec!["Hello.".to_string()],
+                vec!["你好。".to_string()],
+                vec!["New.".to_string()],
+            ]
+        );
+        assert_eq!(result.text, text.to_uppercase());
+    }
+
+    /// 测试开启`skip_same_language`时目标语言的段落原样保留
+    #[tokio::test]
+    async fn test_translate_mixed_skips_target_language() {
+        let translator = MockTranslator::new();
+        let options = MixedOptions {
+            skip_same_language: true,
+            ..Default::default()
+        };
+        let result = translate_mixed(&translator, MIXED, &Language::English, &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec![
+                    "这是第一段中文。".to_string(),
+                    "这是第二段中文，和上一段语言相同。".to_string()
+                ],
+                vec!["今日はいい天気ですね。".to_string()],
+            ]
+        );
+        assert_eq!(
+            result
+                .paragraphs
+                .iter()
+                .map(|p| p.translated)
+                .collect::<Vec<_>>(),
+            vec![false, true, true, false, true]
+        );
+        assert_eq!(result.paragraphs[0].text, result.paragraphs[0].source);
+        assert_eq!(result.text, MIXED);
+    }
+
+    /// 测试本地检测不够可信的段落使用翻译器的语言检测
+    #[tokio::test]
+    async fn test_translate_mixed_uses_remote_detection() {
+        let translator = MockTranslator::new().with_detection(Language::French);
+        let text = "Bonjour\n\n这是一个足够长的中文段落，本地检测就足够可信了\n\n2023";
+        let result = translate_mixed(
+            &translator,
+            text,
+            &Language::English,
+            &MixedOptions::default(),
+        )
+        .await
+        .unwrap();
+        let detected = result
+            .paragraphs
+            .iter()
+            .map(|p| p.detected)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            detected,
+            vec![Some(Language::French), Some(Language::Chinese), None]
+        );
+        assert_eq!(
+            translator.requests()[0],
+            vec!["Bonjour".to_string()],
+            "only the short paragraph is sent for detection"
+        );
+        assert_eq!(translator.calls(), 3);
+    }
+
+    /// 测试输入长度检查按字节计算
+    #[test]
+    fn test_check_byte_limit() {
+        assert!(check_byte_limit("short text", 500).is_ok());
+        assert!(check_byte_limit(&"a".repeat(500), 500).is_ok());
+        assert!(matches!(
+            check_byte_limit(&"a".repeat(600), 500),
+            Err(TranslatorError::RequestTooLong(600, 500))
+        ));
+        assert!(check_byte_limit(&"长".repeat(200), 500).is_err());
+    }
+
+    /// 测试按字节拆分在句子边界处断开且能拼接回原文
+    #[test]
+    fn test_split_by_bytes() {
+        let text = "第一句话。第二句话！\n\nThird sentence. Fourth one?";
+        let parts = split_by_bytes(text, 20);
+        assert_eq!(parts.concat(), text);
+        assert!(parts.iter().all(|part| part.len() <= 20));
+        assert_eq!(
+            parts,
+            vec![
+                "第一句话。",
+                "第二句话！\n\n",
+                "Third sentence. ",
+                "Fourth one?"
+            ]
+        );
+        // 小数点和缩写中的句点不是句子边界
+        assert_eq!(
+            split_by_bytes("Pi is 3.14 approx.", 100),
+            vec!["Pi is 3.14 approx."]
+        );
+        // 没有标点的长句在空白处拆分，再没有空白时按字符边界截断
+        assert_eq!(split_by_bytes("aaa bbb ccc", 8), vec!["aaa bbb ", "ccc"]);
+        assert_eq!(split_by_bytes("一二三四五", 7), vec!["一二", "三四", "五"]);
+        assert!(split_by_bytes("", 10).is_empty());
+    }
+
+    /// 测试逐行翻译：CRLF、连续空行、行首缩进和末尾换行逐字节保留
+    #[tokio::test(start_paused = true)]
+    async fn test_translate_lines() {
+        let cases = [
+            (
+                "first line\r\nsecond line\r\n",
+                "FIRST LINE\r\nSECOND LINE\r\n",
+                2,
+            ),
+            ("a\n\n\n  b\n", "A\n\n\n  B\n", 2),
+            ("\r\n\r\nx \r\n \n", "\r\n\r\nX \r\n \n", 1),
+            ("no newline", "NO NEWLINE", 1),
+            ("\n\n", "\n\n", 0),
+        ];
+        for (text, expected, calls) in cases {
+            let translator = MockTranslator::new();
+            let output = translate_lines(
+                &translator,
+                text,
+                None,
+                &Language::French,
+                &TranslateOptions::new().with_request_id("lines-1"),
+                4,
+            )
+            .await
+            .unwrap();
+            assert_eq!(output.text, expected, "{:?}", text);
+            assert_eq!(translator.calls(), calls, "{:?}", text);
+            assert!(translator.requests().iter().all(|r| !r[0].contains('\n')));
+            assert_eq!(output.detail.request_id.as_deref(), Some("lines-1"));
+        }
+    }
+
+    /// 测试逐行翻译按并发上限同时请求，结果保持原顺序
+    #[tokio::test(start_paused = true)]
+    async fn test_translate_lines_concurrency() {
+        let text = (1..=8)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let translator = MockTranslator::new().with_delay(Duration::from_secs(1));
+        let start = Instant::now();
+        let output = translate_lines(
+            &translator,
+            &text,
+            None,
+            &Language::French,
+            &TranslateOptions::default(),
+            4,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.text, text.to_uppercase());
+        // 8行每次4个并发，共两轮
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    /// 测试超长中文文本拆分为多次请求，译文保留段落间隔
+    #[tokio::test]
+    async fn test_translate_split_sub_requests() {
+        let sentence = "这是一个用于测试自动拆分的句子。";
+        let paragraph = sentence.repeat(10);
+        let text = format!("{}\n\n{}", paragraph, paragraph);
+        assert_eq!(text.len(), 962);
+        let translator = MockTranslator::new();
+        let output = translate_split(
+            &translator,
+            &text,
+            Some(Language::Chinese),
+            &Language::English,
+            &TranslateOptions::new().with_request_id("split-1"),
+            300,
+        )
+        .await
+        .unwrap();
+        // 每个句子48字节，每次请求最多6句，段落间隔随所在的请求一起发送；
+        // 第三段与第一段内容相同，不再重复请求
+        let requests = translator.requests();
+        assert_eq!(
+            requests,
+            vec![
+                vec![sentence.repeat(6)],
+                vec![format!("{}\n\n{}", sentence.repeat(4), sentence.repeat(2))],
+                vec![sentence.repeat(2)],
+            ]
+        );
+        assert!(requests.iter().all(|r| r[0].len() <= 300));
+        assert_eq!(output.text, text);
+        assert_eq!(output.detail.request_id.as_deref(), Some("split-1"));
+    }
+}
@@ -0,0 +1,664 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TokenStream, TranslationDetail, TranslationListOutput,
+    TranslationOutput,
+};
+use crate::fusion_translator::batch;
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::{ApiError, TranslatorError};
+use crate::fusion_translator::whitespace;
+use futures_util::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 模拟翻译器
+///
+/// 不调用任何远程API，把文本转换为大写作为“译文”，并记录每一次上游调用，
+/// 用于测试和示例中替代真实翻译服务
+#[derive(Debug, Default)]
+pub struct MockTranslator {
+    /// 上游调用次数
+    calls: AtomicUsize,
+    /// 每次调用收到的文本
+    requests: Mutex<Vec<Vec<String>>>,
+    /// 包含这些标记的文本会导致调用失败
+    fail_markers: Vec<String>,
+    /// 每次调用的模拟延迟
+    delay: Option<Duration>,
+    /// 模拟的单次批量请求最多文本数量
+    max_batch_len: Option<usize>,
+    /// 模拟的单次请求最多UTF-8字节数
+    input_limit: Option<usize>,
+    /// 剩余的暂时性失败次数
+    transient_failures: AtomicUsize,
+    /// 模拟的支持语言，None表示不限
+    languages: Option<Vec<Language>>,
+    /// 包含这些标记的文本会被模拟的内容审核拒绝
+    reject_markers: Vec<String>,
+    /// 包含这些标记的文本会返回免费额度已用完
+    quota_markers: Vec<String>,
+    /// 是否模拟按请求量计费的翻译器
+    paid: bool,
+    /// 模拟的语言检测结果，None表示不提供语言检测
+    detection: Option<Language>,
+    /// 是否模拟支持流式输出的翻译器
+    streaming: bool,
+    /// 追加在每个译文末尾的后缀
+    suffix: String,
+    /// `warm_up`的调用次数
+    warm_ups: AtomicUsize,
+}
+
+impl MockTranslator {
+    /// 创建新的模拟翻译器实例
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置失败标记
+    ///
+    /// 任意一次调用中只要有文本包含该标记，整次调用返回错误
+    ///
+    /// # 参数
+    /// - `marker`: 失败标记
+    pub fn fail_on(mut self, marker: impl Into<String>) -> Self {
+        self.fail_markers.push(marker.into());
+        self
+    }
+
+    /// 设置内容审核拒绝的标记
+    ///
+    /// 任意一次调用中只要有文本包含该标记，整次调用返回与百度翻译20003相同的内容审核错误
+    ///
+    /// # 参数
+    /// - `marker`: 拒绝标记
+    pub fn reject_on(mut self, marker: impl Into<String>) -> Self {
+        self.reject_markers.push(marker.into());
+        self
+    }
+
+    /// 设置额度用完的标记
+    ///
+    /// 任意一次调用中只要有文本包含该标记，整次调用返回`TranslatorError::Quota`
+    ///
+    /// # 参数
+    /// - `marker`: 额度用完标记
+    pub fn quota_on(mut self, marker: impl Into<String>) -> Self {
+        self.quota_markers.push(marker.into());
+        self
+    }
+
+    /// 设置是否模拟按请求量计费的翻译器
+    ///
+    /// # 参数
+    /// - `paid`: 是否计费
+    pub fn with_paid(mut self, paid: bool) -> Self {
+        self.paid = paid;
+        self
+    }
+
+    /// 设置模拟的语言检测结果
+    ///
+    /// 设置后`detect_language`对任何文本都返回该语言，并像翻译一样记录调用、检查失败标记
+    ///
+    /// # 参数
+    /// - `lang`: 检测结果
+    pub fn with_detection(mut self, lang: Language) -> Self {
+        self.detection = Some(lang);
+        self
+    }
+
+    /// 设置是否模拟支持流式输出的翻译器
+    ///
+    /// 开启后`translate_streaming`按空格把译文拆成多个片段产出
+    ///
+    /// # 参数
+    /// - `streaming`: 是否支持流式输出
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// 设置追加在每个译文末尾的后缀
+    ///
+    /// 用于模拟翻译服务更新后译文发生变化
+    ///
+    /// # 参数
+    /// - `suffix`: 后缀
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// 设置每次调用的模拟延迟
+    ///
+    /// # 参数
+    /// - `delay`: 返回结果前等待的时长
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// 设置暂时性失败
+    ///
+    /// 前`times`次调用返回`RequestFailed(503)`，之后恢复正常
+    ///
+    /// # 参数
+    /// - `times`: 失败的次数
+    pub fn with_transient_failures(self, times: usize) -> Self {
+        self.transient_failures.store(times, Ordering::SeqCst);
+        self
+    }
+
+    /// 设置模拟的批量上限
+    ///
+    /// `translate_vec`按上限分块调用，超出上限的调用返回`RequestFailed(413)`。
+    /// 与真实翻译器一样，单个文本超出`input_limit`时按`TranslateOptions::auto_split`
+    /// 拆分翻译或返回`TranslatorError::RequestTooLong`
+    ///
+    /// # 参数
+    /// - `max_batch_len`: 单次调用最多的文本数量，None表示不限
+    /// - `input_limit`: 单次调用最多的UTF-8字节数，None表示不限
+    pub fn with_limits(mut self, max_batch_len: Option<usize>, input_limit: Option<usize>) -> Self {
+        self.max_batch_len = max_batch_len;
+        self.input_limit = input_limit;
+        self
+    }
+
+    /// 设置模拟的支持语言
+    ///
+    /// 只影响`supported_languages`的返回值，翻译时不检查语言
+    ///
+    /// # 参数
+    /// - `languages`: 支持的语言
+    pub fn with_languages(mut self, languages: impl IntoIterator<Item = Language>) -> Self {
+        self.languages = Some(languages.into_iter().collect());
+        self
+    }
+
+    /// 生成模拟的译文：转换为大写后追加后缀
+    ///
+    /// # 参数
+    /// - `sent`: 发送给上游的文本
+    fn render(&self, sent: &str) -> String {
+        format!("{}{}", sent.to_uppercase(), self.suffix)
+    }
+
+    /// 获取上游调用次数
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// 获取`warm_up`的调用次数，预热不计入上游调用次数
+    pub fn warm_ups(&self) -> usize {
+        self.warm_ups.load(Ordering::SeqCst)
+    }
+
+    /// 获取所有上游调用收到的文本
+    ///
+    /// # 返回值
+    /// 每个元素对应一次调用，单文本调用记录为只有一个元素的数组
+    pub fn requests(&self) -> Vec<Vec<String>> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// 获取所有上游调用收到的文本总数
+    pub fn translated_items(&self) -> usize {
+        self.requests.lock().unwrap().iter().map(Vec::len).sum()
+    }
+
+    /// 记录一次调用并检查失败标记和批量上限
+    ///
+    /// # 参数
+    /// - `query`: 本次调用的文本
+    async fn record(&self, query: &[String]) -> Result<(), TranslatorError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.requests.lock().unwrap().push(query.to_vec());
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        let transient = self
+            .transient_failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if transient {
+            return Err(TranslatorError::RequestFailed(503));
+        }
+        let failed = query
+            .iter()
+            .any(|q| self.fail_markers.iter().any(|m| q.contains(m.as_str())));
+        if failed {
+            return Err(TranslatorError::RequestFailed(500));
+        }
+        let rejected = query
+            .iter()
+            .any(|q| self.reject_markers.iter().any(|m| q.contains(m.as_str())));
+        if rejected {
+            return Err(TranslatorError::ApiError(ApiError::Baidu {
+                code: "20003".to_string(),
+                message: "content rejected".to_string(),
+            }));
+        }
+        let exhausted = query
+            .iter()
+            .any(|q| self.quota_markers.iter().any(|m| q.contains(m.as_str())));
+        if exhausted {
+            return Err(TranslatorError::Quota {
+                message: "quota exhausted".to_string(),
+                completed: Vec::new(),
+            });
+        }
+        let bytes = query.iter().map(String::len).sum::<usize>();
+        if self.max_batch_len.is_some_and(|max| query.len() > max)
+            || self.input_limit.is_some_and(|limit| bytes > limit)
+        {
+            return Err(TranslatorError::RequestFailed(413));
+        }
+        Ok(())
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 与真实翻译器一样按`normalize_input`规范化原文、按`preserve_boundary_whitespace`去掉两端空白后再记录调用，
+    /// 超出`input_limit`时按`auto_split`拆分翻译
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 转换为大写的文本
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = &*preprocess::apply(query, options)?;
+        if let Some(limit) = self.input_limit {
+            if let Err(err) = long_text::check_byte_limit(query, limit) {
+                if !options.auto_split {
+                    return Err(err.into());
+                }
+                return long_text::translate_split(self, query, from, to, options, limit).await;
+            }
+        }
+        let sent = whitespace::trim(query, options);
+        self.record(&[sent.to_string()]).await?;
+        Ok(TranslationOutput {
+            text: whitespace::restore(query, self.render(sent), options),
+            lang: Some(*to),
+            detail: TranslationDetail {
+                request_id: options.resolve_request_id(),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for MockTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 模拟翻译器不调用远程API，返回true
+    fn local(&self) -> bool {
+        true
+    }
+
+    /// 单次批量请求最多的文本数量
+    fn max_batch_len(&self) -> Option<usize> {
+        self.max_batch_len
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.paid
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 只记录调用次数
+    async fn warm_up(&self) {
+        self.warm_ups.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 通过`with_detection`设置，未设置时返回None且不记录调用
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        let Some(lang) = self.detection else {
+            return Ok(None);
+        };
+        self.record(&[text.to_string()]).await?;
+        Ok(Some(lang))
+    }
+
+    /// 是否支持流式输出
+    fn supports_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    /// 流式翻译单个文本
+    ///
+    /// 支持流式输出时按空格拆分译文，每个片段包含其后的空格；否则整段产出
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 译文片段流
+    fn translate_streaming<'a>(
+        &'a self,
+        query: &'a str,
+        from: Option<Language>,
+        to: &'a Language,
+    ) -> TokenStream<'a> {
+        let tokens = async move {
+            let items = match self.translate(query, from, to).await {
+                Ok(output) if self.streaming => output
+                    .text
+                    .split_inclusive(' ')
+                    .map(|token| Ok(token.to_string()))
+                    .collect(),
+                Ok(output) => vec![Ok(output.text)],
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        };
+        Box::pin(stream::once(tokens).flatten())
+    }
+
+    /// 单次请求最多的UTF-8字节数
+    fn input_limit(&self) -> Option<usize> {
+        self.input_limit
+    }
+
+    /// 支持的语言
+    ///
+    /// 通过`with_languages`设置，未设置时不限
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.languages.clone()
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 转换为大写的文本
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 逐个转换为大写的文本数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 与真实翻译器一样按`normalize_input`规范化原文、按`preserve_boundary_whitespace`去掉两端空白后再记录调用，
+    /// 超出`input_limit`时按`auto_split`拆分翻译
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 转换为大写的文本
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("mock", query, from, to, options)
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 逐个转换为大写的文本数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result =
+            batch::translate_in_chunks(query, self.max_batch_len, self.input_limit, 0, |chunk| {
+                let options = &options;
+                async move {
+                    // 超出上限的单个文本单独成为一个分块，按单文本翻译拆分
+                    if let [text] = chunk {
+                        if self.input_limit.is_some_and(|limit| text.len() > limit) {
+                            let output =
+                                self.translate_with_options(text, from, to, options).await?;
+                            return Ok(TranslationListOutput {
+                                text: vec![output.text],
+                                lang: output.lang,
+                                detail: output.detail,
+                            });
+                        }
+                    }
+                    let sent = whitespace::trim_all(chunk, options);
+                    self.record(&sent).await?;
+                    let text = sent.iter().map(|q| self.render(q)).collect();
+                    Ok(TranslationListOutput {
+                        text: whitespace::restore_all(chunk, text, options),
+                        lang: Some(*to),
+                        detail: TranslationDetail {
+                            request_id: options.request_id.clone(),
+                            ..Default::default()
+                        },
+                    })
+                }
+            })
+            .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("mock", query, from, to, &options)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+
+    /// 测试模拟翻译器返回大写文本并记录调用
+    #[tokio::test]
+    async fn test_uppercase_and_counting() {
+        let translator = MockTranslator::new();
+        assert!(translator.local());
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+        let output = translator
+            .translate_vec(
+                &["a".to_string(), "b".to_string()],
+                None,
+                &Language::Chinese,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["A", "B"]);
+        assert_eq!(translator.calls(), 2);
+        assert_eq!(translator.translated_items(), 3);
+    }
+
+    /// 测试发送前去掉两端空白，译文逐字节还原原文的空白
+    #[tokio::test]
+    async fn test_preserve_boundary_whitespace() {
+        let translator = MockTranslator::new();
+        let output = translator
+            .translate("  hello \n", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "  HELLO \n");
+        let query = ["\thello world\r\n", "x", " \n"].map(String::from);
+        let output = translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["\tHELLO WORLD\r\n", "X", " \n"]);
+        assert_eq!(
+            translator.requests(),
+            vec![vec!["hello"], vec!["hello world", "x", " \n"]]
+        );
+
+        let translator = MockTranslator::new();
+        let options = TranslateOptions::new().with_preserve_boundary_whitespace(false);
+        let output = translator
+            .translate_with_options("  hello \n", None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "  HELLO \n");
+        assert_eq!(translator.requests(), vec![vec!["  hello \n"]]);
+    }
+
+    /// 测试开启原文规范化后发送给翻译服务的是规范化后的文本
+    #[tokio::test]
+    async fn test_normalize_input() {
+        use crate::fusion_translator::preprocess::InputNormalization;
+
+        let translator = MockTranslator::new();
+        let pasted = "\u{201c}co\u{ad}op\u{201d}\u{a0}\u{200b}x";
+        let output = translator
+            .translate(pasted, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, pasted.to_uppercase());
+
+        let options = TranslateOptions::new()
+            .with_input_normalization(InputNormalization::default().with_straighten_quotes(true));
+        let output = translator
+            .translate_with_options(pasted, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "\"COOP\" X");
+        let query = [pasted.to_string()];
+        translator
+            .translate_vec_with_options(&query, None, &Language::Chinese, &options)
+            .await
+            .unwrap();
+        let requests = translator.requests();
+        assert_eq!(requests[1], vec!["\"coop\" x"]);
+        assert_eq!(requests[2], requests[1]);
+    }
+
+    /// 测试超出批量上限的输入按上限分块调用
+    #[tokio::test]
+    async fn test_limits_split_batches() {
+        let translator = MockTranslator::new().with_limits(Some(2), Some(6));
+        let query = ["ab", "cd", "e", "fghijk", "l"].map(String::from);
+        let output = translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["AB", "CD", "E", "FGHIJK", "L"]);
+        assert_eq!(
+            translator.requests(),
+            vec![vec!["ab", "cd"], vec!["e"], vec!["fghijk"], vec!["l"]]
+        );
+    }
+
+    /// 随机批量形状下，每次上游调用都不超过上限且译文顺序不变
+    #[tokio::test]
+    async fn test_limits_random_shapes() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(638);
+        for _ in 0..200 {
+            let max_batch_len = rng.random_bool(0.8).then(|| rng.random_range(1..8));
+            let input_limit = rng.random_bool(0.8).then(|| rng.random_range(4..40));
+            let longest = input_limit.unwrap_or(40);
+            let query = (0..rng.random_range(0..30))
+                .map(|_| {
+                    let len = rng.random_range(0..=longest);
+                    (0..len)
+                        .map(|_| rng.random_range(b'a'..=b'z') as char)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let translator = MockTranslator::new().with_limits(max_batch_len, input_limit);
+            let output = translator
+                .translate_vec(&query, None, &Language::Chinese)
+                .await
+                .unwrap();
+            let expected = query.iter().map(|q| q.to_uppercase()).collect::<Vec<_>>();
+            assert_eq!(output.text, expected);
+            for request in translator.requests() {
+                assert!(max_batch_len.is_none_or(|max| request.len() <= max));
+                let bytes = request.iter().map(String::len).sum::<usize>();
+                assert!(input_limit.is_none_or(|limit| bytes <= limit));
+            }
+            assert_eq!(translator.requests().concat(), query);
+        }
+    }
+
+    /// 测试失败标记
+    #[tokio::test]
+    async fn test_fail_marker() {
+        let translator = MockTranslator::new().fail_on("boom");
+        assert!(translator
+            .translate("boom!", None, &Language::Chinese)
+            .await
+            .is_err());
+        assert_eq!(translator.calls(), 1);
+    }
+}
@@ -1,12 +1,274 @@
+// 标注`deny(clippy::indexing_slicing)`的模块解析任意输入（字幕、XML、JSONL、SSE、编码探测、断句等），
+// 下标访问和切片越界会在服务端引发panic，这些模块改用`get`等方法并显式处理越界；测试代码不受限制
+
+/// 自适应分块
+///
+/// 按观测到的请求延迟、限流和超时调整批量翻译的分块大小
+pub mod adaptive;
 mod alibaba_translator;
 /// 翻译模块
 ///
 /// 提供多种翻译服务的集成支持，包括百度翻译、有道翻译、阿里翻译、彩云翻译和MyMemory翻译
 /// 使用工厂模式统一管理不同翻译器的创建和使用
 pub mod async_translator;
+/// 自动选择翻译器
+///
+/// 按环境变量中的凭据和优先级组装带重试、限流、缓存和故障转移的默认翻译器
+pub mod auto;
 mod baidu_translator;
+/// 批量翻译辅助函数
+///
+/// 在`translate_vec`之上提供去重、分块和键值关联等批量翻译能力
+pub mod batch;
+mod bing_web_translator;
+/// 翻译结果缓存
+///
+/// 在进程内按LRU策略缓存译文的翻译器包装，可设置容量和有效期；过期条目重新翻译后译文变化时通知监听器
+pub mod cache;
 mod caiyun_translator;
+/// 能力矩阵
+///
+/// 汇总多个翻译引擎支持的语言和请求限制，按语言对筛选可用的引擎
+pub mod capability;
+/// 语言代码格式
+///
+/// 按BCP 47、小写下划线、大写ISO或翻译器自己的代码输出语言，并不区分格式地解析语言代码
+pub mod code_style;
+/// 多引擎比较
+///
+/// 使用多个翻译引擎并发翻译同一文本，返回每个引擎的译文、耗时和错误分类
+pub mod compare;
+/// 分层配置
+///
+/// 按显式参数 > 环境变量 > 配置文件 > 默认值的优先级合并翻译器配置，记录每个字段的来源并集中校验
+pub mod config;
+/// 配置热加载
+///
+/// 轮询配置文件，内容变化时重新解析并原子地替换翻译器，新配置无效时保留之前的配置
+pub mod config_watch;
+/// 语言检测
+///
+/// 综合按文字体系的本地检测和翻译器的远程检测判断文本语言，并给出置信度
+pub mod detect;
+/// 文本领域
+///
+/// 按关键词和启发式规则判断文本属于聊天、法律、代码还是一般文本
+pub mod domain;
+/// 表情符号
+///
+/// 翻译前按策略保留（替换为占位符后还原）、原样发送或删除表情符号和ASCII表情，
+/// 零宽连接符序列、肤色修饰符和国旗作为整体处理
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod emoji;
+/// 错误代码表
+///
+/// 各翻译服务错误代码的中英文说明、是否值得重试和错误分类，`ApiError`的分类和说明都来自这里
+pub mod error_codes;
+/// 错误上下文
+///
+/// 翻译失败时为错误附加翻译服务、操作、语言对、分块和重试次数，原文只在显式开启后记录
+pub mod error_context;
+/// A/B实验
+///
+/// 按文本哈希把请求稳定地分给对照组和实验组的翻译器，并记录各组的用量和耗时
+pub mod experiment;
+/// 故障转移
+///
+/// 按顺序尝试多个翻译器，前一个失败时换用下一个
+pub mod fallback;
+/// C语言接口
+///
+/// 以C ABI导出翻译器的创建、翻译和释放函数，头文件位于`include/fusion_translator.h`；
+/// 出错时返回负数错误码，可通过`ft_last_error_message`获取当前线程最近一次的错误信息
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod google_web_translator;
+/// 本地化文件翻译
+///
+/// 流式翻译顶层为数组的大型JSON文件，按字段路径只替换指定的字符串，内存占用与文件大小无关
+pub mod i18n_files;
+/// 输入长度检查
+///
+/// 按翻译服务的单位（字节、字符、UTF-16码元或词元）计算输入长度，给出不拆开字素簇的拆分位置
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod input_check;
+/// JSON Lines导出
+///
+/// 把批量翻译的结果（包括失败的片段）逐条写成机器翻译评测工具使用的JSONL，并可重新导入为翻译记忆库或检查点
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod jsonl;
+/// 语言代码
+///
+/// 提供`Language::iso639_3`/`Language::from_iso639_3`和`Language::default_script`，便于与使用ISO代码的其他库互通
+pub mod language_codes;
+/// 语言回退
+///
+/// 提供`Language::closest`和语言回退表，翻译器不支持请求的语言时可以使用最接近的替代语言
+pub mod language_fallback;
+/// 语言代码表注册中心
+///
+/// 在运行时按名称注册和查询各翻译器的语言代码表，支持`Language::to_vendor`/`Language::from_vendor`。
+/// 希伯来语（`he`/`iw`）、爪哇语（`jv`/`jw`）、菲律宾语（`fil`/`tl`）和挪威语（`no`/`nb`）的新旧代码都能反查为同一语言
+pub mod language_map;
+/// 长度估算
+///
+/// 提供`LengthEstimator`（字符数、字节数，开启`tiktoken`特性后可按BPE词元数），按上下文窗口减去响应预留的预算拆分提示词
+pub mod length_estimator;
+/// 逐行翻译流
+///
+/// 把`AsyncBufRead`包装为按批次翻译的`Stream`，适用于日志等逐行处理的场景
+pub mod line_stream;
+/// 数字和日期保护
+///
+/// 把数字、百分数、带单位的数量、ISO 8601日期和时间替换为占位符后再翻译，译文中原样还原或按目标语言重新格式化
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod literals;
+/// 长文本翻译
+///
+/// 按段落拆分文本，逐段检测源语言后分组翻译，适用于多种语言混排的文档
+pub mod long_text;
+pub mod mock_translator;
 mod mymemory_translator;
+/// 语言协商
+///
+/// 按`Accept-Language`请求头选择目标语言，并为翻译结果计算`ETag`、处理`If-None-Match`
+pub mod negotiate;
+/// 礼貌请求
+///
+/// 免密钥翻译器的请求间隔、随机延迟和被拒绝（429/403）后的指数冷却，冷却状态可以保存到文件
+pub mod politeness;
+/// 译文后处理
+///
+/// 统一清理HTML实体、字面转义序列、多余包裹引号，并进行Unicode NFC规范化
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod postprocess;
+/// 常用类型
+///
+/// `use fusion_translator::fusion_translator::prelude::*;`即可引入翻译器特征、语言、选项、错误和工厂
+pub mod prelude;
+/// 原文预处理
+///
+/// 发送给翻译服务前清理原文：还原Windows-1252标点、替换不换行空格、去除软连字符和零宽字符、转换弯引号
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod preprocess;
+/// 译文来历
+///
+/// 缓存、重试、故障转移和拆分等组合环节在`TranslationDetail::provenance`中追加的事件
+pub mod provenance;
+/// Python接口
+///
+/// 以PyO3导出`Translator`类、`supported_languages`函数和按错误种类区分的异常类，
+/// 通过maturin构建为`fusion_translator`扩展模块
+#[cfg(feature = "python")]
+pub mod python;
+/// 译文质量检查
+///
+/// 把译文回译为源语言并与原文比较相似度，用于发现明显偏离原意的机器翻译
+pub mod quality;
+/// 翻译队列
+///
+/// 生产者逐个提交翻译请求，工作任务把相同引擎和语言对的请求合并为批次翻译后分别返回结果
+pub mod queue;
+/// 请求限流
+///
+/// 令牌桶限流翻译器，相同服务和凭据的实例可在进程内共享同一个令牌桶
+pub mod rate_limit;
+/// 翻译请求
+///
+/// 以构建器组合原文、语言和调用选项，通过`ExecuteRequest::execute`统一执行单个和批量翻译
+pub mod request;
+/// 失败重试
+///
+/// 遇到网络错误、限流等暂时性错误时按指数退避重试的翻译器包装
+pub mod retry;
+mod reverso_translator;
+/// 按领域路由
+///
+/// 按文本领域选择优先使用的翻译引擎，失败时按顺序换用其他引擎
+pub mod routing;
+/// 后台任务
+///
+/// `TaskGuard`持有crate内启动的所有后台任务，所有者被丢弃时中止任务，避免任务泄漏
+pub mod runtime;
+/// 定时翻译
+///
+/// 只在允许的时间窗口内（如闲时价格时段）调用翻译服务，窗口之外的请求排队或拒绝，额度用完后等到重置时间
+pub mod scheduled;
+/// 响应结构检查
+///
+/// 比较两个JSON值的字段名和值类型，用于发现翻译服务响应格式的变化
+pub mod schema_check;
+/// 句子切分
+///
+/// 按语言规则拆分句子，正确处理中日韩标点、缩写、小数和引号，可返回字节范围以还原原文
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod segment;
+mod serde_util;
+/// 翻译会话
+///
+/// 在一次逻辑操作内记录已完成的翻译，重试和复合翻译流程不会重复请求相同的文本
+pub mod session;
+/// 正常关闭
+///
+/// 缓冲请求或结果的组件共用的关闭接口：停止接受新的工作，处理完或保存已接受的工作
+pub mod shutdown;
+/// 相似度
+///
+/// 与翻译服务无关的文本相似度函数（字符n元组、词Jaccard、归一化编辑距离），按字素簇比较，
+/// 供回译检查和翻译记忆库模糊匹配使用
+pub mod similarity;
+/// 服务器发送事件（SSE）
+///
+/// 增量解析`text/event-stream`响应，并把OpenAI兼容接口的流式补全转换为译文片段流
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod sse;
+/// 字幕翻译
+///
+/// 解析和生成SRT字幕，按每行字符数和行数约束重新排版译文，中日文换行遵守避头尾规则
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod subtitle;
+/// 术语提取
+///
+/// 按原文和译文词组在句对中的共同出现次数，从翻译记忆库中提取候选术语对并导出为术语表
+pub mod terminology;
+/// 测试辅助工具
+///
+/// 只在测试中编译，供各翻译器的测试共用
+#[cfg(test)]
+pub(crate) mod test_support;
+/// 文本编码检测与转换
+///
+/// 把GBK、Big5、Shift-JIS等编码的输入解码为UTF-8，并可按原始编码写回
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod text_encoding;
+/// 翻译记忆库
+///
+/// 提供可与TMX 1.4文件互相转换的`TranslationMemory`，以及优先查询记忆库的`TmTranslator`
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod tmx;
+pub mod translate_options;
 pub mod translator_error;
 pub mod translator_factory;
+/// 翻译器实例选项
+///
+/// 按翻译器设置默认的User-Agent和Referer等请求头，可通过`TranslatorOptions`覆盖或追加
+pub mod translator_options;
+mod transmart_translator;
+/// HTTP传输层
+///
+/// 翻译器构造与HTTP客户端无关的请求，通过可替换的`HttpTransport`发送，默认使用`reqwest`
+pub mod transport;
+/// 未翻译检测
+///
+/// 检查翻译服务是否原样返回了原文，并按选项标记或报错
+pub mod untranslated;
+/// 用量记录
+///
+/// 按名称累计调用次数、文本量、失败次数和耗时
+pub mod usage;
+/// 空白保留
+///
+/// 发送前去掉文本两端的空白，并在译文中还原原文的前导、末尾空白和按列对齐的连续空格
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub mod whitespace;
 mod youdao_translator;
@@ -1,9 +1,40 @@
 use crate::fusion_translator::async_translator::{
-    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    AlternativeTranslation, AsyncTranslator, Language, TranslationDetail, TranslationListOutput,
+    TranslationOutput,
 };
+use crate::fusion_translator::batch::empty_batch;
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::rate_limit::{LimiterKey, RateLimiter};
+use crate::fusion_translator::serde_util::lenient_f32;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
 use crate::fusion_translator::translator_error::TranslatorError;
-use reqwest::{header::REFERER, Client};
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::{TranslatorOptions, DEFAULT_MAX_URL_LEN};
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
+use reqwest::{StatusCode, Url};
+use serde::Deserialize;
 use serde_json::Value;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// 每次请求前获取令牌的共享限流器的每秒请求数
+///
+/// MyMemory按IP限制免费接口的请求频率，同一进程内的所有实例共享一个令牌桶
+const REQUEST_QPS: f64 = 5.0;
+
+/// 每日免费额度用完时译文的开头
+const QUOTA_WARNING: &str = "MYMEMORY WARNING: YOU USED ALL AVAILABLE FREE TRANSLATIONS";
+
+/// 逐行翻译多行文本时最多同时进行的请求数
+const LINE_CONCURRENCY: usize = 4;
 
 /// MyMemory翻译器实现
 ///
@@ -14,8 +45,18 @@ pub struct MyMemoryTranslator {
     input_limit: u32,
     /// API请求地址
     host: String,
-    /// HTTP客户端
-    client: Client,
+    /// GET请求允许的最大URL长度
+    max_url_len: usize,
+    /// 是否返回机器翻译结果（`mt`参数），None表示使用服务端默认值
+    machine_translation: Option<bool>,
+    /// 是否只返回私有翻译记忆（`onlyprivate`参数），None表示使用服务端默认值
+    only_private: Option<bool>,
+    /// 每次请求前获取令牌的限流器
+    limiter: Arc<RateLimiter>,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
 }
 
 /// 默认实现
@@ -25,26 +66,8 @@ impl Default for MyMemoryTranslator {
     }
 }
 
-/// 检查输入文本长度是否超出限制
-///
-/// # 参数
-/// - `query`: 待检查的文本
-/// - `input_limit`: 最大长度限制
-///
-/// # 返回值
-/// - `Ok(())` - 长度在限制范围内
-/// - `Err(TranslatorError::RequestToLong)` - 长度超出限制
-pub fn input_limit_checker(query: &str, input_limit: u32) -> Result<(), TranslatorError> {
-    if query.len() > input_limit as usize {
-        return Err(TranslatorError::RequestToLong(
-            query.len() as u32,
-            input_limit,
-        ));
-    }
-    Ok(())
-}
-
-#[async_trait::async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 impl AsyncTranslator for MyMemoryTranslator {
     /// 判断是否为本地翻译器
     ///
@@ -53,6 +76,27 @@ impl AsyncTranslator for MyMemoryTranslator {
         false
     }
 
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "mymemory", &self.host).await
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(Language::to_mymemory))
+    }
+
+    /// 单次请求最多的文本数量
+    ///
+    /// 每次请求只发送一个文本
+    fn max_batch_len(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    /// 单次请求最多的UTF-8字节数
+    fn input_limit(&self) -> Option<usize> {
+        Some(self.input_limit as usize)
+    }
+
     /// 翻译单个文本
     ///
     /// # 参数
@@ -68,79 +112,236 @@ impl AsyncTranslator for MyMemoryTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationOutput> {
-        input_limit_checker(query, self.input_limit)?;
-        let _from_orig = from;
-        let _from = match _from_orig {
-            Some(lang) => lang
-                .to_mymemory()
-                .ok_or(TranslatorError::UnknownLanguage(lang))?,
-            None => "Autodetect",
-        };
-
-        let url = format!(
-            "{}?q={}&langpair={}|{}",
-            self.host,
-            query,
-            _from,
-            to.to_mymemory()
-                .ok_or(TranslatorError::UnknownLanguage(*to))?
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .header(REFERER, "https://mymemory.translated.net")
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(TranslatorError::RequestFailed(response.status().as_u16()).into());
-        }
-
-        let resp: Value = response.json().await?;
-        let resp = &resp["responseData"];
-        let _lang = resp["detectedLanguage"].to_string();
-        let mut text = resp["translatedText"].to_string();
-
-        if text == "null" {
-            return Err(TranslatorError::NoResponse.into());
-        }
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
 
-        if text.starts_with('"') && text.ends_with('"') {
-            text = text[1..text.len() - 1].to_string();
-        }
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
 
-        Ok(TranslationOutput {
-            text,
-            lang: Some(*to),
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递。
+    /// 文本通过GET查询参数传递，换行会丢失，因此多行文本按行拆分后分别请求；
+    /// 编码后的URL超过`with_max_url_len`设置的长度时改为POST表单。
+    /// 每日免费额度用完时返回`TranslatorError::Quota`
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("mymemory", query, from, to, options)
         })
     }
 
-    /// 翻译多个文本
+    /// 按指定选项翻译多个文本
+    ///
+    /// 每个文本单独请求，依次通过共享的限流器。
+    /// 某个请求提示每日免费额度用完时不再发送后续请求，
+    /// 返回的`TranslatorError::Quota`中包含此前已完成的译文
     ///
     /// # 参数
     /// - `query`: 待翻译的文本数组
     /// - `from`: 源语言，None表示自动检测
     /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
     ///
     /// # 返回值
     /// 翻译结果数组
-    async fn translate_vec(
+    async fn translate_vec_with_options(
         &self,
         query: &[String],
         from: Option<Language>,
         to: &Language,
+        options: &TranslateOptions,
     ) -> anyhow::Result<TranslationListOutput> {
-        let t = self.translate(&query.join("_._._"), from, to).await?;
-        Ok(TranslationListOutput {
-            text: t
-                .text
-                .split("_._._")
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-            lang: t.lang,
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        if query.is_empty() {
+            return Ok(empty_batch(&options));
+        }
+        let mut result = TranslationListOutput::default();
+        for (i, q) in query.iter().enumerate() {
+            let output = match self.translate_preprocessed(q, from, to, &options).await {
+                Ok(output) => output,
+                Err(e) => {
+                    let err = match e.downcast::<TranslatorError>() {
+                        Ok(TranslatorError::Quota { message, .. }) => TranslatorError::Quota {
+                            message,
+                            completed: result.text,
+                        }
+                        .into(),
+                        Ok(TranslatorError::Cancelled(_)) => TranslatorError::Cancelled(i).into(),
+                        Ok(e) => e.into(),
+                        Err(e) => e,
+                    };
+                    // 还原为TranslatorError时会丢掉单个文本的上下文，改为附加整批的上下文
+                    return error_context::with_query_context(Err(err), || {
+                        QueryContext::batch("mymemory", query, from, to, &options)
+                    });
+                }
+            };
+            let possibly_untranslated = output.detail.possibly_untranslated;
+            if i == 0 {
+                result.lang = output.lang;
+                result.detail = output.detail;
+            }
+            result.detail.possibly_untranslated |= possibly_untranslated;
+            result.text.push(output.text);
+        }
+        Ok(result)
+    }
+}
+
+/// 翻译记忆库匹配项
+///
+/// 对应MyMemory响应中`matches`数组的单个元素
+#[derive(Debug, Deserialize)]
+struct MyMemoryMatch {
+    /// 匹配到的译文
+    translation: String,
+    /// 译文质量评分，MyMemory可能返回字符串或数字
+    #[serde(default, deserialize_with = "lenient_f32")]
+    quality: Option<f32>,
+    /// 与查询文本的匹配度，范围0到1，同样可能是字符串
+    #[serde(default, rename = "match", deserialize_with = "lenient_f32")]
+    score: Option<f32>,
+    /// 译文贡献者
+    #[serde(default, rename = "created-by")]
+    created_by: Option<String>,
+}
+
+/// 逐个请求限流的共享键
+///
+/// 与`TranslatorConfig::limiter_key`按调用限流的令牌桶分开，
+/// 避免批量翻译时同一次调用重复计入，也不会沿用配置文件中的`rate_limit_qps`
+fn request_limiter_key() -> LimiterKey {
+    LimiterKey::new("mymemory", &["requests"])
+}
+
+/// 检查响应是否为每日免费额度用完的提示
+///
+/// MyMemory在额度用完时仍返回成功响应，译文为警告文本，并可能带有`quotaFinished`字段
+///
+/// # 参数
+/// - `resp`: MyMemory返回的JSON响应
+///
+/// # 返回值
+/// 提示文本，不是额度提示时为None
+fn quota_warning(resp: &Value) -> Option<String> {
+    let text = resp["responseData"]["translatedText"].as_str();
+    if resp["quotaFinished"].as_bool() == Some(true)
+        || text.is_some_and(|text| text.starts_with(QUOTA_WARNING))
+    {
+        Some(text.unwrap_or("quota finished").to_string())
+    } else {
+        None
+    }
+}
+
+/// 解析MyMemory响应
+///
+/// 提取主译文，并将`matches`中的其他译文整理为候选译文
+///
+/// # 参数
+/// - `resp`: MyMemory返回的JSON响应
+///
+/// # 返回值
+/// 翻译结果，`lang`字段由调用方填充；额度用完时返回`TranslatorError::Quota`
+fn parse_response(resp: &Value) -> Result<TranslationOutput, TranslatorError> {
+    if let Some(message) = quota_warning(resp) {
+        return Err(TranslatorError::Quota {
+            message,
+            completed: Vec::new(),
+        });
+    }
+    let text = resp["responseData"]["translatedText"]
+        .as_str()
+        .ok_or(TranslatorError::NoResponse)?
+        .to_string();
+    let alternatives = parse_alternatives(&resp["matches"], &text);
+    Ok(TranslationOutput {
+        text,
+        lang: None,
+        detail: TranslationDetail {
+            alternatives,
+            ..Default::default()
+        },
+    })
+}
+
+/// 从`matches`数组提取候选译文
+///
+/// 按匹配度降序（匹配度相同时按质量评分降序）排列，
+/// 并去除与主译文或彼此重复的译文
+///
+/// # 参数
+/// - `matches`: 响应中的`matches`字段
+/// - `primary`: 主译文
+///
+/// # 返回值
+/// 候选译文列表
+fn parse_alternatives(matches: &Value, primary: &str) -> Vec<AlternativeTranslation> {
+    let mut matches = matches
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| MyMemoryMatch::deserialize(item).ok())
+                .collect::<Vec<_>>()
         })
+        .unwrap_or_default();
+    matches.sort_by(|a, b| {
+        let key = |m: &MyMemoryMatch| (m.score.unwrap_or(0.0), m.quality.unwrap_or(0.0));
+        key(b)
+            .partial_cmp(&key(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut seen = vec![primary.trim().to_string()];
+    let mut alternatives = Vec::new();
+    for m in matches {
+        let text = m.translation.trim();
+        if text.is_empty() || seen.iter().any(|s| s == text) {
+            continue;
+        }
+        seen.push(text.to_string());
+        alternatives.push(AlternativeTranslation {
+            text: text.to_string(),
+            score: m.score,
+            source: m.created_by.filter(|s| !s.is_empty()),
+        });
     }
+    alternatives
 }
 
 impl MyMemoryTranslator {
@@ -151,17 +352,271 @@ impl MyMemoryTranslator {
     #[allow(dead_code)]
     pub fn new() -> Self {
         MyMemoryTranslator {
-            client: Client::new(),
+            transport: TranslatorOptions::default().build_transport(TranslatorType::MyMemory),
+            politeness: Some(Politeness::shared("mymemory", PolitenessConfig::default())),
             input_limit: 500,
             host: "https://api.mymemory.translated.net/get".to_string(),
+            max_url_len: DEFAULT_MAX_URL_LEN,
+            machine_translation: None,
+            only_private: None,
+            limiter: RateLimiter::shared(request_limiter_key(), REQUEST_QPS),
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::MyMemory);
+        self.politeness = options.politeness.resolve("mymemory");
+        self.with_max_url_len(options.max_url_len)
+    }
+
+    /// 设置GET请求允许的最大URL长度
+    ///
+    /// 编码后的URL超过该长度时改为向同一地址POST表单，默认为`DEFAULT_MAX_URL_LEN`
+    ///
+    /// # 参数
+    /// - `max_url_len`: 编码后URL的最大字节数
+    pub fn with_max_url_len(mut self, max_url_len: usize) -> Self {
+        self.max_url_len = max_url_len;
+        self
+    }
+
+    /// 设置是否返回机器翻译结果
+    ///
+    /// 对应`mt`参数，关闭后只返回翻译记忆库中的人工译文；默认不发送，由服务端决定
+    ///
+    /// # 参数
+    /// - `enabled`: 是否返回机器翻译结果
+    pub fn with_machine_translation(mut self, enabled: bool) -> Self {
+        self.machine_translation = Some(enabled);
+        self
+    }
+
+    /// 设置是否只返回私有翻译记忆
+    ///
+    /// 对应`onlyprivate`参数，只在请求带有私有翻译记忆的密钥时生效；默认不发送
+    ///
+    /// # 参数
+    /// - `enabled`: 是否只返回私有翻译记忆
+    pub fn with_only_private(mut self, enabled: bool) -> Self {
+        self.only_private = Some(enabled);
+        self
+    }
+
+    /// 构造翻译请求
+    ///
+    /// 参数编码到URL中发送GET请求，URL过长时改为POST表单，MyMemory的`/get`接口同时接受两种方式
+    ///
+    /// # 参数
+    /// - `params`: 请求参数
+    fn request(&self, params: &[(&str, &str)]) -> anyhow::Result<HttpRequest> {
+        let url = Url::parse_with_params(&self.host, params)?;
+        if url.as_str().len() > self.max_url_len {
+            HttpRequest::post(&self.host).form(params)
+        } else {
+            Ok(HttpRequest::get(url))
         }
     }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 预处理原文后由`translate_preprocessed`翻译
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = preprocess::apply(query, options)?;
+        self.translate_preprocessed(&query, from, to, options).await
+    }
+
+    /// 翻译已经过`preprocess::apply`处理的单个文本
+    ///
+    /// 批量翻译在分块前已预处理每个文本，直接调用此方法，不再重复预处理。
+    /// 请求ID通过`X-Request-Id`请求头传递。
+    /// 文本通过GET查询参数传递，换行会丢失，因此多行文本按行拆分后分别请求；
+    /// 编码后的URL超过`with_max_url_len`设置的长度时改为POST表单。
+    /// 每日免费额度用完时返回`TranslatorError::Quota`
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_preprocessed(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        if query.trim().contains('\n') {
+            return long_text::translate_lines(self, query, from, to, options, LINE_CONCURRENCY)
+                .await;
+        }
+        if let Err(err) = long_text::check_byte_limit(query, self.input_limit as usize) {
+            if !options.auto_split {
+                return Err(err.into());
+            }
+            let max_bytes = self.input_limit as usize;
+            return long_text::translate_split(self, query, from, to, options, max_bytes).await;
+        }
+        let mut languages = LanguageResolver::new(options, Language::to_mymemory);
+        let _from = match from {
+            Some(lang) => languages.resolve(lang)?,
+            None => "Autodetect",
+        };
+
+        let langpair = format!("{}|{}", _from, languages.resolve(*to)?);
+        let mut params = vec![
+            ("q", whitespace::trim(query, options)),
+            ("langpair", &langpair),
+        ];
+        let flag = |enabled: bool| if enabled { "1" } else { "0" };
+        if let Some(enabled) = self.machine_translation {
+            params.push(("mt", flag(enabled)));
+        }
+        if let Some(enabled) = self.only_private {
+            params.push(("onlyprivate", flag(enabled)));
+        }
+        let request = self.request(&params)?;
+
+        let request_id = options.resolve_request_id();
+        let span = tracing::debug_span!("translate", backend = "mymemory", request_id = request_id);
+        let resp: Value = options
+            .run_cancellable(
+                0,
+                async {
+                    self.limiter.acquire_with(options.priority).await;
+                    let request = with_request_id_header(request, request_id.as_deref());
+                    let response =
+                        politeness::send(self.politeness.as_deref(), &*self.transport, request)
+                            .await?;
+                    if response.is_success() {
+                        return response.json();
+                    }
+                    // 额度用完的提示也可能以429状态码返回
+                    if response.status == StatusCode::TOO_MANY_REQUESTS.as_u16() {
+                        if let Ok(body) = response.json::<Value>() {
+                            if quota_warning(&body).is_some() {
+                                return Ok(body);
+                            }
+                        }
+                    }
+                    Err(TranslatorError::RequestFailed(response.status).into())
+                }
+                .instrument(span),
+            )
+            .await?;
+        let mut output = parse_response(&resp)?;
+        output.text = postprocess::apply(query, output.text, options);
+        output.detail.possibly_untranslated =
+            untranslated::check(query, &output.text, from, to, options)?;
+        output.lang = Some(*to);
+        output.detail.request_id = request_id;
+        output.detail.substitutions = languages.into_substitutions();
+        Ok(output)
+    }
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl MyMemoryTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, Value)> {
+        let url = format!("{}?q=Hello, world&langpair=en|zh-CN", self.host);
+        Ok((
+            "success.json",
+            self.transport
+                .execute(HttpRequest::get(url))
+                .await?
+                .json()?,
+        ))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "success.json" => parse_response(&serde_json::from_str(body)?)?,
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    };
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::fusion_translator::async_translator::AsyncTranslator;
-    use crate::fusion_translator::mymemory_translator::MyMemoryTranslator;
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mymemory_translator::{parse_response, MyMemoryTranslator};
+    use crate::fusion_translator::rate_limit::RateLimiter;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::test_support::http;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::transport::HttpMethod;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// 每日免费额度用完时的响应
+    const QUOTA_RESPONSE: &str = r#"{
+        "responseData": {
+            "translatedText": "MYMEMORY WARNING: YOU USED ALL AVAILABLE FREE TRANSLATIONS FOR TODAY. NEXT AVAILABLE IN  10 HOURS 20 MINUTES 29 SECONDS",
+            "match": 0
+        },
+        "quotaFinished": true,
+        "responseStatus": 429,
+        "matches": []
+    }"#;
+
+    /// 创建请求本地服务器的翻译器，不受共享限流器的速率限制
+    ///
+    /// # 参数
+    /// - `url`: 本地服务器地址
+    fn local(url: &str) -> MyMemoryTranslator {
+        let mut translator = MyMemoryTranslator::new();
+        translator.host = format!("{}/get", url);
+        translator.politeness = None;
+        translator.limiter = Arc::new(RateLimiter::new(1000.0));
+        translator
+    }
+
+    /// 生成把原文转为大写的成功响应
+    ///
+    /// # 参数
+    /// - `request`: 收到的请求
+    fn echo(request: &http::Request) -> (u16, String) {
+        let q = request.param("q").unwrap_or_default();
+        let body = serde_json::json!({
+            "responseData": {"translatedText": q.to_uppercase()},
+            "matches": [],
+        });
+        (200, body.to_string())
+    }
 
     /// 测试创建翻译器实例
     #[tokio::test]
@@ -170,19 +625,6 @@ mod tests {
         assert!(!translator.local());
     }
 
-    /// 测试输入长度检查
-    #[test]
-    fn test_input_limit_checker() {
-        use crate::fusion_translator::mymemory_translator::input_limit_checker;
-
-        let result = input_limit_checker("short text", 500);
-        assert!(result.is_ok());
-
-        let long_text = "a".repeat(600);
-        let result = input_limit_checker(&long_text, 500);
-        assert!(result.is_err());
-    }
-
     /// 测试默认实现
     #[test]
     fn test_default() {
@@ -197,4 +639,279 @@ mod tests {
         assert_eq!(translator.input_limit, 500);
         assert!(translator.host.contains("mymemory.translated.net"));
     }
+
+    /// 测试URL过长时改用POST表单，参数中的特殊字符被正确编码
+    #[test]
+    fn test_request_falls_back_to_post() {
+        let translator = MyMemoryTranslator::new().with_max_url_len(120);
+        let request = translator
+            .request(&[("q", "Tom & Jerry #1"), ("langpair", "en|it")])
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::Get);
+        assert_eq!(
+            reqwest::Url::parse(&request.url).unwrap().query(),
+            Some("q=Tom+%26+Jerry+%231&langpair=en%7Cit")
+        );
+
+        let long = "中文".repeat(20);
+        let request = translator
+            .request(&[("q", &long), ("langpair", "zh-CN|en")])
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.url, translator.host);
+        let body = &request.body;
+        let body = reqwest::Url::parse(&format!(
+            "http://form/?{}",
+            std::str::from_utf8(body).unwrap()
+        ))
+        .unwrap();
+        let params = body.query_pairs().collect::<Vec<_>>();
+        assert_eq!(params[0].1, long);
+        assert_eq!(params[1].1, "zh-CN|en");
+    }
+
+    /// 测试URL过长改用POST表单时，标准语料上的结果与GET请求相同
+    #[tokio::test]
+    async fn test_post_matches_get() {
+        use crate::fusion_translator::test_support::differential;
+
+        let url = http::serve(|request| {
+            assert_eq!(request.path, "/get");
+            echo(request)
+        });
+        let local = |max_url_len| {
+            let mut translator = MyMemoryTranslator::new().with_max_url_len(max_url_len);
+            translator.host = format!("{}/get", url);
+            translator.politeness = None;
+            translator.limiter = Arc::new(RateLimiter::new(1000.0));
+            Arc::new(translator) as Arc<dyn AsyncTranslator>
+        };
+        differential::assert_equivalent(&local(usize::MAX), &local(0)).await;
+    }
+
+    /// 测试任意字节经过预处理后总能构造出合法的请求，或返回类型化的错误
+    #[test]
+    fn test_byte_soup_requests() {
+        use crate::fusion_translator::preprocess;
+        use crate::fusion_translator::translate_options::TranslateOptions;
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let translator = MyMemoryTranslator::new().with_max_url_len(200);
+        let mut rng = StdRng::seed_from_u64(660);
+        let (mut gets, mut posts) = (0, 0);
+        for _ in 0..500 {
+            let len = rng.random_range(0..120);
+            let bytes = (0..len).map(|_| rng.random()).collect::<Vec<u8>>();
+            let text = String::from_utf8_lossy(&bytes);
+            let query = match preprocess::apply(&text, &TranslateOptions::default()) {
+                Ok(query) => query,
+                Err(TranslatorError::NulCharacter { .. }) => continue,
+                Err(e) => panic!("{:?}: {:?}", bytes, e),
+            };
+            let request = translator
+                .request(&[("q", &query), ("langpair", "en|it")])
+                .unwrap();
+            if request.method == HttpMethod::Get {
+                gets += 1;
+                assert!(request.url.len() <= 200);
+            } else {
+                posts += 1;
+            }
+        }
+        assert!(gets > 0 && posts > 0, "{} {}", gets, posts);
+    }
+
+    /// 测试解析带候选译文的响应
+    ///
+    /// `quality`同时出现字符串和数字两种形式，结果按匹配度排序并去除与主译文重复的项
+    #[test]
+    fn test_parse_response_with_matches() {
+        use crate::fusion_translator::mymemory_translator::parse_response;
+
+        let resp: serde_json::Value = serde_json::from_str(
+            r#"{
+                "responseData": {"translatedText": "Ciao mondo", "match": 1},
+                "matches": [
+                    {"translation": "Salve mondo", "quality": "70", "match": 0.85, "created-by": "MateCat"},
+                    {"translation": "Ciao mondo", "quality": 74, "match": 1, "created-by": "MT!"},
+                    {"translation": "Buongiorno mondo", "quality": 80, "match": 0.9, "created-by": ""},
+                    {"translation": "Salve mondo", "quality": "50", "match": 0.5, "created-by": "Other"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let output = parse_response(&resp).unwrap();
+        assert_eq!(output.text, "Ciao mondo");
+
+        let alternatives = &output.detail.alternatives;
+        assert_eq!(alternatives.len(), 2);
+        assert_eq!(alternatives[0].text, "Buongiorno mondo");
+        assert_eq!(alternatives[0].score, Some(0.9));
+        assert_eq!(alternatives[0].source, None);
+        assert_eq!(alternatives[1].text, "Salve mondo");
+        assert_eq!(alternatives[1].source.as_deref(), Some("MateCat"));
+    }
+
+    /// 测试宽松解析质量评分
+    ///
+    /// 数字、字符串和无法解析的值都不应导致解析失败
+    #[test]
+    fn test_parse_match_quality() {
+        use crate::fusion_translator::mymemory_translator::MyMemoryMatch;
+
+        let number: MyMemoryMatch =
+            serde_json::from_str(r#"{"translation": "a", "quality": 74}"#).unwrap();
+        assert_eq!(number.quality, Some(74.0));
+        let string: MyMemoryMatch =
+            serde_json::from_str(r#"{"translation": "a", "quality": " 74 "}"#).unwrap();
+        assert_eq!(string.quality, Some(74.0));
+        let invalid: MyMemoryMatch =
+            serde_json::from_str(r#"{"translation": "a", "quality": "n/a"}"#).unwrap();
+        assert_eq!(invalid.quality, None);
+        let missing: MyMemoryMatch = serde_json::from_str(r#"{"translation": "a"}"#).unwrap();
+        assert_eq!(missing.quality, None);
+        let score: MyMemoryMatch =
+            serde_json::from_str(r#"{"translation": "a", "match": "0.85"}"#).unwrap();
+        assert_eq!(score.score, Some(0.85));
+    }
+
+    /// 测试缺少译文的响应
+    #[test]
+    fn test_parse_response_without_text() {
+        use crate::fusion_translator::mymemory_translator::parse_response;
+
+        let resp = serde_json::json!({"responseData": {"translatedText": null}});
+        assert!(parse_response(&resp).is_err());
+        let resp = serde_json::json!({"responseData": {"translatedText": "x"}});
+        assert!(parse_response(&resp)
+            .unwrap()
+            .detail
+            .alternatives
+            .is_empty());
+    }
+
+    /// 测试批量翻译逐个请求，第7个请求提示额度用完后不再发送后续请求
+    ///
+    /// 返回的`TranslatorError::Quota`包含此前完成的6条译文
+    #[tokio::test]
+    async fn test_quota_stops_batch() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let url = http::serve(move |request| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 6 {
+                return (200, QUOTA_RESPONSE.to_string());
+            }
+            echo(request)
+        });
+        let query = (1..=10).map(|i| format!("item {}", i)).collect::<Vec<_>>();
+        let err = local(&url)
+            .translate_vec(&query, Some(Language::English), &Language::Italian)
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+        match err.downcast_ref::<TranslatorError>() {
+            Some(TranslatorError::Quota { message, completed }) => {
+                assert!(message.starts_with("MYMEMORY WARNING"));
+                assert_eq!(completed.len(), 6);
+                assert_eq!(completed[0], "ITEM 1");
+                assert_eq!(completed[5], "ITEM 6");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let output = local(&url)
+            .translate_vec(&query[..3], Some(Language::English), &Language::Italian)
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["ITEM 1", "ITEM 2", "ITEM 3"]);
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+    }
+
+    /// 测试额度提示以429状态码返回时同样识别为`TranslatorError::Quota`，其他429仍为请求失败
+    #[tokio::test]
+    async fn test_quota_with_429_status() {
+        let url = http::serve(|request| match request.param("q").as_deref() {
+            Some("quota") => (429, QUOTA_RESPONSE.to_string()),
+            _ => (429, "{}".to_string()),
+        });
+        let translator = local(&url);
+        let err = translator
+            .translate("quota", Some(Language::English), &Language::Italian)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Quota { completed, .. }) if completed.is_empty()
+        ));
+        let err = translator
+            .translate("busy", Some(Language::English), &Language::Italian)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RequestFailed(429))
+        ));
+    }
+
+    /// 测试`mt`和`onlyprivate`参数只在设置后发送
+    #[tokio::test]
+    async fn test_filter_flags() {
+        let params = Arc::new(Mutex::new(Vec::new()));
+        let recorded = params.clone();
+        let url = http::serve(move |request| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((request.param("mt"), request.param("onlyprivate")));
+            echo(request)
+        });
+        let translate = |translator: MyMemoryTranslator| async move {
+            translator
+                .translate("hello", Some(Language::English), &Language::Italian)
+                .await
+                .unwrap()
+        };
+        translate(local(&url)).await;
+        translate(local(&url).with_machine_translation(false)).await;
+        translate(
+            local(&url)
+                .with_machine_translation(true)
+                .with_only_private(true),
+        )
+        .await;
+        let one = |v: &str| Some(v.to_string());
+        assert_eq!(
+            *params.lock().unwrap(),
+            [(None, None), (one("0"), None), (one("1"), one("1")),]
+        );
+    }
+
+    /// 测试批量翻译形状
+    ///
+    /// 使用真实API运行标准的批量形状检查
+    /// 注意：此测试会计入API调用次数
+    #[tokio::test]
+    #[ignore]
+    async fn test_batch_shape() {
+        use crate::fusion_translator::async_translator::Language;
+        use crate::fusion_translator::batch::assert_batch_shape;
+
+        assert_batch_shape(&MyMemoryTranslator::new(), &Language::Chinese).await;
+    }
+
+    /// 模糊测试：任意响应的解析都不会panic
+    #[test]
+    fn test_fuzz_parse_response() {
+        let corpus = [
+            include_str!("../../tests/fixtures/mymemory/success.json"),
+            r#"{"responseData":{"translatedText":"MYMEMORY WARNING: YOU USED ALL AVAILABLE FREE TRANSLATIONS"},"quotaFinished":true}"#,
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            if let Ok(resp) = serde_json::from_str(&input) {
+                let _ = parse_response(&resp);
+            }
+        }
+    }
 }
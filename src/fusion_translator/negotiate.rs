@@ -0,0 +1,183 @@
+use crate::fusion_translator::async_translator::Language;
+use sha2::{Digest, Sha256};
+
+/// 解析BCP 47语言标签
+///
+/// 取主语言子标签匹配ISO 639-1或639-3代码，带有`Hant`/`TW`/`HK`/`MO`子标签的中文视为繁体中文
+///
+/// # 参数
+/// - `tag`: 语言标签，如`en-US`、`zh-Hant`，也接受`_`分隔
+pub fn parse_language_tag(tag: &str) -> Option<Language> {
+    let tag = tag.trim().to_ascii_lowercase().replace('_', "-");
+    let mut parts = tag.split('-');
+    let primary = parts.next()?;
+    if primary == "zh" && parts.any(|p| matches!(p, "hant" | "tw" | "hk" | "mo")) {
+        return Some(Language::ChineseTraditional);
+    }
+    Language::from_639_1(primary).or_else(|| Language::from_639_3(primary))
+}
+
+/// 解析`Accept-Language`请求头
+///
+/// 忽略`*`、无法识别的标签和`q=0`的项；同一语言出现多次时保留第一次出现的权重
+///
+/// # 参数
+/// - `header`: 请求头的值，如`zh-CN,zh;q=0.9,en;q=0.8`
+///
+/// # 返回值
+/// 按权重从高到低排列的（语言，权重），权重相同时保持请求头中的顺序
+pub fn parse_accept_language(header: &str) -> Vec<(Language, f32)> {
+    let mut languages: Vec<(Language, f32)> = Vec::new();
+    for item in header.split(',') {
+        let mut params = item.split(';');
+        let tag = params.next().unwrap_or_default().trim();
+        let quality = params
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if tag == "*" || quality.is_nan() || quality <= 0.0 {
+            continue;
+        }
+        if let Some(lang) = parse_language_tag(tag) {
+            if !languages.iter().any(|(l, _)| *l == lang) {
+                languages.push((lang, quality.min(1.0)));
+            }
+        }
+    }
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages
+}
+
+/// 按`Accept-Language`请求头选择目标语言
+///
+/// # 参数
+/// - `header`: 请求头的值
+/// - `supported`: 判断翻译器是否支持某个语言，如`|lang| lang.to_baidu().is_some()`
+///
+/// # 返回值
+/// 权重最高的受支持语言，没有时为None
+pub fn negotiate_target(header: &str, supported: impl Fn(&Language) -> bool) -> Option<Language> {
+    parse_accept_language(header)
+        .into_iter()
+        .map(|(lang, _)| lang)
+        .find(|lang| supported(lang))
+}
+
+/// 计算翻译结果的实体标签（ETag）
+///
+/// 对（原文，目标语言，翻译器名称）计算SHA-256，相同的输入总是得到相同的标签，
+/// 可用于HTTP响应的`ETag`头并配合`if_none_match`返回304
+///
+/// # 参数
+/// - `text`: 原文
+/// - `to`: 目标语言（协商后的结果）
+/// - `engine`: 翻译器名称
+///
+/// # 返回值
+/// 带双引号的强标签，如`"3f2a…"`
+pub fn translation_etag(text: &str, to: &Language, engine: &str) -> String {
+    let mut hasher = Sha256::new();
+    // 逐个字段写入长度前缀，避免不同字段拼接后相同
+    for field in [text, &format!("{:?}", to), engine] {
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    format!("\"{}\"", hex::encode(&hasher.finalize()[..16]))
+}
+
+/// 判断`If-None-Match`请求头是否与实体标签匹配
+///
+/// 按弱比较处理：忽略`W/`前缀，`*`匹配任意标签
+///
+/// # 参数
+/// - `header`: `If-None-Match`请求头的值
+/// - `etag`: `translation_etag`返回的标签
+///
+/// # 返回值
+/// 匹配时为true，此时应返回304
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::negotiate::{
+        if_none_match, negotiate_target, parse_accept_language, parse_language_tag,
+        translation_etag,
+    };
+
+    /// 测试解析语言标签
+    #[test]
+    fn test_parse_language_tag() {
+        assert_eq!(parse_language_tag("en-US"), Some(Language::English));
+        assert_eq!(
+            parse_language_tag("zh_TW"),
+            Some(Language::ChineseTraditional)
+        );
+        assert_eq!(
+            parse_language_tag("zh-Hant-HK"),
+            Some(Language::ChineseTraditional)
+        );
+        assert_eq!(parse_language_tag("zh-CN"), Language::from_639_1("zh"));
+        assert_eq!(parse_language_tag("x-klingon"), None);
+    }
+
+    /// 测试按权重排序，忽略通配符、无法识别和权重为0的项
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("fr;q=0.5, ja, *;q=0.9, de;q=0, xx, en-GB;q=0.8, en;q=0.7"),
+            vec![
+                (Language::Japanese, 1.0),
+                (Language::English, 0.8),
+                (Language::French, 0.5),
+            ]
+        );
+        assert!(parse_accept_language("").is_empty());
+        assert_eq!(
+            parse_accept_language("ko;q=abc"),
+            vec![(Language::Korean, 1.0)]
+        );
+    }
+
+    /// 测试选择权重最高的受支持语言
+    #[test]
+    fn test_negotiate_target() {
+        let header = "xx, ja;q=0.9, en;q=0.8";
+        assert_eq!(
+            negotiate_target(header, |lang| *lang != Language::Japanese),
+            Some(Language::English)
+        );
+        assert_eq!(negotiate_target(header, |_| false), None);
+    }
+
+    /// 测试实体标签只由原文、目标语言和翻译器决定，并按弱比较匹配
+    #[test]
+    fn test_etag() {
+        let etag = translation_etag("hello", &Language::French, "mymemory");
+        assert_eq!(
+            etag,
+            translation_etag("hello", &Language::French, "mymemory")
+        );
+        assert_ne!(
+            etag,
+            translation_etag("hello", &Language::German, "mymemory")
+        );
+        assert_ne!(etag, translation_etag("hello", &Language::French, "baidu"));
+        assert_ne!(
+            translation_etag("ab", &Language::French, "c"),
+            translation_etag("a", &Language::French, "bc")
+        );
+        assert!(etag.starts_with('"') && etag.ends_with('"') && etag.len() == 34);
+
+        assert!(if_none_match(&etag, &etag));
+        assert!(if_none_match(&format!("\"other\", W/{}", etag), &etag));
+        assert!(if_none_match("*", &etag));
+        assert!(!if_none_match("\"other\"", &etag));
+    }
+}
@@ -0,0 +1,543 @@
+use crate::fusion_translator::runtime::{self, Instant};
+use crate::fusion_translator::scheduled::{Clock, SystemClock};
+use crate::fusion_translator::transport::{HttpRequest, HttpResponse, HttpTransport};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 礼貌请求配置
+///
+/// 免密钥的翻译器（阿里、MyMemory、TranSmart、Reverso、必应网页版和谷歌网页版）调用的是
+/// 网页版或免费接口，请求过于频繁时服务会按IP封禁。这些翻译器默认使用`PolitenessConfig::default()`，
+/// 与服务另有约定的用户可以通过`TranslatorOptions::with_politeness`调整或关闭；
+/// 需要密钥的翻译器不使用礼貌请求控制
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolitenessConfig {
+    /// 两次请求开始之间的最小间隔
+    pub min_delay: Duration,
+    /// 在最小间隔之上额外增加的随机延迟的上限，实际延迟在0到此值之间均匀分布
+    pub jitter: Duration,
+    /// 第一次被拒绝（429或403）后的冷却时长
+    pub initial_cooldown: Duration,
+    /// 连续被拒绝时冷却时长的上限
+    pub max_cooldown: Duration,
+    /// 连续被拒绝时冷却时长的增长倍数
+    pub cooldown_multiplier: f64,
+    /// 保存冷却状态的文件，None表示不保存
+    ///
+    /// 设置后冷却结束时刻会写入该文件，重新创建时读取，进程重启后不会立即再次触发封禁
+    pub ban_file: Option<PathBuf>,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(300),
+            jitter: Duration::from_millis(300),
+            initial_cooldown: Duration::from_secs(60),
+            max_cooldown: Duration::from_secs(3600),
+            cooldown_multiplier: 2.0,
+            ban_file: None,
+        }
+    }
+}
+
+impl PolitenessConfig {
+    /// 设置两次请求之间的最小间隔和随机延迟上限
+    ///
+    /// # 参数
+    /// - `min_delay`: 最小间隔
+    /// - `jitter`: 随机延迟上限
+    pub fn with_delay(mut self, min_delay: Duration, jitter: Duration) -> Self {
+        self.min_delay = min_delay;
+        self.jitter = jitter;
+        self
+    }
+
+    /// 设置被拒绝后的冷却时长
+    ///
+    /// # 参数
+    /// - `initial`: 第一次被拒绝后的冷却时长
+    /// - `max`: 冷却时长上限
+    /// - `multiplier`: 连续被拒绝时的增长倍数，小于1时视为1
+    pub fn with_cooldown(mut self, initial: Duration, max: Duration, multiplier: f64) -> Self {
+        self.initial_cooldown = initial;
+        self.max_cooldown = max;
+        self.cooldown_multiplier = multiplier;
+        self
+    }
+
+    /// 设置保存冷却状态的文件
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    pub fn with_ban_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ban_file = Some(path.into());
+        self
+    }
+
+    /// 第`strikes`次连续被拒绝后的冷却时长
+    ///
+    /// # 参数
+    /// - `strikes`: 连续被拒绝的次数，从1开始
+    fn cooldown(&self, strikes: u32) -> Duration {
+        let factor = self
+            .cooldown_multiplier
+            .max(1.0)
+            .powi(strikes.saturating_sub(1).min(64) as i32);
+        let secs = self.initial_cooldown.as_secs_f64() * factor;
+        Duration::try_from_secs_f64(secs)
+            .unwrap_or(Duration::MAX)
+            .min(self.max_cooldown)
+    }
+}
+
+/// 翻译器使用的礼貌请求控制
+#[derive(Debug, Clone, Default)]
+pub enum PolitenessSetting {
+    /// 使用同一服务所有实例共享的默认配置（`PolitenessConfig::default()`）
+    #[default]
+    Default,
+    /// 使用指定的实例，可以在多个翻译器之间共享
+    Custom(Arc<Politeness>),
+    /// 关闭，适用于与服务另有约定的用户
+    Disabled,
+}
+
+impl PolitenessSetting {
+    /// 按配置得到某个翻译服务使用的实例
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译服务名称
+    ///
+    /// # 返回值
+    /// 礼貌请求控制，关闭时为None
+    pub fn resolve(&self, vendor: &str) -> Option<Arc<Politeness>> {
+        match self {
+            Self::Default => Some(Politeness::shared(vendor, PolitenessConfig::default())),
+            Self::Custom(politeness) => Some(politeness.clone()),
+            Self::Disabled => None,
+        }
+    }
+}
+
+/// 当前的冷却状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PolitenessStatus {
+    /// 连续被拒绝的次数，请求成功后清零
+    pub strikes: u32,
+    /// 剩余的冷却时长，不在冷却中时为None
+    pub cooldown_remaining: Option<Duration>,
+    /// 冷却结束的系统时间，不在冷却中时为None
+    pub banned_until: Option<SystemTime>,
+}
+
+impl PolitenessStatus {
+    /// 是否正在冷却
+    pub fn cooling_down(&self) -> bool {
+        self.cooldown_remaining.is_some()
+    }
+}
+
+/// 保存在`PolitenessConfig::ban_file`中的冷却状态
+#[derive(Debug, Serialize, Deserialize)]
+struct BanRecord {
+    /// 冷却结束时刻的Unix时间戳（秒）
+    banned_until: f64,
+    /// 连续被拒绝的次数
+    strikes: u32,
+}
+
+/// 可变的请求状态
+#[derive(Debug, Default)]
+struct State {
+    /// 下一个请求最早可以开始的时刻
+    next: Option<Instant>,
+    /// 冷却结束的时刻
+    banned_until: Option<Instant>,
+    /// 连续被拒绝的次数
+    strikes: u32,
+}
+
+/// 礼貌请求控制
+///
+/// 每次请求前调用`wait`：请求之间至少间隔`min_delay`加上随机延迟，冷却期间一直等待到冷却结束；
+/// 请求返回后调用`observe`：429或403进入冷却，连续被拒绝时冷却时长按倍数增长，请求成功后清零。
+/// 同一服务的所有翻译器实例默认共享一个实例（见`Politeness::shared`），合计请求频率不超过限制
+#[derive(Debug)]
+pub struct Politeness {
+    /// 配置
+    config: PolitenessConfig,
+    /// 请求状态
+    state: Mutex<State>,
+}
+
+impl Politeness {
+    /// 创建礼貌请求控制
+    ///
+    /// 设置了`ban_file`且其中记录的冷却尚未结束时，从记录的状态继续
+    ///
+    /// # 参数
+    /// - `config`: 配置
+    pub fn new(config: PolitenessConfig) -> Self {
+        let mut state = State::default();
+        if let Some(record) = config.ban_file.as_ref().and_then(|path| load_ban(path)) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64();
+            if let Ok(remaining) = Duration::try_from_secs_f64(record.banned_until - now) {
+                if !remaining.is_zero() {
+                    state.banned_until = Instant::now().checked_add(remaining);
+                    state.strikes = record.strikes;
+                }
+            }
+        }
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// 获取某个翻译服务共享的礼貌请求控制
+    ///
+    /// 进程内相同`vendor`的调用返回同一个实例，已存在时沿用其原有配置；
+    /// 注册表只保存弱引用，所有持有者释放后随之释放
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译服务名称
+    /// - `config`: 新建时使用的配置
+    pub fn shared(vendor: &str, config: PolitenessConfig) -> Arc<Self> {
+        let mut registry = Self::registry().lock().unwrap();
+        if let Some(politeness) = registry.get(vendor).and_then(Weak::upgrade) {
+            return politeness;
+        }
+        registry.retain(|_, politeness| politeness.strong_count() > 0);
+        let politeness = Arc::new(Self::new(config));
+        registry.insert(vendor.to_string(), Arc::downgrade(&politeness));
+        politeness
+    }
+
+    /// 获取某个翻译服务当前共享的实例，用于查看默认配置下的冷却状态
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译服务名称，如`mymemory`、`bing_web`
+    ///
+    /// # 返回值
+    /// 没有翻译器使用该服务的共享实例时为None
+    pub fn current(vendor: &str) -> Option<Arc<Self>> {
+        Self::registry()
+            .lock()
+            .unwrap()
+            .get(vendor)
+            .and_then(Weak::upgrade)
+    }
+
+    /// 共享实例的注册表，只保存弱引用
+    fn registry() -> &'static Mutex<HashMap<String, Weak<Politeness>>> {
+        static REGISTRY: OnceLock<Mutex<HashMap<String, Weak<Politeness>>>> = OnceLock::new();
+        REGISTRY.get_or_init(Default::default)
+    }
+
+    /// 配置
+    pub fn config(&self) -> &PolitenessConfig {
+        &self.config
+    }
+
+    /// 当前的冷却状态
+    pub fn status(&self) -> PolitenessStatus {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let remaining = state
+            .banned_until
+            .filter(|until| *until > now)
+            .map(|until| until - now);
+        PolitenessStatus {
+            strikes: state.strikes,
+            cooldown_remaining: remaining,
+            banned_until: remaining.map(|remaining| SystemClock.now() + remaining),
+        }
+    }
+
+    /// 等待可以发送下一个请求
+    ///
+    /// 按到达顺序排队；等待期间进入冷却时继续等待到冷却结束
+    pub async fn wait(&self) {
+        let jitter = self.jitter();
+        let slot = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let mut slot = state.next.map_or(now, |next| next.max(now));
+            if let Some(until) = state.banned_until {
+                slot = slot.max(until);
+            }
+            state.next = Some(slot + self.config.min_delay + jitter);
+            slot
+        };
+        runtime::sleep_until(slot).await;
+        loop {
+            let until = self.state.lock().unwrap().banned_until;
+            match until {
+                Some(until) if until > Instant::now() => runtime::sleep_until(until).await,
+                _ => break,
+            }
+        }
+    }
+
+    /// 按响应状态码更新冷却状态
+    ///
+    /// 429和403进入冷却，成功的响应清零连续被拒绝的次数，其余状态码不影响冷却
+    ///
+    /// # 参数
+    /// - `status`: 响应状态码
+    pub fn observe(&self, status: StatusCode) {
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::FORBIDDEN {
+            self.reject();
+        } else if status.is_success() {
+            let mut state = self.state.lock().unwrap();
+            if state.strikes > 0 {
+                state.strikes = 0;
+                if let Some(path) = &self.config.ban_file {
+                    let _ = std::fs::remove_file(path);
+                }
+            }
+        }
+    }
+
+    /// 记录一次被拒绝，进入冷却
+    fn reject(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.strikes = state.strikes.saturating_add(1);
+        let cooldown = self.config.cooldown(state.strikes);
+        let until = Instant::now() + cooldown;
+        state.banned_until = Some(
+            state
+                .banned_until
+                .map_or(until, |current| current.max(until)),
+        );
+        tracing::warn!(
+            strikes = state.strikes,
+            cooldown_secs = cooldown.as_secs_f64(),
+            "request rejected, cooling down"
+        );
+        if let Some(path) = &self.config.ban_file {
+            let banned_until = (SystemTime::now() + cooldown)
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64();
+            let record = BanRecord {
+                banned_until,
+                strikes: state.strikes,
+            };
+            let written = serde_json::to_vec(&record)
+                .map_err(std::io::Error::from)
+                .and_then(|bytes| std::fs::write(path, bytes));
+            if let Err(e) = written {
+                tracing::warn!(path = %path.display(), error = %e, "failed to save cooldown state");
+            }
+        }
+    }
+
+    /// 随机延迟
+    fn jitter(&self) -> Duration {
+        let max = self.config.jitter.as_nanos().min(u64::MAX as u128) as u64;
+        if max == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(runtime::random_up_to(max))
+    }
+}
+
+/// 读取保存的冷却状态，文件不存在或内容无效时为None
+///
+/// # 参数
+/// - `path`: 文件路径
+fn load_ban(path: &Path) -> Option<BanRecord> {
+    let bytes = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "ignoring invalid cooldown state");
+            None
+        }
+    }
+}
+
+/// 按礼貌请求控制发送请求
+///
+/// 发送前等待，收到响应后按状态码更新冷却状态；`politeness`为None时直接发送
+///
+/// # 参数
+/// - `politeness`: 礼貌请求控制
+/// - `transport`: 发送请求的传输层
+/// - `request`: 待发送的请求
+pub(crate) async fn send(
+    politeness: Option<&Politeness>,
+    transport: &dyn HttpTransport,
+    request: HttpRequest,
+) -> anyhow::Result<HttpResponse> {
+    let Some(politeness) = politeness else {
+        return transport.execute(request).await;
+    };
+    politeness.wait().await;
+    let response = transport.execute(request).await?;
+    if let Ok(status) = StatusCode::from_u16(response.status) {
+        politeness.observe(status);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::politeness::{Politeness, PolitenessConfig, PolitenessSetting};
+    use reqwest::StatusCode;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 最小间隔100毫秒、随机延迟最多50毫秒，冷却从1秒开始翻倍、最多8秒的配置
+    fn config() -> PolitenessConfig {
+        PolitenessConfig::default()
+            .with_delay(Duration::from_millis(100), Duration::from_millis(50))
+            .with_cooldown(Duration::from_secs(1), Duration::from_secs(8), 2.0)
+    }
+
+    /// 测试请求之间的间隔不小于最小间隔，且随机延迟不超过上限
+    #[tokio::test(start_paused = true)]
+    async fn test_delay_and_jitter_bounds() {
+        let politeness = Politeness::new(config());
+        let mut starts = Vec::new();
+        for _ in 0..200 {
+            politeness.wait().await;
+            starts.push(Instant::now());
+        }
+        let gaps = starts
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect::<Vec<_>>();
+        assert!(gaps
+            .iter()
+            .all(|gap| *gap >= Duration::from_millis(100) && *gap <= Duration::from_millis(150)));
+        let spread = *gaps.iter().max().unwrap() - *gaps.iter().min().unwrap();
+        assert!(spread > Duration::from_millis(25), "{:?}", spread);
+
+        let quiet = Politeness::new(config().with_delay(Duration::ZERO, Duration::ZERO));
+        let start = Instant::now();
+        for _ in 0..10 {
+            quiet.wait().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    /// 测试连续被拒绝时冷却时长翻倍直到上限，成功后清零
+    #[tokio::test(start_paused = true)]
+    async fn test_cooldown_escalation() {
+        let politeness = Politeness::new(config().with_delay(Duration::ZERO, Duration::ZERO));
+        assert!(!politeness.status().cooling_down());
+        let mut cooldowns = Vec::new();
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::FORBIDDEN,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::TOO_MANY_REQUESTS,
+        ] {
+            politeness.wait().await;
+            politeness.observe(status);
+            cooldowns.push(politeness.status().cooldown_remaining.unwrap());
+        }
+        assert_eq!(cooldowns, [1, 2, 4, 8, 8].map(Duration::from_secs));
+        assert_eq!(politeness.status().strikes, 5);
+
+        let start = Instant::now();
+        politeness.wait().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(8));
+        politeness.observe(StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(politeness.status().strikes, 5);
+        politeness.observe(StatusCode::OK);
+        assert_eq!(politeness.status().strikes, 0);
+        politeness.observe(StatusCode::FORBIDDEN);
+        assert_eq!(
+            politeness.status().cooldown_remaining,
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    /// 测试已在排队的请求在进入冷却后等到冷却结束
+    #[tokio::test(start_paused = true)]
+    async fn test_queued_requests_respect_new_ban() {
+        let politeness = Arc::new(Politeness::new(
+            config().with_delay(Duration::from_millis(100), Duration::ZERO),
+        ));
+        let start = Instant::now();
+        politeness.wait().await;
+        let queued = tokio::spawn({
+            let politeness = politeness.clone();
+            async move {
+                politeness.wait().await;
+                Instant::now()
+            }
+        });
+        tokio::task::yield_now().await;
+        politeness.observe(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(queued.await.unwrap() - start, Duration::from_secs(1));
+    }
+
+    /// 测试冷却状态保存到文件，重新创建后继续冷却，成功后删除文件
+    #[tokio::test(start_paused = true)]
+    async fn test_ban_file_survives_restart() {
+        let path =
+            std::env::temp_dir().join(format!("fusion-translator-ban-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let config = config()
+            .with_cooldown(Duration::from_secs(600), Duration::from_secs(3600), 2.0)
+            .with_ban_file(&path);
+
+        let politeness = Politeness::new(config.clone());
+        politeness.observe(StatusCode::TOO_MANY_REQUESTS);
+        politeness.observe(StatusCode::TOO_MANY_REQUESTS);
+        drop(politeness);
+
+        let restarted = Politeness::new(config.clone());
+        let status = restarted.status();
+        assert_eq!(status.strikes, 2);
+        let remaining = status.cooldown_remaining.unwrap();
+        assert!(remaining > Duration::from_secs(1190), "{:?}", remaining);
+        assert!(remaining <= Duration::from_secs(1200), "{:?}", remaining);
+        assert!(status.banned_until.is_some());
+
+        restarted.observe(StatusCode::OK);
+        assert!(!path.exists());
+        assert!(!Politeness::new(config).status().cooling_down());
+    }
+
+    /// 测试同一服务共享实例
+    #[test]
+    fn test_shared() {
+        let first = Politeness::shared("test-politeness", config());
+        let second = Politeness::shared("test-politeness", PolitenessConfig::default());
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(second.config(), &config());
+        assert!(!Arc::ptr_eq(
+            &first,
+            &Politeness::shared("test-politeness-other", config())
+        ));
+        assert!(Arc::ptr_eq(
+            &Politeness::current("test-politeness").unwrap(),
+            &first
+        ));
+        assert!(Politeness::current("test-politeness-missing").is_none());
+
+        assert!(PolitenessSetting::Disabled
+            .resolve("test-politeness")
+            .is_none());
+        let custom = Arc::new(Politeness::new(PolitenessConfig::default()));
+        let resolved = PolitenessSetting::Custom(custom.clone()).resolve("test-politeness");
+        assert!(Arc::ptr_eq(&resolved.unwrap(), &custom));
+        let resolved = PolitenessSetting::Default.resolve("test-politeness");
+        assert!(Arc::ptr_eq(&resolved.unwrap(), &first));
+    }
+}
@@ -0,0 +1,901 @@
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::whitespace;
+use icu_normalizer::ComposingNormalizerBorrowed;
+
+/// 后处理最多重复的轮数
+///
+/// 双重编码的内容（如`&amp;#39;`）需要多轮处理，达到该轮数后停止以避免异常输入导致的长时间循环
+const MAX_ROUNDS: usize = 8;
+
+/// 中日韩译文中需要转换的全角和半角标点
+const PUNCTUATION_PAIRS: [(char, char); 6] = [
+    ('，', ','),
+    ('。', '.'),
+    ('！', '!'),
+    ('？', '?'),
+    ('：', ':'),
+    ('；', ';'),
+];
+
+/// 句末句号
+const PERIODS: [char; 3] = ['.', '。', '．'];
+
+/// 后处理配置
+///
+/// 每个字段控制一项清理步骤，清理步骤默认开启，风格调整默认关闭
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostprocessOptions {
+    /// 解码HTML实体（`&amp;`、`&#39;`、`&#x4e2d;`等）
+    pub decode_entities: bool,
+    /// 还原字面转义序列（`\n`、`\t`、`\r`、`\uXXXX`）
+    pub unescape: bool,
+    /// 去除原文没有的成对包裹引号
+    pub strip_quotes: bool,
+    /// 进行Unicode NFC规范化
+    pub normalize_nfc: bool,
+    /// 原文全部大写或每个单词首字母大写时，对拉丁字母译文采用相同的大小写
+    pub match_source_case: bool,
+    /// 中日韩译文的标点风格，None表示保持翻译服务返回的标点
+    pub punctuation_style: Option<PunctuationStyle>,
+    /// 原文不以句号结尾时，去掉译文末尾的句号
+    pub trim_trailing_period_if_source_lacks_one: bool,
+}
+
+impl Default for PostprocessOptions {
+    fn default() -> Self {
+        Self {
+            decode_entities: true,
+            unescape: true,
+            strip_quotes: true,
+            normalize_nfc: true,
+            match_source_case: false,
+            punctuation_style: None,
+            trim_trailing_period_if_source_lacks_one: false,
+        }
+    }
+}
+
+/// 标点风格
+///
+/// 只影响`，。！？：；`与`,.!?:;`这几组标点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunctuationStyle {
+    /// 与原文一致：原文使用全角标点时转为全角，使用半角标点时转为半角
+    Source,
+    /// 全角标点
+    FullWidth,
+    /// 半角标点，标点后紧跟文字时补一个空格
+    HalfWidth,
+}
+
+/// 按配置清理译文
+///
+/// 各步骤会重复执行直到结果不再变化，因此对同一原文重复调用的结果保持不变
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 翻译服务返回的译文
+/// - `options`: 后处理配置
+///
+/// # 返回值
+/// 清理后的译文
+pub fn postprocess(source: &str, output: &str, options: &PostprocessOptions) -> String {
+    let mut text = output.to_string();
+    for _ in 0..MAX_ROUNDS {
+        let mut next = text.clone();
+        if options.decode_entities {
+            next = decode_html_entities(source, &next);
+        }
+        if options.unescape {
+            next = unescape_sequences(source, &next);
+        }
+        if options.strip_quotes {
+            next = strip_wrapper_quotes(source, &next);
+        }
+        if options.normalize_nfc {
+            next = normalize_nfc(&next);
+        }
+        if next == text {
+            break;
+        }
+        text = next;
+    }
+    if let Some(style) = options.punctuation_style {
+        text = convert_punctuation(source, &text, style);
+    }
+    if options.trim_trailing_period_if_source_lacks_one {
+        text = trim_trailing_period(source, &text);
+    }
+    if options.match_source_case {
+        text = match_source_case(source, &text);
+    }
+    text
+}
+
+/// 按调用选项清理译文
+///
+/// 供各翻译器在解析响应后调用，`raw_output`开启时跳过清理步骤；
+/// 开启`preserve_boundary_whitespace`时最后还原原文的空白
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 翻译服务返回的译文
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 清理后的译文
+pub(crate) fn apply(source: &str, output: String, options: &TranslateOptions) -> String {
+    let output = if options.raw_output {
+        output
+    } else {
+        postprocess(source, &output, &options.postprocess)
+    };
+    whitespace::restore(source, output, options)
+}
+
+/// 按调用选项清理一组译文
+///
+/// # 参数
+/// - `sources`: 原文数组
+/// - `outputs`: 译文数组
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 清理后的译文数组
+pub(crate) fn apply_all(
+    sources: &[String],
+    outputs: Vec<String>,
+    options: &TranslateOptions,
+) -> Vec<String> {
+    let outputs = if options.raw_output {
+        outputs
+    } else {
+        postprocess_all(sources, outputs, &options.postprocess)
+    };
+    whitespace::restore_all(sources, outputs, options)
+}
+
+/// 按配置清理一组译文
+///
+/// 原文与译文数量不一致时无法一一对应，按空原文处理
+///
+/// # 参数
+/// - `sources`: 原文数组
+/// - `outputs`: 译文数组
+/// - `options`: 后处理配置
+///
+/// # 返回值
+/// 清理后的译文数组
+pub fn postprocess_all(
+    sources: &[String],
+    outputs: Vec<String>,
+    options: &PostprocessOptions,
+) -> Vec<String> {
+    let aligned = sources.len() == outputs.len();
+    outputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let source = sources
+                .get(i)
+                .filter(|_| aligned)
+                .map_or("", String::as_str);
+            postprocess(source, &text, options)
+        })
+        .collect()
+}
+
+/// 解码HTML实体
+///
+/// 支持常用命名实体和十进制、十六进制数字实体；
+/// 原文中原样出现的实体（如原文本身就包含`&amp;`）保持不变
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 解码后的文本
+pub fn decode_html_entities(source: &str, text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[..=end];
+            if source.contains(entity) {
+                return None;
+            }
+            decode_entity(&entity[1..end]).map(|c| (c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                result.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 解码单个实体名称
+///
+/// # 参数
+/// - `name`: 去掉`&`和`;`后的实体名称
+///
+/// # 返回值
+/// 实体对应的字符，无法识别时为None
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "middot" => '·',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// 还原字面转义序列
+///
+/// 将译文中的`\n`、`\t`、`\r`、`\uXXXX`还原为对应字符；
+/// 原文包含反斜杠时无法区分转义和正文，不做任何处理
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 还原后的文本
+pub fn unescape_sequences(source: &str, text: &str) -> String {
+    if source.contains('\\') || !text.contains('\\') {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let replacement = match chars.peek().map(|(_, next)| *next) {
+            Some('n') => Some(('\n', 1)),
+            Some('t') => Some(('\t', 1)),
+            Some('r') => Some(('\r', 1)),
+            Some('u') => text
+                .get(i + 2..i + 6)
+                .filter(|hex| hex.chars().all(|h| h.is_ascii_hexdigit()))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+                .filter(|c| *c != '\\' && *c != '\0')
+                .map(|c| (c, 5)),
+            _ => None,
+        };
+        match replacement {
+            Some((decoded, skip)) => {
+                result.push(decoded);
+                for _ in 0..skip {
+                    chars.next();
+                }
+            }
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// 成对的包裹引号
+const QUOTE_PAIRS: [(char, char); 5] = [
+    ('"', '"'),
+    ('\'', '\''),
+    ('“', '”'),
+    ('‘', '’'),
+    ('「', '」'),
+];
+
+/// 去除多余的包裹引号
+///
+/// 仅当原文没有被任何一对引号包裹、译文首尾是一对匹配的引号且中间不再出现这对引号时才去除，
+/// 避免把`"Yes," he said, "no"`这类本身以引号开头和结尾的文本去掉首尾引号
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 处理后的文本
+pub fn strip_wrapper_quotes(source: &str, text: &str) -> String {
+    let wrapped = |s: &str, open: char, close: char| {
+        s.chars().count() >= 2 && s.starts_with(open) && s.ends_with(close)
+    };
+    let source = source.trim();
+    if QUOTE_PAIRS
+        .iter()
+        .any(|&(open, close)| wrapped(source, open, close))
+    {
+        return text.to_string();
+    }
+    for (open, close) in QUOTE_PAIRS {
+        if !wrapped(text, open, close) {
+            continue;
+        }
+        let inner = &text[open.len_utf8()..text.len() - close.len_utf8()];
+        if !inner.contains([open, close]) {
+            return inner.to_string();
+        }
+    }
+    text.to_string()
+}
+
+/// 原文的大小写形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceCase {
+    /// 全部大写，如`SAVE FILE`
+    Upper,
+    /// 每个单词首字母大写，如`Save File`
+    Title,
+    /// 只有一个单词且首字母大写，如`Save`
+    Capitalized,
+}
+
+/// 识别原文的大小写形式
+///
+/// # 参数
+/// - `source`: 原文
+///
+/// # 返回值
+/// 没有可区分大小写的字母或属于普通句子时为None
+fn source_case(source: &str) -> Option<SourceCase> {
+    let cased = source
+        .chars()
+        .filter(|c| c.is_uppercase() || c.is_lowercase())
+        .collect::<Vec<_>>();
+    if cased.len() >= 2 && cased.iter().all(|c| c.is_uppercase()) {
+        return Some(SourceCase::Upper);
+    }
+    let initials = source
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .filter(|c| c.is_uppercase() || c.is_lowercase())
+        .collect::<Vec<_>>();
+    match initials.as_slice() {
+        [] => None,
+        [first] if first.is_uppercase() => Some(SourceCase::Capitalized),
+        [_, _, ..] if initials.iter().all(|c| c.is_uppercase()) => Some(SourceCase::Title),
+        _ => None,
+    }
+}
+
+/// 是否为拉丁字母
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic()
+        || ('\u{00C0}'..='\u{024F}').contains(&c) && c != '×' && c != '÷'
+        || ('\u{1E00}'..='\u{1EFF}').contains(&c)
+}
+
+/// 首字母大写
+///
+/// # 参数
+/// - `word`: 单词
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// 按原文的大小写调整译文
+///
+/// 只处理字母全部为拉丁字母的译文：原文全部大写时译文转为大写，
+/// 每个单词首字母大写时译文的每个单词首字母大写，只有一个首字母大写的单词时译文首字母大写
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 处理后的文本
+pub fn match_source_case(source: &str, text: &str) -> String {
+    let mut letters = text.chars().filter(|c| c.is_alphabetic()).peekable();
+    if letters.peek().is_none() || !letters.all(is_latin) {
+        return text.to_string();
+    }
+    match source_case(source) {
+        Some(SourceCase::Upper) => text.to_uppercase(),
+        Some(SourceCase::Title) => {
+            let mut result = String::with_capacity(text.len());
+            let mut at_word_start = true;
+            for c in text.chars() {
+                if at_word_start {
+                    result.extend(c.to_uppercase());
+                } else {
+                    result.push(c);
+                }
+                at_word_start = c.is_whitespace();
+            }
+            result
+        }
+        Some(SourceCase::Capitalized) => {
+            let start = text.len() - text.trim_start().len();
+            format!("{}{}", &text[..start], capitalize(&text[start..]))
+        }
+        None => text.to_string(),
+    }
+}
+
+/// 是否为中日韩文字（汉字、假名、谚文）
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}')
+}
+
+/// 是否为中日韩文字或全角标点
+pub(crate) fn is_cjk_context(c: char) -> bool {
+    is_cjk(c) || ('\u{3000}'..='\u{303F}').contains(&c) || ('\u{FF01}'..='\u{FF60}').contains(&c)
+}
+
+/// 识别原文的标点风格
+///
+/// # 参数
+/// - `source`: 原文
+///
+/// # 返回值
+/// 原文不含相关标点时为None
+fn source_punctuation(source: &str) -> Option<PunctuationStyle> {
+    if source
+        .chars()
+        .any(|c| PUNCTUATION_PAIRS.iter().any(|(full, _)| *full == c))
+    {
+        Some(PunctuationStyle::FullWidth)
+    } else if source
+        .chars()
+        .any(|c| PUNCTUATION_PAIRS.iter().any(|(_, half)| *half == c))
+    {
+        Some(PunctuationStyle::HalfWidth)
+    } else {
+        None
+    }
+}
+
+/// 转换中日韩译文的标点风格
+///
+/// 只处理包含中日韩文字的译文。转为全角时，只转换紧跟在中日韩文字之后、
+/// 且后面是空白、结尾或中日韩文字的半角标点（不会改动`3.14`、`e.g.`、`...`等），
+/// 并去掉标点与后续中日韩文字之间的空格；转为半角时，标点后紧跟文字则补一个空格
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+/// - `style`: 标点风格
+///
+/// # 返回值
+/// 处理后的文本
+pub fn convert_punctuation(source: &str, text: &str, style: PunctuationStyle) -> String {
+    if !text.chars().any(is_cjk) {
+        return text.to_string();
+    }
+    let style = match style {
+        PunctuationStyle::Source => match source_punctuation(source) {
+            Some(style) => style,
+            None => return text.to_string(),
+        },
+        style => style,
+    };
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        let prev = i.checked_sub(1).and_then(|p| chars.get(p)).copied();
+        let next = chars.get(i + 1).copied();
+        match style {
+            PunctuationStyle::FullWidth => {
+                let full = PUNCTUATION_PAIRS
+                    .iter()
+                    .find(|(_, half)| *half == c)
+                    .map(|(full, _)| *full);
+                let convertible = prev.is_some_and(is_cjk_context)
+                    && next.is_none_or(|n| n.is_whitespace() || is_cjk(n));
+                match full {
+                    Some(full) if convertible => {
+                        result.push(full);
+                        let mut j = i + 1;
+                        while chars.get(j).is_some_and(|c| *c == ' ') {
+                            j += 1;
+                        }
+                        if chars.get(j).is_some_and(|c| is_cjk_context(*c)) {
+                            i = j;
+                            continue;
+                        }
+                    }
+                    _ => result.push(c),
+                }
+            }
+            PunctuationStyle::HalfWidth => {
+                let half = PUNCTUATION_PAIRS
+                    .iter()
+                    .find(|(full, _)| *full == c)
+                    .map(|(_, half)| *half);
+                match half {
+                    Some(half) => {
+                        result.push(half);
+                        if next.is_some_and(|n| {
+                            !n.is_whitespace()
+                                && (n.is_alphanumeric() || is_cjk(n))
+                                && !PUNCTUATION_PAIRS.iter().any(|(f, h)| *f == n || *h == n)
+                        }) {
+                            result.push(' ');
+                        }
+                    }
+                    None => result.push(c),
+                }
+            }
+            PunctuationStyle::Source => unreachable!(),
+        }
+        i += 1;
+    }
+    result
+}
+
+/// 原文不以句号结尾时去掉译文末尾的句号
+///
+/// 末尾为省略号（`..`、`...`）时保持不变
+///
+/// # 参数
+/// - `source`: 原文
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 处理后的文本
+pub fn trim_trailing_period(source: &str, text: &str) -> String {
+    if source.trim_end().ends_with(PERIODS) {
+        return text.to_string();
+    }
+    let trimmed = text.trim_end();
+    match trimmed.strip_suffix(PERIODS) {
+        Some(rest) if !rest.ends_with(PERIODS) => rest.to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Unicode NFC规范化
+///
+/// # 参数
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 规范化后的文本
+pub fn normalize_nfc(text: &str) -> String {
+    ComposingNormalizerBorrowed::new_nfc()
+        .normalize(text)
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::postprocess::{
+        convert_punctuation, decode_html_entities, match_source_case, normalize_nfc, postprocess,
+        strip_wrapper_quotes, trim_trailing_period, unescape_sequences, PostprocessOptions,
+        PunctuationStyle,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+
+    /// 测试常见HTML实体解码
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_html_entities("it's", "c&#39;est &amp; &lt;b&gt; &#x4e2d;"),
+            "c'est & <b> 中"
+        );
+        assert_eq!(decode_html_entities("", "&unknown; & x"), "&unknown; & x");
+        assert_eq!(decode_html_entities("", "&#0; &#xZZ;"), "&#0; &#xZZ;");
+    }
+
+    /// 测试原文中已有的实体不会被过度解码
+    #[test]
+    fn test_entities_present_in_source_are_kept() {
+        let source = "Use &amp; to escape an ampersand";
+        let output = "使用 &amp; 转义 &quot;&amp;&quot;";
+        assert_eq!(
+            decode_html_entities(source, output),
+            "使用 &amp; 转义 \"&amp;\""
+        );
+    }
+
+    /// 测试双重编码的实体会被完全解码
+    #[test]
+    fn test_double_encoded_entities() {
+        let options = PostprocessOptions::default();
+        assert_eq!(postprocess("it's", "it&amp;#39;s", &options), "it's");
+    }
+
+    /// 测试字面转义序列还原
+    #[test]
+    fn test_unescape_sequences() {
+        assert_eq!(
+            unescape_sequences("a\nb", "a\\nb\\tc\\u4e2d\\x"),
+            "a\nb\tc中\\x"
+        );
+        assert_eq!(unescape_sequences("C:\\new", "C:\\new"), "C:\\new");
+        assert_eq!(unescape_sequences("", "\\u005c\\u12"), "\\u005c\\u12");
+    }
+
+    /// 测试包裹引号去除
+    #[test]
+    fn test_strip_wrapper_quotes() {
+        assert_eq!(strip_wrapper_quotes("hello", "\"你好\""), "你好");
+        assert_eq!(strip_wrapper_quotes("\"hello\"", "\"你好\""), "\"你好\"");
+        assert_eq!(strip_wrapper_quotes("hi", "“你好”"), "你好");
+        assert_eq!(strip_wrapper_quotes("a", "\""), "\"");
+        // 原文被另一种引号包裹时也保留
+        assert_eq!(strip_wrapper_quotes("“你好”", "\"Hello\""), "\"Hello\"");
+        assert_eq!(strip_wrapper_quotes("「你好」", "‘Hello’"), "‘Hello’");
+        // 首尾引号不是同一对时不去除
+        assert_eq!(
+            strip_wrapper_quotes("他说是，又说不", "\"Yes,\" he said, \"no\""),
+            "\"Yes,\" he said, \"no\""
+        );
+        assert_eq!(
+            strip_wrapper_quotes("x", "“Yes,” he said, “no”"),
+            "“Yes,” he said, “no”"
+        );
+    }
+
+    /// 测试NFC规范化
+    #[test]
+    fn test_normalize_nfc() {
+        assert_eq!(normalize_nfc("e\u{301}"), "\u{e9}");
+        assert_eq!(normalize_nfc("中文"), "中文");
+    }
+
+    /// 测试按原文大小写调整译文
+    #[test]
+    fn test_match_source_case() {
+        let cases = [
+            ("SAVE FILE", "guardar archivo", "GUARDAR ARCHIVO"),
+            ("SAVE", "guardar", "GUARDAR"),
+            ("Save File", "guardar el archivo", "Guardar El Archivo"),
+            ("Save", "guardar archivo", "Guardar archivo"),
+            ("Save", "  guardar", "  Guardar"),
+            ("Save file", "guardar archivo", "guardar archivo"),
+            ("save file", "Guardar Archivo", "Guardar Archivo"),
+            ("OK", "d'accord", "D'ACCORD"),
+            ("STRASSE", "straße", "STRASSE"),
+            ("ÉCOLE", "école", "ÉCOLE"),
+            ("Open File", "ouvrir le fichier", "Ouvrir Le Fichier"),
+            // 译文含非拉丁字母时不处理
+            ("SAVE FILE", "保存文件", "保存文件"),
+            ("SAVE FILE", "сохранить файл", "сохранить файл"),
+            ("SAVE FILE", "save ファイル", "save ファイル"),
+            // 原文没有可区分大小写的字母时不处理
+            ("保存文件", "save file", "save file"),
+            ("123", "abc", "abc"),
+            ("A", "un", "Un"),
+            ("", "abc", "abc"),
+            ("SAVE FILE", "", ""),
+            ("SAVE FILE", "42 %", "42 %"),
+        ];
+        for (source, text, expected) in cases {
+            assert_eq!(
+                match_source_case(source, text),
+                expected,
+                "{source:?} {text:?}"
+            );
+        }
+    }
+
+    /// 测试转换为全角标点
+    #[test]
+    fn test_full_width_punctuation() {
+        let cases = [
+            ("你好, 世界!", "你好，世界！"),
+            ("是吗?好的.", "是吗？好的。"),
+            ("注意: 版本3.14已发布.", "注意：版本3.14已发布。"),
+            ("例如 e.g. 这样", "例如 e.g. 这样"),
+            ("等等...", "等等..."),
+            ("价格是1,000元", "价格是1,000元"),
+            ("访问 example.com 获取", "访问 example.com 获取"),
+            ("「好的」.", "「好的」。"),
+            ("はい, 分かりました.", "はい，分かりました。"),
+            ("안녕하세요, 세계!", "안녕하세요，세계！"),
+            ("你好，世界！", "你好，世界！"),
+            ("Hello, world!", "Hello, world!"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                convert_punctuation("", text, PunctuationStyle::FullWidth),
+                expected,
+                "{text:?}"
+            );
+        }
+    }
+
+    /// 测试转换为半角标点
+    #[test]
+    fn test_half_width_punctuation() {
+        let cases = [
+            ("你好，世界！", "你好, 世界!"),
+            ("是吗？好的。", "是吗? 好的."),
+            ("注意：版本3.14", "注意: 版本3.14"),
+            ("真的？！", "真的?!"),
+            ("好的。\n下一行", "好的.\n下一行"),
+            ("はい、分かりました。", "はい、分かりました."),
+            ("Hello，world", "Hello，world"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                convert_punctuation("", text, PunctuationStyle::HalfWidth),
+                expected,
+                "{text:?}"
+            );
+        }
+    }
+
+    /// 测试按原文选择标点风格
+    #[test]
+    fn test_source_punctuation_style() {
+        let style = PunctuationStyle::Source;
+        assert_eq!(
+            convert_punctuation("Hi, there!", "你好，朋友！", style),
+            "你好, 朋友!"
+        );
+        assert_eq!(
+            convert_punctuation("你好，朋友！", "你好, 朋友!", style),
+            "你好，朋友！"
+        );
+        assert_eq!(
+            convert_punctuation("Hi there", "你好，朋友！", style),
+            "你好，朋友！"
+        );
+    }
+
+    /// 测试去掉末尾句号
+    #[test]
+    fn test_trim_trailing_period() {
+        let cases = [
+            ("Save file", "Guardar archivo.", "Guardar archivo"),
+            ("Save file", "Guardar archivo. ", "Guardar archivo"),
+            ("Save file", "保存文件。", "保存文件"),
+            ("Save file", "ファイルを保存．", "ファイルを保存"),
+            ("Save file.", "Guardar archivo.", "Guardar archivo."),
+            ("保存文件。", "Save file.", "Save file."),
+            ("Loading", "Cargando...", "Cargando..."),
+            ("Save file", "Guardar archivo", "Guardar archivo"),
+            ("Save file", "Guardar?", "Guardar?"),
+            ("Save", ".", ""),
+            ("Save", "", ""),
+        ];
+        for (source, text, expected) in cases {
+            assert_eq!(
+                trim_trailing_period(source, text),
+                expected,
+                "{source:?} {text:?}"
+            );
+        }
+    }
+
+    /// 测试风格调整默认关闭，开启后在清理步骤之后执行
+    #[test]
+    fn test_style_options() {
+        let source = "SAVE FILE";
+        let output = "&quot;guardar archivo.&quot;";
+        assert_eq!(
+            postprocess(source, output, &PostprocessOptions::default()),
+            "guardar archivo."
+        );
+        let options = PostprocessOptions {
+            match_source_case: true,
+            trim_trailing_period_if_source_lacks_one: true,
+            punctuation_style: Some(PunctuationStyle::FullWidth),
+            ..Default::default()
+        };
+        assert_eq!(postprocess(source, output, &options), "GUARDAR ARCHIVO");
+        assert_eq!(postprocess("Save file", "保存文件.", &options), "保存文件");
+        assert_eq!(
+            postprocess("Hello, world", "你好, 世界", &options),
+            "你好，世界"
+        );
+    }
+
+    /// 测试关闭所有步骤时译文保持原样
+    #[test]
+    fn test_all_steps_disabled() {
+        let options = PostprocessOptions {
+            decode_entities: false,
+            unescape: false,
+            strip_quotes: false,
+            normalize_nfc: false,
+            ..Default::default()
+        };
+        let output = "\"&amp;\\n\"";
+        assert_eq!(postprocess("x", output, &options), output);
+    }
+
+    /// 测试后处理的幂等性
+    ///
+    /// 用确定性的伪随机序列组合容易出问题的片段，对每个组合验证重复处理结果不变
+    #[test]
+    fn test_postprocess_idempotent() {
+        let pieces = [
+            "&", "amp;", "&amp;", "#39;", "&#", "x4e2d;", "\\", "n", "u0026", "\"", "“", "”", "'",
+            "e\u{301}", "中", " ", ";", "&lt;", "\\u", "0041",
+        ];
+        let sources = ["", "&amp;", "\"quoted\"", "a\\b", "plain"];
+        let options = PostprocessOptions::default();
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            let mut text = String::new();
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let len = (seed % 8) as usize + 1;
+            let mut state = seed;
+            for _ in 0..len {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                text.push_str(pieces[(state >> 33) as usize % pieces.len()]);
+            }
+            let source = sources[(seed >> 40) as usize % sources.len()];
+            let once = postprocess(source, &text, &options);
+            let twice = postprocess(source, &once, &options);
+            assert_eq!(once, twice, "source: {source:?}, text: {text:?}");
+        }
+    }
+
+    /// 模糊测试：任意原文和译文的后处理都不会panic
+    #[test]
+    fn test_fuzz_postprocess() {
+        let corpus = [
+            "&quot;Tom &amp; Jerry&#39;s&quot; &#x4e2d;&#20013; &bogus; \\n\\t\\u00e9\\u",
+            "“Hello, world.” SAVE FILE",
+            "「你好，世界。」（注）",
+        ];
+        let inputs = fuzz::inputs(701, &corpus);
+        let options = PostprocessOptions {
+            decode_entities: true,
+            unescape: true,
+            strip_quotes: true,
+            normalize_nfc: true,
+            match_source_case: true,
+            punctuation_style: Some(PunctuationStyle::Source),
+            trim_trailing_period_if_source_lacks_one: true,
+        };
+        for (source, text) in inputs.iter().zip(inputs.iter().rev()) {
+            postprocess(source, text, &options);
+            for style in [PunctuationStyle::FullWidth, PunctuationStyle::HalfWidth] {
+                convert_punctuation(source, text, style);
+            }
+        }
+    }
+}
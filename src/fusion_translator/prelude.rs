@@ -0,0 +1,13 @@
+pub use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+pub use crate::fusion_translator::auto::AutoConfig;
+pub use crate::fusion_translator::request::{
+    ExecuteRequest, TranslationRequest, TranslationResponse,
+};
+pub use crate::fusion_translator::translate_options::TranslateOptions;
+pub use crate::fusion_translator::translator_error::{ApiError, ErrorKind, TranslatorError};
+pub use crate::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory, TranslatorStackConfig, TranslatorType,
+};
+pub use crate::fusion_translator::translator_options::TranslatorOptions;
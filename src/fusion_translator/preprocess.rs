@@ -0,0 +1,577 @@
+use crate::fusion_translator::postprocess::normalize_nfc;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::borrow::Cow;
+
+/// 视为普通空格的不换行空格
+///
+/// 不换行空格（U+00A0）、窄不换行空格（U+202F）和数字空格（U+2007）
+const NO_BREAK_SPACES: [char; 3] = ['\u{a0}', '\u{202f}', '\u{2007}'];
+
+/// 软连字符（U+00AD），只在换行处显示
+const SOFT_HYPHEN: char = '\u{ad}';
+
+/// 去除的零宽字符
+///
+/// 零宽空格（U+200B）、词连接符（U+2060）和字节顺序标记（U+FEFF）
+const ZERO_WIDTH: [char; 3] = ['\u{200b}', '\u{2060}', '\u{feff}'];
+
+/// 零宽不连接符（U+200C）
+const ZWNJ: char = '\u{200c}';
+
+/// 零宽连接符（U+200D）
+const ZWJ: char = '\u{200d}';
+
+/// 转换为直引号的弯引号
+const CURLY_QUOTES: [(char, char); 8] = [
+    ('\u{2018}', '\''),
+    ('\u{2019}', '\''),
+    ('\u{201a}', '\''),
+    ('\u{201b}', '\''),
+    ('\u{201c}', '"'),
+    ('\u{201d}', '"'),
+    ('\u{201e}', '"'),
+    ('\u{201f}', '"'),
+];
+
+/// Windows-1252在0x80至0x9F之间的标点
+///
+/// 按Latin-1解码Windows-1252文本时，这些标点会变成C1控制字符
+const WINDOWS_1252_PUNCTUATION: [(char, char); 11] = [
+    ('\u{82}', '\u{201a}'),
+    ('\u{84}', '\u{201e}'),
+    ('\u{85}', '\u{2026}'),
+    ('\u{8b}', '\u{2039}'),
+    ('\u{91}', '\u{2018}'),
+    ('\u{92}', '\u{2019}'),
+    ('\u{93}', '\u{201c}'),
+    ('\u{94}', '\u{201d}'),
+    ('\u{95}', '\u{2022}'),
+    ('\u{96}', '\u{2013}'),
+    ('\u{97}', '\u{2014}'),
+];
+
+/// 原文规范化配置
+///
+/// 用于清理从Word等编辑器粘贴的文本，每个字段控制一项转换。
+/// 所有转换都不会改变可见的字母和数字，默认开启除弯引号转换之外的全部转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputNormalization {
+    /// 把按Latin-1误解码的Windows-1252标点（U+0080至U+009F中的弯引号、破折号等）还原为对应字符
+    pub repair_windows_1252: bool,
+    /// 把不换行空格替换为普通空格
+    pub replace_no_break_spaces: bool,
+    /// 去除软连字符
+    pub remove_soft_hyphens: bool,
+    /// 去除零宽空格、词连接符和字节顺序标记，以及不影响字形的零宽连接符和零宽不连接符
+    pub strip_zero_width: bool,
+    /// 把弯引号转换为直引号
+    pub straighten_quotes: bool,
+    /// 进行Unicode NFC规范化
+    pub normalize_nfc: bool,
+}
+
+impl Default for InputNormalization {
+    fn default() -> Self {
+        Self {
+            repair_windows_1252: true,
+            replace_no_break_spaces: true,
+            remove_soft_hyphens: true,
+            strip_zero_width: true,
+            straighten_quotes: false,
+            normalize_nfc: true,
+        }
+    }
+}
+
+impl InputNormalization {
+    /// 设置是否把弯引号转换为直引号
+    ///
+    /// # 参数
+    /// - `enabled`: 是否开启
+    pub fn with_straighten_quotes(mut self, enabled: bool) -> Self {
+        self.straighten_quotes = enabled;
+        self
+    }
+}
+
+/// 按配置规范化原文
+///
+/// 依次执行Windows-1252标点还原、不换行空格替换、软连字符去除、零宽字符去除、弯引号转换和NFC规范化
+///
+/// # 参数
+/// - `text`: 原文
+/// - `options`: 规范化配置
+///
+/// # 返回值
+/// 规范化后的文本
+pub fn normalize_input(text: &str, options: &InputNormalization) -> String {
+    let mut result = text.to_string();
+    if options.repair_windows_1252 {
+        result = repair_windows_1252(&result);
+    }
+    if options.replace_no_break_spaces {
+        result = replace_no_break_spaces(&result);
+    }
+    if options.remove_soft_hyphens {
+        result = remove_soft_hyphens(&result);
+    }
+    if options.strip_zero_width {
+        result = strip_zero_width(&result);
+    }
+    if options.straighten_quotes {
+        result = straighten_quotes(&result);
+    }
+    if options.normalize_nfc {
+        result = normalize_nfc(&result);
+    }
+    result
+}
+
+/// 检查并清理发送给翻译服务的原文
+///
+/// 总是去除换行（`\n`、`\r`）和制表符之外的C0控制字符，
+/// 设置了`TranslateOptions::normalize_input`时再按配置规范化
+///
+/// # 参数
+/// - `text`: 原文
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 原文包含NUL字符时返回`TranslatorError::NulCharacter`
+pub(crate) fn apply<'a>(
+    text: &'a str,
+    options: &TranslateOptions,
+) -> Result<Cow<'a, str>, TranslatorError> {
+    let text = strip_control_chars(text)
+        .map_err(|offset| TranslatorError::NulCharacter { index: 0, offset })?;
+    Ok(match &options.normalize_input {
+        Some(normalization) => Cow::Owned(normalize_input(&text, normalization)),
+        None => text,
+    })
+}
+
+/// 检查并清理一组原文
+///
+/// # 参数
+/// - `query`: 原文数组
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 任一原文包含NUL字符时返回`TranslatorError::NulCharacter`
+pub(crate) fn apply_all<'a>(
+    query: &'a [String],
+    options: &TranslateOptions,
+) -> Result<Cow<'a, [String]>, TranslatorError> {
+    let mut cleaned = Vec::with_capacity(query.len());
+    let mut changed = false;
+    for (index, text) in query.iter().enumerate() {
+        let text = strip_control_chars(text)
+            .map_err(|offset| TranslatorError::NulCharacter { index, offset })?;
+        changed |= matches!(text, Cow::Owned(_));
+        cleaned.push(text);
+    }
+    Ok(match &options.normalize_input {
+        Some(normalization) => Cow::Owned(
+            cleaned
+                .iter()
+                .map(|q| normalize_input(q, normalization))
+                .collect(),
+        ),
+        None if changed => Cow::Owned(cleaned.into_iter().map(Cow::into_owned).collect()),
+        None => Cow::Borrowed(query),
+    })
+}
+
+/// 是否为需要去除的C0控制字符
+///
+/// 保留换行（`\n`、`\r`）和制表符
+fn is_stripped_control(c: char) -> bool {
+    c < ' ' && !matches!(c, '\n' | '\r' | '\t')
+}
+
+/// 去除换行和制表符之外的C0控制字符
+///
+/// 从二进制数据或终端输出复制的文本常带有这些字符，翻译服务通常会拒绝或截断这样的请求。
+/// 不包含控制字符时不复制文本
+///
+/// # 参数
+/// - `text`: 待处理的文本
+///
+/// # 返回值
+/// 文本包含NUL字符时返回其字节偏移，NUL通常意味着输入不是文本，不做静默删除
+pub fn strip_control_chars(text: &str) -> Result<Cow<'_, str>, usize> {
+    if let Some(offset) = text.find('\0') {
+        return Err(offset);
+    }
+    if !text.contains(is_stripped_control) {
+        return Ok(Cow::Borrowed(text));
+    }
+    Ok(Cow::Owned(
+        text.chars().filter(|c| !is_stripped_control(*c)).collect(),
+    ))
+}
+
+/// 把按Latin-1误解码的Windows-1252标点还原为对应字符
+///
+/// 只处理U+0080至U+009F中Windows-1252定义为标点的字符，其余C1控制字符保持不变
+///
+/// # 参数
+/// - `text`: 待处理的文本
+pub fn repair_windows_1252(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            WINDOWS_1252_PUNCTUATION
+                .iter()
+                .find(|(control, _)| *control == c)
+                .map_or(c, |(_, punctuation)| *punctuation)
+        })
+        .collect()
+}
+
+/// 把不换行空格（U+00A0、U+202F、U+2007）替换为普通空格
+///
+/// # 参数
+/// - `text`: 待处理的文本
+pub fn replace_no_break_spaces(text: &str) -> String {
+    text.replace(NO_BREAK_SPACES, " ")
+}
+
+/// 去除软连字符（U+00AD）
+///
+/// # 参数
+/// - `text`: 待处理的文本
+pub fn remove_soft_hyphens(text: &str) -> String {
+    text.replace(SOFT_HYPHEN, "")
+}
+
+/// 去除零宽空格（U+200B）、词连接符（U+2060）和字节顺序标记（U+FEFF）
+///
+/// 零宽连接符（U+200D）和零宽不连接符（U+200C）按所在文字决定：
+/// 在阿拉伯文（包括波斯文）、印度系文字等连写文字的字母之间会改变字形，予以保留，
+/// 零宽连接符在表情符号之间组成组合表情，也予以保留；其余位置（如拉丁字母单词中）不影响显示，
+/// 却会让部分翻译服务把一个单词拆成两个，予以去除
+///
+/// # 参数
+/// - `text`: 待处理的文本
+pub fn strip_zero_width(text: &str) -> String {
+    let chars = text
+        .chars()
+        .filter(|c| !ZERO_WIDTH.contains(c))
+        .collect::<Vec<_>>();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            if c != ZWJ && c != ZWNJ {
+                return true;
+            }
+            let prev = i.checked_sub(1).and_then(|i| chars.get(i)).copied();
+            let next = chars.get(i + 1).copied();
+            match (prev, next) {
+                (Some(prev), Some(next)) => {
+                    (is_joining_script(prev) && is_joining_script(next))
+                        || (c == ZWJ && is_emoji(prev) && is_emoji(next))
+                }
+                _ => false,
+            }
+        })
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// 判断字符是否属于零宽连接符和零宽不连接符会影响字形的文字
+///
+/// 包括阿拉伯文、叙利亚文、它拿文、西非书面文字、蒙古文、印度系文字、缅甸文和高棉文
+///
+/// # 参数
+/// - `c`: 待判断的字符
+fn is_joining_script(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0600..=0x07FF
+            | 0x0860..=0x08FF
+            | 0x0900..=0x0DFF
+            | 0x1000..=0x109F
+            | 0x1780..=0x17FF
+            | 0x1800..=0x18AF
+            | 0xA8E0..=0xA8FF
+            | 0xFB50..=0xFDFF
+            | 0xFE70..=0xFEFE
+    )
+}
+
+/// 判断字符是否可以出现在零宽连接符组合的表情符号中
+///
+/// 包括表情符号、肤色修饰符和变体选择符（U+FE0F）
+///
+/// # 参数
+/// - `c`: 待判断的字符
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x2190..=0x21FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x1F000..=0x1FAFF
+    )
+}
+
+/// 把弯引号（‘’‚‛“”„‟）转换为直引号（`'`和`"`）
+///
+/// # 参数
+/// - `text`: 待处理的文本
+pub fn straighten_quotes(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            CURLY_QUOTES
+                .iter()
+                .find(|(curly, _)| *curly == c)
+                .map_or(c, |(_, straight)| *straight)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::postprocess::normalize_nfc;
+    use crate::fusion_translator::preprocess::{
+        self, normalize_input, remove_soft_hyphens, repair_windows_1252, replace_no_break_spaces,
+        straighten_quotes, strip_control_chars, strip_zero_width, InputNormalization,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::borrow::Cow;
+
+    /// 测试各项转换只影响对应的字符
+    #[test]
+    fn test_transforms() {
+        assert_eq!(
+            replace_no_break_spaces("10\u{a0}km\u{202f}/\u{2007}h\u{2009}x"),
+            "10 km / h\u{2009}x"
+        );
+        assert_eq!(
+            remove_soft_hyphens("trans\u{ad}la\u{ad}tion-ready"),
+            "translation-ready"
+        );
+        assert_eq!(
+            strip_zero_width("\u{feff}a\u{200b}b\u{2060}c\u{200c}d\u{200d}e"),
+            "abcde"
+        );
+        assert_eq!(
+            straighten_quotes(
+                "\u{201c}It\u{2019}s\u{201d} \u{201e}ja\u{201f} \u{2039}x\u{203a} 「y」"
+            ),
+            "\"It's\" \"ja\" \u{2039}x\u{203a} 「y」"
+        );
+        assert_eq!(
+            repair_windows_1252("\u{93}Hi\u{94} \u{96} \u{85}\u{81}"),
+            "\u{201c}Hi\u{201d} \u{2013} \u{2026}\u{81}"
+        );
+    }
+
+    /// 测试按文字决定零宽连接符和零宽不连接符的去留
+    #[test]
+    fn test_joiners_by_script() {
+        let cases = [
+            // 波斯文的词内分隔和复数后缀
+            (
+                "می\u{200c}خواهم کتاب\u{200c}ها",
+                "می\u{200c}خواهم کتاب\u{200c}ها",
+            ),
+            // 印地文的半字形
+            ("क\u{94d}\u{200d}ष", "क\u{94d}\u{200d}ष"),
+            // 组合表情
+            (
+                "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+                "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+            ),
+            (
+                "\u{1f3f3}\u{fe0f}\u{200d}\u{1f308}",
+                "\u{1f3f3}\u{fe0f}\u{200d}\u{1f308}",
+            ),
+            // 拉丁字母、汉字之间和文字边界处没有作用
+            ("Auf\u{200c}lage Zero\u{200d}Width", "Auflage ZeroWidth"),
+            ("中\u{200d}文", "中文"),
+            ("کتاب\u{200c} ها\u{200c}", "کتاب ها"),
+            ("\u{200c}a\u{200d}\u{1f600}", "a\u{1f600}"),
+            ("کتاب\u{200c}book", "کتابbook"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(strip_zero_width(text), expected, "{:?}", text);
+        }
+    }
+
+    /// 测试默认配置保留弯引号，开启后转换为直引号
+    #[test]
+    fn test_normalize_input() {
+        let pasted = "\u{feff}\u{92}Cafe\u{301}\u{92}\u{a0}co\u{ad}operative";
+        let options = InputNormalization::default();
+        assert_eq!(
+            normalize_input(pasted, &options),
+            "\u{2019}Café\u{2019} cooperative"
+        );
+        assert_eq!(
+            normalize_input(pasted, &options.with_straighten_quotes(true)),
+            "'Café' cooperative"
+        );
+        let disabled = InputNormalization {
+            repair_windows_1252: false,
+            replace_no_break_spaces: false,
+            remove_soft_hyphens: false,
+            strip_zero_width: false,
+            straighten_quotes: false,
+            normalize_nfc: false,
+        };
+        assert_eq!(normalize_input(pasted, &disabled), pasted);
+    }
+
+    /// 随机测试规范化不会改变可见的字母和数字，并且重复执行结果不变
+    #[test]
+    fn test_letters_unchanged() {
+        const ALPHABET: &[char] = &[
+            'a', 'Z', 'é', 'e', '\u{301}', 'ß', '中', 'あ', '한', 'ب', 'क', '\u{94d}', '7', '٣',
+            ' ', '\u{a0}', '\u{202f}', '\u{ad}', '\u{200b}', '\u{200c}', '\u{200d}', '\u{feff}',
+            '\u{2060}', '\u{2018}', '\u{201d}', '\u{92}', '\u{96}', '\u{81}', '-', '"',
+        ];
+        const COMBINING: &[char] = &['\u{301}', '\u{94d}'];
+        let options = InputNormalization::default().with_straighten_quotes(true);
+        let letters = |s: &str| {
+            normalize_nfc(s)
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        };
+        let mut rng = StdRng::seed_from_u64(650);
+        for _ in 0..500 {
+            let len = rng.random_range(0..24);
+            let mut text = String::new();
+            for _ in 0..len {
+                let c = ALPHABET[rng.random_range(0..ALPHABET.len())];
+                // 组合符号只跟在字母后面，孤立的组合符号本身就没有确定的显示效果
+                if COMBINING.contains(&c) && !text.ends_with(char::is_alphabetic) {
+                    continue;
+                }
+                text.push(c);
+            }
+            let normalized = normalize_input(&text, &options);
+            assert_eq!(letters(&normalized), letters(&text), "{:?}", text);
+            assert_eq!(
+                normalize_input(&normalized, &options),
+                normalized,
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    /// 测试去除控制字符时保留换行和制表符，NUL返回字节偏移
+    #[test]
+    fn test_strip_control_chars() {
+        assert!(matches!(
+            strip_control_chars("a\tb\r\nc"),
+            Ok(Cow::Borrowed("a\tb\r\nc"))
+        ));
+        assert_eq!(
+            strip_control_chars("\u{1b}[1mbold\u{1b}[0m\u{7}\u{8}").unwrap(),
+            "[1mbold[0m"
+        );
+        // DEL和C1控制字符不属于C0，保留给Windows-1252修复处理
+        assert_eq!(
+            strip_control_chars("a\u{7f}\u{93}").unwrap(),
+            "a\u{7f}\u{93}"
+        );
+        assert_eq!(strip_control_chars("中\u{1}文\0x"), Err(7));
+
+        let query = ["ok".to_string(), "x\u{2}".to_string(), "中\0".to_string()];
+        let error = preprocess::apply_all(&query, &TranslateOptions::default()).unwrap_err();
+        assert!(matches!(
+            error,
+            TranslatorError::NulCharacter {
+                index: 2,
+                offset: 3
+            }
+        ));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        let cleaned = preprocess::apply_all(&query[..2], &TranslateOptions::default()).unwrap();
+        assert_eq!(&*cleaned, ["ok", "x"]);
+        let clean = ["a".to_string()];
+        assert!(matches!(
+            preprocess::apply_all(&clean, &TranslateOptions::default()),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
+
+    /// 测试任意字节（按UTF-8有损解码）都不会导致崩溃
+    ///
+    /// 结果要么是只去除了控制字符的文本，要么是指向NUL字符的`NulCharacter`错误
+    #[test]
+    fn test_byte_soup() {
+        let mut rng = StdRng::seed_from_u64(660);
+        let options = TranslateOptions {
+            normalize_input: Some(InputNormalization::default()),
+            ..Default::default()
+        };
+        for round in 0..2000 {
+            let len = rng.random_range(0..48);
+            let bytes = (0..len)
+                .map(|_| match rng.random_range(0..4) {
+                    // 偏向控制字符和多字节序列的起始字节
+                    0 => rng.random_range(0..0x20),
+                    1 => rng.random_range(0xc0..=0xff),
+                    _ => rng.random(),
+                })
+                .collect::<Vec<u8>>();
+            let text = String::from_utf8_lossy(&bytes);
+            let expected = text
+                .chars()
+                .filter(|c| *c >= ' ' || matches!(c, '\n' | '\r' | '\t'))
+                .collect::<String>();
+            let default = TranslateOptions::default();
+            let options = if round % 2 == 0 { &default } else { &options };
+            match preprocess::apply(&text, options) {
+                Ok(cleaned) => {
+                    assert!(!text.contains('\0'), "{:?}", bytes);
+                    assert!(
+                        cleaned
+                            .chars()
+                            .all(|c| c >= ' ' || matches!(c, '\n' | '\r' | '\t')),
+                        "{:?}",
+                        bytes
+                    );
+                    if options.normalize_input.is_none() {
+                        assert_eq!(cleaned, expected, "{:?}", bytes);
+                    }
+                }
+                Err(TranslatorError::NulCharacter { index, offset }) => {
+                    assert_eq!(index, 0);
+                    assert_eq!(text.as_bytes()[offset], 0, "{:?}", bytes);
+                    assert!(!text[..offset].contains('\0'));
+                }
+                Err(e) => panic!("{:?}: {:?}", bytes, e),
+            }
+        }
+    }
+
+    /// 模糊测试：任意文本的规范化和控制字符检查都不会panic
+    #[test]
+    fn test_fuzz_normalize() {
+        let corpus = [
+            "\u{201c}Caf\u{e9}\u{201d}\u{a0}co\u{ad}op\u{200b} don\u{2019}t",
+            "cafÃ© â€œquotedâ€\u{9d} Ã\u{a0}",
+            "tab\there\u{7}bell\u{1b}[0m",
+        ];
+        let options = InputNormalization {
+            repair_windows_1252: true,
+            replace_no_break_spaces: true,
+            remove_soft_hyphens: true,
+            strip_zero_width: true,
+            straighten_quotes: true,
+            normalize_nfc: true,
+        };
+        for input in fuzz::inputs(701, &corpus) {
+            normalize_input(&input, &options);
+            let _ = strip_control_chars(&input);
+        }
+    }
+}
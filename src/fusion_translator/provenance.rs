@@ -0,0 +1,262 @@
+use crate::fusion_translator::async_translator::{
+    TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::translator_error::ErrorKind;
+use std::fmt;
+
+/// 译文经过的一个组合环节
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProvenanceEvent {
+    /// `cache::CachedTranslator`的查询结果：命中和未命中的文本数，单个文本时其中一个为1
+    Cache {
+        /// 命中缓存的文本数
+        hits: usize,
+        /// 未命中、交给内部翻译器的文本数
+        misses: usize,
+    },
+    /// `retry::RetryTranslator`的尝试次数，第一次就成功时为1
+    Retry {
+        /// 包括最后一次成功在内的尝试次数
+        attempts: u32,
+    },
+    /// `fallback::FallbackTranslator`依次尝试的翻译器
+    Fallback {
+        /// 失败的翻译器名称及错误类别，按尝试顺序排列
+        failed: Vec<(String, ErrorKind)>,
+        /// 给出结果的翻译器名称，从延迟预算的缓存中取出旧译文时为None
+        engine: Option<String>,
+    },
+    /// 超长文本或超出批量上限的数组拆分为多段分别请求
+    Chunked {
+        /// 拆分后的段数
+        chunks: usize,
+    },
+}
+
+impl fmt::Display for ProvenanceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cache { hits: 1, misses: 0 } => f.write_str("cache hit"),
+            Self::Cache { hits: 0, misses: 1 } => f.write_str("cache miss"),
+            Self::Cache { hits, misses } => write!(f, "cache: {} hit, {} miss", hits, misses),
+            Self::Retry { attempts: 1 } => f.write_str("retry: 1 attempt"),
+            Self::Retry { attempts } => write!(f, "retry: {} attempts", attempts),
+            Self::Fallback { failed, engine } => {
+                f.write_str("fallback: ")?;
+                for (name, kind) in failed {
+                    write!(f, "{} failed ({}), ", name, kind)?;
+                }
+                match engine {
+                    Some(engine) => write!(f, "answered by {}", engine),
+                    None => f.write_str("served from stale cache"),
+                }
+            }
+            Self::Chunked { chunks } => write!(f, "split into {} chunks", chunks),
+        }
+    }
+}
+
+/// 译文的来历
+///
+/// 组合翻译器在内部翻译器返回结果后各自追加一个事件，因此事件按从内到外的顺序排列：
+/// 例如`RetryTranslator`包装`CachedTranslator`、缓存再包装`FallbackTranslator`时，
+/// 未命中缓存的结果依次为`Fallback`、`Cache`、`Retry`。命中缓存时缓存内部的环节没有执行，不会出现
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// 按从内到外的顺序排列的事件
+    events: Vec<ProvenanceEvent>,
+}
+
+impl Provenance {
+    /// 追加一个事件
+    ///
+    /// # 参数
+    /// - `event`: 当前环节的事件
+    pub fn push(&mut self, event: ProvenanceEvent) {
+        self.events.push(event);
+    }
+
+    /// 按从内到外的顺序排列的事件
+    pub fn events(&self) -> &[ProvenanceEvent] {
+        &self.events
+    }
+
+    /// 是否没有任何事件（结果直接来自翻译器）
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl fmt::Display for Provenance {
+    /// 按从内到外的顺序用`; `连接各事件，没有事件时为`direct`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.events.is_empty() {
+            return f.write_str("direct");
+        }
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+/// 带有附加信息的翻译结果
+pub(crate) trait Detailed {
+    /// 结果的附加信息
+    fn detail_mut(&mut self) -> &mut TranslationDetail;
+
+    /// 追加一个来历事件
+    ///
+    /// # 参数
+    /// - `event`: 当前环节的事件
+    fn trace(&mut self, event: ProvenanceEvent) {
+        self.detail_mut().provenance.push(event);
+    }
+}
+
+impl Detailed for TranslationOutput {
+    fn detail_mut(&mut self) -> &mut TranslationDetail {
+        &mut self.detail
+    }
+}
+
+impl Detailed for TranslationListOutput {
+    fn detail_mut(&mut self) -> &mut TranslationDetail {
+        &mut self.detail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::cache::CachedTranslator;
+    use crate::fusion_translator::fallback::FallbackTranslator;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::provenance::{Provenance, ProvenanceEvent};
+    use crate::fusion_translator::retry::RetryTranslator;
+    use crate::fusion_translator::translator_error::ErrorKind;
+    use std::sync::Arc;
+
+    /// 创建`retry(cache(fallback[a, b]))`三层组合
+    fn stack(a: MockTranslator, b: MockTranslator) -> RetryTranslator {
+        let fallback = FallbackTranslator::new(vec![
+            ("a".to_string(), Arc::new(a) as Arc<dyn AsyncTranslator>),
+            ("b".to_string(), Arc::new(b.with_suffix("-b"))),
+        ]);
+        let cache = CachedTranslator::new(Arc::new(fallback), 10);
+        RetryTranslator::new(Arc::new(cache), 2)
+    }
+
+    /// 翻译`hello`并返回来历事件
+    async fn events(translator: &RetryTranslator) -> Vec<ProvenanceEvent> {
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        output.detail.provenance.events().to_vec()
+    }
+
+    /// 测试未命中时记录每一层，命中时缓存内部的环节不再出现
+    #[tokio::test]
+    async fn test_miss_then_hit() {
+        let translator = stack(MockTranslator::new(), MockTranslator::new());
+        assert_eq!(
+            events(&translator).await,
+            vec![
+                ProvenanceEvent::Fallback {
+                    failed: vec![],
+                    engine: Some("a".to_string()),
+                },
+                ProvenanceEvent::Cache { hits: 0, misses: 1 },
+                ProvenanceEvent::Retry { attempts: 1 },
+            ]
+        );
+        assert_eq!(
+            events(&translator).await,
+            vec![
+                ProvenanceEvent::Cache { hits: 1, misses: 0 },
+                ProvenanceEvent::Retry { attempts: 1 },
+            ]
+        );
+    }
+
+    /// 测试所有翻译器暂时失败后重试成功时记录尝试次数
+    #[tokio::test(start_paused = true)]
+    async fn test_miss_with_retry() {
+        let translator = stack(
+            MockTranslator::new().with_transient_failures(1),
+            MockTranslator::new().with_transient_failures(1),
+        );
+        assert_eq!(
+            events(&translator).await,
+            vec![
+                ProvenanceEvent::Fallback {
+                    failed: vec![],
+                    engine: Some("a".to_string()),
+                },
+                ProvenanceEvent::Cache { hits: 0, misses: 1 },
+                ProvenanceEvent::Retry { attempts: 2 },
+            ]
+        );
+    }
+
+    /// 测试故障转移记录失败的翻译器及错误类别
+    #[tokio::test]
+    async fn test_fallback() {
+        let translator = stack(
+            MockTranslator::new().reject_on("hello"),
+            MockTranslator::new(),
+        );
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO-b");
+        assert_eq!(
+            output.detail.provenance.events(),
+            [
+                ProvenanceEvent::Fallback {
+                    failed: vec![("a".to_string(), ErrorKind::ContentRejected)],
+                    engine: Some("b".to_string()),
+                },
+                ProvenanceEvent::Cache { hits: 0, misses: 1 },
+                ProvenanceEvent::Retry { attempts: 1 },
+            ]
+        );
+        assert_eq!(
+            output.detail.provenance.to_string(),
+            "fallback: a failed (content_rejected), answered by b; cache miss; retry: 1 attempt"
+        );
+    }
+
+    /// 测试批量翻译记录缓存命中数和分块数
+    #[tokio::test]
+    async fn test_batch_chunks() {
+        let mock = MockTranslator::new().with_limits(Some(2), None);
+        let cache = CachedTranslator::new(Arc::new(mock), 10);
+        cache
+            .translate("a", None, &Language::Chinese)
+            .await
+            .unwrap();
+        let query = ["a", "b", "c", "d", "e", "f"].map(String::from);
+        let output = cache
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.detail.provenance.events(),
+            [
+                ProvenanceEvent::Chunked { chunks: 3 },
+                ProvenanceEvent::Cache { hits: 1, misses: 5 },
+            ]
+        );
+        assert_eq!(
+            output.detail.provenance.to_string(),
+            "split into 3 chunks; cache: 1 hit, 5 miss"
+        );
+        assert_eq!(Provenance::default().to_string(), "direct");
+    }
+}
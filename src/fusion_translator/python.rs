@@ -0,0 +1,436 @@
+// PyO3 0.22为带默认值的参数生成的代码会触发clippy的useless_conversion误报
+#![allow(clippy::useless_conversion)]
+
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::mock_translator::MockTranslator;
+use crate::fusion_translator::negotiate::parse_language_tag;
+use crate::fusion_translator::translator_error::ErrorKind;
+use crate::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory, TranslatorType,
+};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyString};
+use std::sync::Arc;
+
+create_exception!(
+    fusion_translator,
+    TranslatorError,
+    PyException,
+    "翻译失败，其他翻译异常的基类"
+);
+create_exception!(
+    fusion_translator,
+    NetworkError,
+    TranslatorError,
+    "网络请求失败"
+);
+create_exception!(
+    fusion_translator,
+    ApiError,
+    TranslatorError,
+    "翻译服务返回错误"
+);
+create_exception!(
+    fusion_translator,
+    LanguageError,
+    TranslatorError,
+    "无法识别或翻译器不支持的语言"
+);
+create_exception!(
+    fusion_translator,
+    NoResponseError,
+    TranslatorError,
+    "翻译服务未返回结果"
+);
+create_exception!(
+    fusion_translator,
+    TooLongError,
+    TranslatorError,
+    "请求文本过长"
+);
+create_exception!(
+    fusion_translator,
+    CancelledError,
+    TranslatorError,
+    "翻译已取消"
+);
+create_exception!(
+    fusion_translator,
+    UntranslatedError,
+    TranslatorError,
+    "译文疑似未翻译"
+);
+create_exception!(
+    fusion_translator,
+    InvalidInputError,
+    TranslatorError,
+    "参数或配置不正确"
+);
+create_exception!(
+    fusion_translator,
+    RateLimitedError,
+    TranslatorError,
+    "请求被限流"
+);
+create_exception!(
+    fusion_translator,
+    ContentRejectedError,
+    TranslatorError,
+    "翻译服务因内容审核拒绝翻译"
+);
+
+/// 把翻译错误转换为对应种类的Python异常
+///
+/// # 参数
+/// - `e`: 翻译错误
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    let message = format!("{:#}", e);
+    match ErrorKind::of(&e) {
+        ErrorKind::Network => NetworkError::new_err(message),
+        ErrorKind::Api => ApiError::new_err(message),
+        ErrorKind::Language => LanguageError::new_err(message),
+        ErrorKind::NoResponse => NoResponseError::new_err(message),
+        ErrorKind::TooLong => TooLongError::new_err(message),
+        ErrorKind::Cancelled => CancelledError::new_err(message),
+        ErrorKind::Untranslated => UntranslatedError::new_err(message),
+        ErrorKind::InvalidInput => InvalidInputError::new_err(message),
+        ErrorKind::RateLimited => RateLimitedError::new_err(message),
+        ErrorKind::ContentRejected => ContentRejectedError::new_err(message),
+        ErrorKind::Unknown => TranslatorError::new_err(message),
+    }
+}
+
+/// 解析语言代码
+///
+/// # 参数
+/// - `code`: 语言代码，接受ISO 639-1/639-3代码和`zh-Hant`等BCP 47标签
+fn parse_language(code: &str) -> PyResult<Language> {
+    parse_language_tag(code)
+        .ok_or_else(|| LanguageError::new_err(format!("unknown language: {}", code)))
+}
+
+/// 解析可选的源语言，`None`和`auto`表示自动检测
+///
+/// # 参数
+/// - `code`: 语言代码
+fn parse_source(code: Option<&str>) -> PyResult<Option<Language>> {
+    match code {
+        None | Some("auto") => Ok(None),
+        Some(code) => parse_language(code).map(Some),
+    }
+}
+
+/// 语言在Python接口中使用的代码
+///
+/// 优先使用ISO 639-1代码，没有时使用639-3代码；繁体中文为`zh-Hant`
+///
+/// # 参数
+/// - `lang`: 语言
+///
+/// # 返回值
+/// 语言代码，语言没有ISO代码时为None
+fn language_code(lang: &Language) -> Option<String> {
+    if *lang == Language::ChineseTraditional {
+        return Some("zh-Hant".to_string());
+    }
+    lang.to_639_1()
+        .or_else(|| lang.to_639_3())
+        .map(str::to_string)
+}
+
+/// 把语言列表转换为代码列表，跳过没有代码的语言
+///
+/// # 参数
+/// - `languages`: 语言列表
+fn language_codes(languages: &[Language]) -> Vec<String> {
+    languages.iter().filter_map(language_code).collect()
+}
+
+/// 把关键字参数中的凭据转换为JSON值
+///
+/// # 参数
+/// - `value`: 参数值，支持字符串、布尔值和数字
+fn credential_value(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    // bool是int的子类，需要先于整数判断
+    if value.is_instance_of::<PyBool>() {
+        Ok(value.extract::<bool>()?.into())
+    } else if value.is_instance_of::<PyInt>() {
+        Ok(value.extract::<i64>()?.into())
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(value.extract::<f64>()?.into())
+    } else if value.is_instance_of::<PyString>() {
+        Ok(value.extract::<String>()?.into())
+    } else {
+        Err(InvalidInputError::new_err(format!(
+            "unsupported credential value: {}",
+            value.get_type().name()?
+        )))
+    }
+}
+
+/// 根据引擎名称和凭据创建翻译器
+///
+/// 凭据按`TranslatorConfig`的字段名反序列化，与配置文件相同；
+/// 除工厂支持的翻译器外，还接受`mock`引擎，便于在不联网的情况下测试接入
+///
+/// # 参数
+/// - `engine`: 引擎名称或别名，也接受`baidu_extended`等配置变体
+/// - `credentials`: 凭据
+fn build_translator(
+    engine: &str,
+    credentials: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Arc<dyn AsyncTranslator>> {
+    if engine.eq_ignore_ascii_case("mock") {
+        return Ok(Arc::new(MockTranslator::new()));
+    }
+    let mut config = serde_json::Map::new();
+    if let Some(credentials) = credentials {
+        for (key, value) in credentials.iter() {
+            config.insert(key.extract::<String>()?, credential_value(&value)?);
+        }
+    }
+    let tag = TranslatorType::parse(engine).map_or(engine, |t| t.as_str());
+    config.insert("engine".to_string(), tag.into());
+    let config: TranslatorConfig = serde_json::from_value(config.into())
+        .map_err(|e| InvalidInputError::new_err(format!("invalid config: {}", e)))?;
+    Ok(TranslatorFactory::create(config))
+}
+
+/// Python中的翻译器
+///
+/// 持有独立的单线程运行时，翻译时释放GIL并在运行时上阻塞等待结果
+#[pyclass(name = "Translator", module = "fusion_translator", frozen)]
+pub struct PyTranslator {
+    /// 翻译器实例
+    translator: Arc<dyn AsyncTranslator>,
+    /// 执行异步翻译的运行时
+    runtime: tokio::runtime::Runtime,
+}
+
+#[pymethods]
+impl PyTranslator {
+    /// 创建翻译器
+    ///
+    /// # 参数
+    /// - `engine`: 引擎名称，如`baidu`、`youdao`、`mymemory`
+    /// - `credentials`: 凭据，如`app_id="..."`、`key="..."`
+    #[new]
+    #[pyo3(signature = (engine, **credentials))]
+    fn new(engine: &str, credentials: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let translator = build_translator(engine, credentials)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| TranslatorError::new_err(e.to_string()))?;
+        Ok(Self {
+            translator,
+            runtime,
+        })
+    }
+
+    /// 翻译文本
+    ///
+    /// # 参数
+    /// - `text`: 待翻译的文本
+    /// - `to`: 目标语言代码
+    /// - `from_`: 源语言代码，None或`auto`表示自动检测
+    ///
+    /// # 返回值
+    /// 译文
+    #[pyo3(signature = (text, to, from_=None))]
+    fn translate(
+        &self,
+        py: Python<'_>,
+        text: String,
+        to: &str,
+        from_: Option<&str>,
+    ) -> PyResult<String> {
+        let to = parse_language(to)?;
+        let from = parse_source(from_)?;
+        py.allow_threads(|| {
+            self.runtime
+                .block_on(self.translator.translate(&text, from, &to))
+        })
+        .map(|output| output.text)
+        .map_err(to_py_err)
+    }
+
+    /// 批量翻译文本
+    ///
+    /// # 参数
+    /// - `texts`: 待翻译的文本列表
+    /// - `to`: 目标语言代码
+    /// - `from_`: 源语言代码，None或`auto`表示自动检测
+    ///
+    /// # 返回值
+    /// 与输入顺序一致的译文列表
+    #[pyo3(signature = (texts, to, from_=None))]
+    fn translate_batch(
+        &self,
+        py: Python<'_>,
+        texts: Vec<String>,
+        to: &str,
+        from_: Option<&str>,
+    ) -> PyResult<Vec<String>> {
+        let to = parse_language(to)?;
+        let from = parse_source(from_)?;
+        py.allow_threads(|| {
+            self.runtime
+                .block_on(self.translator.translate_vec(&texts, from, &to))
+        })
+        .map(|output| output.text)
+        .map_err(to_py_err)
+    }
+
+    /// 翻译器支持的语言代码
+    ///
+    /// # 返回值
+    /// 语言代码列表，翻译器未声明支持范围时为所有语言
+    fn supported_languages(&self) -> Vec<String> {
+        match self.translator.supported_languages() {
+            Some(languages) => language_codes(&languages),
+            None => language_codes(&Language::all()),
+        }
+    }
+}
+
+/// 所有语言的代码
+///
+/// # 返回值
+/// 语言代码列表
+#[pyfunction]
+fn supported_languages() -> Vec<String> {
+    language_codes(&Language::all())
+}
+
+/// Python模块入口
+///
+/// # 参数
+/// - `m`: 模块对象
+#[pymodule]
+fn fusion_translator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyTranslator>()?;
+    m.add_function(wrap_pyfunction!(supported_languages, m)?)?;
+    m.add("TranslatorError", py.get_type_bound::<TranslatorError>())?;
+    m.add("NetworkError", py.get_type_bound::<NetworkError>())?;
+    m.add("ApiError", py.get_type_bound::<ApiError>())?;
+    m.add("LanguageError", py.get_type_bound::<LanguageError>())?;
+    m.add("NoResponseError", py.get_type_bound::<NoResponseError>())?;
+    m.add("TooLongError", py.get_type_bound::<TooLongError>())?;
+    m.add("CancelledError", py.get_type_bound::<CancelledError>())?;
+    m.add(
+        "UntranslatedError",
+        py.get_type_bound::<UntranslatedError>(),
+    )?;
+    m.add(
+        "InvalidInputError",
+        py.get_type_bound::<InvalidInputError>(),
+    )?;
+    m.add("RateLimitedError", py.get_type_bound::<RateLimitedError>())?;
+    m.add(
+        "ContentRejectedError",
+        py.get_type_bound::<ContentRejectedError>(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::python::*;
+    use pyo3::types::PyDict;
+
+    /// 在导入了扩展模块的解释器中执行Python代码
+    ///
+    /// # 参数
+    /// - `code`: 要执行的代码，可使用全局变量`ft`访问模块
+    fn run_python(code: &str) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "fusion_translator").unwrap();
+            fusion_translator(&module).unwrap();
+            let globals = PyDict::new_bound(py);
+            globals.set_item("ft", module).unwrap();
+            if let Err(e) = py.run_bound(code, Some(&globals), None) {
+                e.print(py);
+                panic!("python code failed");
+            }
+        });
+    }
+
+    /// 测试通过Python接口翻译单个文本和批量文本
+    #[test]
+    fn test_translate_and_batch() {
+        run_python(
+            r#"
+t = ft.Translator("mock")
+assert t.translate("hello", "zh") == "HELLO"
+assert t.translate("hello", to="zh", from_="en") == "HELLO"
+assert t.translate_batch(["a", "b"], "zh") == ["A", "B"]
+"#,
+        );
+    }
+
+    /// 测试语言代码列表和未知语言的异常
+    #[test]
+    fn test_languages() {
+        run_python(
+            r#"
+codes = ft.supported_languages()
+assert "en" in codes and "zh" in codes and "zh-Hant" in codes
+assert ft.Translator("mock").supported_languages() == codes
+try:
+    ft.Translator("mock").translate("hello", "not-a-language")
+    raise AssertionError("expected LanguageError")
+except ft.LanguageError as e:
+    assert isinstance(e, ft.TranslatorError)
+"#,
+        );
+    }
+
+    /// 测试未知引擎和缺少凭据时抛出参数异常
+    #[test]
+    fn test_invalid_config() {
+        run_python(
+            r#"
+for args, kwargs in [(("nope",), {}), (("baidu",), {"app_id": "id"})]:
+    try:
+        ft.Translator(*args, **kwargs)
+        raise AssertionError("expected InvalidInputError")
+    except ft.InvalidInputError:
+        pass
+ft.Translator("baidu", app_id="id", key="secret")
+ft.Translator("mymemory_filtered", machine_translation=False)
+"#,
+        );
+    }
+
+    /// 测试错误种类映射到不同的异常类
+    #[test]
+    fn test_error_kinds_map_to_exceptions() {
+        use crate::fusion_translator::translator_error::TranslatorError as Error;
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let cases = [
+                (
+                    Error::RequestTooLong(10, 5),
+                    py.get_type_bound::<TooLongError>(),
+                ),
+                (Error::Cancelled(1), py.get_type_bound::<CancelledError>()),
+                (Error::NoLanguage, py.get_type_bound::<LanguageError>()),
+                (Error::NoResponse, py.get_type_bound::<NoResponseError>()),
+            ];
+            for (error, expected) in cases {
+                let err = to_py_err(error.into());
+                assert!(err.get_type_bound(py).is(&expected));
+                assert!(err.is_instance_of::<TranslatorError>(py));
+            }
+            let err = to_py_err(anyhow::anyhow!("other"));
+            assert!(err
+                .get_type_bound(py)
+                .is(&py.get_type_bound::<TranslatorError>()));
+        });
+    }
+}
@@ -0,0 +1,290 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::batch::validate_batch;
+use crate::fusion_translator::long_text::detect_script;
+use crate::fusion_translator::postprocess::is_cjk;
+use crate::fusion_translator::similarity::{char_ngram_similarity, token_jaccard};
+use crate::fusion_translator::translator_error::TranslatorError;
+
+/// 默认的相似度阈值，回译相似度低于该值时译文值得人工检查
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// 回译检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityReport {
+    /// 原文
+    pub source: String,
+    /// 译文
+    pub translation: String,
+    /// 把译文翻译回源语言的结果
+    pub back_translation: String,
+    /// 原文与回译的相似度，范围0到1
+    pub similarity: f32,
+}
+
+impl QualityReport {
+    /// 相似度是否低于阈值
+    ///
+    /// # 参数
+    /// - `threshold`: 阈值，通常使用`DEFAULT_SIMILARITY_THRESHOLD`
+    pub fn is_suspicious(&self, threshold: f32) -> bool {
+        self.similarity < threshold
+    }
+}
+
+/// 计算两段文本的相似度
+///
+/// 先进行NFC规范化并转为小写，再按文字选择比较单位：
+/// 任一文本包含中日韩文字时使用`similarity::char_ngram_similarity`（字素簇二元组，单字片段使用单字），
+/// 否则使用单词；标点和空白不参与比较。结果为两组单位的Dice系数（按出现次数计），
+/// 单词的Dice系数由`similarity::token_jaccard`换算
+///
+/// # 参数
+/// - `a`: 第一段文本
+/// - `b`: 第二段文本
+///
+/// # 返回值
+/// 相似度，范围0到1；两段文本都没有可比较的内容时为1
+pub fn similarity(a: &str, b: &str) -> f32 {
+    if a.chars().chain(b.chars()).any(is_cjk) {
+        return char_ngram_similarity(a, b);
+    }
+    // 按出现次数计时，Dice = 2J / (1 + J)
+    let jaccard = token_jaccard(a, b);
+    2.0 * jaccard / (1.0 + jaccard)
+}
+
+/// 确定回译的目标语言
+///
+/// # 参数
+/// - `text`: 原文
+/// - `from`: 调用方指定的源语言
+fn source_language(text: &str, from: Option<Language>) -> Result<Language, TranslatorError> {
+    from.or_else(|| detect_script(text))
+        .ok_or(TranslatorError::NoLanguage)
+}
+
+/// 回译检查单个文本
+///
+/// 用`forward`把原文翻译为目标语言，再用`backward`把译文翻译回源语言，计算原文与回译的相似度。
+/// 两个翻译器可以是同一个
+///
+/// # 参数
+/// - `forward`: 正向翻译使用的翻译器
+/// - `backward`: 回译使用的翻译器
+/// - `text`: 原文
+/// - `from`: 源语言，None时按文字检测，无法检测时返回`TranslatorError::NoLanguage`
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 回译检查结果
+pub async fn back_translate_check(
+    forward: &dyn AsyncTranslator,
+    backward: &dyn AsyncTranslator,
+    text: &str,
+    from: Option<Language>,
+    to: &Language,
+) -> anyhow::Result<QualityReport> {
+    let source = source_language(text, from)?;
+    let translation = forward.translate(text, from, to).await?.text;
+    let back_translation = backward
+        .translate(&translation, Some(*to), &source)
+        .await?
+        .text;
+    Ok(QualityReport {
+        similarity: similarity(text, &back_translation),
+        source: text.to_string(),
+        translation,
+        back_translation,
+    })
+}
+
+/// 回译检查一组文本
+///
+/// 正向翻译和回译各调用一次`translate_vec`
+///
+/// # 参数
+/// - `forward`: 正向翻译使用的翻译器
+/// - `backward`: 回译使用的翻译器
+/// - `texts`: 原文数组
+/// - `from`: 源语言，None时按全部原文的文字检测
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 与`texts`一一对应的检查结果
+pub async fn back_translate_check_batch(
+    forward: &dyn AsyncTranslator,
+    backward: &dyn AsyncTranslator,
+    texts: &[String],
+    from: Option<Language>,
+    to: &Language,
+) -> anyhow::Result<Vec<QualityReport>> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+    source_language(&texts.concat(), from)?;
+    let translations = forward.translate_vec(texts, from, to).await?;
+    let translations = validate_batch(texts.len(), translations.text)?;
+    verify_translations(backward, texts, &translations, from, to).await
+}
+
+/// 回译检查已有的译文
+///
+/// 适用于译文已经由其他流程（如带检查点的批量翻译）得到的场景，只调用一次回译
+///
+/// # 参数
+/// - `backward`: 回译使用的翻译器
+/// - `sources`: 原文数组
+/// - `translations`: 与原文一一对应的译文数组
+/// - `from`: 源语言，None时按全部原文的文字检测
+/// - `to`: 译文的语言
+///
+/// # 返回值
+/// 与`sources`一一对应的检查结果
+pub async fn verify_translations(
+    backward: &dyn AsyncTranslator,
+    sources: &[String],
+    translations: &[String],
+    from: Option<Language>,
+    to: &Language,
+) -> anyhow::Result<Vec<QualityReport>> {
+    let count = validate_batch(sources.len(), translations.to_vec())?.len();
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let source = source_language(&sources.concat(), from)?;
+    let back = backward
+        .translate_vec(translations, Some(*to), &source)
+        .await?;
+    let back = validate_batch(count, back.text)?;
+    Ok(sources
+        .iter()
+        .zip(translations)
+        .zip(back)
+        .map(|((source, translation), back_translation)| QualityReport {
+            similarity: similarity(source, &back_translation),
+            source: source.clone(),
+            translation: translation.clone(),
+            back_translation,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{
+        AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    };
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::quality::{
+        back_translate_check, back_translate_check_batch, similarity, DEFAULT_SIMILARITY_THRESHOLD,
+    };
+    use std::collections::HashMap;
+
+    /// 按固定词表翻译的测试翻译器，词表中没有的文本原样返回
+    struct Dictionary(HashMap<&'static str, &'static str>);
+
+    #[async_trait::async_trait]
+    impl AsyncTranslator for Dictionary {
+        fn local(&self) -> bool {
+            true
+        }
+
+        async fn translate(
+            &self,
+            query: &str,
+            _from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationOutput> {
+            Ok(TranslationOutput {
+                text: self.0.get(query).copied().unwrap_or(query).to_string(),
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+
+        async fn translate_vec(
+            &self,
+            query: &[String],
+            from: Option<Language>,
+            to: &Language,
+        ) -> anyhow::Result<TranslationListOutput> {
+            let mut text = Vec::new();
+            for q in query {
+                text.push(self.translate(q, from, to).await?.text);
+            }
+            Ok(TranslationListOutput {
+                text,
+                lang: Some(*to),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// 测试相似度计算
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Hello, world!", "hello world"), 1.0);
+        assert_eq!(similarity("", "  "), 1.0);
+        assert_eq!(similarity("hello", ""), 0.0);
+        assert_eq!(similarity("the cat sat", "the dog sat"), 2.0 / 3.0);
+        assert_eq!(similarity("a a b", "a b b"), 2.0 / 3.0);
+        // 中文按字符二元组比较：今天/天天/天气/气很/很好 与 今天/天天/天气/气不/不错
+        assert_eq!(similarity("今天天气很好。", "今天天气不错"), 0.6);
+        assert_eq!(similarity("你好", "你好！"), 1.0);
+        assert_eq!(similarity("猫", "狗"), 0.0);
+        assert!(similarity("東京に行きます", "東京へ行きます") > 0.5);
+        // 大小写和Unicode组合形式不影响结果
+        assert_eq!(similarity("Cafe\u{301}", "CAFÉ"), 1.0);
+    }
+
+    /// 测试回译检查：回译与原文接近时相似度高，偏离时低于阈值
+    #[tokio::test]
+    async fn test_back_translate_check() {
+        let forward = Dictionary(HashMap::from([
+            ("The cat sleeps", "猫在睡觉"),
+            ("Open the bank account", "打开河岸"),
+        ]));
+        let backward = Dictionary(HashMap::from([
+            ("猫在睡觉", "The cat is sleeping"),
+            ("打开河岸", "Go to the riverside"),
+        ]));
+        let report = back_translate_check(
+            &forward,
+            &backward,
+            "The cat sleeps",
+            Some(Language::English),
+            &Language::Chinese,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.translation, "猫在睡觉");
+        assert_eq!(report.back_translation, "The cat is sleeping");
+        assert!(!report.is_suspicious(DEFAULT_SIMILARITY_THRESHOLD));
+
+        let texts = ["The cat sleeps", "Open the bank account"].map(String::from);
+        let reports =
+            back_translate_check_batch(&forward, &backward, &texts, None, &Language::Chinese)
+                .await
+                .unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].similarity, report.similarity);
+        assert_eq!(reports[1].back_translation, "Go to the riverside");
+        assert!(reports[1].is_suspicious(DEFAULT_SIMILARITY_THRESHOLD));
+    }
+
+    /// 测试同一个翻译器往返时记录两次调用
+    #[tokio::test]
+    async fn test_same_translator_roundtrip() {
+        let mock = MockTranslator::new();
+        let report = back_translate_check(&mock, &mock, "hello there", None, &Language::French)
+            .await
+            .unwrap();
+        assert_eq!(report.similarity, 1.0);
+        assert_eq!(mock.calls(), 2);
+        assert!(
+            back_translate_check(&mock, &mock, "123", None, &Language::French)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,677 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language, TranslationOutput};
+use crate::fusion_translator::batch::{translate_tagged_with, BatchOptions, DEFAULT_CHUNK_SIZE};
+use crate::fusion_translator::runtime::TaskGuard;
+use crate::fusion_translator::shutdown::Shutdown;
+use crate::fusion_translator::translator_error::TranslatorError;
+use futures_util::future::join_all;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+/// 队列中单个请求的结果
+///
+/// 同一批次中失败的请求共享同一个错误
+pub type QueueResult = Result<TranslationOutput, Arc<anyhow::Error>>;
+
+/// 默认的工作任务数量
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// 队列中的翻译请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueRequest {
+    /// 引擎名称，必须是创建队列时注册的名称
+    pub engine: String,
+    /// 待翻译的文本
+    pub text: String,
+    /// 源语言，None表示自动检测
+    pub from: Option<Language>,
+    /// 目标语言
+    pub to: Language,
+}
+
+impl QueueRequest {
+    /// 创建自动检测源语言的请求
+    ///
+    /// # 参数
+    /// - `engine`: 引擎名称
+    /// - `text`: 待翻译的文本
+    /// - `to`: 目标语言
+    pub fn new(engine: impl Into<String>, text: impl Into<String>, to: Language) -> Self {
+        Self {
+            engine: engine.into(),
+            text: text.into(),
+            from: None,
+            to,
+        }
+    }
+
+    /// 设置源语言
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    pub fn with_from(mut self, from: Language) -> Self {
+        self.from = Some(from);
+        self
+    }
+}
+
+/// 翻译队列配置
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    /// 并发处理批次的工作任务数量，0视为1
+    pub workers: usize,
+    /// 每个批次最多合并的请求数量，0视为1
+    pub max_batch_len: usize,
+    /// 队列容量，None表示不限
+    ///
+    /// 设置后队列已满时`TranslationQueue::enqueue`会等待工作任务取走请求，向生产者施加背压
+    pub capacity: Option<usize>,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            workers: DEFAULT_WORKERS,
+            max_batch_len: DEFAULT_CHUNK_SIZE,
+            capacity: None,
+        }
+    }
+}
+
+impl QueueConfig {
+    /// 设置工作任务数量
+    ///
+    /// # 参数
+    /// - `workers`: 工作任务数量
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// 设置每个批次最多合并的请求数量
+    ///
+    /// # 参数
+    /// - `max_batch_len`: 请求数量
+    pub fn with_max_batch_len(mut self, max_batch_len: usize) -> Self {
+        self.max_batch_len = max_batch_len;
+        self
+    }
+
+    /// 设置队列容量
+    ///
+    /// # 参数
+    /// - `capacity`: 最多等待处理的请求数量，0视为1
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+}
+
+/// 队列的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// 等待工作任务取走的请求数，即队列深度
+    pub queued: usize,
+    /// 队列深度的历史最大值
+    pub peak_queued: usize,
+    /// 已被工作任务取走、尚未得到结果的请求数
+    pub in_flight: usize,
+    /// 已得到结果（成功或失败）的请求数
+    pub completed: usize,
+    /// 发送给翻译器的批次数，每个批次为引擎、源语言和目标语言都相同的一组请求
+    pub batches: usize,
+}
+
+/// 等待处理的请求及其结果的发送端
+struct Job {
+    /// 请求
+    request: QueueRequest,
+    /// 结果的发送端
+    reply: Reply,
+}
+
+/// 结果的发送端
+///
+/// 没有发送结果就被丢弃时（队列被丢弃、关闭被中途放弃或工作任务被中止）
+/// 发送`TranslatorError::Cancelled(0)`，接收端不会得到`RecvError`
+struct Reply(Option<oneshot::Sender<QueueResult>>);
+
+impl Reply {
+    /// 发送结果，生产者已不再等待时忽略
+    ///
+    /// # 参数
+    /// - `result`: 翻译结果
+    fn send(mut self, result: QueueResult) {
+        if let Some(sender) = self.0.take() {
+            let _ = sender.send(result);
+        }
+    }
+}
+
+impl Drop for Reply {
+    fn drop(&mut self) {
+        if let Some(sender) = self.0.take() {
+            let _ = sender.send(Err(Arc::new(TranslatorError::Cancelled(0).into())));
+        }
+    }
+}
+
+/// 可以合并为一个批次的请求的键：（引擎名称，源语言，目标语言）
+type GroupKey = (String, Option<Language>, Language);
+
+/// 队列的发送端，按是否限制容量选择通道
+#[derive(Clone)]
+enum JobSender {
+    /// 有容量限制
+    Bounded(mpsc::Sender<Job>),
+    /// 无容量限制
+    Unbounded(mpsc::UnboundedSender<Job>),
+}
+
+/// 队列的接收端
+enum JobReceiver {
+    /// 有容量限制
+    Bounded(mpsc::Receiver<Job>),
+    /// 无容量限制
+    Unbounded(mpsc::UnboundedReceiver<Job>),
+}
+
+impl JobReceiver {
+    /// 等待下一个请求，队列关闭且为空时返回None
+    async fn recv(&mut self) -> Option<Job> {
+        match self {
+            Self::Bounded(receiver) => receiver.recv().await,
+            Self::Unbounded(receiver) => receiver.recv().await,
+        }
+    }
+
+    /// 取出已在队列中的下一个请求，不等待
+    fn try_recv(&mut self) -> Option<Job> {
+        match self {
+            Self::Bounded(receiver) => receiver.try_recv().ok(),
+            Self::Unbounded(receiver) => receiver.try_recv().ok(),
+        }
+    }
+}
+
+/// 生产者和工作任务共享的状态
+struct Shared {
+    /// 按名称注册的引擎
+    engines: HashMap<String, Arc<dyn AsyncTranslator>>,
+    /// 每个批次最多合并的请求数量
+    max_batch_len: usize,
+    /// 统计信息
+    stats: Mutex<QueueStats>,
+}
+
+impl Shared {
+    /// 记录一个进入队列的请求
+    fn pushed(&self) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.queued += 1;
+        stats.peak_queued = stats.peak_queued.max(stats.queued);
+    }
+
+    /// 记录从队列中取走、开始处理的请求
+    ///
+    /// # 参数
+    /// - `count`: 请求数量
+    fn taken(&self, count: usize) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.queued -= count;
+        stats.in_flight += count;
+    }
+
+    /// 记录一个完成的批次
+    ///
+    /// # 参数
+    /// - `count`: 批次中的请求数量
+    fn finished(&self, count: usize) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.in_flight -= count;
+        stats.completed += count;
+        stats.batches += 1;
+    }
+
+    /// 从队列中取出一组请求
+    ///
+    /// 等待第一个请求，再取出已在队列中的请求直到`max_batch_len`个，不等待后续请求
+    ///
+    /// # 参数
+    /// - `receiver`: 工作任务共享的接收端
+    ///
+    /// # 返回值
+    /// 取出的请求，队列关闭且为空时为None
+    async fn next_jobs(&self, receiver: &tokio::sync::Mutex<JobReceiver>) -> Option<Vec<Job>> {
+        let mut receiver = receiver.lock().await;
+        let mut jobs = vec![receiver.recv().await?];
+        while jobs.len() < self.max_batch_len {
+            match receiver.try_recv() {
+                Some(job) => jobs.push(job),
+                None => break,
+            }
+        }
+        drop(receiver);
+        self.taken(jobs.len());
+        Some(jobs)
+    }
+
+    /// 翻译一组引擎、源语言和目标语言都相同的请求，并分别发送结果
+    ///
+    /// 通过`batch::translate_tagged_with`翻译：相同的文本只请求一次，
+    /// 失败的分块只影响其中的请求
+    ///
+    /// # 参数
+    /// - `key`: （引擎名称，源语言，目标语言）
+    /// - `jobs`: 请求
+    async fn dispatch(&self, key: GroupKey, jobs: Vec<Job>) {
+        let (engine, from, to) = key;
+        let count = jobs.len();
+        let (replies, items): (Vec<_>, Vec<_>) = jobs
+            .into_iter()
+            .enumerate()
+            .map(|(index, job)| (job.reply, (index, job.request.text)))
+            .unzip();
+        let options = BatchOptions {
+            chunk_size: self.max_batch_len,
+            partial: true,
+            ..Default::default()
+        };
+        let results = match translate_tagged_with(
+            &*self.engines[&engine],
+            items,
+            from,
+            &to,
+            &options,
+        )
+        .await
+        {
+            Ok(results) => results.into_iter().map(|(_, result)| result).collect(),
+            Err(e) => vec![Err(Arc::new(e)); count],
+        };
+        self.finished(count);
+        for (reply, result) in replies.into_iter().zip(results) {
+            reply.send(result);
+        }
+    }
+
+    /// 工作任务的主循环
+    ///
+    /// 反复取出一组请求，按（引擎，源语言，目标语言）分组后并发翻译，队列关闭且为空时结束
+    ///
+    /// # 参数
+    /// - `receiver`: 工作任务共享的接收端
+    async fn work(self: Arc<Self>, receiver: Arc<tokio::sync::Mutex<JobReceiver>>) {
+        while let Some(jobs) = self.next_jobs(&receiver).await {
+            // 按首次出现的顺序分组，保证相同输入总是产生相同的上游请求顺序
+            let mut groups: Vec<(GroupKey, Vec<Job>)> = Vec::new();
+            for job in jobs {
+                let key = (job.request.engine.clone(), job.request.from, job.request.to);
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, group)) => group.push(job),
+                    None => groups.push((key, vec![job])),
+                }
+            }
+            join_all(
+                groups
+                    .into_iter()
+                    .map(|(key, jobs)| self.dispatch(key, jobs)),
+            )
+            .await;
+        }
+    }
+}
+
+/// 翻译队列
+///
+/// 生产者通过`enqueue`提交单个文本，立即得到结果的接收端；固定数量的工作任务从队列中取出请求，
+/// 把引擎、源语言和目标语言都相同的请求合并为一个批次（最多`max_batch_len`个）翻译，
+/// 再把每个请求的结果分别发送给对应的接收端。适合短文本请求量大、翻译器需要限流的服务，
+/// 生产者不需要等待翻译器，也不需要自己攒批
+///
+/// 工作任务只合并取出时已在队列中的请求，不会为了凑满批次而等待。
+/// 工作任务由队列持有，队列被丢弃时立即中止，不再调用翻译器，
+/// 尚未完成的请求的接收端收到`TranslatorError::Cancelled`；需要处理完已提交的请求时先调用`shutdown`
+pub struct TranslationQueue {
+    /// 共享状态
+    shared: Arc<Shared>,
+    /// 发送端，关闭后为None
+    sender: Mutex<Option<JobSender>>,
+    /// 工作任务
+    workers: Mutex<TaskGuard>,
+}
+
+impl TranslationQueue {
+    /// 创建翻译队列并启动工作任务
+    ///
+    /// 必须在tokio运行时中调用
+    ///
+    /// # 参数
+    /// - `engines`: （引擎名称，翻译器）列表，请求按名称选择翻译器
+    /// - `config`: 队列配置
+    pub fn new<N>(
+        engines: impl IntoIterator<Item = (N, Arc<dyn AsyncTranslator>)>,
+        config: QueueConfig,
+    ) -> Self
+    where
+        N: Into<String>,
+    {
+        let (sender, receiver) = match config.capacity {
+            Some(capacity) => {
+                let (sender, receiver) = mpsc::channel(capacity.max(1));
+                (JobSender::Bounded(sender), JobReceiver::Bounded(receiver))
+            }
+            None => {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                (
+                    JobSender::Unbounded(sender),
+                    JobReceiver::Unbounded(receiver),
+                )
+            }
+        };
+        let shared = Arc::new(Shared {
+            engines: engines
+                .into_iter()
+                .map(|(name, translator)| (name.into(), translator))
+                .collect(),
+            max_batch_len: config.max_batch_len.max(1),
+            stats: Mutex::new(QueueStats::default()),
+        });
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let mut workers = TaskGuard::new();
+        for _ in 0..config.workers.max(1) {
+            workers.spawn(shared.clone().work(receiver.clone()));
+        }
+        Self {
+            shared,
+            sender: Mutex::new(Some(sender)),
+            workers: Mutex::new(workers),
+        }
+    }
+
+    /// 提交一个翻译请求
+    ///
+    /// 限制了容量且队列已满时等待工作任务取走请求。引擎未注册时结果为
+    /// `TranslatorError::Configuration`，队列已关闭时结果为`TranslatorError::QueueClosed`
+    ///
+    /// # 参数
+    /// - `request`: 翻译请求
+    ///
+    /// # 返回值
+    /// 结果的接收端
+    pub async fn enqueue(&self, request: QueueRequest) -> oneshot::Receiver<QueueResult> {
+        let (reply, receiver) = oneshot::channel();
+        let reply = Reply(Some(reply));
+        if !self.shared.engines.contains_key(&request.engine) {
+            let error =
+                TranslatorError::Configuration(format!("unknown engine: {}", request.engine));
+            reply.send(Err(Arc::new(error.into())));
+            return receiver;
+        }
+        let sender = self.sender.lock().unwrap().clone();
+        let job = Job { request, reply };
+        let rejected = match sender {
+            // 先取得容量再计数，等待期间被取消不会影响统计
+            Some(JobSender::Bounded(sender)) => match sender.reserve().await {
+                Ok(permit) => {
+                    self.shared.pushed();
+                    permit.send(job);
+                    None
+                }
+                Err(_) => Some(job),
+            },
+            Some(JobSender::Unbounded(sender)) => {
+                self.shared.pushed();
+                sender.send(job).err().map(|e| {
+                    self.shared.stats.lock().unwrap().queued -= 1;
+                    e.0
+                })
+            }
+            None => Some(job),
+        };
+        if let Some(job) = rejected {
+            job.reply
+                .send(Err(Arc::new(TranslatorError::QueueClosed.into())));
+        }
+        receiver
+    }
+
+    /// 当前的统计信息
+    pub fn stats(&self) -> QueueStats {
+        *self.shared.stats.lock().unwrap()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Shutdown for TranslationQueue {
+    /// 关闭队列并等待已提交的请求处理完成
+    ///
+    /// 调用后`enqueue`不再接受新的请求；已在队列中和正在翻译的请求都会得到结果。
+    /// 多次调用时只有第一次等待工作任务；等待期间这个future被丢弃时（如外层设置了关闭时限），
+    /// 工作任务随之中止，尚未得到结果的请求收到`TranslatorError::Cancelled`
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.sender.lock().unwrap().take();
+        let mut workers = std::mem::take(&mut *self.workers.lock().unwrap());
+        workers.join_all().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::queue::{QueueConfig, QueueRequest, TranslationQueue};
+    use crate::fusion_translator::shutdown::Shutdown;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 创建只有一个引擎`mock`的队列
+    fn queue(translator: &Arc<MockTranslator>, config: QueueConfig) -> TranslationQueue {
+        TranslationQueue::new(
+            [("mock", translator.clone() as Arc<dyn AsyncTranslator>)],
+            config,
+        )
+    }
+
+    /// 取出结果中的错误
+    fn error_of(result: super::QueueResult) -> Arc<anyhow::Error> {
+        result.err().unwrap()
+    }
+
+    /// 测试相同引擎和语言对的请求合并为批次，每个生产者得到自己的译文
+    #[tokio::test]
+    async fn test_coalesces_compatible_requests() {
+        let first = Arc::new(MockTranslator::new());
+        let second = Arc::new(MockTranslator::new());
+        let queue = TranslationQueue::new(
+            [
+                ("first", first.clone() as Arc<dyn AsyncTranslator>),
+                ("second", second.clone() as Arc<dyn AsyncTranslator>),
+            ],
+            QueueConfig::default().with_workers(1),
+        );
+        let requests = [
+            QueueRequest::new("first", "one", Language::English),
+            QueueRequest::new("first", "two", Language::English),
+            QueueRequest::new("first", "three", Language::French),
+            QueueRequest::new("second", "four", Language::English),
+            QueueRequest::new("first", "one", Language::English),
+            QueueRequest::new("first", "five", Language::English).with_from(Language::Chinese),
+            QueueRequest::new("first", "six", Language::English),
+        ];
+        let mut receivers = Vec::new();
+        for request in requests {
+            receivers.push(queue.enqueue(request).await);
+        }
+        let mut texts = Vec::new();
+        for receiver in receivers {
+            texts.push(receiver.await.unwrap().unwrap().text);
+        }
+        assert_eq!(texts, ["ONE", "TWO", "THREE", "FOUR", "ONE", "FIVE", "SIX"]);
+        assert_eq!(
+            first.requests(),
+            vec![
+                vec!["one".to_string(), "six".to_string(), "two".to_string()],
+                vec!["three".to_string()],
+                vec!["five".to_string()],
+            ]
+        );
+        assert_eq!(second.requests(), vec![vec!["four".to_string()]]);
+        let stats = queue.stats();
+        assert_eq!((stats.completed, stats.batches), (7, 4));
+        assert_eq!(
+            (stats.queued, stats.in_flight, stats.peak_queued),
+            (0, 0, 7)
+        );
+
+        let unknown = queue
+            .enqueue(QueueRequest::new("third", "x", Language::English))
+            .await;
+        assert!(matches!(
+            error_of(unknown.await.unwrap()).downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Configuration(_))
+        ));
+    }
+
+    /// 测试批次大小受`max_batch_len`限制，失败只影响所在的分块
+    #[tokio::test]
+    async fn test_batches_are_bounded() {
+        let translator = Arc::new(MockTranslator::new().fail_on("boom"));
+        let queue = queue(
+            &translator,
+            QueueConfig::default().with_workers(1).with_max_batch_len(2),
+        );
+        let mut receivers = Vec::new();
+        for text in ["a", "b", "c", "boom", "e"] {
+            receivers.push(
+                queue
+                    .enqueue(QueueRequest::new("mock", text, Language::English))
+                    .await,
+            );
+        }
+        let mut results = Vec::new();
+        for receiver in receivers {
+            results.push(receiver.await.unwrap().map(|output| output.text).ok());
+        }
+        assert_eq!(
+            results,
+            [
+                Some("A".into()),
+                Some("B".into()),
+                None,
+                None,
+                Some("E".into())
+            ]
+        );
+        assert_eq!(
+            translator.requests(),
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["boom".to_string(), "c".to_string()],
+                vec!["e".to_string()],
+            ]
+        );
+    }
+
+    /// 测试关闭队列时处理完已提交的请求，之后的请求返回`QueueClosed`
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_drains_pending_requests() {
+        let translator = Arc::new(MockTranslator::new().with_delay(Duration::from_secs(1)));
+        let queue = queue(
+            &translator,
+            QueueConfig::default().with_workers(2).with_max_batch_len(2),
+        );
+        let mut receivers = Vec::new();
+        for i in 0..5 {
+            let request = QueueRequest::new("mock", format!("text {}", i), Language::English);
+            receivers.push(queue.enqueue(request).await);
+        }
+        queue.shutdown().await.unwrap();
+        let stats = queue.stats();
+        assert_eq!((stats.queued, stats.in_flight, stats.completed), (0, 0, 5));
+        for (i, mut receiver) in receivers.into_iter().enumerate() {
+            let output = receiver.try_recv().unwrap().unwrap();
+            assert_eq!(output.text, format!("TEXT {}", i));
+        }
+
+        let closed = queue
+            .enqueue(QueueRequest::new("mock", "late", Language::English))
+            .await;
+        assert!(matches!(
+            error_of(closed.await.unwrap()).downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::QueueClosed)
+        ));
+        assert_eq!(translator.translated_items(), 5);
+    }
+
+    /// 测试关闭超出时限被放弃时，已完成的请求保留结果，其余请求都收到`Cancelled`
+    #[tokio::test(start_paused = true)]
+    async fn test_abandoned_shutdown_cancels_outstanding_requests() {
+        let translator = Arc::new(MockTranslator::new().with_delay(Duration::from_secs(1)));
+        let queue = queue(
+            &translator,
+            QueueConfig::default().with_workers(1).with_max_batch_len(2),
+        );
+        let mut receivers = Vec::new();
+        for i in 0..5 {
+            let request = QueueRequest::new("mock", format!("text {}", i), Language::English);
+            receivers.push(queue.enqueue(request).await);
+        }
+        let shutdown = tokio::time::timeout(Duration::from_millis(1500), queue.shutdown());
+        assert!(shutdown.await.is_err());
+        assert_eq!(queue.stats().completed, 2);
+
+        let mut results = Vec::new();
+        for receiver in receivers {
+            results.push(match receiver.await.unwrap() {
+                Ok(output) => output.text,
+                Err(e) => match e.downcast_ref::<TranslatorError>() {
+                    Some(TranslatorError::Cancelled(0)) => "cancelled".to_string(),
+                    _ => panic!("unexpected error: {}", e),
+                },
+            });
+        }
+        assert_eq!(
+            results,
+            ["TEXT 0", "TEXT 1", "cancelled", "cancelled", "cancelled"]
+        );
+        assert_eq!(translator.calls(), 2);
+    }
+
+    /// 测试限制容量时队列已满的`enqueue`等待慢速翻译器取走请求
+    #[tokio::test(start_paused = true)]
+    async fn test_backpressure_with_slow_translator() {
+        let translator = Arc::new(MockTranslator::new().with_delay(Duration::from_secs(1)));
+        let queue = queue(
+            &translator,
+            QueueConfig::default()
+                .with_workers(1)
+                .with_max_batch_len(1)
+                .with_capacity(1),
+        );
+        let request = |text: &str| QueueRequest::new("mock", text, Language::English);
+        let first = queue.enqueue(request("a")).await;
+        // 让工作任务取走第一个请求并开始翻译
+        tokio::task::yield_now().await;
+        let second = queue.enqueue(request("b")).await;
+        let stats = queue.stats();
+        assert_eq!((stats.queued, stats.in_flight), (1, 1));
+
+        let blocked = tokio::time::timeout(Duration::from_millis(500), queue.enqueue(request("c")));
+        assert!(blocked.await.is_err());
+        assert_eq!(queue.stats().queued, 1);
+
+        let started = Instant::now();
+        let third = queue.enqueue(request("c")).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+        assert_eq!(queue.stats().peak_queued, 1);
+
+        assert_eq!(first.await.unwrap().unwrap().text, "A");
+        assert_eq!(second.await.unwrap().unwrap().text, "B");
+        assert_eq!(third.await.unwrap().unwrap().text, "C");
+    }
+}
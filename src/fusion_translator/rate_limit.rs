@@ -0,0 +1,774 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::runtime::{self, Instant};
+use crate::fusion_translator::translate_options::TranslateOptions;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// 默认保证批量请求获得的令牌份额
+///
+/// 两类请求都在排队时，每连续发放4个令牌给交互式请求后至少发放1个给批量请求
+pub const DEFAULT_BATCH_SHARE: f64 = 0.2;
+
+/// 请求的优先级
+///
+/// 通过`TranslateOptions::priority`指定，只影响等待限流令牌的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Priority {
+    /// 交互式请求（如界面上的即时翻译），优先于排队中的批量请求获得令牌
+    Interactive,
+    /// 批量请求
+    #[default]
+    Batch,
+}
+
+/// 限流器的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimiterStats {
+    /// 正在等待令牌的交互式请求数
+    pub interactive_queued: usize,
+    /// 正在等待令牌的批量请求数
+    pub batch_queued: usize,
+    /// 已发放给交互式请求的令牌数
+    pub interactive_granted: u64,
+    /// 已发放给批量请求的令牌数
+    pub batch_granted: u64,
+}
+
+impl LimiterStats {
+    /// 指定优先级的队列深度
+    ///
+    /// # 参数
+    /// - `priority`: 优先级
+    pub fn queued(&self, priority: Priority) -> usize {
+        match priority {
+            Priority::Interactive => self.interactive_queued,
+            Priority::Batch => self.batch_queued,
+        }
+    }
+}
+
+/// 限流器的内部状态
+#[derive(Debug, Default)]
+struct LimiterState {
+    /// 下一个令牌可用的时刻
+    next: Option<Instant>,
+    /// 等待令牌的交互式请求编号，按到达顺序排列
+    interactive: VecDeque<u64>,
+    /// 等待令牌的批量请求编号，按到达顺序排列
+    batch: VecDeque<u64>,
+    /// 下一个请求编号
+    next_ticket: u64,
+    /// 批量请求排队期间连续发放给交互式请求的令牌数
+    interactive_streak: usize,
+    /// 批量请求排队时最多连续发放给交互式请求的令牌数
+    max_interactive_streak: usize,
+    /// 统计信息
+    stats: LimiterStats,
+}
+
+impl LimiterState {
+    /// 指定优先级的等待队列
+    ///
+    /// # 参数
+    /// - `priority`: 优先级
+    fn queue(&mut self, priority: Priority) -> &mut VecDeque<u64> {
+        match priority {
+            Priority::Interactive => &mut self.interactive,
+            Priority::Batch => &mut self.batch,
+        }
+    }
+
+    /// 下一个令牌应发放给的请求
+    ///
+    /// 交互式请求优先；批量请求排队期间交互式请求连续获得的令牌达到上限时，先发放给批量请求
+    fn chosen(&self) -> Option<u64> {
+        match (self.interactive.front(), self.batch.front()) {
+            (Some(_), Some(&batch)) if self.interactive_streak >= self.max_interactive_streak => {
+                Some(batch)
+            }
+            (Some(&interactive), _) => Some(interactive),
+            (None, batch) => batch.copied(),
+        }
+    }
+
+    /// 发放一个令牌
+    ///
+    /// # 参数
+    /// - `priority`: 获得令牌的请求的优先级
+    /// - `now`: 当前时刻
+    /// - `interval`: 两次请求之间的最小间隔
+    fn grant(&mut self, priority: Priority, now: Instant, interval: Duration) {
+        let slot = self.next.map_or(now, |next| next.max(now));
+        self.next = Some(slot + interval);
+        match priority {
+            Priority::Interactive => {
+                self.interactive_streak = match self.batch.is_empty() {
+                    true => 0,
+                    false => self.interactive_streak + 1,
+                };
+                self.stats.interactive_granted += 1;
+            }
+            Priority::Batch => {
+                self.interactive_streak = 0;
+                self.stats.batch_granted += 1;
+            }
+        }
+    }
+}
+
+/// 按批量请求的份额计算交互式请求最多连续获得的令牌数
+///
+/// # 参数
+/// - `share`: 两类请求都在排队时保证批量请求获得的令牌份额，取值范围为(0, 1]
+fn max_interactive_streak(share: f64) -> usize {
+    assert!(share > 0.0 && share <= 1.0, "batch share must be in (0, 1]");
+    ((1.0 - share) / share + 1e-9).floor() as usize
+}
+
+/// 排队中的请求，被丢弃（如等待被取消）时离开队列
+struct Waiting<'a> {
+    /// 限流器
+    limiter: &'a RateLimiter,
+    /// 请求编号
+    ticket: u64,
+    /// 优先级
+    priority: Priority,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        let queue = state.queue(self.priority);
+        if let Some(index) = queue.iter().position(|&ticket| ticket == self.ticket) {
+            queue.remove(index);
+            drop(state);
+            // 离开的可能是下一个应获得令牌的请求
+            self.limiter.notify.notify_waiters();
+        }
+    }
+}
+
+/// 限流器的共享键
+///
+/// 同一翻译服务、同一凭据的所有翻译器共享一个限流器，
+/// 凭据只以哈希形式保存
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LimiterKey {
+    /// 翻译服务名称
+    pub vendor: String,
+    /// 凭据的哈希值
+    pub credential_hash: String,
+}
+
+impl LimiterKey {
+    /// 创建共享键
+    ///
+    /// # 参数
+    /// - `vendor`: 翻译服务名称
+    /// - `credentials`: 标识账号的凭据（如百度的app_id），没有凭据时传空数组
+    pub fn new(vendor: &str, credentials: &[&str]) -> Self {
+        let mut hasher = Sha256::new();
+        for credential in credentials {
+            hasher.update((credential.len() as u64).to_le_bytes());
+            hasher.update(credential.as_bytes());
+        }
+        Self {
+            vendor: vendor.to_string(),
+            credential_hash: hex::encode(&hasher.finalize()[..8]),
+        }
+    }
+}
+
+/// 令牌桶限流器
+///
+/// 容量为1，按固定间隔发放令牌。等待令牌的调用按优先级分为两个队列，各自按到达顺序排队：
+/// 交互式请求优先获得下一个令牌，两类请求都在排队时批量请求至少获得`batch_share`的令牌，不会被饿死
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// 每秒允许的请求数
+    qps: f64,
+    /// 两次请求之间的最小间隔
+    interval: Duration,
+    /// 下一个令牌可用的时刻和等待队列
+    state: Mutex<LimiterState>,
+    /// 状态变化（发放令牌、请求到达或离开）时唤醒等待的请求
+    notify: Notify,
+}
+
+impl RateLimiter {
+    /// 创建限流器
+    ///
+    /// # 参数
+    /// - `qps`: 每秒允许的请求数，必须大于0
+    pub fn new(qps: f64) -> Self {
+        assert!(qps > 0.0, "qps must be positive");
+        Self {
+            qps,
+            interval: Duration::from_secs_f64(1.0 / qps),
+            state: Mutex::new(LimiterState {
+                max_interactive_streak: max_interactive_streak(DEFAULT_BATCH_SHARE),
+                ..Default::default()
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// 设置两类请求都在排队时保证批量请求获得的令牌份额
+    ///
+    /// 共享的限流器对所有持有者生效
+    ///
+    /// # 参数
+    /// - `share`: 份额，取值范围为(0, 1]，默认为`DEFAULT_BATCH_SHARE`；为1时批量请求总是先于交互式请求
+    pub fn set_batch_share(&self, share: f64) {
+        self.state.lock().unwrap().max_interactive_streak = max_interactive_streak(share);
+    }
+
+    /// 统计信息，包括每个优先级的队列深度
+    pub fn stats(&self) -> LimiterStats {
+        let state = self.state.lock().unwrap();
+        LimiterStats {
+            interactive_queued: state.interactive.len(),
+            batch_queued: state.batch.len(),
+            ..state.stats
+        }
+    }
+
+    /// 获取共享限流器
+    ///
+    /// 进程内相同`key`的调用返回同一个限流器；注册表只保存弱引用，
+    /// 所有持有者释放后限流器随之释放，之后再获取会重新创建。
+    /// 限流器已存在时沿用其原有的`qps`
+    ///
+    /// # 参数
+    /// - `key`: 共享键
+    /// - `qps`: 新建限流器时使用的每秒请求数
+    pub fn shared(key: LimiterKey, qps: f64) -> Arc<Self> {
+        static REGISTRY: OnceLock<Mutex<HashMap<LimiterKey, Weak<RateLimiter>>>> = OnceLock::new();
+        let mut registry = REGISTRY.get_or_init(Default::default).lock().unwrap();
+        if let Some(limiter) = registry.get(&key).and_then(Weak::upgrade) {
+            if limiter.qps != qps {
+                tracing::warn!(
+                    vendor = key.vendor,
+                    existing = limiter.qps,
+                    requested = qps,
+                    "rate limiter already exists, keeping its qps"
+                );
+            }
+            return limiter;
+        }
+        registry.retain(|_, limiter| limiter.strong_count() > 0);
+        let limiter = Arc::new(Self::new(qps));
+        registry.insert(key, Arc::downgrade(&limiter));
+        limiter
+    }
+
+    /// 每秒允许的请求数
+    pub fn qps(&self) -> f64 {
+        self.qps
+    }
+
+    /// 以批量请求的优先级等待获取一个令牌
+    pub async fn acquire(&self) {
+        self.acquire_with(Priority::Batch).await
+    }
+
+    /// 按指定优先级等待获取一个令牌
+    ///
+    /// 没有请求排队且令牌可用时立即返回；等待被取消（future被丢弃）时离开队列
+    ///
+    /// # 参数
+    /// - `priority`: 优先级
+    pub async fn acquire_with(&self, priority: Priority) {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let idle = state.interactive.is_empty() && state.batch.is_empty();
+            if idle && state.next.is_none_or(|next| next <= now) {
+                state.grant(priority, now, self.interval);
+                return;
+            }
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.queue(priority).push_back(ticket);
+            ticket
+        };
+        // 新到达的交互式请求可能改变下一个令牌的归属
+        self.notify.notify_waiters();
+        // 等待被取消时离开队列
+        let _waiting = Waiting {
+            limiter: self,
+            ticket,
+            priority,
+        };
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let slot = {
+                let mut state = self.state.lock().unwrap();
+                if state.chosen() == Some(ticket) {
+                    let now = Instant::now();
+                    match state.next.filter(|&next| next > now) {
+                        Some(next) => Some(next),
+                        None => {
+                            state.queue(priority).pop_front();
+                            state.grant(priority, now, self.interval);
+                            drop(state);
+                            self.notify.notify_waiters();
+                            return;
+                        }
+                    }
+                } else {
+                    None
+                }
+            };
+            match slot {
+                Some(slot) => {
+                    tokio::select! {
+                        _ = runtime::sleep_until(slot) => {}
+                        _ = &mut notified => {}
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+}
+
+/// 限流翻译器
+///
+/// 每次调用内部翻译器（单条或批量）前获取一个令牌。
+/// 使用`shared`创建的多个实例共享同一个令牌桶，合计请求速率不超过限制
+///
+#[doc = include_str!("../../doc/examples/rate_limit.md")]
+pub struct RateLimitedTranslator {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 限流器
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedTranslator {
+    /// 创建独占限流器的翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `qps`: 每秒允许的请求数，必须大于0
+    pub fn new(inner: Arc<dyn AsyncTranslator>, qps: f64) -> Self {
+        Self {
+            inner,
+            limiter: Arc::new(RateLimiter::new(qps)),
+        }
+    }
+
+    /// 创建与其他实例共享限流器的翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `key`: 共享键，通常由翻译服务名称和凭据生成
+    /// - `qps`: 每秒允许的请求数，限流器已存在时沿用原有值
+    pub fn shared(inner: Arc<dyn AsyncTranslator>, key: LimiterKey, qps: f64) -> Self {
+        Self {
+            inner,
+            limiter: RateLimiter::shared(key, qps),
+        }
+    }
+
+    /// 获取使用的限流器
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for RateLimitedTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 与翻译请求共用限流器
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.limiter.acquire().await;
+        self.inner.detect_language(text).await
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 预热请求不翻译文本，不占用限流器的令牌
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.limiter.acquire().await;
+        self.inner.translate(query, from, to).await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.limiter.acquire().await;
+        self.inner.translate_vec(query, from, to).await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 按`TranslateOptions::priority`排队等待令牌，等待的过程可以被取消
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        options
+            .run_cancellable(0, async {
+                self.limiter.acquire_with(options.priority).await;
+                Ok(())
+            })
+            .await?;
+        self.inner
+            .translate_with_options(query, from, to, options)
+            .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 按`TranslateOptions::priority`排队等待令牌，等待的过程可以被取消
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        options
+            .run_cancellable(0, async {
+                self.limiter.acquire_with(options.priority).await;
+                Ok(())
+            })
+            .await?;
+        self.inner
+            .translate_vec_with_options(query, from, to, options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::rate_limit::{
+        LimiterKey, Priority, RateLimitedTranslator, RateLimiter,
+    };
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 按顺序让每个请求排队等待令牌，返回获得令牌的顺序
+    ///
+    /// 每个请求间隔1毫秒到达，保证到达顺序确定
+    ///
+    /// # 参数
+    /// - `limiter`: 限流器
+    /// - `requests`: （名称，优先级）列表
+    async fn grant_order(
+        limiter: Arc<RateLimiter>,
+        requests: &[(String, Priority)],
+    ) -> Vec<String> {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (name, priority) in requests.iter().cloned() {
+            let (limiter, order) = (limiter.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                limiter.acquire_with(priority).await;
+                order.lock().unwrap().push(name);
+            }));
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let order = order.lock().unwrap().clone();
+        order
+    }
+
+    /// 生成一组请求
+    ///
+    /// # 参数
+    /// - `prefix`: 名称前缀
+    /// - `count`: 数量
+    /// - `priority`: 优先级
+    fn requests(prefix: &str, count: usize, priority: Priority) -> Vec<(String, Priority)> {
+        (0..count)
+            .map(|i| (format!("{}{}", prefix, i), priority))
+            .collect()
+    }
+
+    /// 两个翻译器并发各翻译`n`次，返回总耗时
+    async fn run_pair(a: RateLimitedTranslator, b: RateLimitedTranslator, n: usize) -> Duration {
+        let start = Instant::now();
+        let run = |translator: RateLimitedTranslator| {
+            tokio::spawn(async move {
+                for i in 0..n {
+                    translator
+                        .translate(&format!("text {}", i), None, &Language::English)
+                        .await
+                        .unwrap();
+                }
+            })
+        };
+        let (a, b) = (run(a), run(b));
+        a.await.unwrap();
+        b.await.unwrap();
+        start.elapsed()
+    }
+
+    /// 测试共享凭据的两个翻译器合计速率不超过限制
+    #[tokio::test(start_paused = true)]
+    async fn test_shared_bucket_caps_combined_throughput() {
+        let key = LimiterKey::new("test-shared", &["app-id"]);
+        let a = RateLimitedTranslator::shared(Arc::new(MockTranslator::new()), key.clone(), 2.0);
+        let b = RateLimitedTranslator::shared(Arc::new(MockTranslator::new()), key, 2.0);
+        assert!(Arc::ptr_eq(a.limiter(), b.limiter()));
+        // 10次请求，2 QPS：第一次立即执行，其余每0.5秒一次
+        assert_eq!(run_pair(a, b, 5).await, Duration::from_millis(4500));
+    }
+
+    /// 测试不同凭据的翻译器各自限流
+    #[tokio::test(start_paused = true)]
+    async fn test_separate_keys_do_not_share() {
+        let a = RateLimitedTranslator::shared(
+            Arc::new(MockTranslator::new()),
+            LimiterKey::new("test-separate", &["first"]),
+            2.0,
+        );
+        let b = RateLimitedTranslator::shared(
+            Arc::new(MockTranslator::new()),
+            LimiterKey::new("test-separate", &["second"]),
+            2.0,
+        );
+        assert!(!Arc::ptr_eq(a.limiter(), b.limiter()));
+        assert_eq!(run_pair(a, b, 5).await, Duration::from_millis(2000));
+    }
+
+    /// 测试所有翻译器释放后共享的限流器也被释放
+    #[test]
+    fn test_registry_is_weak() {
+        let key = LimiterKey::new("test-weak", &["app-id"]);
+        let first = RateLimiter::shared(key.clone(), 1.0);
+        let again = RateLimiter::shared(key.clone(), 5.0);
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(again.qps(), 1.0);
+        let weak = Arc::downgrade(&first);
+        drop(first);
+        drop(again);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(RateLimiter::shared(key, 5.0).qps(), 5.0);
+    }
+
+    /// 测试凭据只以哈希形式出现在共享键中
+    #[test]
+    fn test_key_hashes_credentials() {
+        let key = LimiterKey::new("baidu", &["secret-app-id"]);
+        assert!(!key.credential_hash.contains("secret"));
+        assert_eq!(key, LimiterKey::new("baidu", &["secret-app-id"]));
+        assert_ne!(key, LimiterKey::new("baidu", &["secret-app", "id"]));
+    }
+
+    /// 测试交互式请求优先于先到达的批量请求获得令牌，同类请求按到达顺序获得
+    #[tokio::test(start_paused = true)]
+    async fn test_interactive_preempts_batch() {
+        let limiter = Arc::new(RateLimiter::new(1.0));
+        let mut interleaved = requests("b", 4, Priority::Batch);
+        interleaved.insert(2, ("i0".to_string(), Priority::Interactive));
+        interleaved.push(("i1".to_string(), Priority::Interactive));
+        interleaved.push(("b4".to_string(), Priority::Batch));
+        interleaved.push(("i2".to_string(), Priority::Interactive));
+        // 到达顺序：b0 b1 i0 b2 b3 i1 b4 i2，b0到达时令牌可用
+        assert_eq!(
+            grant_order(limiter.clone(), &interleaved).await,
+            ["b0", "i0", "i1", "i2", "b1", "b2", "b3", "b4"]
+        );
+        let stats = limiter.stats();
+        assert_eq!(stats.interactive_granted, 3);
+        assert_eq!(stats.batch_granted, 5);
+        assert_eq!(stats.queued(Priority::Batch), 0);
+    }
+
+    /// 测试交互式请求持续排队时，批量请求仍至少获得配置的份额
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_share_prevents_starvation() {
+        for (share, streak) in [(0.2, 4), (0.5, 1), (1.0, 0)] {
+            let limiter = Arc::new(RateLimiter::new(10.0));
+            limiter.set_batch_share(share);
+            let mut all = vec![("first".to_string(), Priority::Batch)];
+            all.extend(requests("b", 10, Priority::Batch));
+            all.extend(requests("i", 40, Priority::Interactive));
+            let order = grant_order(limiter, &all).await;
+            // 所有请求排队后，两类请求都还在排队期间每个批量令牌之前最多有`streak`个交互式令牌
+            let pattern = order
+                .iter()
+                .skip(1)
+                .take(10 * (streak + 1))
+                .map(|name| name.starts_with('b'))
+                .collect::<Vec<_>>();
+            let expected = (0..10)
+                .flat_map(|_| std::iter::repeat_n(false, streak).chain([true]))
+                .collect::<Vec<_>>();
+            assert_eq!(pattern, expected, "share {}", share);
+        }
+    }
+
+    /// 测试队列深度统计，取消等待的请求离开队列
+    #[tokio::test(start_paused = true)]
+    async fn test_queue_depths_and_cancellation() {
+        let limiter = Arc::new(RateLimiter::new(1.0));
+        limiter.acquire().await;
+        let waiters = [Priority::Batch, Priority::Batch, Priority::Interactive]
+            .into_iter()
+            .map(|priority| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire_with(priority).await })
+            })
+            .collect::<Vec<_>>();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let stats = limiter.stats();
+        assert_eq!(stats.queued(Priority::Batch), 2);
+        assert_eq!(stats.queued(Priority::Interactive), 1);
+
+        // 被中止的批量请求离开队列，其余请求照常获得令牌
+        waiters[0].abort();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(limiter.stats().batch_queued, 1);
+        let started = Instant::now();
+        for waiter in waiters.into_iter().skip(1) {
+            waiter.await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_millis(1998));
+        assert_eq!(limiter.stats().interactive_queued, 0);
+    }
+
+    /// 测试通过`TranslateOptions::priority`指定限流翻译器中请求的优先级
+    #[tokio::test(start_paused = true)]
+    async fn test_translator_priority_option() {
+        let translator = Arc::new(RateLimitedTranslator::new(
+            Arc::new(MockTranslator::new()),
+            1.0,
+        ));
+        let started = Instant::now();
+        let batch = (0..5)
+            .map(|i| {
+                let translator = translator.clone();
+                tokio::spawn(async move {
+                    translator
+                        .translate(&format!("batch {}", i), None, &Language::German)
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let options = TranslateOptions::new().with_priority(Priority::Interactive);
+        translator
+            .translate_with_options("hello", None, &Language::German, &options)
+            .await
+            .unwrap();
+        // 第一个批量请求立即执行，交互式请求获得下一个令牌，而不是排在其余4个批量请求之后
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        for task in batch {
+            task.await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::validate_batch;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+
+/// 翻译请求
+///
+/// 把原文、语言和调用选项组合为一个对象，避免在调用处传递一长串位置参数。
+/// 通过`ExecuteRequest::execute`执行，单个文本和批量文本使用同一个入口：
+///
+/// ```
+/// use fusion_translator::fusion_translator::async_translator::Language;
+/// use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+/// use fusion_translator::fusion_translator::request::{ExecuteRequest, TranslationRequest};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let translator = MockTranslator::new();
+/// let request = TranslationRequest::new("hello")
+///     .from(Language::English)
+///     .to(Language::Chinese)
+///     .option(|options| options.with_request_id("order-42"));
+/// let response = translator.execute(&request).await?;
+/// assert_eq!(response.text(), "HELLO");
+/// assert_eq!(response.detail.request_id.as_deref(), Some("order-42"));
+///
+/// let batch = TranslationRequest::default()
+///     .texts(["one", "two"])
+///     .to(Language::French);
+/// assert_eq!(translator.execute(&batch).await?.texts, ["ONE", "TWO"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TranslationRequest {
+    /// 原文
+    query: Vec<String>,
+    /// 是否为批量请求
+    batch: bool,
+    /// 源语言，None表示自动检测
+    from: Option<Language>,
+    /// 目标语言
+    to: Option<Language>,
+    /// 本次调用的选项
+    options: TranslateOptions,
+}
+
+impl TranslationRequest {
+    /// 创建翻译单个文本的请求
+    ///
+    /// # 参数
+    /// - `text`: 待翻译的文本
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            query: vec![text.into()],
+            ..Default::default()
+        }
+    }
+
+    /// 改为批量翻译多个文本
+    ///
+    /// 替换之前设置的原文，执行时调用`translate_vec_with_options`
+    ///
+    /// # 参数
+    /// - `texts`: 待翻译的文本
+    pub fn texts<I, S>(mut self, texts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.query = texts.into_iter().map(Into::into).collect();
+        self.batch = true;
+        self
+    }
+
+    /// 设置源语言，未设置时自动检测
+    ///
+    /// # 参数
+    /// - `lang`: 源语言，也可以传入`Option<Language>`，None表示自动检测
+    pub fn from(mut self, lang: impl Into<Option<Language>>) -> Self {
+        self.from = lang.into();
+        self
+    }
+
+    /// 设置目标语言
+    ///
+    /// # 参数
+    /// - `lang`: 目标语言
+    pub fn to(mut self, lang: Language) -> Self {
+        self.to = Some(lang);
+        self
+    }
+
+    /// 替换调用选项
+    ///
+    /// # 参数
+    /// - `options`: 本次调用的选项
+    pub fn options(mut self, options: TranslateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 在当前调用选项上修改一项设置
+    ///
+    /// # 参数
+    /// - `update`: 接收当前选项并返回修改后选项的函数，如`|o| o.with_request_id("id")`
+    pub fn option(mut self, update: impl FnOnce(TranslateOptions) -> TranslateOptions) -> Self {
+        self.options = update(self.options);
+        self
+    }
+
+    /// 原文
+    pub fn query(&self) -> &[String] {
+        &self.query
+    }
+
+    /// 是否为批量请求
+    pub fn is_batch(&self) -> bool {
+        self.batch
+    }
+
+    /// 源语言，None表示自动检测
+    pub fn source(&self) -> Option<Language> {
+        self.from
+    }
+
+    /// 目标语言
+    pub fn target(&self) -> Option<Language> {
+        self.to
+    }
+
+    /// 调用选项
+    pub fn translate_options(&self) -> &TranslateOptions {
+        &self.options
+    }
+
+    /// 检查请求是否完整
+    ///
+    /// # 返回值
+    /// 目标语言；未设置目标语言或批量请求没有文本时返回`TranslatorError::InvalidRequest`
+    pub fn validate(&self) -> Result<Language, TranslatorError> {
+        let to = self
+            .to
+            .ok_or_else(|| TranslatorError::InvalidRequest("missing target language".into()))?;
+        if self.query.is_empty() {
+            return Err(TranslatorError::InvalidRequest("empty batch".into()));
+        }
+        Ok(to)
+    }
+}
+
+/// 翻译响应
+///
+/// 统一单个文本和批量文本的结果，`texts`与请求的原文一一对应
+#[derive(Debug, Clone, Default)]
+pub struct TranslationResponse {
+    /// 译文
+    pub texts: Vec<String>,
+    /// 翻译服务返回的语言
+    pub lang: Option<Language>,
+    /// 翻译详情
+    pub detail: TranslationDetail,
+}
+
+impl TranslationResponse {
+    /// 第一条译文，单个文本的请求即为其译文
+    pub fn text(&self) -> &str {
+        self.texts.first().map_or("", String::as_str)
+    }
+}
+
+impl From<TranslationOutput> for TranslationResponse {
+    fn from(output: TranslationOutput) -> Self {
+        Self {
+            texts: vec![output.text],
+            lang: output.lang,
+            detail: output.detail,
+        }
+    }
+}
+
+impl From<TranslationListOutput> for TranslationResponse {
+    fn from(output: TranslationListOutput) -> Self {
+        Self {
+            texts: output.text,
+            lang: output.lang,
+            detail: output.detail,
+        }
+    }
+}
+
+/// 以`TranslationRequest`调用翻译器
+///
+/// 为所有`AsyncTranslator`自动实现，翻译器无需改动
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+pub trait ExecuteRequest {
+    /// 执行翻译请求
+    ///
+    /// 单个文本调用`translate_with_options`，批量文本调用`translate_vec_with_options`
+    ///
+    /// # 参数
+    /// - `request`: 翻译请求
+    ///
+    /// # 返回值
+    /// 翻译响应；请求不完整时返回`TranslatorError::InvalidRequest`，
+    /// 批量译文数量与原文不一致时返回`TranslatorError::BatchSizeMismatch`
+    async fn execute(&self, request: &TranslationRequest) -> anyhow::Result<TranslationResponse>;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<T: AsyncTranslator + ?Sized> ExecuteRequest for T {
+    async fn execute(&self, request: &TranslationRequest) -> anyhow::Result<TranslationResponse> {
+        let to = request.validate()?;
+        if !request.batch {
+            let output = self
+                .translate_with_options(&request.query[0], request.from, &to, &request.options)
+                .await?;
+            return Ok(output.into());
+        }
+        let mut output = self
+            .translate_vec_with_options(&request.query, request.from, &to, &request.options)
+            .await?;
+        output.text = validate_batch(request.query.len(), output.text)?;
+        Ok(output.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::request::{ExecuteRequest, TranslationRequest};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+
+    /// 测试缺少目标语言和空批量请求在调用翻译器前报错
+    #[tokio::test]
+    async fn test_validation() {
+        let mock = MockTranslator::new();
+        let missing_target = TranslationRequest::new("hello").from(Language::English);
+        let empty_batch = TranslationRequest::new("hello")
+            .texts(Vec::<String>::new())
+            .to(Language::French);
+        for (request, reason) in [
+            (missing_target, "missing target language"),
+            (empty_batch, "empty batch"),
+        ] {
+            let err = mock.execute(&request).await.unwrap_err();
+            assert_eq!(ErrorKind::of(&err), ErrorKind::InvalidInput);
+            assert!(matches!(
+                err.downcast_ref::<TranslatorError>(),
+                Some(TranslatorError::InvalidRequest(r)) if r == reason
+            ));
+        }
+        assert_eq!(mock.calls(), 0);
+        // 单个文本允许为空
+        assert!(TranslationRequest::new("")
+            .to(Language::French)
+            .validate()
+            .is_ok());
+    }
+
+    /// 测试单个请求和批量请求分别调用对应的方法，并传递语言和选项
+    #[tokio::test]
+    async fn test_execute() {
+        let mock = MockTranslator::new();
+        let request = TranslationRequest::new("hello")
+            .to(Language::German)
+            .options(TranslateOptions::new().with_request_id("a"))
+            .option(|o| o.with_raw_output(true));
+        assert_eq!(request.translate_options().request_id.as_deref(), Some("a"));
+        assert!(request.translate_options().raw_output);
+        let response = mock.execute(&request).await.unwrap();
+        assert_eq!(response.texts, ["HELLO"]);
+        assert_eq!(response.lang, Some(Language::German));
+
+        let batch = TranslationRequest::default()
+            .texts(vec!["a".to_string(), "b".to_string()])
+            .from(Language::English)
+            .to(Language::German);
+        assert!(batch.is_batch());
+        assert_eq!(batch.source(), Some(Language::English));
+        let response = mock.execute(&batch).await.unwrap();
+        assert_eq!(response.texts, ["A", "B"]);
+        assert_eq!(response.text(), "A");
+        assert_eq!(
+            mock.requests(),
+            vec![
+                vec!["hello".to_string()],
+                vec!["a".to_string(), "b".to_string()]
+            ]
+        );
+    }
+
+    /// 测试通过trait对象调用
+    #[tokio::test]
+    async fn test_execute_dyn() {
+        let translator: Box<dyn AsyncTranslator> = Box::new(MockTranslator::new());
+        let request = TranslationRequest::new("x").to(Language::Chinese);
+        assert_eq!(translator.execute(&request).await.unwrap().text(), "X");
+    }
+}
@@ -0,0 +1,374 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::provenance::{Detailed, ProvenanceEvent};
+use crate::fusion_translator::translate_options::{generate_request_id, TranslateOptions};
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 第一次重试前的默认等待时长，之后每次重试翻倍
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// 判断错误是否值得重试
+///
+/// 网络错误、未收到响应、被限流以及HTTP 429和5xx状态码视为暂时性错误，
+/// 翻译服务的错误代码按`error_codes`中的`retryable`判断；
+/// 语言不支持、文本过长、取消等重试也不会成功的错误直接返回
+///
+/// # 参数
+/// - `error`: 翻译器返回的错误
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<TranslatorError>() {
+        Some(TranslatorError::Reqwest(_))
+        | Some(TranslatorError::NoResponse)
+        | Some(TranslatorError::RateLimited(_)) => true,
+        Some(TranslatorError::RequestFailed(status)) => *status == 429 || *status >= 500,
+        Some(TranslatorError::ApiError(e)) => e.is_retryable(),
+        Some(_) => false,
+        None => error.is::<reqwest::Error>(),
+    }
+}
+
+/// 失败后自动重试的翻译器
+///
+/// 内部翻译器返回暂时性错误（见`is_retryable`）时按指数退避重试，
+/// 最多重试`retries`次后返回最后一次的错误。
+///
+/// 每次逻辑调用只分配一个请求ID，所有尝试都通过`TranslateOptions::request_id`传给内部翻译器。
+/// 超时后响应丢失的请求可能已被翻译服务受理，支持按请求ID去重的服务可以据此识别重试，避免重复计费
+///
+#[doc = include_str!("../../doc/examples/retry.md")]
+pub struct RetryTranslator {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 最多重试的次数
+    retries: u32,
+    /// 第一次重试前的等待时长
+    backoff: Duration,
+}
+
+impl RetryTranslator {
+    /// 创建重试翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `retries`: 最多重试的次数，为0时不重试
+    pub fn new(inner: Arc<dyn AsyncTranslator>, retries: u32) -> Self {
+        Self {
+            inner,
+            retries,
+            backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+
+    /// 设置第一次重试前的等待时长
+    ///
+    /// # 参数
+    /// - `backoff`: 等待时长，之后每次重试翻倍
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 本次逻辑调用的选项
+    ///
+    /// 解析调用方的请求ID；调用方没有提供且未开启自动生成时，可能重试的调用也会生成一个
+    ///
+    /// # 参数
+    /// - `options`: 调用方传入的选项
+    fn call_options(&self, options: &TranslateOptions) -> TranslateOptions {
+        let mut options = options.resolved();
+        if options.request_id.is_none() && self.retries > 0 {
+            options.request_id = Some(generate_request_id());
+        }
+        options
+    }
+
+    /// 执行操作，遇到暂时性错误时重试
+    ///
+    /// 等待重试的过程可以被取消，最终返回的错误附加了尝试次数的上下文，
+    /// 成功的结果追加`ProvenanceEvent::Retry`记录尝试次数
+    ///
+    /// # 参数
+    /// - `options`: 本次调用的选项
+    /// - `call`: 每次尝试时调用的操作
+    async fn run<T, F, Fut>(&self, options: &TranslateOptions, mut call: F) -> anyhow::Result<T>
+    where
+        T: Detailed,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut delay = self.backoff;
+        for attempt in 0.. {
+            let err = match call().await {
+                Ok(mut output) => {
+                    output.trace(ProvenanceEvent::Retry {
+                        attempts: attempt + 1,
+                    });
+                    return Ok(output);
+                }
+                Err(err) => err,
+            };
+            if attempt >= self.retries || !is_retryable(&err) {
+                return Err(err.context(format!(
+                    "attempt {} of {}",
+                    attempt + 1,
+                    self.retries + 1
+                )));
+            }
+            tracing::debug!(attempt = attempt + 1, error = %err, "retrying translation");
+            options
+                .run_cancellable(0, async {
+                    tokio::time::sleep(delay).await;
+                    Ok(())
+                })
+                .await?;
+            delay = delay.saturating_mul(2);
+        }
+        unreachable!("the retry loop only exits by returning")
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for RetryTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 直接交给内部翻译器，失败时不重试
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.inner.detect_language(text).await
+    }
+
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 所有尝试使用同一个请求ID（见`call_options`）
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let options = self.call_options(options);
+        self.run(&options, || {
+            self.inner.translate_with_options(query, from, to, &options)
+        })
+        .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 所有尝试使用同一个请求ID（见`call_options`）
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = self.call_options(options);
+        self.run(&options, || {
+            self.inner
+                .translate_vec_with_options(query, from, to, &options)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::retry::{is_retryable, RetryTranslator};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// 测试暂时性错误按指数退避重试
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_transient_failures() {
+        let mock = Arc::new(MockTranslator::new().with_transient_failures(2));
+        let translator = RetryTranslator::new(mock.clone(), 3);
+        let start = Instant::now();
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(mock.calls(), 3);
+        // 0.5秒 + 1秒
+        assert_eq!(start.elapsed(), Duration::from_millis(1500));
+    }
+
+    /// 测试重试次数用完后返回最后一次的错误，不可重试的错误直接返回
+    #[tokio::test(start_paused = true)]
+    async fn test_gives_up() {
+        let mock = Arc::new(MockTranslator::new().with_transient_failures(5));
+        let translator = RetryTranslator::new(mock.clone(), 2);
+        let query = ["a".to_string()];
+        assert!(translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .is_err());
+        assert_eq!(mock.calls(), 3);
+
+        let mock = Arc::new(MockTranslator::new().with_limits(Some(0), None));
+        let translator = RetryTranslator::new(mock.clone(), 2);
+        // 413不是暂时性错误
+        assert!(translator
+            .translate_vec(&query, None, &Language::Chinese)
+            .await
+            .is_err());
+        assert_eq!(mock.calls(), 1);
+    }
+
+    /// 测试重试、分块和翻译服务各层的错误上下文都出现在错误链中，且默认不包含原文
+    #[tokio::test(start_paused = true)]
+    async fn test_error_context_chain() {
+        let mock = Arc::new(
+            MockTranslator::new()
+                .with_limits(Some(2), None)
+                .fail_on("gamma"),
+        );
+        let translator = RetryTranslator::new(mock.clone(), 2);
+        let query = ["alpha", "beta", "gamma", "delta"].map(String::from);
+        let err = translator
+            .translate_vec(&query, Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap_err();
+        let chain = format!("{:#}", err);
+        assert!(chain.starts_with("attempt 3 of 3: "), "{}", chain);
+        assert!(chain.contains("mock translate_vec English->Chinese (4 items)"));
+        assert!(chain.contains("chunk 2 (items 2..4)"));
+        assert!(chain.ends_with("Request failed with status code"));
+        assert!(!chain.contains("gamma"));
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RequestFailed(500))
+        ));
+
+        let options = TranslateOptions::new().with_log_query_text(true);
+        let err = translator
+            .translate_vec_with_options(&query, None, &Language::Chinese, &options)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("query [\"alpha\", \"beta\", \"gamma\"] and 1 more"));
+    }
+
+    /// 测试错误分类
+    #[test]
+    fn test_is_retryable() {
+        let retryable = |e: TranslatorError| is_retryable(&e.into());
+        assert!(retryable(TranslatorError::RequestFailed(503)));
+        assert!(retryable(TranslatorError::RequestFailed(429)));
+        assert!(retryable(TranslatorError::RateLimited("429".to_string())));
+        assert!(!retryable(TranslatorError::RequestFailed(400)));
+        assert!(!retryable(TranslatorError::NoLanguage));
+        assert!(!is_retryable(&anyhow::anyhow!("other")));
+    }
+}
@@ -0,0 +1,747 @@
+use crate::fusion_translator::async_translator::{
+    AlternativeTranslation, AsyncTranslator, Language, TranslationDetail, TranslationListOutput,
+    TranslationOutput,
+};
+use crate::fusion_translator::batch::{self, empty_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// Reverso支持的语言及其代码
+const LANGUAGES: &[(Language, &str)] = &[
+    (Language::Arabic, "ara"),
+    (Language::Chinese, "chi"),
+    (Language::Czech, "cze"),
+    (Language::Danish, "dan"),
+    (Language::Dutch, "dut"),
+    (Language::English, "eng"),
+    (Language::French, "fra"),
+    (Language::German, "ger"),
+    (Language::Greek, "gre"),
+    (Language::Hebrew, "heb"),
+    (Language::Hindi, "hin"),
+    (Language::Hungarian, "hun"),
+    (Language::Italian, "ita"),
+    (Language::Japanese, "jpn"),
+    (Language::Korean, "kor"),
+    (Language::Persian, "per"),
+    (Language::Polish, "pol"),
+    (Language::Portuguese, "por"),
+    (Language::Romanian, "rum"),
+    (Language::Russian, "rus"),
+    (Language::Slovak, "slo"),
+    (Language::Spanish, "spa"),
+    (Language::Swedish, "swe"),
+    (Language::Turkish, "tur"),
+    (Language::Ukrainian, "ukr"),
+];
+
+/// 网页版单次翻译的最大长度
+///
+/// 网页限制为2000个字符（Unicode标量值）；批量分块时按UTF-8字节数保守处理
+const INPUT_LIMIT: usize = 2000;
+
+/// 逐行翻译多行文本时最多同时进行的请求数
+///
+/// Reverso对匿名请求的频率限制很严格，多行文本逐行顺序请求
+const LINE_CONCURRENCY: usize = 1;
+
+/// 把语言转换为Reverso的语言代码
+///
+/// # 返回值
+/// 语言代码，Reverso不支持该语言时为None
+fn to_reverso(lang: &Language) -> Option<&'static str> {
+    LANGUAGES
+        .iter()
+        .find(|(l, _)| l == lang)
+        .map(|&(_, code)| code)
+}
+
+/// Reverso翻译请求
+#[derive(Serialize)]
+struct ReversoRequest<'a> {
+    /// 文本格式，纯文本为"text"
+    format: &'static str,
+    /// 源语言代码
+    from: &'static str,
+    /// 目标语言代码
+    to: &'static str,
+    /// 待翻译的文本
+    input: &'a str,
+    /// 请求选项
+    options: RequestOptions,
+}
+
+/// Reverso请求选项
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestOptions {
+    /// 是否按句子拆分，拆分后`translation`按句子返回
+    sentence_splitter: bool,
+    /// 请求来源，网页为"translation.web"
+    origin: &'static str,
+    /// 是否返回上下文例句
+    context_results: bool,
+    /// 是否检测源语言，检测结果与请求不同时Reverso会交换翻译方向
+    language_detection: bool,
+}
+
+/// Reverso翻译响应
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReversoResponse {
+    /// 译文，不拆分句子时只有一项
+    #[serde(default)]
+    translation: Vec<String>,
+    /// 上下文结果，只有单词和短语才有，长句为null
+    #[serde(default)]
+    context_results: Option<ContextResults>,
+}
+
+/// 上下文结果
+#[derive(Deserialize)]
+struct ContextResults {
+    /// 按常用程度排列的译法
+    #[serde(default)]
+    results: Vec<ContextResult>,
+}
+
+/// 一种译法及其例句
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContextResult {
+    /// 译法
+    translation: String,
+    /// 原文例句，关键词用`<em>`标记
+    #[serde(default)]
+    source_examples: Vec<String>,
+    /// 与原文例句一一对应的译文例句
+    #[serde(default)]
+    target_examples: Vec<String>,
+}
+
+/// 去除例句中的HTML标记并还原字符实体
+///
+/// # 参数
+/// - `html`: Reverso返回的例句
+fn strip_markup(html: &str) -> String {
+    scraper::Html::parse_fragment(html)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// 解析Reverso响应
+///
+/// 主译文取自`translation`，`contextResults`中的其他译法整理为候选译文，
+/// 所有译法的例句按顺序整理为（原文例句，译文例句）
+///
+/// # 参数
+/// - `resp`: Reverso返回的响应
+///
+/// # 返回值
+/// 翻译结果，`lang`字段由调用方填充；没有译文时返回`TranslatorError::NoResponse`
+fn parse_response(resp: ReversoResponse) -> Result<TranslationOutput, TranslatorError> {
+    if resp.translation.is_empty() {
+        return Err(TranslatorError::NoResponse);
+    }
+    let text = resp.translation.join(" ");
+    let results = resp.context_results.map(|c| c.results).unwrap_or_default();
+
+    let mut seen = vec![text.trim().to_string()];
+    let mut alternatives = Vec::new();
+    let mut examples = Vec::new();
+    for result in results {
+        let translation = result.translation.trim();
+        if !translation.is_empty() && !seen.iter().any(|s| s == translation) {
+            seen.push(translation.to_string());
+            alternatives.push(AlternativeTranslation {
+                text: translation.to_string(),
+                score: None,
+                source: None,
+            });
+        }
+        for (source, target) in result.source_examples.iter().zip(&result.target_examples) {
+            let pair = (strip_markup(source), strip_markup(target));
+            if !pair.0.is_empty() && !pair.1.is_empty() && !examples.contains(&pair) {
+                examples.push(pair);
+            }
+        }
+    }
+    Ok(TranslationOutput {
+        text,
+        lang: None,
+        detail: TranslationDetail {
+            alternatives,
+            examples,
+            ..Default::default()
+        },
+    })
+}
+
+/// Reverso Context翻译器实现
+///
+/// 调用Reverso网页使用的匿名接口，无需注册API密钥；
+/// 单词和短语的结果除主译文外还包含其他译法（候选译文）和上下文例句。
+/// 接口要求浏览器User-Agent，并且频繁返回429，建议配合限流和重试使用
+pub struct ReversoTranslator {
+    /// API请求地址
+    host: String,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
+}
+
+/// 默认实现
+impl Default for ReversoTranslator {
+    fn default() -> Self {
+        ReversoTranslator::new()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for ReversoTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// Reverso翻译器需要调用远程API，返回false
+    fn local(&self) -> bool {
+        false
+    }
+
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "reverso", &self.host).await
+    }
+
+    /// 单次请求最多的文本数量
+    ///
+    /// 接口每次只接受一个文本
+    fn max_batch_len(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(to_reverso))
+    }
+
+    /// 单次请求最多的字符数
+    fn input_limit(&self) -> Option<usize> {
+        Some(INPUT_LIMIT)
+    }
+
+    /// 按字符数计算长度
+    fn input_unit(&self) -> InputUnit {
+        InputUnit::Chars
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递；多行文本按行拆分后顺序请求。
+    /// Reverso要求明确的源语言，未指定时按`long_text::detect_script`检测
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果；接口返回429或403时为`TranslatorError::RateLimited`
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let result = self.translate_text(query, from, to, options).await;
+        error_context::with_query_context(result, || {
+            QueryContext::single("reverso", query, from, to, options)
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 接口每次只接受一个文本，逐个顺序请求
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            0,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("reverso", query, from, to, &options)
+        })
+    }
+}
+
+impl ReversoTranslator {
+    /// 创建新的Reverso翻译器实例
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new() -> Self {
+        ReversoTranslator {
+            transport: TranslatorOptions::default().build_transport(TranslatorType::Reverso),
+            politeness: Some(Politeness::shared("reverso", PolitenessConfig::default())),
+            host: "https://api.reverso.net/translate/v1/translation".to_string(),
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::Reverso);
+        self.politeness = options.politeness.resolve("reverso");
+        self
+    }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，最多一个
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let Some(text) = query.first() else {
+            return Ok(empty_batch(options));
+        };
+        let output = self.translate_with_options(text, from, to, options).await?;
+        Ok(TranslationListOutput {
+            text: vec![output.text],
+            lang: output.lang,
+            detail: output.detail,
+        })
+    }
+
+    /// 按指定选项翻译单个文本，由`translate_with_options`附加错误上下文
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递；多行文本按行拆分后顺序请求。
+    /// Reverso要求明确的源语言，未指定时按`long_text::detect_script`检测
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果；接口返回429或403时为`TranslatorError::RateLimited`
+    async fn translate_text(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let query = &*preprocess::apply(query, options)?;
+        if query.trim().is_empty() {
+            return Ok(TranslationOutput {
+                text: query.to_string(),
+                lang: Some(*to),
+                detail: TranslationDetail {
+                    request_id: options.resolve_request_id(),
+                    ..Default::default()
+                },
+            });
+        }
+        if query.trim().contains('\n') {
+            return long_text::translate_lines(self, query, from, to, options, LINE_CONCURRENCY)
+                .await;
+        }
+        let check = self.check_input(query);
+        if let Err(err) = check.check() {
+            if !options.auto_split {
+                return Err(err.into());
+            }
+            return long_text::translate_checked(self, query, &check, from, to, options).await;
+        }
+        let mut languages = LanguageResolver::new(options, to_reverso);
+        let source = match from {
+            Some(lang) => lang,
+            None => long_text::detect_script(query).ok_or(TranslatorError::NoLanguage)?,
+        };
+        let request = ReversoRequest {
+            format: "text",
+            from: languages.resolve(source)?,
+            to: languages.resolve(*to)?,
+            input: whitespace::trim(query, options),
+            options: RequestOptions {
+                sentence_splitter: false,
+                origin: "translation.web",
+                context_results: true,
+                language_detection: false,
+            },
+        };
+
+        let request_id = options.resolve_request_id();
+        let span = tracing::debug_span!("translate", backend = "reverso", request_id = request_id);
+        let resp: ReversoResponse = options
+            .run_cancellable(
+                0,
+                async {
+                    let request = with_request_id_header(
+                        HttpRequest::post(&self.host).json(&request)?,
+                        request_id.as_deref(),
+                    );
+                    let response =
+                        politeness::send(self.politeness.as_deref(), &*self.transport, request)
+                            .await?;
+                    match StatusCode::from_u16(response.status) {
+                        Ok(status @ (StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN)) => {
+                            return Err(TranslatorError::RateLimited(status.to_string()).into());
+                        }
+                        _ if !response.is_success() => {
+                            return Err(TranslatorError::RequestFailed(response.status).into());
+                        }
+                        _ => {}
+                    }
+                    response.json()
+                }
+                .instrument(span),
+            )
+            .await?;
+        let mut output = parse_response(resp)?;
+        output.text = postprocess::apply(query, output.text, options);
+        output.detail.possibly_untranslated =
+            untranslated::check(query, &output.text, from, to, options)?;
+        output.lang = Some(*to);
+        output.detail.request_id = request_id;
+        output.detail.substitutions = languages.into_substitutions();
+        Ok(output)
+    }
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl ReversoTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, serde_json::Value)> {
+        let request = ReversoRequest {
+            format: "text",
+            from: "eng",
+            to: "fra",
+            input: "house",
+            options: RequestOptions {
+                sentence_splitter: false,
+                origin: "translation.web",
+                context_results: true,
+                language_detection: false,
+            },
+        };
+        let request = HttpRequest::post(&self.host).json(&request)?;
+        let body = self.transport.execute(request).await?;
+        Ok(("word.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "sentence.json" | "word.json" => {
+            parse_response(serde_json::from_str(body)?)?;
+        }
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::reverso_translator::{
+        parse_response, strip_markup, to_reverso, ReversoTranslator, LANGUAGES,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::test_support::http::{self, Request};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::translator_options::BROWSER_USER_AGENT;
+    use std::sync::mpsc;
+
+    /// 单词的响应，带候选译法和例句
+    const WORD: &str = include_str!("../../tests/fixtures/reverso/word.json");
+
+    /// 长句的响应，没有上下文结果
+    const SENTENCE: &str = include_str!("../../tests/fixtures/reverso/sentence.json");
+
+    /// 启动按固定状态码和响应体应答的本地服务器
+    ///
+    /// # 返回值
+    /// 请求本地服务器的翻译器和收到的请求
+    fn serve(status: u16, body: &'static str) -> (ReversoTranslator, mpsc::Receiver<Request>) {
+        let (sender, receiver) = mpsc::channel();
+        let url = http::serve(move |request| {
+            let _ = sender.send(request.clone());
+            (status, body.to_string())
+        });
+        let mut translator = ReversoTranslator::new();
+        translator.host = format!("{}/translate/v1/translation", url);
+        translator.politeness = None;
+        (translator, receiver)
+    }
+
+    /// 测试创建翻译器实例
+    #[test]
+    fn test_create_translator() {
+        let translator = ReversoTranslator::default();
+        assert!(!translator.local());
+        assert_eq!(translator.max_batch_len(), Some(1));
+        assert!(translator.host.contains("api.reverso.net"));
+    }
+
+    /// 测试语言代码映射
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(LANGUAGES.len(), 25);
+        assert_eq!(to_reverso(&Language::English), Some("eng"));
+        assert_eq!(to_reverso(&Language::French), Some("fra"));
+        assert_eq!(to_reverso(&Language::Chinese), Some("chi"));
+        assert_eq!(to_reverso(&Language::Welsh), None);
+        let supported = ReversoTranslator::new().supported_languages().unwrap();
+        assert_eq!(supported.len(), LANGUAGES.len());
+    }
+
+    /// 测试解析单词响应：主译文、去重后的候选译文，以及去除标记后的例句
+    #[test]
+    fn test_parse_word() {
+        let output = parse_response(serde_json::from_str(WORD).unwrap()).unwrap();
+        assert_eq!(output.text, "maison");
+        let alternatives = output
+            .detail
+            .alternatives
+            .iter()
+            .map(|a| a.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(alternatives, ["domicile", "chambre"]);
+        assert_eq!(
+            output.detail.examples,
+            [
+                (
+                    "He lives in a small house near the river.".to_string(),
+                    "Il vit dans une petite maison près de la rivière.".to_string()
+                ),
+                (
+                    "The house was built in 1920.".to_string(),
+                    "La maison a été construite en 1920.".to_string()
+                ),
+                (
+                    "They searched the house of the suspect & his car.".to_string(),
+                    "Ils ont fouillé le domicile du suspect et sa voiture.".to_string()
+                ),
+            ]
+        );
+    }
+
+    /// 测试解析没有上下文结果的长句响应和缺少译文的响应
+    #[test]
+    fn test_parse_sentence() {
+        let output = parse_response(serde_json::from_str(SENTENCE).unwrap()).unwrap();
+        assert_eq!(output.text, "Comment allez-vous aujourd'hui ?");
+        assert!(output.detail.alternatives.is_empty());
+        assert!(output.detail.examples.is_empty());
+
+        let empty = serde_json::from_str(r#"{"translation": []}"#).unwrap();
+        assert!(matches!(
+            parse_response(empty),
+            Err(TranslatorError::NoResponse)
+        ));
+    }
+
+    /// 测试请求体和浏览器User-Agent
+    #[tokio::test]
+    async fn test_request_shape() {
+        let (translator, requests) = serve(200, WORD);
+        let output = translator
+            .translate(" house ", Some(Language::English), &Language::French)
+            .await
+            .unwrap();
+        assert_eq!(output.text, " maison ");
+        assert_eq!(output.lang, Some(Language::French));
+        assert_eq!(output.detail.examples.len(), 3);
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/translate/v1/translation");
+        assert_eq!(request.header("user-agent"), Some(BROWSER_USER_AGENT));
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["format"], "text");
+        assert_eq!(body["from"], "eng");
+        assert_eq!(body["to"], "fra");
+        assert_eq!(body["input"], "house");
+        assert_eq!(body["options"]["contextResults"], true);
+        assert_eq!(body["options"]["origin"], "translation.web");
+    }
+
+    /// 测试429和403状态码返回`RateLimited`
+    #[tokio::test]
+    async fn test_rate_limited() {
+        for status in [429, 403] {
+            let (translator, _requests) = serve(status, "");
+            let err = translator
+                .translate("house", Some(Language::English), &Language::French)
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<TranslatorError>(),
+                    Some(TranslatorError::RateLimited(_))
+                ),
+                "{}: {:?}",
+                status,
+                err
+            );
+        }
+    }
+
+    /// 测试批量翻译逐个请求，只有空白的文本不发送请求
+    #[tokio::test]
+    async fn test_translate_vec_one_request_per_text() {
+        let (translator, requests) = serve(200, SENTENCE);
+        let query = ["How are you today?", " ", "How are you today?"].map(String::from);
+        let output = translator
+            .translate_vec(&query, Some(Language::English), &Language::French)
+            .await
+            .unwrap();
+        assert_eq!(output.text.len(), 3);
+        assert_eq!(output.text[1], " ");
+        assert_eq!(requests.try_iter().count(), 2);
+    }
+
+    /// 测试按字符数而不是字节数检查长度
+    #[tokio::test]
+    async fn test_input_limit_in_chars() {
+        let (translator, requests) = serve(200, SENTENCE);
+        let check = translator.check_input(&"长".repeat(1500));
+        assert_eq!((check.length, check.limit), (1500, Some(2000)));
+        assert!(!check.exceeds());
+
+        let query = "长".repeat(2500);
+        let check = translator.check_input(&query);
+        assert_eq!(check.would_split_at, vec![6000]);
+        let options = TranslateOptions::default().with_auto_split(false);
+        let err = translator
+            .translate_with_options(&query, Some(Language::Chinese), &Language::French, &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RequestTooLong(2500, 2000))
+        ));
+        assert_eq!(requests.try_iter().count(), 0);
+    }
+
+    /// 模糊测试：任意响应和例句标记的解析都不会panic
+    #[test]
+    fn test_fuzz_parse_response() {
+        let corpus = [
+            include_str!("../../tests/fixtures/reverso/sentence.json"),
+            include_str!("../../tests/fixtures/reverso/word.json"),
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            if let Ok(resp) = serde_json::from_str(&input) {
+                let _ = parse_response(resp);
+            }
+            strip_markup(&input);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::domain::{DomainClassifier, TextDomain};
+use crate::fusion_translator::fallback::FallbackTranslator;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 按领域路由的翻译器
+///
+/// 先确定文本领域（调用选项中的`TranslateOptions::domain`，未指定时由`DomainClassifier`判断），
+/// 再按该领域配置的引擎顺序尝试，之后按创建时的顺序尝试其余引擎；失败时的处理与`FallbackTranslator`相同。
+/// 没有配置路由的领域按创建时的顺序尝试所有引擎
+pub struct RoutingTranslator {
+    /// （名称，翻译器），没有配置路由时的顺序
+    engines: Vec<(String, Arc<dyn AsyncTranslator>)>,
+    /// 每个领域优先使用的引擎名称
+    routes: HashMap<TextDomain, Vec<String>>,
+    /// 领域判断
+    classifier: DomainClassifier,
+}
+
+impl RoutingTranslator {
+    /// 创建按领域路由的翻译器
+    ///
+    /// # 参数
+    /// - `engines`: （名称，翻译器），没有配置路由的领域按此顺序尝试
+    pub fn new(engines: Vec<(String, Arc<dyn AsyncTranslator>)>) -> Self {
+        Self {
+            engines,
+            routes: HashMap::new(),
+            classifier: DomainClassifier::default(),
+        }
+    }
+
+    /// 设置某个领域优先使用的引擎
+    ///
+    /// # 参数
+    /// - `domain`: 文本领域
+    /// - `engines`: 按优先级从高到低排列的引擎名称，必须是创建时传入的名称
+    ///
+    /// # 返回值
+    /// 包含未知的引擎名称时返回`TranslatorError::Configuration`
+    pub fn with_route<S: Into<String>>(
+        mut self,
+        domain: TextDomain,
+        engines: impl IntoIterator<Item = S>,
+    ) -> Result<Self, TranslatorError> {
+        let engines = engines.into_iter().map(Into::into).collect::<Vec<String>>();
+        if let Some(unknown) = engines
+            .iter()
+            .find(|name| !self.engines.iter().any(|(engine, _)| engine == *name))
+        {
+            return Err(TranslatorError::Configuration(format!(
+                "unknown engine {} in the {} route",
+                unknown, domain
+            )));
+        }
+        self.routes.insert(domain, engines);
+        Ok(self)
+    }
+
+    /// 设置领域判断
+    ///
+    /// # 参数
+    /// - `classifier`: 领域判断的阈值
+    pub fn with_classifier(mut self, classifier: DomainClassifier) -> Self {
+        self.classifier = classifier;
+        self
+    }
+
+    /// 某个领域尝试引擎的顺序
+    ///
+    /// # 参数
+    /// - `domain`: 文本领域
+    ///
+    /// # 返回值
+    /// 按尝试顺序排列的引擎名称
+    pub fn route(&self, domain: TextDomain) -> Vec<&str> {
+        self.ordered(domain)
+            .into_iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// 确定文本的领域
+    ///
+    /// 批量翻译时把所有文本作为整体判断
+    ///
+    /// # 参数
+    /// - `texts`: 待翻译的文本
+    /// - `options`: 本次调用的选项，其中指定的领域优先
+    pub fn domain_of(&self, texts: &[&str], options: &TranslateOptions) -> TextDomain {
+        options
+            .domain
+            .unwrap_or_else(|| self.classifier.classify(&texts.join("\n")))
+    }
+
+    /// 按领域排列的（名称，翻译器）
+    ///
+    /// # 参数
+    /// - `domain`: 文本领域
+    fn ordered(&self, domain: TextDomain) -> Vec<&(String, Arc<dyn AsyncTranslator>)> {
+        let preferred = self.routes.get(&domain).map_or(&[][..], Vec::as_slice);
+        let mut ordered = preferred
+            .iter()
+            .filter_map(|name| self.engines.iter().find(|(engine, _)| engine == name))
+            .collect::<Vec<_>>();
+        ordered.extend(
+            self.engines
+                .iter()
+                .filter(|(engine, _)| !preferred.contains(engine)),
+        );
+        ordered
+    }
+
+    /// 按领域顺序尝试引擎的故障转移翻译器
+    ///
+    /// # 参数
+    /// - `texts`: 待翻译的文本
+    /// - `options`: 本次调用的选项
+    fn fallback(&self, texts: &[&str], options: &TranslateOptions) -> FallbackTranslator {
+        let domain = self.domain_of(texts, options);
+        let engines = self
+            .ordered(domain)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        tracing::debug!(
+            domain = %domain,
+            engine = %engines.first().map_or("", |(name, _)| name.as_str()),
+            "routing translation"
+        );
+        FallbackTranslator::new(engines)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for RoutingTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 所有翻译器都是本地翻译器时为true
+    fn local(&self) -> bool {
+        self.engines.iter().all(|(_, t)| t.local())
+    }
+
+    /// 是否按请求量计费
+    ///
+    /// 任一翻译器计费时为true
+    fn paid(&self) -> bool {
+        self.engines.iter().any(|(_, t)| t.paid())
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 按创建时的顺序尝试，与`FallbackTranslator`相同
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        FallbackTranslator::new(self.engines.clone())
+            .detect_language(text)
+            .await
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 所有翻译器同时预热
+    async fn warm_up(&self) {
+        FallbackTranslator::new(self.engines.clone())
+            .warm_up()
+            .await
+    }
+
+    /// 支持的语言
+    ///
+    /// 各翻译器支持的语言的并集，任一翻译器不限时为None
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        FallbackTranslator::new(self.engines.clone()).supported_languages()
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按领域顺序第一个成功的翻译器的结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.fallback(&[query], &TranslateOptions::default())
+            .translate(query, from, to)
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// 所有文本作为整体判断领域，交给同一个翻译器
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 按领域顺序第一个成功的翻译器的结果
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let texts = query.iter().map(String::as_str).collect::<Vec<_>>();
+        self.fallback(&texts, &TranslateOptions::default())
+            .translate_vec(query, from, to)
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 选项中指定了`domain`时按该领域路由，不再判断文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 按领域顺序第一个成功的翻译器的结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.fallback(&[query], options)
+            .translate_with_options(query, from, to, options)
+            .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 选项中指定了`domain`时按该领域路由，不再判断文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 按领域顺序第一个成功的翻译器的结果
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let texts = query.iter().map(String::as_str).collect::<Vec<_>>();
+        self.fallback(&texts, options)
+            .translate_vec_with_options(query, from, to, options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::domain::TextDomain;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::routing::RoutingTranslator;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::Arc;
+
+    /// 测试每个领域的尝试顺序：配置的引擎在前，其余引擎按创建顺序在后
+    #[test]
+    fn test_route_order() {
+        let router = RoutingTranslator::new(vec![
+            ("a".to_string(), Arc::new(MockTranslator::new())),
+            ("b".to_string(), Arc::new(MockTranslator::new())),
+            ("c".to_string(), Arc::new(MockTranslator::new())),
+        ])
+        .with_route(TextDomain::Code, ["c"])
+        .unwrap()
+        .with_route(TextDomain::Chat, ["b", "a"])
+        .unwrap();
+        assert_eq!(router.route(TextDomain::Code), ["c", "a", "b"]);
+        assert_eq!(router.route(TextDomain::Chat), ["b", "a", "c"]);
+        assert_eq!(router.route(TextDomain::Legal), ["a", "b", "c"]);
+
+        assert!(matches!(
+            router.with_route(TextDomain::Legal, ["d"]),
+            Err(TranslatorError::Configuration(_))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+use std::future::Future;
+use tokio::task::{AbortHandle, JoinError, JoinSet};
+
+/// 后台任务的所有者
+///
+/// crate内启动的后台任务都必须放在`TaskGuard`中，并由启动它们的结构体（或函数）持有：
+/// `TaskGuard`被丢弃时中止所有仍在运行的任务，任务不会在所有者被丢弃后继续调用翻译服务。
+/// 需要等待任务正常结束时调用`join_all`。
+///
+/// 任务在下一个等待点被中止，正在等待的HTTP请求会被放弃，不会再发起新的请求
+#[derive(Debug)]
+pub struct TaskGuard<T = ()> {
+    /// 持有的任务，`JoinSet`被丢弃时中止其中所有的任务
+    tasks: JoinSet<T>,
+}
+
+impl<T: 'static> Default for TaskGuard<T> {
+    fn default() -> Self {
+        Self {
+            tasks: JoinSet::new(),
+        }
+    }
+}
+
+impl<T: Send + 'static> TaskGuard<T> {
+    /// 创建空的任务集合
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 启动一个任务
+    ///
+    /// 必须在tokio运行时中调用
+    ///
+    /// # 参数
+    /// - `task`: 任务
+    ///
+    /// # 返回值
+    /// 可以单独中止该任务的句柄
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.tasks.spawn(task)
+    }
+
+    /// 启动一个任务
+    ///
+    /// wasm32上的future不满足`Send`，任务在当前线程运行，必须在tokio的`LocalSet`中调用
+    ///
+    /// # 参数
+    /// - `task`: 任务
+    ///
+    /// # 返回值
+    /// 可以单独中止该任务的句柄
+    #[cfg(target_arch = "wasm32")]
+    pub fn spawn<F>(&mut self, task: F) -> AbortHandle
+    where
+        F: Future<Output = T> + 'static,
+    {
+        self.tasks.spawn_local(task)
+    }
+
+    /// 尚未被取走结果的任务数量，包括已结束但还没有通过`join_next`取走结果的任务
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// 是否没有任务
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// 等待任意一个任务结束
+    ///
+    /// # 返回值
+    /// 先结束的任务的结果，没有任务时为None；任务panic或被中止时为`JoinError`
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        self.tasks.join_next().await
+    }
+
+    /// 等待所有任务结束
+    ///
+    /// 等待期间这个future被丢弃时，剩余的任务仍由`TaskGuard`持有，随`TaskGuard`一起中止
+    ///
+    /// # 返回值
+    /// 按结束顺序排列的结果
+    pub async fn join_all(&mut self) -> Vec<Result<T, JoinError>> {
+        let mut results = Vec::with_capacity(self.tasks.len());
+        while let Some(result) = self.tasks.join_next().await {
+            results.push(result);
+        }
+        results
+    }
+
+    /// 中止所有任务
+    ///
+    /// 被中止的任务仍需通过`join_next`取走结果（`JoinError::is_cancelled`为true）
+    pub fn abort_all(&mut self) {
+        self.tasks.abort_all();
+    }
+}
+
+/// 生成16字节的随机数
+///
+/// 用于请求ID和签名的salt。原生平台使用线程本地的随机数生成器；
+/// wasm32上取自JavaScript的`Math.random`，避免依赖需要额外配置的getrandom
+pub(crate) fn random_bytes() -> [u8; 16] {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        rand::random()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        std::array::from_fn(|_| (js_sys::Math::random() * 256.0) as u8)
+    }
+}
+
+/// 生成`0..=max`范围内的随机数
+///
+/// # 参数
+/// - `max`: 最大值（包含）
+pub(crate) fn random_up_to(max: u64) -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use rand::Rng;
+        rand::rng().random_range(0..=max)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Math::random() * (max as f64 + 1.0)).min(max as f64) as u64
+    }
+}
+
+/// 单调时间
+///
+/// 原生平台使用tokio的`Instant`，测试中可以暂停和推进；
+/// wasm32上`std::time::Instant`不可用，改用基于`performance.now()`的`web_time::Instant`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// 休眠到指定时间
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::sleep_until;
+
+/// 休眠到指定时间
+///
+/// 浏览器中没有tokio的计时器，通过JavaScript的`setTimeout`等待
+///
+/// # 参数
+/// - `deadline`: 结束休眠的时间
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep_until(deadline: Instant) {
+    use wasm_bindgen::JsCast;
+    let millis = deadline
+        .saturating_duration_since(Instant::now())
+        .as_millis()
+        .min(i32::MAX as u128) as i32;
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&wasm_bindgen::JsValue::NULL, &resolve, &millis.into());
+            }
+            None => {
+                let _ = resolve.call0(&wasm_bindgen::JsValue::NULL);
+            }
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::runtime::TaskGuard;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 当前运行时中存活的任务数
+    fn alive_tasks() -> usize {
+        tokio::runtime::Handle::current()
+            .metrics()
+            .num_alive_tasks()
+    }
+
+    /// 测试丢弃`TaskGuard`时中止所有任务，任务不再运行
+    #[tokio::test(start_paused = true)]
+    async fn test_drop_aborts_tasks() {
+        let baseline = alive_tasks();
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut guard = TaskGuard::new();
+        for _ in 0..3 {
+            let ticks = ticks.clone();
+            guard.spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+        }
+        tokio::time::sleep(Duration::from_millis(55)).await;
+        assert_eq!(alive_tasks(), baseline + 3);
+        drop(guard);
+        let stopped = ticks.load(Ordering::SeqCst);
+        assert_eq!(stopped, 15);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), stopped);
+        assert_eq!(alive_tasks(), baseline);
+    }
+
+    /// 测试`join_all`等待所有任务结束并返回结果
+    #[tokio::test(start_paused = true)]
+    async fn test_join_all() {
+        let mut guard = TaskGuard::new();
+        for i in [3u64, 1, 2] {
+            guard.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(i)).await;
+                i
+            });
+        }
+        assert_eq!(guard.len(), 3);
+        let results = guard
+            .join_all()
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(results, [1, 2, 3]);
+        assert!(guard.is_empty());
+
+        guard.spawn(std::future::pending());
+        guard.abort_all();
+        assert!(guard.join_next().await.unwrap().unwrap_err().is_cancelled());
+    }
+}
@@ -0,0 +1,786 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 一天的秒数
+const DAY: i64 = 86_400;
+
+/// 一天的分钟数
+const MINUTES_PER_DAY: u16 = 1440;
+
+/// 所有星期
+const ALL_WEEKDAYS: u8 = 0b111_1111;
+
+/// 星期的名称，从星期一开始
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// 排队等待时单次休眠的最长时间，系统时间被调整后最迟在这之后重新计算
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// 时钟
+///
+/// `ScheduledTranslator`通过时钟获取当前时间，测试中可以替换为`MockClock`
+pub trait Clock: Send + Sync {
+    /// 当前时间
+    fn now(&self) -> SystemTime;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// 当前的系统时间
+    ///
+    /// wasm32-unknown-unknown上`SystemTime::now`不可用，改为读取JavaScript的`Date.now()`
+    fn now(&self) -> SystemTime {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            SystemTime::now()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
+        }
+    }
+}
+
+/// 随tokio时间前进的模拟时钟
+///
+/// 从给定的时间开始，按tokio的单调时间前进；在暂停的tokio时间（`start_paused`）中，
+/// 等待窗口开放的休眠会自动推进时钟，可以快速测试跨越窗口边界的行为
+#[derive(Debug, Clone, Copy)]
+pub struct MockClock {
+    /// 创建时对应的时间
+    origin: SystemTime,
+    /// 创建时的tokio时间
+    started: tokio::time::Instant,
+}
+
+impl MockClock {
+    /// 创建模拟时钟
+    ///
+    /// # 参数
+    /// - `origin`: 当前对应的时间
+    pub fn new(origin: SystemTime) -> Self {
+        Self {
+            origin,
+            started: tokio::time::Instant::now(),
+        }
+    }
+}
+
+impl Clock for MockClock {
+    /// 起始时间加上tokio时间经过的时长
+    fn now(&self) -> SystemTime {
+        self.origin + self.started.elapsed()
+    }
+}
+
+/// 每天（或每周的某几天）重复的时间窗口
+///
+/// 时间为`Schedule`所设时区的当地时间。结束时间不晚于开始时间时跨越午夜，
+/// 如`22:00-06:00`为每晚22点到次日6点；开始和结束相同时为从开始时间起的24小时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// 开始时间，当地午夜之后的分钟数
+    start: u16,
+    /// 结束时间，当地午夜之后的分钟数，最大为1440
+    end: u16,
+    /// 窗口开始的星期，第0位为星期一
+    weekdays: u8,
+}
+
+impl TimeWindow {
+    /// 创建每天重复的时间窗口
+    ///
+    /// # 参数
+    /// - `start`: 开始时间（时，分）
+    /// - `end`: 结束时间（时，分），`(24, 0)`表示午夜
+    ///
+    /// # 返回值
+    /// 时间超出范围时为None
+    pub fn daily(start: (u8, u8), end: (u8, u8)) -> Option<Self> {
+        Some(Self {
+            start: minute_of_day(start).filter(|m| *m < MINUTES_PER_DAY)?,
+            end: minute_of_day(end)?,
+            weekdays: ALL_WEEKDAYS,
+        })
+    }
+
+    /// 窗口是否包含某个当地时刻
+    ///
+    /// # 参数
+    /// - `local`: 当地时间的Unix秒数
+    fn contains(&self, local: i64) -> bool {
+        let day = local.div_euclid(DAY);
+        let second = local.rem_euclid(DAY);
+        let (start, end) = (i64::from(self.start) * 60, i64::from(self.end) * 60);
+        if start < end {
+            self.starts_on(day) && start <= second && second < end
+        } else {
+            (self.starts_on(day) && second >= start) || (self.starts_on(day - 1) && second < end)
+        }
+    }
+
+    /// 不早于某个当地时刻的下一次开始时刻
+    ///
+    /// # 参数
+    /// - `local`: 当地时间的Unix秒数
+    ///
+    /// # 返回值
+    /// 当地时间的Unix秒数，没有任何星期时为None
+    fn next_start(&self, local: i64) -> Option<i64> {
+        let day = local.div_euclid(DAY);
+        (0..=7)
+            .map(|offset| day + offset)
+            .filter(|day| self.starts_on(*day))
+            .map(|day| day * DAY + i64::from(self.start) * 60)
+            .find(|start| *start >= local)
+    }
+
+    /// 窗口是否在某天开始
+    ///
+    /// # 参数
+    /// - `day`: 1970-01-01之后的天数
+    fn starts_on(&self, day: i64) -> bool {
+        // 1970-01-01是星期四
+        let weekday = (day + 3).rem_euclid(7);
+        self.weekdays & (1 << weekday) != 0
+    }
+}
+
+/// 时间窗口格式错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid time window `{0}`, expected `[mon-fri] HH:MM-HH:MM`")]
+pub struct InvalidTimeWindow(pub String);
+
+impl FromStr for TimeWindow {
+    type Err = InvalidTimeWindow;
+
+    /// 解析时间窗口
+    ///
+    /// 格式为`HH:MM-HH:MM`，前面可以加上以空格分隔的星期，
+    /// 如`mon-fri 09:00-17:00`、`sat,sun 00:00-24:00`；星期为窗口开始的那天
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidTimeWindow(s.to_string());
+        let (days, range) = match s.trim().rsplit_once(char::is_whitespace) {
+            Some((days, range)) => (Some(days.trim()), range),
+            None => (None, s.trim()),
+        };
+        let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+        let mut window = Self::daily(
+            parse_clock(start).ok_or_else(invalid)?,
+            parse_clock(end).ok_or_else(invalid)?,
+        )
+        .ok_or_else(invalid)?;
+        if let Some(days) = days {
+            window.weekdays = parse_weekdays(days).ok_or_else(invalid)?;
+        }
+        Ok(window)
+    }
+}
+
+/// 解析`HH:MM`
+fn parse_clock(s: &str) -> Option<(u8, u8)> {
+    let (hour, minute) = s.split_once(':')?;
+    Some((hour.parse().ok()?, minute.parse().ok()?))
+}
+
+/// 解析以逗号分隔的星期或星期范围，如`mon-fri`、`sat,sun`
+///
+/// # 返回值
+/// 星期的位掩码，第0位为星期一
+fn parse_weekdays(s: &str) -> Option<u8> {
+    let index = |name: &str| {
+        let name = name.trim().to_ascii_lowercase();
+        WEEKDAYS.iter().position(|day| name.starts_with(day))
+    };
+    let mut mask = 0u8;
+    for part in s.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (index(first)?, index(last)?);
+                let mut day = first;
+                loop {
+                    mask |= 1 << day;
+                    if day == last {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => mask |= 1 << index(part)?,
+        }
+    }
+    Some(mask)
+}
+
+/// 把（时，分）转为午夜之后的分钟数
+///
+/// # 返回值
+/// 超出`24:00`时为None
+fn minute_of_day((hour, minute): (u8, u8)) -> Option<u16> {
+    let minutes = u16::from(hour) * 60 + u16::from(minute);
+    (minute < 60 && minutes <= MINUTES_PER_DAY).then_some(minutes)
+}
+
+/// 允许请求的时间表
+///
+/// 由若干时间窗口和可选的每日额度重置时间组成，时间均为按UTC偏移计算的当地时间；
+/// 没有时间窗口时任何时间都允许请求
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schedule {
+    /// 当地时间相对UTC的偏移秒数
+    utc_offset: i64,
+    /// 允许请求的时间窗口
+    windows: Vec<TimeWindow>,
+    /// 每日额度重置时间，当地午夜之后的分钟数
+    quota_reset: Option<u16>,
+}
+
+impl Schedule {
+    /// 创建没有时间窗口的时间表
+    ///
+    /// # 参数
+    /// - `utc_offset_minutes`: 当地时间相对UTC的偏移分钟数，如UTC+8为480
+    pub fn new(utc_offset_minutes: i32) -> Self {
+        Self {
+            utc_offset: i64::from(utc_offset_minutes) * 60,
+            ..Default::default()
+        }
+    }
+
+    /// 添加允许请求的时间窗口
+    ///
+    /// # 参数
+    /// - `window`: 时间窗口
+    pub fn with_window(mut self, window: TimeWindow) -> Self {
+        self.windows.push(window);
+        self
+    }
+
+    /// 设置每日额度重置时间
+    ///
+    /// 翻译器返回`TranslatorError::Quota`后，之后的请求等到下一次重置时间（且在时间窗口内）才发送
+    ///
+    /// # 参数
+    /// - `time`: 重置时间（时，分）
+    pub fn with_quota_reset(mut self, time: (u8, u8)) -> Self {
+        self.quota_reset = minute_of_day(time).map(|minute| minute % MINUTES_PER_DAY);
+        self
+    }
+
+    /// 某个时刻是否在时间窗口内
+    ///
+    /// # 参数
+    /// - `at`: 时刻
+    pub fn is_open(&self, at: SystemTime) -> bool {
+        let local = self.local(at);
+        self.windows.is_empty() || self.windows.iter().any(|window| window.contains(local))
+    }
+
+    /// 不早于某个时刻的下一次允许请求的时刻
+    ///
+    /// # 参数
+    /// - `at`: 时刻
+    ///
+    /// # 返回值
+    /// `at`在时间窗口内时为`at`，没有任何窗口会再开放时为None
+    pub fn next_open(&self, at: SystemTime) -> Option<SystemTime> {
+        if self.is_open(at) {
+            return Some(at);
+        }
+        let local = self.local(at);
+        self.windows
+            .iter()
+            .filter_map(|window| window.next_start(local))
+            .min()
+            .map(|local| self.system_time(local))
+    }
+
+    /// 某个时刻之后的下一次额度重置时刻
+    ///
+    /// # 参数
+    /// - `at`: 时刻
+    ///
+    /// # 返回值
+    /// 未设置重置时间时为None
+    pub fn next_reset(&self, at: SystemTime) -> Option<SystemTime> {
+        let reset = i64::from(self.quota_reset?) * 60;
+        let local = self.local(at);
+        let mut next = local.div_euclid(DAY) * DAY + reset;
+        if next <= local {
+            next += DAY;
+        }
+        Some(self.system_time(next))
+    }
+
+    /// 转为当地时间的Unix秒数
+    fn local(&self, at: SystemTime) -> i64 {
+        let unix = match at.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+        };
+        unix + self.utc_offset
+    }
+
+    /// 把当地时间的Unix秒数转为时刻
+    fn system_time(&self, local: i64) -> SystemTime {
+        let unix = local - self.utc_offset;
+        if unix >= 0 {
+            UNIX_EPOCH + Duration::from_secs(unix as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(unix.unsigned_abs())
+        }
+    }
+}
+
+/// 时间窗口之外的请求的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WindowPolicy {
+    /// 排队等待窗口开放，按到达顺序放行
+    #[default]
+    Queue,
+    /// 立即返回`TranslatorError::OutsideWindow`
+    Reject,
+}
+
+/// 按时间窗口发送请求的翻译器
+///
+/// 只在`Schedule`的时间窗口内调用内部翻译器，适用于有闲时价格或每日定时重置额度的翻译服务。
+/// 窗口之外的请求按`WindowPolicy`排队或拒绝；排队的请求在窗口开放时按到达顺序放行。
+/// 设置了额度重置时间时，内部翻译器返回`TranslatorError::Quota`后（该请求的错误照常返回），
+/// 之后的请求同样排队或拒绝，直到下一次重置时间
+pub struct ScheduledTranslator {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 时间表
+    schedule: Schedule,
+    /// 窗口之外的处理方式
+    policy: WindowPolicy,
+    /// 时钟
+    clock: Arc<dyn Clock>,
+    /// 排队的请求依次持有，tokio的互斥锁按到达顺序唤醒等待者
+    queue: tokio::sync::Mutex<()>,
+    /// 额度用完后暂停请求直到的时刻
+    blocked_until: Mutex<Option<SystemTime>>,
+}
+
+impl ScheduledTranslator {
+    /// 创建按时间表发送请求的翻译器，窗口之外的请求排队等待
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `schedule`: 时间表
+    pub fn new(inner: Arc<dyn AsyncTranslator>, schedule: Schedule) -> Self {
+        Self {
+            inner,
+            schedule,
+            policy: WindowPolicy::default(),
+            clock: Arc::new(SystemClock),
+            queue: tokio::sync::Mutex::new(()),
+            blocked_until: Mutex::new(None),
+        }
+    }
+
+    /// 设置窗口之外的处理方式
+    ///
+    /// # 参数
+    /// - `policy`: 处理方式
+    pub fn with_policy(mut self, policy: WindowPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 设置时钟
+    ///
+    /// # 参数
+    /// - `clock`: 时钟，测试中通常为`MockClock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 下一次允许请求的时刻
+    ///
+    /// # 返回值
+    /// 现在允许请求时为当前时刻；不会再允许时为None
+    pub fn next_allowed(&self) -> Option<SystemTime> {
+        let now = self.clock.now();
+        let mut blocked_until = self.blocked_until.lock().unwrap();
+        let from = match *blocked_until {
+            Some(until) if until > now => until,
+            _ => {
+                *blocked_until = None;
+                now
+            }
+        };
+        self.schedule.next_open(from)
+    }
+
+    /// 等待到允许请求的时刻
+    ///
+    /// # 返回值
+    /// 拒绝策略下不允许请求时返回`TranslatorError::OutsideWindow`
+    async fn wait(&self) -> Result<(), TranslatorError> {
+        let wait = |allowed: Option<SystemTime>| {
+            allowed.map(|at| at.duration_since(self.clock.now()).unwrap_or_default())
+        };
+        let first = wait(self.next_allowed());
+        if first == Some(Duration::ZERO) && self.queue.try_lock().is_ok() {
+            return Ok(());
+        }
+        if self.policy == WindowPolicy::Reject {
+            return Err(TranslatorError::OutsideWindow(first));
+        }
+        let _turn = self.queue.lock().await;
+        loop {
+            match wait(self.next_allowed()) {
+                Some(Duration::ZERO) => return Ok(()),
+                Some(remaining) => tokio::time::sleep(remaining.min(MAX_SLEEP)).await,
+                None => return Err(TranslatorError::OutsideWindow(None)),
+            }
+        }
+    }
+
+    /// 等待到允许请求的时刻后调用内部翻译器，记录额度用完
+    ///
+    /// # 参数
+    /// - `options`: 本次调用的选项，等待可以被取消
+    /// - `call`: 调用内部翻译器
+    async fn run<T, F>(&self, options: &TranslateOptions, call: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        options
+            .run_cancellable(0, async { Ok(self.wait().await?) })
+            .await?;
+        let result = call.await;
+        if let Err(e) = &result {
+            if let Some(TranslatorError::Quota { .. }) = e.downcast_ref::<TranslatorError>() {
+                let reset = self.schedule.next_reset(self.clock.now());
+                if reset.is_some() {
+                    *self.blocked_until.lock().unwrap() = reset;
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for ScheduledTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 与翻译请求一样只在时间窗口内发送
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.run(
+            &TranslateOptions::default(),
+            self.inner.detect_language(text),
+        )
+        .await
+    }
+
+    /// 预先建立与翻译服务的连接
+    ///
+    /// 预热请求不翻译文本，不受时间窗口限制，直接转发给内部翻译器
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.run(
+            &TranslateOptions::default(),
+            self.inner.translate(query, from, to),
+        )
+        .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.run(
+            &TranslateOptions::default(),
+            self.inner.translate_vec(query, from, to),
+        )
+        .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// 排队等待的过程可以被取消
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.run(
+            options,
+            self.inner.translate_with_options(query, from, to, options),
+        )
+        .await
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 排队等待的过程可以被取消
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.run(
+            options,
+            self.inner
+                .translate_vec_with_options(query, from, to, options),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::scheduled::{
+        Clock, MockClock, Schedule, ScheduledTranslator, TimeWindow, WindowPolicy,
+    };
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// 一小时
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    /// UTC+8的某个星期五（2024-10-04）当地时间`hour`:`minute`
+    fn friday(hour: u64, minute: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_728_000_000 + hour * 3600 + minute * 60) - 8 * HOUR
+    }
+
+    /// UTC+8、每晚22点到次日6点开放的时间表
+    fn night() -> Schedule {
+        Schedule::new(480).with_window("22:00-06:00".parse().unwrap())
+    }
+
+    /// 测试时间窗口的解析和开放判断
+    #[test]
+    fn test_windows() {
+        let schedule = night();
+        assert!(!schedule.is_open(friday(21, 59)));
+        assert!(schedule.is_open(friday(22, 0)));
+        assert!(schedule.is_open(friday(29, 59)));
+        assert!(!schedule.is_open(friday(30, 0)));
+        assert_eq!(schedule.next_open(friday(12, 0)), Some(friday(22, 0)));
+        assert_eq!(schedule.next_open(friday(23, 0)), Some(friday(23, 0)));
+
+        let weekdays = Schedule::new(480).with_window("mon-fri 09:00-17:00".parse().unwrap());
+        assert!(weekdays.is_open(friday(16, 59)));
+        // 周五17点之后下一次开放是下周一9点
+        assert_eq!(
+            weekdays.next_open(friday(17, 0)),
+            Some(friday(9, 0) + 3 * 24 * HOUR)
+        );
+        let weekend = Schedule::new(480).with_window("sat,sun 00:00-24:00".parse().unwrap());
+        assert!(!weekend.is_open(friday(23, 0)));
+        assert!(weekend.is_open(friday(23, 0) + 8 * HOUR));
+        assert!(Schedule::new(0).is_open(friday(3, 0)));
+
+        for invalid in ["", "22:00", "25:00-06:00", "xyz 01:00-02:00", "01:60-02:00"] {
+            assert!(invalid.parse::<TimeWindow>().is_err(), "{invalid}");
+        }
+        assert_eq!(
+            TimeWindow::daily((22, 0), (6, 0)),
+            "22:00-06:00".parse().ok()
+        );
+
+        let reset = night().with_quota_reset((0, 0));
+        assert_eq!(reset.next_reset(friday(12, 0)), Some(friday(24, 0)));
+        assert_eq!(reset.next_reset(friday(24, 0)), Some(friday(48, 0)));
+    }
+
+    /// 测试窗口之外排队的请求在窗口开放时按到达顺序放行
+    #[tokio::test(start_paused = true)]
+    async fn test_queue_released_in_order() {
+        let clock = MockClock::new(friday(21, 0));
+        let mock = Arc::new(MockTranslator::new());
+        let translator =
+            Arc::new(ScheduledTranslator::new(mock.clone(), night()).with_clock(Arc::new(clock)));
+        let released = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for i in 0..3 {
+            let translator = translator.clone();
+            let released = released.clone();
+            tasks.push(tokio::spawn(async move {
+                let text = format!("text {}", i);
+                translator
+                    .translate(&text, None, &Language::English)
+                    .await
+                    .unwrap();
+                released.lock().unwrap().push((i, clock.now()));
+            }));
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        tokio::time::sleep(HOUR / 2).await;
+        assert_eq!(mock.calls(), 0);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let released = released.lock().unwrap().clone();
+        assert_eq!(
+            released.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert!(released.iter().all(|(_, at)| *at >= friday(22, 0)));
+        assert!(released.iter().all(|(_, at)| *at < friday(22, 1)));
+        assert_eq!(
+            mock.requests().concat(),
+            ["text 0", "text 1", "text 2"].map(String::from)
+        );
+    }
+
+    /// 测试拒绝策略立即返回距离开放的时间
+    #[tokio::test(start_paused = true)]
+    async fn test_reject_outside_window() {
+        let mock = Arc::new(MockTranslator::new());
+        let translator = ScheduledTranslator::new(mock.clone(), night())
+            .with_policy(WindowPolicy::Reject)
+            .with_clock(Arc::new(MockClock::new(friday(21, 30))));
+        let err = translator
+            .translate("hello", None, &Language::English)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::OutsideWindow(Some(wait))) if *wait == HOUR / 2
+        ));
+        assert_eq!(mock.calls(), 0);
+        tokio::time::sleep(HOUR / 2).await;
+        assert!(translator
+            .translate("hello", None, &Language::English)
+            .await
+            .is_ok());
+    }
+
+    /// 测试额度用完后的请求等到额度重置时间才放行
+    #[tokio::test(start_paused = true)]
+    async fn test_quota_reset_releases_blocked_work() {
+        let clock = MockClock::new(friday(23, 0));
+        let mock = Arc::new(MockTranslator::new().quota_on("quota"));
+        let translator = ScheduledTranslator::new(mock.clone(), night().with_quota_reset((0, 0)))
+            .with_clock(Arc::new(clock));
+        let err = translator
+            .translate("quota", None, &Language::English)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Quota { .. })
+        ));
+        assert_eq!(translator.next_allowed(), Some(friday(24, 0)));
+
+        translator
+            .translate("hello", None, &Language::English)
+            .await
+            .unwrap();
+        assert!(clock.now() >= friday(24, 0));
+        assert_eq!(mock.calls(), 2);
+        assert_eq!(translator.next_allowed(), Some(clock.now()));
+    }
+}
@@ -0,0 +1,519 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// JSON值的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonType {
+    /// null
+    Null,
+    /// 布尔值
+    Bool,
+    /// 数字
+    Number,
+    /// 字符串
+    String,
+    /// 数组
+    Array,
+    /// 对象
+    Object,
+}
+
+impl JsonType {
+    /// 取得JSON值的类型
+    ///
+    /// # 参数
+    /// - `value`: JSON值
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonType::Null,
+            Value::Bool(_) => JsonType::Bool,
+            Value::Number(_) => JsonType::Number,
+            Value::String(_) => JsonType::String,
+            Value::Array(_) => JsonType::Array,
+            Value::Object(_) => JsonType::Object,
+        }
+    }
+}
+
+impl fmt::Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JsonType::Null => "null",
+            JsonType::Bool => "bool",
+            JsonType::Number => "number",
+            JsonType::String => "string",
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+        })
+    }
+}
+
+/// 结构差异的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceKind {
+    /// 预期结构中有、实际结构中没有的字段，附带预期的类型
+    Missing(JsonType),
+    /// 实际结构中新增的字段，附带实际的类型
+    Added(JsonType),
+    /// 字段类型改变
+    TypeChanged {
+        /// 预期的类型
+        expected: JsonType,
+        /// 实际的类型
+        actual: JsonType,
+    },
+}
+
+/// 一处结构差异
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDifference {
+    /// 字段路径，根为`$`，对象字段为`.key`，数组元素为`[]`
+    pub path: String,
+    /// 差异种类
+    pub kind: DifferenceKind,
+}
+
+impl fmt::Display for SchemaDifference {
+    /// 格式为`- 路径: 类型`（缺失）、`+ 路径: 类型`（新增）或`~ 路径: 预期类型 -> 实际类型`（类型改变）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            DifferenceKind::Missing(ty) => write!(f, "- {}: {}", self.path, ty),
+            DifferenceKind::Added(ty) => write!(f, "+ {}: {}", self.path, ty),
+            DifferenceKind::TypeChanged { expected, actual } => {
+                write!(f, "~ {}: {} -> {}", self.path, expected, actual)
+            }
+        }
+    }
+}
+
+/// JSON值的结构，只保留字段名和类型
+#[derive(Debug, Clone)]
+enum Shape {
+    /// 标量或null
+    Scalar(JsonType),
+    /// 数组，所有元素的结构合并为一个，空数组为None
+    Array(Option<Box<Shape>>),
+    /// 对象
+    Object(BTreeMap<String, Shape>),
+}
+
+impl Shape {
+    /// 提取JSON值的结构
+    ///
+    /// # 参数
+    /// - `value`: JSON值
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Array(items) => Shape::Array(
+                items
+                    .iter()
+                    .map(Shape::of)
+                    .reduce(Shape::merge)
+                    .map(Box::new),
+            ),
+            Value::Object(map) => {
+                Shape::Object(map.iter().map(|(k, v)| (k.clone(), Shape::of(v))).collect())
+            }
+            other => Shape::Scalar(JsonType::of(other)),
+        }
+    }
+
+    /// 结构对应的类型
+    fn json_type(&self) -> JsonType {
+        match self {
+            Shape::Scalar(ty) => *ty,
+            Shape::Array(_) => JsonType::Array,
+            Shape::Object(_) => JsonType::Object,
+        }
+    }
+
+    /// 合并同一数组中两个元素的结构
+    ///
+    /// 对象取字段的并集，null让位于其他类型，其余类型冲突时保留先出现的结构
+    fn merge(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Scalar(JsonType::Null), other) => other,
+            (this, Shape::Scalar(JsonType::Null)) => this,
+            (Shape::Object(mut a), Shape::Object(b)) => {
+                for (key, shape) in b {
+                    let merged = match a.remove(&key) {
+                        Some(existing) => existing.merge(shape),
+                        None => shape,
+                    };
+                    a.insert(key, merged);
+                }
+                Shape::Object(a)
+            }
+            (Shape::Array(a), Shape::Array(b)) => Shape::Array(match (a, b) {
+                (Some(a), Some(b)) => Some(Box::new(a.merge(*b))),
+                (a, b) => a.or(b),
+            }),
+            (this, _) => this,
+        }
+    }
+}
+
+/// 比较两个JSON值的结构
+///
+/// 只比较字段名和值的类型，不比较具体的值。数组的所有元素合并后比较，路径中记为`[]`；
+/// null与任意类型兼容（可选字段常以null表示缺省），预期为null的字段缺失时不视为差异，空数组与任意元素结构兼容
+///
+/// # 参数
+/// - `expected`: 预期的JSON值，通常是提交到仓库中的响应样例
+/// - `actual`: 实际的JSON值，通常是服务新返回的响应
+///
+/// # 返回值
+/// 按路径排序的结构差异，结构一致时为空
+pub fn structural_diff(expected: &Value, actual: &Value) -> Vec<SchemaDifference> {
+    let mut differences = Vec::new();
+    diff_shapes(
+        "$",
+        &Shape::of(expected),
+        &Shape::of(actual),
+        &mut differences,
+    );
+    differences
+}
+
+/// 把结构差异格式化为多行文本，每行一处差异
+///
+/// # 参数
+/// - `differences`: `structural_diff`返回的差异
+pub fn format_diff(differences: &[SchemaDifference]) -> String {
+    differences
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 递归比较两个结构
+///
+/// # 参数
+/// - `path`: 当前路径
+/// - `expected`: 预期的结构
+/// - `actual`: 实际的结构
+/// - `differences`: 收集差异的数组
+fn diff_shapes(
+    path: &str,
+    expected: &Shape,
+    actual: &Shape,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    match (expected, actual) {
+        (Shape::Scalar(JsonType::Null), _) | (_, Shape::Scalar(JsonType::Null)) => {}
+        (Shape::Object(expected), Shape::Object(actual)) => {
+            for (key, shape) in expected {
+                let child = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(other) => diff_shapes(&child, shape, other, differences),
+                    None if shape.json_type() == JsonType::Null => {}
+                    None => differences.push(SchemaDifference {
+                        path: child,
+                        kind: DifferenceKind::Missing(shape.json_type()),
+                    }),
+                }
+            }
+            for (key, shape) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(SchemaDifference {
+                        path: format!("{}.{}", path, key),
+                        kind: DifferenceKind::Added(shape.json_type()),
+                    });
+                }
+            }
+        }
+        (Shape::Array(expected), Shape::Array(actual)) => {
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                diff_shapes(&format!("{}[]", path), expected, actual, differences);
+            }
+        }
+        (expected, actual) if expected.json_type() != actual.json_type() => {
+            differences.push(SchemaDifference {
+                path: path.to_string(),
+                kind: DifferenceKind::TypeChanged {
+                    expected: expected.json_type(),
+                    actual: actual.json_type(),
+                },
+            })
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// 生成`FIXTURES`中的一项，内容在编译时读取
+    macro_rules! fixture {
+        ($backend:literal, $name:literal) => {
+            (
+                $backend,
+                $name,
+                include_str!(concat!("../../tests/fixtures/", $backend, "/", $name)),
+            )
+        };
+    }
+
+    use crate::fusion_translator::schema_check::{
+        format_diff, structural_diff, DifferenceKind, JsonType, SchemaDifference,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::{
+        alibaba_translator, baidu_translator, bing_web_translator, caiyun_translator,
+        google_web_translator, mymemory_translator, reverso_translator, transmart_translator,
+        youdao_translator,
+    };
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    /// 各翻译器的响应样例：（翻译器目录，样例文件名，样例内容）
+    const FIXTURES: &[(&str, &str, &str)] = &[
+        fixture!("alibaba", "success.json"),
+        fixture!("baidu", "error_numeric_code.json"),
+        fixture!("baidu", "error_string_code.json"),
+        fixture!("baidu", "error_with_result.json"),
+        fixture!("baidu", "job_pending.json"),
+        fixture!("baidu", "sentences.json"),
+        fixture!("baidu", "success_with_code.json"),
+        fixture!("baidu", "unexpected.json"),
+        fixture!("baidu", "word_en.json"),
+        fixture!("baidu", "word_zh.json"),
+        fixture!("bing", "captcha.json"),
+        fixture!("bing", "success.json"),
+        fixture!("bing", "token_expired.json"),
+        fixture!("caiyun", "invalid_token.json"),
+        fixture!("caiyun", "success.json"),
+        fixture!("google", "detect.json"),
+        fixture!("google", "multi.json"),
+        fixture!("google", "single.json"),
+        fixture!("mymemory", "success.json"),
+        fixture!("reverso", "sentence.json"),
+        fixture!("reverso", "word.json"),
+        fixture!("transmart", "rejected.json"),
+        fixture!("transmart", "success.json"),
+        fixture!("youdao", "batch_error.json"),
+        fixture!("youdao", "batch_error_numeric.json"),
+        fixture!("youdao", "batch_partial.json"),
+        fixture!("youdao", "batch_success.json"),
+        fixture!("youdao", "error_numeric_code.json"),
+        fixture!("youdao", "success.json"),
+    ];
+
+    /// 按翻译器当前的响应结构解析样例
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器目录
+    /// - `name`: 样例文件名
+    /// - `body`: 样例内容
+    fn parse_fixture(backend: &str, name: &str, body: &str) -> anyhow::Result<()> {
+        match backend {
+            "alibaba" => alibaba_translator::parse_fixture(name, body),
+            "baidu" => baidu_translator::parse_fixture(name, body),
+            "bing" => bing_web_translator::parse_fixture(name, body),
+            "caiyun" => caiyun_translator::parse_fixture(name, body),
+            "google" => google_web_translator::parse_fixture(name, body),
+            "mymemory" => mymemory_translator::parse_fixture(name, body),
+            "reverso" => reverso_translator::parse_fixture(name, body),
+            "transmart" => transmart_translator::parse_fixture(name, body),
+            "youdao" => youdao_translator::parse_fixture(name, body),
+            _ => anyhow::bail!("未知的翻译器：{}", backend),
+        }
+    }
+
+    /// 测试所有响应样例都能按当前的响应结构解析
+    #[test]
+    fn test_fixtures_deserialize() {
+        for (backend, name, body) in FIXTURES {
+            if let Err(err) = parse_fixture(backend, name, body) {
+                panic!("{}/{}: {:#}", backend, name, err);
+            }
+        }
+    }
+
+    /// 模糊测试：由响应样例变异得到的任意响应按各翻译器的响应结构解析都不会panic
+    #[test]
+    fn test_fuzz_fixtures() {
+        for (backend, name, body) in FIXTURES {
+            for input in fuzz::inputs(701, &[body]) {
+                let _ = parse_fixture(backend, name, &input);
+            }
+        }
+    }
+
+    /// 测试样例目录中的每个JSON文件都登记在`FIXTURES`中
+    #[test]
+    fn test_fixtures_registered() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let registered = FIXTURES
+            .iter()
+            .map(|(backend, name, _)| format!("{}/{}", backend, name))
+            .collect::<BTreeSet<_>>();
+        let mut on_disk = BTreeSet::new();
+        for backend in FIXTURES.iter().map(|(backend, _, _)| *backend) {
+            for entry in std::fs::read_dir(root.join(backend)).unwrap() {
+                let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+                if name.ends_with(".json") {
+                    on_disk.insert(format!("{}/{}", backend, name));
+                }
+            }
+        }
+        assert_eq!(registered, on_disk);
+    }
+
+    /// 向各翻译服务发送真实请求，检查响应结构与提交的样例一致
+    ///
+    /// 无需密钥的MyMemory、阿里、TranSmart、Reverso、Bing网页版和Google网页版总是检查，百度、有道和彩云在设置了对应的环境变量时检查。
+    /// 新的响应保存在临时目录中，便于更新样例。运行方式：
+    /// `cargo test --features live-schema-check -- --ignored live_schema`
+    #[cfg(feature = "live-schema-check")]
+    #[tokio::test]
+    #[ignore]
+    async fn test_live_schema() {
+        use crate::fusion_translator::alibaba_translator::AlibabaTranslator;
+        use crate::fusion_translator::baidu_translator::BaiduTranslator;
+        use crate::fusion_translator::bing_web_translator::BingWebTranslator;
+        use crate::fusion_translator::caiyun_translator::CaiyunTranslator;
+        use crate::fusion_translator::google_web_translator::GoogleWebTranslator;
+        use crate::fusion_translator::mymemory_translator::MyMemoryTranslator;
+        use crate::fusion_translator::reverso_translator::ReversoTranslator;
+        use crate::fusion_translator::transmart_translator::TranSmartTranslator;
+        use crate::fusion_translator::youdao_translator::YoudaoTranslator;
+        use std::env::var;
+
+        dotenv::dotenv().ok();
+        let mut samples = vec![
+            ("mymemory", MyMemoryTranslator::new().fetch_sample().await),
+            ("alibaba", AlibabaTranslator::new().fetch_sample().await),
+            ("transmart", TranSmartTranslator::new().fetch_sample().await),
+            ("reverso", ReversoTranslator::new().fetch_sample().await),
+            ("bing", BingWebTranslator::new().fetch_sample().await),
+            ("google", GoogleWebTranslator::new().fetch_sample().await),
+        ];
+        if let (Ok(app_id), Ok(key)) = (var("BAIDU_APP_ID"), var("BAIDU_KEY")) {
+            let translator = BaiduTranslator::new(&app_id, &key);
+            samples.push(("baidu", translator.fetch_sample().await));
+        }
+        if let (Ok(app_key), Ok(secret)) = (var("YOUDAO_APP_KEY"), var("YOUDAO_APP_SECRET")) {
+            let translator = YoudaoTranslator::new(&app_key, &secret);
+            samples.push(("youdao", translator.fetch_sample().await));
+        }
+        if let Ok(token) = var("CAIYUN_TOKEN") {
+            let translator = CaiyunTranslator::new(&token, "demo");
+            samples.push(("caiyun", translator.fetch_sample().await));
+        }
+
+        let output = std::env::temp_dir().join("fusion_translator_schema_check");
+        let mut failures = Vec::new();
+        for (backend, sample) in samples {
+            let (name, fresh) = match sample {
+                Ok(sample) => sample,
+                Err(err) => {
+                    failures.push(format!("{}: 请求失败：{:#}", backend, err));
+                    continue;
+                }
+            };
+            let path = output.join(backend).join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, serde_json::to_string_pretty(&fresh).unwrap()).unwrap();
+            let (_, _, committed) = FIXTURES
+                .iter()
+                .find(|(b, n, _)| *b == backend && *n == name)
+                .unwrap();
+            let differences = structural_diff(&serde_json::from_str(committed).unwrap(), &fresh);
+            if !differences.is_empty() {
+                failures.push(format!(
+                    "{}/{}（新响应：{}）\n{}",
+                    backend,
+                    name,
+                    path.display(),
+                    format_diff(&differences)
+                ));
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "响应结构与样例不一致：\n\n{}",
+            failures.join("\n\n")
+        );
+    }
+
+    /// 测试只有值不同时没有结构差异
+    #[test]
+    fn test_same_structure() {
+        let expected = json!({"code": 0, "data": {"text": "你好", "tags": ["a"]}});
+        let actual = json!({"code": 52, "data": {"text": "hello", "tags": ["b", "c"]}});
+        assert!(structural_diff(&expected, &actual).is_empty());
+    }
+
+    /// 测试缺失、新增和类型改变的字段
+    #[test]
+    fn test_differences() {
+        let expected = json!({"code": "0", "data": {"text": "a", "score": 1.0}});
+        let actual = json!({"code": 0, "data": {"text": "a", "extra": true}});
+        let differences = structural_diff(&expected, &actual);
+        assert_eq!(
+            differences,
+            vec![
+                SchemaDifference {
+                    path: "$.code".to_string(),
+                    kind: DifferenceKind::TypeChanged {
+                        expected: JsonType::String,
+                        actual: JsonType::Number,
+                    },
+                },
+                SchemaDifference {
+                    path: "$.data.score".to_string(),
+                    kind: DifferenceKind::Missing(JsonType::Number),
+                },
+                SchemaDifference {
+                    path: "$.data.extra".to_string(),
+                    kind: DifferenceKind::Added(JsonType::Bool),
+                },
+            ]
+        );
+        assert_eq!(
+            format_diff(&differences),
+            "~ $.code: string -> number\n- $.data.score: number\n+ $.data.extra: bool"
+        );
+    }
+
+    /// 测试数组元素合并后比较，null和空数组与任意结构兼容
+    #[test]
+    fn test_arrays_and_null() {
+        let expected = json!({
+            "matches": [{"id": 1, "reference": null}, {"id": 2, "subject": "All"}],
+            "empty": [],
+            "optional": null,
+        });
+        let actual = json!({
+            "matches": [{"id": 3, "reference": "x", "subject": "All"}],
+            "empty": [{"a": 1}],
+            "optional": {"nested": true},
+        });
+        assert!(structural_diff(&expected, &actual).is_empty());
+
+        let renamed = json!({
+            "matches": [{"id": "3", "subject": "All"}, {"id": "4", "domain": 1}],
+            "empty": [],
+            "optional": null,
+        });
+        assert_eq!(
+            format_diff(&structural_diff(&expected, &renamed)),
+            "~ $.matches[].id: number -> string\n+ $.matches[].domain: number"
+        );
+        assert_eq!(
+            format_diff(&structural_diff(&json!([[1]]), &json!([["1"], {}]))),
+            "~ $[][]: number -> string"
+        );
+    }
+
+    /// 测试根节点类型不同
+    #[test]
+    fn test_root_type_changed() {
+        assert_eq!(
+            format_diff(&structural_diff(&json!({"a": 1}), &json!("error"))),
+            "~ $: object -> string"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::long_text::detect_script;
+use std::ops::Range;
+
+/// 中日韩句末标点，之后不需要空白即可断句
+const CJK_TERMINATORS: &[char] = &['。', '！', '？', '…', '｡', '‼', '⁇', '⁈', '⁉'];
+
+/// 西文句末标点，之后需要空白或文本结束才断句
+const WESTERN_TERMINATORS: &[char] = &['.', '!', '?'];
+
+/// 成对的引号和括号（开，闭）
+const BRACKETS: &[(char, char)] = &[
+    ('“', '”'),
+    ('‘', '’'),
+    ('「', '」'),
+    ('『', '』'),
+    ('（', '）'),
+    ('(', ')'),
+    ('[', ']'),
+    ('《', '》'),
+    ('〈', '〉'),
+    ('«', '»'),
+];
+
+/// 句末标点之后仍属于同一句的闭合符号
+const CLOSING_MARKS: &[char] = &[
+    '”', '’', '」', '』', '）', ')', ']', '》', '〉', '»', '"', '\'',
+];
+
+/// 日语引用之后表示引述的助词
+const QUOTATIVE_PARTICLES: &[char] = &['と', 'っ'];
+
+/// 以`.`结尾但不表示句子结束的常见缩写（小写，不含末尾的`.`）
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "etc", "e.g", "i.e", "cf", "al",
+    "approx", "dept", "est", "fig", "inc", "ltd", "co", "corp", "no", "vol", "pp", "u.s", "u.k",
+    "a.m", "p.m", "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov",
+    "dec",
+];
+
+/// 按语言拆分句子
+///
+/// 拼接所有句子即可逐字节还原原文：句子之后的空白（包括换行）归入前一句。
+/// 断句规则：
+/// - 中日韩句末标点（`。！？…`）之后直接断句
+/// - 西文`.`、`!`、`?`之后需要空白或文本结束，且下一句不以小写字母开头；
+///   `.`前是常见缩写（如`Mr.`、`e.g.`）或单个大写字母（如人名缩写`J.`）时不断句，
+///   数字中的小数点不会断句
+/// - 连续的句末标点（如`?!`、`...`、`……`）视为一个整体
+/// - 引号和括号内不断句；在引号内结束的句子保留闭合引号，在引号闭合后断句，
+///   日语引号之后紧跟`と`、`って`时不断句
+/// - 换行总是结束当前句子
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+/// - `lang`: 文本语言，None时按文字检测；中日韩文本中的半角`!`、`?`之后不需要空白也会断句
+///
+/// # 返回值
+/// 句子列表，空文本返回空列表
+pub fn split_sentences(text: &str, lang: Option<Language>) -> Vec<&str> {
+    split_sentence_ranges(text, lang)
+        .into_iter()
+        .map(|range| &text[range])
+        .collect()
+}
+
+/// 按语言拆分句子，返回每个句子的字节范围
+///
+/// 规则与`split_sentences`相同，相邻的范围首尾相接并覆盖整个文本
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+/// - `lang`: 文本语言，None时按文字检测
+///
+/// # 返回值
+/// 每个句子在原文中的字节范围
+pub fn split_sentence_ranges(text: &str, lang: Option<Language>) -> Vec<Range<usize>> {
+    let cjk = lang
+        .or_else(|| detect_script(text))
+        .is_some_and(|lang| is_cjk_language(&lang));
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut in_ascii_quote = false;
+    let mut i = 0;
+    while let Some(&(pos, c)) = chars.get(i) {
+        i += 1;
+        if c == '\n' {
+            depth = 0;
+            in_ascii_quote = false;
+            let end = skip_whitespace(&chars, &mut i, text.len());
+            ranges.push(start..end);
+            start = end;
+            continue;
+        }
+        if c == '"' {
+            in_ascii_quote = !in_ascii_quote;
+            continue;
+        }
+        if BRACKETS.iter().any(|(open, _)| *open == c) {
+            depth += 1;
+            continue;
+        }
+        if BRACKETS.iter().any(|(_, close)| *close == c) {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        let western = WESTERN_TERMINATORS.contains(&c);
+        if !western && !CJK_TERMINATORS.contains(&c) {
+            continue;
+        }
+        // 连续的句末标点视为一个整体
+        let mut strong = !western || (cjk && c != '.');
+        while let Some(&(_, next)) = chars.get(i) {
+            if !WESTERN_TERMINATORS.contains(&next) && !CJK_TERMINATORS.contains(&next) {
+                break;
+            }
+            strong |= !WESTERN_TERMINATORS.contains(&next) || (cjk && next != '.');
+            i += 1;
+        }
+        if c == '.' && chars.get(i).is_none_or(|(_, next)| next.is_whitespace()) {
+            let word = word_before(text, pos);
+            if is_abbreviation(word) {
+                continue;
+            }
+        }
+        // 引号和括号内不断句，闭合符号属于当前句
+        let quoted_end = i;
+        let mut closed = depth == 0 && !in_ascii_quote;
+        while let Some(&(_, next)) = chars.get(i) {
+            if !CLOSING_MARKS.contains(&next) {
+                break;
+            }
+            if next == '"' {
+                if !in_ascii_quote {
+                    break;
+                }
+                in_ascii_quote = false;
+            } else if BRACKETS.iter().any(|(_, close)| *close == next) {
+                depth = depth.saturating_sub(1);
+            }
+            closed = depth == 0 && !in_ascii_quote;
+            i += 1;
+        }
+        if !closed {
+            continue;
+        }
+        let after = chars.get(i).map(|&(_, next)| next);
+        // 日语引用后接`と`、`って`时句子继续，如「はい。」と言った
+        if i > quoted_end && after.is_some_and(|next| QUOTATIVE_PARTICLES.contains(&next)) {
+            continue;
+        }
+        if !strong {
+            let continues = match after {
+                None => false,
+                Some(next) if next.is_whitespace() => next_word_is_lowercase(&chars, i),
+                Some(_) => true,
+            };
+            if continues {
+                continue;
+            }
+        }
+        let end = skip_whitespace(&chars, &mut i, text.len());
+        ranges.push(start..end);
+        start = end;
+    }
+    if start < text.len() {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+/// 是否为中日韩语言
+pub(crate) fn is_cjk_language(lang: &Language) -> bool {
+    matches!(
+        lang,
+        Language::Chinese | Language::ChineseTraditional | Language::Japanese
+    )
+}
+
+/// 跳过空白，返回空白之后的字节位置
+///
+/// # 参数
+/// - `chars`: 文本的字符及其位置
+/// - `i`: 当前字符下标，跳过后指向第一个非空白字符
+/// - `len`: 文本的字节长度
+fn skip_whitespace(chars: &[(usize, char)], i: &mut usize, len: usize) -> usize {
+    while chars.get(*i).is_some_and(|(_, c)| c.is_whitespace()) {
+        *i += 1;
+    }
+    chars.get(*i).map_or(len, |&(pos, _)| pos)
+}
+
+/// 空白之后的第一个字符是否为小写字母
+///
+/// # 参数
+/// - `chars`: 文本的字符及其位置
+/// - `i`: 空白开始的字符下标
+fn next_word_is_lowercase(chars: &[(usize, char)], i: usize) -> bool {
+    chars
+        .iter()
+        .skip(i)
+        .find(|(_, c)| !c.is_whitespace())
+        .is_some_and(|(_, c)| c.is_lowercase())
+}
+
+/// 获取`.`之前的单词（包含其中的`.`，如`e.g`）
+///
+/// # 参数
+/// - `text`: 原文
+/// - `pos`: `.`的字节位置
+fn word_before(text: &str, pos: usize) -> &str {
+    let before = &text[..pos];
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '.'))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    &before[start..]
+}
+
+/// 是否为不结束句子的缩写
+///
+/// # 参数
+/// - `word`: `.`之前的单词
+fn is_abbreviation(word: &str) -> bool {
+    let mut letters = word.chars();
+    // 单个大写字母，如人名缩写`J. K. Rowling`
+    if let (Some(first), None) = (letters.next(), letters.next()) {
+        if first.is_uppercase() {
+            return true;
+        }
+    }
+    let lower = word.to_lowercase();
+    ABBREVIATIONS.contains(&lower.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::segment::{split_sentence_ranges, split_sentences};
+    use crate::fusion_translator::test_support::fuzz;
+
+    /// 英文断句
+    #[test]
+    fn test_english() {
+        let cases: &[(&str, &[&str])] = &[
+            (
+                "Hello world. How are you?",
+                &["Hello world. ", "How are you?"],
+            ),
+            ("Wait!! Really?! Yes.", &["Wait!! ", "Really?! ", "Yes."]),
+            ("Pi is 3.14 approx. Fine.", &["Pi is 3.14 approx. Fine."]),
+            ("It costs $3.50. Cheap.", &["It costs $3.50. ", "Cheap."]),
+            (
+                "Mr. Smith met Dr. Jones. They talked.",
+                &["Mr. Smith met Dr. Jones. ", "They talked."],
+            ),
+            (
+                "Use tools, e.g. hammers. Then rest.",
+                &["Use tools, e.g. hammers. ", "Then rest."],
+            ),
+            (
+                "J. K. Rowling wrote it. Yes.",
+                &["J. K. Rowling wrote it. ", "Yes."],
+            ),
+            ("I think... maybe not.", &["I think... maybe not."]),
+            ("Wait... What happened?", &["Wait... ", "What happened?"]),
+            (
+                "He said \"Stop.\" Then he left.",
+                &["He said \"Stop.\" ", "Then he left."],
+            ),
+            (
+                "\"Hello!\" he said. She waved.",
+                &["\"Hello!\" he said. ", "She waved."],
+            ),
+            (
+                "(This is one. And two.) Next.",
+                &["(This is one. And two.) ", "Next."],
+            ),
+            (
+                "Visit example.com today. Ok.",
+                &["Visit example.com today. ", "Ok."],
+            ),
+            ("Line one\nLine two", &["Line one\n", "Line two"]),
+            ("First.\n\nSecond.", &["First.\n\n", "Second."]),
+            ("No terminator", &["No terminator"]),
+            ("Trailing spaces.   ", &["Trailing spaces.   "]),
+            ("  Leading. Text.", &["  Leading. ", "Text."]),
+            ("It's done. Isn't it?", &["It's done. ", "Isn't it?"]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                split_sentences(text, Some(Language::English)),
+                *expected,
+                "{text:?}"
+            );
+        }
+    }
+
+    /// 中文断句
+    #[test]
+    fn test_chinese() {
+        let cases: &[(&str, &[&str])] = &[
+            ("你好。今天天气很好！", &["你好。", "今天天气很好！"]),
+            ("真的吗？！我不信。", &["真的吗？！", "我不信。"]),
+            (
+                "他说：“你好。”然后走了。",
+                &["他说：“你好。”", "然后走了。"],
+            ),
+            (
+                "他说：“你好。我是小明。”大家笑了。",
+                &["他说：“你好。我是小明。”", "大家笑了。"],
+            ),
+            ("等等……好吧。", &["等等……", "好吧。"]),
+            ("圆周率约为3.14。对吗", &["圆周率约为3.14。", "对吗"]),
+            ("好!我来了?嗯", &["好!", "我来了?", "嗯"]),
+            (
+                "（括号里。还有。）外面。",
+                &["（括号里。还有。）", "外面。"],
+            ),
+            ("第一行\n第二行。", &["第一行\n", "第二行。"]),
+            ("版本v1.2发布了。", &["版本v1.2发布了。"]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                split_sentences(text, Some(Language::Chinese)),
+                *expected,
+                "{text:?}"
+            );
+        }
+    }
+
+    /// 日文断句
+    #[test]
+    fn test_japanese() {
+        let cases: &[(&str, &[&str])] = &[
+            (
+                "こんにちは。元気ですか？",
+                &["こんにちは。", "元気ですか？"],
+            ),
+            ("彼は「はい。」と言った。", &["彼は「はい。」と言った。"]),
+            (
+                "「行こう。」彼は言った。",
+                &["「行こう。」", "彼は言った。"],
+            ),
+            ("すごい！！本当に？", &["すごい！！", "本当に？"]),
+            ("ｱﾘｶﾞﾄｳ｡ﾏﾀﾈ", &["ｱﾘｶﾞﾄｳ｡", "ﾏﾀﾈ"]),
+            ("えっと…そうですね。", &["えっと…", "そうですね。"]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                split_sentences(text, Some(Language::Japanese)),
+                *expected,
+                "{text:?}"
+            );
+        }
+    }
+
+    /// 未指定语言时按文字检测
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(split_sentences("好!走吧", None), vec!["好!", "走吧"]);
+        assert_eq!(split_sentences("Go!now", None), vec!["Go!now"]);
+        assert!(split_sentences("", None).is_empty());
+        assert!(split_sentence_ranges("", Some(Language::English)).is_empty());
+    }
+
+    /// 随机文本拆分后首尾相接，拼接结果与原文逐字节相同
+    #[test]
+    fn test_roundtrip_random() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        const ALPHABET: &[&str] = &[
+            "a", "B", "z", "1", ".", "!", "?", " ", "\n", "\t", "\"", "'", "(", ")", "“", "”",
+            "「", "」", "。", "！", "？", "…", "你", "好", "の", "Mr", "e.g", "3.14", "\u{a0}",
+        ];
+        let mut rng = StdRng::seed_from_u64(646);
+        for _ in 0..500 {
+            let text = (0..rng.random_range(0..40))
+                .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())])
+                .collect::<String>();
+            for lang in [None, Some(Language::English), Some(Language::Chinese)] {
+                let ranges = split_sentence_ranges(&text, lang);
+                let mut expected_start = 0;
+                for range in &ranges {
+                    assert_eq!(range.start, expected_start, "{text:?}");
+                    assert!(range.end > range.start, "{text:?}");
+                    expected_start = range.end;
+                }
+                assert_eq!(expected_start, text.len(), "{text:?}");
+                assert_eq!(split_sentences(&text, lang).concat(), text);
+            }
+        }
+    }
+
+    /// 模糊测试：任意文本都能拆分，范围首尾相接并覆盖整个文本
+    #[test]
+    fn test_fuzz_split() {
+        let corpus = [
+            include_str!("../../tests/fixtures/long_text/mixed.txt"),
+            "Mr. J. Smith paid $3.50 (e.g. \"cash\"). Really?! Yes... \u{201c}Fine.\u{201d} ok",
+            "他说：“你好！”然后走了……真的吗？！（是的。）",
+            "「行きます。」と言った。本当？",
+        ];
+        let langs = [
+            None,
+            Some(Language::English),
+            Some(Language::Chinese),
+            Some(Language::Japanese),
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            for lang in langs {
+                let ranges = split_sentence_ranges(&input, lang);
+                let mut end = 0;
+                for range in &ranges {
+                    assert_eq!(range.start, end, "{:?}", input);
+                    assert!(input.is_char_boundary(range.end), "{:?}", input);
+                    end = range.end;
+                }
+                assert_eq!(end, input.len(), "{:?}", input);
+                assert_eq!(split_sentences(&input, lang).concat(), input);
+            }
+        }
+    }
+}
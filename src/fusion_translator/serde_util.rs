@@ -0,0 +1,140 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// 把字符串或数字解析为字符串
+///
+/// 用于同一字段在不同接口或账号等级下有时返回数字（如`52003`）、
+/// 有时返回字符串（如`"52003"`）的情况；整数不带小数点
+pub(crate) fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or a number, found {}",
+            other
+        ))),
+    }
+}
+
+/// 把可能缺失的字符串或数字解析为字符串
+///
+/// 与`string_or_number`相同，但字段为`null`时解析为None，需要配合`default`处理字段缺失
+pub(crate) fn optional_string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => string_or_number(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// 宽松解析浮点数
+///
+/// 接受数字和数字字符串（允许首尾空白），
+/// 无法解析的值视为None而不是让整个响应解析失败
+pub(crate) fn lenient_f32<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(n) => n.as_f64().map(|v| v as f32),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// 把`null`解析为默认值
+///
+/// serde的`default`只处理字段缺失的情况，字段显式为`null`时仍会报错
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::serde_util::{
+        lenient_f32, null_as_default, optional_string_or_number, string_or_number,
+    };
+    use serde::Deserialize;
+
+    /// 测试用结构
+    #[derive(Debug, Deserialize)]
+    struct Sample {
+        #[serde(default, deserialize_with = "string_or_number")]
+        code: String,
+        #[serde(default, deserialize_with = "optional_string_or_number")]
+        optional_code: Option<String>,
+        #[serde(default, deserialize_with = "lenient_f32")]
+        score: Option<f32>,
+        #[serde(default, deserialize_with = "null_as_default")]
+        items: Vec<String>,
+    }
+
+    /// 解析JSON
+    fn parse(json: &str) -> Sample {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// 测试字符串和数字都解析为字符串
+    #[test]
+    fn test_string_or_number() {
+        assert_eq!(parse(r#"{"code": "52003"}"#).code, "52003");
+        assert_eq!(parse(r#"{"code": 52003}"#).code, "52003");
+        assert_eq!(parse(r#"{"code": 0}"#).code, "0");
+        assert_eq!(parse(r#"{"code": -1}"#).code, "-1");
+        assert_eq!(parse(r#"{"code": 1.5}"#).code, "1.5");
+        assert_eq!(parse("{}").code, "");
+        assert!(serde_json::from_str::<Sample>(r#"{"code": [1]}"#).is_err());
+    }
+
+    /// 测试可选的字符串或数字
+    #[test]
+    fn test_optional_string_or_number() {
+        assert_eq!(
+            parse(r#"{"optional_code": 52000}"#)
+                .optional_code
+                .as_deref(),
+            Some("52000")
+        );
+        assert_eq!(
+            parse(r#"{"optional_code": "54003"}"#)
+                .optional_code
+                .as_deref(),
+            Some("54003")
+        );
+        assert_eq!(parse(r#"{"optional_code": null}"#).optional_code, None);
+        assert_eq!(parse("{}").optional_code, None);
+        assert!(serde_json::from_str::<Sample>(r#"{"optional_code": {}}"#).is_err());
+    }
+
+    /// 测试宽松解析浮点数
+    #[test]
+    fn test_lenient_f32() {
+        assert_eq!(parse(r#"{"score": 0.85}"#).score, Some(0.85));
+        assert_eq!(parse(r#"{"score": 1}"#).score, Some(1.0));
+        assert_eq!(parse(r#"{"score": " 0.5 "}"#).score, Some(0.5));
+        assert_eq!(parse(r#"{"score": "n/a"}"#).score, None);
+        assert_eq!(parse(r#"{"score": null}"#).score, None);
+        assert_eq!(parse(r#"{"score": false}"#).score, None);
+        assert_eq!(parse("{}").score, None);
+    }
+
+    /// 测试null解析为默认值
+    #[test]
+    fn test_null_as_default() {
+        assert_eq!(parse(r#"{"items": null}"#).items, Vec::<String>::new());
+        assert_eq!(parse(r#"{"items": ["a"]}"#).items, vec!["a"]);
+        assert_eq!(parse("{}").items, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::validate_batch;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 会话记录的键：（翻译器名称，原文，源语言，目标语言）
+type SessionKey = (String, String, Option<Language>, Language);
+
+/// 会话的调用统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// 通过会话请求翻译的文本数
+    pub requested: usize,
+    /// 直接使用会话记录、没有发送给翻译器的文本数
+    pub reused: usize,
+    /// 调用翻译器的次数
+    pub upstream_calls: usize,
+    /// 发送给翻译器的文本数
+    pub upstream_texts: usize,
+}
+
+/// 单次逻辑操作内的翻译记录
+///
+/// 记录`(翻译器名称, 原文, 源语言, 目标语言) -> 译文`，在同一个操作中再次请求相同的文本时
+/// 直接返回记录的结果，不会重复调用翻译器、重复消耗配额。只记录成功的结果，失败的文本下次仍会请求。
+///
+/// 会话只应覆盖一次逻辑操作（如一次长文本翻译），同一会话内的调用应使用相同的调用选项；
+/// 跨操作复用译文请使用`cache::CachedTranslator`。并发发出的相同请求不会合并，
+/// 调用方应在并发前去重
+#[derive(Debug, Default)]
+pub struct TranslationSession {
+    /// 已完成的翻译结果
+    entries: Mutex<HashMap<SessionKey, TranslationOutput>>,
+    /// 调用统计
+    stats: Mutex<SessionStats>,
+}
+
+impl TranslationSession {
+    /// 创建空的会话
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前的调用统计
+    pub fn stats(&self) -> SessionStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// 生成会话记录的键
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器名称
+    /// - `query`: 原文
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    fn key(backend: &str, query: &str, from: Option<Language>, to: &Language) -> SessionKey {
+        (backend.to_string(), query.to_string(), from, *to)
+    }
+
+    /// 通过会话翻译单个文本
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器名称，只使用一个翻译器的操作可以使用空字符串
+    /// - `translator`: 翻译器
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果，会话中已有记录时直接返回记录
+    pub async fn translate(
+        &self,
+        backend: &str,
+        translator: &dyn AsyncTranslator,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let key = Self::key(backend, query, from, to);
+        let recorded = self.entries.lock().unwrap().get(&key).cloned();
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.requested += 1;
+            if recorded.is_some() {
+                stats.reused += 1;
+            } else {
+                stats.upstream_calls += 1;
+                stats.upstream_texts += 1;
+            }
+        }
+        if let Some(output) = recorded {
+            return Ok(output);
+        }
+        let output = translator
+            .translate_with_options(query, from, to, options)
+            .await?;
+        self.entries.lock().unwrap().insert(key, output.clone());
+        Ok(output)
+    }
+
+    /// 通过会话翻译多个文本
+    ///
+    /// 会话中没有记录的文本去重后合并为一次`translate_vec_with_options`调用，
+    /// 全部文本都有记录时不调用翻译器
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器名称，只使用一个翻译器的操作可以使用空字符串
+    /// - `translator`: 翻译器
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 与`query`一一对应的译文；语言和附加信息取自本次调用，没有调用时取自第一条记录
+    pub async fn translate_vec(
+        &self,
+        backend: &str,
+        translator: &dyn AsyncTranslator,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let mut missing = Vec::new();
+        {
+            let entries = self.entries.lock().unwrap();
+            for q in query {
+                if !entries.contains_key(&Self::key(backend, q, from, to)) && !missing.contains(q) {
+                    missing.push(q.clone());
+                }
+            }
+        }
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.requested += query.len();
+            stats.reused += query.len() - missing.len();
+            if !missing.is_empty() {
+                stats.upstream_calls += 1;
+                stats.upstream_texts += missing.len();
+            }
+        }
+
+        let mut result = TranslationListOutput::default();
+        if !missing.is_empty() {
+            let output = translator
+                .translate_vec_with_options(&missing, from, to, options)
+                .await?;
+            let text = validate_batch(missing.len(), output.text)?;
+            let mut entries = self.entries.lock().unwrap();
+            for (q, text) in missing.iter().zip(text) {
+                let recorded = TranslationOutput {
+                    text,
+                    lang: output.lang,
+                    detail: output.detail.clone(),
+                };
+                entries.insert(Self::key(backend, q, from, to), recorded);
+            }
+            result.lang = output.lang;
+            result.detail = output.detail;
+        }
+
+        let entries = self.entries.lock().unwrap();
+        for (i, q) in query.iter().enumerate() {
+            let recorded = &entries[&Self::key(backend, q, from, to)];
+            if i == 0 && missing.is_empty() {
+                result.lang = recorded.lang;
+                result.detail = recorded.detail.clone();
+            }
+            result.detail.possibly_untranslated |= recorded.detail.possibly_untranslated;
+            result.text.push(recorded.text.clone());
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::session::{SessionStats, TranslationSession};
+    use crate::fusion_translator::translate_options::TranslateOptions;
+
+    /// 测试同一会话内相同的请求只调用一次翻译器，不同翻译器和语言分别记录
+    #[tokio::test]
+    async fn test_translate_reuses_results() {
+        let session = TranslationSession::new();
+        let primary = MockTranslator::new();
+        let secondary = MockTranslator::new();
+        let options = TranslateOptions::default();
+        for _ in 0..3 {
+            let output = session
+                .translate("a", &primary, "hello", None, &Language::French, &options)
+                .await
+                .unwrap();
+            assert_eq!(output.text, "HELLO");
+        }
+        session
+            .translate("b", &secondary, "hello", None, &Language::French, &options)
+            .await
+            .unwrap();
+        session
+            .translate("a", &primary, "hello", None, &Language::German, &options)
+            .await
+            .unwrap();
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(secondary.calls(), 1);
+        assert_eq!(
+            session.stats(),
+            SessionStats {
+                requested: 5,
+                reused: 2,
+                upstream_calls: 3,
+                upstream_texts: 3,
+            }
+        );
+    }
+
+    /// 测试部分失败后重试整批时只重新发送失败的文本
+    ///
+    /// 模拟按分块翻译、第二个分块失败后换用另一个翻译器整批重试的调用方
+    #[tokio::test]
+    async fn test_retry_after_partial_failure() {
+        let session = TranslationSession::new();
+        let flaky = MockTranslator::new().fail_on("boom");
+        let backup = MockTranslator::new();
+        let options = TranslateOptions::default();
+        let query = ["one", "two", "boom", "four"].map(String::from);
+        let to = Language::French;
+
+        let first = session
+            .translate_vec("flaky", &flaky, &query[..2], None, &to, &options)
+            .await
+            .unwrap();
+        assert_eq!(first.text, ["ONE", "TWO"]);
+        assert!(session
+            .translate_vec("flaky", &flaky, &query[2..], None, &to, &options)
+            .await
+            .is_err());
+        // 重试同一翻译器时已成功的分块不再发送
+        assert!(session
+            .translate_vec("flaky", &flaky, &query, None, &to, &options)
+            .await
+            .is_err());
+        assert_eq!(
+            flaky.requests(),
+            vec![
+                vec!["one".to_string(), "two".to_string()],
+                vec!["boom".to_string(), "four".to_string()],
+                vec!["boom".to_string(), "four".to_string()],
+            ]
+        );
+
+        let output = session
+            .translate_vec("backup", &backup, &query[2..], None, &to, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["BOOM", "FOUR"]);
+        let again = session
+            .translate_vec("backup", &backup, &query[2..], None, &to, &options)
+            .await
+            .unwrap();
+        assert_eq!(again.text, output.text);
+        assert_eq!(backup.calls(), 1);
+    }
+
+    /// 测试批量请求中的重复文本只发送一次
+    #[tokio::test]
+    async fn test_translate_vec_deduplicates() {
+        let session = TranslationSession::new();
+        let mock = MockTranslator::new();
+        let query = ["a", "b", "a"].map(String::from);
+        let output = session
+            .translate_vec(
+                "",
+                &mock,
+                &query,
+                None,
+                &Language::French,
+                &TranslateOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, ["A", "B", "A"]);
+        assert_eq!(
+            mock.requests(),
+            vec![vec!["a".to_string(), "b".to_string()]]
+        );
+        assert_eq!(session.stats().reused, 1);
+    }
+}
@@ -0,0 +1,17 @@
+/// 可以正常关闭的组件
+///
+/// 在内存中缓冲请求或结果的组件（翻译队列、逐行翻译流、可续传批量翻译、用量记录）实现这个trait。
+/// `shutdown`返回前组件不再接受新的工作，已接受的工作按各自文档处理完毕或写入磁盘；
+/// 无法完成的请求得到`TranslatorError::Cancelled`而不是被静默丢弃。
+/// 每个实现都在文档中说明进程在关闭过程中被强制终止时可能丢失的部分
+///
+/// 多次调用是安全的，之后的调用不再等待已结束的工作
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+pub trait Shutdown {
+    /// 停止接受新的工作，并处理完或保存已接受的工作
+    ///
+    /// # 返回值
+    /// 保存缓冲的数据失败时返回错误
+    async fn shutdown(&self) -> anyhow::Result<()>;
+}
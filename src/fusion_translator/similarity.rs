@@ -0,0 +1,381 @@
+use crate::fusion_translator::postprocess::{is_cjk, normalize_nfc};
+use icu_normalizer::properties::CanonicalCombiningClassMapBorrowed;
+use std::collections::HashMap;
+
+/// 零宽连接符，连接前后两个字符组成一个字素簇（如家庭表情符号）
+const ZWJ: char = '\u{200D}';
+
+/// 是否为附加到前一个字符上的字符
+///
+/// 包括组合类不为0的组合字符、变体选择符、表情符号肤色修饰符、标签字符、
+/// 组合用围框符号（如键帽）和零宽连接符
+///
+/// # 参数
+/// - `c`: 字符
+/// - `ccc`: 组合类查询表
+fn is_extend(c: char, ccc: &CanonicalCombiningClassMapBorrowed) -> bool {
+    ccc.get_u8(c) != 0
+        || matches!(c,
+            '\u{0300}'..='\u{036F}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}'
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            | '\u{E0020}'..='\u{E007F}'
+            | '\u{E0100}'..='\u{E01EF}'
+            | ZWJ)
+}
+
+/// 是否为区域指示符号，两个组成一个国旗
+///
+/// # 参数
+/// - `c`: 字符
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// 把文本拆分为字素簇
+///
+/// 近似Unicode扩展字素簇：组合字符、变体选择符、肤色修饰符和零宽连接符连接的表情符号、
+/// 成对的区域指示符号（国旗）以及`\r\n`各自与前面的字符组成一个簇。
+/// 不处理韩文字母组合等少见情况，韩文音节通常已是预组合字符
+///
+/// # 参数
+/// - `text`: 文本
+///
+/// # 返回值
+/// 按顺序排列的字素簇，拼接后与原文相同
+pub fn graphemes(text: &str) -> Vec<&str> {
+    let ccc = CanonicalCombiningClassMapBorrowed::new();
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    // 当前簇的上一个字符，以及当前簇中区域指示符号的数量
+    let mut prev: Option<char> = None;
+    let mut regional = 0;
+    for (i, c) in text.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some(p) => {
+                is_extend(c, &ccc)
+                    || p == ZWJ
+                    || (p == '\r' && c == '\n')
+                    || (is_regional_indicator(c) && regional % 2 == 1)
+            }
+        };
+        if !joins && i > start {
+            clusters.push(&text[start..i]);
+            start = i;
+            regional = 0;
+        }
+        if is_regional_indicator(c) {
+            regional += 1;
+        }
+        prev = Some(c);
+    }
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+    clusters
+}
+
+/// 比较前的规范化：NFC规范化后转为小写
+///
+/// # 参数
+/// - `text`: 文本
+fn fold(text: &str) -> String {
+    normalize_nfc(text).to_lowercase()
+}
+
+/// 字素簇是否为文字（首字符为字母或数字），标点、空白和符号不参与比较
+///
+/// # 参数
+/// - `cluster`: 字素簇
+fn is_word_cluster(cluster: &str) -> bool {
+    cluster.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+/// 按文字片段拆分：标点、空白和符号之间的连续文字为一个片段
+///
+/// # 参数
+/// - `clusters`: 字素簇
+///
+/// # 返回值
+/// 每个片段的字素簇
+fn runs<'a>(clusters: &[&'a str]) -> Vec<Vec<&'a str>> {
+    clusters
+        .split(|c| !is_word_cluster(c))
+        .filter(|run| !run.is_empty())
+        .map(<[&str]>::to_vec)
+        .collect()
+}
+
+/// 两个计数表中共同的计数和总计数
+///
+/// # 返回值
+/// （按最小出现次数计的共同单位数，两表的单位总数）
+fn overlap(a: &HashMap<&str, usize>, b: &HashMap<&str, usize>) -> (usize, usize) {
+    let common = a
+        .iter()
+        .map(|(unit, count)| b.get(unit).map_or(0, |other| (*count).min(*other)))
+        .sum::<usize>();
+    let total = a.values().sum::<usize>() + b.values().sum::<usize>();
+    (common, total)
+}
+
+/// 按字素簇二元组统计出现次数
+///
+/// 每个文字片段内相邻的两个字素簇组成一个二元组，只有一个字素簇的片段按单个字素簇统计
+///
+/// # 参数
+/// - `text`: 已规范化的文本
+fn bigrams(text: &str) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    let clusters = graphemes(text);
+    for run in runs(&clusters) {
+        if run.len() == 1 {
+            *counts.entry(run[0]).or_insert(0) += 1;
+            continue;
+        }
+        for pair in run.windows(2) {
+            // 相邻的字素簇在原文中连续，二元组是原文的一个切片
+            let start = pair[0].as_ptr() as usize - text.as_ptr() as usize;
+            let end = start + pair[0].len() + pair[1].len();
+            *counts.entry(&text[start..end]).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// 按词统计出现次数
+///
+/// 文字片段按空白和标点切分为词；包含中日韩文字的片段没有词边界，按单个字素簇统计
+///
+/// # 参数
+/// - `text`: 已规范化的文本
+fn tokens(text: &str) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    let clusters = graphemes(text);
+    for run in runs(&clusters) {
+        if run.iter().any(|c| c.chars().any(is_cjk)) {
+            for cluster in run {
+                *counts.entry(cluster).or_insert(0) += 1;
+            }
+            continue;
+        }
+        let start = run[0].as_ptr() as usize - text.as_ptr() as usize;
+        let len = run.iter().map(|c| c.len()).sum::<usize>();
+        *counts.entry(&text[start..start + len]).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// 字符n元组相似度
+///
+/// NFC规范化并转为小写后，统计每个文字片段内的字素簇二元组，
+/// 返回两组二元组的Dice系数（按出现次数计）。标点、空白和符号不参与比较，
+/// 适用于没有词边界的中日韩文本和有拼写差异的文本
+///
+/// # 参数
+/// - `a`: 第一段文本
+/// - `b`: 第二段文本
+///
+/// # 返回值
+/// 相似度，范围0到1；两段文本都没有可比较的内容时为1
+pub fn char_ngram_similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (fold(a), fold(b));
+    let (common, total) = overlap(&bigrams(&a), &bigrams(&b));
+    if total == 0 {
+        return 1.0;
+    }
+    (2 * common) as f32 / total as f32
+}
+
+/// 词的Jaccard相似度
+///
+/// NFC规范化并转为小写后按词比较，中日韩文字按单个字素簇比较。
+/// 按出现次数计算：共同出现次数之和除以合并后的出现次数之和
+///
+/// # 参数
+/// - `a`: 第一段文本
+/// - `b`: 第二段文本
+///
+/// # 返回值
+/// 相似度，范围0到1；两段文本都没有可比较的内容时为1
+pub fn token_jaccard(a: &str, b: &str) -> f32 {
+    let (a, b) = (fold(a), fold(b));
+    let (common, total) = overlap(&tokens(&a), &tokens(&b));
+    if total == 0 {
+        return 1.0;
+    }
+    common as f32 / (total - common) as f32
+}
+
+/// 编辑距离
+///
+/// NFC规范化后按字素簇计算插入、删除和替换的最少次数，区分大小写
+///
+/// # 参数
+/// - `a`: 第一段文本
+/// - `b`: 第二段文本
+///
+/// # 返回值
+/// 编辑距离，两段文本相同时为0
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let (a, b) = (normalize_nfc(a), normalize_nfc(b));
+    if a == b {
+        return 0;
+    }
+    distance(&graphemes(&a), &graphemes(&b))
+}
+
+/// 按字素簇计算编辑距离
+fn distance(a: &[&str], b: &[&str]) -> usize {
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// 归一化编辑距离相似度
+///
+/// NFC规范化后按字素簇计算编辑距离，结果为`1 - 距离 / 较长文本的字素簇数`。
+/// 区分大小写，标点和空白同样参与比较，适用于翻译记忆库的模糊匹配
+///
+/// # 参数
+/// - `a`: 第一段文本
+/// - `b`: 第二段文本
+///
+/// # 返回值
+/// 相似度，范围0到1；两段文本都为空时为1
+pub fn normalized_levenshtein(a: &str, b: &str) -> f32 {
+    let (a, b) = (normalize_nfc(a), normalize_nfc(b));
+    if a == b {
+        return 1.0;
+    }
+    let (a, b) = (graphemes(&a), graphemes(&b));
+    let longest = a.len().max(b.len());
+    1.0 - distance(&a, &b) as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::similarity::{
+        char_ngram_similarity, graphemes, levenshtein, normalized_levenshtein, token_jaccard,
+    };
+
+    /// 相似度函数
+    type Metric = fn(&str, &str) -> f32;
+
+    /// 测试的三个相似度函数
+    const METRICS: [(&str, Metric); 3] = [
+        ("char_ngram_similarity", char_ngram_similarity),
+        ("token_jaccard", token_jaccard),
+        ("normalized_levenshtein", normalized_levenshtein),
+    ];
+
+    /// 测试字素簇拆分
+    #[test]
+    fn test_graphemes() {
+        assert_eq!(graphemes("abc"), ["a", "b", "c"]);
+        assert_eq!(graphemes("e\u{301}x"), ["e\u{301}", "x"]);
+        assert_eq!(graphemes("👩‍👩‍👧!"), ["👩‍👩‍👧", "!"]);
+        assert_eq!(graphemes("👍🏽ok"), ["👍🏽", "o", "k"]);
+        assert_eq!(graphemes("🇨🇳🇯🇵"), ["🇨🇳", "🇯🇵"]);
+        assert_eq!(
+            graphemes("1\u{FE0F}\u{20E3}\r\n"),
+            ["1\u{FE0F}\u{20E3}", "\r\n"]
+        );
+        assert_eq!(graphemes("你好"), ["你", "好"]);
+        assert!(graphemes("").is_empty());
+    }
+
+    /// 测试手工计算的英文和中文示例
+    #[test]
+    fn test_golden_values() {
+        // "night"的二元组ni ig gh ht，"nacht"为na ac ch ht，共同1个
+        assert_eq!(char_ngram_similarity("night", "nacht"), 0.25);
+        assert_eq!(char_ngram_similarity("Hello, World", "hello world!"), 1.0);
+        // 今天 天天 天气 气很 很好 与 今天 天天 天气 气不 不错，共同3个
+        assert_eq!(char_ngram_similarity("今天天气很好。", "今天天气不错"), 0.6);
+        assert_eq!(char_ngram_similarity("猫", "狗"), 0.0);
+
+        // {the, cat, sat} 与 {the, dog, sat}：共同2个，合并4个
+        assert_eq!(token_jaccard("the cat sat", "the dog sat"), 0.5);
+        // 中文按字比较：我 爱 北 京 与 我 爱 上 海
+        assert_eq!(token_jaccard("我爱北京", "我爱上海"), 2.0 / 6.0);
+        assert_eq!(token_jaccard("a a b", "a b b"), 0.5);
+
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("你好世界", "你好"), 2);
+        assert_eq!(levenshtein("e\u{301}", "\u{e9}"), 0);
+        assert_eq!(normalized_levenshtein("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+        assert_eq!(normalized_levenshtein("你好世界", "你好"), 0.5);
+        // 大小写不同计为替换
+        assert_eq!(normalized_levenshtein("Cat", "cat"), 1.0 - 1.0 / 3.0);
+    }
+
+    /// 测试按字素簇而不是码位比较
+    #[test]
+    fn test_unicode() {
+        // NFD与NFC形式相同
+        for (name, metric) in METRICS {
+            assert_eq!(metric("Cafe\u{301}", "Café"), 1.0, "{}", name);
+        }
+        // 肤色不同的表情符号只差一个字素簇
+        assert_eq!(normalized_levenshtein("ok👍🏽", "ok👍🏿"), 1.0 - 1.0 / 3.0);
+        assert_eq!(normalized_levenshtein("🇨🇳", "🇯🇵"), 0.0);
+        assert_eq!(char_ngram_similarity("naïve", "NAÏVE"), 1.0);
+        assert!(token_jaccard("東京に行きます", "東京へ行きます") > 0.5);
+    }
+
+    /// 随机文本上的对称性、自反性和取值范围
+    #[test]
+    fn test_properties() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let alphabet = [
+            "a",
+            "b",
+            "c",
+            "A",
+            " ",
+            ",",
+            "e\u{301}",
+            "é",
+            "你",
+            "好",
+            "世",
+            "界",
+            "👍🏽",
+            "👩‍👧",
+            "🇨🇳",
+            "ß",
+            "\n",
+        ];
+        let mut rng = StdRng::seed_from_u64(667);
+        let random_text = |rng: &mut StdRng| {
+            (0..rng.random_range(0..12))
+                .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                .collect::<String>()
+        };
+        for _ in 0..500 {
+            let a = random_text(&mut rng);
+            let b = random_text(&mut rng);
+            for (name, metric) in METRICS {
+                let score = metric(&a, &b);
+                assert!((0.0..=1.0).contains(&score), "{} {:?} {:?}", name, a, b);
+                assert_eq!(score, metric(&b, &a), "{} {:?} {:?}", name, a, b);
+                assert_eq!(metric(&a, &a), 1.0, "{} {:?}", name, a);
+            }
+            assert_eq!(graphemes(&a).concat(), a);
+        }
+    }
+}
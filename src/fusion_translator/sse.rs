@@ -0,0 +1,548 @@
+use crate::fusion_translator::translator_error::TranslatorError;
+use futures_core::Stream;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+/// OpenAI兼容接口流式补全的结束标记
+pub const DONE: &str = "[DONE]";
+
+/// 流开头可能出现的UTF-8 BOM
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// SSE事件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// 事件类型（`event`字段），未指定时为None
+    pub event: Option<String>,
+    /// 事件数据，多个`data`行以换行连接
+    pub data: String,
+    /// 最近一次收到的事件ID（`id`字段）
+    pub id: Option<String>,
+}
+
+/// 增量SSE解析器
+///
+/// 按`text/event-stream`格式解析任意切分的字节块：行可以以`\r\n`、`\n`或`\r`结尾，
+/// 事件、行和UTF-8字符都可以跨块；以`:`开头的注释行和未知字段被忽略
+#[derive(Debug, Default)]
+pub struct SseParser {
+    /// 尚未读到行尾的字节
+    buffer: Vec<u8>,
+    /// 正在接收的事件
+    event: SseEvent,
+    /// 正在接收的事件是否有`data`行
+    has_data: bool,
+    /// 是否已跳过流开头的BOM
+    started: bool,
+}
+
+impl SseParser {
+    /// 创建新的解析器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 输入一个字节块
+    ///
+    /// # 参数
+    /// - `chunk`: 响应体中的下一个字节块
+    ///
+    /// # 返回值
+    /// 本次输入后完整接收的事件，按顺序排列
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        if !self.started {
+            if self.buffer.len() < BOM.len() && BOM.starts_with(&self.buffer) {
+                return Vec::new();
+            }
+            if self.buffer.starts_with(BOM) {
+                self.buffer.drain(..BOM.len());
+            }
+            self.started = true;
+        }
+        let mut events = Vec::new();
+        let mut start = 0;
+        while let Some((offset, &eol)) = self
+            .buffer
+            .iter()
+            .skip(start)
+            .enumerate()
+            .find(|(_, b)| matches!(b, b'\n' | b'\r'))
+        {
+            let end = start + offset;
+            let next = match (eol, self.buffer.get(end + 1)) {
+                (b'\r', Some(b'\n')) => end + 2,
+                // 块末尾的`\r`可能与下一块开头的`\n`组成一个行尾
+                (b'\r', None) => break,
+                _ => end + 1,
+            };
+            let line = self.buffer.get(start..end).unwrap_or_default();
+            let line = String::from_utf8_lossy(line).into_owned();
+            events.extend(self.process_line(&line));
+            start = next;
+        }
+        self.buffer.drain(..start);
+        events
+    }
+
+    /// 结束输入
+    ///
+    /// 处理最后一行并派发尚未以空行结束的事件；部分服务在最后一个事件之后直接关闭连接，
+    /// 因此这里不像规范那样丢弃它
+    ///
+    /// # 返回值
+    /// 最后一个事件，没有未派发的数据时为None
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let rest = std::mem::take(&mut self.buffer);
+        let line = String::from_utf8_lossy(&rest);
+        let line = line.trim_end_matches('\r');
+        if !line.is_empty() {
+            if let Some(event) = self.process_line(line) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    /// 处理一行
+    ///
+    /// # 参数
+    /// - `line`: 不含行尾的一行
+    ///
+    /// # 返回值
+    /// 遇到空行时派发的事件
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => {
+                if self.has_data {
+                    self.event.data.push('\n');
+                }
+                self.event.data.push_str(value);
+                self.has_data = true;
+            }
+            "event" => self.event.event = Some(value.to_string()),
+            "id" if !value.contains('\0') => self.event.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    /// 派发当前事件
+    ///
+    /// 没有`data`行的事件只清空类型，不派发；事件ID保留给之后的事件
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let id = self.event.id.clone();
+        let event = std::mem::take(&mut self.event);
+        self.event.id = id;
+        std::mem::take(&mut self.has_data).then_some(event)
+    }
+}
+
+/// OpenAI兼容接口的流式补全事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionEvent {
+    /// 新生成的译文片段
+    Token(String),
+    /// 结束标记
+    Done,
+}
+
+/// 流式补全的数据块
+#[derive(Deserialize)]
+struct CompletionChunk {
+    /// 候选结果，翻译只使用第一个
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    /// 生成过程中出现的错误
+    error: Option<serde_json::Value>,
+}
+
+/// 数据块中的候选结果
+#[derive(Deserialize)]
+struct ChunkChoice {
+    /// 新增的内容
+    #[serde(default)]
+    delta: ChunkDelta,
+}
+
+/// 候选结果新增的内容
+#[derive(Default, Deserialize)]
+struct ChunkDelta {
+    /// 新增的文本，只包含角色等信息的数据块为None
+    content: Option<String>,
+}
+
+/// 从错误事件中取出错误信息
+///
+/// 兼容`{"message": ...}`对象和字符串两种格式，其他格式原样返回
+///
+/// # 参数
+/// - `error`: `error`字段或错误事件的数据
+fn error_message(error: &serde_json::Value) -> String {
+    match error {
+        serde_json::Value::String(message) => message.clone(),
+        _ => error
+            .get("message")
+            .and_then(serde_json::Value::as_str)
+            .map_or_else(|| error.to_string(), str::to_string),
+    }
+}
+
+/// 解析OpenAI兼容接口的一个流式补全事件
+///
+/// # 参数
+/// - `event`: SSE事件
+///
+/// # 返回值
+/// 译文片段或结束标记；只有角色等信息、没有新文本的数据块为None。
+/// 错误事件（`event: error`或带`error`字段的数据块）和无法解析的数据返回`TranslatorError::StreamError`
+pub fn parse_completion_event(
+    event: &SseEvent,
+) -> Result<Option<CompletionEvent>, TranslatorError> {
+    let data = event.data.trim();
+    if data == DONE {
+        return Ok(Some(CompletionEvent::Done));
+    }
+    if event.event.as_deref() == Some("error") {
+        let message = serde_json::from_str::<serde_json::Value>(data)
+            .map(|value| error_message(value.get("error").unwrap_or(&value)))
+            .unwrap_or_else(|_| data.to_string());
+        return Err(TranslatorError::StreamError(message));
+    }
+    let chunk: CompletionChunk = serde_json::from_str(data)
+        .map_err(|e| TranslatorError::StreamError(format!("invalid chunk: {}", e)))?;
+    if let Some(error) = &chunk.error {
+        return Err(TranslatorError::StreamError(error_message(error)));
+    }
+    Ok(chunk
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.delta.content)
+        .filter(|content| !content.is_empty())
+        .map(CompletionEvent::Token))
+}
+
+/// 按顺序产出流式补全的译文片段
+///
+/// 收到结束标记后结束；收到错误事件、响应体出错或在结束标记之前断开时产出一个错误并结束
+///
+/// # 参数
+/// - `body`: 响应体的字节块流
+///
+/// # 返回值
+/// 按顺序拼接后为完整译文的片段流
+pub fn completion_tokens<S, B, E>(body: S) -> impl Stream<Item = anyhow::Result<String>>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+{
+    /// 解析状态
+    struct State<S> {
+        /// 响应体
+        body: Pin<Box<S>>,
+        /// SSE解析器
+        parser: SseParser,
+        /// 已解析、尚未处理的事件
+        pending: VecDeque<SseEvent>,
+        /// 响应体是否已结束
+        ended: bool,
+    }
+
+    let state = State {
+        body: Box::pin(body),
+        parser: SseParser::new(),
+        pending: VecDeque::new(),
+        ended: false,
+    };
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                match parse_completion_event(&event) {
+                    Ok(Some(CompletionEvent::Token(token))) => {
+                        return Some((Ok(token), Some(state)))
+                    }
+                    Ok(Some(CompletionEvent::Done)) => return None,
+                    Ok(None) => continue,
+                    Err(e) => return Some((Err(e.into()), None)),
+                }
+            }
+            if state.ended {
+                let e = TranslatorError::StreamError(format!("stream ended before {}", DONE));
+                return Some((Err(e.into()), None));
+            }
+            match state.body.next().await {
+                Some(Ok(chunk)) => {
+                    let events = state.parser.feed(chunk.as_ref());
+                    state.pending.extend(events);
+                }
+                Some(Err(e)) => return Some((Err(e.into()), None)),
+                None => {
+                    state.ended = true;
+                    state.pending.extend(state.parser.finish());
+                }
+            }
+        }
+    })
+}
+
+/// 拼接流式输出的全部片段
+///
+/// # 参数
+/// - `tokens`: 片段流
+///
+/// # 返回值
+/// 完整译文，任一片段出错时返回该错误
+pub async fn collect_text<S>(tokens: S) -> anyhow::Result<String>
+where
+    S: Stream<Item = anyhow::Result<String>>,
+{
+    tokens
+        .try_fold(String::new(), |mut text, token| async move {
+            text.push_str(&token);
+            Ok(text)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::sse::{
+        collect_text, completion_tokens, parse_completion_event, CompletionEvent, SseEvent,
+        SseParser,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translator_error::{ErrorKind, TranslatorError};
+    use futures_util::stream::{self, StreamExt};
+
+    /// 生成OpenAI兼容接口的数据块事件
+    ///
+    /// # 参数
+    /// - `content`: 新增的文本
+    fn chunk(content: &str) -> String {
+        format!(
+            "data: {{\"choices\":[{{\"index\":0,\"delta\":{{\"content\":{}}}}}]}}\n\n",
+            serde_json::to_string(content).unwrap()
+        )
+    }
+
+    /// 生成数据事件
+    ///
+    /// # 参数
+    /// - `data`: 事件数据
+    fn data(data: &str) -> SseEvent {
+        SseEvent {
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 测试字段、注释、多行数据和事件ID的解析
+    #[test]
+    fn test_parse_fields() {
+        let mut parser = SseParser::new();
+        let events = parser.feed(
+            b": keep-alive\n\nevent: update\nid: 7\ndata: first\ndata:second\nretry: 100\nunknown\n\ndata\n\n",
+        );
+        assert_eq!(
+            events,
+            [
+                SseEvent {
+                    event: Some("update".to_string()),
+                    data: "first\nsecond".to_string(),
+                    id: Some("7".to_string()),
+                },
+                SseEvent {
+                    event: None,
+                    data: String::new(),
+                    id: Some("7".to_string()),
+                },
+            ]
+        );
+        assert_eq!(parser.feed(b"event: ping\n\n"), []);
+        assert_eq!(parser.finish(), None);
+    }
+
+    /// 测试任意切分字节块时结果相同，包括跨块的`\r\n`和UTF-8字符
+    #[test]
+    fn test_split_across_chunks() {
+        let body = "\u{FEFF}data: 你好\r\n\r\ndata: 世界 🌍\r\rdata: a\n\n";
+        let bytes = body.as_bytes();
+        let expected = vec![data("你好"), data("世界 🌍"), data("a")];
+        assert_eq!(SseParser::new().feed(bytes), expected);
+        for first in 0..bytes.len() {
+            for second in first..bytes.len() {
+                let mut parser = SseParser::new();
+                let mut events = parser.feed(&bytes[..first]);
+                events.extend(parser.feed(&bytes[first..second]));
+                events.extend(parser.feed(&bytes[second..]));
+                events.extend(parser.finish());
+                assert_eq!(events, expected, "split at {} and {}", first, second);
+            }
+        }
+
+        let mut parser = SseParser::new();
+        let mut events = Vec::new();
+        for byte in bytes {
+            events.extend(parser.feed(std::slice::from_ref(byte)));
+        }
+        assert_eq!(events, expected);
+    }
+
+    /// 测试连接关闭时派发没有以空行结束的事件
+    #[test]
+    fn test_finish() {
+        let mut parser = SseParser::new();
+        assert_eq!(parser.feed(b"data: one\n\ndata: two\r"), [data("one")]);
+        assert_eq!(parser.finish(), Some(data("two")));
+
+        let mut parser = SseParser::new();
+        assert_eq!(parser.feed(b"data: three"), []);
+        assert_eq!(parser.finish(), Some(data("three")));
+        assert_eq!(parser.finish(), None);
+    }
+
+    /// 测试流式补全事件的解析
+    #[test]
+    fn test_parse_completion_event() {
+        let token = |body: &str| {
+            let mut parser = SseParser::new();
+            parse_completion_event(&parser.feed(body.as_bytes())[0])
+        };
+        assert_eq!(
+            token(&chunk("Hello")).unwrap(),
+            Some(CompletionEvent::Token("Hello".to_string()))
+        );
+        assert_eq!(
+            token("data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n").unwrap(),
+            None
+        );
+        assert_eq!(
+            token("data: [DONE]\n\n").unwrap(),
+            Some(CompletionEvent::Done)
+        );
+
+        for body in [
+            "event: error\ndata: {\"error\":{\"message\":\"overloaded\"}}\n\n",
+            "data: {\"error\":{\"message\":\"overloaded\",\"type\":\"server_error\"}}\n\n",
+            "event: error\ndata: overloaded\n\n",
+        ] {
+            let err = token(body).unwrap_err();
+            assert!(
+                matches!(&err, TranslatorError::StreamError(m) if m == "overloaded"),
+                "{:?}",
+                err
+            );
+            assert_eq!(err.kind(), ErrorKind::Api);
+        }
+        assert!(matches!(
+            token("data: {not json\n\n"),
+            Err(TranslatorError::StreamError(_))
+        ));
+    }
+
+    /// 测试片段流：按顺序产出片段，在结束标记处结束，错误事件和提前断开时产出错误
+    #[tokio::test]
+    async fn test_completion_tokens() {
+        let body = format!(
+            "data: {{\"choices\":[{{\"delta\":{{\"role\":\"assistant\"}}}}]}}\n\n{}{}{}data: [DONE]\n\n{}",
+            chunk("Bon"),
+            chunk("jour"),
+            chunk(" le monde"),
+            chunk("ignored")
+        );
+        // 按7字节切分，事件和JSON都会跨块
+        let chunks = body
+            .as_bytes()
+            .chunks(7)
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        let body = || stream::iter(chunks.clone()).map(Ok::<_, std::io::Error>);
+        let tokens = completion_tokens(body())
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(tokens, ["Bon", "jour", " le monde"]);
+        assert_eq!(
+            collect_text(completion_tokens(body())).await.unwrap(),
+            "Bonjour le monde"
+        );
+
+        let body = format!(
+            "{}event: error\ndata: {{\"message\":\"rate limited\"}}\n\n{}",
+            chunk("partial"),
+            chunk("never")
+        );
+        let items = completion_tokens(stream::iter([Ok::<_, std::io::Error>(body)]))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap(), "partial");
+        let err = items[1].as_ref().unwrap_err();
+        assert_eq!(ErrorKind::of(err), ErrorKind::Api);
+        assert!(err.to_string().contains("rate limited"));
+
+        let truncated = [Ok::<_, std::io::Error>(chunk("cut"))];
+        let err = collect_text(completion_tokens(stream::iter(truncated)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("[DONE]"));
+
+        let failing = [
+            Ok(chunk("a")),
+            Err(std::io::Error::other("connection reset")),
+        ];
+        let items = completion_tokens(stream::iter(failing))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert!(items[1]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("connection reset"));
+    }
+
+    /// 模糊测试：任意字节按任意方式分块输入都不会panic，结果与整体输入相同
+    #[test]
+    fn test_fuzz_parse() {
+        let body = format!(
+            "\u{feff}: comment\nevent: message\nid: 1\nretry: 10\n{}{}data: [DONE]\n\n",
+            chunk("Hello"),
+            chunk(" world")
+        );
+        let corpus = [
+            body.as_bytes(),
+            b"event: error\r\ndata: {\"error\":{\"message\":\"x\"}}\r\n\r\n",
+        ];
+        for input in fuzz::byte_inputs(701, &corpus) {
+            let mut whole = SseParser::new();
+            let mut expected = whole.feed(&input);
+            expected.extend(whole.finish());
+            let mut split = SseParser::new();
+            let mut events = Vec::new();
+            for piece in input.chunks(3) {
+                events.extend(split.feed(piece));
+            }
+            events.extend(split.finish());
+            assert_eq!(events, expected, "{:?}", input);
+            for event in &events {
+                let _ = parse_completion_event(event);
+            }
+        }
+    }
+}
@@ -0,0 +1,732 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::batch::{translate_map_with, BatchOptions};
+use crate::fusion_translator::long_text::detect_script;
+use crate::fusion_translator::postprocess::{is_cjk, is_cjk_context};
+use crate::fusion_translator::segment::{is_cjk_language, split_sentence_ranges};
+use crate::fusion_translator::similarity::graphemes;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 默认每行最多的字符数，西文字幕的常用上限
+pub const DEFAULT_MAX_CHARS_PER_LINE: usize = 42;
+
+/// 默认每条字幕最多的行数
+pub const DEFAULT_MAX_LINES: usize = 2;
+
+/// 不能出现在行首的字符（避头规则）
+const NO_LINE_START: &[char] = &[
+    '、', '。', '，', '．', '・', '：', '；', '？', '！', '‼', '⁇', '⁉', 'ー', '々', '…', '‥',
+    '）', '」', '』', '】', '〕', '〉', '》', '〟', '”', '’', '～', 'ぁ', 'ぃ', 'ぅ', 'ぇ', 'ぉ',
+    'っ', 'ゃ', 'ゅ', 'ょ', 'ゎ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ッ', 'ャ', 'ュ', 'ョ', 'ヮ', 'ヵ',
+    'ヶ', ',', '.', ':', ';', '?', '!', ')', ']', '%',
+];
+
+/// 不能出现在行尾的字符（避尾规则）
+const NO_LINE_END: &[char] = &[
+    '（', '「', '『', '【', '〔', '〈', '《', '〝', '“', '‘', '(', '[',
+];
+
+/// 字幕块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    /// 序号
+    pub index: String,
+    /// 时间轴，如`00:00:01,000 --> 00:00:03,200`
+    pub timing: String,
+    /// 字幕文本的各行
+    pub lines: Vec<String>,
+}
+
+impl Cue {
+    /// 合并为一行的字幕文本
+    ///
+    /// 相邻两行在中日韩文字之间直接拼接，其余情况以空格拼接
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let joined = text.chars().next_back().is_some_and(is_cjk_context)
+                && line.chars().next().is_some_and(is_cjk_context);
+            if !text.is_empty() && !joined {
+                text.push(' ');
+            }
+            text.push_str(line);
+        }
+        text
+    }
+}
+
+/// 解析SRT字幕
+///
+/// 按空行拆分字幕块，每块的第一行为序号、第二行为时间轴，其余为字幕文本
+///
+/// # 参数
+/// - `srt`: 字幕文件内容
+///
+/// # 返回值
+/// 字幕块列表，不足两行的块被忽略
+pub fn parse_srt(srt: &str) -> Vec<Cue> {
+    srt.trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.trim().lines();
+            Some(Cue {
+                index: lines.next()?.trim().to_string(),
+                timing: lines.next()?.trim().to_string(),
+                lines: lines.map(str::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+/// 生成SRT字幕
+///
+/// # 参数
+/// - `cues`: 字幕块列表
+///
+/// # 返回值
+/// 字幕文件内容，`parse_srt`可以原样解析
+pub fn render_srt(cues: &[Cue]) -> String {
+    let mut srt = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        if i > 0 {
+            srt.push('\n');
+        }
+        srt.push_str(&cue.index);
+        srt.push('\n');
+        srt.push_str(&cue.timing);
+        srt.push('\n');
+        for line in &cue.lines {
+            srt.push_str(line);
+            srt.push('\n');
+        }
+    }
+    srt
+}
+
+/// 每条字幕的排版约束
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CueConstraints {
+    /// 每行最多的字符数，按字素簇计算，全角字符同样计为1
+    pub max_chars_per_line: usize,
+    /// 每条字幕最多的行数
+    pub max_lines: usize,
+}
+
+impl Default for CueConstraints {
+    fn default() -> Self {
+        Self {
+            max_chars_per_line: DEFAULT_MAX_CHARS_PER_LINE,
+            max_lines: DEFAULT_MAX_LINES,
+        }
+    }
+}
+
+impl CueConstraints {
+    /// 创建排版约束
+    ///
+    /// # 参数
+    /// - `max_chars_per_line`: 每行最多的字符数，至少为1
+    /// - `max_lines`: 每条字幕最多的行数，至少为1
+    pub fn new(max_chars_per_line: usize, max_lines: usize) -> Self {
+        Self {
+            max_chars_per_line: max_chars_per_line.max(1),
+            max_lines: max_lines.max(1),
+        }
+    }
+
+    /// 判断排版后的各行是否满足约束
+    ///
+    /// # 参数
+    /// - `lines`: 字幕文本的各行
+    pub fn fits(&self, lines: &[String]) -> bool {
+        lines.len() <= self.max_lines
+            && lines
+                .iter()
+                .all(|line| graphemes(line).len() <= self.max_chars_per_line)
+    }
+}
+
+/// 需要人工检查的字幕
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CueReview {
+    /// 原字幕的序号
+    pub index: String,
+    /// 排版后的行数，拆分为多条时取最多的一条
+    pub lines: usize,
+    /// 最长一行的字符数
+    pub longest_line: usize,
+}
+
+/// 字幕文件的翻译报告
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    /// 原文件中的字幕条数
+    pub cues: usize,
+    /// 为满足约束拆分为多条的字幕序号
+    pub split: Vec<String>,
+    /// 拆分和压缩后仍无法满足约束的字幕
+    pub needs_review: Vec<CueReview>,
+    /// 翻译失败、保留原文的字幕序号
+    pub untranslated: Vec<String>,
+}
+
+/// 字幕文件的翻译结果
+#[derive(Debug, Clone)]
+pub struct SubtitleTranslation {
+    /// 翻译并排版后的字幕；有字幕被拆分时重新从1编号
+    pub cues: Vec<Cue>,
+    /// 翻译报告
+    pub report: FileReport,
+}
+
+impl SubtitleTranslation {
+    /// 生成SRT字幕
+    pub fn to_srt(&self) -> String {
+        render_srt(&self.cues)
+    }
+}
+
+/// 翻译SRT字幕并按约束重新排版
+///
+/// 多行台词合并后发送，重复的台词只翻译一次。译文按`break_lines`重新换行，
+/// 超出行数时先压缩空白和省略号，仍然超出时按句子拆分为多条字幕，时间轴按字符数比例划分；
+/// 无法满足约束的字幕保留最接近的排版，记录在`FileReport::needs_review`中供人工检查
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `srt`: 字幕文件内容
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言，决定换行规则
+/// - `constraints`: 排版约束
+/// - `options`: 批量翻译选项，开启`partial`时翻译失败的字幕保留原文并记录在报告中
+///
+/// # 返回值
+/// 翻译后的字幕和报告
+pub async fn translate_srt(
+    translator: &dyn AsyncTranslator,
+    srt: &str,
+    from: Option<Language>,
+    to: &Language,
+    constraints: &CueConstraints,
+    options: &BatchOptions,
+) -> anyhow::Result<SubtitleTranslation> {
+    let cues = parse_srt(srt);
+    let texts = cues
+        .iter()
+        .enumerate()
+        .map(|(i, cue)| (i, cue.text()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect::<HashMap<_, _>>();
+    let output = translate_map_with(translator, &texts, from, to, options).await?;
+
+    let mut report = FileReport {
+        cues: cues.len(),
+        ..Default::default()
+    };
+    let mut translated = Vec::with_capacity(cues.len());
+    for (i, cue) in cues.iter().enumerate() {
+        let Some(translation) = output.translations.get(&i) else {
+            if texts.contains_key(&i) {
+                report.untranslated.push(cue.index.clone());
+            }
+            translated.push(cue.clone());
+            continue;
+        };
+        let mut parts = fit(&translation.text, to, constraints);
+        let timings = match parse_timing(&cue.timing) {
+            Some(timing) if parts.len() > 1 => split_timing(timing, &parts),
+            // 时间轴无法解析时不能拆分，合并为一条
+            _ => {
+                parts = vec![parts.concat()];
+                vec![cue.timing.clone()]
+            }
+        };
+        if !parts.iter().all(|lines| constraints.fits(lines)) {
+            report.needs_review.push(CueReview {
+                index: cue.index.clone(),
+                lines: parts.iter().map(Vec::len).max().unwrap_or(0),
+                longest_line: parts
+                    .iter()
+                    .flatten()
+                    .map(|line| graphemes(line).len())
+                    .max()
+                    .unwrap_or(0),
+            });
+        }
+        if parts.len() > 1 {
+            report.split.push(cue.index.clone());
+        }
+        for (lines, timing) in parts.into_iter().zip(timings) {
+            translated.push(Cue {
+                index: cue.index.clone(),
+                timing,
+                lines,
+            });
+        }
+    }
+    if !report.split.is_empty() {
+        for (i, cue) in translated.iter_mut().enumerate() {
+            cue.index = (i + 1).to_string();
+        }
+    }
+    Ok(SubtitleTranslation {
+        cues: translated,
+        report,
+    })
+}
+
+/// 按语言把文本拆分为不超过行宽的多行
+///
+/// 西文（以及韩文等以空格分词的文字）只在空格处换行；中文和日文可以在任意字符之间换行，
+/// 但其中的西文单词和数字保持完整，并遵守避头尾规则：`、。」`等标点和日文小假名不出现在行首，
+/// `「（`等开括号不出现在行尾。在行数最少的前提下尽量让各行长度接近。
+/// 单个单词超过行宽时按字素簇硬拆
+///
+/// # 参数
+/// - `text`: 待换行的文本，其中的换行和连续空白视为一个空格
+/// - `lang`: 文本语言，None时按文字检测
+/// - `max_chars`: 每行最多的字符数，按字素簇计算
+///
+/// # 返回值
+/// 各行文本，去掉了行首和行尾的空白；空文本返回空列表
+pub fn break_lines(text: &str, lang: Option<Language>, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let groups = groups(text, is_cjk_text(text, lang));
+    let lines = wrap(&groups, max_chars);
+    if lines.len() <= 1 {
+        return lines;
+    }
+    // 行数不变时尽量用更窄的行宽，让各行长度接近，但不为此硬拆单词
+    let total = groups
+        .iter()
+        .map(|group| group.clusters.len() + usize::from(group.space_before))
+        .sum::<usize>();
+    let widest = groups
+        .iter()
+        .map(|group| group.clusters.len().min(max_chars))
+        .max()
+        .unwrap_or(1);
+    let narrowest = total.div_ceil(lines.len()).max(widest);
+    (narrowest..max_chars)
+        .map(|width| wrap(&groups, width))
+        .find(|candidate| candidate.len() <= lines.len())
+        .unwrap_or(lines)
+}
+
+/// 是否按中日文规则换行
+///
+/// # 参数
+/// - `text`: 文本
+/// - `lang`: 文本语言，None时按文字检测
+fn is_cjk_text(text: &str, lang: Option<Language>) -> bool {
+    lang.or_else(|| detect_script(text))
+        .is_some_and(|lang| is_cjk_language(&lang))
+}
+
+/// 换行时不可拆开的一组字素簇
+struct Group<'a> {
+    /// 组内的字素簇，可能包含空格
+    clusters: Vec<&'a str>,
+    /// 与前一组之间是否有空格
+    space_before: bool,
+}
+
+/// 把文本划分为不可拆开的组，组与组之间允许换行
+///
+/// # 参数
+/// - `text`: 文本
+/// - `cjk`: 是否按中日文规则换行
+fn groups(text: &str, cjk: bool) -> Vec<Group<'_>> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut space = false;
+    for cluster in graphemes(text) {
+        if cluster.chars().all(char::is_whitespace) {
+            space = true;
+            continue;
+        }
+        let breakable = match groups.last().and_then(|group| group.clusters.last()) {
+            None => true,
+            Some(prev) => {
+                let opportunity = space || (cjk && !(is_word(prev) && is_word(cluster)));
+                opportunity && !cluster.starts_with(NO_LINE_START) && !prev.ends_with(NO_LINE_END)
+            }
+        };
+        match groups.last_mut() {
+            Some(group) if !breakable => {
+                if space {
+                    group.clusters.push(" ");
+                }
+                group.clusters.push(cluster);
+            }
+            _ => groups.push(Group {
+                clusters: vec![cluster],
+                space_before: space && !groups.is_empty(),
+            }),
+        }
+        space = false;
+    }
+    groups
+}
+
+/// 是否为西文单词或数字的一部分
+///
+/// # 参数
+/// - `cluster`: 字素簇
+fn is_word(cluster: &str) -> bool {
+    cluster
+        .chars()
+        .next()
+        .is_some_and(|c| (c.is_alphanumeric() || c == '\'' || c == '-') && !is_cjk(c))
+}
+
+/// 按行宽贪心排版
+///
+/// # 参数
+/// - `groups`: `groups`划分的组
+/// - `width`: 每行最多的字素簇数
+fn wrap(groups: &[Group], width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut used = 0;
+    for group in groups {
+        let space = usize::from(group.space_before && used > 0);
+        if used > 0 && used + space + group.clusters.len() > width {
+            lines.push(std::mem::take(&mut line).trim_end().to_string());
+            used = 0;
+        } else if space == 1 {
+            line.push(' ');
+            used += 1;
+        }
+        // 超过行宽的组按字素簇硬拆
+        for cluster in &group.clusters {
+            if used == width {
+                lines.push(std::mem::take(&mut line).trim_end().to_string());
+                used = 0;
+            }
+            if used == 0 && *cluster == " " {
+                continue;
+            }
+            line.push_str(cluster);
+            used += 1;
+        }
+    }
+    if used > 0 {
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// 压缩译文以减少占用的字符
+///
+/// 合并连续空白，把`...`替换为`…`，去掉中日文字符与全角标点两侧的空白
+///
+/// # 参数
+/// - `text`: 译文
+/// - `cjk`: 是否按中日文规则处理
+fn condense(text: &str, cjk: bool) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let mut condensed = String::with_capacity(text.len());
+    for (i, word) in words.iter().enumerate() {
+        let joined = cjk
+            && condensed.chars().next_back().is_some_and(is_cjk_context)
+            && word.chars().next().is_some_and(is_cjk_context);
+        if i > 0 && !joined {
+            condensed.push(' ');
+        }
+        condensed.push_str(word);
+    }
+    condensed.replace("...", "…")
+}
+
+/// 按约束排版一条字幕的译文
+///
+/// 直接换行超出行数时，按句子拆分为多段，每段尽量容纳更多句子
+///
+/// # 参数
+/// - `text`: 译文
+/// - `to`: 译文语言
+/// - `constraints`: 排版约束
+///
+/// # 返回值
+/// 每段的各行，单个句子过长时对应的段仍会超出约束
+fn fit(text: &str, to: &Language, constraints: &CueConstraints) -> Vec<Vec<String>> {
+    let lang = Some(*to);
+    let cjk = is_cjk_text(text, lang);
+    let text = condense(text, cjk);
+    let max_chars = constraints.max_chars_per_line;
+    let lines = break_lines(&text, lang, max_chars);
+    if lines.len() <= constraints.max_lines {
+        return vec![lines];
+    }
+
+    let separator = if cjk { "" } else { " " };
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_lines = Vec::new();
+    for range in split_sentence_ranges(&text, lang) {
+        let sentence = text[range].trim();
+        if sentence.is_empty() {
+            continue;
+        }
+        if !current.is_empty() {
+            let candidate = format!("{}{}{}", current, separator, sentence);
+            let candidate_lines = break_lines(&candidate, lang, max_chars);
+            if candidate_lines.len() <= constraints.max_lines {
+                current = candidate;
+                current_lines = candidate_lines;
+                continue;
+            }
+            parts.push(std::mem::take(&mut current_lines));
+        }
+        current = sentence.to_string();
+        current_lines = break_lines(&current, lang, max_chars);
+    }
+    if !current_lines.is_empty() {
+        parts.push(current_lines);
+    }
+    parts
+}
+
+/// 解析后的时间轴
+struct Timing<'a> {
+    /// 开始时间
+    start: Duration,
+    /// 结束时间
+    end: Duration,
+    /// 结束时间之后的位置等设置，原样保留
+    settings: &'a str,
+}
+
+/// 解析时间轴
+///
+/// # 参数
+/// - `timing`: 时间轴，如`00:00:01,000 --> 00:00:03,200`
+fn parse_timing(timing: &str) -> Option<Timing<'_>> {
+    let (start, rest) = timing.split_once("-->")?;
+    let rest = rest.trim_start();
+    let (end, settings) = rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+    let start = parse_timestamp(start.trim())?;
+    let end = parse_timestamp(end)?;
+    (end >= start).then_some(Timing {
+        start,
+        end,
+        settings,
+    })
+}
+
+/// 解析`HH:MM:SS,mmm`格式的时间戳，也接受`.`作为毫秒分隔符
+///
+/// 各字段只能由数字组成，总时长超出`u64`毫秒时返回None
+///
+/// # 参数
+/// - `timestamp`: 时间戳
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    // 只接受数字，`parse`会接受`+12`这样带符号的字段
+    let number = |field: &str| {
+        (!field.is_empty() && field.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| field.parse::<u64>().ok())
+            .flatten()
+    };
+    let (clock, millis) = timestamp.split_once([',', '.'])?;
+    let mut fields = clock.split(':');
+    let hours = number(fields.next()?)?;
+    let minutes = number(fields.next()?)?;
+    let seconds = number(fields.next()?)?;
+    if fields.next().is_some() || minutes >= 60 || seconds >= 60 || millis.len() != 3 {
+        return None;
+    }
+    let millis = number(millis)?;
+    // 小时数过大时无法表示，视为无法解析
+    let total = hours
+        .checked_mul(3_600_000)?
+        .checked_add((minutes * 60 + seconds) * 1000 + millis)?;
+    Some(Duration::from_millis(total))
+}
+
+/// 格式化为`HH:MM:SS,mmm`格式的时间戳
+///
+/// # 参数
+/// - `time`: 时间
+fn format_timestamp(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// 按各段的字符数比例划分时间轴
+///
+/// # 参数
+/// - `timing`: 原字幕的时间轴
+/// - `parts`: 拆分后每段的各行
+///
+/// # 返回值
+/// 每段的时间轴，首尾相接并覆盖原时间轴
+fn split_timing(timing: Timing, parts: &[Vec<String>]) -> Vec<String> {
+    let lengths = parts
+        .iter()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| graphemes(line).len())
+                .sum::<usize>()
+                .max(1)
+        })
+        .collect::<Vec<_>>();
+    let total = lengths.iter().sum::<usize>() as u128;
+    let duration = (timing.end - timing.start).as_millis();
+    let mut elapsed = 0;
+    let mut start = timing.start;
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(i, &length)| {
+            elapsed += length as u128;
+            let end = if i + 1 == lengths.len() {
+                timing.end
+            } else {
+                timing.start + Duration::from_millis((duration * elapsed / total) as u64)
+            };
+            let formatted = format!(
+                "{} --> {}{}",
+                format_timestamp(start),
+                format_timestamp(end),
+                timing.settings
+            );
+            start = end;
+            formatted
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::subtitle::{
+        break_lines, fit, parse_srt, parse_timestamp, parse_timing, render_srt, split_timing,
+        CueConstraints, NO_LINE_END, NO_LINE_START,
+    };
+    use crate::fusion_translator::test_support::fuzz;
+    use std::time::Duration;
+
+    /// 测试西文只在空格处换行，且各行长度接近
+    #[test]
+    fn test_break_latin_balanced() {
+        let lines = break_lines(
+            "The last train leaves the station at midnight tonight.",
+            Some(Language::English),
+            42,
+        );
+        assert_eq!(
+            lines,
+            vec!["The last train leaves the", "station at midnight tonight."]
+        );
+        assert_eq!(
+            break_lines("  Where are\nyou going?  ", None, 42),
+            vec!["Where are you going?"]
+        );
+        // 超过行宽的单词按字符硬拆
+        assert_eq!(
+            break_lines("Supercalifragilistic", Some(Language::English), 8),
+            vec!["Supercal", "ifragili", "stic"]
+        );
+        assert!(break_lines(" ", None, 10).is_empty());
+    }
+
+    /// 测试中日文按字符换行并遵守避头尾规则，西文单词保持完整
+    #[test]
+    fn test_break_cjk_prohibition_rules() {
+        let lines = break_lines("我们明天早上，在车站见面。", Some(Language::Chinese), 7);
+        assert_eq!(lines, vec!["我们明天早上，", "在车站见面。"]);
+        // “，”不能出现在行首，和前一个字一起换到下一行
+        let lines = break_lines("我们明天早上，在车站见面。", Some(Language::Chinese), 6);
+        assert_eq!(lines[0], "我们明天早");
+        assert!(lines[1].starts_with("上，"), "{:?}", lines);
+
+        let lines = break_lines("「東京駅」で会いましょう。", Some(Language::Japanese), 4);
+        for line in &lines {
+            assert!(!line.starts_with(NO_LINE_START), "{:?}", lines);
+            assert!(!line.ends_with(NO_LINE_END), "{:?}", lines);
+        }
+        assert_eq!(lines.concat(), "「東京駅」で会いましょう。");
+
+        let lines = break_lines("请打开iPhone设置", Some(Language::Chinese), 6);
+        assert_eq!(lines, vec!["请打开", "iPhone", "设置"]);
+    }
+
+    /// 测试换行不拆开表情符号等字素簇
+    #[test]
+    fn test_break_keeps_graphemes() {
+        let text = "好👍🏽👍🏽👍🏽👍🏽";
+        let lines = break_lines(text, Some(Language::Chinese), 2);
+        assert_eq!(lines.concat(), text);
+        assert_eq!(lines.len(), 3);
+    }
+
+    /// 测试SRT解析后重新生成保持不变
+    #[test]
+    fn test_srt_roundtrip() {
+        let srt = "1\n00:00:01,000 --> 00:00:03,200\nWhere are you going?\n\n\
+                   2\n00:00:03,400 --> 00:00:06,000 X1:10\nTo the station.\nAt midnight.\n";
+        let cues = parse_srt(srt);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[1].text(), "To the station. At midnight.");
+        assert_eq!(render_srt(&cues), srt);
+        assert!(CueConstraints::new(20, 2).fits(&cues[1].lines));
+        assert!(!CueConstraints::new(10, 2).fits(&cues[1].lines));
+    }
+
+    /// 模糊测试：任意内容的字幕解析、时间轴拆分和排版都不会panic
+    #[test]
+    fn test_fuzz_parse() {
+        let corpus = [
+            include_str!("../../tests/fixtures/subtitle/en.srt"),
+            include_str!("../../tests/fixtures/subtitle/zh.srt"),
+        ];
+        let constraints = CueConstraints::new(8, 2);
+        for input in fuzz::inputs(701, &corpus) {
+            for cue in parse_srt(&input) {
+                let text = cue.text();
+                for to in [Language::English, Language::Chinese] {
+                    let parts = fit(&text, &to, &constraints);
+                    if let Some(timing) = parse_timing(&cue.timing) {
+                        assert_eq!(split_timing(timing, &parts).len(), parts.len());
+                    }
+                }
+            }
+            for line in input.lines() {
+                parse_timing(line);
+            }
+            break_lines(&input, None, 8);
+        }
+    }
+
+    /// 测试超出范围的时间戳无法解析，而不是溢出
+    #[test]
+    fn test_timestamp_overflow() {
+        assert_eq!(
+            parse_timestamp("01:02:03,004"),
+            Some(Duration::from_millis(3_723_004))
+        );
+        for timestamp in [
+            "18446744073709551615:00:00,000",
+            "5124095576030431:00:00,000",
+            "99999999999999999999:00:00,000",
+            "00:00:00,-01",
+            "00:00:00,+12",
+        ] {
+            assert_eq!(parse_timestamp(timestamp), None, "{}", timestamp);
+        }
+        assert!(parse_timing("00:00:00,000 --> 18446744073709551615:00:00,000").is_none());
+    }
+}
@@ -0,0 +1,409 @@
+use crate::fusion_translator::postprocess::is_cjk;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// 默认的最低出现次数
+pub const DEFAULT_MIN_FREQUENCY: usize = 2;
+
+/// 默认的最长词组（按单词计，逐字切分的文字按两倍计）
+pub const DEFAULT_MAX_NGRAM: usize = 3;
+
+/// 默认的最低一致性
+pub const DEFAULT_MIN_CONSISTENCY: f32 = 0.6;
+
+/// n元组内部词元之间的分隔符，不会出现在切分后的词元中
+const SEPARATOR: char = '\u{1F}';
+
+/// 术语提取选项
+#[derive(Debug, Clone)]
+pub struct TerminologyOptions {
+    /// 原文和译文词组至少出现在多少个句对中，为0时按1处理
+    pub min_frequency: usize,
+    /// 词组最多包含的单词数；中文、日文等逐字切分的文字最多包含两倍数量的字，为0时按1处理
+    pub max_ngram: usize,
+    /// 含有原文词组的句对中，译文同时含有候选译法的最低比例，范围0到1
+    pub min_consistency: f32,
+}
+
+impl Default for TerminologyOptions {
+    fn default() -> Self {
+        Self {
+            min_frequency: DEFAULT_MIN_FREQUENCY,
+            max_ngram: DEFAULT_MAX_NGRAM,
+            min_consistency: DEFAULT_MIN_CONSISTENCY,
+        }
+    }
+}
+
+/// 候选术语对
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TermCandidate {
+    /// 原文词组，西文为小写
+    pub source: String,
+    /// 最常对应的译文词组
+    pub target: String,
+    /// 含有原文词组的句对数
+    pub frequency: usize,
+    /// 含有原文词组的句对中，译文同时含有`target`的比例
+    pub consistency: f32,
+}
+
+/// 从对齐的句对中提取候选术语对
+///
+/// 按文字切分词元：西文等以空格分词的文字按单词（小写）切分，连字符和撇号连接的单词视为一个词，
+/// 中文和日文逐字切分；标点和文字体系变化处断开，词组不跨越断点。
+/// 统计每个原文词组和译文词组出现的句对数及共同出现的句对数，
+/// 为每个原文词组选择Dice系数最高的译文词组（相同时选择较长的），
+/// 一致性低于`min_consistency`的不作为候选。
+/// 只出现在某个更长候选之中（出现次数相同）的词组，以及比所含的较短候选出现次数少且译法相同的词组会被去除
+///
+/// # 参数
+/// - `pairs`: （原文，译文）句对，通常来自翻译记忆库
+/// - `options`: 提取选项
+///
+/// # 返回值
+/// 按出现次数从多到少排列的候选术语对，次数相同时按一致性从高到低、再按原文排列
+pub fn extract(pairs: &[(String, String)], options: &TerminologyOptions) -> Vec<TermCandidate> {
+    let max_ngram = options.max_ngram.max(1);
+    let min_frequency = options.min_frequency.max(1);
+    let grams = pairs
+        .iter()
+        .map(|(source, target)| (ngrams(source, max_ngram), ngrams(target, max_ngram)))
+        .collect::<Vec<_>>();
+    let source_frequency = frequencies(grams.iter().map(|(source, _)| source));
+    let target_frequency = frequencies(grams.iter().map(|(_, target)| target));
+
+    let mut cooccurrence = HashMap::<(&str, &str), usize>::new();
+    for (sources, targets) in &grams {
+        let targets = targets
+            .iter()
+            .filter(|target| target_frequency[target.as_str()] >= min_frequency)
+            .collect::<Vec<_>>();
+        let sources = sources
+            .iter()
+            .filter(|source| source_frequency[source.as_str()] >= min_frequency && is_term(source));
+        for source in sources {
+            for target in &targets {
+                *cooccurrence.entry((source, target)).or_default() += 1;
+            }
+        }
+    }
+
+    // 每个原文词组的最佳译法：（译文，Dice系数，共同出现次数）
+    let mut best = HashMap::<&str, (&str, f32, usize)>::new();
+    for (&(source, target), &count) in &cooccurrence {
+        let dice =
+            2.0 * count as f32 / (source_frequency[source] + target_frequency[target]) as f32;
+        let candidate = (target, dice, count);
+        match best.get(source) {
+            Some(current) if better_target(current, &candidate) != Ordering::Less => {}
+            _ => {
+                best.insert(source, candidate);
+            }
+        }
+    }
+    let candidates = best
+        .into_iter()
+        .filter_map(|(source, (target, _, count))| {
+            let frequency = source_frequency[source];
+            let consistency = count as f32 / frequency as f32;
+            (consistency >= options.min_consistency)
+                .then_some((source, (target, frequency, consistency)))
+        })
+        .collect::<HashMap<_, _>>();
+
+    // 只出现在更长候选之中的较短候选，以及包含出现次数更多、译法相同的较短候选的较长候选
+    let mut pruned = HashSet::new();
+    for (&source, &(target, frequency, _)) in &candidates {
+        for shorter in sub_ngrams(source) {
+            let Some((&shorter, &(other, other_frequency, _))) =
+                candidates.get_key_value(shorter.as_str())
+            else {
+                continue;
+            };
+            if other_frequency == frequency {
+                pruned.insert(shorter);
+            } else if other_frequency > frequency && other == target {
+                pruned.insert(source);
+            }
+        }
+    }
+    let mut terms = candidates
+        .iter()
+        .filter(|(source, _)| !pruned.contains(*source))
+        .map(
+            |(&source, &(target, frequency, consistency))| TermCandidate {
+                source: display(source),
+                target: display(target),
+                frequency,
+                consistency,
+            },
+        )
+        .collect::<Vec<_>>();
+    terms.sort_by(|a, b| {
+        b.frequency
+            .cmp(&a.frequency)
+            .then_with(|| b.consistency.total_cmp(&a.consistency))
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    terms
+}
+
+/// 导出为术语表
+///
+/// 每行一个术语对，格式为`原文\t译文`，顺序与`candidates`相同
+///
+/// # 参数
+/// - `candidates`: 候选术语对
+pub fn export_glossary(candidates: &[TermCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|term| format!("{}\t{}\n", term.source, term.target))
+        .collect()
+}
+
+/// 比较两个候选译法
+///
+/// # 返回值
+/// `a`优于`b`时为Greater：Dice系数更高，其次共同出现次数更多，其次更长，最后按字典序较小
+fn better_target(a: &(&str, f32, usize), b: &(&str, f32, usize)) -> Ordering {
+    a.1.total_cmp(&b.1)
+        .then_with(|| a.2.cmp(&b.2))
+        .then_with(|| a.0.chars().count().cmp(&b.0.chars().count()))
+        .then_with(|| b.0.cmp(a.0))
+}
+
+/// n元组中所有更短的连续子n元组
+fn sub_ngrams(gram: &str) -> Vec<String> {
+    let tokens = gram.split(SEPARATOR).collect::<Vec<_>>();
+    let count = tokens.len();
+    (0..count)
+        .flat_map(|start| (start + 1..=count).map(move |end| (start, end)))
+        .filter(|(start, end)| end - start < count)
+        .map(|(start, end)| tokens[start..end].join(&SEPARATOR.to_string()))
+        .collect()
+}
+
+/// 是否适合作为原文术语：不是单个字母或单个字，也不全是数字
+fn is_term(gram: &str) -> bool {
+    let mut chars = gram.chars();
+    let long_enough = chars.next().is_some() && chars.next().is_some();
+    long_enough && !gram.chars().all(|c| c.is_numeric() || c == SEPARATOR)
+}
+
+/// 统计每个n元组出现的句对数
+fn frequencies<'a>(grams: impl Iterator<Item = &'a HashSet<String>>) -> HashMap<&'a str, usize> {
+    let mut counts = HashMap::new();
+    for set in grams {
+        for gram in set {
+            *counts.entry(gram.as_str()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// 是否逐字切分（汉字和假名），谚文按空格分词
+fn is_char_token(c: char) -> bool {
+    is_cjk(c) && !('\u{AC00}'..='\u{D7A3}').contains(&c)
+}
+
+/// 按文字切分为词元片段，片段内的词元相邻，词组不跨越片段
+///
+/// # 参数
+/// - `text`: 句子
+fn segments(text: &str) -> Vec<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut segment = Vec::<String>::new();
+    let mut word = String::new();
+    // 当前片段是否为逐字切分的文字
+    let mut char_script = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() {
+            let char_token = is_char_token(c);
+            if char_token != char_script || char_token {
+                if !word.is_empty() {
+                    segment.push(std::mem::take(&mut word));
+                }
+                if char_token != char_script && !segment.is_empty() {
+                    segments.push(std::mem::take(&mut segment));
+                }
+                char_script = char_token;
+            }
+            if char_token {
+                segment.push(c.to_string());
+            } else {
+                word.extend(c.to_lowercase());
+            }
+            continue;
+        }
+        // 连字符和撇号连接的单词视为一个词
+        let connects = matches!(c, '-' | '\'' | '’')
+            && !word.is_empty()
+            && chars
+                .peek()
+                .is_some_and(|next| next.is_alphanumeric() && !is_char_token(*next));
+        if connects {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            segment.push(std::mem::take(&mut word));
+        }
+        if (!c.is_whitespace() || char_script) && !segment.is_empty() {
+            segments.push(std::mem::take(&mut segment));
+        }
+    }
+    if !word.is_empty() {
+        segment.push(word);
+    }
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+    segments
+}
+
+/// 句子中所有不重复的n元组，词元以`SEPARATOR`连接
+///
+/// # 参数
+/// - `text`: 句子
+/// - `max_ngram`: 最多包含的单词数，逐字切分的文字为两倍
+fn ngrams(text: &str, max_ngram: usize) -> HashSet<String> {
+    let mut grams = HashSet::new();
+    for segment in segments(text) {
+        let char_script = segment[0].chars().next().is_some_and(is_char_token);
+        let max = if char_script {
+            max_ngram * 2
+        } else {
+            max_ngram
+        };
+        for start in 0..segment.len() {
+            for end in start + 1..=segment.len().min(start + max) {
+                grams.insert(segment[start..end].join(&SEPARATOR.to_string()));
+            }
+        }
+    }
+    grams
+}
+
+/// 把n元组转为显示的文本：逐字切分的文字直接连接，其他文字以空格连接
+fn display(gram: &str) -> String {
+    if gram.chars().next().is_some_and(is_char_token) {
+        gram.replace(SEPARATOR, "")
+    } else {
+        gram.replace(SEPARATOR, " ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::terminology::{
+        export_glossary, extract, ngrams, segments, TerminologyOptions,
+    };
+
+    /// 合成的平行语料：每个术语套用若干句型
+    fn corpus() -> Vec<(String, String)> {
+        const TERMS: &[(&str, &str)] = &[
+            ("neural network", "神经网络"),
+            ("database", "数据库"),
+            ("compiler", "编译器"),
+            ("load balancer", "负载均衡器"),
+        ];
+        const TEMPLATES: &[(&str, &str)] = &[
+            ("The {} is fast.", "{}很快。"),
+            ("We improved the {} yesterday.", "我们昨天改进了{}。"),
+            ("Every {} needs testing!", "每个{}都需要测试！"),
+            ("Is this {} ready for production?", "这个{}可以上线了吗？"),
+        ];
+        TERMS
+            .iter()
+            .flat_map(|(en, zh)| {
+                TEMPLATES
+                    .iter()
+                    .map(|(source, target)| (source.replace("{}", en), target.replace("{}", zh)))
+            })
+            .collect()
+    }
+
+    /// 测试按文字切分词元
+    #[test]
+    fn test_segments() {
+        assert_eq!(
+            segments("State-of-the-art GPU, 神经网络！"),
+            vec![
+                vec!["state-of-the-art".to_string(), "gpu".to_string()],
+                vec![
+                    "神".to_string(),
+                    "经".to_string(),
+                    "网".to_string(),
+                    "络".to_string()
+                ],
+            ]
+        );
+        assert_eq!(
+            segments("用GPU训练"),
+            vec![vec!["用"], vec!["gpu"], vec!["训", "练"]]
+        );
+        assert!(segments(" ... ").is_empty());
+        assert_eq!(ngrams("a b c", 2).len(), 5);
+        assert_eq!(ngrams("一二三", 1).len(), 5);
+    }
+
+    /// 测试从合成语料中找回植入的术语
+    #[test]
+    fn test_recovers_planted_terms() {
+        let terms = extract(&corpus(), &TerminologyOptions::default());
+        for (source, target) in [
+            ("neural network", "神经网络"),
+            ("database", "数据库"),
+            ("compiler", "编译器"),
+            ("load balancer", "负载均衡器"),
+        ] {
+            let term = terms
+                .iter()
+                .find(|term| term.source == source)
+                .unwrap_or_else(|| panic!("{} not found in {:?}", source, terms));
+            assert_eq!(term.target, target);
+            assert_eq!(term.frequency, 4);
+            assert_eq!(term.consistency, 1.0);
+        }
+        // 只出现在术语中的单词不单独作为候选，带上下文的较长词组也不作为候选
+        for source in ["neural", "network", "balancer", "the neural network"] {
+            assert!(terms.iter().all(|term| term.source != source), "{source}");
+        }
+        let needs = terms.iter().find(|term| term.source == "needs testing");
+        assert_eq!(needs.map(|term| term.target.as_str()), Some("都需要测试"));
+        assert_eq!(terms[0].frequency, 4);
+    }
+
+    /// 测试反向提取和提取选项
+    #[test]
+    fn test_options() {
+        let reversed = corpus()
+            .into_iter()
+            .map(|(source, target)| (target, source))
+            .collect::<Vec<_>>();
+        let terms = extract(&reversed, &TerminologyOptions::default());
+        let term = terms.iter().find(|term| term.source == "数据库").unwrap();
+        assert_eq!(term.target, "database");
+
+        let options = TerminologyOptions {
+            min_frequency: 5,
+            ..Default::default()
+        };
+        assert!(extract(&corpus(), &options)
+            .iter()
+            .all(|term| term.frequency >= 5));
+        assert!(extract(&[], &TerminologyOptions::default()).is_empty());
+    }
+
+    /// 测试导出术语表
+    #[test]
+    fn test_export_glossary() {
+        let terms = extract(&corpus(), &TerminologyOptions::default());
+        let glossary = export_glossary(&terms);
+        assert_eq!(glossary.lines().count(), terms.len());
+        assert!(glossary.contains("neural network\t神经网络\n"));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::translator_error::ErrorKind;
+use std::fmt;
+use std::sync::Arc;
+
+/// 语料中的一个用例
+#[derive(Debug, Clone)]
+pub(crate) struct Case {
+    /// 用例名称，出现在差异报告中
+    pub name: &'static str,
+    /// 待翻译的文本
+    pub query: Vec<String>,
+    /// 是否通过`translate_vec`批量翻译，否则只翻译第一个文本
+    pub batch: bool,
+    /// 源语言，None表示自动检测
+    pub from: Option<Language>,
+    /// 目标语言
+    pub to: Language,
+}
+
+impl Case {
+    /// 单个文本的用例
+    fn single(name: &'static str, text: impl Into<String>) -> Self {
+        Self {
+            name,
+            query: vec![text.into()],
+            batch: false,
+            from: Some(Language::English),
+            to: Language::Chinese,
+        }
+    }
+
+    /// 批量翻译的用例
+    fn batch(name: &'static str, texts: &[&str]) -> Self {
+        Self {
+            name,
+            query: texts.iter().map(|s| s.to_string()).collect(),
+            batch: true,
+            from: Some(Language::English),
+            to: Language::Chinese,
+        }
+    }
+}
+
+/// 标准语料
+///
+/// 覆盖各翻译器容易出错的输入：换行、批量分隔符和URL特殊字符、表情符号、超长文本、空字符串和不支持的语言
+pub(crate) fn corpus() -> Vec<Case> {
+    // 所有内置翻译器都不支持阿布哈兹语
+    let unsupported = Language::Abkhazian;
+    vec![
+        Case::single("plain", "Hello, world"),
+        Case::single("newlines", "First line\nSecond line\n\nFourth line"),
+        Case::single("crlf", "one\r\ntwo\r\n"),
+        Case::single("delimiters", "a _._._ b | c & d # e ? f = g"),
+        Case::single("percent", "100% sure, 50%OFF"),
+        Case::single("emoji", "I ❤️ Rust 🦀👩‍👩‍👧"),
+        Case::single("cjk", "你好，世界"),
+        Case::single("long", "Lorem ipsum dolor sit amet. ".repeat(40)),
+        Case::single("near_limit", "word ".repeat(90)),
+        Case::single("empty", ""),
+        Case::single("whitespace", "  padded  "),
+        Case {
+            to: unsupported,
+            ..Case::single("unsupported_target", "Hello")
+        },
+        Case {
+            from: Some(unsupported),
+            ..Case::single("unsupported_source", "Hello")
+        },
+        Case {
+            from: None,
+            ..Case::single("autodetect", "Bonjour")
+        },
+        Case::batch("batch", &["one", "two", "three"]),
+        Case::batch("batch_empty_item", &["one", "", "three"]),
+        Case::batch("batch_newlines", &["a\nb", "c"]),
+        Case::batch("batch_delimiter", &["x _._._ y", "z"]),
+        Case::batch("batch_emoji", &["🦀", "👍🏽 ok"]),
+        Case::batch("batch_none", &[]),
+    ]
+}
+
+/// 翻译器对一个用例的结果
+#[derive(Debug, Clone)]
+pub(crate) enum Outcome {
+    /// 翻译成功
+    Translated {
+        /// 译文
+        text: Vec<String>,
+        /// 目标语言
+        lang: Option<Language>,
+    },
+    /// 翻译失败
+    Failed {
+        /// 错误分类
+        kind: ErrorKind,
+        /// 错误信息，只用于报告
+        message: String,
+    },
+}
+
+impl Outcome {
+    /// 两个结果是否等价
+    ///
+    /// 成功时译文和语言必须相同，失败时错误分类相同即可
+    pub fn equivalent(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (
+                Outcome::Translated { text, lang },
+                Outcome::Translated {
+                    text: other_text,
+                    lang: other_lang,
+                },
+            ) => text == other_text && lang == other_lang,
+            (Outcome::Failed { kind, .. }, Outcome::Failed { kind: other, .. }) => kind == other,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Translated { text, lang } => write!(f, "{:?} ({:?})", text, lang),
+            Outcome::Failed { kind, message } => write!(f, "error {}: {}", kind, message),
+        }
+    }
+}
+
+/// 一个用例上的差异
+#[derive(Debug, Clone)]
+pub(crate) struct Divergence {
+    /// 用例名称
+    pub case: &'static str,
+    /// 旧实现的结果
+    pub old: Outcome,
+    /// 新实现的结果
+    pub new: Outcome,
+}
+
+/// 差异测试报告
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Report {
+    /// 运行的用例数
+    pub cases: usize,
+    /// 结果不等价的用例
+    pub divergences: Vec<Divergence>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} cases diverged",
+            self.divergences.len(),
+            self.cases
+        )?;
+        for divergence in &self.divergences {
+            writeln!(f, "- {}", divergence.case)?;
+            writeln!(f, "    old: {}", divergence.old)?;
+            writeln!(f, "    new: {}", divergence.new)?;
+        }
+        Ok(())
+    }
+}
+
+/// 用翻译器运行一个用例
+async fn run(translator: &dyn AsyncTranslator, case: &Case) -> Outcome {
+    let result = if case.batch {
+        translator
+            .translate_vec(&case.query, case.from, &case.to)
+            .await
+            .map(|output| (output.text, output.lang))
+    } else {
+        translator
+            .translate(&case.query[0], case.from, &case.to)
+            .await
+            .map(|output| (vec![output.text], output.lang))
+    };
+    match result {
+        Ok((text, lang)) => Outcome::Translated { text, lang },
+        Err(e) => Outcome::Failed {
+            kind: ErrorKind::of(&e),
+            message: format!("{:#}", e),
+        },
+    }
+}
+
+/// 用同一组语料比较两个翻译器
+///
+/// 用例依次执行，两个翻译器的请求不会交错
+///
+/// # 参数
+/// - `old`: 旧实现
+/// - `new`: 新实现
+/// - `corpus`: 语料
+pub(crate) async fn compare(
+    old: &Arc<dyn AsyncTranslator>,
+    new: &Arc<dyn AsyncTranslator>,
+    corpus: &[Case],
+) -> Report {
+    let mut report = Report::default();
+    for case in corpus {
+        report.cases += 1;
+        let old = run(old.as_ref(), case).await;
+        let new = run(new.as_ref(), case).await;
+        if !old.equivalent(&new) {
+            report.divergences.push(Divergence {
+                case: case.name,
+                old,
+                new,
+            });
+        }
+    }
+    report
+}
+
+/// 断言两个翻译器在标准语料上的行为等价，否则打印差异报告
+///
+/// # 参数
+/// - `old`: 旧实现
+/// - `new`: 新实现
+pub(crate) async fn assert_equivalent(
+    old: &Arc<dyn AsyncTranslator>,
+    new: &Arc<dyn AsyncTranslator>,
+) {
+    let report = compare(old, new, &corpus()).await;
+    assert!(report.divergences.is_empty(), "{}", report);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::AsyncTranslator;
+    use crate::fusion_translator::cache::CachedTranslator;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::test_support::differential::{
+        assert_equivalent, compare, corpus, Outcome,
+    };
+    use crate::fusion_translator::translator_error::ErrorKind;
+    use std::sync::Arc;
+
+    /// 测试包装层不改变行为
+    #[tokio::test]
+    async fn test_equivalent() {
+        let old: Arc<dyn AsyncTranslator> = Arc::new(MockTranslator::new());
+        let new: Arc<dyn AsyncTranslator> =
+            Arc::new(CachedTranslator::new(Arc::new(MockTranslator::new()), 100));
+        assert_equivalent(&old, &new).await;
+    }
+
+    /// 测试报告列出结果不同的用例
+    #[tokio::test]
+    async fn test_reports_divergences() {
+        let old: Arc<dyn AsyncTranslator> = Arc::new(MockTranslator::new());
+        let new: Arc<dyn AsyncTranslator> = Arc::new(MockTranslator::new().fail_on("🦀"));
+        let report = compare(&old, &new, &corpus()).await;
+        assert_eq!(report.cases, corpus().len());
+        let cases = report
+            .divergences
+            .iter()
+            .map(|d| d.case)
+            .collect::<Vec<_>>();
+        assert_eq!(cases, ["emoji", "batch_emoji"]);
+        assert!(matches!(
+            report.divergences[0].old,
+            Outcome::Translated { .. }
+        ));
+        assert!(matches!(
+            report.divergences[0].new,
+            Outcome::Failed {
+                kind: ErrorKind::Api,
+                ..
+            }
+        ));
+        let text = report.to_string();
+        assert!(text.starts_with("2 of 20 cases diverged"), "{}", text);
+        assert!(text.contains("- batch_emoji"), "{}", text);
+    }
+}
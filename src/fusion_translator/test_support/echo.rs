@@ -0,0 +1,148 @@
+use crate::fusion_translator::translator_factory::{TranslatorConfig, TranslatorType};
+use crate::fusion_translator::transport::{HttpRequest, HttpResponse, HttpTransport};
+use reqwest::Url;
+use serde_json::{json, Value};
+
+/// Bing翻译页面，其中包含会话参数
+const BING_PAGE: &str = include_str!("../../../tests/fixtures/bing/translator.html");
+
+/// 按各翻译服务的响应格式回显原文的传输层
+///
+/// 把请求中的每个原文转为大写作为译文返回，不访问网络
+pub(crate) struct EchoTransport {
+    /// 模拟的翻译服务
+    backend: TranslatorType,
+}
+
+impl EchoTransport {
+    /// 创建模拟指定翻译服务的传输层
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器类型
+    pub fn new(backend: TranslatorType) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for EchoTransport {
+    async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        Ok(HttpResponse::new(200, echo(self.backend, &request)))
+    }
+}
+
+/// 使用假凭据的翻译器配置
+///
+/// # 参数
+/// - `backend`: 翻译器类型
+pub(crate) fn config(backend: TranslatorType) -> TranslatorConfig {
+    match backend {
+        TranslatorType::Baidu => TranslatorConfig::Baidu {
+            app_id: "app".to_string(),
+            key: "secret".to_string(),
+        },
+        TranslatorType::Youdao => TranslatorConfig::Youdao {
+            app_key: "app".to_string(),
+            app_secret: "secret".to_string(),
+        },
+        TranslatorType::Alibaba => TranslatorConfig::Alibaba {
+            token: String::new(),
+        },
+        TranslatorType::Caiyun => TranslatorConfig::Caiyun {
+            token: "token".to_string(),
+            request_id: "demo".to_string(),
+        },
+        TranslatorType::MyMemory => TranslatorConfig::MyMemory,
+        TranslatorType::TranSmart => TranslatorConfig::TranSmart,
+        TranslatorType::Reverso => TranslatorConfig::Reverso,
+        TranslatorType::BingWeb => TranslatorConfig::BingWeb,
+        TranslatorType::GoogleWeb => TranslatorConfig::GoogleWeb,
+    }
+}
+
+/// 按翻译服务的格式生成回显响应
+///
+/// # 参数
+/// - `backend`: 翻译器类型
+/// - `request`: 收到的请求
+///
+/// # 返回值
+/// 响应体
+fn echo(backend: TranslatorType, request: &HttpRequest) -> String {
+    let params = params(request);
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.to_uppercase())
+            .unwrap_or_default()
+    };
+    let body = || serde_json::from_slice::<Value>(&request.body).unwrap_or_default();
+    let upper = |value: &Value| value.as_str().unwrap_or_default().to_uppercase();
+    match backend {
+        TranslatorType::Baidu => {
+            let lines = param("q")
+                .split('\n')
+                .map(|line| json!({"src": line, "dst": line}))
+                .collect::<Vec<_>>();
+            json!({"from": "en", "to": "zh", "trans_result": lines}).to_string()
+        }
+        TranslatorType::Youdao if request.url.ends_with("/v2/api") => {
+            let results = params
+                .iter()
+                .filter(|(k, _)| k == "q")
+                .map(|(_, q)| json!({"query": q, "translation": q.to_uppercase()}))
+                .collect::<Vec<_>>();
+            json!({"errorCode": "0", "errorIndex": [], "translateResults": results}).to_string()
+        }
+        TranslatorType::Youdao => {
+            json!({"errorCode": "0", "query": param("q"), "translation": [param("q")]}).to_string()
+        }
+        TranslatorType::Alibaba => {
+            json!({"success": true, "data": {"translateText": param("query")}}).to_string()
+        }
+        TranslatorType::Caiyun => {
+            let target = body()["source"]
+                .as_array()
+                .map(|source| source.iter().map(upper).collect::<Vec<_>>())
+                .unwrap_or_default();
+            json!({ "target": target }).to_string()
+        }
+        TranslatorType::MyMemory => {
+            json!({"responseData": {"translatedText": param("q")}, "matches": []}).to_string()
+        }
+        TranslatorType::TranSmart => {
+            let target = body()["source"]["text_list"]
+                .as_array()
+                .map(|list| list.iter().map(upper).collect::<Vec<_>>())
+                .unwrap_or_default();
+            json!({"header": {"ret_code": "succ"}, "auto_translation": target}).to_string()
+        }
+        TranslatorType::Reverso => {
+            let input = upper(&body()["input"]);
+            json!({"from": "eng", "to": "chi", "input": [input], "translation": [input]})
+                .to_string()
+        }
+        TranslatorType::BingWeb if request.url.contains("/translator") => BING_PAGE.to_string(),
+        TranslatorType::BingWeb => {
+            json!([{"translations": [{"text": param("text"), "to": "zh-Hans"}]}]).to_string()
+        }
+        TranslatorType::GoogleWeb => {
+            json!([[[param("q"), param("q"), null, null, 10]], null, "en"]).to_string()
+        }
+    }
+}
+
+/// 请求的查询参数和表单参数
+///
+/// # 参数
+/// - `request`: 收到的请求
+fn params(request: &HttpRequest) -> Vec<(String, String)> {
+    let mut params = Url::parse(&request.url)
+        .map(|url| url.query_pairs().into_owned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Ok(form) = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&request.body) {
+        params.extend(form);
+    }
+    params
+}
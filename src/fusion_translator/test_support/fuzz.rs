@@ -0,0 +1,182 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// 每个种子语料默认生成的变异输入数量
+///
+/// 设置环境变量`FUZZ_MUTATIONS`可以增加数量做更长时间的模糊测试，
+/// 如`FUZZ_MUTATIONS=20000 cargo test --release fuzz`
+const MUTATIONS_PER_SEED: usize = 400;
+
+/// 插入到输入中的片段，覆盖解析器常见的分隔符、边界值和多字节字符
+const TOKENS: &[&str] = &[
+    "\n",
+    "\r\n",
+    "\r",
+    " ",
+    "\t",
+    "-->",
+    " --> ",
+    ":",
+    ",",
+    ".",
+    "...",
+    "\"",
+    "\\",
+    "\\u",
+    "\\u00",
+    "{",
+    "}",
+    "[",
+    "]",
+    "<",
+    ">",
+    "</",
+    "&",
+    "&#",
+    "&#x",
+    "&amp;",
+    ";",
+    "=",
+    "0",
+    "-1",
+    "99",
+    "4294967296",
+    "99999999999999999999",
+    "18446744073709551615",
+    "00:00:00,000",
+    "99:99:99,999",
+    "data: ",
+    "[DONE]",
+    "null",
+    "true",
+    "“",
+    "”",
+    "。",
+    "！",
+    "…",
+    "「",
+    "é",
+    "e\u{301}",
+    "\u{200d}",
+    "\u{200c}",
+    "\u{feff}",
+    "\u{a0}",
+    "\u{ad}",
+    "\u{0}",
+    "👨\u{200d}👩\u{200d}👧",
+    "🇨🇳",
+    "中文",
+    "한국어",
+    "العربية",
+];
+
+/// 每个种子语料生成的变异输入数量
+fn mutations() -> usize {
+    std::env::var("FUZZ_MUTATIONS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(MUTATIONS_PER_SEED)
+}
+
+/// 由种子语料变异生成模糊测试输入
+///
+/// 除种子本身外，每个种子生成`mutations()`个变异：删除、重复、截断、交换字符和插入`TOKENS`中的片段，
+/// 另外生成同样数量的随机拼接片段。所有输入都是合法的UTF-8
+///
+/// # 参数
+/// - `seed`: 随机数种子，通常为请求编号
+/// - `corpus`: 种子语料，通常取自`tests/fixtures`或单元测试中的样例
+pub(crate) fn inputs(seed: u64, corpus: &[&str]) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mutations = mutations();
+    let mut inputs = vec![String::new()];
+    for sample in corpus {
+        inputs.push(sample.to_string());
+        for _ in 0..mutations {
+            let mut chars = sample.chars().collect::<Vec<_>>();
+            for _ in 0..rng.random_range(1..=4) {
+                mutate(&mut rng, &mut chars);
+            }
+            inputs.push(chars.into_iter().collect());
+        }
+    }
+    for _ in 0..mutations {
+        let text = (0..rng.random_range(0..24))
+            .map(|_| TOKENS[rng.random_range(0..TOKENS.len())])
+            .collect::<String>();
+        inputs.push(text);
+    }
+    inputs
+}
+
+/// 由种子语料变异生成任意字节的模糊测试输入
+///
+/// 在`inputs`的基础上额外翻转、截断字节，得到的输入可能不是合法的UTF-8
+///
+/// # 参数
+/// - `seed`: 随机数种子
+/// - `corpus`: 种子语料
+pub(crate) fn byte_inputs(seed: u64, corpus: &[&[u8]]) -> Vec<Vec<u8>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mutations = mutations();
+    let mut inputs = vec![Vec::new()];
+    for sample in corpus {
+        inputs.push(sample.to_vec());
+        for _ in 0..mutations {
+            let mut bytes = sample.to_vec();
+            for _ in 0..rng.random_range(1..=4) {
+                let len = bytes.len();
+                match rng.random_range(0..4) {
+                    0 if len > 0 => bytes[rng.random_range(0..len)] = rng.random(),
+                    1 => bytes.truncate(rng.random_range(0..=len)),
+                    2 => {
+                        let at = rng.random_range(0..=len);
+                        let token = TOKENS[rng.random_range(0..TOKENS.len())].as_bytes();
+                        bytes.splice(at..at, token.iter().copied());
+                    }
+                    _ => bytes.insert(rng.random_range(0..=len), rng.random()),
+                }
+            }
+            inputs.push(bytes);
+        }
+    }
+    inputs
+}
+
+/// 对字符序列做一次随机变异
+///
+/// # 参数
+/// - `rng`: 随机数生成器
+/// - `chars`: 字符序列
+fn mutate(rng: &mut StdRng, chars: &mut Vec<char>) {
+    let len = chars.len();
+    let at = rng.random_range(0..=len);
+    match rng.random_range(0..6) {
+        // 删除一段
+        0 if len > 0 => {
+            let end = rng.random_range(at..=len);
+            chars.drain(at..end);
+        }
+        // 重复一段
+        1 if len > 0 => {
+            let end = rng.random_range(at..=len.min(at + 32));
+            let copy = chars[at..end].to_vec();
+            chars.splice(at..at, copy);
+        }
+        // 截断
+        2 => chars.truncate(at),
+        // 交换两个字符
+        3 if len > 1 => chars.swap(rng.random_range(0..len), rng.random_range(0..len)),
+        // 替换为随机字符
+        4 if len > 0 => {
+            if let Some(c) = char::from_u32(rng.random_range(0..0x2_0000)) {
+                chars[rng.random_range(0..len)] = c;
+            }
+        }
+        // 插入片段
+        _ => {
+            let token = TOKENS[rng.random_range(0..TOKENS.len())];
+            chars.splice(at..at, token.chars());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use reqwest::Url;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+/// 本地服务器收到的请求
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    /// 请求方法，如`GET`
+    pub method: String,
+    /// 请求路径，不含查询参数
+    pub path: String,
+    /// 查询参数，未编码前的原始字符串
+    pub query: String,
+    /// 请求头（名称，值），名称为小写
+    pub headers: Vec<(String, String)>,
+    /// 请求体
+    pub body: String,
+}
+
+impl Request {
+    /// 解码后的请求参数
+    ///
+    /// GET请求取查询参数，其他请求按表单解析请求体
+    pub fn params(&self) -> Vec<(String, String)> {
+        let encoded = if self.method == "GET" {
+            &self.query
+        } else {
+            &self.body
+        };
+        Url::parse(&format!("http://local/?{}", encoded))
+            .unwrap()
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+
+    /// 按名称取请求头，不区分大小写
+    ///
+    /// # 参数
+    /// - `name`: 请求头名称
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 按名称取请求参数
+    ///
+    /// # 参数
+    /// - `name`: 参数名称
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.params()
+            .into_iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v)
+    }
+}
+
+/// 启动由`handler`生成响应的本地HTTP服务器
+///
+/// 服务器在后台线程中逐个处理连接，每个响应后关闭连接
+///
+/// # 参数
+/// - `handler`: 按请求返回（状态码，JSON响应体）
+///
+/// # 返回值
+/// 服务器地址，如`http://127.0.0.1:12345`
+pub(crate) fn serve<F>(handler: F) -> String
+where
+    F: Fn(&Request) -> (u16, String) + Send + 'static,
+{
+    serve_raw(move |request| {
+        let (status, body) = handler(request);
+        format!(
+            "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .into_bytes()
+    })
+}
+
+/// 启动由`handler`生成完整响应的本地HTTP服务器
+///
+/// 用于需要自定义响应头或二进制响应体（如gzip压缩）的测试，其余同`serve`
+///
+/// # 参数
+/// - `handler`: 按请求返回包括状态行和响应头在内的原始响应
+///
+/// # 返回值
+/// 服务器地址，如`http://127.0.0.1:12345`
+pub(crate) fn serve_raw<F>(handler: F) -> String
+where
+    F: Fn(&Request) -> Vec<u8> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let mut length = 0;
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let mut parts = request_line.split(' ');
+            let method = parts.next().unwrap_or_default().to_string();
+            let target = parts.next().unwrap_or_default();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let request = Request {
+                method,
+                path: path.to_string(),
+                query: query.to_string(),
+                headers,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            };
+            let _ = (&stream).write_all(&handler(&request));
+        }
+    });
+    url
+}
@@ -0,0 +1,16 @@
+/// 差异测试
+///
+/// 用同一组语料比较两个翻译器实现的输出，用于验证重构翻译器时行为不变
+pub(crate) mod differential;
+/// 回显传输层
+///
+/// 按各翻译服务的响应格式把原文转为大写返回，用于不联网地运行对所有翻译器通用的检查
+pub(crate) mod echo;
+/// 模糊测试输入
+///
+/// 由种子语料变异生成任意文本和字节，供各解析器的模糊测试共用
+pub(crate) mod fuzz;
+/// 本地HTTP服务器
+///
+/// 按请求生成响应，代替真实的翻译服务
+pub(crate) mod http;
@@ -0,0 +1,425 @@
+use crate::fusion_translator::translator_error::TranslatorError;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// 自动检测时可信的最低置信度
+///
+/// 低于此值时检测结果可能是乱码，调用方应提示用户指定编码
+pub const MIN_CONFIDENCE: f32 = 0.6;
+
+/// 字节顺序标记（U+FEFF）
+const BOM: char = '\u{feff}';
+
+/// 文本编码选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// 自动检测：优先识别BOM，其次是有效的UTF-8，最后由chardetng按内容猜测
+    #[default]
+    Auto,
+    /// 使用指定的编码
+    Fixed(&'static Encoding),
+}
+
+impl InputEncoding {
+    /// 按编码名称创建选项
+    ///
+    /// # 参数
+    /// - `label`: `auto`或WHATWG编码标签，如`gbk`、`big5`、`shift_jis`、`utf-8`
+    ///
+    /// # 返回值
+    /// 名称无法识别时返回`TranslatorError::InvalidEncoding`
+    pub fn from_label(label: &str) -> Result<Self, TranslatorError> {
+        if label.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        Encoding::for_label(label.trim().as_bytes())
+            .map(Self::Fixed)
+            .ok_or_else(|| TranslatorError::InvalidEncoding(format!("unknown encoding {}", label)))
+    }
+}
+
+/// 解码后的文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedText {
+    /// UTF-8文本，不含BOM
+    pub text: String,
+    /// 原始编码
+    pub encoding: &'static Encoding,
+    /// 原始内容是否带有BOM
+    pub bom: bool,
+    /// 检测置信度，取值0.0~1.0；指定编码、BOM或有效的UTF-8为1.0
+    pub confidence: f32,
+}
+
+impl DecodedText {
+    /// 检测结果是否可信
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= MIN_CONFIDENCE
+    }
+
+    /// 按行拆分文本，用于逐行翻译
+    ///
+    /// 合并多个带BOM的文件时，BOM会出现在行首，这里一并去除，
+    /// 避免BOM混入原文，导致相同的行被当作不同的文本
+    ///
+    /// # 返回值
+    /// 不含换行符和BOM的各行
+    pub fn lines(&self) -> Vec<String> {
+        self.text
+            .lines()
+            .map(|line| line.trim_start_matches(BOM).to_string())
+            .collect()
+    }
+
+    /// 编码输出文本
+    ///
+    /// # 参数
+    /// - `text`: 要输出的文本，通常是译文
+    /// - `preserve_encoding`: 为true时使用原始编码并保留原有的BOM；
+    ///   为false时输出不带BOM的UTF-8
+    ///
+    /// # 返回值
+    /// 编码后的字节，原始编码无法表示的字符会转换为HTML数字字符引用
+    pub fn encode_output(&self, text: &str, preserve_encoding: bool) -> Vec<u8> {
+        if preserve_encoding {
+            encode(text, self.encoding, self.bom)
+        } else {
+            encode(text, UTF_8, false)
+        }
+    }
+}
+
+/// 解码文本
+///
+/// # 参数
+/// - `bytes`: 原始内容
+/// - `encoding`: 编码选项
+///
+/// # 返回值
+/// 解码后的文本，开头重复的BOM也会去除；指定编码且内容不合法时返回`TranslatorError::InvalidEncoding`。
+/// 自动检测时不会失败，但可能返回置信度较低的结果
+pub fn decode(bytes: &[u8], encoding: InputEncoding) -> Result<DecodedText, TranslatorError> {
+    let mut decoded = decode_with_bom(bytes, encoding)?;
+    if decoded.text.starts_with(BOM) {
+        decoded.text = decoded.text.trim_start_matches(BOM).to_string();
+        decoded.bom = true;
+    }
+    Ok(decoded)
+}
+
+/// 按BOM或编码选项解码
+///
+/// # 参数
+/// - `bytes`: 原始内容
+/// - `encoding`: 编码选项
+fn decode_with_bom(bytes: &[u8], encoding: InputEncoding) -> Result<DecodedText, TranslatorError> {
+    if let Some((bom_encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let expected = match encoding {
+            InputEncoding::Auto => bom_encoding,
+            InputEncoding::Fixed(fixed) => fixed,
+        };
+        if expected == bom_encoding {
+            let body = bytes.get(bom_len..).unwrap_or_default();
+            return decode_strict(body, bom_encoding, true);
+        }
+    }
+    match encoding {
+        InputEncoding::Fixed(fixed) => decode_strict(bytes, fixed, false),
+        InputEncoding::Auto => Ok(detect(bytes)),
+    }
+}
+
+/// 按指定编码解码，内容不合法时报错
+///
+/// # 参数
+/// - `bytes`: 去除BOM后的内容
+/// - `encoding`: 编码
+/// - `bom`: 原始内容是否带有BOM
+fn decode_strict(
+    bytes: &[u8],
+    encoding: &'static Encoding,
+    bom: bool,
+) -> Result<DecodedText, TranslatorError> {
+    let text = encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .ok_or_else(|| {
+            TranslatorError::InvalidEncoding(format!("input is not valid {}", encoding.name()))
+        })?;
+    Ok(DecodedText {
+        text: text.into_owned(),
+        encoding,
+        bom,
+        confidence: 1.0,
+    })
+}
+
+/// 自动检测编码
+///
+/// 有效的UTF-8直接采用；否则由chardetng猜测编码。猜测结果优于其他候选且解码无错误时置信度为0.9；
+/// 没有优于其他候选，或解码出自然文本中不会出现的C1控制字符时为0.3；解码出现错误时为0.0
+///
+/// # 参数
+/// - `bytes`: 不含BOM的内容
+fn detect(bytes: &[u8]) -> DecodedText {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return DecodedText {
+            text: text.to_string(),
+            encoding: UTF_8,
+            bom: false,
+            confidence: 1.0,
+        };
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let (encoding, distinct) = detector.guess_assess(None, false);
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    let control = text.chars().any(|c| c.is_control() && !c.is_ascii());
+    let confidence = if had_errors {
+        0.0
+    } else if distinct && !control {
+        0.9
+    } else {
+        0.3
+    };
+    DecodedText {
+        text: text.into_owned(),
+        encoding,
+        bom: false,
+        confidence,
+    }
+}
+
+/// 编码文本
+///
+/// # 参数
+/// - `text`: 文本
+/// - `encoding`: 目标编码
+/// - `bom`: 是否写入BOM，只对UTF-8和UTF-16有效
+///
+/// # 返回值
+/// 编码后的字节，目标编码无法表示的字符会转换为HTML数字字符引用
+pub fn encode(text: &str, encoding: &'static Encoding, bom: bool) -> Vec<u8> {
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let little_endian = encoding == UTF_16LE;
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        return units
+            .flat_map(|unit: u16| {
+                if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            })
+            .collect();
+    }
+    if encoding == UTF_8 {
+        let mut output = Vec::with_capacity(text.len() + 3);
+        if bom {
+            output.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        output.extend_from_slice(text.as_bytes());
+        return output;
+    }
+    encoding.encode(text).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::preprocess::{normalize_input, InputNormalization};
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::text_encoding::{decode, encode, InputEncoding};
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use encoding_rs::{BIG5, GBK, SHIFT_JIS, UTF_16LE, UTF_8};
+
+    /// GBK编码的中文字幕
+    const GBK_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/encoding/gbk.srt");
+
+    /// Shift-JIS编码的日文文本
+    const SHIFT_JIS_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/encoding/shift_jis.txt");
+
+    /// 带UTF-8 BOM的波斯文文本，第三行开头还有合并文件留下的BOM
+    const PERSIAN_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/encoding/bom_persian.txt");
+
+    /// 逐行翻译并按原始编码写回
+    async fn roundtrip(bytes: &[u8]) -> Vec<u8> {
+        let decoded = decode(bytes, InputEncoding::Auto).unwrap();
+        let lines = decoded
+            .text
+            .split('\n')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let output = MockTranslator::new()
+            .translate_vec(&lines, None, &Language::English)
+            .await
+            .unwrap();
+        decoded.encode_output(&output.text.join("\n"), true)
+    }
+
+    /// 测试识别GBK和Shift-JIS文件
+    #[test]
+    fn test_detect_legacy_encodings() {
+        let gbk = decode(GBK_FIXTURE, InputEncoding::Auto).unwrap();
+        assert_eq!(gbk.encoding, GBK);
+        assert!(gbk.is_confident(), "{}", gbk.confidence);
+        assert!(gbk.text.contains("这是一个测试字幕"));
+
+        let sjis = decode(SHIFT_JIS_FIXTURE, InputEncoding::Auto).unwrap();
+        assert_eq!(sjis.encoding, SHIFT_JIS);
+        assert!(sjis.is_confident(), "{}", sjis.confidence);
+        assert!(sjis.text.contains("これはテストです"));
+    }
+
+    /// 测试识别Big5文本
+    #[test]
+    fn test_detect_big5() {
+        let bytes = BIG5.encode("這是一個測試，我們的翻譯工具需要處理中文。").0;
+        let decoded = decode(&bytes, InputEncoding::Auto).unwrap();
+        assert_eq!(decoded.encoding, BIG5);
+        assert!(decoded.is_confident());
+    }
+
+    /// 测试用模拟翻译器翻译后按原始编码写回
+    #[tokio::test]
+    async fn test_roundtrip_with_mock_translator() {
+        let gbk = roundtrip(GBK_FIXTURE).await;
+        let expected = decode(GBK_FIXTURE, InputEncoding::Auto).unwrap();
+        assert_eq!(
+            decode(&gbk, InputEncoding::Fixed(GBK)).unwrap().text,
+            expected.text.to_uppercase()
+        );
+        assert_eq!(roundtrip(SHIFT_JIS_FIXTURE).await, SHIFT_JIS_FIXTURE);
+    }
+
+    /// 测试BOM的识别和保留
+    #[test]
+    fn test_bom() {
+        let decoded = decode(b"\xEF\xBB\xBFhello", InputEncoding::Auto).unwrap();
+        assert_eq!(decoded.text, "hello");
+        assert!(decoded.bom);
+        assert_eq!(decoded.encode_output("hi", false), b"hi");
+        assert_eq!(decoded.encode_output("hi", true), b"\xEF\xBB\xBFhi");
+
+        let utf16 = encode("你好", UTF_16LE, true);
+        let decoded = decode(&utf16, InputEncoding::Auto).unwrap();
+        assert_eq!(decoded.encoding, UTF_16LE);
+        assert_eq!(decoded.text, "你好");
+        assert_eq!(decoded.encode_output("你好", true), utf16);
+        assert_eq!(decoded.encode_output("你好", false), "你好".as_bytes());
+    }
+
+    /// 测试重复的BOM和合并文件时行首的BOM
+    #[test]
+    fn test_stray_boms() {
+        let decoded = decode(b"\xEF\xBB\xBF\xEF\xBB\xBFhello", InputEncoding::Auto).unwrap();
+        assert_eq!(decoded.text, "hello");
+        assert!(decoded.bom);
+        assert_eq!(decoded.encode_output("hi", true), b"\xEF\xBB\xBFhi");
+
+        let utf16 = encode("\u{feff}你好", UTF_16LE, false);
+        let decoded = decode(&utf16, InputEncoding::Fixed(UTF_16LE)).unwrap();
+        assert_eq!(decoded.text, "你好");
+        assert!(decoded.bom);
+
+        let decoded = decode(
+            "first\r\n\u{feff}second\n\u{feff}first\n".as_bytes(),
+            InputEncoding::Auto,
+        )
+        .unwrap();
+        assert!(!decoded.bom);
+        assert_eq!(decoded.lines(), ["first", "second", "first"]);
+    }
+
+    /// 测试带BOM的波斯文文件：BOM在分行前去除，保留编码时写回，零宽不连接符保留在波斯文单词中
+    #[tokio::test]
+    async fn test_bom_persian_fixture() {
+        let decoded = decode(PERSIAN_FIXTURE, InputEncoding::Auto).unwrap();
+        assert_eq!(decoded.encoding, UTF_8);
+        assert!(decoded.bom);
+        let lines = decoded.lines();
+        assert!(lines.iter().all(|line| !line.contains('\u{feff}')));
+        assert_eq!(lines[0], "من می\u{200c}خواهم این کتاب\u{200c}ها را بخوانم.");
+
+        let normalization = InputNormalization::default();
+        let query = lines
+            .iter()
+            .map(|line| normalize_input(line, &normalization))
+            .collect::<Vec<_>>();
+        assert_eq!(query[0], lines[0]);
+        assert!(query[1].contains("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}"));
+        assert_eq!(query[2], "Auflage und ZeroWidth");
+
+        let output = MockTranslator::new()
+            .translate_vec(&query, None, &Language::English)
+            .await
+            .unwrap();
+        let mut text = output.text.join("\n");
+        text.push('\n');
+        let bytes = decoded.encode_output(&text, true);
+        assert!(bytes.starts_with(b"\xEF\xBB\xBF"));
+        assert_eq!(bytes.windows(3).filter(|w| w == b"\xEF\xBB\xBF").count(), 1);
+        assert!(!decoded
+            .encode_output(&text, false)
+            .starts_with(b"\xEF\xBB\xBF"));
+        assert!(std::str::from_utf8(&bytes)
+            .unwrap()
+            .contains("می\u{200c}خواهم"));
+    }
+
+    /// 测试指定编码
+    #[test]
+    fn test_fixed_encoding() {
+        assert_eq!(
+            InputEncoding::from_label("Shift_JIS").unwrap(),
+            InputEncoding::Fixed(SHIFT_JIS)
+        );
+        assert_eq!(
+            InputEncoding::from_label("auto").unwrap(),
+            InputEncoding::Auto
+        );
+        assert!(matches!(
+            InputEncoding::from_label("klingon"),
+            Err(TranslatorError::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            decode(b"\xFF\xFE\xFD", InputEncoding::Fixed(UTF_8)),
+            Err(TranslatorError::InvalidEncoding(_))
+        ));
+        let decoded = decode(GBK_FIXTURE, InputEncoding::Fixed(GBK)).unwrap();
+        assert_eq!(decoded.confidence, 1.0);
+    }
+
+    /// 测试无法可靠识别的内容给出低置信度
+    #[test]
+    fn test_low_confidence() {
+        let decoded = decode(b"\x81\x30\xFF\xFE\x80", InputEncoding::Auto).unwrap();
+        assert!(!decoded.is_confident(), "{:?}", decoded);
+        let ascii = decode(b"plain text", InputEncoding::Auto).unwrap();
+        assert_eq!(ascii.encoding, UTF_8);
+        assert!(ascii.is_confident());
+    }
+
+    /// 模糊测试：任意字节按任意编码解码都不会panic
+    #[test]
+    fn test_fuzz_decode() {
+        let corpus: [&[u8]; 4] = [
+            include_bytes!("../../tests/fixtures/encoding/bom_persian.txt"),
+            include_bytes!("../../tests/fixtures/encoding/gbk.srt"),
+            include_bytes!("../../tests/fixtures/encoding/shift_jis.txt"),
+            b"\xff\xfeh\x00i\x00",
+        ];
+        for input in fuzz::byte_inputs(701, &corpus) {
+            for encoding in [
+                InputEncoding::Auto,
+                InputEncoding::Fixed(UTF_8),
+                InputEncoding::Fixed(UTF_16LE),
+                InputEncoding::Fixed(GBK),
+            ] {
+                if let Ok(decoded) = decode(&input, encoding) {
+                    assert!(!decoded.text.starts_with('\u{feff}'));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1161 @@
+use crate::fusion_translator::async_translator::{
+    AlternativeTranslation, AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::validate_batch;
+use crate::fusion_translator::input_check::InputUnit;
+use crate::fusion_translator::negotiate::parse_language_tag;
+use crate::fusion_translator::similarity::normalized_levenshtein;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 默认的模糊匹配阈值
+///
+/// 相似度不低于该值的记忆库条目会作为候选译文返回
+pub const DEFAULT_FUZZY_THRESHOLD: f32 = 0.75;
+
+/// 候选译文的来源标记
+const TM_SOURCE: &str = "tm";
+
+/// 翻译记忆库条目
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmEntry {
+    /// 原文
+    pub source: String,
+    /// 译文
+    pub target: String,
+    /// 创建时间，TMX格式（`YYYYMMDDThhmmssZ`）
+    pub creation_date: String,
+    /// 产生该译文的翻译引擎，未知时为None
+    pub engine: Option<String>,
+}
+
+/// 翻译记忆库
+///
+/// 以（源语言，目标语言，原文）为键保存句段对，可以与TMX 1.4文件互相转换
+#[derive(Debug, Clone, Default)]
+pub struct TranslationMemory {
+    /// 按语言对分组的条目
+    entries: HashMap<(Language, Language), HashMap<String, TmEntry>>,
+}
+
+impl TranslationMemory {
+    /// 创建空的翻译记忆库
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从TMX文件加载翻译记忆库
+    ///
+    /// 文件不存在时返回空的记忆库
+    ///
+    /// # 参数
+    /// - `path`: TMX文件路径
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut memory = Self::new();
+        match std::fs::read_to_string(path) {
+            Ok(xml) => {
+                memory.import_tmx(&xml)?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(memory)
+    }
+
+    /// 把翻译记忆库保存为TMX文件
+    ///
+    /// # 参数
+    /// - `path`: TMX文件路径
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(path, self.export_tmx())?;
+        Ok(())
+    }
+
+    /// 条目总数
+    pub fn len(&self) -> usize {
+        self.entries.values().map(HashMap::len).sum()
+    }
+
+    /// 是否没有任何条目
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 添加或覆盖一个条目，创建时间为当前时间
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    /// - `source`: 原文
+    /// - `target`: 译文
+    /// - `engine`: 产生译文的翻译引擎
+    pub fn insert(
+        &mut self,
+        from: Language,
+        to: Language,
+        source: &str,
+        target: &str,
+        engine: Option<&str>,
+    ) {
+        self.insert_entry(
+            from,
+            to,
+            TmEntry {
+                source: source.to_string(),
+                target: target.to_string(),
+                creation_date: tmx_date(SystemTime::now()),
+                engine: engine.map(str::to_string),
+            },
+        );
+    }
+
+    /// 添加或覆盖一个条目
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    /// - `entry`: 条目
+    pub fn insert_entry(&mut self, from: Language, to: Language, entry: TmEntry) {
+        self.entries
+            .entry((from, to))
+            .or_default()
+            .insert(entry.source.clone(), entry);
+    }
+
+    /// 精确查找条目
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    /// - `source`: 原文
+    pub fn get(&self, from: Language, to: Language, source: &str) -> Option<&TmEntry> {
+        self.entries.get(&(from, to))?.get(source)
+    }
+
+    /// 记忆库中的所有语言对，按TMX语言代码排序
+    pub fn language_pairs(&self) -> Vec<(Language, Language)> {
+        let mut pairs = self.entries.keys().copied().collect::<Vec<_>>();
+        pairs.sort_by_key(|(from, to)| (tmx_lang(from), tmx_lang(to)));
+        pairs
+    }
+
+    /// 某个语言对的所有条目，按原文排序
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    pub fn entries(&self, from: Language, to: Language) -> Vec<&TmEntry> {
+        let mut entries = self
+            .entries
+            .get(&(from, to))
+            .map(|entries| entries.values().collect::<Vec<_>>())
+            .unwrap_or_default();
+        entries.sort_by(|a, b| a.source.cmp(&b.source));
+        entries
+    }
+
+    /// 查找模糊匹配的条目
+    ///
+    /// # 参数
+    /// - `from`: 源语言
+    /// - `to`: 目标语言
+    /// - `source`: 原文
+    /// - `threshold`: 最低相似度，范围0到1
+    ///
+    /// # 返回值
+    /// 相似度不低于阈值的候选译文，按相似度从高到低排列，不包含精确匹配
+    pub fn fuzzy_matches(
+        &self,
+        from: Language,
+        to: Language,
+        source: &str,
+        threshold: f32,
+    ) -> Vec<AlternativeTranslation> {
+        let Some(entries) = self.entries.get(&(from, to)) else {
+            return Vec::new();
+        };
+        let mut matches = entries
+            .values()
+            .filter(|entry| entry.source != source)
+            .map(|entry| (normalized_levenshtein(source, &entry.source), entry))
+            .filter(|(score, _)| *score >= threshold)
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, x), (b, y)| b.total_cmp(a).then_with(|| x.source.cmp(&y.source)));
+        matches
+            .into_iter()
+            .map(|(score, entry)| AlternativeTranslation {
+                text: entry.target.clone(),
+                score: Some(score),
+                source: Some(TM_SOURCE.to_string()),
+            })
+            .collect()
+    }
+
+    /// 导入TMX文档
+    ///
+    /// 支持TMX 1.4的`xml:lang`和旧版本的`lang`属性，忽略命名空间前缀；
+    /// 无法识别语言的`tuv`会被跳过
+    ///
+    /// # 参数
+    /// - `xml`: TMX文档内容
+    ///
+    /// # 返回值
+    /// 导入的条目数
+    pub fn import_tmx(&mut self, xml: &str) -> Result<usize, TranslatorError> {
+        let (header_srclang, units) = TmxReader::read(xml)?;
+        let mut imported = 0;
+        for tu in units {
+            let TuBuilder {
+                srclang,
+                creation_date,
+                engine,
+                variants,
+            } = tu;
+            let srclang = srclang.or_else(|| header_srclang.clone());
+            let source_lang = srclang
+                .as_deref()
+                .filter(|l| !l.eq_ignore_ascii_case("*all*"))
+                .and_then(parse_language_tag);
+            for (from, source) in &variants {
+                if source_lang.is_some_and(|l| l != *from) {
+                    continue;
+                }
+                for (to, target) in &variants {
+                    if from == to {
+                        continue;
+                    }
+                    self.insert_entry(
+                        *from,
+                        *to,
+                        TmEntry {
+                            source: source.clone(),
+                            target: target.clone(),
+                            creation_date: creation_date.clone(),
+                            engine: engine.clone(),
+                        },
+                    );
+                    imported += 1;
+                }
+            }
+        }
+        Ok(imported)
+    }
+
+    /// 导出为TMX 1.4文档
+    ///
+    /// 条目按语言对和原文排序，保证相同的记忆库总是导出相同的内容
+    ///
+    /// # 返回值
+    /// TMX文档内容
+    pub fn export_tmx(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<tmx version=\"1.4\">\n");
+        out.push_str(&format!(
+            "  <header creationtool=\"{}\" creationtoolversion=\"{}\" segtype=\"sentence\" o-tmf=\"{}\" adminlang=\"en\" srclang=\"*all*\" datatype=\"plaintext\"/>\n",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            env!("CARGO_PKG_NAME"),
+        ));
+        out.push_str("  <body>\n");
+
+        let mut pairs = self
+            .entries
+            .iter()
+            .filter_map(|(&(from, to), entries)| Some((tmx_lang(&from)?, tmx_lang(&to)?, entries)))
+            .collect::<Vec<_>>();
+        pairs.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        for (from, to, entries) in pairs {
+            let mut entries = entries.values().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.source.cmp(&b.source));
+            for entry in entries {
+                if entry.creation_date.is_empty() {
+                    out.push_str(&format!("    <tu srclang=\"{}\">\n", escape(&from)));
+                } else {
+                    out.push_str(&format!(
+                        "    <tu srclang=\"{}\" creationdate=\"{}\">\n",
+                        escape(&from),
+                        escape(&entry.creation_date)
+                    ));
+                }
+                if let Some(engine) = &entry.engine {
+                    out.push_str(&format!(
+                        "      <prop type=\"x-engine\">{}</prop>\n",
+                        escape(engine)
+                    ));
+                }
+                for (lang, text) in [(&from, &entry.source), (&to, &entry.target)] {
+                    out.push_str(&format!(
+                        "      <tuv xml:lang=\"{}\"><seg>{}</seg></tuv>\n",
+                        escape(lang),
+                        escape(text)
+                    ));
+                }
+                out.push_str("    </tu>\n");
+            }
+        }
+        out.push_str("  </body>\n</tmx>\n");
+        out
+    }
+}
+
+/// 带翻译记忆库的翻译器
+///
+/// 翻译前先查询记忆库：精确匹配直接返回，不调用内部翻译器；
+/// 相似度不低于阈值的模糊匹配作为候选译文返回；内部翻译器的结果会写回记忆库。
+/// 未指定源语言时无法确定语言对，直接调用内部翻译器
+pub struct TmTranslator {
+    /// 内部翻译器
+    inner: Arc<dyn AsyncTranslator>,
+    /// 内部翻译器的引擎名称，导出TMX时作为属性保存
+    engine: String,
+    /// 翻译记忆库
+    memory: Mutex<TranslationMemory>,
+    /// 模糊匹配阈值
+    threshold: f32,
+}
+
+impl TmTranslator {
+    /// 创建新的记忆库翻译器
+    ///
+    /// # 参数
+    /// - `inner`: 内部翻译器
+    /// - `engine`: 内部翻译器的引擎名称
+    /// - `memory`: 初始的翻译记忆库
+    pub fn new(inner: Arc<dyn AsyncTranslator>, engine: &str, memory: TranslationMemory) -> Self {
+        Self {
+            inner,
+            engine: engine.to_string(),
+            memory: Mutex::new(memory),
+            threshold: DEFAULT_FUZZY_THRESHOLD,
+        }
+    }
+
+    /// 设置模糊匹配阈值
+    ///
+    /// # 参数
+    /// - `threshold`: 最低相似度，范围0到1
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// 获取当前翻译记忆库的副本
+    pub fn memory(&self) -> TranslationMemory {
+        self.memory.lock().unwrap().clone()
+    }
+
+    /// 把当前翻译记忆库保存为TMX文件
+    ///
+    /// # 参数
+    /// - `path`: TMX文件路径
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.memory.lock().unwrap().save(path)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for TmTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// 与内部翻译器相同
+    fn local(&self) -> bool {
+        self.inner.local()
+    }
+
+    /// 单次批量请求最多的文本数量
+    ///
+    /// 与内部翻译器相同
+    fn max_batch_len(&self) -> Option<usize> {
+        self.inner.max_batch_len()
+    }
+
+    /// 单次请求的长度上限
+    ///
+    /// 与内部翻译器相同
+    fn input_limit(&self) -> Option<usize> {
+        self.inner.input_limit()
+    }
+
+    /// 输入长度的单位
+    ///
+    /// 与内部翻译器相同
+    fn input_unit(&self) -> InputUnit {
+        self.inner.input_unit()
+    }
+
+    /// 支持的语言
+    ///
+    /// 与内部翻译器相同
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        self.inner.supported_languages()
+    }
+
+    /// 是否按请求量计费
+    fn paid(&self) -> bool {
+        self.inner.paid()
+    }
+
+    /// 检测文本的语言
+    ///
+    /// 直接交给内部翻译器，不查询翻译记忆
+    async fn detect_language(&self, text: &str) -> anyhow::Result<Option<Language>> {
+        self.inner.detect_language(text).await
+    }
+
+    async fn warm_up(&self) {
+        self.inner.warm_up().await
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果，模糊匹配的记忆库条目排在候选译文的最前面
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let Some(from) = from else {
+            return self
+                .inner
+                .translate_with_options(query, None, to, options)
+                .await;
+        };
+        let fuzzy = {
+            let memory = self.memory.lock().unwrap();
+            if let Some(entry) = memory.get(from, *to, query) {
+                let mut output = TranslationOutput {
+                    text: entry.target.clone(),
+                    lang: Some(*to),
+                    ..Default::default()
+                };
+                output.detail.request_id = options.resolve_request_id();
+                return Ok(output);
+            }
+            memory.fuzzy_matches(from, *to, query, self.threshold)
+        };
+
+        let mut output = self
+            .inner
+            .translate_with_options(query, Some(from), to, options)
+            .await?;
+        if !output.detail.possibly_untranslated {
+            self.memory
+                .lock()
+                .unwrap()
+                .insert(from, *to, query, &output.text, Some(&self.engine));
+        }
+        output.detail.alternatives.splice(0..0, fuzzy);
+        Ok(output)
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 只把记忆库中没有精确匹配的文本发送给内部翻译器
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let Some(from) = from else {
+            return self
+                .inner
+                .translate_vec_with_options(query, None, to, options)
+                .await;
+        };
+        let mut texts = {
+            let memory = self.memory.lock().unwrap();
+            query
+                .iter()
+                .map(|q| memory.get(from, *to, q).map(|e| e.target.clone()))
+                .collect::<Vec<_>>()
+        };
+        let misses = texts
+            .iter()
+            .zip(query)
+            .filter(|(text, _)| text.is_none())
+            .map(|(_, q)| q.clone())
+            .collect::<Vec<_>>();
+
+        let mut output = TranslationListOutput {
+            lang: Some(*to),
+            ..Default::default()
+        };
+        output.detail.request_id = options.resolve_request_id();
+        if !misses.is_empty() {
+            let translated = self
+                .inner
+                .translate_vec_with_options(&misses, Some(from), to, options)
+                .await?;
+            let translated_texts = validate_batch(misses.len(), translated.text)?;
+            output.detail = translated.detail;
+            if !output.detail.possibly_untranslated {
+                let mut memory = self.memory.lock().unwrap();
+                for (source, target) in misses.iter().zip(&translated_texts) {
+                    memory.insert(from, *to, source, target, Some(&self.engine));
+                }
+            }
+            let mut translated_texts = translated_texts.into_iter();
+            for text in texts.iter_mut().filter(|t| t.is_none()) {
+                *text = translated_texts.next();
+            }
+        }
+        output.text = texts.into_iter().map(Option::unwrap_or_default).collect();
+        Ok(output)
+    }
+}
+
+/// 获取语言在TMX中的代码
+///
+/// # 参数
+/// - `lang`: 语言
+///
+/// # 返回值
+/// 语言代码，语言没有ISO代码时为None
+fn tmx_lang(lang: &Language) -> Option<String> {
+    if *lang == Language::ChineseTraditional {
+        return Some("zh-Hant".to_string());
+    }
+    lang.to_639_1()
+        .or_else(|| lang.to_639_3())
+        .map(str::to_string)
+}
+
+/// 把时间格式化为TMX日期（`YYYYMMDDThhmmssZ`，UTC）
+///
+/// # 参数
+/// - `time`: 时间
+fn tmx_date(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = utc_fields(time);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// 把时间拆分为UTC的年、月、日、时、分、秒
+///
+/// 早于1970年的时间按1970-01-01 00:00:00处理
+///
+/// # 参数
+/// - `time`: 时间
+pub(crate) fn utc_fields(time: SystemTime) -> (i64, i64, i64, u64, u64, u64) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // 公历日期换算（Howard Hinnant的days_from_civil逆算法）
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60)
+}
+
+/// 转义XML特殊字符
+///
+/// # 参数
+/// - `text`: 原始文本
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// TMX内联标记中表示原始格式代码的元素，其中的文本不属于译文
+const INLINE_CODES: [&str; 4] = ["bpt", "ept", "ph", "it"];
+
+/// 读取中的翻译单元（`tu`）
+#[derive(Debug, Default)]
+struct TuBuilder {
+    /// `srclang`属性
+    srclang: Option<String>,
+    /// `creationdate`属性
+    creation_date: String,
+    /// 第一个`type="x-engine"`的`prop`中的文本
+    engine: Option<String>,
+    /// 各`tuv`的语言和第一个`seg`的文本
+    variants: Vec<(Language, String)>,
+}
+
+/// 正在收集文本的元素
+#[derive(Debug)]
+enum Capture {
+    /// `type="x-engine"`的`prop`
+    Engine(String),
+    /// `tuv`中的第一个`seg`，语言无法识别时为None
+    Seg(Option<Language>, String),
+}
+
+/// 按quick-xml的事件流读取TMX文档
+///
+/// 只记录所在元素的路径，不构造文档树，嵌套深度只影响路径的长度
+#[derive(Debug, Default)]
+struct TmxReader {
+    /// 第一个`header`的`srclang`属性
+    header_srclang: Option<String>,
+    /// 是否已读到`header`
+    header_seen: bool,
+    /// 已读到的`body`数量，只导入第一个`body`中的翻译单元
+    bodies: usize,
+    /// 已读完的翻译单元
+    units: Vec<TuBuilder>,
+    /// 正在读取的翻译单元
+    tu: Option<TuBuilder>,
+    /// 正在读取的`tuv`的语言，不在`tuv`中时为None
+    tuv: Option<Option<Language>>,
+    /// 当前`tuv`是否已读到`seg`
+    seg_seen: bool,
+    /// 正在收集的文本及其元素所在的深度
+    capture: Option<(usize, Capture)>,
+    /// 收集文本时所在的内联代码元素层数
+    skip: usize,
+}
+
+impl TmxReader {
+    /// 读取整个文档
+    ///
+    /// # 参数
+    /// - `xml`: TMX文档内容
+    ///
+    /// # 返回值
+    /// 第一个`header`的`srclang`属性和第一个`body`中的翻译单元
+    fn read(xml: &str) -> Result<(Option<String>, Vec<TuBuilder>), TranslatorError> {
+        let mut reader = Reader::from_str(xml.trim_start_matches('\u{feff}'));
+        let invalid = |reader: &Reader<&[u8]>, message: String| {
+            TranslatorError::InvalidTmx(format!("{} at byte {}", message, reader.buffer_position()))
+        };
+        let mut state = TmxReader::default();
+        let mut path: Vec<String> = Vec::new();
+        let mut root = None;
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| invalid(&reader, e.to_string()))?;
+            match &event {
+                Event::Start(start) | Event::Empty(start) => {
+                    let name = local_name(start.local_name().as_ref());
+                    let attrs = attributes(start).map_err(|e| invalid(&reader, e.to_string()))?;
+                    if path.is_empty() {
+                        if root.is_some() {
+                            return Err(invalid(
+                                &reader,
+                                "unexpected content after root element".to_string(),
+                            ));
+                        }
+                        if name != "tmx" {
+                            return Err(TranslatorError::InvalidTmx(format!(
+                                "unexpected root element <{}>",
+                                name
+                            )));
+                        }
+                        root = Some(());
+                    }
+                    state.open(&path, &name, &attrs);
+                    path.push(name);
+                    if matches!(event, Event::Empty(_)) {
+                        state.close(&path);
+                        path.pop();
+                    }
+                }
+                Event::End(_) => {
+                    state.close(&path);
+                    path.pop();
+                }
+                Event::Text(text) => {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| invalid(&reader, e.to_string()))?;
+                    if path.is_empty() && !text.trim().is_empty() {
+                        return Err(invalid(
+                            &reader,
+                            "unexpected text outside the root element".to_string(),
+                        ));
+                    }
+                    state.text(&text);
+                }
+                Event::CData(cdata) => {
+                    if path.is_empty() {
+                        return Err(invalid(
+                            &reader,
+                            "unexpected CDATA outside the root element".to_string(),
+                        ));
+                    }
+                    state.text(&String::from_utf8_lossy(cdata));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        if let Some(name) = path.last() {
+            return Err(invalid(&reader, format!("missing </{}>", name)));
+        }
+        if root.is_none() {
+            return Err(invalid(&reader, "expected root element".to_string()));
+        }
+        if state.bodies == 0 {
+            return Err(TranslatorError::InvalidTmx("missing <body>".to_string()));
+        }
+        Ok((state.header_srclang, state.units))
+    }
+
+    /// 处理开始标签
+    ///
+    /// # 参数
+    /// - `path`: 父元素的路径（不含本元素）
+    /// - `name`: 元素名
+    /// - `attrs`: 属性
+    fn open(&mut self, path: &[String], name: &str, attrs: &[(String, String)]) {
+        let attr = |key: &str| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+        if let Some((depth, _)) = &self.capture {
+            if path.len() > *depth && (self.skip > 0 || INLINE_CODES.contains(&name)) {
+                self.skip += 1;
+            }
+            return;
+        }
+        let parent = path.last().map(String::as_str);
+        match (path.len(), parent, name) {
+            (1, Some("tmx"), "header") if !self.header_seen => {
+                self.header_seen = true;
+                self.header_srclang = attr("srclang");
+            }
+            (1, Some("tmx"), "body") => self.bodies += 1,
+            (2, Some("body"), "tu") if self.bodies == 1 => {
+                self.tu = Some(TuBuilder {
+                    srclang: attr("srclang"),
+                    creation_date: attr("creationdate").unwrap_or_default(),
+                    ..TuBuilder::default()
+                });
+            }
+            (3, Some("tu"), "prop") => {
+                let engine = attr("type").as_deref() == Some("x-engine");
+                if engine && self.tu.as_ref().is_some_and(|tu| tu.engine.is_none()) {
+                    self.capture = Some((path.len(), Capture::Engine(String::new())));
+                }
+            }
+            (3, Some("tu"), "tuv") if self.tu.is_some() => {
+                self.tuv = Some(attr("lang").as_deref().and_then(parse_language_tag));
+                self.seg_seen = false;
+            }
+            (4, Some("tuv"), "seg") if !self.seg_seen => {
+                if let Some(lang) = self.tuv {
+                    self.seg_seen = true;
+                    self.capture = Some((path.len(), Capture::Seg(lang, String::new())));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 处理结束标签
+    ///
+    /// # 参数
+    /// - `path`: 元素的路径（含本元素）
+    fn close(&mut self, path: &[String]) {
+        let depth = path.len().saturating_sub(1);
+        if let Some((start, _)) = &self.capture {
+            if depth > *start {
+                self.skip = self.skip.saturating_sub(1);
+                return;
+            }
+            match self.capture.take() {
+                Some((_, Capture::Engine(text))) => {
+                    if let Some(tu) = &mut self.tu {
+                        tu.engine = Some(text);
+                    }
+                }
+                Some((_, Capture::Seg(Some(lang), text))) => {
+                    if let Some(tu) = &mut self.tu {
+                        tu.variants.push((lang, text));
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        match (depth, path.last().map(String::as_str)) {
+            (3, Some("tuv")) => self.tuv = None,
+            (2, Some("tu")) => self.units.extend(self.tu.take()),
+            _ => {}
+        }
+    }
+
+    /// 处理文本和CDATA
+    ///
+    /// # 参数
+    /// - `text`: 已解码实体的文本
+    fn text(&mut self, text: &str) {
+        if self.skip > 0 {
+            return;
+        }
+        match &mut self.capture {
+            Some((_, Capture::Engine(out) | Capture::Seg(_, out))) => out.push_str(text),
+            None => {}
+        }
+    }
+}
+
+/// 读取元素的属性
+///
+/// 属性名去除命名空间前缀（`xml:lang`记为`lang`），属性值已解码实体
+///
+/// # 参数
+/// - `start`: 开始标签
+fn attributes(start: &BytesStart<'_>) -> quick_xml::Result<Vec<(String, String)>> {
+    start
+        .attributes()
+        .map(|attr| {
+            let attr = attr?;
+            Ok((
+                local_name(attr.key.local_name().as_ref()),
+                attr.unescape_value()?.into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// 去除命名空间前缀后的名称
+///
+/// # 参数
+/// - `name`: quick-xml给出的本地名称
+fn local_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use crate::fusion_translator::similarity::normalized_levenshtein;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::tmx::{tmx_date, TmTranslator, TranslationMemory};
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// 标准TMX 1.4示例文件
+    const BASIC: &str = include_str!("../../tests/fixtures/tmx/basic.tmx");
+    /// 使用命名空间、旧版lang属性、CDATA和内联标记的TMX示例文件
+    const LEGACY: &str = include_str!("../../tests/fixtures/tmx/legacy.tmx");
+
+    /// 测试导入标准TMX文件
+    #[test]
+    fn test_import_basic() {
+        let mut memory = TranslationMemory::new();
+        assert_eq!(memory.import_tmx(BASIC).unwrap(), 3);
+        let entry = memory
+            .get(Language::English, Language::Chinese, "Hello world")
+            .unwrap();
+        assert_eq!(entry.target, "你好，世界");
+        assert_eq!(entry.creation_date, "20250102T030405Z");
+        assert_eq!(entry.engine.as_deref(), Some("baidu"));
+        let entry = memory
+            .get(
+                Language::English,
+                Language::ChineseTraditional,
+                "Save & exit",
+            )
+            .unwrap();
+        assert_eq!(entry.target, "儲存並離開");
+        assert!(memory
+            .get(Language::Chinese, Language::English, "你好，世界")
+            .is_none());
+        assert_eq!(
+            memory.language_pairs(),
+            vec![
+                (Language::English, Language::Japanese),
+                (Language::English, Language::Chinese),
+                (Language::English, Language::ChineseTraditional)
+            ]
+        );
+        let entries = memory.entries(Language::English, Language::Chinese);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, "Hello world");
+        assert!(memory
+            .entries(Language::Chinese, Language::English)
+            .is_empty());
+    }
+
+    /// 测试命名空间和内联标记的兼容性
+    #[test]
+    fn test_import_legacy() {
+        let mut memory = TranslationMemory::new();
+        assert_eq!(memory.import_tmx(LEGACY).unwrap(), 3);
+        let entry = memory
+            .get(
+                Language::English,
+                Language::German,
+                "Click Save to continue.",
+            )
+            .unwrap();
+        assert_eq!(entry.target, "Klicken Sie auf Speichern, um fortzufahren.");
+        assert!(memory
+            .get(
+                Language::German,
+                Language::English,
+                "Klicken Sie auf Speichern, um fortzufahren."
+            )
+            .is_some());
+        let entry = memory
+            .get(Language::German, Language::English, "<b>Fett</b> & kursiv")
+            .unwrap();
+        assert_eq!(entry.target, "Bold & italic");
+    }
+
+    /// 测试导出后重新导入的结果一致
+    #[test]
+    fn test_round_trip() {
+        let mut memory = TranslationMemory::new();
+        memory.import_tmx(BASIC).unwrap();
+        memory.insert(
+            Language::English,
+            Language::Japanese,
+            "a < b \"quoted\"",
+            "a < b「引用」",
+            Some("mock"),
+        );
+        let exported = memory.export_tmx();
+
+        let mut reloaded = TranslationMemory::new();
+        reloaded.import_tmx(&exported).unwrap();
+        assert_eq!(reloaded.len(), memory.len());
+        for (from, to, source) in [
+            (Language::English, Language::Chinese, "Hello world"),
+            (
+                Language::English,
+                Language::ChineseTraditional,
+                "Save & exit",
+            ),
+            (Language::English, Language::Japanese, "a < b \"quoted\""),
+        ] {
+            assert_eq!(reloaded.get(from, to, source), memory.get(from, to, source));
+        }
+        assert_eq!(reloaded.export_tmx(), exported);
+    }
+
+    /// 测试无效文档返回错误
+    #[test]
+    fn test_invalid_documents() {
+        let mut memory = TranslationMemory::new();
+        for xml in [
+            "",
+            "<tmx><body>",
+            "<tmx><body></tmx></body>",
+            "<root/>",
+            "<tmx version=1.4><body/></tmx>",
+            "<tmx/>",
+        ] {
+            assert!(memory.import_tmx(xml).is_err(), "{}", xml);
+        }
+        let deep = "<a>".repeat(1000);
+        assert!(memory.import_tmx(&deep).is_err());
+    }
+
+    /// 测试深层嵌套的内联标记按流式读取，不会栈溢出
+    #[test]
+    fn test_deeply_nested_segment() {
+        let xml = format!(
+            "<tmx><body><tu><tuv xml:lang=\"en\"><seg>{}Hello{}</seg></tuv><tuv xml:lang=\"zh\"><seg>你好</seg></tuv></tu></body></tmx>",
+            "<hi>".repeat(100_000),
+            "</hi>".repeat(100_000),
+        );
+        let mut memory = TranslationMemory::new();
+        assert_eq!(memory.import_tmx(&xml).unwrap(), 2);
+        assert_eq!(
+            memory
+                .get(Language::English, Language::Chinese, "Hello")
+                .map(|e| e.target.as_str()),
+            Some("你好")
+        );
+    }
+
+    /// 测试模糊匹配使用的相似度
+    #[test]
+    fn test_normalized_levenshtein() {
+        assert_eq!(normalized_levenshtein("", ""), 1.0);
+        assert_eq!(normalized_levenshtein("abc", "abc"), 1.0);
+        assert_eq!(normalized_levenshtein("abc", "xyz"), 0.0);
+        assert!((normalized_levenshtein("Hello world", "Hello world!") - 11.0 / 12.0).abs() < 1e-6);
+        assert!((normalized_levenshtein("你好世界", "你好") - 0.5).abs() < 1e-6);
+    }
+
+    /// 测试TMX日期格式
+    #[test]
+    fn test_tmx_date() {
+        assert_eq!(tmx_date(UNIX_EPOCH), "19700101T000000Z");
+        assert_eq!(
+            tmx_date(UNIX_EPOCH + Duration::from_secs(1_735_787_045)),
+            "20250102T030405Z"
+        );
+        assert_eq!(
+            tmx_date(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "20000229T000000Z"
+        );
+    }
+
+    /// 测试精确匹配不调用内部翻译器
+    #[tokio::test]
+    async fn test_exact_match_short_circuits() {
+        let mock = Arc::new(MockTranslator::new());
+        let mut memory = TranslationMemory::new();
+        memory.import_tmx(BASIC).unwrap();
+        let translator = TmTranslator::new(mock.clone(), "mock", memory);
+
+        let output = translator
+            .translate("Hello world", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "你好，世界");
+        assert_eq!(mock.calls(), 0);
+
+        let output = translator
+            .translate_vec(
+                &["Hello world".to_string(), "new text".to_string()],
+                Some(Language::English),
+                &Language::Chinese,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec!["你好，世界", "NEW TEXT"]);
+        assert_eq!(mock.requests(), vec![vec!["new text".to_string()]]);
+    }
+
+    /// 测试模糊匹配作为候选译文返回，新译文写回记忆库
+    #[tokio::test]
+    async fn test_fuzzy_alternatives_and_store() {
+        let mock = Arc::new(MockTranslator::new());
+        let mut memory = TranslationMemory::new();
+        memory.import_tmx(BASIC).unwrap();
+        let translator = TmTranslator::new(mock.clone(), "mock", memory).with_threshold(0.8);
+
+        let output = translator
+            .translate("Hello world!", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO WORLD!");
+        assert_eq!(output.detail.alternatives.len(), 1);
+        assert_eq!(output.detail.alternatives[0].text, "你好，世界");
+        assert_eq!(output.detail.alternatives[0].source.as_deref(), Some("tm"));
+
+        let memory = translator.memory();
+        let stored = memory
+            .get(Language::English, Language::Chinese, "Hello world!")
+            .unwrap();
+        assert_eq!(stored.target, "HELLO WORLD!");
+        assert_eq!(stored.engine.as_deref(), Some("mock"));
+        assert_eq!(stored.creation_date.len(), 16);
+
+        translator
+            .translate("Hello world!", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), 1);
+    }
+
+    /// 测试未指定源语言时直接调用内部翻译器
+    #[tokio::test]
+    async fn test_auto_detect_bypasses_memory() {
+        let mock = Arc::new(MockTranslator::new());
+        let mut memory = TranslationMemory::new();
+        memory.import_tmx(BASIC).unwrap();
+        let translator = TmTranslator::new(mock.clone(), "mock", memory);
+        let output = translator
+            .translate("Hello world", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO WORLD");
+        assert_eq!(mock.calls(), 1);
+        assert_eq!(translator.memory().len(), 3);
+    }
+
+    /// 模糊测试：任意内容的TMX导入都不会panic
+    #[test]
+    fn test_fuzz_import() {
+        let corpus = [
+            include_str!("../../tests/fixtures/tmx/basic.tmx"),
+            include_str!("../../tests/fixtures/tmx/legacy.tmx"),
+            "<a x='1' y=\"&lt;&#x4e2d;&#20013;&bogus;\"><!-- c --><![CDATA[<raw>]]><b/>t&amp;</a>",
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            let _ = TranslationMemory::new().import_tmx(&input);
+        }
+    }
+}
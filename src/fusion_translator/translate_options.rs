@@ -0,0 +1,434 @@
+use crate::fusion_translator::domain::TextDomain;
+use crate::fusion_translator::emoji::EmojiPolicy;
+use crate::fusion_translator::experiment::ExperimentArm;
+use crate::fusion_translator::literals::LiteralMode;
+use crate::fusion_translator::postprocess::PostprocessOptions;
+use crate::fusion_translator::preprocess::InputNormalization;
+use crate::fusion_translator::rate_limit::Priority;
+use crate::fusion_translator::runtime;
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::transport::HttpRequest;
+use crate::fusion_translator::untranslated::UntranslatedCheck;
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+/// 请求ID的HTTP头名称
+///
+/// 对于没有专门请求ID字段的翻译服务，通过该请求头传递关联ID
+pub(crate) const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// 单次翻译调用的选项
+///
+/// 用于控制一次`translate`/`translate_vec`调用的行为，
+/// 所有字段都有合理的默认值，未设置时翻译器保持原有行为
+#[derive(Debug, Clone)]
+pub struct TranslateOptions {
+    /// 本次调用的请求ID（关联ID）
+    ///
+    /// 会转发给支持请求ID的翻译服务（如彩云的`request_id`字段），
+    /// 其余翻译服务以`X-Request-Id`请求头传递，并在翻译结果中原样返回
+    pub request_id: Option<String>,
+    /// 未提供`request_id`时是否自动生成UUID v4作为请求ID
+    pub generate_request_id: bool,
+    /// 是否返回未经后处理的原始译文
+    ///
+    /// 开启后跳过`postprocess`中的所有清理步骤
+    pub raw_output: bool,
+    /// 译文后处理配置，`raw_output`开启时不生效
+    pub postprocess: PostprocessOptions,
+    /// 取消令牌
+    ///
+    /// 令牌被取消后，正在进行的请求会被中止，批量翻译不再发起新的请求，
+    /// 并返回`TranslatorError::Cancelled`
+    pub cancellation: Option<CancellationToken>,
+    /// 是否开启语言回退
+    ///
+    /// 开启后，翻译器不支持请求的语言时使用`Language::closest`找到的替代语言，
+    /// 并在`TranslationDetail::substitutions`中记录替换，而不是返回`UnknownLanguage`错误
+    pub language_fallback: bool,
+    /// 语言回退时是否允许在简体中文和繁体中文之间互相替换
+    pub allow_script_fallback: bool,
+    /// 未翻译检查模式
+    ///
+    /// 默认只在`TranslationDetail::possibly_untranslated`中标记，
+    /// 严格模式下疑似未翻译时返回`TranslatorError::UntranslatedOutput`
+    pub untranslated_check: UntranslatedCheck,
+    /// 文本超出翻译服务的长度限制时是否自动拆分
+    ///
+    /// 默认开启，按句子边界拆分为多次请求后拼接译文；
+    /// 关闭时超出限制直接返回`TranslatorError::RequestTooLong`
+    pub auto_split: bool,
+    /// 是否保留原文两端的空白
+    ///
+    /// 默认开启，发送给翻译服务前去掉每个文本两端的空白，并把原文的前导和末尾空白逐字节还原到译文；
+    /// 译文不是中日韩文字时，还会尽量还原原文中按列对齐的连续空格
+    pub preserve_boundary_whitespace: bool,
+    /// 发送给翻译服务前的原文规范化配置，None表示不规范化
+    ///
+    /// 默认关闭。开启后按`preprocess::normalize_input`清理不换行空格、软连字符、零宽字符等，
+    /// 后处理和空白还原都以规范化后的原文为准
+    pub normalize_input: Option<InputNormalization>,
+    /// 文本领域，None表示由`routing::RoutingTranslator`按`DomainClassifier`判断
+    ///
+    /// 只影响按领域选择引擎，不会发送给翻译服务
+    pub domain: Option<TextDomain>,
+    /// A/B实验的分组，None表示由`experiment::ExperimentTranslator`按文本哈希分组
+    ///
+    /// 用于排查问题时强制使用某一组，不会发送给翻译服务
+    pub experiment_arm: Option<ExperimentArm>,
+    /// 是否在错误上下文中记录原文
+    ///
+    /// 默认关闭，错误中只包含翻译服务、操作、语言对和文本数量；
+    /// 开启后`error_context::QueryContext`会记录截断后的前几个原文，便于排查，但可能泄露敏感内容
+    pub log_query_text: bool,
+    /// 等待限流令牌时的优先级，默认为`Priority::Batch`
+    ///
+    /// `Priority::Interactive`的请求在`rate_limit::RateLimiter`中优先于排队的批量请求获得令牌，
+    /// 不会发送给翻译服务
+    pub priority: Priority,
+    /// 数字、日期等字面量的保护模式，None表示由`literals::LiteralGuard`的配置决定
+    ///
+    /// 只影响`LiteralGuard`，不会发送给翻译服务
+    pub protect_literals: Option<LiteralMode>,
+    /// 表情符号的处理策略，None表示由`emoji::EmojiGuard`的配置决定
+    ///
+    /// 只影响`EmojiGuard`，不会发送给翻译服务
+    pub emoji_policy: Option<EmojiPolicy>,
+}
+
+impl Default for TranslateOptions {
+    fn default() -> Self {
+        Self {
+            request_id: None,
+            generate_request_id: false,
+            raw_output: false,
+            postprocess: PostprocessOptions::default(),
+            cancellation: None,
+            language_fallback: false,
+            allow_script_fallback: false,
+            untranslated_check: UntranslatedCheck::default(),
+            auto_split: true,
+            preserve_boundary_whitespace: true,
+            normalize_input: None,
+            domain: None,
+            experiment_arm: None,
+            log_query_text: false,
+            priority: Priority::default(),
+            protect_literals: None,
+            emoji_policy: None,
+        }
+    }
+}
+
+impl TranslateOptions {
+    /// 创建默认选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置请求ID
+    ///
+    /// # 参数
+    /// - `request_id`: 本次调用的请求ID
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// 设置是否自动生成请求ID
+    ///
+    /// # 参数
+    /// - `generate`: 未提供请求ID时是否自动生成
+    pub fn with_generate_request_id(mut self, generate: bool) -> Self {
+        self.generate_request_id = generate;
+        self
+    }
+
+    /// 设置是否返回原始译文
+    ///
+    /// # 参数
+    /// - `raw`: 为true时跳过译文后处理
+    pub fn with_raw_output(mut self, raw: bool) -> Self {
+        self.raw_output = raw;
+        self
+    }
+
+    /// 设置取消令牌
+    ///
+    /// # 参数
+    /// - `token`: 取消令牌
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// 设置是否开启语言回退
+    ///
+    /// # 参数
+    /// - `enabled`: 是否开启
+    pub fn with_language_fallback(mut self, enabled: bool) -> Self {
+        self.language_fallback = enabled;
+        self
+    }
+
+    /// 设置语言回退时是否允许更换书写系统
+    ///
+    /// # 参数
+    /// - `allowed`: 是否允许在简体中文和繁体中文之间互相替换
+    pub fn with_script_fallback(mut self, allowed: bool) -> Self {
+        self.allow_script_fallback = allowed;
+        self
+    }
+
+    /// 设置未翻译检查模式
+    ///
+    /// # 参数
+    /// - `check`: 检查模式
+    pub fn with_untranslated_check(mut self, check: UntranslatedCheck) -> Self {
+        self.untranslated_check = check;
+        self
+    }
+
+    /// 设置超出长度限制时是否自动拆分
+    ///
+    /// # 参数
+    /// - `enabled`: 是否开启
+    pub fn with_auto_split(mut self, enabled: bool) -> Self {
+        self.auto_split = enabled;
+        self
+    }
+
+    /// 设置是否保留原文两端的空白
+    ///
+    /// # 参数
+    /// - `enabled`: 是否开启
+    pub fn with_preserve_boundary_whitespace(mut self, enabled: bool) -> Self {
+        self.preserve_boundary_whitespace = enabled;
+        self
+    }
+
+    /// 设置原文规范化配置
+    ///
+    /// # 参数
+    /// - `normalization`: 规范化配置，通常使用`InputNormalization::default()`
+    pub fn with_input_normalization(mut self, normalization: InputNormalization) -> Self {
+        self.normalize_input = Some(normalization);
+        self
+    }
+
+    /// 指定文本领域，跳过按内容判断
+    ///
+    /// # 参数
+    /// - `domain`: 文本领域
+    pub fn with_domain(mut self, domain: TextDomain) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// 指定A/B实验的分组，跳过按文本哈希分组
+    ///
+    /// # 参数
+    /// - `arm`: 实验分组
+    pub fn with_experiment_arm(mut self, arm: ExperimentArm) -> Self {
+        self.experiment_arm = Some(arm);
+        self
+    }
+
+    /// 设置是否在错误上下文中记录原文
+    ///
+    /// # 参数
+    /// - `enabled`: 是否开启
+    pub fn with_log_query_text(mut self, enabled: bool) -> Self {
+        self.log_query_text = enabled;
+        self
+    }
+
+    /// 设置等待限流令牌时的优先级
+    ///
+    /// # 参数
+    /// - `priority`: 优先级
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 指定本次调用的字面量保护模式
+    ///
+    /// # 参数
+    /// - `mode`: 保护模式
+    pub fn with_protect_literals(mut self, mode: LiteralMode) -> Self {
+        self.protect_literals = Some(mode);
+        self
+    }
+
+    /// 指定本次调用的表情符号处理策略
+    ///
+    /// # 参数
+    /// - `policy`: 处理策略
+    pub fn with_emoji_policy(mut self, policy: EmojiPolicy) -> Self {
+        self.emoji_policy = Some(policy);
+        self
+    }
+
+    /// 判断本次调用是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// 在可取消的上下文中执行异步操作
+    ///
+    /// 令牌在操作完成前被取消时，丢弃操作（从而中止进行中的HTTP请求）并返回取消错误
+    ///
+    /// # 参数
+    /// - `completed`: 取消时报告的已完成条目数
+    /// - `fut`: 要执行的异步操作
+    ///
+    /// # 返回值
+    /// 操作的结果，或`TranslatorError::Cancelled`
+    pub async fn run_cancellable<T, F>(&self, completed: usize, fut: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        match &self.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(TranslatorError::Cancelled(completed).into()),
+                result = fut => result,
+            },
+            None => fut.await,
+        }
+    }
+
+    /// 解析本次调用实际使用的请求ID
+    ///
+    /// 优先使用显式设置的`request_id`，否则在开启`generate_request_id`时生成新的UUID v4
+    ///
+    /// 每次调用都会生成新的ID，组合翻译器应只解析一次，
+    /// 再通过`resolved`把结果传给内部翻译器，保证整条调用链使用同一个ID
+    ///
+    /// # 返回值
+    /// 请求ID，未设置且不自动生成时为None
+    pub fn resolve_request_id(&self) -> Option<String> {
+        match &self.request_id {
+            Some(id) => Some(id.clone()),
+            None if self.generate_request_id => Some(generate_request_id()),
+            None => None,
+        }
+    }
+
+    /// 返回已解析请求ID的选项副本
+    ///
+    /// 返回的选项中`request_id`已固定，传给内部翻译器时不会再次生成新ID
+    pub fn resolved(&self) -> Self {
+        Self {
+            request_id: self.resolve_request_id(),
+            ..self.clone()
+        }
+    }
+}
+
+/// 生成随机请求ID
+///
+/// # 返回值
+/// UUID v4格式的字符串
+pub fn generate_request_id() -> String {
+    uuid::Builder::from_random_bytes(runtime::random_bytes())
+        .into_uuid()
+        .to_string()
+}
+
+/// 为HTTP请求附加请求ID请求头
+///
+/// # 参数
+/// - `request`: HTTP请求
+/// - `request_id`: 请求ID，为None时不做修改
+///
+/// # 返回值
+/// 附加请求头后的请求
+pub(crate) fn with_request_id_header(
+    request: HttpRequest,
+    request_id: Option<&str>,
+) -> HttpRequest {
+    match request_id {
+        Some(id) => request.header(REQUEST_ID_HEADER, id),
+        None => request,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::translate_options::TranslateOptions;
+
+    /// 测试默认选项不产生请求ID
+    #[test]
+    fn test_default_has_no_request_id() {
+        let options = TranslateOptions::default();
+        assert_eq!(options.resolve_request_id(), None);
+        assert!(options.auto_split);
+    }
+
+    /// 测试显式请求ID优先于自动生成
+    #[test]
+    fn test_explicit_request_id() {
+        let options = TranslateOptions::new()
+            .with_request_id("trace-1")
+            .with_generate_request_id(true);
+        assert_eq!(options.resolve_request_id().as_deref(), Some("trace-1"));
+    }
+
+    /// 测试自动生成的请求ID为UUID v4
+    #[test]
+    fn test_generated_request_id() {
+        let options = TranslateOptions::new().with_generate_request_id(true);
+        let id = options.resolve_request_id().unwrap();
+        let uuid = uuid::Uuid::parse_str(&id).unwrap();
+        assert_eq!(uuid.get_version_num(), 4);
+        assert_ne!(options.resolve_request_id().unwrap(), id);
+    }
+
+    /// 测试取消令牌中止进行中的操作
+    #[tokio::test(start_paused = true)]
+    async fn test_run_cancellable() {
+        use crate::fusion_translator::translator_error::TranslatorError;
+        use std::time::Duration;
+        use tokio_util::sync::CancellationToken;
+
+        let token = CancellationToken::new();
+        let options = TranslateOptions::new().with_cancellation(token.clone());
+        assert_eq!(
+            options.run_cancellable(0, async { Ok(1) }).await.unwrap(),
+            1
+        );
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        let err = options
+            .run_cancellable(3, async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(3))
+        ));
+        assert!(options.is_cancelled());
+    }
+
+    /// 测试解析后的选项在多次使用时保持同一个请求ID
+    #[test]
+    fn test_resolved_is_stable() {
+        let options = TranslateOptions::new()
+            .with_generate_request_id(true)
+            .resolved();
+        let first = options.resolve_request_id();
+        assert!(first.is_some());
+        assert_eq!(options.resolve_request_id(), first);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::error_codes::{self, Locale};
+use std::time::Duration;
 
 /// 翻译模块错误类型
 ///
@@ -39,7 +41,7 @@ pub enum TranslatorError {
     /// - 第一个u32: 实际请求长度
     /// - 第二个u32: 最大允许长度
     #[error("Request was too long")]
-    RequestToLong(u32, u32),
+    RequestTooLong(u32, u32),
     /// 请求失败
     ///
     /// HTTP请求返回了错误的响应状态码
@@ -53,6 +55,350 @@ pub enum TranslatorError {
     /// 某些翻译API需要明确指定源语言，但调用时未提供
     #[error("Translator required a input language")]
     NoLanguage,
+    /// 翻译已取消
+    ///
+    /// 调用方通过`CancellationToken`取消了本次翻译
+    ///
+    /// # 参数
+    /// - usize: 取消前已完成翻译的条目数
+    #[error("Translation was cancelled")]
+    Cancelled(usize),
+    /// 批量翻译结果数量不一致
+    ///
+    /// 翻译服务返回的译文数量与请求的文本数量不同，无法按顺序一一对应
+    ///
+    /// # 参数
+    /// - 第一个usize: 请求的文本数量
+    /// - 第二个usize: 返回的译文数量
+    #[error("Translator returned a different number of results than requested")]
+    BatchSizeMismatch(usize, usize),
+    /// 译文疑似未翻译
+    ///
+    /// 严格模式下，翻译服务原样返回了包含文字的原文
+    #[error("Translator returned the source text unchanged")]
+    UntranslatedOutput,
+    /// TMX文档无效
+    ///
+    /// 导入的翻译记忆库文件不是格式正确的TMX文档
+    ///
+    /// # 参数
+    /// - String: 错误位置和原因
+    #[error("Invalid TMX document: {0}")]
+    InvalidTmx(String),
+    /// 检查点不匹配
+    ///
+    /// 续传时检查点文件记录的引擎、语言对或输入列表与本次批量翻译不同
+    ///
+    /// # 参数
+    /// - String: 不匹配的原因
+    #[error("Checkpoint does not match this batch: {0}")]
+    CheckpointMismatch(String),
+    /// 语言代码表冲突
+    ///
+    /// 注册的翻译器已存在，或同一语言在代码表中对应了不同的代码
+    ///
+    /// # 参数
+    /// - String: 冲突的原因
+    #[error("Language map conflict: {0}")]
+    LanguageMapConflict(String),
+    /// 文本编码无效
+    ///
+    /// 编码名称无法识别，或内容不是指定编码的有效文本
+    ///
+    /// # 参数
+    /// - String: 错误原因
+    #[error("Invalid encoding: {0}")]
+    InvalidEncoding(String),
+    /// JSON输入无效
+    ///
+    /// 流式翻译的输入不是格式正确的JSON数组，或字段路径无法解析
+    ///
+    /// # 参数
+    /// - String: 错误位置和原因
+    #[error("Invalid JSON input: {0}")]
+    InvalidJson(String),
+    /// 请求被限流
+    ///
+    /// 翻译服务因请求过于频繁或反滥用策略拒绝了请求，稍后重试或降低请求速率
+    ///
+    /// # 参数
+    /// - String: 翻译服务返回的状态码或返回码
+    #[error("Rate limited by the translation service: {0}")]
+    RateLimited(String),
+    /// 异步任务尚未完成
+    ///
+    /// 翻译服务返回了需要轮询的异步任务，但调用方关闭了轮询或等待超时
+    ///
+    /// # 参数
+    /// - String: 任务ID，可稍后自行查询结果
+    #[error("Translation job {0} is still pending")]
+    JobPending(String),
+    /// 证书无效
+    ///
+    /// 根证书文件无法读取或不包含有效的PEM证书
+    ///
+    /// # 参数
+    /// - String: 文件路径和错误原因
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(String),
+    /// 翻译请求不完整
+    ///
+    /// `TranslationRequest`缺少目标语言，或批量请求没有文本
+    ///
+    /// # 参数
+    /// - String: 缺少的内容
+    #[error("Invalid translation request: {0}")]
+    InvalidRequest(String),
+    /// 专业领域不支持该语言组合
+    ///
+    /// 彩云翻译的专业领域模型只支持部分语言组合，并且需要明确指定源语言
+    #[error("Domain {domain} does not support {trans_type}, supported: {}", supported.join(", "))]
+    UnsupportedDomain {
+        /// 专业领域
+        domain: String,
+        /// 请求的语言组合，如`en2ja`或`auto2zh`
+        trans_type: String,
+        /// 该领域支持的语言组合
+        supported: Vec<&'static str>,
+    },
+    /// 翻译器不支持预期的语言对
+    ///
+    /// 创建翻译器栈时按`supported_languages`检查，而不是等到翻译时才失败
+    #[error("{engine} cannot translate {} to {to:?}", from.map_or("auto".to_string(), |from| format!("{:?}", from)))]
+    UnsupportedPair {
+        /// 引擎名称，故障转移栈为按优先级以` > `连接的所有引擎
+        engine: String,
+        /// 源语言，None表示自动检测
+        from: Option<Language>,
+        /// 目标语言
+        to: Language,
+    },
+    /// 翻译器配置无效
+    ///
+    /// 没有可用的翻译器，或环境变量中的配置无法识别
+    ///
+    /// # 参数
+    /// - String: 错误原因
+    #[error("Invalid translator configuration: {0}")]
+    Configuration(String),
+    /// 原文包含NUL字符
+    ///
+    /// NUL通常意味着输入是二进制数据而不是文本，不会静默删除
+    #[error("Query {index} contains a NUL character at byte offset {offset}")]
+    NulCharacter {
+        /// 原文在本次请求中的序号，单个文本为0
+        index: usize,
+        /// NUL字符在原文中的字节偏移
+        offset: usize,
+    },
+    /// 流式响应出错
+    ///
+    /// 翻译服务在流式响应中发送了错误事件，或在结束标记之前断开
+    ///
+    /// # 参数
+    /// - String: 错误原因
+    #[error("Streaming response failed: {0}")]
+    StreamError(String),
+    /// 会话握手失败
+    ///
+    /// 需要先从网页获取会话参数的翻译器无法从页面中解析出参数，或刷新会话后仍被拒绝
+    ///
+    /// # 参数
+    /// - String: 失败原因
+    #[error("Session handshake failed: {0}")]
+    Handshake(String),
+    /// 响应结构无法识别
+    ///
+    /// 翻译服务返回的JSON能够解析，但既不是成功结果也不是错误信息
+    ///
+    /// # 参数
+    /// - String: 响应中缺少或矛盾的内容
+    #[error("Unexpected response from the translation service: {0}")]
+    UnexpectedResponse(String),
+    /// 免费额度已用完
+    ///
+    /// 翻译服务提示当前周期（如MyMemory的每日字符额度）的免费额度已用完，
+    /// 批量翻译收到提示后不再发送后续请求
+    #[error("Translation quota exhausted after {} items: {message}", completed.len())]
+    Quota {
+        /// 翻译服务返回的提示
+        message: String,
+        /// 额度用完之前已完成的译文，顺序与原文相同
+        completed: Vec<String>,
+    },
+    /// 翻译队列已关闭
+    ///
+    /// 调用`TranslationQueue::shutdown`之后提交的请求不会被处理
+    #[error("Translation queue is shut down")]
+    QueueClosed,
+    /// 超出延迟预算
+    ///
+    /// 设置了`LatencyBudget`的故障转移翻译器在预算（或某一阶段的时限）内没有得到结果
+    ///
+    /// # 参数
+    /// - Duration: 超出的时限
+    #[error("No translation within {0:?}")]
+    DeadlineExceeded(Duration),
+    /// 不在允许的时间窗口内
+    ///
+    /// `ScheduledTranslator`按拒绝策略处理窗口之外（或额度重置之前）的请求
+    ///
+    /// # 参数
+    /// - Option<Duration>: 距离下次允许请求的时间，不会再开放时为None
+    #[error("Outside the allowed translation window{}", .0.map(|wait| format!(", opens in {:?}", wait)).unwrap_or_default())]
+    OutsideWindow(Option<Duration>),
+    /// 受保护的字面量在译文中缺失或重复
+    ///
+    /// `literals::LiteralGuard`把数字、日期等替换为占位符后，译文中的占位符数量或编号与原文不一致，无法还原
+    #[error("Translation {index} has {found} placeholders, expected {expected}")]
+    PlaceholderMismatch {
+        /// 译文在本次请求中的序号，单个文本为0
+        index: usize,
+        /// 原文中替换的字面量数量
+        expected: usize,
+        /// 译文中找到的占位符数量
+        found: usize,
+    },
+}
+
+/// 错误分类
+///
+/// 把具体的错误归为少数几类，便于调用方按类别处理或展示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// 网络请求失败
+    Network,
+    /// 翻译服务返回错误或无效的结果
+    Api,
+    /// 无法识别或不支持的语言
+    Language,
+    /// 翻译服务未返回结果
+    NoResponse,
+    /// 请求文本过长
+    TooLong,
+    /// 翻译已取消
+    Cancelled,
+    /// 译文疑似未翻译
+    Untranslated,
+    /// 输入数据无效
+    InvalidInput,
+    /// 请求被限流
+    RateLimited,
+    /// 翻译服务因内容审核拒绝翻译
+    ContentRejected,
+    /// 不是`TranslatorError`的其他错误
+    Unknown,
+}
+
+impl ErrorKind {
+    /// 获取错误的分类
+    ///
+    /// 翻译器直接返回的`reqwest::Error`也归为网络错误
+    ///
+    /// # 参数
+    /// - `error`: 翻译器返回的错误
+    pub fn of(error: &anyhow::Error) -> Self {
+        if let Some(e) = error.downcast_ref::<TranslatorError>() {
+            return e.kind();
+        }
+        if error.is::<reqwest::Error>() {
+            return Self::Network;
+        }
+        Self::Unknown
+    }
+
+    /// 转换为字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Api => "api",
+            Self::Language => "language",
+            Self::NoResponse => "no_response",
+            Self::TooLong => "too_long",
+            Self::Cancelled => "cancelled",
+            Self::Untranslated => "untranslated",
+            Self::InvalidInput => "invalid_input",
+            Self::RateLimited => "rate_limited",
+            Self::ContentRejected => "content_rejected",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// 从`as_str`返回的字符串解析
+    ///
+    /// # 参数
+    /// - `name`: 分类名称，如`rate_limited`
+    ///
+    /// # 返回值
+    /// 对应的分类，无法识别时为None
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "network" => Self::Network,
+            "api" => Self::Api,
+            "language" => Self::Language,
+            "no_response" => Self::NoResponse,
+            "too_long" => Self::TooLong,
+            "cancelled" => Self::Cancelled,
+            "untranslated" => Self::Untranslated,
+            "invalid_input" => Self::InvalidInput,
+            "rate_limited" => Self::RateLimited,
+            "content_rejected" => Self::ContentRejected,
+            "unknown" => Self::Unknown,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TranslatorError {
+    /// 请求文本过长
+    ///
+    /// 已更名为`RequestTooLong`，此别名只能用于构造错误，匹配时请使用新名称
+    #[deprecated(note = "renamed to `TranslatorError::RequestTooLong`")]
+    #[allow(non_upper_case_globals)]
+    pub const RequestToLong: fn(u32, u32) -> Self = Self::RequestTooLong;
+
+    /// 获取错误的分类
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Reqwest(_) => ErrorKind::Network,
+            Self::ApiError(e) => e.kind(),
+            Self::RequestFailed(_)
+            | Self::BatchSizeMismatch(_, _)
+            | Self::StreamError(_)
+            | Self::Handshake(_)
+            | Self::UnexpectedResponse(_)
+            | Self::PlaceholderMismatch { .. } => ErrorKind::Api,
+            Self::UnknownLanguage(_)
+            | Self::CouldNotMapLanguage(_)
+            | Self::NoLanguage
+            | Self::UnsupportedDomain { .. }
+            | Self::UnsupportedPair { .. } => ErrorKind::Language,
+            Self::NoResponse | Self::JobPending(_) | Self::DeadlineExceeded(_) => {
+                ErrorKind::NoResponse
+            }
+            Self::RequestTooLong(_, _) => ErrorKind::TooLong,
+            Self::Cancelled(_) | Self::QueueClosed => ErrorKind::Cancelled,
+            Self::UntranslatedOutput => ErrorKind::Untranslated,
+            Self::InvalidTmx(_)
+            | Self::CheckpointMismatch(_)
+            | Self::LanguageMapConflict(_)
+            | Self::InvalidEncoding(_)
+            | Self::InvalidJson(_)
+            | Self::InvalidCertificate(_)
+            | Self::InvalidRequest(_)
+            | Self::Configuration(_)
+            | Self::NulCharacter { .. } => ErrorKind::InvalidInput,
+            Self::RateLimited(_) | Self::Quota { .. } | Self::OutsideWindow(_) => {
+                ErrorKind::RateLimited
+            }
+        }
+    }
 }
 
 /// API错误详细信息
@@ -69,6 +415,60 @@ pub enum ApiError {
     /// - `code`: 百度API返回的错误代码
     /// - `message`: 错误描述信息
     Baidu { code: String, message: String },
+    /// 有道翻译API错误
+    ///
+    /// # 字段
+    /// - `code`: 有道API返回的错误代码
+    Youdao { code: String },
+}
+
+impl ApiError {
+    /// 翻译服务名称，与`error_codes`中的错误代码表名称相同
+    pub fn vendor(&self) -> &'static str {
+        match self {
+            ApiError::Baidu { .. } => "baidu",
+            ApiError::Youdao { .. } => "youdao",
+        }
+    }
+
+    /// 翻译服务返回的错误代码
+    pub fn code(&self) -> &str {
+        match self {
+            ApiError::Baidu { code, .. } | ApiError::Youdao { code } => code,
+        }
+    }
+
+    /// 获取错误的分类
+    ///
+    /// 按错误代码表中的`category`分类，代码表中没有的代码归为`ErrorKind::Api`
+    pub fn kind(&self) -> ErrorKind {
+        error_codes::lookup(self.vendor(), self.code()).map_or(ErrorKind::Api, |info| info.category)
+    }
+
+    /// 是否值得重试
+    ///
+    /// 按错误代码表中的`retryable`判断，代码表中没有的代码不重试
+    pub fn is_retryable(&self) -> bool {
+        error_codes::lookup(self.vendor(), self.code()).is_some_and(|info| info.retryable)
+    }
+
+    /// 是否为内容审核拒绝
+    ///
+    /// 错误代码表中分类为`content_rejected`的代码，如百度翻译的20003
+    pub fn is_content_rejection(&self) -> bool {
+        self.kind() == ErrorKind::ContentRejected
+    }
+
+    /// 获取错误说明和解决方案
+    ///
+    /// # 参数
+    /// - `locale`: 说明使用的语言
+    ///
+    /// # 返回值
+    /// 错误代码表中的说明，代码表中没有的代码返回“unknown <vendor> error <code>”
+    pub fn advice(&self, locale: Locale) -> String {
+        error_codes::advice(self.vendor(), self.code(), locale)
+    }
 }
 
 impl std::fmt::Display for ApiError {
@@ -77,6 +477,14 @@ impl std::fmt::Display for ApiError {
             ApiError::Baidu { code, message } => {
                 write!(f, "Baidu API Error [{}]: {}", code, message)
             }
+            ApiError::Youdao { code } => {
+                write!(
+                    f,
+                    "Youdao API Error [{}]: {}",
+                    code,
+                    self.advice(Locale::Zh)
+                )
+            }
         }
     }
 }
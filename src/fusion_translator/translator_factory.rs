@@ -1,11 +1,29 @@
 use crate::fusion_translator::alibaba_translator::AlibabaTranslator;
-use crate::fusion_translator::async_translator::AsyncTranslator;
+use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+use crate::fusion_translator::auto::AutoConfig;
 use crate::fusion_translator::baidu_translator::BaiduTranslator;
+use crate::fusion_translator::bing_web_translator::BingWebTranslator;
+use crate::fusion_translator::cache::CachedTranslator;
+pub use crate::fusion_translator::caiyun_translator::CaiyunDomain;
 use crate::fusion_translator::caiyun_translator::CaiyunTranslator;
+use crate::fusion_translator::capability::capabilities;
+use crate::fusion_translator::config::{ConfigResolver, PartialConfig};
+use crate::fusion_translator::fallback::FallbackTranslator;
+use crate::fusion_translator::google_web_translator::GoogleWebTranslator;
 use crate::fusion_translator::mymemory_translator::MyMemoryTranslator;
+use crate::fusion_translator::rate_limit::{LimiterKey, RateLimitedTranslator};
+use crate::fusion_translator::retry::RetryTranslator;
+use crate::fusion_translator::reverso_translator::ReversoTranslator;
+use crate::fusion_translator::similarity::levenshtein;
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transmart_translator::TranSmartTranslator;
 use crate::fusion_translator::youdao_translator::YoudaoTranslator;
+use futures_util::future::join_all;
+use serde::Deserialize;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// 翻译器类型枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,24 +34,148 @@ pub enum TranslatorType {
     Alibaba,
     Caiyun,
     MyMemory,
+    TranSmart,
+    Reverso,
+    BingWeb,
+    GoogleWeb,
 }
 
+/// 翻译器名称和别名
+///
+/// `FromStr`按此表解析，命令行帮助也由此表生成；名称均为小写，解析时忽略首尾空白和大小写
+const ALIASES: &[(&str, TranslatorType)] = &[
+    ("baidu", TranslatorType::Baidu),
+    ("bd", TranslatorType::Baidu),
+    ("百度", TranslatorType::Baidu),
+    ("youdao", TranslatorType::Youdao),
+    ("yd", TranslatorType::Youdao),
+    ("有道", TranslatorType::Youdao),
+    ("alibaba", TranslatorType::Alibaba),
+    ("ali", TranslatorType::Alibaba),
+    ("阿里", TranslatorType::Alibaba),
+    ("caiyun", TranslatorType::Caiyun),
+    ("彩云", TranslatorType::Caiyun),
+    ("mymemory", TranslatorType::MyMemory),
+    ("my-memory", TranslatorType::MyMemory),
+    ("my_memory", TranslatorType::MyMemory),
+    ("my memory", TranslatorType::MyMemory),
+    ("transmart", TranslatorType::TranSmart),
+    ("tran-smart", TranslatorType::TranSmart),
+    ("tencent", TranslatorType::TranSmart),
+    ("腾讯", TranslatorType::TranSmart),
+    ("reverso", TranslatorType::Reverso),
+    ("bing_web", TranslatorType::BingWeb),
+    ("bingweb", TranslatorType::BingWeb),
+    ("bing-web", TranslatorType::BingWeb),
+    ("bing", TranslatorType::BingWeb),
+    ("必应", TranslatorType::BingWeb),
+    ("google_web", TranslatorType::GoogleWeb),
+    ("googleweb", TranslatorType::GoogleWeb),
+    ("google-web", TranslatorType::GoogleWeb),
+    ("google", TranslatorType::GoogleWeb),
+    ("谷歌", TranslatorType::GoogleWeb),
+];
+
+/// 翻译器名称无法识别
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTranslatorType {
+    /// 输入的名称
+    pub input: String,
+    /// 编辑距离最近的已知名称，没有足够接近的名称时为None
+    pub suggestion: Option<&'static str>,
+}
+
+impl UnknownTranslatorType {
+    /// 创建错误并查找最接近的名称
+    ///
+    /// 编辑距离不超过输入长度的三分之一（至少为1）的名称才作为建议
+    ///
+    /// # 参数
+    /// - `input`: 输入的名称
+    fn new(input: &str) -> Self {
+        let name = input.trim().to_lowercase();
+        let limit = (name.chars().count() / 3).max(1);
+        let suggestion = ALIASES
+            .iter()
+            .map(|(alias, _)| (levenshtein(&name, alias), *alias))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, alias)| alias);
+        Self {
+            input: input.to_string(),
+            suggestion,
+        }
+    }
+}
+
+impl std::fmt::Display for UnknownTranslatorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown translator {:?}", self.input)?;
+        if let Some(suggestion) = self.suggestion {
+            write!(f, ", did you mean {:?}?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownTranslatorType {}
+
 impl std::str::FromStr for TranslatorType {
-    type Err = ();
+    type Err = UnknownTranslatorType;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "baidu" => Ok(Self::Baidu),
-            "youdao" => Ok(Self::Youdao),
-            "alibaba" | "ali" => Ok(Self::Alibaba),
-            "caiyun" | "彩云" => Ok(Self::Caiyun),
-            "mymemory" | "my-memory" | "my memory" => Ok(Self::MyMemory),
-            _ => Err(()),
-        }
+        let name = s.trim().to_lowercase();
+        ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, translator_type)| *translator_type)
+            .ok_or_else(|| UnknownTranslatorType::new(s))
+    }
+}
+
+impl TryFrom<&str> for TranslatorType {
+    type Error = UnknownTranslatorType;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl std::fmt::Display for TranslatorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 impl TranslatorType {
+    /// 所有翻译器类型
+    pub const ALL: [TranslatorType; 9] = [
+        Self::Baidu,
+        Self::Youdao,
+        Self::Alibaba,
+        Self::Caiyun,
+        Self::MyMemory,
+        Self::TranSmart,
+        Self::Reverso,
+        Self::BingWeb,
+        Self::GoogleWeb,
+    ];
+
+    /// 所有翻译器类型，顺序同`ALL`
+    pub fn all() -> &'static [TranslatorType] {
+        &Self::ALL
+    }
+
+    /// 可以解析为此类型的名称和别名
+    ///
+    /// 第一个名称与`as_str`相同
+    pub fn aliases(&self) -> impl Iterator<Item = &'static str> + '_ {
+        ALIASES
+            .iter()
+            .filter(move |(_, translator_type)| translator_type == self)
+            .map(|(alias, _)| *alias)
+    }
+
     /// 从字符串解析翻译器类型
     pub fn parse(s: &str) -> Option<Self> {
         Self::from_str(s).ok()
@@ -48,18 +190,202 @@ impl TranslatorType {
             Self::Alibaba => "alibaba",
             Self::Caiyun => "caiyun",
             Self::MyMemory => "mymemory",
+            Self::TranSmart => "transmart",
+            Self::Reverso => "reverso",
+            Self::BingWeb => "bing_web",
+            Self::GoogleWeb => "google_web",
+        }
+    }
+
+    /// 翻译器使用的语言代码表在`language_map`中的名称
+    ///
+    /// # 返回值
+    /// 代码表名称，翻译器使用自带的代码表时为None
+    pub fn language_table(&self) -> Option<&'static str> {
+        match self {
+            Self::Baidu => Some("baidu"),
+            Self::Youdao => Some("youdao"),
+            Self::Alibaba => Some("mymemory-short"),
+            Self::Caiyun => Some("caiyun"),
+            Self::MyMemory => Some("mymemory"),
+            Self::GoogleWeb => Some("google"),
+            Self::TranSmart | Self::Reverso | Self::BingWeb => None,
         }
     }
 }
 
 /// 翻译器配置
-#[derive(Debug, Clone)]
+///
+/// 可以从配置文件反序列化，`engine`字段指定翻译器，如`engine = "baidu"`，
+/// 其余字段为对应翻译器的凭据
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "engine", rename_all = "snake_case")]
 pub enum TranslatorConfig {
-    Baidu { app_id: String, key: String },
-    Youdao { app_key: String, app_secret: String },
-    Alibaba { token: String },
-    Caiyun { token: String, request_id: String },
+    Baidu {
+        app_id: String,
+        key: String,
+    },
+    /// 百度翻译，额外请求单词的发音音频地址（`tts`）和词典释义（`dict`）
+    BaiduExtended {
+        app_id: String,
+        key: String,
+        tts: bool,
+        dict: bool,
+    },
+    Youdao {
+        app_key: String,
+        app_secret: String,
+    },
+    Alibaba {
+        token: String,
+    },
+    Caiyun {
+        token: String,
+        /// 未配置时与`create_from_env`一样使用`demo`
+        #[serde(default = "default_caiyun_request_id")]
+        request_id: String,
+    },
+    /// 彩云翻译的专业领域模型（`domain`），只支持中英互译
+    CaiyunProfessional {
+        token: String,
+        #[serde(default = "default_caiyun_request_id")]
+        request_id: String,
+        domain: CaiyunDomain,
+    },
+    #[serde(rename = "mymemory")]
     MyMemory,
+    /// MyMemory翻译，按`mt`和`onlyprivate`参数筛选翻译记忆库的结果
+    #[serde(rename = "mymemory_filtered")]
+    MyMemoryFiltered {
+        /// 是否返回机器翻译结果，关闭后只返回人工译文
+        #[serde(default = "default_machine_translation")]
+        machine_translation: bool,
+        /// 是否只返回私有翻译记忆
+        #[serde(default)]
+        only_private: bool,
+    },
+    /// 腾讯TranSmart网页接口，无需凭据
+    #[serde(rename = "transmart")]
+    TranSmart,
+    /// Reverso Context网页接口，无需凭据
+    Reverso,
+    /// Bing翻译网页接口，无需凭据
+    BingWeb,
+    /// Google翻译网页接口，无需凭据
+    GoogleWeb,
+}
+
+/// 配置文件未指定MyMemory的`machine_translation`时的默认值，与服务端默认相同
+fn default_machine_translation() -> bool {
+    true
+}
+
+/// 配置文件未指定彩云`request_id`时的默认值
+fn default_caiyun_request_id() -> String {
+    "demo".to_string()
+}
+
+impl TranslatorConfig {
+    /// 配置对应的翻译器类型
+    pub fn translator_type(&self) -> TranslatorType {
+        match self {
+            Self::Baidu { .. } | Self::BaiduExtended { .. } => TranslatorType::Baidu,
+            Self::Youdao { .. } => TranslatorType::Youdao,
+            Self::Alibaba { .. } => TranslatorType::Alibaba,
+            Self::Caiyun { .. } | Self::CaiyunProfessional { .. } => TranslatorType::Caiyun,
+            Self::MyMemory | Self::MyMemoryFiltered { .. } => TranslatorType::MyMemory,
+            Self::TranSmart => TranslatorType::TranSmart,
+            Self::Reverso => TranslatorType::Reverso,
+            Self::BingWeb => TranslatorType::BingWeb,
+            Self::GoogleWeb => TranslatorType::GoogleWeb,
+        }
+    }
+
+    /// 限流器的共享键
+    ///
+    /// 由翻译服务名称和标识账号的凭据生成，同一账号的不同配置共享同一个键
+    pub fn limiter_key(&self) -> LimiterKey {
+        match self {
+            Self::Baidu { app_id, .. } | Self::BaiduExtended { app_id, .. } => {
+                LimiterKey::new("baidu", &[app_id])
+            }
+            Self::Youdao { app_key, .. } => LimiterKey::new("youdao", &[app_key]),
+            Self::Alibaba { token } => LimiterKey::new("alibaba", &[token]),
+            Self::Caiyun { token, .. } | Self::CaiyunProfessional { token, .. } => {
+                LimiterKey::new("caiyun", &[token])
+            }
+            Self::MyMemory | Self::MyMemoryFiltered { .. } => LimiterKey::new("mymemory", &[]),
+            Self::TranSmart => LimiterKey::new("transmart", &[]),
+            Self::Reverso => LimiterKey::new("reverso", &[]),
+            Self::BingWeb => LimiterKey::new("bing_web", &[]),
+            Self::GoogleWeb => LimiterKey::new("google_web", &[]),
+        }
+    }
+}
+
+/// 翻译器栈配置
+///
+/// 描述一个翻译器及其包装层，可以从配置文件反序列化。例如TOML：
+///
+/// ```toml
+/// engine = "baidu"
+/// app_id = "..."
+/// key = "..."
+/// timeout_ms = 15000
+/// retries = 3
+/// rate_limit_qps = 1
+/// cache = { capacity = 10000, ttl_secs = 86400 }
+/// ```
+///
+/// `TranslatorFactory::create_stack`按固定顺序组装：重试 → 限流 → 缓存 → 翻译器，
+/// 即重试在最外层，每次尝试都经过限流；缓存紧贴翻译器，命中时同样占用一个令牌
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranslatorStackConfig {
+    /// 翻译器及其凭据
+    #[serde(flatten)]
+    pub backend: TranslatorConfig,
+    /// 单个HTTP请求的超时时间（毫秒），未配置时不限
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 暂时性错误最多重试的次数，为0时不添加重试层
+    #[serde(default)]
+    pub retries: u32,
+    /// 第一次重试前的等待时间（毫秒），未配置时使用`retry::DEFAULT_RETRY_BACKOFF`
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
+    /// 每秒允许的请求数，未配置时不添加限流层
+    #[serde(default)]
+    pub rate_limit_qps: Option<f64>,
+    /// 缓存配置，未配置时不添加缓存层
+    #[serde(default)]
+    pub cache: Option<CacheConfig>,
+    /// 覆盖翻译器默认的User-Agent
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// 缓存层配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct CacheConfig {
+    /// 最多保存的条目数
+    pub capacity: usize,
+    /// 条目有效期（秒），未配置时不过期
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+impl TranslatorStackConfig {
+    /// 按配置生成创建翻译器使用的选项
+    pub fn translator_options(&self) -> TranslatorOptions {
+        let mut options = TranslatorOptions::default();
+        if let Some(timeout_ms) = self.timeout_ms {
+            options = options.with_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(user_agent) = &self.user_agent {
+            options = options.with_user_agent(user_agent);
+        }
+        options
+    }
 }
 
 /// 翻译器工厂
@@ -69,20 +395,225 @@ impl TranslatorFactory {
     /// 根据类型和配置创建翻译器实例
     #[allow(dead_code)]
     pub fn create(config: TranslatorConfig) -> Arc<dyn AsyncTranslator> {
+        Self::create_with_options(config, &TranslatorOptions::default())
+    }
+
+    /// 根据配置和翻译器选项创建翻译器实例
+    ///
+    /// # 参数
+    /// - `config`: 翻译器配置
+    /// - `options`: 翻译器选项，用于覆盖User-Agent或添加额外的请求头
+    pub fn create_with_options(
+        config: TranslatorConfig,
+        options: &TranslatorOptions,
+    ) -> Arc<dyn AsyncTranslator> {
         match config {
             TranslatorConfig::Baidu { app_id, key } => {
-                Arc::new(BaiduTranslator::new(&app_id, &key))
+                Arc::new(BaiduTranslator::new(&app_id, &key).with_translator_options(options))
             }
+            TranslatorConfig::BaiduExtended {
+                app_id,
+                key,
+                tts,
+                dict,
+            } => Arc::new(
+                BaiduTranslator::new(&app_id, &key)
+                    .with_tts(tts)
+                    .with_dict(dict)
+                    .with_translator_options(options),
+            ),
             TranslatorConfig::Youdao {
                 app_key,
                 app_secret,
-            } => Arc::new(YoudaoTranslator::new(&app_key, &app_secret)),
-            TranslatorConfig::Alibaba { .. } => Arc::new(AlibabaTranslator::new()),
-            TranslatorConfig::Caiyun { token, request_id } => {
-                Arc::new(CaiyunTranslator::new(&token, &request_id))
+            } => Arc::new(
+                YoudaoTranslator::new(&app_key, &app_secret).with_translator_options(options),
+            ),
+            TranslatorConfig::Alibaba { .. } => {
+                Arc::new(AlibabaTranslator::new().with_translator_options(options))
+            }
+            TranslatorConfig::Caiyun { token, request_id } => Arc::new(
+                CaiyunTranslator::new(&token, &request_id).with_translator_options(options),
+            ),
+            TranslatorConfig::CaiyunProfessional {
+                token,
+                request_id,
+                domain,
+            } => Arc::new(
+                CaiyunTranslator::new(&token, &request_id)
+                    .with_domain(domain)
+                    .with_translator_options(options),
+            ),
+            TranslatorConfig::MyMemory => {
+                Arc::new(MyMemoryTranslator::new().with_translator_options(options))
+            }
+            TranslatorConfig::MyMemoryFiltered {
+                machine_translation,
+                only_private,
+            } => Arc::new(
+                MyMemoryTranslator::new()
+                    .with_machine_translation(machine_translation)
+                    .with_only_private(only_private)
+                    .with_translator_options(options),
+            ),
+            TranslatorConfig::TranSmart => {
+                Arc::new(TranSmartTranslator::new().with_translator_options(options))
+            }
+            TranslatorConfig::Reverso => {
+                Arc::new(ReversoTranslator::new().with_translator_options(options))
+            }
+            TranslatorConfig::BingWeb => {
+                Arc::new(BingWebTranslator::new().with_translator_options(options))
+            }
+            TranslatorConfig::GoogleWeb => {
+                Arc::new(GoogleWebTranslator::new().with_translator_options(options))
+            }
+        }
+    }
+
+    /// 创建限流的翻译器实例
+    ///
+    /// 使用相同服务和凭据创建的所有实例共享同一个令牌桶，合计请求速率不超过`qps`
+    ///
+    /// # 参数
+    /// - `config`: 翻译器配置
+    /// - `qps`: 每秒允许的请求数，必须大于0；共享的令牌桶已存在时沿用原有值
+    pub fn create_rate_limited(config: TranslatorConfig, qps: f64) -> Arc<dyn AsyncTranslator> {
+        let key = config.limiter_key();
+        Arc::new(RateLimitedTranslator::shared(
+            Self::create(config),
+            key,
+            qps,
+        ))
+    }
+
+    /// 按栈配置创建翻译器及其包装层
+    ///
+    /// 组装顺序见`TranslatorStackConfig`；限流层按`TranslatorConfig::limiter_key`
+    /// 与同一账号的其他实例共享令牌桶
+    ///
+    /// # 参数
+    /// - `config`: 栈配置
+    pub fn create_stack(config: TranslatorStackConfig) -> Arc<dyn AsyncTranslator> {
+        let backend =
+            Self::create_with_options(config.backend.clone(), &config.translator_options());
+        Self::wrap_stack(backend, &config)
+    }
+
+    /// 按栈配置创建翻译器，并检查翻译器能否翻译预期的语言对
+    ///
+    /// 适用于语言对固定的场景（如总是英译日），配置错误时在创建时报错，
+    /// 而不是在批量翻译中途失败。只检查翻译器本身，重试、限流和缓存层不影响支持的语言
+    ///
+    /// # 参数
+    /// - `config`: 栈配置
+    /// - `expected_pairs`: （源语言，目标语言）列表，源语言None表示自动检测
+    ///
+    /// # 返回值
+    /// 翻译器无法翻译任一语言对时返回`TranslatorError::UnsupportedPair`
+    pub fn create_stack_for_pairs(
+        config: TranslatorStackConfig,
+        expected_pairs: &[(Option<Language>, Language)],
+    ) -> Result<Arc<dyn AsyncTranslator>, TranslatorError> {
+        let backend =
+            Self::create_with_options(config.backend.clone(), &config.translator_options());
+        Self::wrap_stack_for_pairs(backend, &config, expected_pairs)
+    }
+
+    /// 检查已有的翻译器能否翻译预期的语言对，再按栈配置添加包装层
+    ///
+    /// # 参数
+    /// - `inner`: 最内层的翻译器
+    /// - `config`: 栈配置，翻译器类型用作错误中的引擎名称
+    /// - `expected_pairs`: （源语言，目标语言）列表，源语言None表示自动检测
+    ///
+    /// # 返回值
+    /// 翻译器无法翻译任一语言对时返回`TranslatorError::UnsupportedPair`
+    pub fn wrap_stack_for_pairs(
+        inner: Arc<dyn AsyncTranslator>,
+        config: &TranslatorStackConfig,
+        expected_pairs: &[(Option<Language>, Language)],
+    ) -> Result<Arc<dyn AsyncTranslator>, TranslatorError> {
+        let name = config.backend.translator_type().as_str().to_string();
+        capabilities(&[(name, inner.clone())]).check_pairs(expected_pairs)?;
+        Ok(Self::wrap_stack(inner, config))
+    }
+
+    /// 同时预热一组翻译器
+    ///
+    /// 在应用启动时对创建好的翻译器调用，让每个翻译服务的第一次请求不必等待连接建立，
+    /// 见`AsyncTranslator::warm_up`。预热失败只记录日志，不会返回错误
+    ///
+    /// # 参数
+    /// - `engines`: （名称，翻译器）
+    pub async fn warm_all(engines: &[(String, Arc<dyn AsyncTranslator>)]) {
+        join_all(engines.iter().map(|(name, translator)| async move {
+            translator.warm_up().await;
+            tracing::debug!(engine = %name, "warm-up finished");
+        }))
+        .await;
+    }
+
+    /// 按优先级创建故障转移翻译器，并检查每个预期的语言对都至少有一个翻译器可以翻译
+    ///
+    /// 部分翻译器无法翻译的语言对只以`tracing`的warn级别记录，翻译时由故障转移换用其他翻译器
+    ///
+    /// # 参数
+    /// - `configs`: 按优先级从高到低排列的栈配置
+    /// - `expected_pairs`: （源语言，目标语言）列表，源语言None表示自动检测
+    ///
+    /// # 返回值
+    /// 某个语言对没有任何翻译器可以翻译时返回`TranslatorError::UnsupportedPair`
+    pub fn create_fallback_for_pairs(
+        configs: Vec<TranslatorStackConfig>,
+        expected_pairs: &[(Option<Language>, Language)],
+    ) -> Result<Arc<dyn AsyncTranslator>, TranslatorError> {
+        let engines = configs
+            .into_iter()
+            .map(|config| {
+                let name = config.backend.translator_type().as_str().to_string();
+                (name, Self::create_stack(config))
+            })
+            .collect();
+        let fallback = FallbackTranslator::new(engines);
+        fallback.check_pairs(expected_pairs)?;
+        Ok(Arc::new(fallback))
+    }
+
+    /// 按栈配置为已有的翻译器添加包装层
+    ///
+    /// 忽略配置中的翻译器和HTTP选项，只使用重试、限流和缓存配置，
+    /// 可用于自定义翻译器或测试中的`MockTranslator`
+    ///
+    /// # 参数
+    /// - `inner`: 最内层的翻译器
+    /// - `config`: 栈配置
+    ///
+    #[doc = include_str!("../../doc/examples/stack.md")]
+    pub fn wrap_stack(
+        inner: Arc<dyn AsyncTranslator>,
+        config: &TranslatorStackConfig,
+    ) -> Arc<dyn AsyncTranslator> {
+        let mut translator = inner;
+        if let Some(cache) = config.cache {
+            let mut cached = CachedTranslator::new(translator, cache.capacity)
+                .with_backend_name(config.backend.translator_type().as_str());
+            if let Some(ttl_secs) = cache.ttl_secs {
+                cached = cached.with_ttl(Duration::from_secs(ttl_secs));
+            }
+            translator = Arc::new(cached);
+        }
+        if let Some(qps) = config.rate_limit_qps {
+            let key = config.backend.limiter_key();
+            translator = Arc::new(RateLimitedTranslator::shared(translator, key, qps));
+        }
+        if config.retries > 0 {
+            let mut retry = RetryTranslator::new(translator, config.retries);
+            if let Some(backoff_ms) = config.retry_backoff_ms {
+                retry = retry.with_backoff(Duration::from_millis(backoff_ms));
             }
-            TranslatorConfig::MyMemory => Arc::new(MyMemoryTranslator::new()),
+            translator = Arc::new(retry);
         }
+        translator
     }
 
     /// 根据类型字符串和配置创建翻译器实例
@@ -98,44 +629,49 @@ impl TranslatorFactory {
             TranslatorType::Alibaba => Arc::new(AlibabaTranslator::new()),
             TranslatorType::Caiyun => Arc::new(CaiyunTranslator::new(app_id, secret)),
             TranslatorType::MyMemory => Arc::new(MyMemoryTranslator::new()),
+            TranslatorType::TranSmart => Arc::new(TranSmartTranslator::new()),
+            TranslatorType::Reverso => Arc::new(ReversoTranslator::new()),
+            TranslatorType::BingWeb => Arc::new(BingWebTranslator::new()),
+            TranslatorType::GoogleWeb => Arc::new(GoogleWebTranslator::new()),
         }
     }
 
+    /// 按环境变量自动选择翻译器
+    ///
+    /// 使用`AutoConfig`的默认配置：按有道 > 百度 > 彩云的顺序（可通过`FUSION_TRANSLATOR_PRIORITY`覆盖）
+    /// 选择已配置凭据的翻译器，各自添加重试和限流后按顺序故障转移，最外层加进程内缓存。
+    /// 需要使用无需凭据的翻译器时改用`AutoConfig::new().with_keyless(true).build()`
+    ///
+    /// # 返回值
+    /// 组装好的翻译器；没有配置任何凭据时返回`TranslatorError::Configuration`
+    pub fn auto() -> Result<Arc<dyn AsyncTranslator>, TranslatorError> {
+        AutoConfig::new().build()
+    }
+
     /// 从环境变量创建翻译器（便捷方法）
+    ///
+    /// 通过`ConfigResolver`解析配置：`translator_type`作为显式参数，凭据、超时和重试等其余字段
+    /// 依次取自环境变量、`FUSION_TRANSLATOR_CONFIG`指定的配置文件和默认值
+    ///
+    /// # 返回值
+    /// 配置有误时返回列出所有问题的错误信息
     pub fn create_from_env(
         translator_type: TranslatorType,
     ) -> Result<Arc<dyn AsyncTranslator>, String> {
-        match translator_type {
-            TranslatorType::Baidu => {
-                let app_id = std::env::var("BAIDU_APP_ID")
-                    .map_err(|_| "BAIDU_APP_ID environment variable not set")?;
-                let key = std::env::var("BAIDU_KEY")
-                    .map_err(|_| "BAIDU_KEY environment variable not set")?;
-                Ok(Arc::new(BaiduTranslator::new(&app_id, &key)))
-            }
-            TranslatorType::Youdao => {
-                let app_key = std::env::var("YOUDAO_APP_KEY")
-                    .map_err(|_| "YOUDAO_APP_KEY environment variable not set")?;
-                let app_secret = std::env::var("YOUDAO_APP_SECRET")
-                    .map_err(|_| "YOUDAO_APP_SECRET environment variable not set")?;
-                Ok(Arc::new(YoudaoTranslator::new(&app_key, &app_secret)))
-            }
-            TranslatorType::Alibaba => Ok(Arc::new(AlibabaTranslator::new())),
-            TranslatorType::Caiyun => {
-                let token = std::env::var("CAIYUN_TOKEN")
-                    .map_err(|_| "CAIYUN_TOKEN environment variable not set")?;
-                let request_id =
-                    std::env::var("CAIYUN_REQUEST_ID").unwrap_or_else(|_| "demo".to_string());
-                Ok(Arc::new(CaiyunTranslator::new(&token, &request_id)))
-            }
-            TranslatorType::MyMemory => Ok(Arc::new(MyMemoryTranslator::new())),
-        }
+        ConfigResolver::new()
+            .with_explicit(PartialConfig::new().with_engine(translator_type))
+            .resolve()
+            .map(|resolved| resolved.build())
+            .map_err(|e| e.to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::mock_translator::MockTranslator;
+    use std::collections::{BTreeMap, HashMap};
 
     #[test]
     fn test_translator_type_parse() {
@@ -160,6 +696,19 @@ mod tests {
             TranslatorType::parse("mymemory"),
             Some(TranslatorType::MyMemory)
         );
+        assert_eq!(
+            TranslatorType::parse("transmart"),
+            Some(TranslatorType::TranSmart)
+        );
+        assert_eq!(
+            TranslatorType::parse("Reverso"),
+            Some(TranslatorType::Reverso)
+        );
+        assert_eq!(TranslatorType::parse("bing"), Some(TranslatorType::BingWeb));
+        assert_eq!(
+            TranslatorType::parse("google"),
+            Some(TranslatorType::GoogleWeb)
+        );
         assert_eq!(TranslatorType::parse("unknown"), None);
     }
 
@@ -186,7 +735,85 @@ mod tests {
             TranslatorType::from_str("mymemory"),
             Ok(TranslatorType::MyMemory)
         );
-        assert_eq!(TranslatorType::from_str("unknown"), Err(()));
+        assert_eq!(
+            TranslatorType::from_str("unknown"),
+            Err(UnknownTranslatorType {
+                input: "unknown".to_string(),
+                suggestion: None,
+            })
+        );
+        assert_eq!(
+            TranslatorType::from_str(" Baidu\n"),
+            Ok(TranslatorType::Baidu)
+        );
+        assert_eq!(
+            TranslatorType::try_from("My_Memory"),
+            Ok(TranslatorType::MyMemory)
+        );
+    }
+
+    /// 测试别名表中的每个名称都能解析，并且每个类型的第一个名称与`as_str`相同
+    #[test]
+    fn test_translator_type_aliases() {
+        for (alias, translator_type) in ALIASES {
+            assert_eq!(
+                alias.parse::<TranslatorType>().as_ref(),
+                Ok(translator_type)
+            );
+            assert_eq!(
+                alias.to_uppercase().parse::<TranslatorType>().as_ref(),
+                Ok(translator_type)
+            );
+            assert_eq!(
+                format!("  {}\t", alias).parse::<TranslatorType>().as_ref(),
+                Ok(translator_type)
+            );
+        }
+        for translator_type in TranslatorType::all() {
+            assert_eq!(
+                translator_type.aliases().next(),
+                Some(translator_type.as_str())
+            );
+        }
+        assert_eq!(
+            TranslatorType::Baidu.aliases().collect::<Vec<_>>(),
+            ["baidu", "bd", "百度"]
+        );
+        assert_eq!(TranslatorType::all(), TranslatorType::ALL);
+    }
+
+    /// 测试无法识别的名称给出编辑距离最近的建议
+    #[test]
+    fn test_translator_type_suggestion() {
+        let suggestion = |name: &str| TranslatorType::try_from(name).unwrap_err().suggestion;
+        assert_eq!(suggestion("baidoo"), Some("baidu"));
+        assert_eq!(suggestion("Goggle"), Some("google"));
+        assert_eq!(suggestion("youdoa"), Some("youdao"));
+        assert_eq!(suggestion("mymemroy"), Some("mymemory"));
+        assert_eq!(suggestion("bing web"), Some("bing_web"));
+        assert_eq!(suggestion("deepl"), None);
+        assert_eq!(suggestion("xyz"), None);
+        assert_eq!(suggestion(""), None);
+
+        let err = TranslatorType::try_from("caiyn").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown translator \"caiyn\", did you mean \"caiyun\"?"
+        );
+        assert_eq!(
+            TranslatorType::try_from("deepl").unwrap_err().to_string(),
+            "unknown translator \"deepl\""
+        );
+    }
+
+    /// 测试`Display`输出可以解析回相同的类型
+    #[test]
+    fn test_translator_type_display_roundtrip() {
+        for translator_type in TranslatorType::all() {
+            let name = translator_type.to_string();
+            assert_eq!(name, translator_type.as_str());
+            assert_eq!(name.parse::<TranslatorType>(), Ok(*translator_type));
+        }
     }
 
     #[test]
@@ -196,6 +823,10 @@ mod tests {
         assert_eq!(TranslatorType::Alibaba.as_str(), "alibaba");
         assert_eq!(TranslatorType::Caiyun.as_str(), "caiyun");
         assert_eq!(TranslatorType::MyMemory.as_str(), "mymemory");
+        assert_eq!(TranslatorType::TranSmart.as_str(), "transmart");
+        assert_eq!(TranslatorType::Reverso.as_str(), "reverso");
+        assert_eq!(TranslatorType::BingWeb.as_str(), "bing_web");
+        assert_eq!(TranslatorType::GoogleWeb.as_str(), "google_web");
     }
 
     #[tokio::test]
@@ -269,5 +900,225 @@ mod tests {
 
         let translator = TranslatorFactory::create_from_type(TranslatorType::MyMemory, "", "");
         assert!(!translator.local());
+
+        let translator = TranslatorFactory::create_from_type(TranslatorType::TranSmart, "", "");
+        assert!(!translator.local());
+
+        let translator = TranslatorFactory::create_from_type(TranslatorType::Reverso, "", "");
+        assert!(!translator.local());
+
+        let translator = TranslatorFactory::create_from_type(TranslatorType::BingWeb, "", "");
+        assert!(!translator.local());
+
+        let translator = TranslatorFactory::create_from_type(TranslatorType::GoogleWeb, "", "");
+        assert!(!translator.local());
+    }
+
+    /// 测试反序列化彩云专业领域配置
+    #[test]
+    fn test_deserialize_caiyun_professional() {
+        let config: TranslatorConfig = serde_json::from_str(
+            r#"{"engine": "caiyun_professional", "token": "t", "domain": "medicine"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &config,
+            TranslatorConfig::CaiyunProfessional { request_id, domain: CaiyunDomain::Medicine, .. }
+                if request_id == "demo"
+        ));
+        assert_eq!(
+            config.limiter_key(),
+            TranslatorConfig::Caiyun {
+                token: "t".to_string(),
+                request_id: "other".to_string(),
+            }
+            .limiter_key()
+        );
+        assert!(!TranslatorFactory::create(config).local());
+    }
+
+    /// 读取栈配置样例
+    fn stack_fixture() -> HashMap<String, TranslatorStackConfig> {
+        toml::from_str(include_str!("../../tests/fixtures/stack/engines.toml")).unwrap()
+    }
+
+    /// 测试TOML和JSON格式的栈配置样例反序列化结果相同
+    #[test]
+    fn test_stack_config_formats() {
+        let json: BTreeMap<String, TranslatorStackConfig> =
+            serde_json::from_str(include_str!("../../tests/fixtures/stack/engines.json")).unwrap();
+        let toml = stack_fixture().into_iter().collect::<BTreeMap<_, _>>();
+        assert_eq!(format!("{:?}", toml), format!("{:?}", json));
+    }
+
+    /// 测试反序列化栈配置
+    #[test]
+    fn test_deserialize_stack_config() {
+        let engines = stack_fixture();
+        let baidu = &engines["baidu"];
+        assert!(matches!(
+            &baidu.backend,
+            TranslatorConfig::Baidu { app_id, key } if app_id == "stack-app" && key == "stack-key"
+        ));
+        assert_eq!(baidu.timeout_ms, Some(15000));
+        assert_eq!(baidu.retries, 3);
+        assert_eq!(baidu.retry_backoff_ms, Some(100));
+        assert_eq!(baidu.rate_limit_qps, Some(1.0));
+        assert_eq!(
+            baidu.cache,
+            Some(CacheConfig {
+                capacity: 10000,
+                ttl_secs: Some(86400)
+            })
+        );
+        assert_eq!(
+            baidu.translator_options().timeout,
+            Some(Duration::from_millis(15000))
+        );
+
+        let caiyun = &engines["caiyun"];
+        assert!(matches!(
+            &caiyun.backend,
+            TranslatorConfig::Caiyun { request_id, .. } if request_id == "demo"
+        ));
+        assert_eq!(
+            caiyun.translator_options().user_agent.as_deref(),
+            Some("stack-test")
+        );
+
+        let mymemory = &engines["mymemory"];
+        assert!(matches!(mymemory.backend, TranslatorConfig::MyMemory));
+        assert_eq!(mymemory.retries, 0);
+        assert!(mymemory.cache.is_none() && mymemory.rate_limit_qps.is_none());
+        assert!(matches!(
+            engines["mymemory_human"].backend,
+            TranslatorConfig::MyMemoryFiltered {
+                machine_translation: false,
+                only_private: false
+            }
+        ));
+
+        for config in engines.into_values() {
+            assert!(!TranslatorFactory::create_stack(config).local());
+        }
+    }
+
+    /// 测试组装后的栈：暂时性错误被重试，缓存命中不调用内部翻译器
+    #[tokio::test(start_paused = true)]
+    async fn test_stack_behavior() {
+        let engines = stack_fixture();
+        let mock = Arc::new(MockTranslator::new().with_transient_failures(2));
+        let translator = TranslatorFactory::wrap_stack(mock.clone(), &engines["baidu"]);
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(mock.calls(), 3);
+        let output = translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+        assert_eq!(mock.calls(), 3);
+
+        // 没有包装层时原样返回内部翻译器
+        let mock = Arc::new(MockTranslator::new().with_transient_failures(1));
+        let translator = TranslatorFactory::wrap_stack(mock.clone(), &engines["mymemory"]);
+        assert!(translator
+            .translate("hello", None, &Language::Chinese)
+            .await
+            .is_err());
+        assert_eq!(mock.calls(), 1);
+    }
+
+    /// 测试预热穿过各包装层到达每个翻译器，且不计入翻译调用
+    #[tokio::test]
+    async fn test_warm_all() {
+        let engines = stack_fixture();
+        let primary = Arc::new(MockTranslator::new());
+        let secondary = Arc::new(MockTranslator::new());
+        let fallback = FallbackTranslator::new(vec![
+            (
+                "primary".to_string(),
+                primary.clone() as Arc<dyn AsyncTranslator>,
+            ),
+            ("secondary".to_string(), secondary.clone()),
+        ]);
+        let stack = TranslatorFactory::wrap_stack(Arc::new(fallback), &engines["baidu"]);
+        let standalone = Arc::new(MockTranslator::new());
+        TranslatorFactory::warm_all(&[
+            ("stack".to_string(), stack),
+            ("standalone".to_string(), standalone.clone()),
+        ])
+        .await;
+        for mock in [&primary, &secondary, &standalone] {
+            assert_eq!(mock.warm_ups(), 1);
+            assert_eq!(mock.calls(), 0);
+        }
+    }
+
+    /// 测试创建栈时检查预期的语言对：支持时正常组装，不支持时返回`UnsupportedPair`
+    #[tokio::test]
+    async fn test_stack_expected_pairs() {
+        let engines = stack_fixture();
+        let mock =
+            Arc::new(MockTranslator::new().with_languages([Language::English, Language::Japanese]));
+        let pairs = [
+            (Some(Language::English), Language::Japanese),
+            (None, Language::Japanese),
+        ];
+        let translator =
+            TranslatorFactory::wrap_stack_for_pairs(mock.clone(), &engines["baidu"], &pairs)
+                .unwrap();
+        let output = translator
+            .translate("hello", Some(Language::English), &Language::Japanese)
+            .await
+            .unwrap();
+        assert_eq!(output.text, "HELLO");
+
+        let pairs = [
+            (Some(Language::English), Language::Japanese),
+            (Some(Language::Korean), Language::Japanese),
+        ];
+        let error =
+            TranslatorFactory::wrap_stack_for_pairs(mock.clone(), &engines["baidu"], &pairs)
+                .err()
+                .unwrap();
+        assert!(matches!(
+            &error,
+            TranslatorError::UnsupportedPair { engine, from: Some(Language::Korean), to: Language::Japanese }
+                if engine == "baidu"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "baidu cannot translate Korean to Japanese"
+        );
+        assert_eq!(mock.calls(), 1);
+
+        // 彩云的专业领域只支持中英文
+        let config = TranslatorStackConfig {
+            backend: TranslatorConfig::CaiyunProfessional {
+                token: "token".to_string(),
+                request_id: "demo".to_string(),
+                domain: CaiyunDomain::Medicine,
+            },
+            timeout_ms: None,
+            retries: 2,
+            retry_backoff_ms: None,
+            rate_limit_qps: None,
+            cache: None,
+            user_agent: None,
+        };
+        let pairs = [(
+            Some(Language::English),
+            Language::from_caiyun("zh").unwrap(),
+        )];
+        assert!(TranslatorFactory::create_stack_for_pairs(config.clone(), &pairs).is_ok());
+        let pairs = [(None, Language::Japanese)];
+        assert!(matches!(
+            TranslatorFactory::create_stack_for_pairs(config, &pairs),
+            Err(TranslatorError::UnsupportedPair { engine, from: None, .. }) if engine == "caiyun"
+        ));
     }
 }
@@ -0,0 +1,643 @@
+use crate::fusion_translator::politeness::PolitenessSetting;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::transport::{DefaultHeaders, HttpTransport, ReqwestTransport};
+use reqwest::header::{HeaderMap, HeaderValue, REFERER, USER_AGENT};
+use reqwest::Client;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{tls::Version, Certificate};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 标识本库的User-Agent，用于正式API
+pub const CRATE_USER_AGENT: &str = concat!("fusion-translator/", env!("CARGO_PKG_VERSION"));
+
+/// 浏览器User-Agent，用于网页接口，避免被识别为爬虫
+pub const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+/// 等待异步翻译任务的默认时长
+pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 通过GET请求传递原文的翻译器允许的默认URL长度（字节）
+///
+/// 多数服务器和代理至少支持2048字节的URL
+pub const DEFAULT_MAX_URL_LEN: usize = 2048;
+
+/// 指定额外根证书PEM文件的环境变量，创建任何翻译器的HTTP客户端时都会加载
+pub const CA_BUNDLE_ENV: &str = "FUSION_TRANSLATOR_CA_BUNDLE";
+
+/// 翻译器实例的选项
+///
+/// 与按调用传递的`TranslateOptions`不同，这些选项在创建翻译器时生效，
+/// 对该翻译器的所有请求都有效
+#[derive(Debug, Clone)]
+pub struct TranslatorOptions {
+    /// 覆盖翻译器默认的User-Agent
+    pub user_agent: Option<String>,
+    /// 额外的请求头，与默认请求头同名时覆盖默认值
+    pub headers: HeaderMap,
+    /// 翻译服务返回异步任务时是否轮询结果，默认开启；关闭时返回`TranslatorError::JobPending`
+    pub poll_jobs: bool,
+    /// 等待异步任务完成的最长时间
+    pub job_timeout: Duration,
+    /// 额外信任的根证书，用于会解密TLS流量的企业代理等场景
+    ///
+    /// wasm32上由浏览器处理TLS，证书和TLS版本相关的选项不可用
+    #[cfg(not(target_arch = "wasm32"))]
+    pub extra_root_certs: Vec<Certificate>,
+    /// 接受无效的证书（包括过期、自签名和域名不符的证书），仅用于调试
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    pub danger_accept_invalid_certs: bool,
+    /// 允许的最低TLS版本，None时使用HTTP客户端的默认值
+    #[cfg(not(target_arch = "wasm32"))]
+    pub min_tls_version: Option<Version>,
+    /// 单个HTTP请求的超时时间（从发起连接到读完响应），None表示不限；wasm32上不生效
+    pub timeout: Option<Duration>,
+    /// 是否请求gzip/brotli压缩的响应并自动解压，默认开启；wasm32上由浏览器处理
+    pub compression: bool,
+    /// 通过GET请求传递原文的翻译器允许的最大URL长度（字节），超过时改用POST表单、拆分原文或返回错误
+    pub max_url_len: usize,
+    /// 免密钥翻译器的礼貌请求控制，默认使用各服务共享的`PolitenessConfig::default()`
+    pub politeness: PolitenessSetting,
+    /// 发送HTTP请求的传输层，None时使用按本选项创建的`ReqwestTransport`
+    ///
+    /// 指定传输层后，证书、TLS版本和超时设置不再生效，由传输层自行处理；请求头仍按本选项补充
+    pub transport: Option<Arc<dyn HttpTransport>>,
+}
+
+impl Default for TranslatorOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            headers: HeaderMap::new(),
+            poll_jobs: true,
+            job_timeout: DEFAULT_JOB_TIMEOUT,
+            #[cfg(not(target_arch = "wasm32"))]
+            extra_root_certs: Vec::new(),
+            #[cfg(feature = "danger-accept-invalid-certs")]
+            danger_accept_invalid_certs: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            min_tls_version: None,
+            timeout: None,
+            compression: true,
+            max_url_len: DEFAULT_MAX_URL_LEN,
+            politeness: PolitenessSetting::Default,
+            transport: None,
+        }
+    }
+}
+
+impl TranslatorOptions {
+    /// 创建默认选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置User-Agent
+    ///
+    /// # 参数
+    /// - `user_agent`: 请求使用的User-Agent
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// 添加额外的请求头
+    ///
+    /// # 参数
+    /// - `headers`: 额外的请求头
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// 设置翻译服务返回异步任务时是否轮询结果
+    ///
+    /// 目前只有百度翻译会返回异步任务
+    ///
+    /// # 参数
+    /// - `poll`: 是否轮询结果
+    pub fn with_job_polling(mut self, poll: bool) -> Self {
+        self.poll_jobs = poll;
+        self
+    }
+
+    /// 设置等待异步任务完成的最长时间
+    ///
+    /// # 参数
+    /// - `timeout`: 最长等待时间，超时后返回`TranslatorError::JobPending`
+    pub fn with_job_timeout(mut self, timeout: Duration) -> Self {
+        self.job_timeout = timeout;
+        self
+    }
+
+    /// 添加额外信任的根证书
+    ///
+    /// # 参数
+    /// - `cert`: 根证书
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_root_certificate(mut self, cert: Certificate) -> Self {
+        self.extra_root_certs.push(cert);
+        self
+    }
+
+    /// 从PEM文件加载额外信任的根证书
+    ///
+    /// # 参数
+    /// - `path`: PEM文件路径，可以包含多个证书
+    ///
+    /// # 返回值
+    /// 文件无法读取或不包含有效证书时返回`TranslatorError::InvalidCertificate`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_ca_bundle(mut self, path: impl AsRef<Path>) -> Result<Self, TranslatorError> {
+        self.extra_root_certs.extend(load_ca_bundle(path.as_ref())?);
+        Ok(self)
+    }
+
+    /// 设置是否接受无效的证书
+    ///
+    /// 关闭证书校验后连接可以被任意中间人解密，只应在调试时使用
+    ///
+    /// # 参数
+    /// - `accept`: 是否接受无效的证书
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// 设置允许的最低TLS版本
+    ///
+    /// # 参数
+    /// - `version`: 最低TLS版本
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_min_tls_version(mut self, version: Version) -> Self {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// 设置单个HTTP请求的超时时间
+    ///
+    /// # 参数
+    /// - `timeout`: 超时时间，超时后请求返回网络错误
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 设置是否请求压缩的响应
+    ///
+    /// 开启时发送`Accept-Encoding: gzip, br`并自动解压响应；关闭后按原样接收响应体，便于抓包调试
+    ///
+    /// # 参数
+    /// - `compression`: 是否请求压缩的响应
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// 设置通过GET请求传递原文时允许的最大URL长度
+    ///
+    /// 目前用于MyMemory、阿里翻译和Google网页版：MyMemory超过时改用POST表单，Google网页版拆分原文分别请求，
+    /// 阿里翻译返回`TranslatorError::RequestTooLong`
+    ///
+    /// # 参数
+    /// - `max_url_len`: 编码后URL的最大字节数
+    pub fn with_max_url_len(mut self, max_url_len: usize) -> Self {
+        self.max_url_len = max_url_len;
+        self
+    }
+
+    /// 设置免密钥翻译器（阿里、MyMemory、TranSmart、Reverso、必应网页版和谷歌网页版）的礼貌请求控制
+    ///
+    /// # 参数
+    /// - `politeness`: 使用默认配置、指定实例或关闭
+    pub fn with_politeness(mut self, politeness: PolitenessSetting) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
+    /// 设置发送HTTP请求的传输层
+    ///
+    /// 用于无法使用`reqwest`的环境，或者需要记录、回放请求的场景
+    ///
+    /// # 参数
+    /// - `transport`: 传输层
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 计算翻译器实际使用的请求头
+    ///
+    /// 依次合并翻译器的默认请求头、`user_agent`和`headers`，后者覆盖前者
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器类型
+    pub fn resolve_headers(&self, backend: TranslatorType) -> HeaderMap {
+        let mut headers = default_headers(backend);
+        if let Some(user_agent) = &self.user_agent {
+            match HeaderValue::from_str(user_agent) {
+                Ok(value) => {
+                    headers.insert(USER_AGENT, value);
+                }
+                Err(_) => tracing::warn!(user_agent, "invalid user agent, keeping the default"),
+            }
+        }
+        headers.extend(self.headers.clone());
+        headers
+    }
+
+    /// 按选项创建翻译器使用的HTTP客户端
+    ///
+    /// 除`extra_root_certs`外，还会加载`FUSION_TRANSLATOR_CA_BUNDLE`环境变量指定的根证书，
+    /// 加载失败时记录警告并忽略
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器类型
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn build_client(&self, backend: TranslatorType) -> Client {
+        let mut builder = Client::builder().default_headers(self.resolve_headers(backend));
+        let from_env = std::env::var_os(CA_BUNDLE_ENV).and_then(|path| {
+            load_ca_bundle(Path::new(&path))
+                .map_err(|err| tracing::warn!(%err, "ignoring {}", CA_BUNDLE_ENV))
+                .ok()
+        });
+        for cert in self
+            .extra_root_certs
+            .iter()
+            .cloned()
+            .chain(from_env.into_iter().flatten())
+        {
+            builder = builder.add_root_certificate(cert);
+        }
+        #[cfg(feature = "danger-accept-invalid-certs")]
+        if self.danger_accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(version) = self.min_tls_version {
+            builder = builder.min_tls_version(version);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder
+            .gzip(self.compression)
+            .brotli(self.compression)
+            .build()
+            .expect("failed to build HTTP client")
+    }
+
+    /// 按选项创建翻译器使用的HTTP客户端
+    ///
+    /// wasm32上请求通过浏览器的fetch发送，只设置请求头
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器类型
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn build_client(&self, backend: TranslatorType) -> Client {
+        Client::builder()
+            .default_headers(self.resolve_headers(backend))
+            .build()
+            .expect("failed to build HTTP client")
+    }
+
+    /// 按选项创建翻译器使用的传输层
+    ///
+    /// 指定了`transport`时为其补充翻译器的请求头，否则使用`build_client`创建的客户端
+    ///
+    /// # 参数
+    /// - `backend`: 翻译器类型
+    pub(crate) fn build_transport(&self, backend: TranslatorType) -> Arc<dyn HttpTransport> {
+        match &self.transport {
+            Some(transport) => Arc::new(DefaultHeaders::new(
+                transport.clone(),
+                &self.resolve_headers(backend),
+            )),
+            None => Arc::new(ReqwestTransport::new(self.build_client(backend))),
+        }
+    }
+}
+
+/// 从PEM文件加载根证书
+///
+/// # 参数
+/// - `path`: PEM文件路径
+///
+/// # 返回值
+/// 文件中的所有证书，无法读取或不包含有效证书时返回`TranslatorError::InvalidCertificate`
+#[cfg(not(target_arch = "wasm32"))]
+fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>, TranslatorError> {
+    let invalid = |reason: String| {
+        TranslatorError::InvalidCertificate(format!("{}: {}", path.display(), reason))
+    };
+    let pem = std::fs::read(path).map_err(|err| invalid(err.to_string()))?;
+    let certs = Certificate::from_pem_bundle(&pem).map_err(|err| invalid(err.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no certificates found".to_string()));
+    }
+    Ok(certs)
+}
+
+/// 翻译器的默认请求头
+///
+/// 阿里、TranSmart、Reverso和Bing网页版使用网页接口，需要浏览器User-Agent和来源页面；
+/// MyMemory的免费额度依据Referer区分来源；其余翻译器（包括Google网页版）使用标识本库的User-Agent
+///
+/// # 参数
+/// - `backend`: 翻译器类型
+pub fn default_headers(backend: TranslatorType) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    match backend {
+        TranslatorType::Alibaba => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(BROWSER_USER_AGENT));
+            headers.insert(
+                REFERER,
+                HeaderValue::from_static("https://translate.alibaba.com/"),
+            );
+        }
+        TranslatorType::TranSmart => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(BROWSER_USER_AGENT));
+            headers.insert(
+                REFERER,
+                HeaderValue::from_static("https://transmart.qq.com/"),
+            );
+        }
+        TranslatorType::Reverso => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(BROWSER_USER_AGENT));
+            headers.insert(
+                REFERER,
+                HeaderValue::from_static("https://www.reverso.net/"),
+            );
+        }
+        TranslatorType::BingWeb => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(BROWSER_USER_AGENT));
+            headers.insert(
+                REFERER,
+                HeaderValue::from_static("https://www.bing.com/translator"),
+            );
+        }
+        TranslatorType::MyMemory => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(CRATE_USER_AGENT));
+            headers.insert(
+                REFERER,
+                HeaderValue::from_static("https://mymemory.translated.net"),
+            );
+        }
+        TranslatorType::Baidu
+        | TranslatorType::Youdao
+        | TranslatorType::Caiyun
+        | TranslatorType::GoogleWeb => {
+            headers.insert(USER_AGENT, HeaderValue::from_static(CRATE_USER_AGENT));
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::test_support::http::{self, Request};
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::translator_factory::TranslatorType;
+    use crate::fusion_translator::translator_options::{
+        TranslatorOptions, BROWSER_USER_AGENT, CRATE_USER_AGENT,
+    };
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use std::sync::mpsc;
+
+    /// 用客户端向本地服务器发送一次请求，返回服务器收到的请求
+    async fn captured_request(client: reqwest::Client) -> Request {
+        let response = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+        exchange(client, response.to_vec()).await.0
+    }
+
+    /// 用客户端向本地服务器发送一次请求，服务器按原样返回`response`
+    ///
+    /// # 返回值
+    /// 服务器收到的请求和客户端读到的响应体
+    async fn exchange(client: reqwest::Client, response: Vec<u8>) -> (Request, Vec<u8>) {
+        let (sender, requests) = mpsc::channel();
+        let url = http::serve_raw(move |request| {
+            let _ = sender.send(request.clone());
+            response.clone()
+        });
+        let body = client.get(url).send().await.unwrap().bytes().await.unwrap();
+        (requests.recv().unwrap(), body.to_vec())
+    }
+
+    /// gzip压缩的响应：`tests/fixtures/mymemory/success.json.gz`
+    fn gzip_response() -> Vec<u8> {
+        let body = include_bytes!("../../tests/fixtures/mymemory/success.json.gz");
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\n\
+             content-length: {}\r\nconnection: close\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    /// 测试默认请求压缩的响应并解压gzip响应体
+    #[tokio::test]
+    async fn test_decodes_gzip_response() {
+        let client = TranslatorOptions::new().build_client(TranslatorType::MyMemory);
+        let (request, body) = exchange(client, gzip_response()).await;
+        let accept = request.header("accept-encoding").unwrap();
+        assert!(
+            accept.contains("gzip") && accept.contains("br"),
+            "{}",
+            accept
+        );
+        let expected = include_bytes!("../../tests/fixtures/mymemory/success.json");
+        assert_eq!(body, expected);
+    }
+
+    /// 测试关闭压缩后不发送`Accept-Encoding`，响应体按原样返回
+    #[tokio::test]
+    async fn test_compression_disabled() {
+        let client = TranslatorOptions::new()
+            .with_compression(false)
+            .build_client(TranslatorType::MyMemory);
+        let (request, body) = exchange(client, gzip_response()).await;
+        assert_eq!(request.header("accept-encoding"), None);
+        let compressed = include_bytes!("../../tests/fixtures/mymemory/success.json.gz");
+        assert_eq!(body, compressed);
+    }
+
+    /// 测试各翻译器的默认请求头实际发送到服务器
+    #[tokio::test]
+    async fn test_default_headers_on_the_wire() {
+        for backend in TranslatorType::ALL {
+            let request = captured_request(TranslatorOptions::new().build_client(backend)).await;
+            let user_agent = request.header("user-agent");
+            let referer = request.header("referer");
+            match backend {
+                TranslatorType::Alibaba => {
+                    assert_eq!(user_agent, Some(BROWSER_USER_AGENT));
+                    assert_eq!(referer, Some("https://translate.alibaba.com/"));
+                }
+                TranslatorType::TranSmart => {
+                    assert_eq!(user_agent, Some(BROWSER_USER_AGENT));
+                    assert_eq!(referer, Some("https://transmart.qq.com/"));
+                }
+                TranslatorType::Reverso => {
+                    assert_eq!(user_agent, Some(BROWSER_USER_AGENT));
+                    assert_eq!(referer, Some("https://www.reverso.net/"));
+                }
+                TranslatorType::BingWeb => {
+                    assert_eq!(user_agent, Some(BROWSER_USER_AGENT));
+                    assert_eq!(referer, Some("https://www.bing.com/translator"));
+                }
+                TranslatorType::MyMemory => {
+                    assert_eq!(user_agent, Some(CRATE_USER_AGENT));
+                    assert_eq!(referer, Some("https://mymemory.translated.net"));
+                }
+                _ => {
+                    assert_eq!(user_agent, Some(CRATE_USER_AGENT), "{:?}", backend);
+                    assert_eq!(referer, None);
+                }
+            }
+        }
+    }
+
+    /// 测试自定义User-Agent和额外请求头覆盖默认值
+    #[tokio::test]
+    async fn test_overrides_on_the_wire() {
+        let mut extra = HeaderMap::new();
+        extra.insert("x-api-tier", HeaderValue::from_static("free"));
+        extra.insert("referer", HeaderValue::from_static("https://example.com"));
+        let options = TranslatorOptions::new()
+            .with_user_agent("my-app/2.0")
+            .with_headers(extra);
+        for backend in TranslatorType::ALL {
+            let request = captured_request(options.build_client(backend)).await;
+            assert_eq!(request.header("user-agent"), Some("my-app/2.0"));
+            assert_eq!(request.header("x-api-tier"), Some("free"));
+            assert_eq!(request.header("referer"), Some("https://example.com"));
+        }
+    }
+
+    /// 测试用CA证书
+    const CA_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/ca.pem");
+
+    /// 启动使用测试CA签发的证书的本地HTTPS服务器
+    ///
+    /// # 返回值
+    /// 服务器地址
+    async fn tls_server() -> String {
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+
+        let mut pem: &[u8] = include_bytes!("../../tests/fixtures/tls/server.pem");
+        let certs = rustls_pemfile::certs(&mut pem).unwrap();
+        let mut pem: &[u8] = include_bytes!("../../tests/fixtures/tls/server.key");
+        let key = rustls_pemfile::pkcs8_private_keys(&mut pem)
+            .unwrap()
+            .remove(0);
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                certs.into_iter().map(Certificate).collect(),
+                PrivateKey(key),
+            )
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("https://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                // 客户端不信任证书时握手失败，继续等待下一个连接
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    continue;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                    )
+                    .await
+                    .unwrap();
+                stream.shutdown().await.ok();
+            }
+        });
+        url
+    }
+
+    /// 测试只有提供了签发服务器证书的CA后才能建立TLS连接
+    #[tokio::test]
+    async fn test_extra_root_certificate() {
+        let url = tls_server().await;
+        let untrusted = TranslatorOptions::new().build_client(TranslatorType::Baidu);
+        assert!(untrusted.get(&url).send().await.is_err());
+
+        let from_file = TranslatorOptions::new()
+            .with_ca_bundle(CA_PATH)
+            .unwrap()
+            .build_client(TranslatorType::Baidu);
+        let response = from_file.get(&url).send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let cert = reqwest::Certificate::from_pem(&std::fs::read(CA_PATH).unwrap()).unwrap();
+        let with_cert = TranslatorOptions::new()
+            .with_root_certificate(cert)
+            .with_min_tls_version(reqwest::tls::Version::TLS_1_2)
+            .build_client(TranslatorType::Youdao);
+        assert!(with_cert
+            .get(&url)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .is_success());
+    }
+
+    /// 测试关闭证书校验后无需CA证书也能连接
+    #[cfg(feature = "danger-accept-invalid-certs")]
+    #[tokio::test]
+    async fn test_danger_accept_invalid_certs() {
+        let url = tls_server().await;
+        let client = TranslatorOptions::new()
+            .with_danger_accept_invalid_certs(true)
+            .build_client(TranslatorType::Baidu);
+        assert!(client.get(&url).send().await.unwrap().status().is_success());
+    }
+
+    /// 测试无法读取或不含证书的PEM文件返回`InvalidCertificate`
+    #[test]
+    fn test_invalid_ca_bundle() {
+        for path in [
+            "tests/fixtures/tls/missing.pem",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/server.key"),
+        ] {
+            assert!(matches!(
+                TranslatorOptions::new().with_ca_bundle(path),
+                Err(TranslatorError::InvalidCertificate(_))
+            ));
+        }
+    }
+
+    /// 测试无效的User-Agent保留默认值
+    #[test]
+    fn test_invalid_user_agent_keeps_default() {
+        let headers = TranslatorOptions::new()
+            .with_user_agent("bad\nagent")
+            .resolve_headers(TranslatorType::Baidu);
+        assert_eq!(headers["user-agent"], CRATE_USER_AGENT);
+    }
+}
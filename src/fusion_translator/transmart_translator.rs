@@ -0,0 +1,685 @@
+use crate::fusion_translator::async_translator::{
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
+};
+use crate::fusion_translator::batch::{self, empty_batch, validate_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::long_text;
+use crate::fusion_translator::politeness::{self, Politeness, PolitenessConfig};
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::translate_options::{
+    generate_request_id, with_request_id_header, TranslateOptions,
+};
+use crate::fusion_translator::translator_error::TranslatorError;
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::Instrument;
+
+/// TranSmart支持的语言及其代码
+const LANGUAGES: &[(Language, &str)] = &[
+    (Language::Chinese, "zh"),
+    (Language::English, "en"),
+    (Language::Japanese, "ja"),
+    (Language::Korean, "ko"),
+    (Language::French, "fr"),
+    (Language::German, "de"),
+    (Language::Spanish, "es"),
+    (Language::Italian, "it"),
+    (Language::Portuguese, "pt"),
+    (Language::Russian, "ru"),
+    (Language::Turkish, "tr"),
+    (Language::Vietnamese, "vi"),
+    (Language::Indonesian, "id"),
+    (Language::Thai, "th"),
+    (Language::Malay, "ms"),
+    (Language::Arabic, "ar"),
+    (Language::Hindi, "hi"),
+];
+
+/// 把语言转换为TranSmart的语言代码
+///
+/// # 返回值
+/// 语言代码，TranSmart不支持该语言时为None
+fn to_transmart(lang: &Language) -> Option<&'static str> {
+    LANGUAGES
+        .iter()
+        .find(|(l, _)| l == lang)
+        .map(|&(_, code)| code)
+}
+
+/// TranSmart翻译请求
+#[derive(Serialize)]
+struct TranSmartRequest<'a> {
+    /// 请求头，指定调用的功能和客户端标识
+    header: RequestHeader<'a>,
+    /// 文本类型，纯文本为"plain"
+    #[serde(rename = "type")]
+    kind: &'static str,
+    /// 翻译模型类别
+    model_category: &'static str,
+    /// 源语言和按句子拆分的文本
+    source: Source<'a>,
+    /// 目标语言
+    target: Target,
+}
+
+/// TranSmart请求头
+#[derive(Serialize)]
+struct RequestHeader<'a> {
+    /// 调用的功能，翻译为"auto_translation"
+    #[serde(rename = "fn")]
+    function: &'static str,
+    /// 网页客户端标识
+    client_key: &'a str,
+}
+
+/// 源语言和文本
+#[derive(Serialize)]
+struct Source<'a> {
+    /// 源语言代码
+    lang: &'static str,
+    /// 按句子拆分的文本，译文按相同顺序返回
+    text_list: Vec<&'a str>,
+}
+
+/// 目标语言
+#[derive(Serialize)]
+struct Target {
+    /// 目标语言代码
+    lang: &'static str,
+}
+
+/// TranSmart翻译响应
+#[derive(Deserialize)]
+struct TranSmartResponse {
+    /// 响应头
+    #[serde(default)]
+    header: ResponseHeader,
+    /// 与`text_list`一一对应的译文
+    #[serde(default)]
+    auto_translation: Option<Vec<String>>,
+}
+
+/// TranSmart响应头
+#[derive(Deserialize, Default)]
+struct ResponseHeader {
+    /// 返回码，成功时为"succ"
+    #[serde(default)]
+    ret_code: String,
+}
+
+impl TranSmartResponse {
+    /// 取出逐句译文
+    ///
+    /// 网页接口没有公开的错误码，匿名调用被拒绝时`ret_code`不为"succ"且没有译文，
+    /// 这种情况按频率限制处理
+    fn into_sentences(self) -> Result<Vec<String>, TranslatorError> {
+        let code = self.header.ret_code;
+        match self.auto_translation {
+            Some(sentences) if code.is_empty() || code == "succ" => Ok(sentences),
+            _ if !code.is_empty() && code != "succ" => Err(TranslatorError::RateLimited(code)),
+            _ => Err(TranslatorError::NoResponse),
+        }
+    }
+}
+
+/// 把文本拆分为发送给TranSmart的句子
+///
+/// # 返回值
+/// `(前导空白, 句子, 尾随空白)`列表，依次拼接后与原文相同；只有空白的部分句子为空字符串
+fn segments(text: &str) -> Vec<(&str, &str, &str)> {
+    long_text::sentences(text)
+        .into_iter()
+        .map(|sentence| {
+            let trimmed = sentence.trim_start();
+            let body = trimmed.trim_end();
+            (
+                &sentence[..sentence.len() - trimmed.len()],
+                body,
+                &trimmed[body.len()..],
+            )
+        })
+        .collect()
+}
+
+/// 腾讯TranSmart翻译器实现
+///
+/// 调用TranSmart网页使用的匿名接口，无需注册API密钥；
+/// 文本按句子拆分为`text_list`发送，再按原有的空白拼接逐句译文
+pub struct TranSmartTranslator {
+    /// API请求地址
+    host: String,
+    /// 网页客户端标识，每个实例生成一次
+    client_key: String,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 礼貌请求控制，None表示关闭
+    politeness: Option<Arc<Politeness>>,
+}
+
+/// 默认实现
+impl Default for TranSmartTranslator {
+    fn default() -> Self {
+        TranSmartTranslator::new()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AsyncTranslator for TranSmartTranslator {
+    /// 判断是否为本地翻译器
+    ///
+    /// TranSmart翻译器需要调用远程API，返回false
+    fn local(&self) -> bool {
+        false
+    }
+
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "transmart", &self.host).await
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(to_transmart))
+    }
+
+    /// 翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationOutput> {
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let mut v = self
+            .translate_vec_with_options(&[query.to_owned()], from, to, options)
+            .await?;
+        Ok(TranslationOutput {
+            text: v.text.remove(0),
+            lang: Some(*to),
+            detail: v.detail,
+        })
+    }
+
+    /// 按指定选项翻译多个文本
+    ///
+    /// 所有文本的句子合并为一次请求，请求ID通过`X-Request-Id`请求头传递
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果数组
+    async fn translate_vec_with_options(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            0,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("transmart", query, from, to, &options)
+        })
+    }
+}
+
+impl TranSmartTranslator {
+    /// 创建新的TranSmart翻译器实例
+    ///
+    /// # 返回值
+    /// 新的翻译器实例
+    pub fn new() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        TranSmartTranslator {
+            transport: TranslatorOptions::default().build_transport(TranslatorType::TranSmart),
+            politeness: Some(Politeness::shared("transmart", PolitenessConfig::default())),
+            client_key: format!(
+                "browser-chrome-124.0.0-Windows 10-{}-{}",
+                generate_request_id(),
+                millis
+            ),
+            host: "https://transmart.qq.com/api/imt".to_string(),
+        }
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::TranSmart);
+        self.politeness = options.politeness.resolve("transmart");
+        self
+    }
+
+    /// 翻译一个分块
+    ///
+    /// TranSmart要求明确的源语言，未指定时按`long_text::detect_script`检测，
+    /// 只能区分文字体系，拉丁字母的非英语文本需要调用方指定源语言
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本
+    /// - `from`: 源语言，None表示按文字检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return Ok(empty_batch(options));
+        }
+        let segments = query.iter().map(|q| segments(q)).collect::<Vec<_>>();
+        let text_list = segments
+            .iter()
+            .flatten()
+            .map(|&(_, body, _)| body)
+            .filter(|body| !body.is_empty())
+            .collect::<Vec<_>>();
+        let mut languages = LanguageResolver::new(options, to_transmart);
+        let source = match from {
+            Some(lang) => lang,
+            None => {
+                long_text::detect_script(&query.join("\n")).ok_or(TranslatorError::NoLanguage)?
+            }
+        };
+        let request = TranSmartRequest {
+            header: RequestHeader {
+                function: "auto_translation",
+                client_key: &self.client_key,
+            },
+            kind: "plain",
+            model_category: "normal",
+            source: Source {
+                lang: languages.resolve(source)?,
+                text_list,
+            },
+            target: Target {
+                lang: languages.resolve(*to)?,
+            },
+        };
+
+        let mut translated = if request.source.text_list.is_empty() {
+            Vec::new()
+        } else {
+            let request_id = options.resolve_request_id();
+            let span =
+                tracing::debug_span!("translate", backend = "transmart", request_id = request_id);
+            let data: TranSmartResponse = options
+                .run_cancellable(
+                    0,
+                    async {
+                        let request = with_request_id_header(
+                            HttpRequest::post(&self.host).json(&request)?,
+                            request_id.as_deref(),
+                        );
+                        let response =
+                            politeness::send(self.politeness.as_deref(), &*self.transport, request)
+                                .await?;
+                        match StatusCode::from_u16(response.status) {
+                            Ok(
+                                status @ (StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN),
+                            ) => {
+                                return Err(TranslatorError::RateLimited(status.to_string()).into());
+                            }
+                            _ if !response.is_success() => {
+                                return Err(TranslatorError::RequestFailed(response.status).into());
+                            }
+                            _ => {}
+                        }
+                        response.json()
+                    }
+                    .instrument(span),
+                )
+                .await?;
+            validate_batch(request.source.text_list.len(), data.into_sentences()?)?
+        }
+        .into_iter();
+
+        let text = segments
+            .iter()
+            .map(|segments| {
+                let mut text = String::new();
+                for &(lead, body, trail) in segments {
+                    text.push_str(lead);
+                    if !body.is_empty() {
+                        text.push_str(&translated.next().unwrap_or_default());
+                    }
+                    text.push_str(trail);
+                }
+                text
+            })
+            .collect::<Vec<_>>();
+        let text = postprocess::apply_all(query, text, options);
+        let possibly_untranslated = untranslated::check_all(query, &text, from, to, options)?;
+
+        Ok(TranslationListOutput {
+            text,
+            lang: Some(*to),
+            detail: TranslationDetail {
+                request_id: options.request_id.clone(),
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(all(test, feature = "live-schema-check"))]
+impl TranSmartTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, serde_json::Value)> {
+        let request = TranSmartRequest {
+            header: RequestHeader {
+                function: "auto_translation",
+                client_key: &self.client_key,
+            },
+            kind: "plain",
+            model_category: "normal",
+            source: Source {
+                lang: "en",
+                text_list: vec!["Hello, world."],
+            },
+            target: Target { lang: "zh" },
+        };
+        let request = HttpRequest::post(&self.host).json(&request)?;
+        let body = self.transport.execute(request).await?;
+        Ok(("success.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "success.json" | "rejected.json" => {
+            serde_json::from_str::<TranSmartResponse>(body)?;
+        }
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::{AsyncTranslator, Language};
+    use crate::fusion_translator::politeness::{Politeness, PolitenessConfig};
+    use crate::fusion_translator::test_support::{fuzz, http};
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::transmart_translator::{
+        segments, to_transmart, TranSmartTranslator,
+    };
+    use serde_json::Value;
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 成功响应
+    const SUCCESS: &str = include_str!("../../tests/fixtures/transmart/success.json");
+
+    /// 被拒绝的响应
+    const REJECTED: &str = include_str!("../../tests/fixtures/transmart/rejected.json");
+
+    /// 启动对每个请求返回相同响应的本地服务器
+    ///
+    /// # 参数
+    /// - `status`: 响应状态码
+    /// - `body`: 响应体
+    ///
+    /// # 返回值
+    /// 服务器地址和按顺序接收请求体的通道
+    fn serve(status: u16, body: &'static str) -> (String, Receiver<Value>) {
+        let (sender, requests) = mpsc::channel();
+        let url = http::serve(move |request| {
+            let _ = sender.send(serde_json::from_str(&request.body).unwrap());
+            (status, body.to_string())
+        });
+        (format!("{}/api/imt", url), requests)
+    }
+
+    /// 创建请求本地服务器的翻译器
+    fn translator(url: String) -> TranSmartTranslator {
+        let mut translator = TranSmartTranslator::new();
+        translator.host = url;
+        translator.politeness = None;
+        translator
+    }
+
+    /// 测试创建翻译器实例
+    #[test]
+    fn test_create_translator() {
+        let translator = TranSmartTranslator::default();
+        assert!(!translator.local());
+        assert!(translator.client_key.starts_with("browser-chrome-"));
+        assert!(translator.host.contains("transmart.qq.com"));
+    }
+
+    /// 测试语言代码映射
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(to_transmart(&Language::Chinese), Some("zh"));
+        assert_eq!(to_transmart(&Language::Japanese), Some("ja"));
+        assert_eq!(to_transmart(&Language::Welsh), None);
+    }
+
+    /// 测试拆分的句子按原样拼接后与原文相同
+    #[test]
+    fn test_segments_roundtrip() {
+        let text = "  Hello world. How are you?\n\nFine, thanks.  ";
+        let parts = segments(text);
+        let bodies = parts
+            .iter()
+            .map(|&(_, body, _)| body)
+            .filter(|body| !body.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bodies,
+            vec!["Hello world.", "How are you?", "Fine, thanks."]
+        );
+        let joined = parts
+            .iter()
+            .map(|(lead, body, trail)| format!("{}{}{}", lead, body, trail))
+            .collect::<String>();
+        assert_eq!(joined, text);
+    }
+
+    /// 测试句子数组的往返：多个文本的句子合并为一个`text_list`，译文按原有空白拼接
+    #[tokio::test]
+    async fn test_sentence_array_roundtrip() {
+        let (url, requests) = serve(200, SUCCESS);
+        let query = ["Hello world. How are you?", "", "Good night.\n\nSee you."].map(String::from);
+        let output = translator(url)
+            .translate_vec(&query, Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.text,
+            vec!["你好，世界。 你好吗？", "", "晚安。\n\n再见。"]
+        );
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request["header"]["fn"], "auto_translation");
+        assert_eq!(request["type"], "plain");
+        assert_eq!(request["source"]["lang"], "en");
+        assert_eq!(request["target"]["lang"], "zh");
+        assert_eq!(
+            request["source"]["text_list"],
+            serde_json::json!(["Hello world.", "How are you?", "Good night.", "See you."])
+        );
+    }
+
+    /// 测试未指定源语言时按文字检测
+    #[tokio::test]
+    async fn test_detects_source_language() {
+        let (url, requests) = serve(200, SUCCESS);
+        // 译文数量与句子数量不同，只检查请求
+        let _ = translator(url)
+            .translate("你好。", None, &Language::English)
+            .await;
+        let request = requests.recv().unwrap();
+        assert_eq!(request["source"]["lang"], "zh");
+        assert_eq!(request["target"]["lang"], "en");
+    }
+
+    /// 测试被拒绝的响应和429状态码均返回`RateLimited`
+    #[tokio::test]
+    async fn test_rejected_is_rate_limited() {
+        for (status, body) in [(200, REJECTED), (429, "")] {
+            let (url, requests) = serve(status, body);
+            let err = translator(url)
+                .translate("Hello.", Some(Language::English), &Language::Chinese)
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<TranslatorError>(),
+                    Some(TranslatorError::RateLimited(_))
+                ),
+                "{}: {:?}",
+                status,
+                err
+            );
+            requests.recv().unwrap();
+        }
+    }
+
+    /// 测试429状态码使礼貌请求控制进入冷却，冷却期间不再发送请求
+    #[tokio::test]
+    async fn test_rate_limited_starts_cooldown() {
+        let (url, requests) = serve(429, "");
+        let politeness = Arc::new(Politeness::new(
+            PolitenessConfig::default().with_delay(Duration::ZERO, Duration::ZERO),
+        ));
+        let mut translator = translator(url);
+        translator.politeness = Some(politeness.clone());
+        translator
+            .translate("Hello.", Some(Language::English), &Language::Chinese)
+            .await
+            .unwrap_err();
+        requests.recv().unwrap();
+        let status = politeness.status();
+        assert_eq!(status.strikes, 1);
+        assert!(status.cooling_down());
+
+        let pending = translator.translate("Hello.", Some(Language::English), &Language::Chinese);
+        assert!(tokio::time::timeout(Duration::from_millis(200), pending)
+            .await
+            .is_err());
+    }
+
+    /// 测试只有空白的文本不发送请求
+    #[tokio::test]
+    async fn test_blank_query_skips_request() {
+        let output = translator("http://127.0.0.1:9/".to_string())
+            .translate_vec(
+                &[" \n".to_string()],
+                Some(Language::English),
+                &Language::Chinese,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.text, vec![" \n"]);
+    }
+
+    /// 模糊测试：任意文本都能拆分为句子，拼接后与原文相同
+    #[test]
+    fn test_fuzz_segments() {
+        let corpus = [
+            include_str!("../../tests/fixtures/long_text/mixed.txt"),
+            "  Hello world.  How are you?\n\n你好。 ",
+        ];
+        for input in fuzz::inputs(701, &corpus) {
+            let joined = segments(&input)
+                .into_iter()
+                .map(|(leading, body, trailing)| format!("{leading}{body}{trailing}"))
+                .collect::<String>();
+            assert_eq!(joined, input);
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use crate::fusion_translator::translator_error::TranslatorError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+
+/// HTTP请求方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    /// GET请求
+    Get,
+    /// POST请求
+    Post,
+    /// HEAD请求，只用于预热连接
+    Head,
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Head => "HEAD",
+        })
+    }
+}
+
+/// 与HTTP客户端无关的请求
+///
+/// 翻译器只构造请求，由`HttpTransport`负责发送。请求头按添加顺序保存，
+/// 翻译器的默认请求头（User-Agent、来源页面等）由传输层附加，不在这里出现
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// 请求方法
+    pub method: HttpMethod,
+    /// 完整的请求地址，包括查询参数
+    pub url: String,
+    /// 请求头（名称，值）
+    pub headers: Vec<(String, String)>,
+    /// 请求体，GET请求为空
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// 创建GET请求
+    ///
+    /// # 参数
+    /// - `url`: 请求地址
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(HttpMethod::Get, url)
+    }
+
+    /// 创建POST请求
+    ///
+    /// # 参数
+    /// - `url`: 请求地址
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(HttpMethod::Post, url)
+    }
+
+    /// 创建HEAD请求
+    ///
+    /// # 参数
+    /// - `url`: 请求地址
+    pub fn head(url: impl Into<String>) -> Self {
+        Self::new(HttpMethod::Head, url)
+    }
+
+    /// 创建请求
+    ///
+    /// # 参数
+    /// - `method`: 请求方法
+    /// - `url`: 请求地址
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// 添加请求头
+    ///
+    /// # 参数
+    /// - `name`: 请求头名称
+    /// - `value`: 请求头的值
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 在地址后追加查询参数
+    ///
+    /// # 参数
+    /// - `query`: 查询参数，可以是结构体或（名称，值）数组
+    ///
+    /// # 返回值
+    /// 参数无法编码时返回错误
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> anyhow::Result<Self> {
+        let query = serde_urlencoded::to_string(query)?;
+        if !query.is_empty() {
+            self.url
+                .push(if self.url.contains('?') { '&' } else { '?' });
+            self.url.push_str(&query);
+        }
+        Ok(self)
+    }
+
+    /// 以`application/x-www-form-urlencoded`表单作为请求体
+    ///
+    /// # 参数
+    /// - `form`: 表单字段，可以是结构体或（名称，值）数组
+    ///
+    /// # 返回值
+    /// 表单无法编码时返回错误
+    pub fn form<T: Serialize + ?Sized>(self, form: &T) -> anyhow::Result<Self> {
+        let body = serde_urlencoded::to_string(form)?;
+        Ok(self
+            .content_type("application/x-www-form-urlencoded")
+            .with_body(body))
+    }
+
+    /// 以JSON作为请求体
+    ///
+    /// # 参数
+    /// - `json`: 请求内容
+    ///
+    /// # 返回值
+    /// 无法序列化时返回错误
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> anyhow::Result<Self> {
+        let body = serde_json::to_vec(json)?;
+        Ok(self.content_type("application/json").with_body(body))
+    }
+
+    /// 未设置`content-type`请求头时设置为指定值
+    ///
+    /// # 参数
+    /// - `value`: 请求体的类型
+    fn content_type(self, value: &str) -> Self {
+        if self.header_value("content-type").is_some() {
+            self
+        } else {
+            self.header("content-type", value)
+        }
+    }
+
+    /// 设置原始请求体
+    ///
+    /// # 参数
+    /// - `body`: 请求体
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// 按名称取请求头，不区分大小写
+    ///
+    /// # 参数
+    /// - `name`: 请求头名称
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// 与HTTP客户端无关的响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// HTTP状态码
+    pub status: u16,
+    /// 响应头（名称，值）
+    pub headers: Vec<(String, String)>,
+    /// 响应体
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// 创建响应
+    ///
+    /// # 参数
+    /// - `status`: HTTP状态码
+    /// - `body`: 响应体
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// 添加响应头
+    ///
+    /// # 参数
+    /// - `name`: 响应头名称
+    /// - `value`: 响应头的值
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 状态码是否为2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// 按名称取响应头，不区分大小写
+    ///
+    /// # 参数
+    /// - `name`: 响应头名称
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// 按UTF-8解码的响应体，无效的字节替换为`U+FFFD`
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// 按JSON解析响应体
+    ///
+    /// # 返回值
+    /// 解析结果，响应体不是有效的JSON时返回`serde_json::Error`
+    pub fn json<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// 按名称查找请求头或响应头，不区分大小写
+///
+/// # 参数
+/// - `headers`: 头部列表
+/// - `name`: 名称
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// HTTP传输层
+///
+/// 所有内置翻译器都通过它发送请求，而不是直接调用`reqwest`。默认实现为`ReqwestTransport`；
+/// 在无法使用`reqwest`的环境（如Cloudflare Workers等wasm运行时）中，
+/// 可以实现该特征并通过`TranslatorOptions::with_transport`传给翻译器，
+/// 也可以包装默认实现来记录、回放或观察请求。
+///
+/// 非2xx状态码不是错误，应作为响应返回，由翻译器按状态码处理；
+/// 只有网络错误、超时等没有收到响应的情况才返回错误
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+pub trait HttpTransport: Send + Sync {
+    /// 发送请求
+    ///
+    /// # 参数
+    /// - `request`: 待发送的请求
+    ///
+    /// # 返回值
+    /// 收到的响应
+    async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse>;
+}
+
+impl fmt::Debug for dyn HttpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HttpTransport")
+    }
+}
+
+/// 基于`reqwest`的默认传输层
+///
+/// 网络错误以`TranslatorError::Reqwest`返回
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    /// HTTP客户端
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// 使用指定的HTTP客户端创建传输层
+    ///
+    /// # 参数
+    /// - `client`: HTTP客户端，其默认请求头、超时和证书设置对所有请求生效
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl HttpTransport for ReqwestTransport {
+    /// 通过`reqwest`发送请求
+    ///
+    /// # 参数
+    /// - `request`: 待发送的请求
+    ///
+    /// # 返回值
+    /// 收到的响应，读取响应体失败时返回`TranslatorError::Reqwest`
+    async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        let method = match request.method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Head => Method::HEAD,
+        };
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body);
+        }
+        let response = builder.send().await.map_err(TranslatorError::from)?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.bytes().await.map_err(TranslatorError::from)?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: body.to_vec(),
+        })
+    }
+}
+
+/// 为请求补充默认请求头的传输层
+///
+/// 自定义传输层没有`reqwest`客户端的默认请求头，由它把翻译器的默认请求头补充到每个请求中，
+/// 请求中已有的同名请求头保持不变
+pub(crate) struct DefaultHeaders {
+    /// 内部传输层
+    inner: Arc<dyn HttpTransport>,
+    /// 默认请求头
+    headers: Vec<(String, String)>,
+}
+
+impl DefaultHeaders {
+    /// 创建补充默认请求头的传输层
+    ///
+    /// # 参数
+    /// - `inner`: 内部传输层
+    /// - `headers`: 默认请求头，无法表示为字符串的值被忽略
+    pub(crate) fn new(inner: Arc<dyn HttpTransport>, headers: &HeaderMap) -> Self {
+        let headers = headers
+            .iter()
+            .filter_map(|(name, value): (&HeaderName, &HeaderValue)| {
+                Some((name.to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        Self { inner, headers }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl HttpTransport for DefaultHeaders {
+    /// 补充默认请求头后交给内部传输层
+    ///
+    /// # 参数
+    /// - `request`: 待发送的请求
+    async fn execute(&self, mut request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        for (name, value) in &self.headers {
+            if request.header_value(name).is_none() {
+                request.headers.push((name.clone(), value.clone()));
+            }
+        }
+        self.inner.execute(request).await
+    }
+}
+
+/// 向翻译接口发送HEAD请求，预先建立连接
+///
+/// 任何状态码都说明连接已经建立，不检查响应；没有收到响应时记录警告日志，不返回错误
+///
+/// # 参数
+/// - `transport`: 翻译器的传输层
+/// - `backend`: 翻译器名称，用于日志
+/// - `url`: 翻译接口地址，不带查询参数
+pub(crate) async fn warm_up(transport: &dyn HttpTransport, backend: &str, url: &str) {
+    match transport.execute(HttpRequest::head(url)).await {
+        Ok(response) => tracing::debug!(backend, status = response.status, "connection warmed up"),
+        Err(err) => tracing::warn!(backend, error = %format!("{:#}", err), "warm-up failed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::test_support::http;
+    use crate::fusion_translator::transport::{
+        HttpMethod, HttpRequest, HttpResponse, HttpTransport, ReqwestTransport,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// 测试表单和JSON请求体的编码
+    #[test]
+    fn test_request_bodies() {
+        let request = HttpRequest::get("http://local/api?a=1")
+            .query(&[("q", "你好")])
+            .unwrap();
+        assert_eq!(request.url, "http://local/api?a=1&q=%E4%BD%A0%E5%A5%BD");
+        assert!(request.body.is_empty());
+
+        let request = HttpRequest::post("http://local/api")
+            .form(&[("q", "a b&c"), ("to", "zh")])
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(
+            request.header_value("Content-Type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(request.body, b"q=a+b%26c&to=zh");
+
+        let request = HttpRequest::post("http://local/api")
+            .json(&serde_json::json!({"q": "hi"}))
+            .unwrap();
+        assert_eq!(request.body, br#"{"q":"hi"}"#);
+        assert_eq!(
+            request.header_value("content-type"),
+            Some("application/json")
+        );
+
+        let response = HttpResponse::new(429, "{}").with_header("Retry-After", "3");
+        assert!(!response.is_success());
+        assert_eq!(response.header("retry-after"), Some("3"));
+    }
+
+    /// 测试默认传输层原样发送请求，非2xx状态码作为响应返回
+    #[tokio::test]
+    async fn test_reqwest_transport() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let base = http::serve(move |request| {
+            recorded.lock().unwrap().push(request.clone());
+            let status = if request.path == "/missing" { 404 } else { 200 };
+            (status, format!("{} {}", request.method, request.body))
+        });
+        let transport = ReqwestTransport::new(reqwest::Client::new());
+        let response = transport
+            .execute(
+                HttpRequest::post(format!("{}/api?x=1", base))
+                    .header("X-Test", "yes")
+                    .with_body("payload"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.text(), "POST payload");
+        let missing = transport
+            .execute(HttpRequest::get(format!("{}/missing", base)))
+            .await
+            .unwrap();
+        assert_eq!(missing.status, 404);
+
+        let requests = seen.lock().unwrap();
+        assert_eq!(requests[0].query, "x=1");
+        assert_eq!(requests[0].header("x-test"), Some("yes"));
+    }
+}
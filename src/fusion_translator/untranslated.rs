@@ -0,0 +1,204 @@
+use crate::fusion_translator::async_translator::Language;
+use crate::fusion_translator::postprocess::normalize_nfc;
+use crate::fusion_translator::translate_options::TranslateOptions;
+use crate::fusion_translator::translator_error::TranslatorError;
+
+/// 未翻译检查模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UntranslatedCheck {
+    /// 不检查
+    Off,
+    /// 在`TranslationDetail::possibly_untranslated`中标记疑似未翻译的结果
+    #[default]
+    Flag,
+    /// 疑似未翻译时返回`TranslatorError::UntranslatedOutput`
+    Strict,
+}
+
+/// 判断译文是否疑似未翻译
+///
+/// 满足以下全部条件时视为疑似未翻译：
+/// - 规范化（NFC、合并空白）后的原文与译文完全相同
+/// - 源语言未知或与目标语言不同
+/// - 原文中包含字母，纯数字、符号等本来就不需要翻译的文本不会被标记
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 译文
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+///
+/// # 返回值
+/// 是否疑似未翻译
+pub fn is_possibly_untranslated(
+    source: &str,
+    output: &str,
+    from: Option<Language>,
+    to: &Language,
+) -> bool {
+    if from == Some(*to) {
+        return false;
+    }
+    if !source.chars().any(char::is_alphabetic) {
+        return false;
+    }
+    normalize(source) == normalize(output)
+}
+
+/// 规范化用于比较的文本
+///
+/// # 参数
+/// - `text`: 要规范化的文本
+fn normalize(text: &str) -> String {
+    normalize_nfc(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 按选项检查单个译文
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 译文
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 是否疑似未翻译，严格模式下疑似未翻译时返回错误
+pub(crate) fn check(
+    source: &str,
+    output: &str,
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+) -> Result<bool, TranslatorError> {
+    if options.untranslated_check == UntranslatedCheck::Off {
+        return Ok(false);
+    }
+    let untranslated = is_possibly_untranslated(source, output, from, to);
+    if untranslated && options.untranslated_check == UntranslatedCheck::Strict {
+        return Err(TranslatorError::UntranslatedOutput);
+    }
+    Ok(untranslated)
+}
+
+/// 按选项检查一组译文
+///
+/// # 参数
+/// - `sources`: 原文数组
+/// - `outputs`: 译文数组
+/// - `from`: 源语言，None表示自动检测
+/// - `to`: 目标语言
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 是否有任一译文疑似未翻译，严格模式下疑似未翻译时返回错误
+pub(crate) fn check_all(
+    sources: &[String],
+    outputs: &[String],
+    from: Option<Language>,
+    to: &Language,
+    options: &TranslateOptions,
+) -> Result<bool, TranslatorError> {
+    let mut untranslated = false;
+    for (source, output) in sources.iter().zip(outputs) {
+        untranslated |= check(source, output, from, to, options)?;
+    }
+    Ok(untranslated)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::async_translator::Language;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::TranslatorError;
+    use crate::fusion_translator::untranslated::{
+        check, check_all, is_possibly_untranslated, UntranslatedCheck,
+    };
+
+    /// 测试原样返回的句子被标记为疑似未翻译
+    #[test]
+    fn test_untranslated_sentence() {
+        assert!(is_possibly_untranslated(
+            "The quick brown fox",
+            "The  quick brown fox ",
+            Some(Language::English),
+            &Language::Chinese
+        ));
+        assert!(is_possibly_untranslated(
+            "Bonjour le monde",
+            "Bonjour le monde",
+            None,
+            &Language::Chinese
+        ));
+        assert!(!is_possibly_untranslated(
+            "The quick brown fox",
+            "敏捷的棕色狐狸",
+            Some(Language::English),
+            &Language::Chinese
+        ));
+    }
+
+    /// 测试纯数字和符号不会被标记
+    #[test]
+    fn test_numeric_input_not_flagged() {
+        for text in ["12345", "3.14", "2024-01-01", "+86 10 1234 5678", "!!!", ""] {
+            assert!(
+                !is_possibly_untranslated(text, text, Some(Language::English), &Language::Chinese),
+                "{}",
+                text
+            );
+        }
+    }
+
+    /// 测试源语言与目标语言相同时不会被标记
+    #[test]
+    fn test_same_language_not_flagged() {
+        assert!(!is_possibly_untranslated(
+            "hello",
+            "hello",
+            Some(Language::English),
+            &Language::English
+        ));
+    }
+
+    /// 测试规范化后相同的文本被视为未翻译
+    #[test]
+    fn test_nfc_equivalent() {
+        assert!(is_possibly_untranslated(
+            "Cafe\u{301}",
+            "Café",
+            Some(Language::French),
+            &Language::Chinese
+        ));
+    }
+
+    /// 测试检查模式
+    #[test]
+    fn test_check_modes() {
+        let to = Language::Chinese;
+        let off = TranslateOptions {
+            untranslated_check: UntranslatedCheck::Off,
+            ..Default::default()
+        };
+        assert!(!check("hello", "hello", None, &to, &off).unwrap());
+        assert!(check("hello", "hello", None, &to, &TranslateOptions::default()).unwrap());
+
+        let strict = TranslateOptions {
+            untranslated_check: UntranslatedCheck::Strict,
+            ..Default::default()
+        };
+        assert!(matches!(
+            check("hello", "hello", None, &to, &strict),
+            Err(TranslatorError::UntranslatedOutput)
+        ));
+        assert!(!check("42", "42", None, &to, &strict).unwrap());
+
+        let sources = ["one".to_string(), "two".to_string()];
+        let outputs = ["一".to_string(), "two".to_string()];
+        assert!(check_all(&sources, &outputs, None, &to, &TranslateOptions::default()).unwrap());
+        assert!(check_all(&sources, &outputs, None, &to, &strict).is_err());
+    }
+}
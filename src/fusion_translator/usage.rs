@@ -0,0 +1,205 @@
+use crate::fusion_translator::shutdown::Shutdown;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 单个名称（引擎、实验分组等）的用量统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageStats {
+    /// 调用次数
+    pub calls: usize,
+    /// 失败的调用次数
+    pub failures: usize,
+    /// 请求翻译的文本数
+    pub texts: usize,
+    /// 请求翻译的字符数
+    pub chars: usize,
+    /// 所有调用的总耗时
+    pub total_latency: Duration,
+}
+
+impl UsageStats {
+    /// 平均每次调用的耗时，没有调用时为0
+    pub fn mean_latency(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.total_latency / calls,
+            Err(_) => self.total_latency.div_f64(self.calls as f64),
+        }
+    }
+}
+
+/// 用量记录
+///
+/// 按名称累计调用次数、文本量和耗时，可以在多个翻译器之间共享
+///
+/// 统计保存在内存中；通过`load`创建时关联一个JSON文件，`save`或`shutdown`时写入。
+/// 进程被强制终止时丢失上次保存之后的记录
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    /// 名称 -> 用量统计
+    entries: Mutex<BTreeMap<String, UsageStats>>,
+    /// 保存用量的文件，None表示只在内存中统计
+    path: Option<PathBuf>,
+}
+
+impl UsageTracker {
+    /// 创建空的用量记录
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建关联到文件的用量记录
+    ///
+    /// 文件存在时在其中的用量上继续累计，不存在时从零开始，第一次保存时创建
+    ///
+    /// # 参数
+    /// - `path`: 保存用量的JSON文件
+    ///
+    /// # 返回值
+    /// 用量记录；文件无法读取或不是`save`写入的格式时返回错误
+    pub fn load(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            entries: Mutex::new(entries),
+            path: Some(path),
+        })
+    }
+
+    /// 把当前的用量写入关联的文件
+    ///
+    /// 先写入同目录下的临时文件再替换，写入中断不会损坏已有的文件。没有关联文件时不做任何事
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.snapshot())?;
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// 记录一次调用
+    ///
+    /// # 参数
+    /// - `name`: 名称
+    /// - `texts`: 本次调用的文本数
+    /// - `chars`: 本次调用的字符数
+    /// - `latency`: 本次调用的耗时
+    /// - `success`: 调用是否成功
+    pub fn record(&self, name: &str, texts: usize, chars: usize, latency: Duration, success: bool) {
+        let mut entries = self.entries.lock().unwrap();
+        let stats = entries.entry(name.to_string()).or_default();
+        stats.calls += 1;
+        stats.texts += texts;
+        stats.chars += chars;
+        stats.total_latency += latency;
+        if !success {
+            stats.failures += 1;
+        }
+    }
+
+    /// 某个名称的用量，没有记录时为默认值
+    ///
+    /// # 参数
+    /// - `name`: 名称
+    pub fn get(&self, name: &str) -> UsageStats {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 所有名称的用量，按名称排序
+    pub fn snapshot(&self) -> BTreeMap<String, UsageStats> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Shutdown for UsageTracker {
+    /// 保存用量，见`save`
+    ///
+    /// 之后的记录仍会累计，需要再次保存
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::shutdown::Shutdown;
+    use crate::fusion_translator::usage::{UsageStats, UsageTracker};
+    use std::time::Duration;
+
+    /// 测试按名称累计用量和平均耗时
+    #[test]
+    fn test_record() {
+        let tracker = UsageTracker::new();
+        tracker.record("caiyun", 2, 10, Duration::from_millis(100), true);
+        tracker.record("caiyun", 1, 4, Duration::from_millis(300), false);
+        tracker.record("baidu", 1, 3, Duration::from_millis(50), true);
+        let caiyun = tracker.get("caiyun");
+        assert_eq!(
+            caiyun,
+            UsageStats {
+                calls: 2,
+                failures: 1,
+                texts: 3,
+                chars: 14,
+                total_latency: Duration::from_millis(400),
+            }
+        );
+        assert_eq!(caiyun.mean_latency(), Duration::from_millis(200));
+        assert_eq!(tracker.get("youdao").mean_latency(), Duration::ZERO);
+        assert_eq!(
+            tracker.snapshot().keys().collect::<Vec<_>>(),
+            ["baidu", "caiyun"]
+        );
+    }
+
+    /// 测试关闭时保存用量，重新加载后继续累计
+    #[tokio::test]
+    async fn test_shutdown_persists_counters() {
+        let path = std::env::temp_dir().join(format!(
+            "fusion-translator-usage-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let tracker = UsageTracker::load(&path).unwrap();
+        tracker.record("caiyun", 2, 10, Duration::from_millis(100), true);
+        tracker.shutdown().await.unwrap();
+        // 关闭之后、再次保存之前的记录在进程退出时丢失
+        tracker.record("caiyun", 1, 1, Duration::from_millis(1), true);
+        drop(tracker);
+
+        let tracker = UsageTracker::load(&path).unwrap();
+        assert_eq!(tracker.get("caiyun").calls, 1);
+        tracker.record("caiyun", 1, 4, Duration::from_millis(300), false);
+        tracker.shutdown().await.unwrap();
+        assert_eq!(
+            UsageTracker::load(&path).unwrap().get("caiyun"),
+            UsageStats {
+                calls: 2,
+                failures: 1,
+                texts: 3,
+                chars: 14,
+                total_latency: Duration::from_millis(400),
+            }
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
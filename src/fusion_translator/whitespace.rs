@@ -0,0 +1,224 @@
+use crate::fusion_translator::postprocess::is_cjk;
+use crate::fusion_translator::translate_options::TranslateOptions;
+
+/// 拆分文本两端的空白
+///
+/// 全部由空白组成的文本整体视为前导空白
+///
+/// # 参数
+/// - `text`: 待拆分的文本
+///
+/// # 返回值
+/// 前导空白、正文和末尾空白
+pub fn split_boundary(text: &str) -> (&str, &str, &str) {
+    let start = text.len() - text.trim_start().len();
+    let end = start + text[start..].trim_end().len();
+    (&text[..start], &text[start..end], &text[end..])
+}
+
+/// 把原文两端的空白逐字节还原到译文
+///
+/// 译文两端已有的空白会被替换为原文的空白
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 译文
+///
+/// # 返回值
+/// 两端空白与原文一致的译文
+pub fn restore_boundary(source: &str, output: &str) -> String {
+    let (leading, _, trailing) = split_boundary(source);
+    let (_, body, _) = split_boundary(output);
+    format!("{leading}{body}{trailing}")
+}
+
+/// 还原原文中连续的空格
+///
+/// 原文正文包含两个以上连续空格（如按列对齐的文本）、译文不含中日韩文字且没有连续空格，
+/// 并且原文和译文按空格拆分后的单词数相同时，按顺序把译文中的单个空格替换为原文对应位置的空格；
+/// 不满足条件时无法可靠对应，原样返回译文
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 译文
+///
+/// # 返回值
+/// 处理后的译文
+pub fn restore_space_runs(source: &str, output: &str) -> String {
+    let (_, source_body, _) = split_boundary(source);
+    let (leading, body, trailing) = split_boundary(output);
+    if !source_body.contains("  ") || body.contains("  ") || body.chars().any(is_cjk) {
+        return output.to_string();
+    }
+    let source_words = source_body.split(' ').filter(|w| !w.is_empty());
+    if source_words.count() != body.split(' ').count() {
+        return output.to_string();
+    }
+    let mut gaps = source_body
+        .split(|c: char| c != ' ')
+        .filter(|gap| !gap.is_empty());
+    let mut result = String::with_capacity(output.len() + source_body.len());
+    result.push_str(leading);
+    for (i, word) in body.split(' ').enumerate() {
+        if i > 0 {
+            result.push_str(gaps.next().unwrap_or(" "));
+        }
+        result.push_str(word);
+    }
+    result.push_str(trailing);
+    result
+}
+
+/// 按调用选项去掉发送给翻译服务的文本两端的空白
+///
+/// 全部由空白组成的文本原样返回
+///
+/// # 参数
+/// - `text`: 原文
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 实际发送的文本
+pub(crate) fn trim<'a>(text: &'a str, options: &TranslateOptions) -> &'a str {
+    let trimmed = text.trim();
+    if options.preserve_boundary_whitespace && !trimmed.is_empty() {
+        trimmed
+    } else {
+        text
+    }
+}
+
+/// 按调用选项去掉一组文本两端的空白
+///
+/// # 参数
+/// - `query`: 原文数组
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 实际发送的文本数组
+pub(crate) fn trim_all(query: &[String], options: &TranslateOptions) -> Vec<String> {
+    query.iter().map(|q| trim(q, options).to_string()).collect()
+}
+
+/// 按调用选项还原译文的空白
+///
+/// 与`trim`配套使用，`raw_output`开启时同样生效
+///
+/// # 参数
+/// - `source`: 原文
+/// - `output`: 译文
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 还原空白后的译文
+pub(crate) fn restore(source: &str, output: String, options: &TranslateOptions) -> String {
+    if !options.preserve_boundary_whitespace {
+        return output;
+    }
+    restore_boundary(source, &restore_space_runs(source, &output))
+}
+
+/// 按调用选项还原一组译文的空白
+///
+/// 原文与译文数量不一致时无法一一对应，原样返回
+///
+/// # 参数
+/// - `sources`: 原文数组
+/// - `outputs`: 译文数组
+/// - `options`: 本次调用的选项
+///
+/// # 返回值
+/// 还原空白后的译文数组
+pub(crate) fn restore_all(
+    sources: &[String],
+    outputs: Vec<String>,
+    options: &TranslateOptions,
+) -> Vec<String> {
+    if sources.len() != outputs.len() {
+        return outputs;
+    }
+    sources
+        .iter()
+        .zip(outputs)
+        .map(|(source, output)| restore(source, output, options))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::whitespace::{
+        restore, restore_all, restore_boundary, restore_space_runs, split_boundary, trim,
+    };
+
+    /// 测试拆分两端空白
+    #[test]
+    fn test_split_boundary() {
+        assert_eq!(split_boundary("  hello \n"), ("  ", "hello", " \n"));
+        assert_eq!(split_boundary("hello"), ("", "hello", ""));
+        assert_eq!(split_boundary(" \t\n"), (" \t\n", "", ""));
+        assert_eq!(split_boundary(""), ("", "", ""));
+    }
+
+    /// 测试逐字节还原两端空白
+    #[test]
+    fn test_restore_boundary() {
+        assert_eq!(restore_boundary("  hello \n", "你好"), "  你好 \n");
+        assert_eq!(
+            restore_boundary("\u{3000}hi\r\n", " 嗨  "),
+            "\u{3000}嗨\r\n"
+        );
+        assert_eq!(restore_boundary("hello", "\n你好\n"), "你好");
+    }
+
+    /// 测试还原按列对齐的连续空格
+    #[test]
+    fn test_restore_space_runs() {
+        assert_eq!(
+            restore_space_runs("name    value", "nom valeur"),
+            "nom    valeur"
+        );
+        // 单词数不同时无法对应
+        assert_eq!(
+            restore_space_runs("name    value", "le nom valeur"),
+            "le nom valeur"
+        );
+        // 中日韩译文不处理
+        assert_eq!(restore_space_runs("name    value", "名称 值"), "名称 值");
+        // 原文没有连续空格时不处理
+        assert_eq!(restore_space_runs("a b", "x y"), "x y");
+    }
+
+    /// 测试选项关闭时不修改文本
+    #[test]
+    fn test_disabled() {
+        let options = TranslateOptions::new().with_preserve_boundary_whitespace(false);
+        assert_eq!(trim("  hello \n", &options), "  hello \n");
+        assert_eq!(restore("  hello \n", "你好".to_string(), &options), "你好");
+
+        let options = TranslateOptions::default();
+        assert_eq!(trim("  hello \n", &options), "hello");
+        assert_eq!(trim(" \n", &options), " \n");
+        assert_eq!(
+            restore_all(
+                &["  a\n".to_string(), "b".to_string()],
+                vec!["x".to_string(), " y ".to_string()],
+                &options
+            ),
+            vec!["  x\n", "y"]
+        );
+    }
+
+    /// 模糊测试：任意原文和译文的空白还原都不会panic，拆分的三部分拼接后与原文相同
+    #[test]
+    fn test_fuzz_restore() {
+        let inputs = fuzz::inputs(701, &["  Name    Value\t\n", "\u{3000}中文\u{a0}\r\n"]);
+        for (source, output) in inputs.iter().zip(inputs.iter().rev()) {
+            let (leading, body, trailing) = split_boundary(source);
+            assert_eq!(format!("{leading}{body}{trailing}"), *source);
+            restore_boundary(source, output);
+            restore_space_runs(source, output);
+        }
+    }
+}
@@ -1,45 +1,58 @@
 use crate::fusion_translator::async_translator::{
-    AsyncTranslator, Language, TranslationListOutput, TranslationOutput,
+    AsyncTranslator, Language, TranslationDetail, TranslationListOutput, TranslationOutput,
 };
-use crate::fusion_translator::translator_error::TranslatorError;
-use rand::Rng as _;
-use reqwest::{header::CONTENT_TYPE, Client};
+use crate::fusion_translator::batch::{self, empty_batch, validate_batch};
+use crate::fusion_translator::error_context::{self, QueryContext};
+use crate::fusion_translator::language_fallback::LanguageResolver;
+use crate::fusion_translator::language_map::languages_with;
+use crate::fusion_translator::postprocess;
+use crate::fusion_translator::preprocess;
+use crate::fusion_translator::runtime;
+use crate::fusion_translator::scheduled::{Clock, SystemClock};
+use crate::fusion_translator::serde_util::{null_as_default, string_or_number};
+use crate::fusion_translator::translate_options::{with_request_id_header, TranslateOptions};
+use crate::fusion_translator::translator_error::{ApiError, TranslatorError};
+use crate::fusion_translator::translator_factory::TranslatorType;
+use crate::fusion_translator::translator_options::TranslatorOptions;
+use crate::fusion_translator::transport::{self, HttpRequest, HttpTransport};
+use crate::fusion_translator::untranslated;
+use crate::fusion_translator::whitespace;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::time::{SystemTime, UNIX_EPOCH};
-use uuid::{Context, Timestamp, Uuid};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::Instrument;
+
+/// 单文本翻译接口地址
+const API_URL: &str = "https://openapi.youdao.com/api";
 
 /// 有道翻译器实现
 ///
 /// 通过调用有道翻译API实现文本翻译功能
 pub struct YoudaoTranslator {
-    /// HTTP客户端
-    client: reqwest::Client,
+    /// 发送HTTP请求的传输层
+    transport: Arc<dyn HttpTransport>,
     /// 有道开放平台应用ID
     app_key: String,
     /// 有道开放平台应用密钥
     app_secret: String,
-    /// UUID上下文，用于生成唯一请求ID
-    context: Context,
-    /// MAC地址，用于UUID生成
-    mac: [u8; 6],
+    /// 签名使用的时钟
+    clock: Arc<dyn Clock>,
 }
 
-/// 生成随机MAC地址
+/// 获取当前Unix时间
+///
+/// 签名中的`curtime`依赖该时间，所有取时逻辑集中在这里；
+/// 默认的`SystemClock`在wasm32上读取JavaScript的`Date.now()`，测试中可以替换时钟来源
 ///
-/// 用于生成唯一的请求ID，设置的比特位确保生成合法的随机MAC地址
+/// # 参数
+/// - `clock`: 时钟
 ///
 /// # 返回值
-/// 长度为6的字节数组，表示MAC地址
-fn generate_random_mac() -> [u8; 6] {
-    let mut rng = rand::rng();
-    let mut mac = [0u8; 6];
-    rng.fill(&mut mac);
-
-    mac[0] |= 0b00000010;
-    mac[0] &= 0b11111110;
-
-    mac
+/// 距Unix纪元的时长，时钟早于Unix纪元时为0
+fn unix_now(clock: &dyn Clock) -> Duration {
+    clock.now().duration_since(UNIX_EPOCH).unwrap_or_default()
 }
 
 impl YoudaoTranslator {
@@ -52,14 +65,104 @@ impl YoudaoTranslator {
     /// # 返回值
     /// 新的翻译器实例
     pub fn new(app_key: &str, app_secret: &str) -> Self {
-        let seed: u16 = rand::rng().random();
         Self {
-            mac: generate_random_mac(),
-            client: Client::new(),
+            transport: TranslatorOptions::default().build_transport(TranslatorType::Youdao),
             app_key: app_key.to_string(),
             app_secret: app_secret.to_string(),
-            context: Context::new(seed),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 替换签名使用的时钟
+    ///
+    /// 用于在测试中固定`curtime`，得到确定的签名
+    ///
+    /// # 参数
+    /// - `clock`: 时钟
+    #[cfg(test)]
+    pub(crate) fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 按翻译器选项重新创建HTTP客户端
+    ///
+    /// 用于覆盖默认的User-Agent或添加额外的请求头
+    ///
+    /// # 参数
+    /// - `options`: 翻译器选项
+    pub fn with_translator_options(mut self, options: &TranslatorOptions) -> Self {
+        self.transport = options.build_transport(TranslatorType::Youdao);
+        self
+    }
+
+    /// 翻译一个分块
+    ///
+    /// # 参数
+    /// - `query`: 分块中的文本，数量和长度不超过翻译器的批量上限
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_chunk(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationListOutput> {
+        if query.is_empty() {
+            return Ok(empty_batch(options));
         }
+        let mut languages = LanguageResolver::new(options, Language::to_youdao);
+        let from_code = match from {
+            Some(from) => languages.resolve(from)?,
+            None => "auto",
+        };
+        let to_code = languages.resolve(*to)?;
+        let request_id = options.resolve_request_id();
+        let span = tracing::debug_span!("translate", backend = "youdao", request_id = request_id);
+        let request = Request {
+            from: from_code,
+            to: to_code,
+            request_id: request_id.as_deref(),
+        };
+
+        let sent = whitespace::trim_all(query, options);
+        let text = if let [single] = sent.as_slice() {
+            let request = self.request_single(single, &request).instrument(span);
+            vec![options.run_cancellable(0, request).await?]
+        } else {
+            let batch = self.request_batch(&sent, &request).instrument(span.clone());
+            let mut text = options.run_cancellable(0, batch).await?;
+            // 批量接口中失败的条目逐条改用单条接口重试；每次重试前检查取消，
+            // 取消时报告已得到译文的条目数
+            let mut completed = text.iter().flatten().count();
+            for (source, item) in sent.iter().zip(text.iter_mut()) {
+                if item.is_none() {
+                    let retry = self
+                        .request_single(source, &request)
+                        .instrument(span.clone());
+                    *item = Some(options.run_cancellable(completed, retry).await?);
+                    completed += 1;
+                }
+            }
+            text.into_iter().flatten().collect()
+        };
+        let text = postprocess::apply_all(query, validate_batch(query.len(), text)?, options);
+        let possibly_untranslated = untranslated::check_all(query, &text, from, to, options)?;
+        Ok(TranslationListOutput {
+            text,
+            lang: None,
+            detail: TranslationDetail {
+                request_id,
+                substitutions: languages.into_substitutions(),
+                possibly_untranslated,
+                ..Default::default()
+            },
+        })
     }
 }
 
@@ -80,7 +183,8 @@ fn sha256_encode(sign_str: &str) -> String {
     hex::encode(result)
 }
 
-#[async_trait::async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 impl AsyncTranslator for YoudaoTranslator {
     /// 判断是否为本地翻译器
     ///
@@ -89,6 +193,20 @@ impl AsyncTranslator for YoudaoTranslator {
         false
     }
 
+    async fn warm_up(&self) {
+        transport::warm_up(&*self.transport, "youdao", API_URL).await
+    }
+
+    /// 支持的语言
+    fn supported_languages(&self) -> Option<Vec<Language>> {
+        Some(languages_with(Language::to_youdao))
+    }
+
+    /// 按请求量计费
+    fn paid(&self) -> bool {
+        true
+    }
+
     /// 翻译单个文本
     ///
     /// # 参数
@@ -104,118 +222,515 @@ impl AsyncTranslator for YoudaoTranslator {
         from: Option<Language>,
         to: &Language,
     ) -> anyhow::Result<TranslationOutput> {
-        let mut t = self.translate_vec(&[query.to_owned()], from, to).await?;
+        self.translate_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 翻译多个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    ///
+    /// # 返回值
+    /// 翻译结果列表
+    async fn translate_vec(
+        &self,
+        query: &[String],
+        from: Option<Language>,
+        to: &Language,
+    ) -> anyhow::Result<TranslationListOutput> {
+        self.translate_vec_with_options(query, from, to, &TranslateOptions::default())
+            .await
+    }
+
+    /// 按指定选项翻译单个文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `from`: 源语言，None表示自动检测
+    /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
+    ///
+    /// # 返回值
+    /// 翻译结果
+    async fn translate_with_options(
+        &self,
+        query: &str,
+        from: Option<Language>,
+        to: &Language,
+        options: &TranslateOptions,
+    ) -> anyhow::Result<TranslationOutput> {
+        let mut t = self
+            .translate_vec_with_options(&[query.to_owned()], from, to, options)
+            .await?;
         Ok(TranslationOutput {
             text: t.text.remove(0),
             lang: Some(*to),
+            detail: t.detail,
         })
     }
 
-    /// 翻译多个文本
+    /// 按指定选项翻译多个文本
+    ///
+    /// 请求ID通过`X-Request-Id`请求头传递
     ///
     /// # 参数
     /// - `query`: 待翻译的文本数组
     /// - `from`: 源语言，None表示自动检测
     /// - `to`: 目标语言
+    /// - `options`: 本次调用的选项
     ///
     /// # 返回值
     /// 翻译结果列表
-    async fn translate_vec(
+    async fn translate_vec_with_options(
         &self,
         query: &[String],
         from: Option<Language>,
         to: &Language,
+        options: &TranslateOptions,
     ) -> anyhow::Result<TranslationListOutput> {
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let curtime = now.as_secs();
-        let nanos = now.subsec_nanos();
-        let ts = Timestamp::from_unix(&self.context, curtime, nanos);
-        let salt = Uuid::new_v1(ts, &self.mac).to_string();
-        let query = query.join("\n");
-        let sign_str = format!(
+        let options = options.resolved();
+        let query = &*preprocess::apply_all(query, &options)?;
+        let result = batch::translate_in_chunks(
+            query,
+            self.max_batch_len(),
+            self.input_limit(),
+            0,
+            |chunk| self.translate_chunk(chunk, from, to, &options),
+        )
+        .await;
+        error_context::with_query_context(result, || {
+            QueryContext::batch("youdao", query, from, to, &options)
+        })
+    }
+}
+
+/// 单次请求的公共参数
+struct Request<'a> {
+    /// 源语言代码
+    from: &'a str,
+    /// 目标语言代码
+    to: &'a str,
+    /// 请求ID
+    request_id: Option<&'a str>,
+}
+
+impl YoudaoTranslator {
+    /// 生成签名参数
+    ///
+    /// `salt`为随机UUID，不依赖共享的计数器，多个任务同时调用时各自生成
+    ///
+    /// # 参数
+    /// - `input`: 参与签名的原文，批量接口为所有`q`按顺序拼接后的文本
+    ///
+    /// # 返回值
+    /// （salt, curtime, sign）
+    fn sign(&self, input: &str) -> (String, String, String) {
+        let curtime = unix_now(self.clock.as_ref()).as_secs().to_string();
+        let salt = uuid::Builder::from_random_bytes(runtime::random_bytes())
+            .into_uuid()
+            .to_string();
+        let sign = self.sign_with(input, &salt, &curtime);
+        (salt, curtime, sign)
+    }
+
+    /// 按给定的salt和curtime计算签名
+    ///
+    /// # 参数
+    /// - `input`: 参与签名的原文
+    /// - `salt`: 随机数
+    /// - `curtime`: Unix时间戳（秒）
+    ///
+    /// # 返回值
+    /// 十六进制的SHA256签名
+    fn sign_with(&self, input: &str, salt: &str, curtime: &str) -> String {
+        sha256_encode(&format!(
             "{}{}{}{}{}",
             self.app_key,
-            truncate(&query),
+            truncate(input),
             salt,
             curtime,
             self.app_secret
-        );
-        let from = match from {
-            Some(from) => from
-                .to_youdao()
-                .ok_or(TranslatorError::UnknownLanguage(from))?,
-            None => "auto",
-        };
-        let data: Resp = self
-            .client
-            .post("https://openapi.youdao.com/api")
-            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
-            .form(&[
-                ("from", from),
-                (
-                    "to",
-                    to.to_youdao()
-                        .ok_or(TranslatorError::UnknownLanguage(*to))?,
-                ),
-                ("signType", "v3"),
-                ("curtime", &curtime.to_string()),
-                ("appKey", self.app_key.as_str()),
-                ("q", query.as_str()),
-                ("salt", salt.as_str()),
-                ("sign", &sha256_encode(&sign_str)),
-            ])
-            .send()
-            .await?
-            .json()
-            .await?;
-        Ok(TranslationListOutput {
-            text: data
-                .translation
-                .into_iter()
-                .flat_map(|v| v.split("/n").map(|v| v.to_owned()).collect::<Vec<String>>())
-                .collect::<Vec<String>>(),
-            lang: None,
-        })
+        ))
+    }
+
+    /// 调用单条文本翻译接口
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本
+    /// - `request`: 公共参数
+    ///
+    /// # 返回值
+    /// 译文
+    async fn request_single(&self, query: &str, request: &Request<'_>) -> anyhow::Result<String> {
+        let (salt, curtime, sign) = self.sign(query);
+        let form = [
+            ("from", request.from),
+            ("to", request.to),
+            ("signType", "v3"),
+            ("curtime", curtime.as_str()),
+            ("appKey", self.app_key.as_str()),
+            ("q", query),
+            ("salt", salt.as_str()),
+            ("sign", sign.as_str()),
+        ];
+        let http_request =
+            with_request_id_header(HttpRequest::post(API_URL), request.request_id).form(&form)?;
+        let data: SingleResp = self.transport.execute(http_request).await?.json()?;
+        Ok(data.into_text()?)
+    }
+
+    /// 调用批量文本翻译接口
+    ///
+    /// 每个文本作为一个`q`参数发送，签名使用所有`q`按顺序拼接后的文本
+    ///
+    /// # 参数
+    /// - `query`: 待翻译的文本数组
+    /// - `request`: 公共参数
+    ///
+    /// # 返回值
+    /// 与原文顺序一致的译文，批量接口中翻译失败的条目为None
+    async fn request_batch(
+        &self,
+        query: &[String],
+        request: &Request<'_>,
+    ) -> anyhow::Result<Vec<Option<String>>> {
+        let (salt, curtime, sign) = self.sign(&query.concat());
+        let mut form = vec![
+            ("from", request.from),
+            ("to", request.to),
+            ("signType", "v3"),
+            ("curtime", curtime.as_str()),
+            ("appKey", self.app_key.as_str()),
+            ("salt", salt.as_str()),
+            ("sign", sign.as_str()),
+        ];
+        form.extend(query.iter().map(|q| ("q", q.as_str())));
+        let http_request = with_request_id_header(
+            HttpRequest::post("https://openapi.youdao.com/v2/api"),
+            request.request_id,
+        )
+        .form(&form)?;
+        let data: BatchResp = self.transport.execute(http_request).await?.json()?;
+        Ok(data.into_ordered(query)?)
     }
 }
 
 /// API响应结构
 ///
-/// 包含翻译结果列表
+/// 包含错误码和翻译结果列表
 #[derive(Deserialize)]
 #[allow(dead_code)]
-pub struct Resp {
-    /// 翻译结果列表
+struct SingleResp {
+    /// 错误码，`0`表示成功，可能是字符串或数字
+    #[serde(rename = "errorCode", default, deserialize_with = "string_or_number")]
+    error_code: String,
+    /// 翻译结果列表，出错时缺失或为null
+    #[serde(default, deserialize_with = "null_as_default")]
     translation: Vec<String>,
 }
 
+impl SingleResp {
+    /// 取出译文
+    ///
+    /// # 返回值
+    /// 多行译文以换行拼接；错误码不为`0`时返回`ApiError::Youdao`
+    fn into_text(self) -> Result<String, TranslatorError> {
+        if !self.error_code.is_empty() && self.error_code != "0" {
+            return Err(TranslatorError::ApiError(ApiError::Youdao {
+                code: self.error_code,
+            }));
+        }
+        Ok(self.translation.join("\n"))
+    }
+}
+
+/// 批量接口响应结构
+#[derive(Deserialize)]
+struct BatchResp {
+    /// 错误码，`0`表示成功，可能是字符串或数字
+    #[serde(rename = "errorCode", deserialize_with = "string_or_number")]
+    error_code: String,
+    /// 翻译成功的条目，不保证与请求顺序一致，出错时缺失或为null
+    #[serde(
+        rename = "translateResults",
+        default,
+        deserialize_with = "null_as_default"
+    )]
+    translate_results: Vec<BatchItem>,
+}
+
+/// 批量接口中的单条翻译结果
+#[derive(Deserialize)]
+struct BatchItem {
+    /// 原文
+    query: String,
+    /// 译文
+    translation: String,
+}
+
+impl BatchResp {
+    /// 按原文顺序整理译文
+    ///
+    /// 接口不保证返回顺序，按`query`字段与原文对应；相同的原文按出现顺序依次对应
+    ///
+    /// # 参数
+    /// - `sources`: 请求的原文
+    ///
+    /// # 返回值
+    /// 与原文顺序一致的译文，接口未返回的条目为None；错误码不为`0`时返回`ApiError::Youdao`
+    fn into_ordered(self, sources: &[String]) -> Result<Vec<Option<String>>, TranslatorError> {
+        if self.error_code != "0" {
+            return Err(TranslatorError::ApiError(ApiError::Youdao {
+                code: self.error_code,
+            }));
+        }
+        let mut by_query: HashMap<String, VecDeque<String>> = HashMap::new();
+        for item in self.translate_results {
+            by_query
+                .entry(item.query)
+                .or_default()
+                .push_back(item.translation);
+        }
+        Ok(sources
+            .iter()
+            .map(|source| by_query.get_mut(source).and_then(VecDeque::pop_front))
+            .collect())
+    }
+}
+
 /// 文本截断处理
 ///
 /// 根据有道翻译API的要求，对过长的文本进行截断处理
-/// 规则：文本长度不超过20时保持原样，否则保留前10个字符、中间长度信息和后10个字符
+/// 规则：文本长度（按字符计）不超过20时保持原样，否则保留前10个字符、中间长度信息和后10个字符
 ///
 /// # 参数
 /// - `s`: 待处理的文本
 ///
 /// # 返回值
 /// 处理后的文本
-#[allow(dead_code)]
 fn truncate(s: &str) -> String {
-    let size = s.len();
+    let size = s.chars().count();
     if size <= 20 {
         s.to_string()
     } else {
-        let start = &s[..10];
-        let end = &s[size - 10..];
+        let start = s.chars().take(10).collect::<String>();
+        let end = s.chars().skip(size - 10).collect::<String>();
         format!("{}{}{}", start, size, end)
     }
 }
 
+#[cfg(all(test, feature = "live-schema-check"))]
+impl YoudaoTranslator {
+    /// 发送一次真实请求，返回原始响应用于检查响应结构
+    ///
+    /// # 返回值
+    /// （对应的响应样例名称，响应JSON）
+    pub(crate) async fn fetch_sample(&self) -> anyhow::Result<(&'static str, serde_json::Value)> {
+        let query = "hello";
+        let (salt, curtime, sign) = self.sign(query);
+        let form = [
+            ("from", "en"),
+            ("to", "zh-CHS"),
+            ("signType", "v3"),
+            ("curtime", curtime.as_str()),
+            ("appKey", self.app_key.as_str()),
+            ("q", query),
+            ("salt", salt.as_str()),
+            ("sign", sign.as_str()),
+        ];
+        let request = HttpRequest::post(API_URL).form(&form)?;
+        let body = self.transport.execute(request).await?;
+        Ok(("success.json", body.json()?))
+    }
+}
+
+/// 按当前的响应结构解析响应样例
+///
+/// # 参数
+/// - `name`: 样例文件名
+/// - `body`: 样例内容
+#[cfg(test)]
+pub(crate) fn parse_fixture(name: &str, body: &str) -> anyhow::Result<()> {
+    match name {
+        "batch_success.json"
+        | "batch_partial.json"
+        | "batch_error.json"
+        | "batch_error_numeric.json" => {
+            serde_json::from_str::<BatchResp>(body)?;
+        }
+        "success.json" | "error_numeric_code.json" => {
+            serde_json::from_str::<SingleResp>(body)?;
+        }
+        _ => anyhow::bail!("未知的响应样例：{}", name),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::fusion_translator::async_translator::{AsyncTranslator as _, Language};
-    use crate::fusion_translator::youdao_translator::{sha256_encode, truncate, YoudaoTranslator};
+    use crate::fusion_translator::scheduled::MockClock;
+    use crate::fusion_translator::test_support::fuzz;
+    use crate::fusion_translator::translate_options::TranslateOptions;
+    use crate::fusion_translator::translator_error::{ApiError, TranslatorError};
+    use crate::fusion_translator::translator_options::TranslatorOptions;
+    use crate::fusion_translator::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use crate::fusion_translator::youdao_translator::{
+        sha256_encode, truncate, BatchResp, SingleResp, YoudaoTranslator,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+    use tokio_util::sync::CancellationToken;
+
+    /// 测试批量接口结果按原文顺序整理
+    ///
+    /// 示例中3条文本的第2条翻译失败，且返回顺序与请求顺序不同
+    #[test]
+    fn test_batch_response_ordering() {
+        let resp: BatchResp = serde_json::from_str(include_str!(
+            "../../tests/fixtures/youdao/batch_partial.json"
+        ))
+        .unwrap();
+        let sources = ["good morning", "thank you", "see you tomorrow"].map(String::from);
+        assert_eq!(
+            resp.into_ordered(&sources).unwrap(),
+            vec![Some("早上好".to_string()), None, Some("明天见".to_string())]
+        );
+    }
+
+    /// 测试逐条重试批量接口中失败的条目时检查取消：取消后不再发起请求，
+    /// 报告的条目数包括批量接口和已完成的重试得到的译文
+    #[tokio::test]
+    async fn test_cancel_between_retries() {
+        /// 批量接口只翻译偶数位置的条目，单条接口在第一次请求时取消令牌
+        struct CancellingTransport {
+            token: CancellationToken,
+            singles: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl HttpTransport for CancellingTransport {
+            async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+                let body = if request.url.ends_with("/v2/api") {
+                    serde_json::json!({
+                        "errorCode": "0",
+                        "errorIndex": [1, 3],
+                        "translateResults": [
+                            {"query": "a", "translation": "A"},
+                            {"query": "c", "translation": "C"},
+                        ]
+                    })
+                } else {
+                    self.singles.fetch_add(1, Ordering::SeqCst);
+                    self.token.cancel();
+                    serde_json::json!({"errorCode": "0", "translation": ["B"]})
+                };
+                Ok(HttpResponse::new(200, body.to_string()))
+            }
+        }
+
+        let token = CancellationToken::new();
+        let transport = Arc::new(CancellingTransport {
+            token: token.clone(),
+            singles: AtomicUsize::new(0),
+        });
+        let translator = YoudaoTranslator::new("app", "secret")
+            .with_translator_options(&TranslatorOptions::new().with_transport(transport.clone()));
+        let query = ["a", "b", "c", "d"].map(String::from);
+        let err = translator
+            .translate_vec_with_options(
+                &query,
+                Some(Language::English),
+                &Language::Chinese,
+                &TranslateOptions::new().with_cancellation(token),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(3))
+        ));
+        assert_eq!(transport.singles.load(Ordering::SeqCst), 1);
+    }
+
+    /// 测试批量接口中重复的原文按出现顺序对应
+    #[test]
+    fn test_batch_response_duplicates() {
+        let resp: BatchResp = serde_json::from_value(serde_json::json!({
+            "errorCode": "0",
+            "translateResults": [
+                {"query": "yes", "translation": "是"},
+                {"query": "no", "translation": "不"},
+                {"query": "yes", "translation": "是的"},
+            ]
+        }))
+        .unwrap();
+        let sources = ["yes", "no", "yes"].map(String::from);
+        assert_eq!(
+            resp.into_ordered(&sources).unwrap(),
+            vec![
+                Some("是".to_string()),
+                Some("不".to_string()),
+                Some("是的".to_string())
+            ]
+        );
+    }
+
+    /// 测试批量接口的错误码
+    #[test]
+    fn test_batch_response_error() {
+        let resp: BatchResp =
+            serde_json::from_str(include_str!("../../tests/fixtures/youdao/batch_error.json"))
+                .unwrap();
+        assert!(matches!(
+            resp.into_ordered(&["a".to_string()]),
+            Err(TranslatorError::ApiError(ApiError::Youdao { code })) if code == "202"
+        ));
+    }
+
+    /// 测试数字错误码和null结果
+    #[test]
+    fn test_numeric_error_code() {
+        let resp: BatchResp = serde_json::from_str(include_str!(
+            "../../tests/fixtures/youdao/batch_error_numeric.json"
+        ))
+        .unwrap();
+        assert!(matches!(
+            resp.into_ordered(&["a".to_string()]),
+            Err(TranslatorError::ApiError(ApiError::Youdao { code })) if code == "202"
+        ));
+
+        let resp: SingleResp = serde_json::from_str(include_str!(
+            "../../tests/fixtures/youdao/error_numeric_code.json"
+        ))
+        .unwrap();
+        assert!(matches!(
+            resp.into_text(),
+            Err(TranslatorError::ApiError(ApiError::Youdao { code })) if code == "108"
+        ));
+
+        let resp: SingleResp =
+            serde_json::from_str(r#"{"errorCode": "0", "translation": ["你好", "世界"]}"#).unwrap();
+        assert_eq!(resp.into_text().unwrap(), "你好\n世界");
+    }
+
+    /// 测试批量签名的输入
+    ///
+    /// 签名使用所有`q`拼接后的文本，长度按字符计算
+    #[test]
+    fn test_batch_sign_input() {
+        let sources = ["good morning", "thank you", "see you tomorrow"].map(String::from);
+        assert_eq!(truncate(&sources.concat()), "good morni37u tomorrow");
+        assert_eq!(
+            truncate("今天天气很好，我们一起去公园散步吧，好不好呀"),
+            "今天天气很好，我们一22公园散步吧，好不好呀"
+        );
+    }
 
     /// 测试翻译器实例创建
     ///
@@ -225,7 +740,6 @@ mod tests {
         let translator = YoudaoTranslator::new("test_app_key", "test_app_secret");
         assert_eq!(translator.app_key, "test_app_key");
         assert_eq!(translator.app_secret, "test_app_secret");
-        assert_eq!(translator.mac.len(), 6);
     }
 
     /// 测试翻译器字段访问
@@ -247,13 +761,42 @@ mod tests {
         assert!(!translator.local());
     }
 
-    /// 测试翻译器MAC地址生成
-    ///
-    /// 验证MAC地址的长度是否符合预期
-    #[tokio::test]
-    async fn test_translator_mac() {
-        let translator = YoudaoTranslator::new("key", "secret");
-        assert_eq!(translator.mac.len(), 6);
+    /// 测试100个任务同时生成10000个salt时互不相同
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_salts() {
+        let translator = std::sync::Arc::new(YoudaoTranslator::new("key", "secret"));
+        let tasks = (0..100)
+            .map(|_| {
+                let translator = translator.clone();
+                tokio::spawn(async move {
+                    (0..100)
+                        .map(|_| translator.sign("hello").0)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut salts = std::collections::HashSet::new();
+        for task in tasks {
+            for salt in task.await.unwrap() {
+                assert_eq!(uuid::Uuid::parse_str(&salt).unwrap().get_version_num(), 4);
+                salts.insert(salt);
+            }
+        }
+        assert_eq!(salts.len(), 10_000);
+    }
+
+    /// 测试固定时钟时curtime和签名是确定的
+    #[test]
+    fn test_sign_with_fixed_clock() {
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        let translator = YoudaoTranslator::new("key", "secret").with_clock(Arc::new(clock));
+        let (salt, curtime, sign) = translator.sign("hello");
+        assert_eq!(curtime, "1700000000");
+        assert_eq!(sign, translator.sign_with("hello", &salt, &curtime));
+        assert_eq!(
+            translator.sign_with("hello", "salt", &curtime),
+            "5bb658e82f4a41cd89ee7e07bcf9436cbb42d5020e1a7272513a76d4b66f6dd5"
+        );
     }
 
     /// 测试SHA256编码功能
@@ -353,7 +896,7 @@ mod tests {
             "yo", "yua", "yue", "zu",
         ];
 
-        assert!(langs.len() > 0);
+        assert!(!langs.is_empty());
         for code in langs {
             Language::from_youdao(code).expect(code);
         }
@@ -376,7 +919,7 @@ mod tests {
             "yo", "yua", "yue", "zu",
         ];
 
-        assert!(langs.len() > 0);
+        assert!(!langs.is_empty());
         for code in langs {
             Language::from_youdao(code).expect(code);
         }
@@ -423,4 +966,19 @@ mod tests {
         assert!(!result.text.is_empty());
         println!("英译中结果: {}", result.text);
     }
+
+    /// 模糊测试：任意文本的截断都不会panic，结果不超过20个字符加长度信息
+    #[test]
+    fn test_fuzz_truncate() {
+        for input in fuzz::inputs(701, &["Hello, world! This is a long text.", "👨‍👩‍👧é中文"])
+        {
+            let size = input.chars().count();
+            let truncated = truncate(&input);
+            if size <= 20 {
+                assert_eq!(truncated, input);
+            } else {
+                assert_eq!(truncated.chars().count(), 20 + size.to_string().len());
+            }
+        }
+    }
 }
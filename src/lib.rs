@@ -1 +1,4 @@
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("编译到wasm32时需要启用`wasm`特性：--no-default-features --features wasm");
+
 pub mod fusion_translator;
@@ -1,14 +1,1323 @@
-pub mod fusion_translator;
+use fusion_translator::fusion_translator::async_translator::{
+    AlternativeTranslation, AsyncTranslator, Language, TranslationOutput,
+};
+use fusion_translator::fusion_translator::batch::{
+    plan_map, translate_map_with, BatchOptions, CheckpointedBatch, MapTranslation, TranslationPlan,
+};
+use fusion_translator::fusion_translator::capability::capabilities;
+use fusion_translator::fusion_translator::code_style::{with_style, CodeStyle};
+use fusion_translator::fusion_translator::compare::{compare_all, pairwise_differences};
+use fusion_translator::fusion_translator::config::{ConfigResolver, PartialConfig};
+use fusion_translator::fusion_translator::jsonl::JsonlWriter;
+use fusion_translator::fusion_translator::language_map::{language_coverage, CoverageReport};
+use fusion_translator::fusion_translator::preprocess::{normalize_input, InputNormalization};
+use fusion_translator::fusion_translator::quality::{
+    verify_translations, DEFAULT_SIMILARITY_THRESHOLD,
+};
+use fusion_translator::fusion_translator::shutdown::Shutdown;
+use fusion_translator::fusion_translator::terminology::{
+    export_glossary, extract, TerminologyOptions,
+};
+use fusion_translator::fusion_translator::text_encoding::{decode, InputEncoding};
+use fusion_translator::fusion_translator::tmx::TranslationMemory;
+use fusion_translator::fusion_translator::translate_options::TranslateOptions;
+use fusion_translator::fusion_translator::translator_error::TranslatorError;
+use fusion_translator::fusion_translator::translator_factory::{
+    TranslatorFactory, TranslatorType, UnknownTranslatorType,
+};
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+/// 命令行用法
+const USAGE: &str = "\
+Usage: fusion-translator compare <text> --to <lang> [--from <lang>] [--engines <a,b,...>] [--json]
+       fusion-translator translate [--engine <name>] [--config <file>] --to <lang> [--from <lang>] [--input <file>]
+                                   [--checkpoint <file>] [--encoding <label>] [--preserve-encoding] [--verify] [--verify-threshold <n>]
+                                   [--normalize-input] [--straight-quotes] [--stream] [--dry-run] [--price <n>] [--explain]
+                                   [--output-format <text|jsonl>] [--alternatives <n>]
+       fusion-translator translate [--engine <name>] [--config <file>] --explain-config
+       fusion-translator languages --engine <name> [--missing] [--json] [--lang-style <style>]
+       fusion-translator capabilities [--engines <a,b,...>] [--from <lang>] [--to <lang>] [--json]
+                                      [--lang-style <style>]
+       fusion-translator terms extract --input <file> [--from <lang>] [--to <lang>] [--min-frequency <n>]
+                                       [--min-consistency <n>] [--max-ngram <n>] [--json]
+
+Commands:
+  compare    Translate <text> with every configured engine and compare the results
+  translate  Translate every line of <file> (or stdin) and print one translation per line
+  languages  Show which languages an engine's language table maps, and documented codes it cannot map
+  capabilities
+             Show the languages and request limits of every configured engine; with --to,
+             also list the engines that can translate the language pair
+  terms extract
+             Extract candidate term pairs from a TMX file or a JSONL file of {\"source\": ..., \"target\": ...}
+             lines and print them as a tab-separated glossary
+
+Options:
+  --to <lang>          Target language (ISO 639-1 or 639-3 code)
+  --from <lang>        Source language, defaults to auto detection
+  --engines <a,b,...>  Only use these engines
+  --json               Print the results as JSON
+  --engine <name>      Engine used by translate, or engine/language table inspected by languages;
+                       translate falls back to FUSION_TRANSLATOR_ENGINE or the config file
+  --config <file>      JSON or TOML (*.toml) config file for translate, defaults to FUSION_TRANSLATOR_CONFIG; settings are
+                       taken from command line options, then environment variables, then this file
+  --explain-config     Print the resolved translate settings and where each one came from, then exit
+  --input <file>       Read lines from <file> instead of stdin
+  --checkpoint <file>  Record progress in <file> and resume from it when rerun; on Ctrl-C the chunk
+                       in progress is finished and saved before exiting
+  --encoding <label>   Input encoding such as gbk, big5 or shift_jis, defaults to auto detection
+  --preserve-encoding  Write the output in the input encoding instead of UTF-8
+  --verify             Back-translate each line and warn when it drifts from the input
+  --verify-threshold <n>
+                       Similarity (0 to 1) below which --verify warns, defaults to 0.5
+  --normalize-input    Clean pasted text before sending it: no-break spaces, soft hyphens,
+                       zero-width characters, Windows-1252 punctuation and Unicode NFC
+  --straight-quotes    Also turn curly quotes into straight quotes (implies --normalize-input)
+  --stream             Print each translation as it is generated when the engine supports it;
+                       cannot be combined with --checkpoint or --preserve-encoding
+  --dry-run            Print how many lines, characters and requests translate would send, then exit
+                       without calling the engine
+  --price <n>          Price per million characters used by --dry-run to estimate the cost
+  --explain            Print to stderr how each line was produced: cache hits, retries, fallback engines
+                       and chunking; cannot be combined with --checkpoint or --stream
+  --output-format <f>  text (default) prints one translation per line; jsonl prints one JSON object per
+                       line with id (line number), src, tgt, src_lang, tgt_lang, engine and ts, or
+                       id, error_kind and message for lines that failed; jsonl cannot be combined with
+                       --stream, --preserve-encoding, --verify or --explain
+  --alternatives <n>   Print up to <n> alternative translations (text, score and source) under each line;
+                       lines are translated one request at a time, and this cannot be combined with
+                       --checkpoint, --stream, --preserve-encoding or --output-format jsonl
+  --missing            Only list languages without a code
+  --lang-style <style> Language codes in JSON output: bcp47 (zh-CN, default), lower_underscore (zh_cn),
+                       upper_iso (ZH) or vendor:<name> (the engine's own codes)
+  --min-frequency <n>  Minimum number of sentence pairs a term appears in, defaults to 2
+  --min-consistency <n>
+                       Minimum share (0 to 1) of those pairs whose translation contains the same target,
+                       defaults to 0.6
+  --max-ngram <n>      Longest term in words (twice as many characters for Chinese and Japanese), defaults to 3";
+
+/// 完整的命令行用法
+///
+/// 在`USAGE`之后列出每个引擎可用的名称，由`TranslatorType`的别名表生成
+fn usage() -> String {
+    let engines = TranslatorType::all()
+        .iter()
+        .map(|engine| {
+            let line = format!(
+                "  {:<20} {}",
+                engine.as_str(),
+                engine.aliases().skip(1).collect::<Vec<_>>().join(", ")
+            );
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}\n\nEngines (names are case-insensitive):\n{}",
+        USAGE, engines
+    )
+}
+
+/// 引擎名称及对应的翻译器
+type Engines = Vec<(String, Arc<dyn AsyncTranslator>)>;
+
+/// 子命令
+enum Command {
+    /// 多引擎比较
+    Compare(CompareArgs),
+    /// 逐行翻译
+    Translate(TranslateArgs),
+    /// 显示`translate`使用的配置及来源
+    ExplainConfig(ConfigResolver),
+    /// 语言代码表覆盖情况
+    Languages(LanguagesArgs),
+    /// 引擎能力矩阵
+    Capabilities(CapabilitiesArgs),
+    /// 术语提取
+    Terms(TermsArgs),
+}
+
+/// `compare`子命令的参数
+struct CompareArgs {
+    /// 待翻译的文本
+    text: String,
+    /// 源语言，None表示自动检测
+    from: Option<Language>,
+    /// 目标语言
+    to: Language,
+    /// 指定的引擎，None表示所有已配置的引擎
+    engines: Option<Vec<TranslatorType>>,
+    /// 是否输出JSON
+    json: bool,
+}
+
+/// `translate`子命令的参数
+struct TranslateArgs {
+    /// 引擎及其配置，命令行参数作为显式参数
+    config: ConfigResolver,
+    /// 源语言，None表示自动检测
+    from: Option<Language>,
+    /// 目标语言
+    to: Language,
+    /// 输入文件，None表示读取标准输入
+    input: Option<String>,
+    /// 检查点文件
+    checkpoint: Option<String>,
+    /// 输入编码
+    encoding: InputEncoding,
+    /// 是否按输入编码输出
+    preserve_encoding: bool,
+    /// 回译检查的相似度阈值，None表示不检查
+    verify: Option<f32>,
+    /// 原文规范化配置，None表示不规范化
+    normalize_input: Option<InputNormalization>,
+    /// 是否在引擎支持时逐段输出译文
+    stream: bool,
+    /// 是否只输出翻译计划而不翻译
+    dry_run: bool,
+    /// 估算费用使用的每百万字符价格
+    price: Option<f64>,
+    /// 是否输出每行译文的来历
+    explain: bool,
+    /// 输出格式
+    output_format: OutputFormat,
+    /// 每行输出的候选译文数量，0表示不输出
+    alternatives: usize,
+}
+
+/// `translate`子命令的输出格式
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// 每行一个译文
+    Text,
+    /// 每行一个`jsonl::JsonlRecord`
+    Jsonl,
+}
+
+/// `capabilities`子命令的参数
+struct CapabilitiesArgs {
+    /// 指定的引擎，None表示所有已配置的引擎
+    engines: Option<Vec<TranslatorType>>,
+    /// 查询的源语言，None表示自动检测
+    from: Option<Language>,
+    /// 查询的目标语言，None表示不查询语言对
+    to: Option<Language>,
+    /// 是否输出JSON
+    json: bool,
+    /// JSON中语言代码的格式
+    lang_style: CodeStyle,
+}
+
+/// `languages`子命令的参数
+struct LanguagesArgs {
+    /// 语言代码表名称
+    table: String,
+    /// 是否只列出缺失的语言
+    missing: bool,
+    /// 是否输出JSON
+    json: bool,
+    /// JSON中语言代码的格式
+    lang_style: CodeStyle,
+}
+
+/// `terms extract`子命令的参数
+struct TermsArgs {
+    /// 输入文件，扩展名为`.tmx`时按TMX读取，否则按JSONL读取
+    input: String,
+    /// TMX中的源语言，None表示记忆库中唯一的语言对
+    from: Option<Language>,
+    /// TMX中的目标语言
+    to: Option<Language>,
+    /// 提取选项
+    options: TerminologyOptions,
+    /// 是否输出JSON
+    json: bool,
+}
 
-use crate::fusion_translator::async_translator::Language;
-use crate::fusion_translator::translator_factory::TranslatorFactory;
-use crate::fusion_translator::translator_factory::TranslatorType;
 #[tokio::main]
-async fn main() {
-    //测试阿里翻译器
-    let translator = TranslatorFactory::create_from_type(TranslatorType::Alibaba, "", "");
-    let translate_result = translator
-        .translate("test", Some(Language::English), &Language::Chinese)
-        .await;
-    println!("{:?}", translate_result);
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("compare") => parse_compare(&args[1..]).map(Command::Compare),
+        Some("translate") => parse_translate(&args[1..]),
+        Some("languages") => parse_languages(&args[1..]).map(Command::Languages),
+        Some("capabilities") => parse_capabilities(&args[1..]).map(Command::Capabilities),
+        Some("terms") => parse_terms(&args[1..]).map(Command::Terms),
+        Some("-h" | "--help") => {
+            println!("{}", usage());
+            return ExitCode::SUCCESS;
+        }
+        Some(other) => Err(format!("unknown command: {}", other)),
+        None => Err("missing command".to_string()),
+    };
+    let command = match result {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, usage());
+            return ExitCode::from(2);
+        }
+    };
+    let result = match command {
+        Command::Compare(args) => compare(args).await,
+        Command::Translate(args) => translate(args).await,
+        Command::ExplainConfig(config) => explain_config(&config),
+        Command::Languages(args) => languages(args),
+        Command::Capabilities(args) => capability_report(args),
+        Command::Terms(args) => terms(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 解析语言代码
+///
+/// # 参数
+/// - `code`: ISO 639-1或639-3语言代码
+fn parse_language(code: &str) -> Result<Language, String> {
+    Language::from_639_1(code)
+        .or_else(|| Language::from_639_3(code))
+        .ok_or_else(|| format!("unknown language: {}", code))
+}
+
+/// 解析`--engines`选项的值
+///
+/// # 参数
+/// - `value`: 逗号分隔的引擎名称
+fn parse_engines(value: &str) -> Result<Vec<TranslatorType>, String> {
+    value
+        .split(',')
+        .map(|name| {
+            name.parse()
+                .map_err(|e: UnknownTranslatorType| e.to_string())
+        })
+        .collect()
+}
+
+/// 解析`compare`子命令的参数
+///
+/// # 参数
+/// - `args`: 子命令之后的参数
+fn parse_compare(args: &[String]) -> Result<CompareArgs, String> {
+    let mut text = None;
+    let mut from = None;
+    let mut to = None;
+    let mut engines = None;
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--from" => {
+                let code = value("--from")?;
+                if code != "auto" {
+                    from = Some(parse_language(code)?);
+                }
+            }
+            "--to" => to = Some(parse_language(value("--to")?)?),
+            "--engines" => engines = Some(parse_engines(value("--engines")?)?),
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            _ if text.is_none() => text = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    Ok(CompareArgs {
+        text: text.ok_or("missing text")?,
+        from,
+        to: to.ok_or("missing --to")?,
+        engines,
+        json,
+    })
+}
+
+/// 解析`translate`子命令的参数
+///
+/// 指定`--explain-config`时只解析引擎和配置文件，不要求`--to`
+///
+/// # 参数
+/// - `args`: 子命令之后的参数
+fn parse_translate(args: &[String]) -> Result<Command, String> {
+    let mut engine = None;
+    let mut config_file = None;
+    let mut explain_config = false;
+    let mut from = None;
+    let mut to = None;
+    let mut input = None;
+    let mut checkpoint = None;
+    let mut encoding = InputEncoding::Auto;
+    let mut preserve_encoding = false;
+    let mut verify = None;
+    let mut normalize_input: Option<InputNormalization> = None;
+    let mut stream = false;
+    let mut dry_run = false;
+    let mut price = None;
+    let mut explain = false;
+    let mut output_format = OutputFormat::Text;
+    let mut alternatives = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--engine" => {
+                let name = value("--engine")?;
+                engine = Some(
+                    name.parse()
+                        .map_err(|e: UnknownTranslatorType| e.to_string())?,
+                );
+            }
+            "--config" => config_file = Some(value("--config")?.clone()),
+            "--explain-config" => explain_config = true,
+            "--from" => {
+                let code = value("--from")?;
+                if code != "auto" {
+                    from = Some(parse_language(code)?);
+                }
+            }
+            "--to" => to = Some(parse_language(value("--to")?)?),
+            "--input" => input = Some(value("--input")?.clone()),
+            "--checkpoint" => checkpoint = Some(value("--checkpoint")?.clone()),
+            "--encoding" => {
+                encoding =
+                    InputEncoding::from_label(value("--encoding")?).map_err(|e| e.to_string())?
+            }
+            "--preserve-encoding" => preserve_encoding = true,
+            "--stream" => stream = true,
+            "--dry-run" => dry_run = true,
+            "--explain" => explain = true,
+            "--output-format" => {
+                output_format = match value("--output-format")?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "jsonl" => OutputFormat::Jsonl,
+                    other => return Err(format!("unknown output format: {}", other)),
+                };
+            }
+            "--alternatives" => {
+                let count = value("--alternatives")?;
+                alternatives = count
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| format!("invalid number of alternatives: {}", count))?;
+            }
+            "--price" => {
+                let amount = value("--price")?;
+                price = Some(
+                    amount
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|p| p.is_finite() && *p >= 0.0)
+                        .ok_or_else(|| format!("invalid price: {}", amount))?,
+                );
+            }
+            "--verify" => {
+                verify.get_or_insert(DEFAULT_SIMILARITY_THRESHOLD);
+            }
+            "--normalize-input" => {
+                normalize_input.get_or_insert_with(InputNormalization::default);
+            }
+            "--straight-quotes" => {
+                normalize_input
+                    .get_or_insert_with(InputNormalization::default)
+                    .straighten_quotes = true;
+            }
+            "--verify-threshold" => {
+                let threshold = value("--verify-threshold")?;
+                verify = Some(
+                    threshold
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|t| (0.0..=1.0).contains(t))
+                        .ok_or_else(|| format!("invalid threshold: {}", threshold))?,
+                );
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    let mut config = ConfigResolver::new().with_explicit(PartialConfig {
+        engine,
+        ..Default::default()
+    });
+    if let Some(path) = config_file {
+        config = config.with_file(path);
+    }
+    if explain_config {
+        return Ok(Command::ExplainConfig(config));
+    }
+    if stream && (checkpoint.is_some() || preserve_encoding) {
+        return Err("--stream cannot be combined with --checkpoint or --preserve-encoding".into());
+    }
+    if explain && (checkpoint.is_some() || stream) {
+        return Err("--explain cannot be combined with --checkpoint or --stream".into());
+    }
+    if output_format == OutputFormat::Jsonl
+        && (stream || preserve_encoding || verify.is_some() || explain)
+    {
+        return Err(
+            "--output-format jsonl cannot be combined with --stream, --preserve-encoding, --verify or --explain"
+                .into(),
+        );
+    }
+    if alternatives > 0
+        && (checkpoint.is_some()
+            || stream
+            || preserve_encoding
+            || output_format == OutputFormat::Jsonl)
+    {
+        return Err(
+            "--alternatives cannot be combined with --checkpoint, --stream, --preserve-encoding or --output-format jsonl"
+                .into(),
+        );
+    }
+    Ok(Command::Translate(TranslateArgs {
+        config,
+        from,
+        to: to.ok_or("missing --to")?,
+        input,
+        checkpoint,
+        encoding,
+        preserve_encoding,
+        verify,
+        normalize_input,
+        stream,
+        dry_run,
+        price,
+        explain,
+        output_format,
+        alternatives,
+    }))
+}
+
+/// 解析`languages`子命令的参数
+///
+/// `--engine`可以是引擎名称，也可以直接是`language_map`中注册的代码表名称（如`deepl`）
+///
+/// # 参数
+/// - `args`: 子命令之后的参数
+fn parse_languages(args: &[String]) -> Result<LanguagesArgs, String> {
+    let mut table = None;
+    let mut missing = false;
+    let mut json = false;
+    let mut lang_style = CodeStyle::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--engine" => {
+                let name = args.next().ok_or("--engine requires a value")?;
+                table = Some(match name.parse::<TranslatorType>() {
+                    Ok(engine) => engine
+                        .language_table()
+                        .ok_or_else(|| format!("{} uses a built-in language table", name))?
+                        .to_string(),
+                    Err(_) if language_coverage(name).registered => name.clone(),
+                    Err(e) => return Err(e.to_string()),
+                });
+            }
+            "--missing" => missing = true,
+            "--json" => json = true,
+            "--lang-style" => {
+                lang_style = parse_lang_style(args.next().ok_or("--lang-style requires a value")?)?
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    Ok(LanguagesArgs {
+        table: table.ok_or("missing --engine")?,
+        missing,
+        json,
+        lang_style,
+    })
+}
+
+/// 解析`capabilities`子命令的参数
+///
+/// # 参数
+/// - `args`: 子命令之后的参数
+fn parse_capabilities(args: &[String]) -> Result<CapabilitiesArgs, String> {
+    let mut parsed = CapabilitiesArgs {
+        engines: None,
+        from: None,
+        to: None,
+        json: false,
+        lang_style: CodeStyle::default(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+        match arg.as_str() {
+            "--engines" => parsed.engines = Some(parse_engines(value("--engines")?)?),
+            "--from" => {
+                let code = value("--from")?;
+                if code != "auto" {
+                    parsed.from = Some(parse_language(code)?);
+                }
+            }
+            "--to" => parsed.to = Some(parse_language(value("--to")?)?),
+            "--json" => parsed.json = true,
+            "--lang-style" => parsed.lang_style = parse_lang_style(value("--lang-style")?)?,
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    if parsed.from.is_some() && parsed.to.is_none() {
+        return Err("--from requires --to".to_string());
+    }
+    Ok(parsed)
+}
+
+/// 解析`terms`子命令的参数
+///
+/// # 参数
+/// - `args`: 子命令之后的参数，第一个是动作（目前只有`extract`）
+fn parse_terms(args: &[String]) -> Result<TermsArgs, String> {
+    match args.first().map(String::as_str) {
+        Some("extract") => {}
+        Some(other) => return Err(format!("unknown terms action: {}", other)),
+        None => return Err("missing terms action".to_string()),
+    }
+    let mut input = None;
+    let mut parsed = TermsArgs {
+        input: String::new(),
+        from: None,
+        to: None,
+        options: TerminologyOptions::default(),
+        json: false,
+    };
+    let mut args = args[1..].iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+        let count = |name: &str, value: &str| {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("invalid {}: {}", name, value))
+        };
+        match arg.as_str() {
+            "--input" => input = Some(value("--input")?.clone()),
+            "--from" => parsed.from = Some(parse_language(value("--from")?)?),
+            "--to" => parsed.to = Some(parse_language(value("--to")?)?),
+            "--min-frequency" => {
+                parsed.options.min_frequency = count("--min-frequency", value("--min-frequency")?)?
+            }
+            "--max-ngram" => {
+                parsed.options.max_ngram = count("--max-ngram", value("--max-ngram")?)?
+            }
+            "--min-consistency" => {
+                let share = value("--min-consistency")?;
+                parsed.options.min_consistency = share
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|t| (0.0..=1.0).contains(t))
+                    .ok_or_else(|| format!("invalid --min-consistency: {}", share))?;
+            }
+            "--json" => parsed.json = true,
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    if parsed.from.is_some() != parsed.to.is_some() {
+        return Err("--from and --to must be given together".to_string());
+    }
+    parsed.input = input.ok_or("missing --input")?;
+    Ok(parsed)
+}
+
+/// 解析`--lang-style`的值
+///
+/// # 参数
+/// - `name`: 格式名称，如`bcp47`、`vendor:google`
+fn parse_lang_style(name: &str) -> Result<CodeStyle, String> {
+    name.parse::<CodeStyle>().map_err(|e| e.to_string())
+}
+
+/// 执行`languages`子命令
+///
+/// # 参数
+/// - `args`: 子命令参数
+fn languages(args: LanguagesArgs) -> Result<(), String> {
+    let report = language_coverage(&args.table);
+    let rows = report
+        .languages
+        .iter()
+        .filter(|entry| !args.missing || entry.code.is_none())
+        .collect::<Vec<_>>();
+    if args.json {
+        let json = serde_json::json!({
+            "table": report.vendor,
+            "languages": rows.iter().map(|entry| serde_json::json!({
+                "language": args.lang_style.format(&entry.language),
+                "code": entry.code,
+            })).collect::<Vec<_>>(),
+            "unmapped_codes": report.unmapped_codes,
+        });
+        println!("{:#}", json);
+        return Ok(());
+    }
+
+    let width = rows
+        .iter()
+        .map(|entry| format!("{:?}", entry.language).len())
+        .max()
+        .unwrap_or_default()
+        .max("LANGUAGE".len());
+    println!("{:<width$}  CODE", "LANGUAGE");
+    for entry in rows {
+        let language = format!("{:?}", entry.language);
+        println!("{:<width$}  {}", language, entry.code.unwrap_or("missing"));
+    }
+    print_summary(&report);
+    Ok(())
+}
+
+/// 输出覆盖情况的汇总和无法转换的文档代码
+///
+/// # 参数
+/// - `report`: 覆盖情况报告
+fn print_summary(report: &CoverageReport) {
+    println!(
+        "\n{}: {}/{} languages mapped",
+        report.vendor,
+        report.mapped_count(),
+        report.languages.len()
+    );
+    if !report.has_official_list {
+        println!("No documented code list is bundled for {}", report.vendor);
+    } else if report.unmapped_codes.is_empty() {
+        println!("Every documented code maps to a language");
+    } else {
+        println!("Documented codes without a language:");
+        for code in &report.unmapped_codes {
+            println!("  {}", code);
+        }
+    }
+}
+
+/// 执行`translate`子命令
+///
+/// 指定检查点时中断后可以重新运行同一命令继续翻译；
+/// 指定`--verify`时用同一个引擎回译，回译与原文相似度过低的行在标准错误输出警告
+///
+/// # 参数
+/// - `args`: 子命令参数
+async fn translate(args: TranslateArgs) -> Result<(), String> {
+    let config = args.config.resolve().map_err(|e| e.to_string())?;
+    let engine = config.engine();
+    let bytes = match &args.input {
+        Some(path) => std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => {
+            let mut bytes = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| e.to_string())?;
+            bytes
+        }
+    };
+    let content = decode(&bytes, args.encoding).map_err(|e| e.to_string())?;
+    if !content.is_confident() {
+        eprintln!(
+            "warning: input looks like {} but detection confidence is low ({:.2}), pass --encoding if the output is garbled",
+            content.encoding.name(),
+            content.confidence
+        );
+    }
+    let lines = content.lines();
+    let translator = config.build();
+    let mut translate_options = TranslateOptions::new();
+    translate_options.normalize_input = args.normalize_input;
+    let options = BatchOptions {
+        translate_options,
+        ..Default::default()
+    };
+    if args.dry_run {
+        let map = lines.into_iter().enumerate().collect::<HashMap<_, _>>();
+        print_plan(&plan_map(translator.as_ref(), &map, &options), args.price);
+        return Ok(());
+    }
+    if args.stream {
+        if translator.supports_streaming() {
+            let translations = stream_lines(translator.as_ref(), &lines, &args).await?;
+            if let Some(threshold) = args.verify {
+                warn_suspicious(translator.as_ref(), &lines, &translations, &args, threshold)
+                    .await?;
+            }
+            return Ok(());
+        }
+        eprintln!(
+            "warning: {} does not support streaming, printing translations once they are complete",
+            engine.as_str()
+        );
+    }
+
+    if args.output_format == OutputFormat::Jsonl {
+        return translate_jsonl(translator.as_ref(), engine.as_str(), lines, options, &args).await;
+    }
+
+    let sources = args.verify.map(|_| lines.clone());
+    let mut alternatives: Vec<Vec<AlternativeTranslation>> = Vec::new();
+    let translations = match &args.checkpoint {
+        Some(path) => {
+            let batch = CheckpointedBatch::new(translator.as_ref(), engine.as_str(), path)
+                .with_options(options);
+            run_checkpointed(&batch, &lines, &args).await?
+        }
+        None => {
+            let mut output = if args.alternatives > 0 {
+                translate_each(
+                    translator.as_ref(),
+                    &lines,
+                    &options.translate_options,
+                    &args,
+                )
+                .await?
+            } else {
+                let map = lines.iter().cloned().enumerate().collect::<HashMap<_, _>>();
+                translate_map_with(translator.as_ref(), &map, args.from, &args.to, &options)
+                    .await
+                    .map_err(|e| format!("{:#}", e))?
+                    .translations
+            };
+            if args.explain {
+                print_provenance(&output, lines.len());
+            }
+            let outputs = (0..lines.len())
+                .map(|i| output.remove(&i).unwrap_or_default())
+                .collect::<Vec<_>>();
+            alternatives = outputs
+                .iter()
+                .map(|o| {
+                    o.detail
+                        .alternatives
+                        .iter()
+                        .take(args.alternatives)
+                        .cloned()
+                        .collect()
+                })
+                .collect();
+            outputs.into_iter().map(|o| o.text).collect()
+        }
+    };
+    if let (Some(threshold), Some(sources)) = (args.verify, sources) {
+        warn_suspicious(
+            translator.as_ref(),
+            &sources,
+            &translations,
+            &args,
+            threshold,
+        )
+        .await?;
+    }
+    let mut output = String::new();
+    for (i, translation) in translations.iter().enumerate() {
+        output.push_str(translation);
+        output.push('\n');
+        for alternative in alternatives.get(i).into_iter().flatten() {
+            output.push_str(&format_alternative(alternative));
+            output.push('\n');
+        }
+    }
+    std::io::stdout()
+        .write_all(&content.encode_output(&output, args.preserve_encoding))
+        .map_err(|e| e.to_string())
+}
+
+/// 逐行翻译，保留每行的候选译文
+///
+/// 批量翻译时同一分块的译文共用一个`TranslationDetail`，候选译文无法对应到行，
+/// 因此`--alternatives`对每行单独发送请求
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `lines`: 原文行
+/// - `options`: 翻译选项
+/// - `args`: 子命令参数
+///
+/// # 返回值
+/// 按行号索引的翻译结果
+async fn translate_each(
+    translator: &dyn AsyncTranslator,
+    lines: &[String],
+    options: &TranslateOptions,
+    args: &TranslateArgs,
+) -> Result<HashMap<usize, TranslationOutput>, String> {
+    let mut translations = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        let output = translator
+            .translate_with_options(line, args.from, &args.to, options)
+            .await
+            .map_err(|e| format!("line {}: {:#}", i + 1, e))?;
+        translations.insert(i, output);
+    }
+    Ok(translations)
+}
+
+/// 格式化`translate --alternatives`输出的一条候选译文
+///
+/// 候选译文缩进输出在主译文之下，得分和来源未知时省略
+///
+/// # 参数
+/// - `alternative`: 候选译文
+fn format_alternative(alternative: &AlternativeTranslation) -> String {
+    let mut line = format!("  - {}", alternative.text);
+    let details = alternative
+        .score
+        .map(|score| format!("score {:.2}", score))
+        .into_iter()
+        .chain(alternative.source.as_ref().map(|s| format!("source {}", s)))
+        .collect::<Vec<_>>();
+    if !details.is_empty() {
+        line.push_str(&format!(" ({})", details.join(", ")));
+    }
+    line
+}
+
+/// 翻译每一行并以JSON Lines输出
+///
+/// 每行输入输出一条记录，`id`为从1开始的行号。未指定检查点时允许部分失败，失败的行输出错误记录；
+/// 指定检查点时任一分块失败都会中止，已完成的分块保留在检查点中
+///
+/// # 参数
+/// - `translator`: 翻译器
+/// - `engine`: 引擎名称
+/// - `lines`: 原文行
+/// - `options`: 批量翻译选项
+/// - `args`: 子命令参数
+async fn translate_jsonl(
+    translator: &dyn AsyncTranslator,
+    engine: &str,
+    lines: Vec<String>,
+    mut options: BatchOptions,
+    args: &TranslateArgs,
+) -> Result<(), String> {
+    let result = match &args.checkpoint {
+        Some(path) => {
+            let batch = CheckpointedBatch::new(translator, engine, path).with_options(options);
+            let translations = run_checkpointed(&batch, &lines, args).await?;
+            MapTranslation {
+                translations: (1..)
+                    .zip(translations)
+                    .map(|(id, text)| {
+                        let output = TranslationOutput {
+                            text,
+                            ..Default::default()
+                        };
+                        (id, output)
+                    })
+                    .collect(),
+                errors: HashMap::new(),
+            }
+        }
+        None => {
+            options.partial = true;
+            let map = (1..).zip(lines.iter().cloned()).collect::<HashMap<_, _>>();
+            translate_map_with(translator, &map, args.from, &args.to, &options)
+                .await
+                .map_err(|e| format!("{:#}", e))?
+        }
+    };
+    let map = (1..).zip(lines).collect::<HashMap<usize, _>>();
+    JsonlWriter::new(std::io::stdout().lock(), engine)
+        .write_map(&map, &result, args.from, &args.to)
+        .map_err(|e| e.to_string())
+}
+
+/// 执行可续传的批量翻译
+///
+/// 收到Ctrl-C时不再开始新的分块，把正在请求的分块写入检查点后退出；
+/// 再次收到Ctrl-C时立即退出，正在请求的分块需要在续传时重新翻译
+///
+/// # 参数
+/// - `batch`: 可续传批量翻译
+/// - `lines`: 原文行
+/// - `args`: 子命令参数
+///
+/// # 返回值
+/// 每行的译文
+async fn run_checkpointed(
+    batch: &CheckpointedBatch<'_>,
+    lines: &[String],
+    args: &TranslateArgs,
+) -> Result<Vec<String>, String> {
+    let run = batch.run(lines, args.from, &args.to);
+    tokio::pin!(run);
+    let result = tokio::select! {
+        result = &mut run => result,
+        Ok(()) = tokio::signal::ctrl_c() => {
+            eprintln!("interrupted, saving the chunk in progress to the checkpoint (press Ctrl-C again to quit now)");
+            tokio::select! {
+                (result, shutdown) = async { tokio::join!(run, batch.shutdown()) } => {
+                    shutdown.map_err(|e| format!("{:#}", e))?;
+                    result
+                }
+                Ok(()) = tokio::signal::ctrl_c() => {
+                    return Err("interrupted, rerun the same command to resume".to_string());
+                }
+            }
+        }
+    };
+    result.map_err(|e| match e.downcast_ref::<TranslatorError>() {
+        Some(TranslatorError::Cancelled(done)) => format!(
+            "interrupted after {} of {} lines, rerun the same command to resume",
+            done,
+            lines.len()
+        ),
+        _ => format!("{:#}", e),
+    })
+}
+
+/// 向标准错误输出`translate --explain`中每行译文的来历
+///
+/// # 参数
+/// - `translations`: 按行号索引的译文，未翻译的行（如空行）不输出
+/// - `lines`: 总行数
+fn print_provenance(translations: &HashMap<usize, TranslationOutput>, lines: usize) {
+    for i in 0..lines {
+        if let Some(output) = translations.get(&i) {
+            eprintln!("line {}: {}", i + 1, output.detail.provenance);
+        }
+    }
+}
+
+/// 输出`translate --dry-run`的翻译计划
+///
+/// # 参数
+/// - `plan`: 翻译计划
+/// - `price`: 每百万字符的价格，None表示不估算计费引擎的费用
+fn print_plan(plan: &TranslationPlan, price: Option<f64>) {
+    println!("segments: {}", plan.segments);
+    println!("unique segments: {}", plan.unique_segments);
+    println!("characters after dedup: {}", plan.characters);
+    println!("requests: {}", plan.requests);
+    match (plan.paid, price) {
+        (false, _) => println!("estimated cost: 0 (engine is not billed per request)"),
+        (true, Some(price)) => println!(
+            "estimated cost: {:.4} (at {} per million characters)",
+            plan.estimated_cost(price),
+            price
+        ),
+        (true, None) => println!("estimated cost: unknown, pass --price to estimate"),
+    }
+}
+
+/// 逐行流式翻译，在译文生成的过程中输出
+///
+/// 空行原样输出，不发送给翻译器
+///
+/// # 参数
+/// - `translator`: 支持流式输出的翻译器
+/// - `lines`: 原文行
+/// - `args`: 子命令参数
+///
+/// # 返回值
+/// 每行的完整译文
+async fn stream_lines(
+    translator: &dyn AsyncTranslator,
+    lines: &[String],
+    args: &TranslateArgs,
+) -> Result<Vec<String>, String> {
+    let mut stdout = std::io::stdout();
+    let mut translations = Vec::with_capacity(lines.len());
+    for line in lines {
+        let mut translation = String::new();
+        if !line.trim().is_empty() {
+            let query = match &args.normalize_input {
+                Some(normalization) => normalize_input(line, normalization),
+                None => line.clone(),
+            };
+            let mut tokens = translator.translate_streaming(&query, args.from, &args.to);
+            while let Some(token) = tokens.next().await {
+                let token = token.map_err(|e| format!("{:#}", e))?;
+                stdout
+                    .write_all(token.as_bytes())
+                    .and_then(|_| stdout.flush())
+                    .map_err(|e| e.to_string())?;
+                translation.push_str(&token);
+            }
+        }
+        writeln!(stdout).map_err(|e| e.to_string())?;
+        translations.push(translation);
+    }
+    Ok(translations)
+}
+
+/// 回译检查译文，对相似度低于阈值的行输出警告
+///
+/// 空行不参与检查
+///
+/// # 参数
+/// - `translator`: 回译使用的翻译器
+/// - `sources`: 原文行
+/// - `translations`: 与原文对应的译文行
+/// - `args`: 子命令参数
+/// - `threshold`: 相似度阈值
+async fn warn_suspicious(
+    translator: &dyn AsyncTranslator,
+    sources: &[String],
+    translations: &[String],
+    args: &TranslateArgs,
+    threshold: f32,
+) -> Result<(), String> {
+    let (indices, (sources, translations)): (Vec<_>, (Vec<_>, Vec<_>)) = sources
+        .iter()
+        .zip(translations)
+        .enumerate()
+        .filter(|(_, (source, _))| !source.trim().is_empty())
+        .map(|(i, (source, translation))| (i, (source.clone(), translation.clone())))
+        .unzip();
+    let reports = verify_translations(translator, &sources, &translations, args.from, &args.to)
+        .await
+        .map_err(|e| format!("verification failed: {:#}", e))?;
+    for (line, report) in indices.iter().zip(&reports) {
+        if report.is_suspicious(threshold) {
+            eprintln!(
+                "warning: line {} may be mistranslated (similarity {:.2}): {:?} came back as {:?}",
+                line + 1,
+                report.similarity,
+                report.source,
+                report.back_translation
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 执行`compare`子命令
+///
+/// 未指定引擎时使用所有已配置凭据的引擎
+///
+/// # 参数
+/// - `args`: 子命令参数
+async fn compare(args: CompareArgs) -> Result<(), String> {
+    let engines = configured_engines(args.engines)?;
+    // 先建立各引擎的连接，避免第一次请求的DNS解析和TLS握手计入耗时
+    TranslatorFactory::warm_all(&engines).await;
+
+    let results = compare_all(&args.text, args.from, &args.to, &engines).await;
+    let differences = pairwise_differences(&results);
+    if args.json {
+        let json = serde_json::json!({
+            "results": results.iter().map(|r| match &r.result {
+                Ok(output) => serde_json::json!({
+                    "engine": r.engine,
+                    "latency_ms": r.latency.as_millis() as u64,
+                    "text": output.text,
+                }),
+                Err(failure) => serde_json::json!({
+                    "engine": r.engine,
+                    "latency_ms": r.latency.as_millis() as u64,
+                    "error": { "kind": failure.kind.as_str(), "message": failure.message },
+                }),
+            }).collect::<Vec<_>>(),
+            "differences": differences.iter()
+                .map(|&(a, b)| [&results[a].engine, &results[b].engine])
+                .collect::<Vec<_>>(),
+        });
+        println!("{:#}", json);
+        return Ok(());
+    }
+
+    let width = results
+        .iter()
+        .map(|r| r.engine.len())
+        .max()
+        .unwrap_or_default()
+        .max("ENGINE".len());
+    println!("{:<width$}  {:>10}  RESULT", "ENGINE", "LATENCY");
+    for result in &results {
+        let latency = format!("{} ms", result.latency.as_millis());
+        let output = match &result.result {
+            Ok(output) => output.text.clone(),
+            Err(failure) => format!("[{}] {}", failure.kind, failure.message),
+        };
+        println!("{:<width$}  {:>10}  {}", result.engine, latency, output);
+    }
+    if !differences.is_empty() {
+        println!("\nDifferences:");
+        for (a, b) in differences {
+            println!("  {} != {}", results[a].engine, results[b].engine);
+        }
+    }
+    Ok(())
+}
+
+/// 执行`translate --explain-config`：打印解析后的配置及每个字段的来源
+///
+/// # 参数
+/// - `config`: 命令行参数对应的配置解析器
+fn explain_config(config: &ConfigResolver) -> Result<(), String> {
+    let resolved = config.resolve().map_err(|e| e.to_string())?;
+    println!("{}", resolved.explain());
+    Ok(())
+}
+
+/// 创建已配置的引擎
+///
+/// 缺少凭据的引擎打印提示后跳过
+///
+/// # 参数
+/// - `types`: 指定的引擎，None表示所有引擎
+///
+/// # 返回值
+/// 引擎名称及对应的翻译器；没有可用的引擎时返回错误
+fn configured_engines(types: Option<Vec<TranslatorType>>) -> Result<Engines, String> {
+    let types = types.unwrap_or_else(|| TranslatorType::ALL.to_vec());
+    let mut engines: Engines = Vec::new();
+    for translator_type in types {
+        match TranslatorFactory::create_from_env(translator_type) {
+            Ok(translator) => engines.push((translator_type.as_str().to_string(), translator)),
+            Err(e) => eprintln!("skipping {}: {}", translator_type.as_str(), e),
+        }
+    }
+    if engines.is_empty() {
+        return Err("no engine is configured".to_string());
+    }
+    Ok(engines)
+}
+
+/// 执行`capabilities`子命令
+///
+/// # 参数
+/// - `args`: 子命令参数
+fn capability_report(args: CapabilitiesArgs) -> Result<(), String> {
+    let matrix = capabilities(&configured_engines(args.engines)?);
+    let viable = args.to.map(|to| matrix.engines_for(args.from, &to));
+    if args.json {
+        let mut json = with_style(&args.lang_style, || serde_json::to_value(&matrix))
+            .map_err(|e| e.to_string())?;
+        if let Some(viable) = &viable {
+            json["engines_for"] = serde_json::json!(viable);
+        }
+        println!("{:#}", json);
+        return Ok(());
+    }
+
+    let width = matrix
+        .engines
+        .iter()
+        .map(|engine| engine.engine.len())
+        .max()
+        .unwrap_or_default()
+        .max("ENGINE".len());
+    let limit = |value: Option<usize>| value.map_or("-".to_string(), |v| v.to_string());
+    println!(
+        "{:<width$}  {:>9}  {:>9}  {:>11}",
+        "ENGINE", "LANGUAGES", "MAX_BATCH", "INPUT_LIMIT"
+    );
+    for engine in &matrix.engines {
+        let languages = engine
+            .languages
+            .as_ref()
+            .map_or("any".to_string(), |languages| languages.len().to_string());
+        println!(
+            "{:<width$}  {:>9}  {:>9}  {:>11}",
+            engine.engine,
+            languages,
+            limit(engine.max_batch_len),
+            limit(engine.input_limit)
+        );
+    }
+    if let (Some(viable), Some(to)) = (viable, args.to) {
+        let from = args
+            .from
+            .map_or("auto".to_string(), |from| format!("{:?}", from));
+        let engines = if viable.is_empty() {
+            "none".to_string()
+        } else {
+            viable.join(", ")
+        };
+        println!("\n{} -> {:?}: {}", from, to, engines);
+    }
+    Ok(())
+}
+
+/// 执行`terms extract`子命令
+///
+/// # 参数
+/// - `args`: 子命令参数
+fn terms(args: TermsArgs) -> Result<(), String> {
+    let text = std::fs::read_to_string(&args.input).map_err(|e| e.to_string())?;
+    let pairs = if args.input.to_ascii_lowercase().ends_with(".tmx") {
+        tmx_pairs(&text, args.from.zip(args.to))?
+    } else {
+        jsonl_pairs(&text)?
+    };
+    let candidates = extract(&pairs, &args.options);
+    if args.json {
+        let json = serde_json::to_string_pretty(&candidates).map_err(|e| e.to_string())?;
+        println!("{}", json);
+    } else {
+        print!("{}", export_glossary(&candidates));
+    }
+    Ok(())
+}
+
+/// 读取TMX文档中一个语言对的句对
+///
+/// # 参数
+/// - `xml`: TMX文档内容
+/// - `pair`: （源语言，目标语言），None表示文档中唯一的语言对
+fn tmx_pairs(
+    xml: &str,
+    pair: Option<(Language, Language)>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut memory = TranslationMemory::new();
+    memory.import_tmx(xml).map_err(|e| e.to_string())?;
+    let (from, to) = match pair {
+        Some(pair) => pair,
+        None => match memory.language_pairs().as_slice() {
+            [pair] => *pair,
+            [] => return Err("the TMX file has no translation units".to_string()),
+            pairs => {
+                return Err(format!(
+                    "the TMX file has {} language pairs, choose one with --from and --to",
+                    pairs.len()
+                ))
+            }
+        },
+    };
+    Ok(memory
+        .entries(from, to)
+        .into_iter()
+        .map(|entry| (entry.source.clone(), entry.target.clone()))
+        .collect())
+}
+
+/// 读取JSONL中的句对，每行一个含`source`和`target`字段的对象，跳过空行
+///
+/// # 参数
+/// - `text`: JSONL内容
+fn jsonl_pairs(text: &str) -> Result<Vec<(String, String)>, String> {
+    #[derive(serde::Deserialize)]
+    struct Pair {
+        source: String,
+        target: String,
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str::<Pair>(line)
+                .map(|pair| (pair.source, pair.target))
+                .map_err(|e| format!("line {}: {}", i + 1, e))
+        })
+        .collect()
 }
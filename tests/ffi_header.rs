@@ -0,0 +1,20 @@
+#![cfg(feature = "ffi")]
+
+use std::path::Path;
+use std::process::Command;
+
+/// 测试C头文件和示例程序可以被C编译器编译
+#[test]
+fn test_header_compiles() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg("-fsyntax-only")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(root.join("examples/ffi/translate.c"))
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success());
+}
@@ -0,0 +1,251 @@
+use fusion_translator::fusion_translator::async_translator::Language;
+use fusion_translator::fusion_translator::i18n_files::{translate_json_array, JsonArrayOptions};
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
+
+/// 记录当前和峰值分配字节数的分配器
+struct CountingAllocator;
+
+/// 当前已分配的字节数
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// 自上次重置以来的峰值
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// 合成元素中使用的标题，循环使用
+const TITLES: [&str; 7] = [
+    "Save",
+    "Cancel",
+    "Open file",
+    "Close window",
+    "Settings",
+    "Are you sure?",
+    "Quit",
+];
+
+/// 按需生成的大型JSON数组，不在内存中保存整个文件
+///
+/// `translated`为true时生成模拟翻译器的期望输出（目标字段转换为大写）
+struct SyntheticArray {
+    /// 元素数量
+    count: usize,
+    /// 下一个要生成的元素
+    next: usize,
+    /// 是否生成译文
+    translated: bool,
+    /// 已生成但尚未输出的字节
+    pending: Vec<u8>,
+    /// `pending`中已输出的字节数
+    pos: usize,
+    /// 是否已输出数组结尾
+    closed: bool,
+}
+
+impl SyntheticArray {
+    /// 创建生成器
+    ///
+    /// # 参数
+    /// - `count`: 元素数量
+    /// - `translated`: 是否生成译文
+    fn new(count: usize, translated: bool) -> Self {
+        Self {
+            count,
+            next: 0,
+            translated,
+            pending: b"[\n".to_vec(),
+            pos: 0,
+            closed: false,
+        }
+    }
+
+    /// 目标字段的值
+    ///
+    /// # 参数
+    /// - `text`: 原文
+    fn text(&self, text: &str) -> String {
+        match self.translated {
+            true => text.to_uppercase(),
+            false => text.to_string(),
+        }
+    }
+
+    /// 生成第`i`个元素
+    ///
+    /// # 参数
+    /// - `i`: 元素下标
+    fn element(&self, i: usize) -> String {
+        let title = TITLES[i % TITLES.len()];
+        let description = TITLES[(i / 3) % TITLES.len()];
+        format!(
+            "  {{\"id\": {}, \"title\": \"{}\", \"key\": \"ui.{}\", \"items\": [{{\"description\": \"{}\", \"order\": {}}}, {{\"description\": \"{}\", \"hidden\": true}}]}}",
+            i,
+            self.text(title),
+            title,
+            self.text(description),
+            i % 10,
+            self.text(title),
+        )
+    }
+
+    /// 把接下来的字节复制到`out`
+    ///
+    /// # 返回值
+    /// 复制的字节数，0表示已经结束
+    fn fill(&mut self, out: &mut [u8]) -> usize {
+        if self.pos == self.pending.len() {
+            self.pending.clear();
+            self.pos = 0;
+            if self.next < self.count {
+                if self.next > 0 {
+                    self.pending.extend_from_slice(b",\n");
+                }
+                let element = self.element(self.next);
+                self.pending.extend_from_slice(element.as_bytes());
+                self.next += 1;
+            } else if !self.closed {
+                self.pending.extend_from_slice(b"\n]\n");
+                self.closed = true;
+            }
+        }
+        let len = out.len().min(self.pending.len() - self.pos);
+        out[..len].copy_from_slice(&self.pending[self.pos..self.pos + len]);
+        self.pos += len;
+        len
+    }
+}
+
+impl AsyncRead for SyntheticArray {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let len = this.fill(buf.initialize_unfilled());
+        buf.advance(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 把写入的内容与期望输出逐字节比较，不保存写入的内容
+struct ExpectedWriter {
+    /// 期望输出
+    expected: SyntheticArray,
+    /// 已写入的字节数
+    written: usize,
+    /// 第一个不同的字节位置
+    mismatch: Option<usize>,
+}
+
+impl AsyncWrite for ExpectedWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let mut expected = vec![0; buf.len()];
+        let mut filled = 0;
+        while filled < buf.len() {
+            let len = this.expected.fill(&mut expected[filled..]);
+            if len == 0 {
+                break;
+            }
+            filled += len;
+        }
+        if this.mismatch.is_none() {
+            this.mismatch = expected[..filled]
+                .iter()
+                .zip(buf)
+                .position(|(a, b)| a != b)
+                .or((filled < buf.len()).then_some(filled))
+                .map(|offset| this.written + offset);
+        }
+        this.written += buf.len();
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 测试流式翻译数十MB的JSON数组：输出与期望逐字节相同，峰值内存远小于文件大小
+#[tokio::test]
+async fn test_large_array_with_bounded_memory() {
+    const ELEMENTS: usize = 150_000;
+    let mut size = 0;
+    let mut generator = SyntheticArray::new(ELEMENTS, false);
+    let mut buf = [0; 8192];
+    loop {
+        let len = generator.fill(&mut buf);
+        if len == 0 {
+            break;
+        }
+        size += len;
+    }
+    assert!(size > 20_000_000, "{}", size);
+
+    let mock = MockTranslator::new();
+    let options = JsonArrayOptions::new(
+        ["title", "items[*].description"]
+            .into_iter()
+            .map(|path| path.parse().unwrap()),
+    );
+    let reader = BufReader::new(SyntheticArray::new(ELEMENTS, false));
+    let mut writer = ExpectedWriter {
+        expected: SyntheticArray::new(ELEMENTS, true),
+        written: 0,
+        mismatch: None,
+    };
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let report = translate_json_array(
+        &mock,
+        reader,
+        &mut writer,
+        Some(Language::English),
+        &Language::Chinese,
+        &options,
+    )
+    .await
+    .unwrap();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+
+    assert_eq!(writer.mismatch, None);
+    assert_eq!(writer.written, size);
+    assert_eq!(report.elements, ELEMENTS);
+    assert_eq!(report.untranslated, 0);
+    assert_eq!(report.translated, ELEMENTS * 3);
+    // 每批去重后最多7个不同的字符串
+    assert!(mock.translated_items() <= ELEMENTS / 100 * TITLES.len());
+    assert!(peak < 4_000_000, "peak {} bytes for {} bytes", peak, size);
+}
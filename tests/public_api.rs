@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::Path;
+
+/// 公开API快照文件
+const SNAPSHOT: &str = "tests/fixtures/public_api.txt";
+
+/// 取出声明中的标识符
+///
+/// # 参数
+/// - `rest`: 关键字之后的文本
+fn ident(rest: &str) -> &str {
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// 解析以`pub`开头的条目声明
+///
+/// # 返回值
+/// `(种类, 名称)`，不是条目声明时为None
+fn item(line: &str) -> Option<(&'static str, &str)> {
+    let rest = line.strip_prefix("pub ")?;
+    let rest = rest.strip_prefix("async ").unwrap_or(rest);
+    let rest = rest.strip_prefix("unsafe ").unwrap_or(rest);
+    let rest = rest.strip_prefix("extern \"C\" ").unwrap_or(rest);
+    for kind in [
+        "fn", "struct", "enum", "trait", "const", "static", "type", "mod",
+    ] {
+        if let Some(name) = rest.strip_prefix(kind).and_then(|r| r.strip_prefix(' ')) {
+            return Some((kind, ident(name)));
+        }
+    }
+    None
+}
+
+/// 列出一个公开模块中的公开条目
+///
+/// 只按源码格式（rustfmt）逐行识别：顶层的`pub`条目和`pub use`、固有impl中的`pub`方法和常量、
+/// 公开结构体的`pub`字段以及公开枚举的变体，测试模块之后的内容不计入
+///
+/// # 参数
+/// - `module`: 模块名
+/// - `source`: 模块源码
+fn module_items(module: &str, source: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    // 当前所在的顶层块：(种类, 名称)
+    let mut block: Option<(&str, String)> = None;
+    let mut pending_use: Option<String> = None;
+    for line in source.lines() {
+        if line.starts_with("#[cfg(test)]") {
+            break;
+        }
+        if let Some(statement) = &mut pending_use {
+            statement.push(' ');
+            statement.push_str(line.trim());
+            if line.trim_end().ends_with(';') {
+                items.push(format!(
+                    "{}::{}",
+                    module,
+                    statement.replace("{ ", "{").replace(", }", "}")
+                ));
+                pending_use = None;
+            }
+            continue;
+        }
+        if line.starts_with("pub use ") {
+            if line.ends_with(';') {
+                items.push(format!("{}::{}", module, line));
+            } else {
+                pending_use = Some(line.to_string());
+            }
+            continue;
+        }
+        if line == "}" {
+            block = None;
+            continue;
+        }
+        if !line.starts_with(' ') {
+            if let Some((kind, name)) = item(line) {
+                items.push(format!("{}::{} {}", module, kind, name));
+                if line.ends_with('{') && matches!(kind, "struct" | "enum") {
+                    block = Some((kind, name.to_string()));
+                }
+            } else if let Some(rest) = line.strip_prefix("impl") {
+                // 只记录固有impl，特征实现中的方法不带`pub`
+                let header = rest.trim_end_matches(" {");
+                let header = header
+                    .strip_prefix(|c| c == '<')
+                    .map_or(header, |h| h.split_once("> ").map_or(h, |(_, t)| t));
+                if !header.contains(" for ") {
+                    block = Some(("impl", ident(header.trim()).to_string()));
+                }
+            }
+            continue;
+        }
+        let Some((kind, name)) = &block else { continue };
+        let Some(member) = line.strip_prefix("    ") else {
+            continue;
+        };
+        if member.starts_with(' ') || member.starts_with("///") || member.starts_with("#[") {
+            continue;
+        }
+        match *kind {
+            "impl" => {
+                if let Some((kind, item)) = item(member) {
+                    items.push(format!("{}::{}::{} {}", module, name, kind, item));
+                }
+            }
+            "struct" => {
+                if let Some(field) = member.strip_prefix("pub ") {
+                    items.push(format!("{}::{}.{}", module, name, ident(field)));
+                }
+            }
+            _ => {
+                let variant = ident(member);
+                if !variant.is_empty() {
+                    items.push(format!("{}::{}::{}", module, name, variant));
+                }
+            }
+        }
+    }
+    items
+}
+
+/// 列出crate的公开API
+fn public_api() -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/fusion_translator");
+    let declarations = fs::read_to_string(root.join("mod.rs")).unwrap();
+    let mut lines = Vec::new();
+    for module in declarations
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub mod "))
+        .map(|line| line.trim_end_matches(';'))
+    {
+        let source = fs::read_to_string(root.join(format!("{}.rs", module))).unwrap();
+        lines.extend(module_items(module, &source));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// 测试公开API与快照一致
+///
+/// 有意修改公开API时，使用`UPDATE_PUBLIC_API=1 cargo test --test public_api`更新快照，
+/// 并在变更记录中说明
+#[test]
+fn test_public_api_snapshot() {
+    let actual = public_api();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(SNAPSHOT);
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    if actual != expected {
+        let added = actual
+            .lines()
+            .filter(|line| !expected.lines().any(|e| e == *line))
+            .collect::<Vec<_>>();
+        let removed = expected
+            .lines()
+            .filter(|line| !actual.lines().any(|a| a == *line))
+            .collect::<Vec<_>>();
+        panic!(
+            "public API changed (run with UPDATE_PUBLIC_API=1 to accept)\nadded: {:#?}\nremoved: {:#?}",
+            added, removed
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::domain::TextDomain;
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::routing::RoutingTranslator;
+use fusion_translator::fusion_translator::translate_options::TranslateOptions;
+use std::sync::Arc;
+
+/// 按领域表创建路由翻译器：代码注释交给`code`，聊天交给`caiyun`，其余使用`general`
+fn router() -> (RoutingTranslator, [Arc<MockTranslator>; 3]) {
+    let general = Arc::new(MockTranslator::new().with_suffix(" [general]"));
+    let caiyun = Arc::new(MockTranslator::new().with_suffix(" [caiyun]"));
+    let code = Arc::new(MockTranslator::new().with_suffix(" [code]"));
+    let engines: Vec<(String, Arc<dyn AsyncTranslator>)> = vec![
+        ("general".to_string(), general.clone()),
+        ("caiyun".to_string(), caiyun.clone()),
+        ("code".to_string(), code.clone()),
+    ];
+    let router = RoutingTranslator::new(engines)
+        .with_route(TextDomain::Code, ["code"])
+        .unwrap()
+        .with_route(TextDomain::Chat, ["caiyun"])
+        .unwrap();
+    (router, [general, caiyun, code])
+}
+
+/// 测试路由按领域表选择引擎
+#[tokio::test]
+async fn test_routing_follows_domain_table() {
+    let (router, [general, caiyun, code]) = router();
+    let cases = [
+        ("// TODO: return null when parse_config() fails", "code"),
+        ("hey, are u coming tonight?", "caiyun"),
+        ("The museum opens at nine in the morning.", "general"),
+        (
+            "The Licensee shall indemnify the Licensor against any liability.",
+            "general",
+        ),
+    ];
+    for (text, engine) in cases {
+        let output = router
+            .translate(text, None, &Language::Chinese)
+            .await
+            .unwrap();
+        assert!(
+            output.text.ends_with(&format!("[{}]", engine)),
+            "{} -> {}",
+            text,
+            output.text
+        );
+    }
+    assert_eq!((general.calls(), caiyun.calls(), code.calls()), (2, 1, 1));
+}
+
+/// 测试调用选项中指定的领域优先于文本判断
+#[tokio::test]
+async fn test_domain_override() {
+    let (router, [general, caiyun, code]) = router();
+    let options = TranslateOptions::new().with_domain(TextDomain::Chat);
+    let output = router
+        .translate_with_options(
+            "let total = items.len();",
+            None,
+            &Language::Chinese,
+            &options,
+        )
+        .await
+        .unwrap();
+    assert!(output.text.ends_with("[caiyun]"));
+
+    let options = TranslateOptions::new().with_domain(TextDomain::General);
+    let query = vec!["// fix me later".to_string(), "fn main() {}".to_string()];
+    let output = router
+        .translate_vec_with_options(&query, None, &Language::Chinese, &options)
+        .await
+        .unwrap();
+    assert!(output.text.iter().all(|text| text.ends_with("[general]")));
+    let output = router
+        .translate_vec(&query, None, &Language::Chinese)
+        .await
+        .unwrap();
+    assert!(output.text.iter().all(|text| text.ends_with("[code]")));
+    assert_eq!((general.calls(), caiyun.calls(), code.calls()), (1, 1, 1));
+}
+
+/// 测试首选引擎失败时换用其他引擎
+#[tokio::test]
+async fn test_falls_over_to_other_engines() {
+    let general = Arc::new(MockTranslator::new());
+    let code = Arc::new(MockTranslator::new().fail_on("boom"));
+    let engines: Vec<(String, Arc<dyn AsyncTranslator>)> = vec![
+        ("general".to_string(), general.clone()),
+        ("code".to_string(), code.clone()),
+    ];
+    let router = RoutingTranslator::new(engines)
+        .with_route(TextDomain::Code, ["code"])
+        .unwrap();
+    let output = router
+        .translate("// boom: parse_config() panics", None, &Language::Chinese)
+        .await
+        .unwrap();
+    assert_eq!(output.text, "// BOOM: PARSE_CONFIG() PANICS");
+    assert_eq!((code.calls(), general.calls()), (1, 1));
+}
@@ -0,0 +1,149 @@
+use fusion_translator::fusion_translator::async_translator::Language;
+use fusion_translator::fusion_translator::batch::BatchOptions;
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::subtitle::{
+    parse_srt, translate_srt, Cue, CueConstraints, SubtitleTranslation,
+};
+
+/// 不能出现在行首的常见标点
+const NO_LINE_START: &[char] = &['、', '。', '，', '？', '！', '」', '）'];
+
+/// 用译文比原文长得多的模拟翻译器翻译字幕文件
+///
+/// # 参数
+/// - `fixture`: `tests/fixtures/subtitle`下的文件名
+/// - `suffix`: 模拟翻译器追加在每个译文后的文本
+/// - `to`: 目标语言
+/// - `constraints`: 排版约束
+async fn translate_fixture(
+    fixture: &str,
+    suffix: &str,
+    to: Language,
+    constraints: CueConstraints,
+) -> SubtitleTranslation {
+    let path = format!("tests/fixtures/subtitle/{}", fixture);
+    let srt = std::fs::read_to_string(path).unwrap();
+    let mock = MockTranslator::new()
+        .with_suffix(suffix)
+        .fail_on("fails on purpose");
+    let options = BatchOptions {
+        chunk_size: 1,
+        partial: true,
+        ..Default::default()
+    };
+    translate_srt(&mock, &srt, None, &to, &constraints, &options)
+        .await
+        .unwrap()
+}
+
+/// 检查不满足约束的字幕不多于待人工检查的字幕，时间轴按顺序首尾不重叠，且生成的SRT可以原样解析
+///
+/// # 参数
+/// - `output`: 翻译结果
+/// - `constraints`: 排版约束
+fn assert_constraints_hold(output: &SubtitleTranslation, constraints: CueConstraints) {
+    let violations = output
+        .cues
+        .iter()
+        .filter(|cue| !constraints.fits(&cue.lines))
+        .count();
+    assert!(violations <= output.report.needs_review.len());
+    let timings = output
+        .cues
+        .iter()
+        .map(|cue| {
+            let (start, end) = cue.timing.split_once(" --> ").unwrap();
+            (start.to_string(), end.to_string())
+        })
+        .collect::<Vec<_>>();
+    for (start, end) in &timings {
+        assert!(start <= end, "{} --> {}", start, end);
+    }
+    for pair in timings.windows(2) {
+        assert!(pair[0].1 <= pair[1].0, "{:?}", pair);
+    }
+    assert_eq!(parse_srt(&output.to_srt()), output.cues);
+}
+
+/// 找出以指定文本开头的字幕
+///
+/// # 参数
+/// - `cues`: 字幕列表
+/// - `prefix`: 字幕文本的开头
+fn cues_starting_with<'a>(cues: &'a [Cue], prefix: &str) -> Vec<&'a Cue> {
+    cues.iter()
+        .filter(|cue| cue.text().starts_with(prefix))
+        .collect()
+}
+
+/// 测试西文字幕按约束换行，超长的字幕按句子拆分，单句过长的字幕标记为需要人工检查
+#[tokio::test]
+async fn test_latin_subtitles_respect_constraints() {
+    let constraints = CueConstraints::new(42, 2);
+    let output = translate_fixture(
+        "en.srt",
+        " And that is what everyone in the village kept saying.",
+        Language::French,
+        constraints,
+    )
+    .await;
+    assert_eq!(output.report.cues, 6);
+    assert_eq!(output.report.untranslated, vec!["6"]);
+    assert_eq!(output.report.split, vec!["2"]);
+    assert_eq!(output.report.needs_review.len(), 1);
+    assert_eq!(output.report.needs_review[0].index, "5");
+
+    // 只有单句过长的第5条不满足约束
+    let too_long = cues_starting_with(&output.cues, "I TOLD YOU");
+    assert_eq!(too_long.len(), 1);
+    assert!(!constraints.fits(&too_long[0].lines));
+    for cue in &output.cues {
+        if cue.index != too_long[0].index {
+            assert!(constraints.fits(&cue.lines), "{:?}", cue);
+        }
+    }
+    // 第2条拆分后的两段各占原时间轴的一部分
+    let station = cues_starting_with(&output.cues, "TO THE STATION.");
+    assert_eq!(
+        station[0].lines,
+        vec!["TO THE STATION. THE LAST", "TRAIN LEAVES AT MIDNIGHT."]
+    );
+    assert_eq!(station[0].timing, "00:00:03,400 --> 00:00:04,661");
+    assert_eq!(output.cues.len(), 7);
+    assert_eq!(output.cues[2].timing, "00:00:04,661 --> 00:00:06,000");
+    // 未翻译的字幕保留原文
+    assert!(output
+        .cues
+        .iter()
+        .any(|cue| cue.lines == vec!["This line fails on purpose."]));
+    assert_constraints_hold(&output, constraints);
+}
+
+/// 测试中文字幕按字符换行并遵守避头尾规则，超出行数的字幕按句子拆分
+#[tokio::test]
+async fn test_cjk_subtitles_respect_constraints() {
+    let constraints = CueConstraints::new(12, 2);
+    let output = translate_fixture(
+        "zh.srt",
+        "这句台词的译文比原文长了许多。",
+        Language::Chinese,
+        constraints,
+    )
+    .await;
+    assert_eq!(output.report.cues, 3);
+    assert!(output.report.untranslated.is_empty());
+    assert!(output.report.needs_review.is_empty());
+    assert_eq!(output.report.split, vec!["2", "3"]);
+    for cue in &output.cues {
+        assert!(constraints.fits(&cue.lines), "{:?}", cue);
+        for line in &cue.lines {
+            assert!(!line.starts_with(NO_LINE_START), "{:?}", cue);
+            assert!(!line.ends_with(['「', '（']), "{:?}", cue);
+        }
+    }
+    assert_eq!(
+        output.cues[0].lines,
+        vec!["你要去哪里？这句台词的", "译文比原文长了许多。"]
+    );
+    assert_constraints_hold(&output, constraints);
+}
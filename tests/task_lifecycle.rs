@@ -0,0 +1,177 @@
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::batch::{translate_map_with, BatchOptions};
+use fusion_translator::fusion_translator::cache::CachedTranslator;
+use fusion_translator::fusion_translator::compare::compare_all;
+use fusion_translator::fusion_translator::experiment::ExperimentTranslator;
+use fusion_translator::fusion_translator::fallback::FallbackTranslator;
+use fusion_translator::fusion_translator::mock_translator::MockTranslator;
+use fusion_translator::fusion_translator::queue::{QueueConfig, QueueRequest, TranslationQueue};
+use fusion_translator::fusion_translator::routing::RoutingTranslator;
+use fusion_translator::fusion_translator::translator_error::TranslatorError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 每次上游调用的耗时
+const UPSTREAM_DELAY: Duration = Duration::from_secs(1);
+
+/// 当前运行时中存活的任务数
+fn alive_tasks() -> usize {
+    tokio::runtime::Handle::current()
+        .metrics()
+        .num_alive_tasks()
+}
+
+/// 每次调用耗时`UPSTREAM_DELAY`的计数模拟翻译器
+fn slow_mock() -> Arc<MockTranslator> {
+    Arc::new(MockTranslator::new().with_delay(UPSTREAM_DELAY))
+}
+
+/// 在第一次上游调用进行到一半时丢弃操作，检查之后不再有上游调用，任务数回到基线
+///
+/// # 参数
+/// - `mocks`: 操作用到的所有模拟翻译器
+/// - `operation`: 待测的操作
+async fn assert_stops_when_dropped(mocks: &[&Arc<MockTranslator>], operation: impl Future) {
+    let baseline = alive_tasks();
+    let dropped = tokio::time::timeout(UPSTREAM_DELAY / 2, operation).await;
+    assert!(dropped.is_err(), "operation finished before it was dropped");
+    let calls = mocks.iter().map(|mock| mock.calls()).collect::<Vec<_>>();
+    assert!(calls.iter().sum::<usize>() > 0);
+    tokio::time::sleep(UPSTREAM_DELAY * 10).await;
+    assert_eq!(
+        mocks.iter().map(|mock| mock.calls()).collect::<Vec<_>>(),
+        calls
+    );
+    assert_eq!(alive_tasks(), baseline);
+}
+
+/// 测试丢弃队列时中止工作任务，已完成的请求保留结果，其余请求的接收端收到`Cancelled`
+#[tokio::test(start_paused = true)]
+async fn test_queue_dropped_mid_operation() {
+    let baseline = alive_tasks();
+    let mock = slow_mock();
+    let queue = TranslationQueue::new(
+        [("mock", mock.clone() as Arc<dyn AsyncTranslator>)],
+        QueueConfig::default().with_workers(1).with_max_batch_len(1),
+    );
+    let mut receivers = Vec::new();
+    for i in 0..5 {
+        let request = QueueRequest::new("mock", format!("text {}", i), Language::English);
+        receivers.push(queue.enqueue(request).await);
+    }
+    assert_eq!(alive_tasks(), baseline + 1);
+    tokio::time::sleep(UPSTREAM_DELAY * 3 / 2).await;
+    assert_eq!(mock.calls(), 2);
+
+    drop(queue);
+    tokio::time::sleep(UPSTREAM_DELAY * 10).await;
+    assert_eq!(mock.calls(), 2);
+    assert_eq!(alive_tasks(), baseline);
+    let mut receivers = receivers.into_iter();
+    assert_eq!(
+        receivers.next().unwrap().await.unwrap().unwrap().text,
+        "TEXT 0"
+    );
+    for receiver in receivers {
+        let error = receiver.await.unwrap().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::Cancelled(0))
+        ));
+    }
+}
+
+/// 测试比较多个引擎时丢弃future会中止所有引擎的请求
+#[tokio::test(start_paused = true)]
+async fn test_compare_dropped_mid_operation() {
+    let first = slow_mock();
+    let second = slow_mock();
+    let engines: Vec<(String, Arc<dyn AsyncTranslator>)> = vec![
+        ("first".to_string(), first.clone()),
+        ("second".to_string(), second.clone()),
+    ];
+    assert_stops_when_dropped(
+        &[&first, &second],
+        compare_all("hello", None, &Language::Chinese, &engines),
+    )
+    .await;
+}
+
+/// 测试组合翻译器在请求进行中被丢弃时不会继续请求其他引擎
+#[tokio::test(start_paused = true)]
+async fn test_wrappers_dropped_mid_operation() {
+    let engines = |primary: &Arc<MockTranslator>, secondary: &Arc<MockTranslator>| {
+        vec![
+            (
+                "primary".to_string(),
+                primary.clone() as Arc<dyn AsyncTranslator>,
+            ),
+            (
+                "secondary".to_string(),
+                secondary.clone() as Arc<dyn AsyncTranslator>,
+            ),
+        ]
+    };
+    let failing = || {
+        Arc::new(
+            MockTranslator::new()
+                .with_delay(UPSTREAM_DELAY)
+                .fail_on("boom"),
+        )
+    };
+
+    let (primary, secondary) = (failing(), slow_mock());
+    let fallback = FallbackTranslator::new(engines(&primary, &secondary));
+    assert_stops_when_dropped(
+        &[&primary, &secondary],
+        fallback.translate("boom", None, &Language::Chinese),
+    )
+    .await;
+    assert_eq!(secondary.calls(), 0);
+
+    let (primary, secondary) = (failing(), slow_mock());
+    let routing = RoutingTranslator::new(engines(&primary, &secondary));
+    assert_stops_when_dropped(
+        &[&primary, &secondary],
+        routing.translate("boom", None, &Language::Chinese),
+    )
+    .await;
+    assert_eq!(secondary.calls(), 0);
+
+    let (control, treatment) = (slow_mock(), slow_mock());
+    let experiment = ExperimentTranslator::new(control.clone(), treatment.clone(), 0.5, "drop");
+    let query = (0..20).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+    assert_stops_when_dropped(
+        &[&control, &treatment],
+        experiment.translate_vec(&query, None, &Language::Chinese),
+    )
+    .await;
+
+    let inner = slow_mock();
+    let cached = CachedTranslator::new(inner.clone(), 16);
+    assert_stops_when_dropped(
+        &[&inner],
+        cached.translate("hello", None, &Language::Chinese),
+    )
+    .await;
+}
+
+/// 测试分块批量翻译被丢弃时不再发送后续分块
+#[tokio::test(start_paused = true)]
+async fn test_batch_dropped_mid_operation() {
+    let mock = slow_mock();
+    let map = (0..10)
+        .map(|i| (i, format!("text {}", i)))
+        .collect::<HashMap<_, _>>();
+    let options = BatchOptions {
+        chunk_size: 1,
+        ..Default::default()
+    };
+    assert_stops_when_dropped(
+        &[&mock],
+        translate_map_with(mock.as_ref(), &map, None, &Language::Chinese, &options),
+    )
+    .await;
+}
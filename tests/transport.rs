@@ -0,0 +1,468 @@
+use fusion_translator::fusion_translator::async_translator::Language;
+use fusion_translator::fusion_translator::politeness::PolitenessSetting;
+use fusion_translator::fusion_translator::translator_error::TranslatorError;
+use fusion_translator::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory,
+};
+use fusion_translator::fusion_translator::translator_options::{
+    TranslatorOptions, BROWSER_USER_AGENT, CRATE_USER_AGENT,
+};
+use fusion_translator::fusion_translator::transport::{
+    HttpMethod, HttpRequest, HttpResponse, HttpTransport,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// 记录请求并按顺序返回预设响应的传输层
+struct FakeTransport {
+    /// 收到的请求
+    requests: Mutex<Vec<HttpRequest>>,
+    /// 待返回的响应
+    responses: Mutex<VecDeque<HttpResponse>>,
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for FakeTransport {
+    async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        self.requests.lock().unwrap().push(request);
+        let response = self.responses.lock().unwrap().pop_front();
+        Ok(response.expect("unexpected request"))
+    }
+}
+
+/// 用假传输层翻译规范的调用（英文`Hello, world`译为中文），返回发出的请求和译文
+///
+/// # 参数
+/// - `config`: 翻译器配置
+/// - `responses`: 按顺序返回的响应体，以`{`开头的是JSON本身，否则是`tests/fixtures`下的文件名
+async fn canonical_call(
+    config: TranslatorConfig,
+    responses: &[&str],
+) -> (Vec<HttpRequest>, String) {
+    let responses = responses
+        .iter()
+        .map(|body| match body.starts_with('{') {
+            true => HttpResponse::new(200, *body),
+            false => HttpResponse::new(
+                200,
+                std::fs::read(format!("tests/fixtures/{}", body)).unwrap(),
+            ),
+        })
+        .collect();
+    let transport = Arc::new(FakeTransport {
+        requests: Mutex::new(Vec::new()),
+        responses: Mutex::new(responses),
+    });
+    let options = TranslatorOptions::new()
+        .with_transport(transport.clone())
+        .with_politeness(PolitenessSetting::Disabled);
+    let translator = TranslatorFactory::create_with_options(config, &options);
+    let output = translator
+        .translate("Hello, world", Some(Language::English), &Language::Chinese)
+        .await
+        .unwrap();
+    let requests = transport.requests.lock().unwrap().clone();
+    (requests, output.text)
+}
+
+/// 构造期望的请求
+///
+/// # 参数
+/// - `method`: 请求方法
+/// - `url`: 请求地址
+/// - `headers`: 请求头
+/// - `body`: 请求体
+fn request(method: HttpMethod, url: &str, headers: &[(&str, &str)], body: &str) -> HttpRequest {
+    HttpRequest {
+        method,
+        url: url.to_string(),
+        headers: headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+/// 网页接口的默认请求头
+///
+/// # 参数
+/// - `referer`: 来源页面
+fn browser_headers(referer: &str) -> [(&str, &str); 2] {
+    [("user-agent", BROWSER_USER_AGENT), ("referer", referer)]
+}
+
+/// 解析表单请求体
+///
+/// # 参数
+/// - `body`: 请求体
+fn form(body: &[u8]) -> Vec<(String, String)> {
+    serde_urlencoded::from_bytes(body).unwrap()
+}
+
+/// 测试百度翻译的请求
+#[tokio::test]
+async fn test_baidu_request() {
+    let config = TranslatorConfig::Baidu {
+        app_id: "app".to_string(),
+        key: "secret".to_string(),
+    };
+    let (requests, text) = canonical_call(config, &["baidu/sentences.json"]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Post,
+            "https://fanyi-api.baidu.com/api/trans/vip/translate",
+            &[
+                ("content-type", "application/x-www-form-urlencoded"),
+                ("user-agent", CRATE_USER_AGENT),
+            ],
+            "q=Hello%2C+world&from=en&to=zh&appid=app&salt=0&sign=8d3ea06d0d6706379b805bd5c2676a52",
+        )]
+    );
+    assert_eq!(text, "你好吗？\n很好。");
+}
+
+/// 测试有道翻译的请求，时间戳、随机盐值和签名每次不同，只检查是否存在
+#[tokio::test]
+async fn test_youdao_request() {
+    let config = TranslatorConfig::Youdao {
+        app_key: "app".to_string(),
+        app_secret: "secret".to_string(),
+    };
+    let (mut requests, text) = canonical_call(config, &["youdao/success.json"]).await;
+    assert_eq!(requests.len(), 1);
+    let body = std::mem::take(&mut requests[0].body);
+    assert_eq!(
+        requests[0],
+        request(
+            HttpMethod::Post,
+            "https://openapi.youdao.com/api",
+            &[
+                ("content-type", "application/x-www-form-urlencoded"),
+                ("user-agent", CRATE_USER_AGENT),
+            ],
+            "",
+        )
+    );
+    let (fixed, varying): (Vec<_>, Vec<_>) = form(&body)
+        .into_iter()
+        .partition(|(name, _)| !["curtime", "salt", "sign"].contains(&name.as_str()));
+    let fixed = fixed
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fixed,
+        [
+            ("from", "en"),
+            ("to", "zh-CHS"),
+            ("signType", "v3"),
+            ("appKey", "app"),
+            ("q", "Hello, world"),
+        ]
+    );
+    assert_eq!(varying.len(), 3);
+    assert_eq!(text, "你好");
+}
+
+/// 测试阿里翻译的请求
+#[tokio::test]
+async fn test_alibaba_request() {
+    let config = TranslatorConfig::Alibaba {
+        token: String::new(),
+    };
+    let (requests, text) = canonical_call(config, &["alibaba/success.json"]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Get,
+            "https://translate.alibaba.com/api/translate/text?domain=general&query=Hello%2C+world&srcLang=en&tgtLang=zh",
+            &browser_headers("https://translate.alibaba.com/"),
+            "",
+        )]
+    );
+    assert_eq!(text, "你好，世界");
+}
+
+/// 测试彩云翻译的请求
+#[tokio::test]
+async fn test_caiyun_request() {
+    let config = TranslatorConfig::Caiyun {
+        token: "token".to_string(),
+        request_id: "demo".to_string(),
+    };
+    let response = r#"{"confidence":0.8,"target":["你好，世界"],"rc":0}"#;
+    let (requests, text) = canonical_call(config, &[response]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Post,
+            "https://api.interpreter.caiyunai.com/v1/translator",
+            &[
+                ("x-authorization", "token token"),
+                ("content-type", "application/json"),
+                ("user-agent", CRATE_USER_AGENT),
+            ],
+            r#"{"trans_type":"en2zh-Hant","source":["Hello, world"],"request_id":"demo"}"#,
+        )]
+    );
+    assert_eq!(text, "你好，世界");
+}
+
+/// 测试MyMemory翻译的请求
+#[tokio::test]
+async fn test_mymemory_request() {
+    let (requests, text) =
+        canonical_call(TranslatorConfig::MyMemory, &["mymemory/success.json"]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Get,
+            "https://api.mymemory.translated.net/get?q=Hello%2C+world&langpair=en-GB%7Czh-CN",
+            &[
+                ("user-agent", CRATE_USER_AGENT),
+                ("referer", "https://mymemory.translated.net"),
+            ],
+            "",
+        )]
+    );
+    assert_eq!(text, "你好，世界");
+}
+
+/// 测试TranSmart翻译的请求，客户端标识带随机部分，只检查前缀
+#[tokio::test]
+async fn test_transmart_request() {
+    let response = r#"{"header":{"type":"auto_translation","ret_code":"succ"},"auto_translation":["你好，世界。"]}"#;
+    let (mut requests, text) = canonical_call(TranslatorConfig::TranSmart, &[response]).await;
+    assert_eq!(requests.len(), 1);
+    let mut body: serde_json::Value =
+        serde_json::from_slice(&std::mem::take(&mut requests[0].body)).unwrap();
+    let client_key = body["header"]["client_key"].take();
+    assert!(client_key
+        .as_str()
+        .unwrap()
+        .starts_with("browser-chrome-124.0.0-Windows 10-"));
+    assert_eq!(
+        requests[0],
+        request(
+            HttpMethod::Post,
+            "https://transmart.qq.com/api/imt",
+            &[
+                ("content-type", "application/json"),
+                ("user-agent", BROWSER_USER_AGENT),
+                ("referer", "https://transmart.qq.com/"),
+            ],
+            "",
+        )
+    );
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "header": {"fn": "auto_translation", "client_key": null},
+            "type": "plain",
+            "model_category": "normal",
+            "source": {"lang": "en", "text_list": ["Hello, world"]},
+            "target": {"lang": "zh"},
+        })
+    );
+    assert_eq!(text, "你好，世界。");
+}
+
+/// 测试Reverso翻译的请求
+#[tokio::test]
+async fn test_reverso_request() {
+    let (requests, text) = canonical_call(TranslatorConfig::Reverso, &["reverso/word.json"]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Post,
+            "https://api.reverso.net/translate/v1/translation",
+            &[
+                ("content-type", "application/json"),
+                ("user-agent", BROWSER_USER_AGENT),
+                ("referer", "https://www.reverso.net/"),
+            ],
+            r#"{"format":"text","from":"eng","to":"chi","input":"Hello, world","options":{"sentenceSplitter":false,"origin":"translation.web","contextResults":true,"languageDetection":false}}"#,
+        )]
+    );
+    assert_eq!(text, "maison");
+}
+
+/// 测试Bing网页翻译先获取会话页面，再带会话参数请求翻译接口
+#[tokio::test]
+async fn test_bing_web_request() {
+    let (requests, text) = canonical_call(
+        TranslatorConfig::BingWeb,
+        &["bing/translator.html", "bing/success.json"],
+    )
+    .await;
+    let headers = browser_headers("https://www.bing.com/translator");
+    let mut form_headers = vec![("content-type", "application/x-www-form-urlencoded")];
+    form_headers.extend(headers);
+    assert_eq!(
+        requests,
+        vec![
+            request(
+                HttpMethod::Get,
+                "https://www.bing.com/translator",
+                &headers,
+                ""
+            ),
+            request(
+                HttpMethod::Post,
+                "https://www.bing.com/ttranslatev3?isVertical=1&IG=4F2D6C0A8E1B4B7C9A3E5D7F1B2C4E6A&IID=translator.5023",
+                &form_headers,
+                "fromLang=en&to=zh-Hans&text=Hello%2C+world&token=Xy7mR2kQ9vTbN3cLpA1sHd8fGjWzEu4o&key=1729238400000",
+            ),
+        ]
+    );
+    assert_eq!(text, "你好，世界");
+}
+
+/// 测试Google网页翻译的请求
+#[tokio::test]
+async fn test_google_web_request() {
+    let (requests, text) =
+        canonical_call(TranslatorConfig::GoogleWeb, &["google/single.json"]).await;
+    assert_eq!(
+        requests,
+        vec![request(
+            HttpMethod::Get,
+            "https://translate.googleapis.com/translate_a/single?client=gtx&sl=en&tl=zh-CN&dt=t&q=Hello%2C+world",
+            &[("user-agent", CRATE_USER_AGENT)],
+            "",
+        )]
+    );
+    assert_eq!(text, "你好，世界");
+}
+
+/// 测试每个翻译器预热时向翻译接口发送一个不带参数的HEAD请求
+#[tokio::test]
+async fn test_warm_up_requests() {
+    let configs = [
+        (
+            TranslatorConfig::Baidu {
+                app_id: "app".to_string(),
+                key: "secret".to_string(),
+            },
+            "https://fanyi-api.baidu.com/api/trans/vip/translate",
+        ),
+        (
+            TranslatorConfig::Youdao {
+                app_key: "app".to_string(),
+                app_secret: "secret".to_string(),
+            },
+            "https://openapi.youdao.com/api",
+        ),
+        (
+            TranslatorConfig::Alibaba {
+                token: String::new(),
+            },
+            "https://translate.alibaba.com/api/translate/text",
+        ),
+        (
+            TranslatorConfig::Caiyun {
+                token: "token".to_string(),
+                request_id: "demo".to_string(),
+            },
+            "https://api.interpreter.caiyunai.com/v1/translator",
+        ),
+        (
+            TranslatorConfig::MyMemory,
+            "https://api.mymemory.translated.net/get",
+        ),
+        (
+            TranslatorConfig::TranSmart,
+            "https://transmart.qq.com/api/imt",
+        ),
+        (
+            TranslatorConfig::Reverso,
+            "https://api.reverso.net/translate/v1/translation",
+        ),
+        (TranslatorConfig::BingWeb, "https://www.bing.com/translator"),
+        (
+            TranslatorConfig::GoogleWeb,
+            "https://translate.googleapis.com/translate_a/single",
+        ),
+    ];
+    for (config, url) in configs {
+        let transport = Arc::new(FakeTransport {
+            requests: Mutex::new(Vec::new()),
+            responses: Mutex::new(VecDeque::from([HttpResponse::new(405, "")])),
+        });
+        let options = TranslatorOptions::new()
+            .with_transport(transport.clone())
+            .with_politeness(PolitenessSetting::Disabled);
+        let name = config.translator_type().as_str();
+        TranslatorFactory::create_with_options(config, &options)
+            .warm_up()
+            .await;
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1, "{}", name);
+        assert_eq!(requests[0].method, HttpMethod::Head, "{}", name);
+        assert_eq!(requests[0].url, url, "{}", name);
+        assert!(requests[0].body.is_empty(), "{}", name);
+    }
+}
+
+/// 没有收到响应的传输层
+struct UnreachableTransport;
+
+#[async_trait::async_trait]
+impl HttpTransport for UnreachableTransport {
+    async fn execute(&self, _request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        Err(anyhow::anyhow!("connection refused"))
+    }
+}
+
+/// 测试预热失败时不返回错误也不panic，之后仍可正常创建请求
+#[tokio::test]
+async fn test_warm_up_failures_are_swallowed() {
+    let options = TranslatorOptions::new().with_transport(Arc::new(UnreachableTransport));
+    let engines = [TranslatorConfig::GoogleWeb, TranslatorConfig::MyMemory]
+        .map(|config| {
+            let name = config.translator_type().as_str().to_string();
+            (
+                name,
+                TranslatorFactory::create_with_options(config, &options),
+            )
+        })
+        .to_vec();
+    TranslatorFactory::warm_all(&engines).await;
+    let err = engines[0]
+        .1
+        .translate("Hello, world", Some(Language::English), &Language::Chinese)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("connection refused"),
+        "{:#}",
+        err
+    );
+}
+
+/// 测试自定义传输层返回的非2xx状态码按翻译器原有的规则处理
+#[tokio::test]
+async fn test_status_errors_from_custom_transport() {
+    let transport = Arc::new(FakeTransport {
+        requests: Mutex::new(Vec::new()),
+        responses: Mutex::new(VecDeque::from([HttpResponse::new(429, "")])),
+    });
+    let options = TranslatorOptions::new()
+        .with_transport(transport)
+        .with_politeness(PolitenessSetting::Disabled);
+    let translator = TranslatorFactory::create_with_options(TranslatorConfig::GoogleWeb, &options);
+    let err = translator
+        .translate("Hello, world", Some(Language::English), &Language::Chinese)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<TranslatorError>(),
+            Some(TranslatorError::RateLimited(_))
+        ),
+        "{:#}",
+        err
+    );
+}
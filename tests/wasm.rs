@@ -0,0 +1,95 @@
+//! 浏览器环境（wasm32-unknown-unknown）下的测试
+//!
+//! ```text
+//! cargo test --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm
+//! ```
+//!
+//! 需要安装与`wasm-bindgen`版本一致的`wasm-bindgen-test-runner`
+#![cfg(target_arch = "wasm32")]
+
+use fusion_translator::fusion_translator::async_translator::{AsyncTranslator, Language};
+use fusion_translator::fusion_translator::politeness::PolitenessSetting;
+use fusion_translator::fusion_translator::scheduled::{Clock, SystemClock};
+use fusion_translator::fusion_translator::translator_error::TranslatorError;
+use fusion_translator::fusion_translator::translator_factory::{
+    TranslatorConfig, TranslatorFactory,
+};
+use fusion_translator::fusion_translator::translator_options::TranslatorOptions;
+use fusion_translator::fusion_translator::transport::{HttpRequest, HttpResponse, HttpTransport};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+/// 代替浏览器fetch的传输层，记录请求并返回固定的响应
+struct FakeFetch {
+    /// 收到的请求地址
+    urls: Mutex<Vec<String>>,
+    /// 响应状态码
+    status: u16,
+    /// 响应体
+    body: &'static str,
+}
+
+#[async_trait::async_trait(?Send)]
+impl HttpTransport for FakeFetch {
+    async fn execute(&self, request: HttpRequest) -> anyhow::Result<HttpResponse> {
+        self.urls.lock().unwrap().push(request.url);
+        Ok(HttpResponse::new(self.status, self.body))
+    }
+}
+
+/// 创建使用假fetch的MyMemory翻译器
+///
+/// 浏览器中没有tokio的计时器，关闭依赖计时器的礼貌请求控制
+///
+/// # 参数
+/// - `fetch`: 假fetch
+fn mymemory(fetch: Arc<FakeFetch>) -> Arc<dyn AsyncTranslator> {
+    let options = TranslatorOptions::new()
+        .with_transport(fetch)
+        .with_politeness(PolitenessSetting::Disabled);
+    TranslatorFactory::create_with_options(TranslatorConfig::MyMemory, &options)
+}
+
+/// 测试在浏览器中解析MyMemory的响应
+#[wasm_bindgen_test]
+async fn test_mymemory_parsing() {
+    let fetch = Arc::new(FakeFetch {
+        urls: Mutex::new(Vec::new()),
+        status: 200,
+        body: include_str!("fixtures/mymemory/success.json"),
+    });
+    let output = mymemory(fetch.clone())
+        .translate("Hello, world", Some(Language::English), &Language::Chinese)
+        .await
+        .unwrap();
+    assert_eq!(output.text, "你好，世界");
+    assert_eq!(
+        *fetch.urls.lock().unwrap(),
+        vec!["https://api.mymemory.translated.net/get?q=Hello%2C+world&langpair=en-GB%7Czh-CN"]
+    );
+}
+
+/// 测试在浏览器中MyMemory的额度用尽响应转换为错误
+#[wasm_bindgen_test]
+async fn test_mymemory_quota() {
+    let fetch = Arc::new(FakeFetch {
+        urls: Mutex::new(Vec::new()),
+        status: 429,
+        body: r#"{"responseData":{"translatedText":"MYMEMORY WARNING: YOU USED ALL AVAILABLE FREE TRANSLATIONS FOR TODAY"},"quotaFinished":true,"responseStatus":429}"#,
+    });
+    let err = mymemory(fetch)
+        .translate("Hello", Some(Language::English), &Language::Chinese)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<TranslatorError>().is_some(), "{:?}", err);
+}
+
+/// 测试系统时钟在浏览器中读取JavaScript的时间
+#[wasm_bindgen_test]
+fn test_system_clock() {
+    let now = SystemClock.now().duration_since(UNIX_EPOCH).unwrap();
+    assert!(now.as_secs() > 1_600_000_000);
+}